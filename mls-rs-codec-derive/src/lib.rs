@@ -4,7 +4,7 @@
 
 use darling::{
     ast::{self, Fields},
-    FromDeriveInput, FromField, FromVariant,
+    FromDeriveInput, FromField, FromMeta, FromVariant,
 };
 use proc_macro2::TokenStream;
 use quote::quote;
@@ -14,6 +14,7 @@ enum Operation {
     Size,
     Encode,
     Decode,
+    DecodeBorrowed,
 }
 
 impl Operation {
@@ -22,6 +23,7 @@ impl Operation {
             Operation::Size => parse_quote! { mls_rs_codec::MlsSize },
             Operation::Encode => parse_quote! { mls_rs_codec::MlsEncode },
             Operation::Decode => parse_quote! { mls_rs_codec::MlsDecode },
+            Operation::DecodeBorrowed => parse_quote! { mls_rs_codec::MlsDecodeBorrowed },
         }
     }
 
@@ -29,7 +31,8 @@ impl Operation {
         match self {
             Operation::Size => quote! { mls_encoded_len },
             Operation::Encode => quote! { mls_encode },
-            Operation::Decode => quote! { mls_decode },
+            Operation::Decode => quote! { mls_decode_with_context },
+            Operation::DecodeBorrowed => quote! { mls_decode_borrowed_with_context },
         }
     }
 
@@ -37,7 +40,7 @@ impl Operation {
         match self {
             Operation::Size => quote! {},
             Operation::Encode => quote! { , writer },
-            Operation::Decode => quote! { reader },
+            Operation::Decode | Operation::DecodeBorrowed => quote! { reader, context },
         }
     }
 
@@ -45,7 +48,64 @@ impl Operation {
         match self {
             Operation::Size => false,
             Operation::Encode => true,
-            Operation::Decode => true,
+            Operation::Decode | Operation::DecodeBorrowed => true,
+        }
+    }
+
+    fn is_decode(&self) -> bool {
+        matches!(self, Operation::Decode | Operation::DecodeBorrowed)
+    }
+}
+
+/// Encoding of enum discriminants set with `#[mls_codec(discriminant = "...")]`.
+#[derive(Clone, Copy, Debug)]
+enum DiscriminantWidth {
+    U8,
+    U16,
+    U32,
+    VarInt,
+}
+
+impl FromMeta for DiscriminantWidth {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        match value {
+            "u8" => Ok(Self::U8),
+            "u16" => Ok(Self::U16),
+            "u32" => Ok(Self::U32),
+            "varint" => Ok(Self::VarInt),
+            _ => Err(darling::Error::unknown_value(value)),
+        }
+    }
+}
+
+impl DiscriminantWidth {
+    /// Type holding discriminant values.
+    fn ty(&self) -> TokenStream {
+        match self {
+            Self::U8 => quote! { u8 },
+            Self::U16 => quote! { u16 },
+            Self::U32 | Self::VarInt => quote! { u32 },
+        }
+    }
+
+    /// Value to encode for the discriminant `value` of type `self.ty()`.
+    fn encoded(&self, value: TokenStream) -> TokenStream {
+        match self {
+            Self::VarInt => quote! { mls_rs_codec::VarInt(#value) },
+            _ => value,
+        }
+    }
+
+    /// Decode a discriminant of type `self.ty()`.
+    fn decode(&self) -> TokenStream {
+        match self {
+            Self::VarInt => quote! {
+                <mls_rs_codec::VarInt as mls_rs_codec::MlsDecode>::mls_decode(reader)?.0
+            },
+            _ => {
+                let ty = self.ty();
+                quote! { <#ty as mls_rs_codec::MlsDecode>::mls_decode(reader)? }
+            }
         }
     }
 }
@@ -55,6 +115,15 @@ impl Operation {
 struct MlsFieldReceiver {
     ident: Option<Ident>,
     with: Option<Path>,
+    #[darling(default)]
+    skip: bool,
+    default: Option<Expr>,
+    #[darling(default)]
+    optional: bool,
+    #[darling(default)]
+    fixed: bool,
+    #[darling(default)]
+    context: bool,
 }
 
 impl MlsFieldReceiver {
@@ -73,6 +142,106 @@ impl MlsFieldReceiver {
             quote! { #index: }
         }
     }
+
+    fn validate(&self, errors: &mut darling::error::Accumulator) {
+        let mut error = |msg: &str| {
+            let error = darling::Error::custom(msg);
+
+            errors.push(match self.ident {
+                Some(ref ident) => error.with_span(ident),
+                None => error,
+            });
+        };
+
+        if self.default.is_some() && !self.skip {
+            error("`default` requires `skip`");
+        }
+
+        if self.skip && (self.with.is_some() || self.optional || self.fixed) {
+            error("`skip` cannot be combined with `with`, `optional` or `fixed`");
+        }
+
+        if self.fixed && self.with.is_some() {
+            error("`fixed` cannot be combined with `with`");
+        }
+
+        if self.context && self.with.is_none() {
+            error("`context` requires `with`");
+        }
+    }
+
+    fn codec_path(&self, operation: &Operation) -> Path {
+        if self.fixed {
+            parse_quote! { mls_rs_codec::fixed_array }
+        } else {
+            self.with.clone().unwrap_or(operation.path())
+        }
+    }
+
+    /// Whether the decode functions of a `with` module take the decode context. This is the
+    /// case for the modules of `mls_rs_codec` and for modules marked with `context`.
+    fn with_context(&self) -> bool {
+        match self.with {
+            Some(ref path) => {
+                self.context
+                    || matches!(path.segments.first(), Some(segment) if segment.ident == "mls_rs_codec")
+            }
+            None => true,
+        }
+    }
+
+    /// Function of the codec path applying `operation` and its arguments after the value.
+    fn call_and_extras(&self, operation: &Operation) -> (TokenStream, TokenStream) {
+        match operation {
+            Operation::Decode if !self.with_context() => (quote! { mls_decode }, quote! { reader }),
+            Operation::DecodeBorrowed if !self.with_context() => {
+                (quote! { mls_decode_borrowed }, quote! { reader })
+            }
+            _ => (operation.call(), operation.extras()),
+        }
+    }
+
+    /// Expression applying `operation` to the field, where `value` is a reference to the field
+    /// when sizing or encoding.
+    fn codec(&self, value: TokenStream, operation: &Operation) -> TokenStream {
+        let path = self.codec_path(operation);
+        let (call, extras) = self.call_and_extras(operation);
+
+        if !self.optional {
+            return match operation {
+                Operation::Size | Operation::Encode => quote! { #path::#call (#value #extras) },
+                Operation::Decode | Operation::DecodeBorrowed => quote! { #path::#call (#extras) },
+            };
+        }
+
+        // Same presence encoding as `Option<T>`, with the codec of the field for the value.
+        match operation {
+            Operation::Size => quote! {
+                1 + match #value {
+                    Some(value) => #path::#call (value),
+                    None => 0,
+                }
+            },
+            Operation::Encode => quote! {
+                match #value {
+                    Some(value) => {
+                        mls_rs_codec::Writer::write(writer, &[1])?;
+                        #path::#call (value, writer)
+                    }
+                    None => {
+                        mls_rs_codec::Writer::write(writer, &[0])
+                    }
+                }
+            },
+            Operation::Decode | Operation::DecodeBorrowed => quote! {
+                match <u8 as mls_rs_codec::MlsDecode>::mls_decode(reader)? {
+                    0 => Ok(None),
+                    1 => #path::#call (#extras).map(Some),
+                    n => Err(mls_rs_codec::Error::OptionOutOfRange(n)),
+                }
+            },
+        }
+    }
 }
 
 #[derive(Debug, FromVariant)]
@@ -81,6 +250,34 @@ struct MlsVariantReceiver {
     ident: Ident,
     discriminant: Option<Expr>,
     fields: ast::Fields<MlsFieldReceiver>,
+    #[darling(default)]
+    unknown: bool,
+}
+
+impl MlsVariantReceiver {
+    fn validate(&self, errors: &mut darling::error::Accumulator) {
+        let mut error = |msg: &str| errors.push(darling::Error::custom(msg).with_span(&self.ident));
+
+        if self.unknown {
+            if !self.fields.is_tuple() || self.fields.len() != 2 {
+                error("`unknown` variants must hold the discriminant and the data in two unnamed fields");
+            }
+        } else {
+            if self.discriminant.is_none() {
+                error("Enum discriminants must be explicitly defined");
+            }
+
+            if self.fields.len() > 1 {
+                error("Enum variants with more than 1 field are not currently supported");
+            }
+        }
+
+        if self.fields.iter().any(|field| field.skip) {
+            error("`skip` is not supported on enum variants");
+        }
+
+        self.fields.iter().for_each(|field| field.validate(errors));
+    }
 }
 
 #[derive(FromDeriveInput)]
@@ -89,89 +286,176 @@ struct MlsInputReceiver {
     ident: Ident,
     generics: Generics,
     data: ast::Data<MlsVariantReceiver, MlsFieldReceiver>,
+    discriminant: Option<DiscriminantWidth>,
 }
 
 impl MlsInputReceiver {
-    fn handle_input(&self, operation: Operation) -> TokenStream {
+    fn validate(&self) -> darling::Result<()> {
+        let mut errors = darling::Error::accumulator();
+
         match self.data {
+            ast::Data::Struct(ref s) => {
+                if self.discriminant.is_some() {
+                    errors.push(
+                        darling::Error::custom("`discriminant` is only supported on enums")
+                            .with_span(&self.ident),
+                    );
+                }
+
+                s.iter().for_each(|field| field.validate(&mut errors));
+            }
+            ast::Data::Enum(ref e) => {
+                e.iter().for_each(|variant| variant.validate(&mut errors));
+
+                if e.iter().filter(|variant| variant.unknown).count() > 1 {
+                    errors.push(
+                        darling::Error::custom("Only one variant can be `unknown`")
+                            .with_span(&self.ident),
+                    );
+                }
+            }
+        }
+
+        errors.finish()
+    }
+
+    fn handle_input(&self, operation: Operation) -> TokenStream {
+        let is_decode = operation.is_decode();
+
+        let body = match self.data {
             ast::Data::Struct(ref s) => struct_impl(s, operation),
-            ast::Data::Enum(ref e) => enum_impl(&self.ident, e, operation),
+            ast::Data::Enum(ref e) => enum_impl(&self.ident, e, self.discriminant, operation),
+        };
+
+        // Decoding counts towards the maximum depth of the decode limits.
+        if is_decode {
+            quote! { context.nested(|context| { #body }) }
+        } else {
+            body
         }
     }
 }
 
-fn enum_impl(ident: &Ident, variants: &[MlsVariantReceiver], operation: Operation) -> TokenStream {
+fn enum_impl(
+    ident: &Ident,
+    variants: &[MlsVariantReceiver],
+    width: Option<DiscriminantWidth>,
+    operation: Operation,
+) -> TokenStream {
     let handle_error = operation.is_result().then_some(quote! { ? });
     let path = operation.path();
     let call = operation.call();
     let extras = operation.extras();
     let enum_name = &ident;
 
-    if matches!(operation, Operation::Decode) {
-        let cases = variants.iter().map(|variant| {
+    let unknown = variants.iter().find(|variant| variant.unknown);
+    let known = variants.iter().filter(|variant| !variant.unknown);
+
+    if operation.is_decode() {
+        let cases = known.map(|variant| {
             let variant_name = &variant.ident;
 
-            // TODO: Calculate discriminant and support integers that are assumed types like u16,
-            // u32 etc based on repr()
-            let discriminant = &variant
-                .discriminant
-                .clone()
-                .expect("Enum discriminants must be explicitly defined");
-
-            // TODO: Support more than 1 field
-            match variant.fields.len() {
-                0 => quote! { #discriminant => Ok(#enum_name::#variant_name), },
-                1 =>{
-                    let path = variant.fields.fields[0].with.as_ref().unwrap_or(&path);
-                    quote! { #discriminant => Ok(#enum_name::#variant_name(#path::#call(#extras) #handle_error)), }
-                },
-                _ => panic!("Enum discriminants with more than 1 field are not currently supported")
-            }
+            // Validated to be present on known variants
+            let discriminant = variant.discriminant.as_ref().unwrap();
+
+            let pattern = match width {
+                Some(width) => {
+                    let ty = width.ty();
+                    quote! { d if d == (#discriminant) as #ty }
+                }
+                None => quote! { #discriminant },
+            };
+
+            let value = variant.fields.fields.first().map(|field| {
+                let field = field.codec(quote! {}, &operation);
+                quote! { (#field #handle_error) }
+            });
+
+            quote! { #pattern => Ok(#enum_name::#variant_name #value), }
         });
 
+        let fallback = match unknown {
+            Some(variant) => {
+                let variant_name = &variant.ident;
+                let data = variant.fields.fields[1].codec(quote! {}, &operation);
+
+                quote! { d => Ok(#enum_name::#variant_name(d, #data #handle_error)), }
+            }
+            None => quote! { _ => Err(mls_rs_codec::Error::UnsupportedEnumDiscriminant), },
+        };
+
+        let discriminant = match width {
+            Some(width) => width.decode(),
+            None => quote! { #path::#call(#extras)#handle_error },
+        };
+
         return quote! {
-            let discriminant = #path::#call(#extras)#handle_error;
+            let discriminant = #discriminant;
 
             match discriminant {
                 #(#cases)*
-                _ => Err(mls_rs_codec::Error::UnsupportedEnumDiscriminant),
+                #fallback
             }
         };
     }
 
-    let cases = variants.iter().map(|variant| {
+    let start = match operation {
+        Operation::Size => quote! { + },
+        _ => quote! {;},
+    };
+
+    let encode_discriminant = |discriminant: TokenStream| {
+        let discriminant = match width {
+            Some(width) => width.encoded(discriminant),
+            None => discriminant,
+        };
+
+        quote! { #path::#call (&#discriminant #extras) #handle_error }
+    };
+
+    let cases = known.map(|variant| {
         let variant_name = &variant.ident;
 
-        let discriminant = &variant
-            .discriminant
-            .clone()
-            .expect("Enum discriminants must be explicitly defined");
+        // Validated to be present on known variants
+        let discriminant = variant.discriminant.as_ref().unwrap();
 
-        let (parameter, field) = if variant.fields.is_empty() {
-            (None, None)
-        } else {
-            let path = variant.fields.fields[0].with.as_ref().unwrap_or(&path);
+        let discriminant = match width {
+            Some(width) => {
+                let ty = width.ty();
+                quote! { ((#discriminant) as #ty) }
+            }
+            None => quote! { #discriminant },
+        };
 
-            let start = match operation {
-                Operation::Size => Some(quote! { + }),
-                Operation::Encode => Some(quote! {;}),
-                Operation::Decode => None,
-            };
+        let (parameter, field) = match variant.fields.fields.first() {
+            None => (None, None),
+            Some(field) => {
+                let field = field.codec(quote! { val }, &operation);
 
-            (
-                Some(quote! {(ref val)}),
-                Some(quote! { #start #path::#call (val #extras) #handle_error }),
-            )
+                (
+                    Some(quote! {(ref val)}),
+                    Some(quote! { #start #field #handle_error }),
+                )
+            }
         };
 
-        let discrim = quote! { #path::#call (&#discriminant #extras) #handle_error };
+        let discrim = encode_discriminant(discriminant);
 
         quote! { #enum_name::#variant_name #parameter => { #discrim #field }}
     });
 
+    let unknown_case = unknown.map(|variant| {
+        let variant_name = &variant.ident;
+        let discrim = encode_discriminant(quote! { (*d) });
+        let data = variant.fields.fields[1].codec(quote! { data }, &operation);
+
+        quote! { #enum_name::#variant_name(ref d, ref data) => { #discrim #start #data #handle_error }}
+    });
+
     let enum_impl = quote! {
         match self {
             #(#cases)*
+            #unknown_case
         }
     };
 
@@ -185,28 +469,36 @@ fn enum_impl(ident: &Ident, variants: &[MlsVariantReceiver], operation: Operatio
 }
 
 fn struct_impl(s: &Fields<MlsFieldReceiver>, operation: Operation) -> TokenStream {
-    let recurse = s.fields.iter().enumerate().map(|(index, field)| {
-        let (call_tokens, field_name) = match operation {
-            Operation::Size | Operation::Encode => {
-                (field.call_tokens(Index::from(index)), quote! {})
-            }
-            Operation::Decode => (quote! {}, field.name(Index::from(index))),
-        };
-
+    let recurse = s.fields.iter().enumerate().filter_map(|(index, field)| {
         let handle_error = operation.is_result().then_some(quote! { ? });
-        let path = field.with.clone().unwrap_or(operation.path());
-        let call = operation.call();
-        let extras = operation.extras();
 
-        quote! {
-           #field_name #path::#call (#call_tokens #extras) #handle_error
+        match operation {
+            Operation::Size | Operation::Encode => (!field.skip).then(|| {
+                let codec = field.codec(field.call_tokens(Index::from(index)), &operation);
+                quote! { #codec #handle_error }
+            }),
+            Operation::Decode | Operation::DecodeBorrowed => {
+                let field_name = field.name(Index::from(index));
+
+                let value = if field.skip {
+                    field.default.as_ref().map_or_else(
+                        || quote! { Default::default() },
+                        |default| quote! { #default },
+                    )
+                } else {
+                    let codec = field.codec(quote! {}, &operation);
+                    quote! { #codec #handle_error }
+                };
+
+                Some(quote! { #field_name #value })
+            }
         }
     });
 
     match operation {
         Operation::Size => quote! { 0 #(+ #recurse)* },
         Operation::Encode => quote! { #(#recurse;)* Ok(()) },
-        Operation::Decode => quote! { Ok(Self { #(#recurse,)* }) },
+        Operation::Decode | Operation::DecodeBorrowed => quote! { Ok(Self { #(#recurse,)* }) },
     }
 }
 
@@ -220,7 +512,14 @@ where
     F: FnOnce(&MlsInputReceiver) -> TokenStream,
 {
     let input = parse_macro_input!(input as DeriveInput);
-    let input = MlsInputReceiver::from_derive_input(&input).unwrap();
+
+    let input = match MlsInputReceiver::from_derive_input(&input).and_then(|input| {
+        input.validate()?;
+        Ok(input)
+    }) {
+        Ok(input) => input,
+        Err(e) => return e.write_errors().into(),
+    };
 
     let name = &input.ident;
 
@@ -242,6 +541,22 @@ where
     proc_macro::TokenStream::from(expanded)
 }
 
+/// Derive macros of the codec traits share the following `mls_codec` attributes.
+///
+/// On fields:
+/// * `with = "path"` uses the functions of the module at `path` instead of the codec traits.
+///   Decoding calls `mls_decode` and `mls_decode_borrowed`, or `mls_decode_with_context` and
+///   `mls_decode_borrowed_with_context` for the modules of `mls_rs_codec` and if `context` is
+///   set as well.
+/// * `skip` omits the field, which decodes as `Default::default()` or as the expression given
+///   with `default = "expr"`.
+/// * `optional` encodes an `Option` with a presence byte followed by the value, using the
+///   codec of the field for the value.
+/// * `fixed` encodes an array as its items without a length prefix.
+///
+/// On enums, `discriminant = "u8" | "u16" | "u32" | "varint"` sets the encoding of the
+/// discriminants. The variant marked `unknown` holds the discriminant and the data of
+/// variants that are not otherwise defined.
 #[proc_macro_derive(MlsSize, attributes(mls_codec))]
 pub fn derive_size(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let trait_name = quote! { mls_rs_codec::MlsSize };
@@ -256,7 +571,9 @@ pub fn derive_size(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 pub fn derive_encode(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let trait_name = quote! { mls_rs_codec::MlsEncode };
 
-    let function_def = quote! { fn mls_encode(&self, writer: &mut mls_rs_codec::Vec<u8>) -> Result<(), mls_rs_codec::Error> };
+    let function_def = quote! {
+        fn mls_encode<__W: mls_rs_codec::Writer + ?Sized>(&self, writer: &mut __W) -> Result<(), mls_rs_codec::Error>
+    };
 
     derive_impl(input, trait_name, function_def, |input| {
         input.handle_input(Operation::Encode)
@@ -267,10 +584,45 @@ pub fn derive_encode(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 pub fn derive_decode(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let trait_name = quote! { mls_rs_codec::MlsDecode };
 
-    let function_def =
-        quote! { fn mls_decode(reader: &mut &[u8]) -> Result<Self, mls_rs_codec::Error> };
+    let function_def = quote! {
+        fn mls_decode_with_context(
+            reader: &mut &[u8],
+            context: &mut mls_rs_codec::DecodeContext,
+        ) -> Result<Self, mls_rs_codec::Error>
+    };
 
     derive_impl(input, trait_name, function_def, |input| {
         input.handle_input(Operation::Decode)
     })
 }
+
+#[proc_macro_derive(MlsDecodeBorrowed, attributes(mls_codec))]
+pub fn derive_decode_borrowed(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let parsed = match syn::parse::<DeriveInput>(input.clone()) {
+        Ok(parsed) => parsed,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    // The decoded value borrows from the reader for the first lifetime of the type.
+    let Some(lifetime) = parsed.generics.lifetimes().next().map(|l| &l.lifetime) else {
+        return syn::Error::new_spanned(
+            &parsed.ident,
+            "MlsDecodeBorrowed requires a type with a lifetime parameter",
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    let trait_name = quote! { mls_rs_codec::MlsDecodeBorrowed<#lifetime> };
+
+    let function_def = quote! {
+        fn mls_decode_borrowed_with_context(
+            reader: &mut &#lifetime [u8],
+            context: &mut mls_rs_codec::DecodeContext,
+        ) -> Result<Self, mls_rs_codec::Error>
+    };
+
+    derive_impl(input, trait_name, function_def, |input| {
+        input.handle_input(Operation::DecodeBorrowed)
+    })
+}
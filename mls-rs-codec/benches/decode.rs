@@ -0,0 +1,95 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use criterion::{BenchmarkId, Criterion, Throughput};
+use mls_rs_codec::{MlsDecode, MlsDecodeBorrowed, MlsEncode, MlsSize};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn count_allocations<T>(f: impl FnOnce() -> T) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let result = f();
+    let count = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    drop(result);
+
+    count
+}
+
+#[derive(MlsSize, MlsEncode, MlsDecode)]
+struct Message {
+    epoch: u64,
+    #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    authenticated_data: Vec<u8>,
+    #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    ciphertext: Vec<u8>,
+}
+
+#[derive(MlsSize, MlsEncode, MlsDecodeBorrowed)]
+struct MessageRef<'a> {
+    epoch: u64,
+    authenticated_data: &'a [u8],
+    ciphertext: &'a [u8],
+}
+
+fn bench(c: &mut Criterion) {
+    let mut bench_group = c.benchmark_group("decode");
+    let mut n = 100;
+
+    while n <= 1000000 {
+        let data = Message {
+            epoch: 42,
+            authenticated_data: vec![0; 32],
+            ciphertext: vec![0; n],
+        }
+        .mls_encode_to_vec()
+        .unwrap();
+
+        let owned = count_allocations(|| Message::mls_decode(&mut &*data).unwrap());
+        let borrowed = count_allocations(|| MessageRef::mls_decode_borrowed(&mut &*data).unwrap());
+        println!("decode/{n}: {owned} allocations owned, {borrowed} borrowed");
+
+        bench_group.throughput(Throughput::Bytes(n as u64));
+
+        bench_group.bench_with_input(BenchmarkId::new("owned", n), &data, |b, data| {
+            b.iter(|| Message::mls_decode(&mut &**data).unwrap())
+        });
+
+        bench_group.bench_with_input(BenchmarkId::new("borrowed", n), &data, |b, data| {
+            b.iter(|| MessageRef::mls_decode_borrowed(&mut &**data).unwrap())
+        });
+
+        n *= 10;
+    }
+
+    bench_group.finish();
+}
+
+criterion::criterion_group!(benches, bench);
+criterion::criterion_main!(benches);
@@ -3,7 +3,6 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
 use crate::{MlsDecode, MlsEncode, MlsSize};
-use alloc::vec::Vec;
 
 impl<const N: usize> MlsSize for [u8; N] {
     #[inline(always)]
@@ -14,14 +13,16 @@ impl<const N: usize> MlsSize for [u8; N] {
 
 impl<const N: usize> MlsEncode for [u8; N] {
     #[inline(always)]
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), crate::Error> {
-        writer.extend_from_slice(self);
-        Ok(())
+    fn mls_encode<W: crate::Writer + ?Sized>(&self, writer: &mut W) -> Result<(), crate::Error> {
+        writer.write(self)
     }
 }
 
 impl<const N: usize> MlsDecode for [u8; N] {
-    fn mls_decode(reader: &mut &[u8]) -> Result<Self, crate::Error> {
+    fn mls_decode_with_context(
+        reader: &mut &[u8],
+        _context: &mut crate::DecodeContext,
+    ) -> Result<Self, crate::Error> {
         let array = reader
             .get(..N)
             .and_then(|head| head.try_into().ok())
@@ -0,0 +1,88 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use crate::{byte_vec, DecodeContext, Error, MlsDecodeBorrowed};
+
+impl<'a> MlsDecodeBorrowed<'a> for &'a [u8] {
+    #[inline]
+    fn mls_decode_borrowed_with_context(
+        reader: &mut &'a [u8],
+        context: &mut DecodeContext,
+    ) -> Result<Self, Error> {
+        byte_vec::mls_decode_borrowed_with_context(reader, context)
+    }
+}
+
+impl<'a> MlsDecodeBorrowed<'a> for &'a str {
+    fn mls_decode_borrowed_with_context(
+        reader: &mut &'a [u8],
+        context: &mut DecodeContext,
+    ) -> Result<Self, Error> {
+        core::str::from_utf8(<&[u8]>::mls_decode_borrowed_with_context(reader, context)?)
+            .map_err(|_| Error::Utf8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, MlsDecode, MlsDecodeBorrowed, MlsEncode};
+    use alloc::{borrow::Cow, vec};
+    use assert_matches::assert_matches;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    #[test]
+    fn slice_is_borrowed_from_input() {
+        let data = vec![1u8, 2, 3].mls_encode_to_vec().unwrap();
+        let reader = &mut &*data;
+        let decoded = <&[u8]>::mls_decode_borrowed(reader).unwrap();
+
+        assert_eq!(decoded, [1, 2, 3]);
+        assert_eq!(decoded.as_ptr(), data[1..].as_ptr());
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn cow_is_borrowed_from_input() {
+        let data = vec![1u8, 2, 3].mls_encode_to_vec().unwrap();
+
+        let decoded: Cow<[u8]> = crate::byte_vec::mls_decode_borrowed(&mut &*data).unwrap();
+
+        assert_matches!(decoded, Cow::Borrowed(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn str_is_borrowed_from_input() {
+        let data = "bar".mls_encode_to_vec().unwrap();
+        assert_eq!(<&str>::mls_decode_borrowed(&mut &*data).unwrap(), "bar");
+    }
+
+    #[test]
+    fn owned_types_decode_as_borrowed() {
+        let data = (vec![1u8, 2], 42u16).mls_encode_to_vec().unwrap();
+        let reader = &mut &*data;
+
+        assert_eq!(
+            <(alloc::vec::Vec<u8>, u16)>::mls_decode_borrowed(reader).unwrap(),
+            <(alloc::vec::Vec<u8>, u16)>::mls_decode(&mut &*data).unwrap()
+        );
+    }
+
+    #[test]
+    fn too_short_slice_gives_an_error() {
+        assert_matches!(
+            <&[u8]>::mls_decode_borrowed(&mut &[2, 3][..]),
+            Err(Error::UnexpectedEOF)
+        );
+    }
+
+    #[test]
+    fn invalid_utf8_gives_an_error() {
+        assert_matches!(
+            <&str>::mls_decode_borrowed(&mut &[0x02, 0xdf, 0xff][..]),
+            Err(Error::Utf8)
+        );
+    }
+}
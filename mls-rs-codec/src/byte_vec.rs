@@ -2,7 +2,9 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use crate::{iter::mls_decode_split_on_collection, Error, MlsEncode, MlsSize, VarInt};
+use crate::{
+    iter::mls_decode_split_on_collection, DecodeContext, Error, MlsEncode, MlsSize, VarInt, Writer,
+};
 
 use alloc::vec::Vec;
 
@@ -22,15 +24,16 @@ where
 }
 
 /// Optimized encoding for types that can be represented as u8 slices.
-pub fn mls_encode<T>(data: &T, writer: &mut Vec<u8>) -> Result<(), Error>
+pub fn mls_encode<T, W>(data: &T, writer: &mut W) -> Result<(), Error>
 where
     T: AsRef<[u8]>,
+    W: Writer + ?Sized,
 {
-    fn encode_slice(data: &[u8], writer: &mut Vec<u8>) -> Result<(), Error> {
+    fn encode_slice<W: Writer + ?Sized>(data: &[u8], writer: &mut W) -> Result<(), Error> {
         let len = VarInt::try_from(data.len())?;
 
         len.mls_encode(writer)?;
-        writer.extend_from_slice(data);
+        writer.write(data)?;
 
         Ok(())
     }
@@ -43,14 +46,51 @@ pub fn mls_decode<T>(reader: &mut &[u8]) -> Result<T, crate::Error>
 where
     T: From<Vec<u8>>,
 {
-    fn decode_vec(reader: &mut &[u8]) -> Result<Vec<u8>, crate::Error> {
-        let (data, rest) = mls_decode_split_on_collection(reader)?;
+    mls_decode_with_context(reader, &mut DecodeContext::unlimited())
+}
+
+/// Optimized decoding for types that can be represented as `Vec<u8>`, checking the length
+/// against the limits of `context`.
+pub fn mls_decode_with_context<T>(
+    reader: &mut &[u8],
+    context: &mut DecodeContext,
+) -> Result<T, crate::Error>
+where
+    T: From<Vec<u8>>,
+{
+    fn decode_vec(reader: &mut &[u8], context: &DecodeContext) -> Result<Vec<u8>, crate::Error> {
+        let (data, rest) = mls_decode_split_on_collection(reader, context)?;
 
         *reader = rest;
 
         Ok(data.to_vec())
     }
 
-    let out = decode_vec(reader)?;
+    let out = decode_vec(reader, context)?;
     Ok(out.into())
 }
+
+/// Decoding for types that can be created from a `&[u8]` borrowed from the input, such as
+/// `&[u8]` or `Cow<[u8]>`.
+pub fn mls_decode_borrowed<'a, T>(reader: &mut &'a [u8]) -> Result<T, crate::Error>
+where
+    T: From<&'a [u8]>,
+{
+    mls_decode_borrowed_with_context(reader, &mut DecodeContext::unlimited())
+}
+
+/// Borrowed decoding as [`mls_decode_borrowed`], checking the length against the limits of
+/// `context`.
+pub fn mls_decode_borrowed_with_context<'a, T>(
+    reader: &mut &'a [u8],
+    context: &mut DecodeContext,
+) -> Result<T, crate::Error>
+where
+    T: From<&'a [u8]>,
+{
+    let (data, rest) = mls_decode_split_on_collection(reader, context)?;
+
+    *reader = rest;
+
+    Ok(data.into())
+}
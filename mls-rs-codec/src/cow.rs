@@ -1,9 +1,6 @@
-use alloc::{
-    borrow::{Cow, ToOwned},
-    vec::Vec,
-};
+use alloc::borrow::{Cow, ToOwned};
 
-use crate::{Error, MlsDecode, MlsEncode, MlsSize};
+use crate::{DecodeContext, Error, MlsDecode, MlsEncode, MlsSize, Writer};
 
 impl<'a, T> MlsSize for Cow<'a, T>
 where
@@ -19,7 +16,7 @@ where
     T: MlsEncode + ToOwned,
 {
     #[inline]
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), Error> {
+    fn mls_encode<W: Writer + ?Sized>(&self, writer: &mut W) -> Result<(), Error> {
         self.as_ref().mls_encode(writer)
     }
 }
@@ -29,7 +26,10 @@ where
     T: ToOwned,
     <T as ToOwned>::Owned: MlsDecode,
 {
-    fn mls_decode(reader: &mut &[u8]) -> Result<Self, Error> {
-        MlsDecode::mls_decode(reader).map(Cow::Owned)
+    fn mls_decode_with_context(
+        reader: &mut &[u8],
+        context: &mut DecodeContext,
+    ) -> Result<Self, Error> {
+        MlsDecode::mls_decode_with_context(reader, context).map(Cow::Owned)
     }
 }
@@ -0,0 +1,94 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::vec::Vec;
+
+use crate::{DecodeContext, Error, MlsDecode, MlsDecodeBorrowed, MlsEncode, MlsSize, Writer};
+
+pub fn mls_encoded_len<T, const N: usize>(data: &[T; N]) -> usize
+where
+    T: MlsSize,
+{
+    data.iter().map(|item| item.mls_encoded_len()).sum()
+}
+
+pub fn mls_encode<T, W, const N: usize>(data: &[T; N], writer: &mut W) -> Result<(), Error>
+where
+    T: MlsEncode,
+    W: Writer + ?Sized,
+{
+    data.iter().try_for_each(|item| item.mls_encode(writer))
+}
+
+pub fn mls_decode<T, const N: usize>(reader: &mut &[u8]) -> Result<[T; N], Error>
+where
+    T: MlsDecode,
+{
+    mls_decode_with_context(reader, &mut DecodeContext::unlimited())
+}
+
+pub fn mls_decode_with_context<T, const N: usize>(
+    reader: &mut &[u8],
+    context: &mut DecodeContext,
+) -> Result<[T; N], Error>
+where
+    T: MlsDecode,
+{
+    collect((0..N).map(|_| T::mls_decode_with_context(reader, context)))
+}
+
+pub fn mls_decode_borrowed<'a, T, const N: usize>(reader: &mut &'a [u8]) -> Result<[T; N], Error>
+where
+    T: MlsDecodeBorrowed<'a>,
+{
+    mls_decode_borrowed_with_context(reader, &mut DecodeContext::unlimited())
+}
+
+pub fn mls_decode_borrowed_with_context<'a, T, const N: usize>(
+    reader: &mut &'a [u8],
+    context: &mut DecodeContext,
+) -> Result<[T; N], Error>
+where
+    T: MlsDecodeBorrowed<'a>,
+{
+    collect((0..N).map(|_| T::mls_decode_borrowed_with_context(reader, context)))
+}
+
+fn collect<T, const N: usize>(
+    items: impl Iterator<Item = Result<T, Error>>,
+) -> Result<[T; N], Error> {
+    let items = items.collect::<Result<Vec<_>, _>>()?;
+
+    // The iterator yields exactly N items.
+    items.try_into().map_err(|_| Error::UnexpectedEOF)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use assert_matches::assert_matches;
+
+    use crate::Error;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    #[test]
+    fn items_are_encoded_without_length() {
+        let mut data = Vec::new();
+        super::mls_encode(&[1u16, 2, 3], &mut data).unwrap();
+
+        assert_eq!(data, [0, 1, 0, 2, 0, 3]);
+        assert_eq!(super::mls_encoded_len(&[1u16, 2, 3]), 6);
+
+        let decoded: [u16; 3] = super::mls_decode(&mut &*data).unwrap();
+        assert_eq!(decoded, [1, 2, 3]);
+    }
+
+    #[test]
+    fn too_few_items_give_an_error() {
+        let res: Result<[u16; 3], _> = super::mls_decode(&mut &[0u8, 1, 0, 2][..]);
+        assert_matches!(res, Err(Error::UnexpectedEOF));
+    }
+}
@@ -2,8 +2,9 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use crate::{MlsDecode, MlsEncode, MlsSize, VarInt};
+use crate::{DecodeContext, MlsDecode, MlsEncode, MlsSize, VarInt, Writer};
 
+#[cfg(not(feature = "preallocate"))]
 use alloc::vec::Vec;
 
 pub fn mls_encoded_len<T>(iter: impl Iterator<Item = T>) -> usize
@@ -18,10 +19,11 @@ where
 }
 
 #[cfg(feature = "preallocate")]
-pub fn mls_encode<I>(iter: I, writer: &mut Vec<u8>) -> Result<(), crate::Error>
+pub fn mls_encode<I, W>(iter: I, writer: &mut W) -> Result<(), crate::Error>
 where
     I: IntoIterator + Clone,
     I::Item: MlsEncode,
+    W: Writer + ?Sized,
 {
     let len = iter
         .clone()
@@ -40,10 +42,11 @@ where
 }
 
 #[cfg(not(feature = "preallocate"))]
-pub fn mls_encode<I>(iter: I, writer: &mut Vec<u8>) -> Result<(), crate::Error>
+pub fn mls_encode<I, W>(iter: I, writer: &mut W) -> Result<(), crate::Error>
 where
     I: IntoIterator + Clone,
     I::Item: MlsEncode,
+    W: Writer + ?Sized,
 {
     let mut buffer = Vec::new();
 
@@ -53,26 +56,35 @@ where
     let len = VarInt::try_from(buffer.len())?;
 
     len.mls_encode(writer)?;
-    writer.extend(buffer);
+    writer.write(&buffer)?;
 
     Ok(())
 }
 
-pub fn mls_decode_collection<T, F>(reader: &mut &[u8], item_decode: F) -> Result<T, crate::Error>
+/// Decode the items of a length-prefixed collection with `item_decode`, one nesting level
+/// deeper in `context`.
+pub fn mls_decode_collection<T, F>(
+    reader: &mut &[u8],
+    context: &mut DecodeContext,
+    item_decode: F,
+) -> Result<T, crate::Error>
 where
-    F: Fn(&mut &[u8]) -> Result<T, crate::Error>,
+    F: FnOnce(&mut &[u8], &mut DecodeContext) -> Result<T, crate::Error>,
 {
-    let (mut data, rest) = mls_decode_split_on_collection(reader)?;
+    let (mut data, rest) = mls_decode_split_on_collection(reader, context)?;
 
-    let items = item_decode(&mut data)?;
+    let items = context.nested(|context| item_decode(&mut data, context))?;
 
     *reader = rest;
 
     Ok(items)
 }
 
+/// Split a length-prefixed collection off `reader`, returning its content and the rest of the
+/// input.
 pub fn mls_decode_split_on_collection<'b>(
     reader: &mut &'b [u8],
+    context: &DecodeContext,
 ) -> Result<(&'b [u8], &'b [u8]), crate::Error> {
     let len = VarInt::mls_decode(reader)?.0 as usize;
 
@@ -80,5 +92,7 @@ pub fn mls_decode_split_on_collection<'b>(
         return Err(crate::Error::UnexpectedEOF);
     }
 
+    context.check_vec_len(len)?;
+
     Ok(reader.split_at(len))
 }
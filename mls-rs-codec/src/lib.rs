@@ -16,8 +16,18 @@ mod array;
 /// Compatible with derive macros by using `mls_codec(with = "mls_rs_codec::byte_vec")`
 pub mod byte_vec;
 
+/// Encoding of fixed-length arrays as their items without a length prefix.
+///
+/// Compatible with derive macros by using `mls_codec(fixed)` or
+/// `mls_codec(with = "mls_rs_codec::fixed_array")`
+pub mod fixed_array;
+
 pub mod iter;
 
+/// Limits on the size and nesting of decoded input.
+pub mod limits;
+
+mod borrowed;
 mod cow;
 mod map;
 mod option;
@@ -26,8 +36,13 @@ mod string;
 mod tuple;
 mod varint;
 mod vec;
+mod writer;
 
+pub use limits::{DecodeContext, DecodeLimits};
 pub use varint::*;
+#[cfg(feature = "std")]
+pub use writer::IoWriter;
+pub use writer::{DigestWriter, Writer};
 
 pub use mls_rs_codec_derive::*;
 
@@ -51,6 +66,18 @@ pub enum Error {
     Utf8,
     #[cfg_attr(feature = "std", error("mls codec error: {0}"))]
     Custom(u8),
+    #[cfg_attr(
+        feature = "std",
+        error("Vector of length {0} exceeds the decode limit")
+    )]
+    VectorTooLong(usize),
+    #[cfg_attr(feature = "std", error("Input of length {0} exceeds the decode limit"))]
+    TotalSizeExceeded(usize),
+    #[cfg_attr(feature = "std", error("Nesting exceeds the decode limit"))]
+    MaxDepthExceeded,
+    #[cfg(feature = "std")]
+    #[cfg_attr(feature = "std", error(transparent))]
+    Io(std::io::Error),
 }
 
 /// Trait that determines the encoded length in MLS encoding.
@@ -80,7 +107,7 @@ where
 
 /// Trait to support serializing a type with MLS encoding.
 pub trait MlsEncode: MlsSize {
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), Error>;
+    fn mls_encode<W: Writer + ?Sized>(&self, writer: &mut W) -> Result<(), Error>;
 
     #[inline]
     fn mls_encode_to_vec(&self) -> Result<Vec<u8>, Error> {
@@ -101,7 +128,7 @@ where
     T: MlsEncode + ?Sized,
 {
     #[inline]
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), Error> {
+    fn mls_encode<W: Writer + ?Sized>(&self, writer: &mut W) -> Result<(), Error> {
         (*self).mls_encode(writer)
     }
 }
@@ -111,14 +138,43 @@ where
     T: MlsEncode + ?Sized,
 {
     #[inline]
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), Error> {
+    fn mls_encode<W: Writer + ?Sized>(&self, writer: &mut W) -> Result<(), Error> {
         self.as_ref().mls_encode(writer)
     }
 }
 
 /// Trait to support deserialzing to a type using MLS encoding.
+///
+/// Implementations must provide at least one of [`mls_decode`](Self::mls_decode) and
+/// [`mls_decode_with_context`](Self::mls_decode_with_context), as each defaults to the
+/// other. Types only implementing `mls_decode` do not pass the limits on to nested values,
+/// although the total length of the input is still checked by
+/// [`mls_decode_with_limits`](Self::mls_decode_with_limits).
 pub trait MlsDecode: Sized {
-    fn mls_decode(reader: &mut &[u8]) -> Result<Self, Error>;
+    /// Decode a value, passing `context` down to nested values so that they are checked
+    /// against its limits.
+    #[inline]
+    fn mls_decode_with_context(
+        reader: &mut &[u8],
+        _context: &mut DecodeContext,
+    ) -> Result<Self, Error> {
+        Self::mls_decode(reader)
+    }
+
+    /// Decode trusted input without limits.
+    #[inline]
+    fn mls_decode(reader: &mut &[u8]) -> Result<Self, Error> {
+        Self::mls_decode_with_context(reader, &mut DecodeContext::unlimited())
+    }
+
+    /// Decode untrusted input, failing if it exceeds `limits`.
+    #[inline]
+    fn mls_decode_with_limits(reader: &mut &[u8], limits: DecodeLimits) -> Result<Self, Error> {
+        let mut context = DecodeContext::new(limits);
+        context.check_total_len(reader.len())?;
+
+        Self::mls_decode_with_context(reader, &mut context)
+    }
 }
 
 impl<T> MlsDecode for Box<T>
@@ -126,7 +182,56 @@ where
     T: MlsDecode + ?Sized,
 {
     #[inline]
-    fn mls_decode(reader: &mut &[u8]) -> Result<Self, Error> {
-        T::mls_decode(reader).map(Box::new)
+    fn mls_decode_with_context(
+        reader: &mut &[u8],
+        context: &mut DecodeContext,
+    ) -> Result<Self, Error> {
+        T::mls_decode_with_context(reader, context).map(Box::new)
+    }
+}
+
+/// Trait to support deserializing to a type that borrows from the input, without copying
+/// byte vectors.
+///
+/// Types implementing [`MlsDecode`] implement this trait by decoding an owned value. Types
+/// with a lifetime can derive it, using `&'a [u8]` for byte fields, or `Cow<'a, [u8]>` with
+/// `mls_codec(with = "mls_rs_codec::byte_vec")`.
+pub trait MlsDecodeBorrowed<'a>: Sized {
+    /// Decode a value, passing `context` down to nested values so that they are checked
+    /// against its limits.
+    fn mls_decode_borrowed_with_context(
+        reader: &mut &'a [u8],
+        context: &mut DecodeContext,
+    ) -> Result<Self, Error>;
+
+    /// Decode trusted input without limits.
+    #[inline]
+    fn mls_decode_borrowed(reader: &mut &'a [u8]) -> Result<Self, Error> {
+        Self::mls_decode_borrowed_with_context(reader, &mut DecodeContext::unlimited())
+    }
+
+    /// Decode untrusted input, failing if it exceeds `limits`.
+    #[inline]
+    fn mls_decode_borrowed_with_limits(
+        reader: &mut &'a [u8],
+        limits: DecodeLimits,
+    ) -> Result<Self, Error> {
+        let mut context = DecodeContext::new(limits);
+        context.check_total_len(reader.len())?;
+
+        Self::mls_decode_borrowed_with_context(reader, &mut context)
+    }
+}
+
+impl<'a, T> MlsDecodeBorrowed<'a> for T
+where
+    T: MlsDecode,
+{
+    #[inline]
+    fn mls_decode_borrowed_with_context(
+        reader: &mut &'a [u8],
+        context: &mut DecodeContext,
+    ) -> Result<Self, Error> {
+        T::mls_decode_with_context(reader, context)
     }
 }
@@ -0,0 +1,192 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use crate::Error;
+
+/// Bounds applied while decoding untrusted input with
+/// [`MlsDecode::mls_decode_with_limits`](crate::MlsDecode::mls_decode_with_limits).
+///
+/// The limits are carried by a [`DecodeContext`] through the decoding of nested values.
+/// [`DecodeLimits::default`] returns [`DecodeLimits::DEFAULT`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodeLimits {
+    /// Maximum length in bytes of a single vector.
+    pub max_vec_len: usize,
+    /// Maximum length in bytes of the input.
+    pub max_total_bytes: usize,
+    /// Maximum nesting of vectors and derived types.
+    pub max_depth: usize,
+}
+
+impl DecodeLimits {
+    /// Limits that accept any input.
+    pub const UNLIMITED: Self = Self {
+        max_vec_len: usize::MAX,
+        max_total_bytes: usize::MAX,
+        max_depth: usize::MAX,
+    };
+
+    /// Limits for untrusted input: 64 MiB per vector, 128 MiB in total and a nesting
+    /// depth of 64, well above what valid MLS messages require.
+    pub const DEFAULT: Self = Self {
+        max_vec_len: 1 << 26,
+        max_total_bytes: 1 << 27,
+        max_depth: 64,
+    };
+
+    pub fn with_max_vec_len(self, max_vec_len: usize) -> Self {
+        Self {
+            max_vec_len,
+            ..self
+        }
+    }
+
+    pub fn with_max_total_bytes(self, max_total_bytes: usize) -> Self {
+        Self {
+            max_total_bytes,
+            ..self
+        }
+    }
+
+    pub fn with_max_depth(self, max_depth: usize) -> Self {
+        Self { max_depth, ..self }
+    }
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Limits and current nesting depth of a decode, passed down to nested values by
+/// [`MlsDecode::mls_decode_with_context`](crate::MlsDecode::mls_decode_with_context).
+///
+/// Limits are checked by [`mls_decode_collection`](crate::iter::mls_decode_collection),
+/// [`mls_decode_split_on_collection`](crate::iter::mls_decode_split_on_collection) and
+/// [`DecodeContext::nested`], which are used by all collection types and derived
+/// implementations.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DecodeContext {
+    limits: DecodeLimits,
+    depth: usize,
+}
+
+impl DecodeContext {
+    pub fn new(limits: DecodeLimits) -> Self {
+        Self { limits, depth: 0 }
+    }
+
+    /// Context accepting any input.
+    pub fn unlimited() -> Self {
+        Self::new(DecodeLimits::UNLIMITED)
+    }
+
+    pub fn limits(&self) -> DecodeLimits {
+        self.limits
+    }
+
+    /// Fail if a vector of length `len` exceeds the limits.
+    pub fn check_vec_len(&self, len: usize) -> Result<(), Error> {
+        if len > self.limits.max_vec_len {
+            return Err(Error::VectorTooLong(len));
+        }
+
+        Ok(())
+    }
+
+    /// Fail if an input of length `len` exceeds the limits.
+    pub fn check_total_len(&self, len: usize) -> Result<(), Error> {
+        if len > self.limits.max_total_bytes {
+            return Err(Error::TotalSizeExceeded(len));
+        }
+
+        Ok(())
+    }
+
+    /// Run `decode` one nesting level deeper, failing if the maximum depth is exceeded.
+    ///
+    /// Derived implementations decode within `nested`. Manual implementations of recursive
+    /// types should do the same.
+    #[inline]
+    pub fn nested<T, F>(&mut self, decode: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut Self) -> Result<T, Error>,
+    {
+        if self.depth >= self.limits.max_depth {
+            return Err(Error::MaxDepthExceeded);
+        }
+
+        self.depth += 1;
+        let res = decode(self);
+        self.depth -= 1;
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use assert_matches::assert_matches;
+
+    use crate::{DecodeLimits, Error, MlsDecode, MlsEncode};
+
+    #[test]
+    fn long_vectors_are_rejected() {
+        let data = vec![0u8; 100].mls_encode_to_vec().unwrap();
+        let limits = DecodeLimits::default().with_max_vec_len(99);
+
+        let res = Vec::<u8>::mls_decode_with_limits(&mut &*data, limits);
+        assert_matches!(res, Err(Error::VectorTooLong(100)));
+
+        let limits = limits.with_max_vec_len(100);
+        let res = Vec::<u8>::mls_decode_with_limits(&mut &*data, limits);
+        assert_eq!(res.unwrap(), vec![0u8; 100]);
+    }
+
+    #[test]
+    fn long_inputs_are_rejected() {
+        let data = vec![0u8; 100].mls_encode_to_vec().unwrap();
+        let limits = DecodeLimits::default().with_max_total_bytes(100);
+
+        let res = Vec::<u8>::mls_decode_with_limits(&mut &*data, limits);
+        assert_matches!(res, Err(Error::TotalSizeExceeded(102)));
+    }
+
+    #[test]
+    fn deep_nesting_is_rejected() {
+        let data = vec![vec![vec![1u8]]].mls_encode_to_vec().unwrap();
+        let limits = DecodeLimits::default().with_max_depth(2);
+
+        let res = Vec::<Vec<Vec<u8>>>::mls_decode_with_limits(&mut &*data, limits);
+        assert_matches!(res, Err(Error::MaxDepthExceeded));
+
+        let res =
+            Vec::<Vec<Vec<u8>>>::mls_decode_with_limits(&mut &*data, limits.with_max_depth(3));
+        assert_eq!(res.unwrap(), vec![vec![vec![1u8]]]);
+    }
+
+    #[test]
+    fn depth_is_restored_after_nested_values() {
+        let data = (vec![vec![1u8]], vec![vec![2u8]])
+            .mls_encode_to_vec()
+            .unwrap();
+
+        let limits = DecodeLimits::default().with_max_depth(2);
+        let res = <(Vec<Vec<u8>>, Vec<Vec<u8>>)>::mls_decode_with_limits(&mut &*data, limits);
+
+        assert_eq!(res.unwrap(), (vec![vec![1u8]], vec![vec![2u8]]));
+    }
+
+    #[test]
+    fn limits_only_apply_within_decode_with_limits() {
+        let data = vec![0u8; 100].mls_encode_to_vec().unwrap();
+        let limits = DecodeLimits::default().with_max_vec_len(1);
+
+        assert!(Vec::<u8>::mls_decode_with_limits(&mut &*data, limits).is_err());
+        assert_eq!(Vec::<u8>::mls_decode(&mut &*data).unwrap(), vec![0u8; 100]);
+    }
+}
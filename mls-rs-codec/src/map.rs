@@ -2,7 +2,7 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use alloc::{collections::BTreeMap, vec::Vec};
+use alloc::collections::BTreeMap;
 
 #[cfg(feature = "std")]
 use std::{collections::HashMap, hash::Hash};
@@ -26,7 +26,7 @@ where
     K: MlsEncode,
     V: MlsEncode,
 {
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), crate::Error> {
+    fn mls_encode<W: crate::Writer + ?Sized>(&self, writer: &mut W) -> Result<(), crate::Error> {
         crate::iter::mls_encode(self.iter(), writer)
     }
 }
@@ -37,12 +37,18 @@ where
     K: MlsDecode + Hash + Eq,
     V: MlsDecode,
 {
-    fn mls_decode(reader: &mut &[u8]) -> Result<Self, crate::Error> {
-        crate::iter::mls_decode_collection(reader, |data| {
+    fn mls_decode_with_context(
+        reader: &mut &[u8],
+        context: &mut crate::DecodeContext,
+    ) -> Result<Self, crate::Error> {
+        crate::iter::mls_decode_collection(reader, context, |data, context| {
             let mut items = HashMap::new();
 
             while !data.is_empty() {
-                items.insert(K::mls_decode(data)?, V::mls_decode(data)?);
+                items.insert(
+                    K::mls_decode_with_context(data, context)?,
+                    V::mls_decode_with_context(data, context)?,
+                );
             }
 
             Ok(items)
@@ -65,7 +71,7 @@ where
     K: MlsEncode,
     V: MlsEncode,
 {
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), crate::Error> {
+    fn mls_encode<W: crate::Writer + ?Sized>(&self, writer: &mut W) -> Result<(), crate::Error> {
         crate::iter::mls_encode(self.iter(), writer)
     }
 }
@@ -75,12 +81,18 @@ where
     K: MlsDecode + Eq + Ord,
     V: MlsDecode,
 {
-    fn mls_decode(reader: &mut &[u8]) -> Result<Self, crate::Error> {
-        crate::iter::mls_decode_collection(reader, |data| {
+    fn mls_decode_with_context(
+        reader: &mut &[u8],
+        context: &mut crate::DecodeContext,
+    ) -> Result<Self, crate::Error> {
+        crate::iter::mls_decode_collection(reader, context, |data, context| {
             let mut items = BTreeMap::new();
 
             while !data.is_empty() {
-                items.insert(K::mls_decode(data)?, V::mls_decode(data)?);
+                items.insert(
+                    K::mls_decode_with_context(data, context)?,
+                    V::mls_decode_with_context(data, context)?,
+                );
             }
 
             Ok(items)
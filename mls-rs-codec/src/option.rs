@@ -3,7 +3,6 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
 use crate::{MlsDecode, MlsEncode, MlsSize};
-use alloc::vec::Vec;
 
 impl<T: MlsSize> MlsSize for Option<T> {
     #[inline]
@@ -16,22 +15,25 @@ impl<T: MlsSize> MlsSize for Option<T> {
 }
 
 impl<T: MlsEncode> MlsEncode for Option<T> {
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), crate::Error> {
+    fn mls_encode<W: crate::Writer + ?Sized>(&self, writer: &mut W) -> Result<(), crate::Error> {
         if let Some(item) = self {
-            writer.push(1);
+            writer.write(&[1])?;
             item.mls_encode(writer)
         } else {
-            writer.push(0);
+            writer.write(&[0])?;
             Ok(())
         }
     }
 }
 
 impl<T: MlsDecode> MlsDecode for Option<T> {
-    fn mls_decode(reader: &mut &[u8]) -> Result<Self, crate::Error> {
+    fn mls_decode_with_context(
+        reader: &mut &[u8],
+        context: &mut crate::DecodeContext,
+    ) -> Result<Self, crate::Error> {
         match u8::mls_decode(reader)? {
             0 => Ok(None),
-            1 => T::mls_decode(reader).map(Some),
+            1 => T::mls_decode_with_context(reader, context).map(Some),
             n => Err(crate::Error::OptionOutOfRange(n)),
         }
     }
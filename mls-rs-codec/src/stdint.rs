@@ -3,7 +3,6 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
 use crate::{MlsDecode, MlsEncode, MlsSize};
-use alloc::vec::Vec;
 
 macro_rules! impl_stdint {
     ($t:ty) => {
@@ -14,15 +13,20 @@ macro_rules! impl_stdint {
         }
 
         impl MlsEncode for $t {
-            fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), crate::Error> {
-                writer.extend(self.to_be_bytes());
-                Ok(())
+            fn mls_encode<W: crate::Writer + ?Sized>(
+                &self,
+                writer: &mut W,
+            ) -> Result<(), crate::Error> {
+                writer.write(&self.to_be_bytes())
             }
         }
 
         impl MlsDecode for $t {
-            fn mls_decode(reader: &mut &[u8]) -> Result<Self, crate::Error> {
-                MlsDecode::mls_decode(reader).map(<$t>::from_be_bytes)
+            fn mls_decode_with_context(
+                reader: &mut &[u8],
+                context: &mut crate::DecodeContext,
+            ) -> Result<Self, crate::Error> {
+                MlsDecode::mls_decode_with_context(reader, context).map(<$t>::from_be_bytes)
             }
         }
     };
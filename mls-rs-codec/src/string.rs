@@ -8,7 +8,7 @@ impl MlsSize for str {
 }
 
 impl MlsEncode for str {
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), crate::Error> {
+    fn mls_encode<W: crate::Writer + ?Sized>(&self, writer: &mut W) -> Result<(), crate::Error> {
         self.as_bytes().mls_encode(writer)
     }
 }
@@ -20,14 +20,18 @@ impl MlsSize for String {
 }
 
 impl MlsEncode for String {
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), crate::Error> {
+    fn mls_encode<W: crate::Writer + ?Sized>(&self, writer: &mut W) -> Result<(), crate::Error> {
         self.as_str().mls_encode(writer)
     }
 }
 
 impl MlsDecode for String {
-    fn mls_decode(reader: &mut &[u8]) -> Result<Self, crate::Error> {
-        String::from_utf8(Vec::mls_decode(reader)?).map_err(|_| crate::Error::Utf8)
+    fn mls_decode_with_context(
+        reader: &mut &[u8],
+        context: &mut crate::DecodeContext,
+    ) -> Result<Self, crate::Error> {
+        String::from_utf8(Vec::mls_decode_with_context(reader, context)?)
+            .map_err(|_| crate::Error::Utf8)
     }
 }
 
@@ -4,8 +4,6 @@
 
 use crate::{MlsDecode, MlsEncode, MlsSize};
 
-use alloc::vec::Vec;
-
 impl<T, U> MlsSize for (T, U)
 where
     T: MlsSize,
@@ -21,7 +19,7 @@ where
     T: MlsEncode,
     U: MlsEncode,
 {
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), crate::Error> {
+    fn mls_encode<W: crate::Writer + ?Sized>(&self, writer: &mut W) -> Result<(), crate::Error> {
         self.0.mls_encode(writer)?;
         self.1.mls_encode(writer)
     }
@@ -32,7 +30,13 @@ where
     T: MlsDecode,
     U: MlsDecode,
 {
-    fn mls_decode(reader: &mut &[u8]) -> Result<Self, crate::Error> {
-        Ok((T::mls_decode(reader)?, U::mls_decode(reader)?))
+    fn mls_decode_with_context(
+        reader: &mut &[u8],
+        context: &mut crate::DecodeContext,
+    ) -> Result<Self, crate::Error> {
+        Ok((
+            T::mls_decode_with_context(reader, context)?,
+            U::mls_decode_with_context(reader, context)?,
+        ))
     }
 }
@@ -2,8 +2,7 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use crate::{Error, MlsDecode, MlsEncode, MlsSize};
-use alloc::vec::Vec;
+use crate::{Error, MlsDecode, MlsEncode, MlsSize, Writer};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct VarInt(pub u32);
@@ -47,7 +46,7 @@ impl MlsSize for VarInt {
 }
 
 impl MlsEncode for VarInt {
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), Error> {
+    fn mls_encode<W: Writer + ?Sized>(&self, writer: &mut W) -> Result<(), Error> {
         let mut bytes = self.0.to_be_bytes();
 
         let bytes = match count_bytes_to_encode_int(*self) {
@@ -62,13 +61,15 @@ impl MlsEncode for VarInt {
             }
         };
 
-        writer.extend_from_slice(bytes);
-        Ok(())
+        writer.write(bytes)
     }
 }
 
 impl MlsDecode for VarInt {
-    fn mls_decode(reader: &mut &[u8]) -> Result<Self, Error> {
+    fn mls_decode_with_context(
+        reader: &mut &[u8],
+        _context: &mut crate::DecodeContext,
+    ) -> Result<Self, Error> {
         let first = u8::mls_decode(reader)?;
 
         let prefix = first >> 6;
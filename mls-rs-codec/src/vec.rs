@@ -29,7 +29,7 @@ impl<T> MlsEncode for [T]
 where
     T: MlsEncode,
 {
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), crate::Error> {
+    fn mls_encode<W: crate::Writer + ?Sized>(&self, writer: &mut W) -> Result<(), crate::Error> {
         crate::iter::mls_encode(self.iter(), writer)
     }
 }
@@ -39,7 +39,7 @@ where
     T: MlsEncode,
 {
     #[inline]
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), crate::Error> {
+    fn mls_encode<W: crate::Writer + ?Sized>(&self, writer: &mut W) -> Result<(), crate::Error> {
         self.as_slice().mls_encode(writer)
     }
 }
@@ -48,12 +48,15 @@ impl<T> MlsDecode for Vec<T>
 where
     T: MlsDecode,
 {
-    fn mls_decode(reader: &mut &[u8]) -> Result<Self, crate::Error> {
-        crate::iter::mls_decode_collection(reader, |data| {
+    fn mls_decode_with_context(
+        reader: &mut &[u8],
+        context: &mut crate::DecodeContext,
+    ) -> Result<Self, crate::Error> {
+        crate::iter::mls_decode_collection(reader, context, |data, context| {
             let mut items = Vec::new();
 
             while !data.is_empty() {
-                items.push(T::mls_decode(data)?);
+                items.push(T::mls_decode_with_context(data, context)?);
             }
 
             Ok(items)
@@ -6,8 +6,17 @@ use crate::Error;
 
 use alloc::vec::Vec;
 
+/// Destination for MLS encoded data.
+///
+/// Implemented for `Vec<u8>`, preallocated `&mut [u8]` buffers, which are advanced past
+/// the written data and fail with [`Error::UnexpectedEOF`] when full, hash functions via
+/// [`DigestWriter`] and, with the `std` feature, any [`std::io::Write`] via [`IoWriter`].
 pub trait Writer {
     fn write(&mut self, bytes: &[u8]) -> Result<(), Error>;
+
+    /// Hint that at least `additional` more bytes are about to be written.
+    #[inline]
+    fn reserve(&mut self, _additional: usize) {}
 }
 
 impl<T: Writer + ?Sized> Writer for &mut T {
@@ -15,6 +24,11 @@ impl<T: Writer + ?Sized> Writer for &mut T {
     fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
         (**self).write(bytes)
     }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        (**self).reserve(additional)
+    }
 }
 
 impl Writer for &mut [u8] {
@@ -37,4 +51,149 @@ impl Writer for Vec<u8> {
         self.extend_from_slice(bytes);
         Ok(())
     }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        Vec::reserve(self, additional)
+    }
+}
+
+/// Writer that passes encoded data to an incremental hash function instead of storing it,
+/// for example `DigestWriter::new(|data| hasher.update(data))`.
+pub struct DigestWriter<F> {
+    update: F,
+    len: usize,
+}
+
+impl<F> DigestWriter<F>
+where
+    F: FnMut(&[u8]),
+{
+    pub fn new(update: F) -> Self {
+        Self { update, len: 0 }
+    }
+
+    /// Number of bytes passed to the hash function so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<F> Writer for DigestWriter<F>
+where
+    F: FnMut(&[u8]),
+{
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        (self.update)(bytes);
+        self.len += bytes.len();
+        Ok(())
+    }
+}
+
+/// Adapter writing encoded data directly to a [`std::io::Write`].
+///
+/// Encoding performs many small writes, so `inner` should usually be buffered.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct IoWriter<W>(W);
+
+#[cfg(feature = "std")]
+impl<W> IoWriter<W>
+where
+    W: std::io::Write,
+{
+    pub fn new(inner: W) -> Self {
+        Self(inner)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.0
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W> Writer for IoWriter<W>
+where
+    W: std::io::Write,
+{
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.0.write_all(bytes).map_err(Error::Io)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use assert_matches::assert_matches;
+
+    use sha2::{Digest, Sha256};
+
+    use crate::{DigestWriter, Error, MlsEncode, MlsSize};
+
+    fn test_value() -> (u16, Vec<Option<Vec<u8>>>) {
+        (42, vec![Some(vec![1, 2, 3]), None])
+    }
+
+    #[test]
+    fn slice_matches_vec() {
+        let value = test_value();
+        let mut buffer = vec![0u8; value.mls_encoded_len()];
+        let mut remaining = &mut buffer[..];
+
+        value.mls_encode(&mut remaining).unwrap();
+
+        assert!(remaining.is_empty());
+        assert_eq!(buffer, value.mls_encode_to_vec().unwrap());
+    }
+
+    #[test]
+    fn slice_fails_when_full() {
+        let value = test_value();
+        let mut buffer = vec![0u8; value.mls_encoded_len() - 1];
+
+        let res = value.mls_encode(&mut &mut buffer[..]);
+        assert_matches!(res, Err(Error::UnexpectedEOF));
+    }
+
+    #[test]
+    fn digest_writer_matches_hash_of_vec() {
+        let value = test_value();
+        let mut hasher = Sha256::new();
+        let mut calls = 0;
+
+        let mut writer = DigestWriter::new(|data: &[u8]| {
+            hasher.update(data);
+            calls += 1;
+        });
+
+        value.mls_encode(&mut writer).unwrap();
+        assert_eq!(writer.len(), value.mls_encoded_len());
+
+        assert!(calls > 1);
+
+        assert_eq!(
+            hasher.finalize(),
+            Sha256::digest(value.mls_encode_to_vec().unwrap())
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io_writer_matches_vec() {
+        let value = test_value();
+        let mut writer = crate::IoWriter::new(std::io::Cursor::new(Vec::new()));
+        value.mls_encode(&mut writer).unwrap();
+
+        assert_eq!(
+            writer.into_inner().into_inner(),
+            value.mls_encode_to_vec().unwrap()
+        );
+    }
 }
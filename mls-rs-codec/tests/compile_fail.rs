@@ -0,0 +1,10 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+#![cfg(not(target_arch = "wasm32"))]
+
+#[test]
+fn derive_misuse_fails_to_compile() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -2,7 +2,9 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+use std::borrow::Cow;
+
+use mls_rs_codec::{DecodeLimits, MlsDecode, MlsDecodeBorrowed, MlsEncode, MlsSize};
 
 #[derive(Debug, Clone, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
 struct TestTupleStruct(u64);
@@ -32,6 +34,15 @@ struct BorrowedTestType<'a> {
     field_c: &'a [u16],
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, MlsSize, MlsEncode, MlsDecodeBorrowed)]
+struct BorrowedDecodeType<'a> {
+    field_a: u8,
+    field_b: &'a [u8],
+    #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    field_c: Cow<'a, [u8]>,
+    field_d: TestFieldStruct,
+}
+
 #[repr(u16)]
 #[derive(Debug, Clone, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
 enum TestEnum {
@@ -69,6 +80,25 @@ fn round_trip_struct_encode() {
     assert_eq!(restored, item);
 }
 
+#[test]
+fn round_trip_borrowed_decode() {
+    let item = BorrowedDecodeType {
+        field_a: 42,
+        field_b: &[1, 3, 5],
+        field_c: Cow::Owned(vec![0, 2, 4]),
+        field_d: TestFieldStruct {
+            item1: None,
+            item2: 84,
+        },
+    };
+
+    let data = item.mls_encode_to_vec().unwrap();
+    let restored = BorrowedDecodeType::mls_decode_borrowed(&mut &*data).unwrap();
+
+    assert_eq!(restored, item);
+    assert!(matches!(restored.field_c, Cow::Borrowed(_)));
+}
+
 #[test]
 fn round_trip_generic_encode() {
     let item = TestGeneric(42u16);
@@ -78,6 +108,33 @@ fn round_trip_generic_encode() {
     assert_eq!(restored, item);
 }
 
+#[test]
+fn derived_types_count_towards_decode_depth() {
+    let item = TestGeneric(TestGeneric(42u16));
+    let data = item.mls_encode_to_vec().unwrap();
+    let limits = DecodeLimits::default().with_max_depth(1);
+
+    let res = TestGeneric::<TestGeneric<u16>>::mls_decode_with_limits(&mut &*data, limits);
+    assert!(matches!(res, Err(mls_rs_codec::Error::MaxDepthExceeded)));
+
+    let limits = limits.with_max_depth(2);
+    let restored = TestGeneric::mls_decode_with_limits(&mut &*data, limits).unwrap();
+    assert_eq!(restored, item);
+}
+
+#[derive(Debug, PartialEq, MlsSize, MlsEncode, MlsDecode)]
+struct Recursive(Option<Box<Recursive>>);
+
+#[test]
+fn default_limits_bound_recursion() {
+    let item = (0..=DecodeLimits::DEFAULT.max_depth)
+        .fold(Recursive(None), |item, _| Recursive(Some(Box::new(item))));
+
+    let data = item.mls_encode_to_vec().unwrap();
+    let res = Recursive::mls_decode_with_limits(&mut &*data, DecodeLimits::default());
+    assert!(matches!(res, Err(mls_rs_codec::Error::MaxDepthExceeded)));
+}
+
 #[test]
 fn round_trip_enum_encode_simple() {
     let item = TestEnum::Case1;
@@ -146,6 +203,203 @@ fn round_trip_custom_module_enum() {
     assert_eq!(item, decoded)
 }
 
+#[test]
+fn custom_modules_with_context_are_limited() {
+    #[derive(Debug, PartialEq, Eq, Clone, MlsSize, MlsEncode, MlsDecode)]
+    struct TestContextStruct {
+        #[mls_codec(with = "self::test_with_context", context)]
+        values: Vec<u16>,
+    }
+
+    let item = TestContextStruct {
+        values: vec![1, 2, 3],
+    };
+
+    let serialized = item.mls_encode_to_vec().unwrap();
+
+    let limits = DecodeLimits::default().with_max_vec_len(5);
+    let res = TestContextStruct::mls_decode_with_limits(&mut &*serialized, limits);
+    assert!(matches!(res, Err(mls_rs_codec::Error::VectorTooLong(6))));
+
+    let limits = limits.with_max_vec_len(6);
+    let decoded = TestContextStruct::mls_decode_with_limits(&mut &*serialized, limits).unwrap();
+    assert_eq!(item, decoded);
+}
+
+#[test]
+fn manual_mls_decode_is_used_with_context() {
+    #[derive(Debug, PartialEq, Eq)]
+    struct Manual(u8);
+
+    impl MlsDecode for Manual {
+        fn mls_decode(reader: &mut &[u8]) -> Result<Self, mls_rs_codec::Error> {
+            u8::mls_decode(reader).map(Self)
+        }
+    }
+
+    let decoded =
+        Vec::<Manual>::mls_decode_with_limits(&mut &[2, 1, 2][..], DecodeLimits::default());
+    assert_eq!(decoded.unwrap(), [Manual(1), Manual(2)]);
+
+    let limits = DecodeLimits::default().with_max_total_bytes(2);
+    let res = Vec::<Manual>::mls_decode_with_limits(&mut &[2, 1, 2][..], limits);
+    assert!(matches!(
+        res,
+        Err(mls_rs_codec::Error::TotalSizeExceeded(3))
+    ));
+}
+
+#[test]
+fn skipped_fields_are_not_encoded() {
+    #[derive(Debug, PartialEq, Eq, Clone, MlsSize, MlsEncode, MlsDecode)]
+    struct TestSkipStruct {
+        value: u8,
+        #[mls_codec(skip)]
+        cached: Option<u8>,
+        #[mls_codec(skip, default = "7")]
+        version: u16,
+    }
+
+    let item = TestSkipStruct {
+        value: 33,
+        cached: Some(1),
+        version: 1,
+    };
+
+    let serialized = item.mls_encode_to_vec().unwrap();
+    assert_eq!(serialized, [33]);
+    assert_eq!(item.mls_encoded_len(), 1);
+
+    let decoded = TestSkipStruct::mls_decode(&mut &*serialized).unwrap();
+
+    assert_eq!(
+        decoded,
+        TestSkipStruct {
+            value: 33,
+            cached: None,
+            version: 7
+        }
+    );
+}
+
+#[test]
+fn optional_fields_use_presence_encoding() {
+    #[derive(Debug, PartialEq, Eq, Clone, MlsSize, MlsEncode, MlsDecode)]
+    struct TestOptionalStruct {
+        #[mls_codec(optional, with = "mls_rs_codec::byte_vec")]
+        some: Option<Vec<u8>>,
+        #[mls_codec(optional, with = "mls_rs_codec::byte_vec")]
+        none: Option<Vec<u8>>,
+    }
+
+    let item = TestOptionalStruct {
+        some: Some(vec![1, 2]),
+        none: None,
+    };
+
+    let serialized = item.mls_encode_to_vec().unwrap();
+    assert_eq!(serialized, [1, 2, 1, 2, 0]);
+    assert_eq!(item.mls_encoded_len(), 5);
+
+    let decoded = TestOptionalStruct::mls_decode(&mut &*serialized).unwrap();
+    assert_eq!(item, decoded);
+
+    let res = TestOptionalStruct::mls_decode(&mut &[2u8][..]);
+    assert!(matches!(res, Err(mls_rs_codec::Error::OptionOutOfRange(2))));
+}
+
+#[test]
+fn fixed_arrays_are_encoded_without_length() {
+    #[derive(Debug, PartialEq, Eq, Clone, MlsSize, MlsEncode, MlsDecode)]
+    struct TestFixedStruct {
+        #[mls_codec(fixed)]
+        values: [u16; 2],
+        #[mls_codec(fixed)]
+        items: [TestTupleStruct; 1],
+    }
+
+    let item = TestFixedStruct {
+        values: [1, 2],
+        items: [TestTupleStruct(3)],
+    };
+
+    let serialized = item.mls_encode_to_vec().unwrap();
+    assert_eq!(serialized, [0, 1, 0, 2, 0, 0, 0, 0, 0, 0, 0, 3]);
+
+    let decoded = TestFixedStruct::mls_decode(&mut &*serialized).unwrap();
+    assert_eq!(item, decoded);
+}
+
+#[test]
+fn discriminant_width_is_configurable() {
+    #[derive(Debug, PartialEq, Eq, Clone, MlsSize, MlsEncode, MlsDecode)]
+    #[mls_codec(discriminant = "u8")]
+    #[repr(u16)]
+    enum TestU8Enum {
+        Case1 = 1,
+        Case2(u8) = 2,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, MlsSize, MlsEncode, MlsDecode)]
+    #[mls_codec(discriminant = "varint")]
+    #[repr(u16)]
+    enum TestVarIntEnum {
+        Case1 = 1,
+        Case2(u8) = 300,
+    }
+
+    let cases = [
+        (TestU8Enum::Case1.mls_encode_to_vec().unwrap(), vec![1]),
+        (
+            TestU8Enum::Case2(5).mls_encode_to_vec().unwrap(),
+            vec![2, 5],
+        ),
+        (TestVarIntEnum::Case1.mls_encode_to_vec().unwrap(), vec![1]),
+        (
+            TestVarIntEnum::Case2(5).mls_encode_to_vec().unwrap(),
+            vec![0x41, 0x2c, 5],
+        ),
+    ];
+
+    for (serialized, expected) in cases {
+        assert_eq!(serialized, expected);
+    }
+
+    assert_eq!(TestU8Enum::Case2(5).mls_encoded_len(), 2);
+    assert_eq!(TestVarIntEnum::Case2(5).mls_encoded_len(), 3);
+
+    let decoded = TestVarIntEnum::mls_decode(&mut &[0x41, 0x2c, 5][..]).unwrap();
+    assert_eq!(decoded, TestVarIntEnum::Case2(5));
+
+    let res = TestU8Enum::mls_decode(&mut &[3u8][..]);
+    assert!(matches!(
+        res,
+        Err(mls_rs_codec::Error::UnsupportedEnumDiscriminant)
+    ));
+}
+
+#[test]
+fn unknown_variants_preserve_data() {
+    #[derive(Debug, PartialEq, Eq, Clone, MlsSize, MlsEncode, MlsDecode)]
+    #[mls_codec(discriminant = "u16")]
+    #[repr(u16)]
+    enum TestUnknownEnum {
+        Known(u8) = 1,
+        #[mls_codec(unknown)]
+        Unknown(u16, #[mls_codec(with = "mls_rs_codec::byte_vec")] Vec<u8>),
+    }
+
+    let serialized = [0u8, 42, 2, 7, 8];
+    let decoded = TestUnknownEnum::mls_decode(&mut &serialized[..]).unwrap();
+
+    assert_eq!(decoded, TestUnknownEnum::Unknown(42, vec![7, 8]));
+    assert_eq!(decoded.mls_encode_to_vec().unwrap(), serialized);
+    assert_eq!(decoded.mls_encoded_len(), serialized.len());
+
+    let decoded = TestUnknownEnum::mls_decode(&mut &[0u8, 1, 3][..]).unwrap();
+    assert_eq!(decoded, TestUnknownEnum::Known(3));
+}
+
 mod test_with {
     use mls_rs_codec::MlsDecode;
 
@@ -153,12 +407,36 @@ mod test_with {
         2
     }
 
-    pub fn mls_encode(val: &u8, writer: &mut Vec<u8>) -> Result<(), mls_rs_codec::Error> {
-        writer.extend([*val, 42]);
-        Ok(())
+    pub fn mls_encode<W: mls_rs_codec::Writer + ?Sized>(
+        val: &u8,
+        writer: &mut W,
+    ) -> Result<(), mls_rs_codec::Error> {
+        writer.write(&[*val, 42])
     }
 
     pub fn mls_decode(reader: &mut &[u8]) -> Result<u8, mls_rs_codec::Error> {
         Ok(<[u8; 2]>::mls_decode(reader)?[0])
     }
 }
+
+mod test_with_context {
+    use mls_rs_codec::{DecodeContext, MlsDecode, MlsEncode, MlsSize};
+
+    pub fn mls_encoded_len(val: &[u16]) -> usize {
+        val.mls_encoded_len()
+    }
+
+    pub fn mls_encode<W: mls_rs_codec::Writer + ?Sized>(
+        val: &[u16],
+        writer: &mut W,
+    ) -> Result<(), mls_rs_codec::Error> {
+        val.mls_encode(writer)
+    }
+
+    pub fn mls_decode_with_context(
+        reader: &mut &[u8],
+        context: &mut DecodeContext,
+    ) -> Result<Vec<u16>, mls_rs_codec::Error> {
+        Vec::mls_decode_with_context(reader, context)
+    }
+}
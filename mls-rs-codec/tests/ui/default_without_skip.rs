@@ -0,0 +1,9 @@
+use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+
+#[derive(MlsSize, MlsEncode, MlsDecode)]
+struct TestStruct {
+    #[mls_codec(default = "7")]
+    value: u8,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+
+#[derive(MlsSize, MlsEncode, MlsDecode)]
+#[mls_codec(discriminant = "u8")]
+struct TestStruct {
+    value: u8,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+
+#[derive(MlsSize, MlsEncode, MlsDecode)]
+struct TestStruct {
+    #[mls_codec(fixed, with = "mls_rs_codec::byte_vec")]
+    value: [u8; 2],
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+
+#[derive(MlsSize, MlsEncode, MlsDecode)]
+#[mls_codec(discriminant = "u7")]
+#[repr(u8)]
+enum TestEnum {
+    Case = 1,
+}
+
+fn main() {}
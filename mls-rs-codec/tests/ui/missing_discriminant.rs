@@ -0,0 +1,10 @@
+use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+
+#[derive(MlsSize, MlsEncode, MlsDecode)]
+#[repr(u8)]
+enum TestEnum {
+    Case1 = 1,
+    Case2(u8),
+}
+
+fn main() {}
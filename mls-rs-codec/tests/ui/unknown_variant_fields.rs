@@ -0,0 +1,11 @@
+use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+
+#[derive(MlsSize, MlsEncode, MlsDecode)]
+#[repr(u16)]
+enum TestEnum {
+    Case = 1u16,
+    #[mls_codec(unknown)]
+    Unknown(Vec<u8>),
+}
+
+fn main() {}
@@ -501,3 +501,88 @@ pub trait CipherSuiteProvider: Send + Sync {
         data: &[u8],
     ) -> Result<(), Self::Error>;
 }
+
+/// Pre-shared key input to the PSK and AuthPSK modes of HPKE. The `value`
+/// MUST have at least 32 bytes of entropy.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct HpkePsk<'a> {
+    pub id: &'a [u8],
+    pub value: &'a [u8],
+}
+
+impl<'a> HpkePsk<'a> {
+    pub fn new(id: &'a [u8], value: &'a [u8]) -> Self {
+        Self { id, value }
+    }
+}
+
+/// Optional extension of [`CipherSuiteProvider`] supporting the Auth and AuthPSK
+/// modes of RFC 9180. These modes additionally authenticate the sender's
+/// possession of a KEM secret key, and are not used by MLS itself.
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+pub trait HpkeAuthCipherSuiteProvider: CipherSuiteProvider {
+    /// Encrypt `pt` to `remote_key` as the holder of the sender key pair
+    /// (`sender_secret`, `sender_public`). If `psk` is provided, the AuthPSK
+    /// mode is used, otherwise the Auth mode.
+    ///
+    /// This function corresponds to the one-shot API in Auth and AuthPSK modes
+    /// in RFC 9180.
+    #[allow(clippy::too_many_arguments)]
+    async fn hpke_seal_auth(
+        &self,
+        remote_key: &HpkePublicKey,
+        sender_secret: &HpkeSecretKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+        aad: Option<&[u8]>,
+        pt: &[u8],
+    ) -> Result<HpkeCiphertext, Self::Error>;
+
+    /// Decrypt the `ciphertext` generated by [hpke_seal_auth](HpkeAuthCipherSuiteProvider::hpke_seal_auth).
+    /// This function should return an error if the ciphertext was not generated
+    /// by the holder of the secret key corresponding to `sender_public`.
+    #[allow(clippy::too_many_arguments)]
+    async fn hpke_open_auth(
+        &self,
+        ciphertext: &HpkeCiphertext,
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+        aad: Option<&[u8]>,
+    ) -> Result<Vec<u8>, Self::Error>;
+
+    /// Authenticated variant of [hpke_setup_s](CipherSuiteProvider::hpke_setup_s).
+    ///
+    /// This function corresponds to the SetupAuthS and SetupAuthPSKS functions
+    /// from RFC 9180.
+    async fn hpke_setup_s_auth(
+        &self,
+        remote_key: &HpkePublicKey,
+        sender_secret: &HpkeSecretKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+    ) -> Result<(Vec<u8>, Self::HpkeContextS), Self::Error>;
+
+    /// Authenticated variant of [hpke_setup_r](CipherSuiteProvider::hpke_setup_r).
+    ///
+    /// This function corresponds to the SetupAuthR and SetupAuthPSKR functions
+    /// from RFC 9180.
+    async fn hpke_setup_r_auth(
+        &self,
+        kem_output: &[u8],
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+    ) -> Result<Self::HpkeContextR, Self::Error>;
+}
@@ -27,10 +27,20 @@ use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
 #[derive(Debug, Copy, Clone, Eq, PartialEq, MlsSize, MlsEncode, MlsDecode, PartialOrd, Ord)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::ffi_type)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(transparent)]
 pub struct CipherSuite(u16);
 
+#[cfg(feature = "serde")]
+crate::named_serde::impl_named_serde!(CipherSuite, {
+    1 => "MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519",
+    2 => "MLS_128_DHKEMP256_AES128GCM_SHA256_P256",
+    3 => "MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519",
+    4 => "MLS_256_DHKEMX448_AES256GCM_SHA512_Ed448",
+    5 => "MLS_256_DHKEMP521_AES256GCM_SHA512_P521",
+    6 => "MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_Ed448",
+    7 => "MLS_256_DHKEMP384_AES256GCM_SHA384_P384",
+});
+
 impl From<u16> for CipherSuite {
     fn from(value: u16) -> Self {
         CipherSuite(value)
@@ -8,7 +8,8 @@ use itertools::Itertools;
 use crate::crypto::HpkeContextR;
 
 use super::{
-    CipherSuiteProvider, CryptoProvider, HpkeCiphertext, HpkeContextS, HpkePublicKey, HpkeSecretKey,
+    CipherSuiteProvider, CryptoProvider, HpkeAuthCipherSuiteProvider, HpkeCiphertext, HpkeContextS,
+    HpkePsk, HpkePublicKey, HpkeSecretKey,
 };
 
 const PATH: &str = concat!(
@@ -23,7 +24,8 @@ const SERIALIZED_TEST_SUITES: &[u8] = include_bytes!(concat!(
 ));
 
 pub use hpke_rfc_conformance::{
-    verify_hpke_context_tests, verify_hpke_encap_tests, EncapOutput, TestHpke,
+    verify_hpke_context_tests, verify_hpke_encap_tests, verify_hpke_setup_tests, EncapOutput,
+    TestHpke,
 };
 
 pub const DATA_SIZES: [usize; 5] = [0, 1, 16, 123, 2000];
@@ -512,10 +514,95 @@ async fn verify_hash_tests<C: CipherSuiteProvider>(cs: &C, test_cases: Vec<HashT
     }
 }
 
+/// Check that `cs` implements the Auth and AuthPSK modes of HPKE consistently,
+/// and that a receiver rejects ciphertexts from an unexpected sender or with
+/// an incorrect PSK.
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+pub async fn verify_hpke_auth_tests<C: HpkeAuthCipherSuiteProvider>(cs: &C) {
+    let (secret, public) = cs.kem_generate().await.unwrap();
+    let (sender_secret, sender_public) = cs.kem_generate().await.unwrap();
+    let (_, other_public) = cs.kem_generate().await.unwrap();
+
+    let psk_value = cs.random_bytes_vec(32).unwrap();
+    let wrong_psk_value = cs.random_bytes_vec(32).unwrap();
+
+    let info = b"info".as_slice();
+    let aad = Some(b"aad".as_slice());
+    let plaintext = b"plaintext".as_slice();
+
+    for psk in [None, Some(HpkePsk::new(b"psk id", &psk_value))] {
+        let ct = cs
+            .hpke_seal_auth(
+                &public,
+                &sender_secret,
+                &sender_public,
+                info,
+                psk,
+                aad,
+                plaintext,
+            )
+            .await
+            .unwrap();
+
+        let opened = cs
+            .hpke_open_auth(&ct, &secret, &public, &sender_public, info, psk, aad)
+            .await
+            .unwrap();
+
+        assert_eq!(opened, plaintext);
+
+        let res = cs
+            .hpke_open_auth(&ct, &secret, &public, &other_public, info, psk, aad)
+            .await;
+
+        assert!(res.is_err());
+
+        // The auth modes are not interchangeable with the base mode
+        let res = cs.hpke_open(&ct, &secret, &public, info, aad).await;
+        assert!(res.is_err());
+
+        let (enc, mut context_s) = cs
+            .hpke_setup_s_auth(&public, &sender_secret, &sender_public, info, psk)
+            .await
+            .unwrap();
+
+        let mut context_r = cs
+            .hpke_setup_r_auth(&enc, &secret, &public, &sender_public, info, psk)
+            .await
+            .unwrap();
+
+        let ct = context_s.seal(aad, plaintext).await.unwrap();
+        let opened = context_r.open(aad, &ct).await.unwrap();
+        assert_eq!(opened, plaintext);
+    }
+
+    let psk = Some(HpkePsk::new(b"psk id", &psk_value));
+    let wrong_psk = Some(HpkePsk::new(b"psk id", &wrong_psk_value));
+
+    let ct = cs
+        .hpke_seal_auth(
+            &public,
+            &sender_secret,
+            &sender_public,
+            info,
+            psk,
+            aad,
+            plaintext,
+        )
+        .await
+        .unwrap();
+
+    let res = cs
+        .hpke_open_auth(&ct, &secret, &public, &sender_public, info, wrong_psk, aad)
+        .await;
+
+    assert!(res.is_err());
+}
+
 mod hpke_rfc_conformance {
     use alloc::vec::Vec;
 
-    use crate::crypto::{CipherSuite, HpkeContextR, HpkeContextS, HpkeModeId};
+    use crate::crypto::{CipherSuite, HpkeContextR, HpkeContextS, HpkeModeId, HpkePsk};
 
     #[derive(serde::Deserialize, Debug, Clone)]
     pub struct TestCaseAlgo {
@@ -527,10 +614,6 @@ mod hpke_rfc_conformance {
 
     impl TestCaseAlgo {
         fn cipher_suite(&self) -> Option<CipherSuite> {
-            if ![HpkeModeId::Base as u8, HpkeModeId::Psk as u8].contains(&self.mode) {
-                return None;
-            }
-
             match (self.kem_id, self.kdf_id, self.aead_id) {
                 (0x0010, 0x0001, 0x0001) => Some(CipherSuite::P256_AES128),
                 (0x0011, 0x0002, 0x0002) => Some(CipherSuite::P384_AES256),
@@ -542,6 +625,10 @@ mod hpke_rfc_conformance {
                 _ => None,
             }
         }
+
+        fn is_auth(&self) -> bool {
+            [HpkeModeId::Auth as u8, HpkeModeId::AuthPsk as u8].contains(&self.mode)
+        }
     }
 
     #[derive(serde::Deserialize, Debug)]
@@ -554,6 +641,16 @@ mod hpke_rfc_conformance {
         sk_rm: Vec<u8>,
         #[serde(with = "hex::serde", rename(deserialize = "ikmE"))]
         ikm_e: Vec<u8>,
+        #[serde(default, with = "hex::serde", rename(deserialize = "pkSm"))]
+        pk_sm: Vec<u8>,
+        #[serde(default, with = "hex::serde", rename(deserialize = "skSm"))]
+        sk_sm: Vec<u8>,
+        #[serde(default, with = "hex::serde")]
+        psk: Vec<u8>,
+        #[serde(default, with = "hex::serde")]
+        psk_id: Vec<u8>,
+        #[serde(with = "hex::serde")]
+        info: Vec<u8>,
         #[serde(with = "hex::serde")]
         shared_secret: Vec<u8>,
         #[serde(with = "hex::serde")]
@@ -568,6 +665,12 @@ mod hpke_rfc_conformance {
         exports: Vec<ExportTestCase>,
     }
 
+    impl TestCase {
+        fn psk(&self) -> Option<HpkePsk<'_>> {
+            (!self.psk.is_empty()).then(|| HpkePsk::new(&self.psk_id, &self.psk))
+        }
+    }
+
     #[derive(serde::Deserialize, Debug)]
     struct EncryptionTestCase {
         #[serde(with = "hex::serde", rename = "pt")]
@@ -629,6 +732,34 @@ mod hpke_rfc_conformance {
 
         fn encap(&mut self, ikm_e: Vec<u8>, pk_rm: Vec<u8>) -> EncapOutput;
         fn decap(&mut self, enc: Vec<u8>, sk_rm: Vec<u8>, pk_rm: Vec<u8>) -> Vec<u8>;
+
+        fn auth_encap(
+            &mut self,
+            ikm_e: Vec<u8>,
+            pk_rm: Vec<u8>,
+            sk_sm: Vec<u8>,
+            pk_sm: Vec<u8>,
+        ) -> EncapOutput;
+
+        fn auth_decap(
+            &mut self,
+            enc: Vec<u8>,
+            sk_rm: Vec<u8>,
+            pk_rm: Vec<u8>,
+            pk_sm: Vec<u8>,
+        ) -> Vec<u8>;
+
+        /// Run the full receiver setup, including the key schedule, in the mode
+        /// determined by the presence of `pk_sm` and `psk`.
+        fn setup_receiver(
+            &self,
+            enc: Vec<u8>,
+            sk_rm: Vec<u8>,
+            pk_rm: Vec<u8>,
+            pk_sm: Option<Vec<u8>>,
+            info: &[u8],
+            psk: Option<HpkePsk<'_>>,
+        ) -> Self::ContextR;
     }
 
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
@@ -674,14 +805,68 @@ mod hpke_rfc_conformance {
             .into_iter()
             .filter(|tc| matches!(tc.algo.cipher_suite(), Some(c) if c == cipher_suite))
         {
-            let out = hpke.encap(test_case.ikm_e, test_case.pk_rm.clone());
+            let (out, shared_secret) = if test_case.algo.is_auth() {
+                let out = hpke.auth_encap(
+                    test_case.ikm_e,
+                    test_case.pk_rm.clone(),
+                    test_case.sk_sm,
+                    test_case.pk_sm.clone(),
+                );
+
+                let shared_secret = hpke.auth_decap(
+                    test_case.enc.clone(),
+                    test_case.sk_rm,
+                    test_case.pk_rm,
+                    test_case.pk_sm,
+                );
+
+                (out, shared_secret)
+            } else {
+                let out = hpke.encap(test_case.ikm_e, test_case.pk_rm.clone());
+                let shared_secret =
+                    hpke.decap(test_case.enc.clone(), test_case.sk_rm, test_case.pk_rm);
+
+                (out, shared_secret)
+            };
 
             assert_eq!(&out.enc, &test_case.enc);
             assert_eq!(&out.shared_secret, &test_case.shared_secret);
+            assert_eq!(shared_secret, test_case.shared_secret);
+        }
+    }
 
-            let shared_secret = hpke.decap(test_case.enc, test_case.sk_rm, test_case.pk_rm);
+    /// Check the full receiver setup, including the key schedule, in each of
+    /// the Base, PSK, Auth and AuthPSK modes.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn verify_hpke_setup_tests<C: TestHpke>(hpke: &C, cipher_suite: CipherSuite) {
+        for test_case in get_test_cases()
+            .into_iter()
+            .filter(|tc| matches!(tc.algo.cipher_suite(), Some(c) if c == cipher_suite))
+        {
+            let pk_sm = test_case.algo.is_auth().then(|| test_case.pk_sm.clone());
+
+            let mut context_r = hpke.setup_receiver(
+                test_case.enc.clone(),
+                test_case.sk_rm.clone(),
+                test_case.pk_rm.clone(),
+                pk_sm,
+                &test_case.info,
+                test_case.psk(),
+            );
 
-            assert_eq!(shared_secret, test_case.shared_secret);
+            for enc_test_case in &test_case.encryptions {
+                let pt = context_r
+                    .open(Some(&enc_test_case.aad), &enc_test_case.ciphertext)
+                    .await
+                    .unwrap();
+
+                assert_eq!(pt, enc_test_case.plaintext);
+            }
+
+            for test in &test_case.exports {
+                let exported = context_r.export(&test.exporter_context, test.length).await;
+                assert_eq!(exported.unwrap(), test.exported_value);
+            }
         }
     }
 }
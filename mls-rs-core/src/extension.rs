@@ -22,10 +22,18 @@ pub use list::*;
 )]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::ffi_type)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(transparent)]
 pub struct ExtensionType(u16);
 
+#[cfg(feature = "serde")]
+crate::named_serde::impl_named_serde!(ExtensionType, {
+    1 => "application_id",
+    2 => "ratchet_tree",
+    3 => "required_capabilities",
+    4 => "external_pub",
+    5 => "external_senders",
+});
+
 #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen)]
 impl ExtensionType {
     pub const APPLICATION_ID: ExtensionType = ExtensionType(1);
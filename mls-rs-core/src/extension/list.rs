@@ -40,12 +40,15 @@ impl PartialEq for ExtensionList {
 }
 
 impl MlsDecode for ExtensionList {
-    fn mls_decode(reader: &mut &[u8]) -> Result<Self, mls_rs_codec::Error> {
-        mls_rs_codec::iter::mls_decode_collection(reader, |data| {
+    fn mls_decode_with_context(
+        reader: &mut &[u8],
+        context: &mut mls_rs_codec::DecodeContext,
+    ) -> Result<Self, mls_rs_codec::Error> {
+        mls_rs_codec::iter::mls_decode_collection(reader, context, |data, context| {
             let mut list = ExtensionList::new();
 
             while !data.is_empty() {
-                let ext = Extension::mls_decode(data)?;
+                let ext = Extension::mls_decode_with_context(data, context)?;
                 let ext_type = ext.extension_type;
 
                 if list.0.iter().any(|e| e.extension_type == ext_type) {
@@ -4,8 +4,10 @@
 
 mod group_state;
 mod proposal_type;
+mod ratchet_tree_provider;
 mod roster;
 
 pub use group_state::*;
 pub use proposal_type::*;
+pub use ratchet_tree_provider::*;
 pub use roster::*;
@@ -6,6 +6,7 @@ use crate::error::IntoAnyError;
 #[cfg(mls_build_async)]
 use alloc::boxed::Box;
 use alloc::vec::Vec;
+use core::fmt::{self, Debug};
 use mls_rs_codec::{MlsDecode, MlsEncode};
 
 /// Generic representation of a group's state.
@@ -20,6 +21,83 @@ pub trait EpochRecord {
     fn id(&self) -> u64;
 }
 
+/// Change to a single node of a tree that is stored node by node.
+#[derive(Clone, PartialEq, Eq)]
+pub struct NodeUpdate {
+    /// Index of the node within its tree.
+    pub index: u32,
+    /// Encoded node, or `None` if the node was removed.
+    pub data: Option<Vec<u8>>,
+}
+
+impl Debug for NodeUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NodeUpdate")
+            .field("index", &self.index)
+            .field(
+                "data",
+                &self.data.as_deref().map(crate::debug::pretty_bytes),
+            )
+            .finish()
+    }
+}
+
+/// Changes made to a group state since it was last written with
+/// [`GroupStateStorage::write_delta`].
+///
+/// The ratchet tree and the secret tree of the group are split off the
+/// state and persisted node by node, so that a write only touches the nodes
+/// that changed.
+#[derive(Clone, PartialEq, Eq)]
+pub struct GroupStateDelta {
+    /// A unique group identifier.
+    pub group_id: Vec<u8>,
+    /// Encoded group state without the nodes of its trees. It replaces the
+    /// previously stored value.
+    pub state: Vec<u8>,
+    /// If `true`, all nodes previously stored for the group must be removed
+    /// before applying `tree_updates` and `secret_tree_updates`.
+    pub reset_nodes: bool,
+    /// Updated nodes of the ratchet tree.
+    pub tree_updates: Vec<NodeUpdate>,
+    /// Updated nodes of the secret tree.
+    pub secret_tree_updates: Vec<NodeUpdate>,
+}
+
+impl Debug for GroupStateDelta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GroupStateDelta")
+            .field("group_id", &crate::debug::pretty_group_id(&self.group_id))
+            .field("state", &crate::debug::pretty_bytes(&self.state))
+            .field("reset_nodes", &self.reset_nodes)
+            .field("tree_updates", &self.tree_updates)
+            .field("secret_tree_updates", &self.secret_tree_updates)
+            .finish()
+    }
+}
+
+/// Group state persisted with [`GroupStateStorage::write_delta`], as
+/// returned by [`GroupStateStorage::delta_state`].
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct StoredGroupState {
+    /// Most recent value of [`GroupStateDelta::state`].
+    pub state: Vec<u8>,
+    /// Stored nodes of the ratchet tree by index, in any order.
+    pub tree_nodes: Vec<(u32, Vec<u8>)>,
+    /// Stored nodes of the secret tree by index, in any order.
+    pub secret_tree_nodes: Vec<(u32, Vec<u8>)>,
+}
+
+impl Debug for StoredGroupState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StoredGroupState")
+            .field("state", &crate::debug::pretty_bytes(&self.state))
+            .field("tree_nodes", &self.tree_nodes.len())
+            .field("secret_tree_nodes", &self.secret_tree_nodes.len())
+            .finish()
+    }
+}
+
 /// Storage that can persist and reload a group state.
 ///
 /// A group state is recorded as a combination of the current state
@@ -34,6 +112,24 @@ pub trait EpochRecord {
 /// group. It is up to the implementer of this trait to provide a mechanism
 /// to delete records that can be used by an application.
 ///
+/// # Incremental writes
+///
+/// Writing the full state of large groups on every change is expensive, as
+/// it contains the whole ratchet tree. Storage returning `true` from
+/// [`supports_deltas`](GroupStateStorage::supports_deltas) receives
+/// [`GroupStateDelta`] values through
+/// [`write_delta`](GroupStateStorage::write_delta) instead, and returns the
+/// stored parts through [`delta_state`](GroupStateStorage::delta_state).
+/// The protocol implementation reassembles the group state from these parts
+/// when the group is loaded. Both methods have default implementations, so
+/// storage that only writes full group states does not need to implement
+/// them.
+///
+/// Groups are loaded with [`delta_state`](GroupStateStorage::delta_state)
+/// first, and only with [`state`](GroupStateStorage::state) if it returns
+/// `None`. A call to [`write`](GroupStateStorage::write) must therefore
+/// clear any delta state stored for the group, otherwise the group would be
+/// loaded from outdated parts.
 
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 #[cfg_attr(mls_build_async, maybe_async::must_be_async)]
@@ -69,6 +165,9 @@ pub trait GroupStateStorage: Send + Sync {
     /// of this trait. Calls to [`write`](GroupStateStorage::write) should
     /// optimally be a single atomic transaction in order to avoid partial writes
     /// that may corrupt the group state.
+    ///
+    /// Storage supporting deltas must also remove any delta state stored for
+    /// the group, see [Incremental writes](GroupStateStorage#incremental-writes).
     async fn write<ST, ET>(
         &mut self,
         state: ST,
@@ -82,4 +181,58 @@ pub trait GroupStateStorage: Send + Sync {
     /// The [`EpochRecord::id`] value that is associated with a stored
     /// prior epoch for a particular group.
     async fn max_epoch_id(&self, group_id: &[u8]) -> Result<Option<u64>, Self::Error>;
+
+    /// Whether group states are written with
+    /// [`write_delta`](GroupStateStorage::write_delta) instead of
+    /// [`write`](GroupStateStorage::write).
+    ///
+    /// Defaults to `false`. Storage returning `true` must implement both
+    /// [`write_delta`](GroupStateStorage::write_delta) and
+    /// [`delta_state`](GroupStateStorage::delta_state).
+    fn supports_deltas(&self) -> bool {
+        false
+    }
+
+    /// Write the changes made to a group state since the previous call,
+    /// along with pending epoch updates as in
+    /// [`write`](GroupStateStorage::write).
+    ///
+    /// Nodes of [`GroupStateDelta`] with `Some` data are inserted or replaced
+    /// and nodes with `None` data are removed. As for
+    /// [`write`](GroupStateStorage::write), all changes should be made in a
+    /// single atomic transaction.
+    ///
+    /// This is only called if [`supports_deltas`](GroupStateStorage::supports_deltas)
+    /// returns `true`.
+    ///
+    /// # Panics
+    ///
+    /// The default implementation panics, as storage that does not support
+    /// deltas is never asked to write them.
+    async fn write_delta<ET>(
+        &mut self,
+        _delta: GroupStateDelta,
+        _epoch_inserts: Vec<ET>,
+        _epoch_updates: Vec<ET>,
+    ) -> Result<(), Self::Error>
+    where
+        ET: EpochRecord + MlsEncode + MlsDecode + Send + Sync,
+    {
+        unimplemented!("group state storage does not support deltas")
+    }
+
+    /// Fetch a group state written with
+    /// [`write_delta`](GroupStateStorage::write_delta).
+    ///
+    /// Returns `None` if the group was not written with
+    /// [`write_delta`](GroupStateStorage::write_delta), in which case it is
+    /// fetched with [`state`](GroupStateStorage::state) instead. This is
+    /// called regardless of [`supports_deltas`](GroupStateStorage::supports_deltas),
+    /// so that groups written with deltas can still be loaded after
+    /// incremental writes are turned off.
+    ///
+    /// The default implementation always returns `None`.
+    async fn delta_state(&self, _group_id: &[u8]) -> Result<Option<StoredGroupState>, Self::Error> {
+        Ok(None)
+    }
 }
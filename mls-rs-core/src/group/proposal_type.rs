@@ -14,10 +14,20 @@ use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
 )]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::ffi_type)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(transparent)]
 pub struct ProposalType(u16);
 
+#[cfg(feature = "serde")]
+crate::named_serde::impl_named_serde!(ProposalType, {
+    1 => "add",
+    2 => "update",
+    3 => "remove",
+    4 => "psk",
+    5 => "reinit",
+    6 => "external_init",
+    7 => "group_context_extensions",
+});
+
 impl ProposalType {
     pub const fn new(value: u16) -> ProposalType {
         ProposalType(value)
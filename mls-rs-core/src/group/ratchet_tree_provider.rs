@@ -0,0 +1,54 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use core::convert::Infallible;
+
+use crate::error::IntoAnyError;
+#[cfg(mls_build_async)]
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+/// Source of ratchet trees that are distributed separately from welcome and
+/// group info messages, for instance by the delivery service.
+///
+/// The provider is called when joining a group, creating an external commit
+/// or observing a group if the group info neither contains a ratchet tree
+/// extension nor was the tree provided by the application. The returned tree
+/// is validated against the tree hash of the group context before being used,
+/// so it does not need to come from a trusted source.
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+pub trait RatchetTreeProvider: Send + Sync {
+    type Error: IntoAnyError;
+
+    /// Fetch the MLS encoded ratchet tree of the group with id `group_id` at
+    /// `epoch`, whose tree hash is `tree_hash`.
+    ///
+    /// Returns `None` if the tree is unknown to this provider.
+    async fn ratchet_tree(
+        &self,
+        group_id: &[u8],
+        epoch: u64,
+        tree_hash: &[u8],
+    ) -> Result<Option<Vec<u8>>, Self::Error>;
+}
+
+/// [`RatchetTreeProvider`] that never finds a tree.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoRatchetTreeProvider;
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+impl RatchetTreeProvider for NoRatchetTreeProvider {
+    type Error = Infallible;
+
+    async fn ratchet_tree(
+        &self,
+        _group_id: &[u8],
+        _epoch: u64,
+        _tree_hash: &[u8],
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(None)
+    }
+}
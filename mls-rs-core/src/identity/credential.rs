@@ -22,10 +22,15 @@ use super::CertificateChain;
 )]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::ffi_type)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(transparent)]
 pub struct CredentialType(u16);
 
+#[cfg(feature = "serde")]
+crate::named_serde::impl_named_serde!(CredentialType, {
+    1 => "basic",
+    2 => "x509",
+});
+
 #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen)]
 impl CredentialType {
     /// Basic identity.
@@ -200,7 +205,10 @@ impl MlsSize for Credential {
 }
 
 impl MlsEncode for Credential {
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), mls_rs_codec::Error> {
+    fn mls_encode<W: mls_rs_codec::Writer + ?Sized>(
+        &self,
+        writer: &mut W,
+    ) -> Result<(), mls_rs_codec::Error> {
         self.credential_type().mls_encode(writer)?;
 
         match self {
@@ -213,16 +221,23 @@ impl MlsEncode for Credential {
 }
 
 impl MlsDecode for Credential {
-    fn mls_decode(reader: &mut &[u8]) -> Result<Self, mls_rs_codec::Error> {
-        let credential_type = CredentialType::mls_decode(reader)?;
+    fn mls_decode_with_context(
+        reader: &mut &[u8],
+        context: &mut mls_rs_codec::DecodeContext,
+    ) -> Result<Self, mls_rs_codec::Error> {
+        let credential_type = CredentialType::mls_decode_with_context(reader, context)?;
 
         Ok(match credential_type {
-            CredentialType::BASIC => Credential::Basic(BasicCredential::mls_decode(reader)?),
+            CredentialType::BASIC => {
+                Credential::Basic(BasicCredential::mls_decode_with_context(reader, context)?)
+            }
             #[cfg(feature = "x509")]
-            CredentialType::X509 => Credential::X509(CertificateChain::mls_decode(reader)?),
+            CredentialType::X509 => {
+                Credential::X509(CertificateChain::mls_decode_with_context(reader, context)?)
+            }
             custom => Credential::Custom(CustomCredential {
                 credential_type: custom,
-                data: mls_rs_codec::byte_vec::mls_decode(reader)?,
+                data: mls_rs_codec::byte_vec::mls_decode_with_context(reader, context)?,
             }),
         })
     }
@@ -29,6 +29,26 @@ pub trait IdentityProvider: Send + Sync {
         extensions: Option<&ExtensionList>,
     ) -> Result<(), Self::Error>;
 
+    /// Determine if `signing_identity` is valid for a group member, given the
+    /// extensions of the leaf node that carries it.
+    ///
+    /// This allows credentials to be validated with data that is attached to
+    /// the leaf node, such as stapled revocation information. The default
+    /// implementation ignores `leaf_extensions` and calls
+    /// [`validate_member`](IdentityProvider::validate_member).
+    async fn validate_member_with_leaf_extensions(
+        &self,
+        signing_identity: &SigningIdentity,
+        timestamp: Option<MlsTime>,
+        extensions: Option<&ExtensionList>,
+        leaf_extensions: &ExtensionList,
+    ) -> Result<(), Self::Error> {
+        let _ = leaf_extensions;
+
+        self.validate_member(signing_identity, timestamp, extensions)
+            .await
+    }
+
     /// Determine if `signing_identity` is valid for an external sender in
     /// the ExternalSendersExtension stored in the group context.
     ///
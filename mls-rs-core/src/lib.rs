@@ -9,6 +9,9 @@ extern crate alloc;
 #[cfg(all(test, target_arch = "wasm32"))]
 wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
 
+#[cfg(feature = "serde")]
+mod named_serde;
+
 pub mod crypto;
 pub mod debug;
 pub mod error;
@@ -0,0 +1,137 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+//! Serde support for `u16` code points such as [`CipherSuite`](crate::crypto::CipherSuite).
+//!
+//! Human readable formats use the name registered by the RFC for known values and the number
+//! otherwise. Both forms are accepted when deserializing, so data written before names were
+//! introduced can still be read. Other formats are unchanged and use the number.
+
+use core::fmt;
+
+use serde::{
+    de::{self, Unexpected, Visitor},
+    Deserialize, Deserializer, Serializer,
+};
+
+pub(crate) type Names = &'static [(u16, &'static str)];
+
+pub(crate) fn serialize<S: Serializer>(
+    type_name: &'static str,
+    value: u16,
+    names: Names,
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    match names.iter().find(|(v, _)| *v == value) {
+        Some((_, name)) if s.is_human_readable() => s.serialize_str(name),
+        _ => s.serialize_newtype_struct(type_name, &value),
+    }
+}
+
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+    type_name: &'static str,
+    names: Names,
+    d: D,
+) -> Result<u16, D::Error> {
+    let visitor = CodePointVisitor { type_name, names };
+
+    if d.is_human_readable() {
+        d.deserialize_any(visitor)
+    } else {
+        d.deserialize_newtype_struct(type_name, visitor)
+    }
+}
+
+struct CodePointVisitor {
+    type_name: &'static str,
+    names: Names,
+}
+
+impl<'de> Visitor<'de> for CodePointVisitor {
+    type Value = u16;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a {} name or number", self.type_name)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<u16, E> {
+        u16::try_from(v).map_err(|_| E::invalid_value(Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<u16, E> {
+        u16::try_from(v).map_err(|_| E::invalid_value(Unexpected::Signed(v), &self))
+    }
+
+    // Map keys are always strings in JSON, so numbers may also arrive as strings.
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<u16, E> {
+        self.names
+            .iter()
+            .find(|(_, name)| *name == v)
+            .map(|(value, _)| *value)
+            .or_else(|| v.parse().ok())
+            .ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, d: D) -> Result<u16, D::Error> {
+        u16::deserialize(d)
+    }
+}
+
+/// Implement `Serialize` and `Deserialize` for a `u16` newtype using [`named_serde`](self).
+macro_rules! impl_named_serde {
+    ($ty:ident, { $($value:literal => $name:literal),* $(,)? }) => {
+        impl serde::Serialize for $ty {
+            fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                const NAMES: $crate::named_serde::Names = &[$(($value, $name)),*];
+                $crate::named_serde::serialize(stringify!($ty), self.0, NAMES, s)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $ty {
+            fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                const NAMES: $crate::named_serde::Names = &[$(($value, $name)),*];
+                $crate::named_serde::deserialize(stringify!($ty), NAMES, d).map(Self)
+            }
+        }
+    };
+}
+
+pub(crate) use impl_named_serde;
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::crypto::CipherSuite;
+    use crate::extension::ExtensionType;
+
+    #[test]
+    fn known_values_use_names() {
+        let json = serde_json::to_string(&CipherSuite::CURVE25519_AES128).unwrap();
+        assert_eq!(json, r#""MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519""#);
+
+        let decoded: CipherSuite = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, CipherSuite::CURVE25519_AES128);
+    }
+
+    #[test]
+    fn unknown_values_use_numbers() {
+        let json = serde_json::to_string(&ExtensionType::new(0xff00)).unwrap();
+        assert_eq!(json, "65280");
+
+        let decoded: ExtensionType = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, ExtensionType::new(0xff00));
+    }
+
+    #[test]
+    fn numbers_are_accepted_for_known_values() {
+        let decoded: Vec<ExtensionType> = serde_json::from_str("[2, \"2\"]").unwrap();
+        assert_eq!(decoded, [ExtensionType::RATCHET_TREE; 2]);
+    }
+
+    #[test]
+    fn unknown_names_are_rejected() {
+        assert!(serde_json::from_str::<CipherSuite>(r#""MLS_0_NONE""#).is_err());
+    }
+}
@@ -12,10 +12,14 @@ use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
 )]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::ffi_type)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(transparent)]
 pub struct ProtocolVersion(u16);
 
+#[cfg(feature = "serde")]
+crate::named_serde::impl_named_serde!(ProtocolVersion, {
+    1 => "mls10",
+});
+
 impl From<u16> for ProtocolVersion {
     fn from(value: u16) -> Self {
         ProtocolVersion(value)
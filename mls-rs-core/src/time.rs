@@ -48,6 +48,54 @@ impl From<u64> for MlsTime {
     }
 }
 
+/// Source of the current time.
+///
+/// The current time is used to set the lifetime of generated key packages
+/// and to validate the lifetime of leaf nodes and the credentials of
+/// group members.
+pub trait TimeProvider: Send + Sync {
+    /// The current time, or `None` if no trusted clock is available in which
+    /// case time based checks are skipped.
+    fn now(&self) -> Option<MlsTime>;
+}
+
+/// [`TimeProvider`] reading the system clock.
+///
+/// There is no system clock on `no_std` targets other than `wasm32`, where
+/// this provider always returns `None`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemTimeProvider;
+
+impl TimeProvider for SystemTimeProvider {
+    fn now(&self) -> Option<MlsTime> {
+        #[cfg(any(feature = "std", target_arch = "wasm32"))]
+        return Some(MlsTime::now());
+
+        #[cfg(not(any(feature = "std", target_arch = "wasm32")))]
+        return None;
+    }
+}
+
+/// [`TimeProvider`] always returning the same time.
+///
+/// This is useful for testing time based checks or on devices whose clock
+/// is not trusted, in which case the time can be obtained from a trusted
+/// source such as the delivery service.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedTimeProvider(MlsTime);
+
+impl FixedTimeProvider {
+    pub fn new(time: MlsTime) -> Self {
+        Self(time)
+    }
+}
+
+impl TimeProvider for FixedTimeProvider {
+    fn now(&self) -> Option<MlsTime> {
+        Some(self.0)
+    }
+}
+
 #[cfg(target_arch = "wasm32")]
 #[wasm_bindgen(inline_js = r#"
 export function date_now() {
@@ -17,10 +17,11 @@ use aws_lc_rs::{digest, error::Unspecified, hmac};
 use aws_lc_sys::SHA256;
 use mls_rs_core::{
     crypto::{
-        CipherSuite, CipherSuiteProvider, CryptoProvider, HpkeCiphertext, HpkePublicKey,
-        HpkeSecretKey, SignaturePublicKey, SignatureSecretKey,
+        CipherSuite, CipherSuiteProvider, CryptoProvider, HpkeAuthCipherSuiteProvider,
+        HpkeCiphertext, HpkePsk, HpkePublicKey, HpkeSecretKey, SignaturePublicKey,
+        SignatureSecretKey,
     },
-    error::IntoAnyError,
+    error::{AnyError, IntoAnyError},
 };
 
 use ec::Ecdh;
@@ -29,7 +30,7 @@ use kdf::AwsLcHkdf;
 use mls_rs_crypto_hpke::{
     context::{ContextR, ContextS},
     dhkem::DhKem,
-    hpke::{Hpke, HpkeError},
+    hpke::{Hpke, HpkeError, SenderKeyPair},
 };
 use mls_rs_crypto_traits::{AeadType, KdfType, KemId};
 use thiserror::Error;
@@ -160,6 +161,12 @@ pub enum AwsLcCryptoError {
     UnsupportedCipherSuite,
     #[error("Cert validation error: {0}")]
     CertValidationFailure(String),
+    #[error("Certificate {0} has been revoked")]
+    CertificateRevoked(String),
+    #[error("Certificate revocation list issued by {0} is past its next update time")]
+    StaleRevocationList(String),
+    #[error(transparent)]
+    CrlProviderError(AnyError),
 }
 
 impl From<Unspecified> for AwsLcCryptoError {
@@ -354,6 +361,94 @@ impl CipherSuiteProvider for AwsLcCipherSuite {
     }
 }
 
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+impl HpkeAuthCipherSuiteProvider for AwsLcCipherSuite {
+    async fn hpke_seal_auth(
+        &self,
+        remote_key: &HpkePublicKey,
+        sender_secret: &HpkeSecretKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+        aad: Option<&[u8]>,
+        pt: &[u8],
+    ) -> Result<HpkeCiphertext, Self::Error> {
+        let sender = SenderKeyPair::new(sender_secret, sender_public);
+
+        self.hpke
+            .seal_auth(remote_key, sender, info, psk.map(Into::into), aad, pt)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn hpke_open_auth(
+        &self,
+        ciphertext: &HpkeCiphertext,
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+        aad: Option<&[u8]>,
+    ) -> Result<Vec<u8>, Self::Error> {
+        self.hpke
+            .open_auth(
+                ciphertext,
+                local_secret,
+                local_public,
+                sender_public,
+                info,
+                psk.map(Into::into),
+                aad,
+            )
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn hpke_setup_s_auth(
+        &self,
+        remote_key: &HpkePublicKey,
+        sender_secret: &HpkeSecretKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+    ) -> Result<(Vec<u8>, Self::HpkeContextS), Self::Error> {
+        let sender = SenderKeyPair::new(sender_secret, sender_public);
+
+        self.hpke
+            .setup_auth_sender(remote_key, sender, info, psk.map(Into::into))
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn hpke_setup_r_auth(
+        &self,
+        enc: &[u8],
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+    ) -> Result<Self::HpkeContextR, Self::Error> {
+        self.hpke
+            .setup_auth_receiver(
+                enc,
+                local_secret,
+                local_public,
+                sender_public,
+                info,
+                psk.map(Into::into),
+            )
+            .await
+            .map_err(Into::into)
+    }
+}
+
 pub fn sha256(data: &[u8]) -> [u8; 32] {
     unsafe {
         let mut out = MaybeUninit::<[u8; 32]>::uninit();
@@ -396,6 +491,11 @@ fn mls_core_tests() {
     mls_rs_core::crypto::test_suite::verify_tests(&AwsLcCryptoProvider::new(), true);
 
     for cs in AwsLcCryptoProvider::new().supported_cipher_suites() {
+        let cipher_suite = AwsLcCryptoProvider::new()
+            .cipher_suite_provider(cs)
+            .unwrap();
+        mls_rs_core::crypto::test_suite::verify_hpke_auth_tests(&cipher_suite);
+
         let mut hpke = AwsLcCryptoProvider::new()
             .cipher_suite_provider(cs)
             .unwrap()
@@ -403,5 +503,6 @@ fn mls_core_tests() {
 
         mls_rs_core::crypto::test_suite::verify_hpke_context_tests(&hpke, cs);
         mls_rs_core::crypto::test_suite::verify_hpke_encap_tests(&mut hpke, cs);
+        mls_rs_core::crypto::test_suite::verify_hpke_setup_tests(&hpke, cs);
     }
 }
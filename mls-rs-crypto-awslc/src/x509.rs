@@ -20,7 +20,9 @@ mod test_utils {
         EVP_PKEY_get_raw_public_key, PEM_read_bio_PrivateKey, PEM_read_bio_X509_REQ, X509_REQ_free,
     };
     use mls_rs_core::crypto::SignatureSecretKey;
-    use mls_rs_identity_x509::{CertificateChain, DerCertificate};
+    use mls_rs_identity_x509::{
+        CertificateChain, DerCertificate, DerCertificateRevocationList, DerOcspResponse,
+    };
 
     pub fn load_test_ca() -> DerCertificate {
         DerCertificate::from(include_bytes!("../test_data/x509/ca.der").to_vec())
@@ -81,6 +83,64 @@ mod test_utils {
         )
     }
 
+    pub fn load_revocation_ca() -> DerCertificate {
+        DerCertificate::from(include_bytes!("../test_data/x509/revocation/root.der").to_vec())
+    }
+
+    pub fn load_revocation_chain(leaf: &str) -> CertificateChain {
+        let (leaf, intermediate) = match leaf {
+            "leaf" => (
+                include_bytes!("../test_data/x509/revocation/leaf.der").to_vec(),
+                include_bytes!("../test_data/x509/revocation/intermediate.der").to_vec(),
+            ),
+            "revoked_leaf" => (
+                include_bytes!("../test_data/x509/revocation/revoked_leaf.der").to_vec(),
+                include_bytes!("../test_data/x509/revocation/intermediate.der").to_vec(),
+            ),
+            "leaf_of_revoked_intermediate" => (
+                include_bytes!("../test_data/x509/revocation/leaf_of_revoked_intermediate.der")
+                    .to_vec(),
+                include_bytes!("../test_data/x509/revocation/revoked_intermediate.der").to_vec(),
+            ),
+            _ => panic!("unknown test leaf {leaf}"),
+        };
+
+        CertificateChain::from_iter([leaf, intermediate].into_iter().map(DerCertificate::from))
+    }
+
+    pub fn load_revocation_lists() -> Vec<DerCertificateRevocationList> {
+        [
+            include_bytes!("../test_data/x509/revocation/root_crl.der").to_vec(),
+            include_bytes!("../test_data/x509/revocation/intermediate_crl.der").to_vec(),
+            include_bytes!("../test_data/x509/revocation/revoked_intermediate_crl.der").to_vec(),
+        ]
+        .into_iter()
+        .map(DerCertificateRevocationList::from)
+        .collect()
+    }
+
+    pub fn load_stale_revocation_list() -> DerCertificateRevocationList {
+        include_bytes!("../test_data/x509/revocation/stale_intermediate_crl.der")
+            .to_vec()
+            .into()
+    }
+
+    pub fn load_ocsp_response(revoked: bool) -> DerOcspResponse {
+        let data = if revoked {
+            include_bytes!("../test_data/x509/revocation/revoked_leaf_ocsp.der").to_vec()
+        } else {
+            include_bytes!("../test_data/x509/revocation/leaf_ocsp.der").to_vec()
+        };
+
+        DerOcspResponse::from(data)
+    }
+
+    pub fn load_foreign_ocsp_response() -> DerOcspResponse {
+        include_bytes!("../test_data/x509/revocation/foreign_leaf_ocsp.der")
+            .to_vec()
+            .into()
+    }
+
     // NOTE: This only works with Ed25519 keys, but that is what we use for our test CSR
     pub fn ec_key_from_pem(pem_bytes: &[u8]) -> SignatureSecretKey {
         unsafe {
@@ -18,7 +18,8 @@ use aws_lc_sys::{
     X509_get0_notBefore, X509_get0_pubkey, X509_get_ext, X509_get_ext_count, X509_get_ext_d2i,
     X509_get_issuer_name, X509_get_serialNumber, X509_get_subject_name, X509_new,
     X509_set_issuer_name, X509_set_notAfter, X509_set_notBefore, X509_set_pubkey,
-    X509_set_serialNumber, X509_set_subject_name, X509_set_version, X509_sign, ASN1_TIME, X509,
+    X509_set_serialNumber, X509_set_subject_name, X509_set_version, X509_sign, X509_up_ref,
+    ASN1_TIME, X509,
 };
 use mls_rs_core::{
     crypto::{CipherSuite, SignaturePublicKey, SignatureSecretKey},
@@ -288,6 +289,13 @@ impl Certificate {
     }
 }
 
+impl Clone for Certificate {
+    fn clone(&self) -> Self {
+        unsafe { X509_up_ref(self.0) };
+        Self(self.0)
+    }
+}
+
 impl Drop for Certificate {
     fn drop(&mut self) {
         unsafe { X509_free(self.0) }
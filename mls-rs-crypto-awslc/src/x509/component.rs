@@ -490,7 +490,7 @@ impl X509Extension {
                 return Err(AwsLcCryptoError::CryptoError);
             }
 
-            let mut out_buffer = vec![0u8; BIO_number_written(bio_out)];
+            let mut out_buffer = vec![0u8; BIO_number_written(bio_out) as usize];
 
             let res = BIO_read(
                 bio_out,
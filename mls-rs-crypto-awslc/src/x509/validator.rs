@@ -2,30 +2,48 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use std::ffi::{c_long, c_ulong, CStr};
+use std::{
+    ffi::{c_int, c_long, c_ulong, CStr},
+    ptr::null_mut,
+};
 
 use aws_lc_sys::{
-    X509_STORE_CTX_free, X509_STORE_CTX_get0_param, X509_STORE_CTX_get_error, X509_STORE_CTX_init,
-    X509_STORE_CTX_new, X509_STORE_CTX_set0_trusted_stack, X509_STORE_free, X509_STORE_new,
+    d2i_OCSP_RESPONSE, d2i_X509_CRL, EVP_sha1, OCSP_BASICRESP_free, OCSP_CERTID_free,
+    OCSP_RESPONSE_free, OCSP_basic_verify, OCSP_cert_to_id, OCSP_resp_find_status,
+    OCSP_response_get1_basic, OCSP_response_status, X509_CRL_free, X509_CRL_get0_by_cert,
+    X509_CRL_get0_nextUpdate, X509_CRL_get_issuer, X509_CRL_verify, X509_NAME_cmp,
+    X509_STORE_CTX_free, X509_STORE_CTX_get0_param, X509_STORE_CTX_get1_chain,
+    X509_STORE_CTX_get_error, X509_STORE_CTX_init, X509_STORE_CTX_new,
+    X509_STORE_CTX_set0_trusted_stack, X509_STORE_free, X509_STORE_new,
     X509_VERIFY_PARAM_get_flags, X509_VERIFY_PARAM_set_flags, X509_VERIFY_PARAM_set_time,
-    X509_verify_cert, X509_verify_cert_error_string, X509_VERIFY_PARAM, X509_V_FLAG_NO_CHECK_TIME,
-    X509_V_OK,
+    X509_cmp_time_posix, X509_get0_pubkey, X509_get_subject_name, X509_verify_cert,
+    X509_verify_cert_error_string, OCSP_BASICRESP, OCSP_CERTID, OCSP_NOINTERN,
+    OCSP_RESPONSE_STATUS_SUCCESSFUL, OCSP_TRUSTOTHER, V_OCSP_CERTSTATUS_REVOKED, X509_CRL,
+    X509_STORE, X509_VERIFY_PARAM, X509_V_FLAG_NO_CHECK_TIME, X509_V_OK,
+};
+use mls_rs_core::{crypto::SignaturePublicKey, error::IntoAnyError, time::MlsTime};
+use mls_rs_identity_x509::{
+    CertificateChain, DerCertificate, DerCertificateRevocationList, DerOcspResponse,
+    X509CredentialValidator, X509CrlProvider,
 };
-use mls_rs_core::{crypto::SignaturePublicKey, time::MlsTime};
-use mls_rs_identity_x509::{CertificateChain, DerCertificate, X509CredentialValidator};
 
 use crate::{check_non_null, AwsLcCryptoError};
 
 use super::{certificate::Certificate, component::Stack};
 
-pub struct CertificateValidator {
+pub struct CertificateValidator<C = Vec<DerCertificateRevocationList>> {
     ca_certs: Stack<Certificate>,
+    crl_provider: C,
 }
 
 impl CertificateValidator {
     pub fn new_der(ca_certs: &[DerCertificate]) -> Result<Self, AwsLcCryptoError> {
         let ca_certs = certs_to_stack(ca_certs)?;
-        Ok(Self { ca_certs })
+
+        Ok(Self {
+            ca_certs,
+            crl_provider: Vec::new(),
+        })
     }
 
     pub fn new(ca_certs: Vec<Certificate>) -> Result<Self, AwsLcCryptoError> {
@@ -34,20 +52,54 @@ impl CertificateValidator {
             stack
         });
 
-        Ok(Self { ca_certs })
+        Ok(Self {
+            ca_certs,
+            crl_provider: Vec::new(),
+        })
     }
+}
 
+impl<C> CertificateValidator<C> {
+    /// Check every certificate of a chain against the revocation lists
+    /// returned by `crl_provider`.
+    ///
+    /// A revocation list is only used if it is signed by the issuer of the
+    /// certificate being checked. Lists that are past their next update time
+    /// cause validation to fail.
+    pub fn with_crl_provider<P: X509CrlProvider>(self, crl_provider: P) -> CertificateValidator<P> {
+        CertificateValidator {
+            ca_certs: self.ca_certs,
+            crl_provider,
+        }
+    }
+}
+
+impl<C: X509CrlProvider> CertificateValidator<C> {
     pub fn validate(
         &self,
         chain: &CertificateChain,
         timestamp: Option<MlsTime>,
+    ) -> Result<SignaturePublicKey, AwsLcCryptoError> {
+        self.validate_with_ocsp(chain, timestamp, &[])
+    }
+
+    /// Validate `chain` and check each of its certificates against the
+    /// revocation lists and the given OCSP responses.
+    ///
+    /// Only OCSP responses signed by the issuer of the certificate they
+    /// refer to are taken into account.
+    pub fn validate_with_ocsp(
+        &self,
+        chain: &CertificateChain,
+        timestamp: Option<MlsTime>,
+        ocsp_responses: &[DerOcspResponse],
     ) -> Result<SignaturePublicKey, AwsLcCryptoError> {
         let leaf = chain
             .leaf()
             .ok_or(AwsLcCryptoError::CryptoError)
             .and_then(Certificate::try_from)?;
 
-        unsafe {
+        let verified_chain = unsafe {
             let cert_stack = certs_to_stack(chain)?;
 
             let ctx = check_non_null(X509_STORE_CTX_new())?;
@@ -65,10 +117,14 @@ impl CertificateValidator {
 
             let validation_result = X509_verify_cert(ctx);
             let validation_code = X509_STORE_CTX_get_error(ctx);
+            let verified_chain = X509_STORE_CTX_get1_chain(ctx);
 
             X509_STORE_free(store);
             X509_STORE_CTX_free(ctx);
 
+            let verified_chain = (!verified_chain.is_null())
+                .then(|| Stack::<Certificate>::from_raw_pointer(verified_chain.cast()));
+
             if validation_result != 1 || validation_code != X509_V_OK {
                 let reason =
                     CStr::from_ptr(X509_verify_cert_error_string(validation_code as c_long))
@@ -79,14 +135,42 @@ impl CertificateValidator {
                 return Err(AwsLcCryptoError::CertValidationFailure(reason));
             }
 
-            core::mem::drop(cert_stack)
-        }
+            core::mem::drop(cert_stack);
+
+            verified_chain.ok_or(AwsLcCryptoError::CryptoError)?
+        };
+
+        // Popping the stack yields the chain from the root to the leaf
+        let verified_chain = verified_chain.into_vec();
+
+        let crls = self
+            .crl_provider
+            .revocation_lists()
+            .map_err(|e| AwsLcCryptoError::CrlProviderError(e.into_any_error()))?
+            .iter()
+            .map(Crl::from_der)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let ocsp_responses = ocsp_responses
+            .iter()
+            .filter_map(|response| OcspBasicResponse::from_der(response).transpose())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let store = Store::new()?;
+        let now = timestamp.map(|t| t.seconds_since_epoch() as i64);
+
+        verified_chain
+            .iter()
+            .zip(verified_chain.iter().skip(1))
+            .try_for_each(|(issuer, cert)| {
+                verify_not_revoked(issuer, cert, now, &store, &crls, &ocsp_responses)
+            })?;
 
         leaf.public_key()
     }
 }
 
-impl X509CredentialValidator for CertificateValidator {
+impl<C: X509CrlProvider> X509CredentialValidator for CertificateValidator<C> {
     type Error = AwsLcCryptoError;
 
     fn validate_chain(
@@ -96,6 +180,166 @@ impl X509CredentialValidator for CertificateValidator {
     ) -> Result<SignaturePublicKey, Self::Error> {
         self.validate(chain, timestamp)
     }
+
+    fn validate_chain_with_ocsp(
+        &self,
+        chain: &CertificateChain,
+        timestamp: Option<MlsTime>,
+        ocsp_responses: &[DerOcspResponse],
+    ) -> Result<SignaturePublicKey, Self::Error> {
+        self.validate_with_ocsp(chain, timestamp, ocsp_responses)
+    }
+}
+
+struct Store(*mut X509_STORE);
+
+impl Store {
+    fn new() -> Result<Self, AwsLcCryptoError> {
+        unsafe { check_non_null(X509_STORE_new()).map(Self) }
+    }
+}
+
+impl Drop for Store {
+    fn drop(&mut self) {
+        unsafe { X509_STORE_free(self.0) }
+    }
+}
+
+struct Crl(*mut X509_CRL);
+
+impl Crl {
+    fn from_der(crl: &DerCertificateRevocationList) -> Result<Self, AwsLcCryptoError> {
+        let mut data = crl.as_ptr();
+
+        unsafe {
+            check_non_null(d2i_X509_CRL(null_mut(), &mut data, crl.len() as c_long)).map(Self)
+        }
+    }
+}
+
+impl Drop for Crl {
+    fn drop(&mut self) {
+        unsafe { X509_CRL_free(self.0) }
+    }
+}
+
+struct OcspBasicResponse(*mut OCSP_BASICRESP);
+
+impl OcspBasicResponse {
+    /// Parse an OCSP response, ignoring responses that do not carry a status.
+    fn from_der(response: &DerOcspResponse) -> Result<Option<Self>, AwsLcCryptoError> {
+        let mut data = response.as_ptr();
+
+        unsafe {
+            let response = check_non_null(d2i_OCSP_RESPONSE(
+                null_mut(),
+                &mut data,
+                response.len() as c_long,
+            ))?;
+
+            let basic = (OCSP_response_status(response) == OCSP_RESPONSE_STATUS_SUCCESSFUL)
+                .then(|| OCSP_response_get1_basic(response));
+
+            OCSP_RESPONSE_free(response);
+
+            basic
+                .map(|basic| check_non_null(basic).map(Self))
+                .transpose()
+        }
+    }
+}
+
+impl Drop for OcspBasicResponse {
+    fn drop(&mut self) {
+        unsafe { OCSP_BASICRESP_free(self.0) }
+    }
+}
+
+struct OcspCertId(*mut OCSP_CERTID);
+
+impl Drop for OcspCertId {
+    fn drop(&mut self) {
+        unsafe { OCSP_CERTID_free(self.0) }
+    }
+}
+
+fn verify_not_revoked(
+    issuer: &Certificate,
+    cert: &Certificate,
+    now: Option<i64>,
+    store: &Store,
+    crls: &[Crl],
+    ocsp_responses: &[OcspBasicResponse],
+) -> Result<(), AwsLcCryptoError> {
+    let revoked =
+        || AwsLcCryptoError::CertificateRevoked(format!("{:?}", cert.subject_components()));
+
+    unsafe {
+        let issuer_name = X509_get_subject_name(issuer.as_ptr());
+        let issuer_key = X509_get0_pubkey(issuer.as_ptr());
+
+        for crl in crls {
+            if X509_NAME_cmp(X509_CRL_get_issuer(crl.0), issuer_name) != 0
+                || X509_CRL_verify(crl.0, issuer_key) != 1
+            {
+                continue;
+            }
+
+            let next_update = X509_CRL_get0_nextUpdate(crl.0);
+
+            if let Some(now) = now.filter(|_| !next_update.is_null()) {
+                if X509_cmp_time_posix(next_update, now) < 0 {
+                    return Err(AwsLcCryptoError::StaleRevocationList(format!(
+                        "{:?}",
+                        issuer.subject_components()
+                    )));
+                }
+            }
+
+            if X509_CRL_get0_by_cert(crl.0, &mut null_mut(), cert.as_ptr()) == 1 {
+                return Err(revoked());
+            }
+        }
+
+        // Only responses signed by the issuer itself are accepted. Delegated
+        // OCSP responders are not supported.
+        let mut responder = Stack::new()?;
+        responder.push(issuer.clone());
+
+        let cert_id = check_non_null(OCSP_cert_to_id(EVP_sha1(), cert.as_ptr(), issuer.as_ptr()))
+            .map(OcspCertId)?;
+
+        for response in ocsp_responses {
+            let mut status: c_int = -1;
+
+            let found = OCSP_resp_find_status(
+                response.0,
+                cert_id.0,
+                &mut status,
+                null_mut(),
+                null_mut(),
+                null_mut(),
+                null_mut(),
+            );
+
+            if found != 1 {
+                continue;
+            }
+
+            let verified = OCSP_basic_verify(
+                response.0,
+                responder.as_ptr().cast(),
+                store.0,
+                (OCSP_TRUSTOTHER | OCSP_NOINTERN) as c_ulong,
+            );
+
+            if verified == 1 && status == V_OCSP_CERTSTATUS_REVOKED {
+                return Err(revoked());
+            }
+        }
+    }
+
+    Ok(())
 }
 
 fn certs_to_stack(certs: &[DerCertificate]) -> Result<Stack<Certificate>, AwsLcCryptoError> {
@@ -130,6 +374,8 @@ mod tests {
     use crate::{
         x509::{
             test_utils::{
+                load_foreign_ocsp_response, load_ocsp_response, load_revocation_ca,
+                load_revocation_chain, load_revocation_lists, load_stale_revocation_list,
                 load_test_ca, load_test_cert_chain, load_test_invalid_ca_chain,
                 load_test_invalid_chain,
             },
@@ -197,4 +443,130 @@ mod tests {
 
         assert_matches!(res, Err(AwsLcCryptoError::CertValidationFailure(_)));
     }
+
+    #[test]
+    fn can_validate_unrevoked_chain_with_crls() {
+        let validator = CertificateValidator::new_der(&[load_revocation_ca()])
+            .unwrap()
+            .with_crl_provider(load_revocation_lists());
+
+        validator
+            .validate_chain(&load_revocation_chain("leaf"), Some(MlsTime::now()))
+            .unwrap();
+    }
+
+    #[test]
+    fn can_detect_revoked_leaf_with_crl() {
+        let validator = CertificateValidator::new_der(&[load_revocation_ca()])
+            .unwrap()
+            .with_crl_provider(load_revocation_lists());
+
+        let res = validator.validate_chain(&load_revocation_chain("revoked_leaf"), None);
+
+        assert_matches!(res, Err(AwsLcCryptoError::CertificateRevoked(_)));
+    }
+
+    #[test]
+    fn can_detect_revoked_intermediate_with_crl() {
+        let validator = CertificateValidator::new_der(&[load_revocation_ca()])
+            .unwrap()
+            .with_crl_provider(load_revocation_lists());
+
+        let chain = load_revocation_chain("leaf_of_revoked_intermediate");
+        let res = validator.validate_chain(&chain, None);
+
+        assert_matches!(res, Err(AwsLcCryptoError::CertificateRevoked(_)));
+    }
+
+    #[test]
+    fn ignores_crl_with_invalid_signature() {
+        let mut crls = load_revocation_lists();
+
+        for crl in crls.iter_mut() {
+            let mut data = crl.to_vec();
+            *data.last_mut().unwrap() ^= 1;
+            *crl = data.into();
+        }
+
+        let validator = CertificateValidator::new_der(&[load_revocation_ca()])
+            .unwrap()
+            .with_crl_provider(crls);
+
+        validator
+            .validate_chain(&load_revocation_chain("revoked_leaf"), None)
+            .unwrap();
+    }
+
+    #[test]
+    fn can_detect_stale_crl() {
+        let validator = CertificateValidator::new_der(&[load_revocation_ca()])
+            .unwrap()
+            .with_crl_provider(vec![load_stale_revocation_list()]);
+
+        let chain = load_revocation_chain("leaf");
+
+        assert_matches!(
+            validator.validate_chain(&chain, Some(MlsTime::now())),
+            Err(AwsLcCryptoError::StaleRevocationList(_))
+        );
+
+        validator.validate_chain(&chain, None).unwrap();
+    }
+
+    #[test]
+    fn can_validate_chain_with_good_ocsp_staple() {
+        let validator = CertificateValidator::new_der(&[load_revocation_ca()]).unwrap();
+
+        validator
+            .validate_chain_with_ocsp(
+                &load_revocation_chain("leaf"),
+                Some(MlsTime::now()),
+                &[load_ocsp_response(false)],
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn can_detect_revoked_leaf_with_ocsp_staple() {
+        let validator = CertificateValidator::new_der(&[load_revocation_ca()]).unwrap();
+        let chain = load_revocation_chain("revoked_leaf");
+
+        validator
+            .validate_chain(&chain, Some(MlsTime::now()))
+            .unwrap();
+
+        let res = validator.validate_chain_with_ocsp(
+            &chain,
+            Some(MlsTime::now()),
+            &[load_ocsp_response(true)],
+        );
+
+        assert_matches!(res, Err(AwsLcCryptoError::CertificateRevoked(_)));
+    }
+
+    #[test]
+    fn ignores_ocsp_staple_for_other_certificate() {
+        let validator = CertificateValidator::new_der(&[load_revocation_ca()]).unwrap();
+
+        validator
+            .validate_chain_with_ocsp(
+                &load_revocation_chain("leaf"),
+                Some(MlsTime::now()),
+                &[load_ocsp_response(true)],
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn ignores_ocsp_staple_for_certificate_of_other_issuer() {
+        let validator = CertificateValidator::new_der(&[load_revocation_ca()]).unwrap();
+
+        validator
+            .validate_chain_with_ocsp(
+                &load_revocation_chain("leaf"),
+                Some(MlsTime::now()),
+                &[load_foreign_ocsp_response()],
+            )
+            .unwrap();
+    }
 }
@@ -0,0 +1,1618 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+//! Cipher suites assembled from independently chosen primitives.
+//!
+//! [`CompositeCipherSuite`] combines any KEM, KDF, AEAD, hash, signature and
+//! RNG implementation into a single [`CipherSuiteProvider`], so that e.g. an
+//! AEAD from one backend can be paired with a KEM from another.
+//! [`CompositeCryptoProvider`] then registers such cipher suites under
+//! arbitrary codepoints, including private use ones created with
+//! [`CipherSuite::new`], next to cipher suites of other backends.
+//!
+//! HPKE ephemeral keys and freshly generated KEM keys are derived from the
+//! configured RNG, which is why the KEM must implement [`SeededKemType`].
+
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+
+use mls_rs_core::{
+    crypto::{
+        CipherSuite, CipherSuiteProvider, CryptoProvider, HpkeAuthCipherSuiteProvider,
+        HpkeCiphertext, HpkeContextR, HpkeContextS, HpkePsk, HpkePublicKey, HpkeSecretKey,
+        SignaturePublicKey, SignatureSecretKey,
+    },
+    error::{AnyError, IntoAnyError},
+};
+use mls_rs_crypto_traits::{
+    AeadId, AeadType, HashType, KdfId, KdfType, KemId, KemType, RngType, SeededAuthKemType,
+    SeededKemType, SignatureType,
+};
+use zeroize::Zeroizing;
+
+use crate::{
+    context::{ContextR, ContextS},
+    hpke::{Hpke, HpkeError, SenderKeyPair},
+};
+
+#[derive(Debug)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+pub enum CompositeError {
+    #[cfg_attr(feature = "std", error(transparent))]
+    HpkeError(HpkeError),
+    #[cfg_attr(feature = "std", error(transparent))]
+    KdfError(AnyError),
+    #[cfg_attr(feature = "std", error(transparent))]
+    AeadError(AnyError),
+    #[cfg_attr(feature = "std", error(transparent))]
+    HashError(AnyError),
+    #[cfg_attr(feature = "std", error(transparent))]
+    SignatureError(AnyError),
+    #[cfg_attr(feature = "std", error(transparent))]
+    RngError(AnyError),
+    #[cfg_attr(feature = "std", error(transparent))]
+    ProviderError(AnyError),
+    /// The KEM, KDF or AEAD does not have the codepoint that the cipher suite
+    /// being built uses for it.
+    #[cfg_attr(feature = "std", error("primitives do not match cipher suite {0:?}"))]
+    CipherSuiteMismatch(CipherSuite),
+}
+
+impl From<HpkeError> for CompositeError {
+    fn from(e: HpkeError) -> Self {
+        CompositeError::HpkeError(e)
+    }
+}
+
+impl IntoAnyError for CompositeError {
+    #[cfg(feature = "std")]
+    fn into_dyn_error(self) -> Result<Box<dyn std::error::Error + Send + Sync>, Self> {
+        Ok(self.into())
+    }
+}
+
+/// A [`CipherSuiteProvider`] built from independent primitive implementations.
+///
+/// Use [`CompositeCipherSuiteBuilder`] to construct one.
+#[derive(Clone)]
+pub struct CompositeCipherSuite<KEM, KDF, AEAD, HASH, SIG, RNG>
+where
+    KEM: KemType + Clone,
+    KDF: KdfType + Clone,
+    AEAD: AeadType + Clone,
+{
+    cipher_suite: CipherSuite,
+    kdf: KDF,
+    aead: AEAD,
+    hash: HASH,
+    signer: SIG,
+    rng: RNG,
+    hpke: Hpke<KEM, KDF, AEAD>,
+}
+
+impl<KEM, KDF, AEAD, HASH, SIG, RNG> CompositeCipherSuite<KEM, KDF, AEAD, HASH, SIG, RNG>
+where
+    KEM: KemType + Clone,
+    KDF: KdfType + Clone,
+    AEAD: AeadType + Clone,
+{
+    /// The underlying HPKE instance built from the KEM, KDF and AEAD.
+    pub fn hpke(&self) -> &Hpke<KEM, KDF, AEAD> {
+        &self.hpke
+    }
+}
+
+impl<KEM, KDF, AEAD, HASH, SIG, RNG> CompositeCipherSuite<KEM, KDF, AEAD, HASH, SIG, RNG>
+where
+    KEM: SeededKemType + Clone,
+    KDF: KdfType + Clone,
+    AEAD: AeadType + Clone,
+    RNG: RngType,
+{
+    fn kem_seed(&self) -> Result<Zeroizing<Vec<u8>>, CompositeError> {
+        let mut seed = Zeroizing::new(alloc::vec![0u8; self.hpke.kem.seed_len()]);
+
+        self.rng
+            .random_bytes(&mut seed)
+            .map_err(|e| CompositeError::RngError(e.into_any_error()))?;
+
+        Ok(seed)
+    }
+}
+
+/// Builder for [`CompositeCipherSuite`].
+///
+/// Every primitive must be provided before [`build`](Self::build) becomes
+/// available. Primitives that are not yet set are represented by `()`.
+#[derive(Clone, Debug)]
+pub struct CompositeCipherSuiteBuilder<KEM = (), KDF = (), AEAD = (), HASH = (), SIG = (), RNG = ()>
+{
+    cipher_suite: CipherSuite,
+    kem: KEM,
+    kdf: KDF,
+    aead: AEAD,
+    hash: HASH,
+    signer: SIG,
+    rng: RNG,
+}
+
+impl CompositeCipherSuiteBuilder {
+    /// Start building a cipher suite that will be identified by `cipher_suite`.
+    pub fn new(cipher_suite: CipherSuite) -> Self {
+        Self {
+            cipher_suite,
+            kem: (),
+            kdf: (),
+            aead: (),
+            hash: (),
+            signer: (),
+            rng: (),
+        }
+    }
+}
+
+impl<KEM, KDF, AEAD, HASH, SIG, RNG> CompositeCipherSuiteBuilder<KEM, KDF, AEAD, HASH, SIG, RNG> {
+    /// Set the KEM used for HPKE.
+    pub fn kem<K: SeededKemType + Clone>(
+        self,
+        kem: K,
+    ) -> CompositeCipherSuiteBuilder<K, KDF, AEAD, HASH, SIG, RNG> {
+        CompositeCipherSuiteBuilder {
+            cipher_suite: self.cipher_suite,
+            kem,
+            kdf: self.kdf,
+            aead: self.aead,
+            hash: self.hash,
+            signer: self.signer,
+            rng: self.rng,
+        }
+    }
+
+    /// Set the KDF used both by MLS and by HPKE.
+    pub fn kdf<K: KdfType + Clone>(
+        self,
+        kdf: K,
+    ) -> CompositeCipherSuiteBuilder<KEM, K, AEAD, HASH, SIG, RNG> {
+        CompositeCipherSuiteBuilder {
+            cipher_suite: self.cipher_suite,
+            kem: self.kem,
+            kdf,
+            aead: self.aead,
+            hash: self.hash,
+            signer: self.signer,
+            rng: self.rng,
+        }
+    }
+
+    /// Set the AEAD used both by MLS and by HPKE.
+    pub fn aead<A: AeadType + Clone>(
+        self,
+        aead: A,
+    ) -> CompositeCipherSuiteBuilder<KEM, KDF, A, HASH, SIG, RNG> {
+        CompositeCipherSuiteBuilder {
+            cipher_suite: self.cipher_suite,
+            kem: self.kem,
+            kdf: self.kdf,
+            aead,
+            hash: self.hash,
+            signer: self.signer,
+            rng: self.rng,
+        }
+    }
+
+    /// Set the hash function and MAC.
+    pub fn hash<H: HashType>(
+        self,
+        hash: H,
+    ) -> CompositeCipherSuiteBuilder<KEM, KDF, AEAD, H, SIG, RNG> {
+        CompositeCipherSuiteBuilder {
+            cipher_suite: self.cipher_suite,
+            kem: self.kem,
+            kdf: self.kdf,
+            aead: self.aead,
+            hash,
+            signer: self.signer,
+            rng: self.rng,
+        }
+    }
+
+    /// Set the signature scheme.
+    pub fn signature<S: SignatureType>(
+        self,
+        signer: S,
+    ) -> CompositeCipherSuiteBuilder<KEM, KDF, AEAD, HASH, S, RNG> {
+        CompositeCipherSuiteBuilder {
+            cipher_suite: self.cipher_suite,
+            kem: self.kem,
+            kdf: self.kdf,
+            aead: self.aead,
+            hash: self.hash,
+            signer,
+            rng: self.rng,
+        }
+    }
+
+    /// Set the random number generator.
+    pub fn rng<R: RngType>(
+        self,
+        rng: R,
+    ) -> CompositeCipherSuiteBuilder<KEM, KDF, AEAD, HASH, SIG, R> {
+        CompositeCipherSuiteBuilder {
+            cipher_suite: self.cipher_suite,
+            kem: self.kem,
+            kdf: self.kdf,
+            aead: self.aead,
+            hash: self.hash,
+            signer: self.signer,
+            rng,
+        }
+    }
+}
+
+impl<KEM, KDF, AEAD, HASH, SIG, RNG> CompositeCipherSuiteBuilder<KEM, KDF, AEAD, HASH, SIG, RNG>
+where
+    KEM: SeededKemType + Clone,
+    KDF: KdfType + Clone,
+    AEAD: AeadType + Clone,
+    HASH: HashType,
+    SIG: SignatureType,
+    RNG: RngType,
+{
+    /// Build the cipher suite.
+    ///
+    /// If the cipher suite is one of the cipher suites defined in RFC 9420,
+    /// the KEM, KDF and AEAD must have the codepoints it specifies. Other
+    /// codepoints accept any primitives. Leaving out a primitive is rejected
+    /// at compile time:
+    ///
+    /// ```compile_fail,E0599
+    /// use mls_rs_core::crypto::CipherSuite;
+    /// use mls_rs_crypto_hpke::composite::CompositeCipherSuiteBuilder;
+    ///
+    /// CompositeCipherSuiteBuilder::new(CipherSuite::CURVE25519_AES128).build();
+    /// ```
+    pub fn build(
+        self,
+    ) -> Result<CompositeCipherSuite<KEM, KDF, AEAD, HASH, SIG, RNG>, CompositeError> {
+        let cs = self.cipher_suite;
+
+        let mismatch = matches!(KemId::new(cs), Some(id) if id as u16 != self.kem.kem_id())
+            || matches!(KdfId::new(cs), Some(id) if id as u16 != self.kdf.kdf_id())
+            || matches!(AeadId::new(cs), Some(id) if id as u16 != self.aead.aead_id());
+
+        if mismatch {
+            return Err(CompositeError::CipherSuiteMismatch(cs));
+        }
+
+        let hpke = Hpke::new(self.kem, self.kdf.clone(), Some(self.aead.clone()));
+
+        Ok(CompositeCipherSuite {
+            cipher_suite: cs,
+            kdf: self.kdf,
+            aead: self.aead,
+            hash: self.hash,
+            signer: self.signer,
+            rng: self.rng,
+            hpke,
+        })
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+impl<KEM, KDF, AEAD, HASH, SIG, RNG> CipherSuiteProvider
+    for CompositeCipherSuite<KEM, KDF, AEAD, HASH, SIG, RNG>
+where
+    KEM: SeededKemType + Clone + Send + Sync,
+    KDF: KdfType + Clone + Send + Sync,
+    AEAD: AeadType + Clone + Send + Sync,
+    HASH: HashType,
+    SIG: SignatureType,
+    RNG: RngType,
+{
+    type Error = CompositeError;
+    type HpkeContextR = ContextR<KDF, AEAD>;
+    type HpkeContextS = ContextS<KDF, AEAD>;
+
+    async fn hash(&self, data: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        self.hash
+            .hash(data)
+            .await
+            .map_err(|e| CompositeError::HashError(e.into_any_error()))
+    }
+
+    async fn mac(&self, key: &[u8], data: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        self.hash
+            .mac(key, data)
+            .await
+            .map_err(|e| CompositeError::HashError(e.into_any_error()))
+    }
+
+    async fn aead_seal(
+        &self,
+        key: &[u8],
+        data: &[u8],
+        aad: Option<&[u8]>,
+        nonce: &[u8],
+    ) -> Result<Vec<u8>, Self::Error> {
+        self.aead
+            .seal(key, data, aad, nonce)
+            .await
+            .map_err(|e| CompositeError::AeadError(e.into_any_error()))
+    }
+
+    async fn aead_open(
+        &self,
+        key: &[u8],
+        cipher_text: &[u8],
+        aad: Option<&[u8]>,
+        nonce: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, Self::Error> {
+        self.aead
+            .open(key, cipher_text, aad, nonce)
+            .await
+            .map_err(|e| CompositeError::AeadError(e.into_any_error()))
+            .map(Zeroizing::new)
+    }
+
+    fn aead_key_size(&self) -> usize {
+        self.aead.key_size()
+    }
+
+    fn aead_nonce_size(&self) -> usize {
+        self.aead.nonce_size()
+    }
+
+    async fn kdf_expand(
+        &self,
+        prk: &[u8],
+        info: &[u8],
+        len: usize,
+    ) -> Result<Zeroizing<Vec<u8>>, Self::Error> {
+        self.kdf
+            .expand(prk, info, len)
+            .await
+            .map_err(|e| CompositeError::KdfError(e.into_any_error()))
+            .map(Zeroizing::new)
+    }
+
+    async fn kdf_extract(
+        &self,
+        salt: &[u8],
+        ikm: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, Self::Error> {
+        self.kdf
+            .extract(salt, ikm)
+            .await
+            .map_err(|e| CompositeError::KdfError(e.into_any_error()))
+            .map(Zeroizing::new)
+    }
+
+    fn kdf_extract_size(&self) -> usize {
+        self.kdf.extract_size()
+    }
+
+    async fn hpke_seal(
+        &self,
+        remote_key: &HpkePublicKey,
+        info: &[u8],
+        aad: Option<&[u8]>,
+        pt: &[u8],
+    ) -> Result<HpkeCiphertext, Self::Error> {
+        let seed = self.kem_seed()?;
+
+        Ok(self
+            .hpke
+            .seal_seeded(remote_key, info, None, aad, pt, &seed)
+            .await?)
+    }
+
+    async fn hpke_open(
+        &self,
+        ciphertext: &HpkeCiphertext,
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        info: &[u8],
+        aad: Option<&[u8]>,
+    ) -> Result<Vec<u8>, Self::Error> {
+        Ok(self
+            .hpke
+            .open(ciphertext, local_secret, local_public, info, None, aad)
+            .await?)
+    }
+
+    async fn hpke_setup_r(
+        &self,
+        enc: &[u8],
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        info: &[u8],
+    ) -> Result<Self::HpkeContextR, Self::Error> {
+        Ok(self
+            .hpke
+            .setup_receiver(enc, local_secret, local_public, info, None)
+            .await?)
+    }
+
+    async fn hpke_setup_s(
+        &self,
+        remote_key: &HpkePublicKey,
+        info: &[u8],
+    ) -> Result<(Vec<u8>, Self::HpkeContextS), Self::Error> {
+        let seed = self.kem_seed()?;
+
+        Ok(self
+            .hpke
+            .setup_sender_seeded(remote_key, info, None, &seed)
+            .await?)
+    }
+
+    async fn kem_derive(&self, ikm: &[u8]) -> Result<(HpkeSecretKey, HpkePublicKey), Self::Error> {
+        Ok(self.hpke.derive(ikm).await?)
+    }
+
+    async fn kem_generate(&self) -> Result<(HpkeSecretKey, HpkePublicKey), Self::Error> {
+        let seed = self.kem_seed()?;
+        Ok(self.hpke.derive(&seed).await?)
+    }
+
+    fn kem_public_key_validate(&self, key: &HpkePublicKey) -> Result<(), Self::Error> {
+        Ok(self.hpke.public_key_validate(key)?)
+    }
+
+    fn random_bytes(&self, out: &mut [u8]) -> Result<(), Self::Error> {
+        self.rng
+            .random_bytes(out)
+            .map_err(|e| CompositeError::RngError(e.into_any_error()))
+    }
+
+    fn cipher_suite(&self) -> CipherSuite {
+        self.cipher_suite
+    }
+
+    async fn sign(
+        &self,
+        secret_key: &SignatureSecretKey,
+        data: &[u8],
+    ) -> Result<Vec<u8>, Self::Error> {
+        self.signer
+            .sign(secret_key, data)
+            .await
+            .map_err(|e| CompositeError::SignatureError(e.into_any_error()))
+    }
+
+    async fn verify(
+        &self,
+        public_key: &SignaturePublicKey,
+        signature: &[u8],
+        data: &[u8],
+    ) -> Result<(), Self::Error> {
+        self.signer
+            .verify(public_key, signature, data)
+            .await
+            .map_err(|e| CompositeError::SignatureError(e.into_any_error()))
+    }
+
+    async fn signature_key_generate(
+        &self,
+    ) -> Result<(SignatureSecretKey, SignaturePublicKey), Self::Error> {
+        self.signer
+            .signature_key_generate()
+            .await
+            .map_err(|e| CompositeError::SignatureError(e.into_any_error()))
+    }
+
+    async fn signature_key_derive_public(
+        &self,
+        secret_key: &SignatureSecretKey,
+    ) -> Result<SignaturePublicKey, Self::Error> {
+        self.signer
+            .signature_key_derive_public(secret_key)
+            .await
+            .map_err(|e| CompositeError::SignatureError(e.into_any_error()))
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+impl<KEM, KDF, AEAD, HASH, SIG, RNG> HpkeAuthCipherSuiteProvider
+    for CompositeCipherSuite<KEM, KDF, AEAD, HASH, SIG, RNG>
+where
+    KEM: SeededAuthKemType + Clone + Send + Sync,
+    KDF: KdfType + Clone + Send + Sync,
+    AEAD: AeadType + Clone + Send + Sync,
+    HASH: HashType,
+    SIG: SignatureType,
+    RNG: RngType,
+{
+    async fn hpke_seal_auth(
+        &self,
+        remote_key: &HpkePublicKey,
+        sender_secret: &HpkeSecretKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+        aad: Option<&[u8]>,
+        pt: &[u8],
+    ) -> Result<HpkeCiphertext, Self::Error> {
+        let sender = SenderKeyPair::new(sender_secret, sender_public);
+        let seed = self.kem_seed()?;
+
+        Ok(self
+            .hpke
+            .seal_auth_seeded(
+                remote_key,
+                sender,
+                info,
+                psk.map(Into::into),
+                aad,
+                pt,
+                &seed,
+            )
+            .await?)
+    }
+
+    async fn hpke_open_auth(
+        &self,
+        ciphertext: &HpkeCiphertext,
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+        aad: Option<&[u8]>,
+    ) -> Result<Vec<u8>, Self::Error> {
+        Ok(self
+            .hpke
+            .open_auth(
+                ciphertext,
+                local_secret,
+                local_public,
+                sender_public,
+                info,
+                psk.map(Into::into),
+                aad,
+            )
+            .await?)
+    }
+
+    async fn hpke_setup_s_auth(
+        &self,
+        remote_key: &HpkePublicKey,
+        sender_secret: &HpkeSecretKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+    ) -> Result<(Vec<u8>, Self::HpkeContextS), Self::Error> {
+        let sender = SenderKeyPair::new(sender_secret, sender_public);
+        let seed = self.kem_seed()?;
+
+        Ok(self
+            .hpke
+            .setup_auth_sender_seeded(remote_key, sender, info, psk.map(Into::into), &seed)
+            .await?)
+    }
+
+    async fn hpke_setup_r_auth(
+        &self,
+        enc: &[u8],
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+    ) -> Result<Self::HpkeContextR, Self::Error> {
+        Ok(self
+            .hpke
+            .setup_auth_receiver(
+                enc,
+                local_secret,
+                local_public,
+                sender_public,
+                info,
+                psk.map(Into::into),
+            )
+            .await?)
+    }
+}
+
+/// A [`CipherSuiteProvider`] with its concrete type erased.
+///
+/// This allows cipher suites implemented by different backends, with
+/// different error and HPKE context types, to be served by the same
+/// [`CompositeCryptoProvider`].
+#[derive(Clone)]
+pub struct DynCipherSuiteProvider {
+    inner: Arc<dyn ErasedCipherSuiteProvider>,
+}
+
+impl DynCipherSuiteProvider {
+    pub fn new<CS>(cipher_suite_provider: CS) -> Self
+    where
+        CS: CipherSuiteProvider + 'static,
+        CS::HpkeContextS: 'static,
+        CS::HpkeContextR: 'static,
+    {
+        Self {
+            inner: Arc::new(cipher_suite_provider),
+        }
+    }
+}
+
+impl core::fmt::Debug for DynCipherSuiteProvider {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DynCipherSuiteProvider")
+            .field("cipher_suite", &self.inner.cipher_suite())
+            .finish()
+    }
+}
+
+/// Sender HPKE context of a [`DynCipherSuiteProvider`].
+pub struct DynHpkeContextS(Box<dyn ErasedHpkeContextS>);
+
+/// Receiver HPKE context of a [`DynCipherSuiteProvider`].
+pub struct DynHpkeContextR(Box<dyn ErasedHpkeContextR>);
+
+fn provider_error<E: IntoAnyError>(e: E) -> CompositeError {
+    CompositeError::ProviderError(e.into_any_error())
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+trait ErasedHpkeContextS: Send + Sync {
+    async fn seal(&mut self, aad: Option<&[u8]>, data: &[u8]) -> Result<Vec<u8>, CompositeError>;
+    async fn export(&self, exporter_context: &[u8], len: usize) -> Result<Vec<u8>, CompositeError>;
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+impl<T: HpkeContextS + Send + Sync> ErasedHpkeContextS for T {
+    async fn seal(&mut self, aad: Option<&[u8]>, data: &[u8]) -> Result<Vec<u8>, CompositeError> {
+        HpkeContextS::seal(self, aad, data)
+            .await
+            .map_err(provider_error)
+    }
+
+    async fn export(&self, exporter_context: &[u8], len: usize) -> Result<Vec<u8>, CompositeError> {
+        HpkeContextS::export(self, exporter_context, len)
+            .await
+            .map_err(provider_error)
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+trait ErasedHpkeContextR: Send + Sync {
+    async fn open(
+        &mut self,
+        aad: Option<&[u8]>,
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, CompositeError>;
+    async fn export(&self, exporter_context: &[u8], len: usize) -> Result<Vec<u8>, CompositeError>;
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+impl<T: HpkeContextR + Send + Sync> ErasedHpkeContextR for T {
+    async fn open(
+        &mut self,
+        aad: Option<&[u8]>,
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, CompositeError> {
+        HpkeContextR::open(self, aad, ciphertext)
+            .await
+            .map_err(provider_error)
+    }
+
+    async fn export(&self, exporter_context: &[u8], len: usize) -> Result<Vec<u8>, CompositeError> {
+        HpkeContextR::export(self, exporter_context, len)
+            .await
+            .map_err(provider_error)
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+impl HpkeContextS for DynHpkeContextS {
+    type Error = CompositeError;
+
+    async fn seal(&mut self, aad: Option<&[u8]>, data: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        self.0.seal(aad, data).await
+    }
+
+    async fn export(&self, exporter_context: &[u8], len: usize) -> Result<Vec<u8>, Self::Error> {
+        self.0.export(exporter_context, len).await
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+impl HpkeContextR for DynHpkeContextR {
+    type Error = CompositeError;
+
+    async fn open(
+        &mut self,
+        aad: Option<&[u8]>,
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, Self::Error> {
+        self.0.open(aad, ciphertext).await
+    }
+
+    async fn export(&self, exporter_context: &[u8], len: usize) -> Result<Vec<u8>, Self::Error> {
+        self.0.export(exporter_context, len).await
+    }
+}
+
+/// Object safe version of [`CipherSuiteProvider`] backing [`DynCipherSuiteProvider`].
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+trait ErasedCipherSuiteProvider: Send + Sync {
+    fn cipher_suite(&self) -> CipherSuite;
+    async fn hash(&self, data: &[u8]) -> Result<Vec<u8>, CompositeError>;
+    async fn mac(&self, key: &[u8], data: &[u8]) -> Result<Vec<u8>, CompositeError>;
+
+    async fn aead_seal(
+        &self,
+        key: &[u8],
+        data: &[u8],
+        aad: Option<&[u8]>,
+        nonce: &[u8],
+    ) -> Result<Vec<u8>, CompositeError>;
+
+    async fn aead_open(
+        &self,
+        key: &[u8],
+        ciphertext: &[u8],
+        aad: Option<&[u8]>,
+        nonce: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, CompositeError>;
+
+    fn aead_key_size(&self) -> usize;
+    fn aead_nonce_size(&self) -> usize;
+
+    async fn kdf_extract(
+        &self,
+        salt: &[u8],
+        ikm: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, CompositeError>;
+
+    async fn kdf_expand(
+        &self,
+        prk: &[u8],
+        info: &[u8],
+        len: usize,
+    ) -> Result<Zeroizing<Vec<u8>>, CompositeError>;
+
+    fn kdf_extract_size(&self) -> usize;
+
+    async fn hpke_seal(
+        &self,
+        remote_key: &HpkePublicKey,
+        info: &[u8],
+        aad: Option<&[u8]>,
+        pt: &[u8],
+    ) -> Result<HpkeCiphertext, CompositeError>;
+
+    async fn hpke_open(
+        &self,
+        ciphertext: &HpkeCiphertext,
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        info: &[u8],
+        aad: Option<&[u8]>,
+    ) -> Result<Vec<u8>, CompositeError>;
+
+    async fn hpke_setup_s(
+        &self,
+        remote_key: &HpkePublicKey,
+        info: &[u8],
+    ) -> Result<(Vec<u8>, DynHpkeContextS), CompositeError>;
+
+    async fn hpke_setup_r(
+        &self,
+        kem_output: &[u8],
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        info: &[u8],
+    ) -> Result<DynHpkeContextR, CompositeError>;
+
+    async fn kem_derive(
+        &self,
+        ikm: &[u8],
+    ) -> Result<(HpkeSecretKey, HpkePublicKey), CompositeError>;
+
+    async fn kem_generate(&self) -> Result<(HpkeSecretKey, HpkePublicKey), CompositeError>;
+    fn kem_public_key_validate(&self, key: &HpkePublicKey) -> Result<(), CompositeError>;
+    fn random_bytes(&self, out: &mut [u8]) -> Result<(), CompositeError>;
+
+    async fn signature_key_generate(
+        &self,
+    ) -> Result<(SignatureSecretKey, SignaturePublicKey), CompositeError>;
+
+    async fn signature_key_derive_public(
+        &self,
+        secret_key: &SignatureSecretKey,
+    ) -> Result<SignaturePublicKey, CompositeError>;
+
+    async fn sign(
+        &self,
+        secret_key: &SignatureSecretKey,
+        data: &[u8],
+    ) -> Result<Vec<u8>, CompositeError>;
+
+    async fn verify(
+        &self,
+        public_key: &SignaturePublicKey,
+        signature: &[u8],
+        data: &[u8],
+    ) -> Result<(), CompositeError>;
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+impl<T> ErasedCipherSuiteProvider for T
+where
+    T: CipherSuiteProvider,
+    T::HpkeContextS: 'static,
+    T::HpkeContextR: 'static,
+{
+    fn cipher_suite(&self) -> CipherSuite {
+        CipherSuiteProvider::cipher_suite(self)
+    }
+
+    async fn hash(&self, data: &[u8]) -> Result<Vec<u8>, CompositeError> {
+        CipherSuiteProvider::hash(self, data)
+            .await
+            .map_err(provider_error)
+    }
+
+    async fn mac(&self, key: &[u8], data: &[u8]) -> Result<Vec<u8>, CompositeError> {
+        CipherSuiteProvider::mac(self, key, data)
+            .await
+            .map_err(provider_error)
+    }
+
+    async fn aead_seal(
+        &self,
+        key: &[u8],
+        data: &[u8],
+        aad: Option<&[u8]>,
+        nonce: &[u8],
+    ) -> Result<Vec<u8>, CompositeError> {
+        CipherSuiteProvider::aead_seal(self, key, data, aad, nonce)
+            .await
+            .map_err(provider_error)
+    }
+
+    async fn aead_open(
+        &self,
+        key: &[u8],
+        ciphertext: &[u8],
+        aad: Option<&[u8]>,
+        nonce: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, CompositeError> {
+        CipherSuiteProvider::aead_open(self, key, ciphertext, aad, nonce)
+            .await
+            .map_err(provider_error)
+    }
+
+    fn aead_key_size(&self) -> usize {
+        CipherSuiteProvider::aead_key_size(self)
+    }
+
+    fn aead_nonce_size(&self) -> usize {
+        CipherSuiteProvider::aead_nonce_size(self)
+    }
+
+    async fn kdf_extract(
+        &self,
+        salt: &[u8],
+        ikm: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, CompositeError> {
+        CipherSuiteProvider::kdf_extract(self, salt, ikm)
+            .await
+            .map_err(provider_error)
+    }
+
+    async fn kdf_expand(
+        &self,
+        prk: &[u8],
+        info: &[u8],
+        len: usize,
+    ) -> Result<Zeroizing<Vec<u8>>, CompositeError> {
+        CipherSuiteProvider::kdf_expand(self, prk, info, len)
+            .await
+            .map_err(provider_error)
+    }
+
+    fn kdf_extract_size(&self) -> usize {
+        CipherSuiteProvider::kdf_extract_size(self)
+    }
+
+    async fn hpke_seal(
+        &self,
+        remote_key: &HpkePublicKey,
+        info: &[u8],
+        aad: Option<&[u8]>,
+        pt: &[u8],
+    ) -> Result<HpkeCiphertext, CompositeError> {
+        CipherSuiteProvider::hpke_seal(self, remote_key, info, aad, pt)
+            .await
+            .map_err(provider_error)
+    }
+
+    async fn hpke_open(
+        &self,
+        ciphertext: &HpkeCiphertext,
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        info: &[u8],
+        aad: Option<&[u8]>,
+    ) -> Result<Vec<u8>, CompositeError> {
+        CipherSuiteProvider::hpke_open(self, ciphertext, local_secret, local_public, info, aad)
+            .await
+            .map_err(provider_error)
+    }
+
+    async fn hpke_setup_s(
+        &self,
+        remote_key: &HpkePublicKey,
+        info: &[u8],
+    ) -> Result<(Vec<u8>, DynHpkeContextS), CompositeError> {
+        let (kem_output, ctx) = CipherSuiteProvider::hpke_setup_s(self, remote_key, info)
+            .await
+            .map_err(provider_error)?;
+
+        Ok((kem_output, DynHpkeContextS(Box::new(ctx))))
+    }
+
+    async fn hpke_setup_r(
+        &self,
+        kem_output: &[u8],
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        info: &[u8],
+    ) -> Result<DynHpkeContextR, CompositeError> {
+        CipherSuiteProvider::hpke_setup_r(self, kem_output, local_secret, local_public, info)
+            .await
+            .map(|ctx| DynHpkeContextR(Box::new(ctx)))
+            .map_err(provider_error)
+    }
+
+    async fn kem_derive(
+        &self,
+        ikm: &[u8],
+    ) -> Result<(HpkeSecretKey, HpkePublicKey), CompositeError> {
+        CipherSuiteProvider::kem_derive(self, ikm)
+            .await
+            .map_err(provider_error)
+    }
+
+    async fn kem_generate(&self) -> Result<(HpkeSecretKey, HpkePublicKey), CompositeError> {
+        CipherSuiteProvider::kem_generate(self)
+            .await
+            .map_err(provider_error)
+    }
+
+    fn kem_public_key_validate(&self, key: &HpkePublicKey) -> Result<(), CompositeError> {
+        CipherSuiteProvider::kem_public_key_validate(self, key).map_err(provider_error)
+    }
+
+    fn random_bytes(&self, out: &mut [u8]) -> Result<(), CompositeError> {
+        CipherSuiteProvider::random_bytes(self, out).map_err(provider_error)
+    }
+
+    async fn signature_key_generate(
+        &self,
+    ) -> Result<(SignatureSecretKey, SignaturePublicKey), CompositeError> {
+        CipherSuiteProvider::signature_key_generate(self)
+            .await
+            .map_err(provider_error)
+    }
+
+    async fn signature_key_derive_public(
+        &self,
+        secret_key: &SignatureSecretKey,
+    ) -> Result<SignaturePublicKey, CompositeError> {
+        CipherSuiteProvider::signature_key_derive_public(self, secret_key)
+            .await
+            .map_err(provider_error)
+    }
+
+    async fn sign(
+        &self,
+        secret_key: &SignatureSecretKey,
+        data: &[u8],
+    ) -> Result<Vec<u8>, CompositeError> {
+        CipherSuiteProvider::sign(self, secret_key, data)
+            .await
+            .map_err(provider_error)
+    }
+
+    async fn verify(
+        &self,
+        public_key: &SignaturePublicKey,
+        signature: &[u8],
+        data: &[u8],
+    ) -> Result<(), CompositeError> {
+        CipherSuiteProvider::verify(self, public_key, signature, data)
+            .await
+            .map_err(provider_error)
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+impl CipherSuiteProvider for DynCipherSuiteProvider {
+    type Error = CompositeError;
+    type HpkeContextS = DynHpkeContextS;
+    type HpkeContextR = DynHpkeContextR;
+
+    fn cipher_suite(&self) -> CipherSuite {
+        self.inner.cipher_suite()
+    }
+
+    async fn hash(&self, data: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        self.inner.hash(data).await
+    }
+
+    async fn mac(&self, key: &[u8], data: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        self.inner.mac(key, data).await
+    }
+
+    async fn aead_seal(
+        &self,
+        key: &[u8],
+        data: &[u8],
+        aad: Option<&[u8]>,
+        nonce: &[u8],
+    ) -> Result<Vec<u8>, Self::Error> {
+        self.inner.aead_seal(key, data, aad, nonce).await
+    }
+
+    async fn aead_open(
+        &self,
+        key: &[u8],
+        ciphertext: &[u8],
+        aad: Option<&[u8]>,
+        nonce: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, Self::Error> {
+        self.inner.aead_open(key, ciphertext, aad, nonce).await
+    }
+
+    fn aead_key_size(&self) -> usize {
+        self.inner.aead_key_size()
+    }
+
+    fn aead_nonce_size(&self) -> usize {
+        self.inner.aead_nonce_size()
+    }
+
+    async fn kdf_extract(
+        &self,
+        salt: &[u8],
+        ikm: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, Self::Error> {
+        self.inner.kdf_extract(salt, ikm).await
+    }
+
+    async fn kdf_expand(
+        &self,
+        prk: &[u8],
+        info: &[u8],
+        len: usize,
+    ) -> Result<Zeroizing<Vec<u8>>, Self::Error> {
+        self.inner.kdf_expand(prk, info, len).await
+    }
+
+    fn kdf_extract_size(&self) -> usize {
+        self.inner.kdf_extract_size()
+    }
+
+    async fn hpke_seal(
+        &self,
+        remote_key: &HpkePublicKey,
+        info: &[u8],
+        aad: Option<&[u8]>,
+        pt: &[u8],
+    ) -> Result<HpkeCiphertext, Self::Error> {
+        self.inner.hpke_seal(remote_key, info, aad, pt).await
+    }
+
+    async fn hpke_open(
+        &self,
+        ciphertext: &HpkeCiphertext,
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        info: &[u8],
+        aad: Option<&[u8]>,
+    ) -> Result<Vec<u8>, Self::Error> {
+        self.inner
+            .hpke_open(ciphertext, local_secret, local_public, info, aad)
+            .await
+    }
+
+    async fn hpke_setup_s(
+        &self,
+        remote_key: &HpkePublicKey,
+        info: &[u8],
+    ) -> Result<(Vec<u8>, Self::HpkeContextS), Self::Error> {
+        self.inner.hpke_setup_s(remote_key, info).await
+    }
+
+    async fn hpke_setup_r(
+        &self,
+        kem_output: &[u8],
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        info: &[u8],
+    ) -> Result<Self::HpkeContextR, Self::Error> {
+        self.inner
+            .hpke_setup_r(kem_output, local_secret, local_public, info)
+            .await
+    }
+
+    async fn kem_derive(&self, ikm: &[u8]) -> Result<(HpkeSecretKey, HpkePublicKey), Self::Error> {
+        self.inner.kem_derive(ikm).await
+    }
+
+    async fn kem_generate(&self) -> Result<(HpkeSecretKey, HpkePublicKey), Self::Error> {
+        self.inner.kem_generate().await
+    }
+
+    fn kem_public_key_validate(&self, key: &HpkePublicKey) -> Result<(), Self::Error> {
+        self.inner.kem_public_key_validate(key)
+    }
+
+    fn random_bytes(&self, out: &mut [u8]) -> Result<(), Self::Error> {
+        self.inner.random_bytes(out)
+    }
+
+    async fn signature_key_generate(
+        &self,
+    ) -> Result<(SignatureSecretKey, SignaturePublicKey), Self::Error> {
+        self.inner.signature_key_generate().await
+    }
+
+    async fn signature_key_derive_public(
+        &self,
+        secret_key: &SignatureSecretKey,
+    ) -> Result<SignaturePublicKey, Self::Error> {
+        self.inner.signature_key_derive_public(secret_key).await
+    }
+
+    async fn sign(
+        &self,
+        secret_key: &SignatureSecretKey,
+        data: &[u8],
+    ) -> Result<Vec<u8>, Self::Error> {
+        self.inner.sign(secret_key, data).await
+    }
+
+    async fn verify(
+        &self,
+        public_key: &SignaturePublicKey,
+        signature: &[u8],
+        data: &[u8],
+    ) -> Result<(), Self::Error> {
+        self.inner.verify(public_key, signature, data).await
+    }
+}
+
+/// A [`CryptoProvider`] serving a fixed set of registered cipher suites.
+///
+/// Cipher suites are looked up by the codepoint returned from
+/// [`CipherSuiteProvider::cipher_suite`]. They may come from different
+/// backends, e.g. a [`CompositeCipherSuite`] next to the monolithic cipher
+/// suite of an existing provider. Registering a second cipher suite with the
+/// same codepoint replaces the first one.
+#[derive(Clone, Debug, Default)]
+pub struct CompositeCryptoProvider {
+    cipher_suites: Vec<DynCipherSuiteProvider>,
+}
+
+impl CompositeCryptoProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `cipher_suite_provider` under its own cipher suite codepoint.
+    pub fn with_cipher_suite<CS>(mut self, cipher_suite_provider: CS) -> Self
+    where
+        CS: CipherSuiteProvider + 'static,
+        CS::HpkeContextS: 'static,
+        CS::HpkeContextR: 'static,
+    {
+        let cipher_suite_provider = DynCipherSuiteProvider::new(cipher_suite_provider);
+        let cipher_suite = cipher_suite_provider.inner.cipher_suite();
+
+        self.cipher_suites
+            .retain(|cs| cs.inner.cipher_suite() != cipher_suite);
+
+        self.cipher_suites.push(cipher_suite_provider);
+        self
+    }
+}
+
+impl CryptoProvider for CompositeCryptoProvider {
+    type CipherSuiteProvider = DynCipherSuiteProvider;
+
+    fn supported_cipher_suites(&self) -> Vec<CipherSuite> {
+        self.cipher_suites
+            .iter()
+            .map(|cs| cs.inner.cipher_suite())
+            .collect()
+    }
+
+    fn cipher_suite_provider(
+        &self,
+        cipher_suite: CipherSuite,
+    ) -> Option<Self::CipherSuiteProvider> {
+        self.cipher_suites
+            .iter()
+            .find(|cs| cs.inner.cipher_suite() == cipher_suite)
+            .cloned()
+    }
+}
+
+#[cfg(all(test, not(mls_build_async)))]
+mod tests {
+    use alloc::vec::Vec;
+    use assert_matches::assert_matches;
+    use mls_rs_core::crypto::{
+        CipherSuite, CipherSuiteProvider, CryptoProvider, HpkePublicKey, HpkeSecretKey,
+    };
+    use mls_rs_crypto_traits::{
+        mock::{MockHashType, MockRngType, MockSignatureType, TestError},
+        AeadId, AeadType, KdfId, KdfType, KemId, KemResult, KemType, SeededKemType,
+    };
+
+    use super::{
+        CompositeCipherSuite, CompositeCipherSuiteBuilder, CompositeCryptoProvider, CompositeError,
+        DynCipherSuiteProvider,
+    };
+
+    const CUSTOM: CipherSuite = CipherSuite::new(0xf0a0);
+
+    #[derive(Clone)]
+    struct TestKem(u16);
+
+    impl KemType for TestKem {
+        type Error = TestError;
+
+        fn kem_id(&self) -> u16 {
+            self.0
+        }
+
+        fn derive(&self, ikm: &[u8]) -> Result<(HpkeSecretKey, HpkePublicKey), TestError> {
+            Ok((ikm.to_vec().into(), ikm.to_vec().into()))
+        }
+
+        fn generate(&self) -> Result<(HpkeSecretKey, HpkePublicKey), TestError> {
+            Err(TestError {})
+        }
+
+        fn public_key_validate(&self, _key: &HpkePublicKey) -> Result<(), TestError> {
+            Ok(())
+        }
+
+        fn encap(&self, _remote_key: &HpkePublicKey) -> Result<KemResult, TestError> {
+            Err(TestError {})
+        }
+
+        fn decap(
+            &self,
+            _enc: &[u8],
+            _secret_key: &HpkeSecretKey,
+            _local_public: &HpkePublicKey,
+        ) -> Result<Vec<u8>, TestError> {
+            Err(TestError {})
+        }
+    }
+
+    impl SeededKemType for TestKem {
+        fn seed_len(&self) -> usize {
+            4
+        }
+
+        fn encap_seeded(
+            &self,
+            _remote_key: &HpkePublicKey,
+            _seed: &[u8],
+        ) -> Result<KemResult, TestError> {
+            Err(TestError {})
+        }
+    }
+
+    #[derive(Clone)]
+    struct TestKdf(u16);
+
+    impl KdfType for TestKdf {
+        type Error = TestError;
+
+        fn kdf_id(&self) -> u16 {
+            self.0
+        }
+
+        fn expand(&self, _prk: &[u8], _info: &[u8], len: usize) -> Result<Vec<u8>, TestError> {
+            Ok(alloc::vec![0; len])
+        }
+
+        fn extract(&self, _salt: &[u8], ikm: &[u8]) -> Result<Vec<u8>, TestError> {
+            Ok(ikm.to_vec())
+        }
+
+        fn extract_size(&self) -> usize {
+            32
+        }
+    }
+
+    #[derive(Clone)]
+    struct TestAead(u16);
+
+    impl AeadType for TestAead {
+        type Error = TestError;
+
+        fn aead_id(&self) -> u16 {
+            self.0
+        }
+
+        fn seal(
+            &self,
+            _key: &[u8],
+            data: &[u8],
+            _aad: Option<&[u8]>,
+            _nonce: &[u8],
+        ) -> Result<Vec<u8>, TestError> {
+            Ok(data.to_vec())
+        }
+
+        fn open(
+            &self,
+            _key: &[u8],
+            ciphertext: &[u8],
+            _aad: Option<&[u8]>,
+            _nonce: &[u8],
+        ) -> Result<Vec<u8>, TestError> {
+            Ok(ciphertext.to_vec())
+        }
+
+        fn key_size(&self) -> usize {
+            16
+        }
+
+        fn nonce_size(&self) -> usize {
+            12
+        }
+    }
+
+    type TestCipherSuite = CompositeCipherSuite<
+        TestKem,
+        TestKdf,
+        TestAead,
+        MockHashType,
+        MockSignatureType,
+        MockRngType,
+    >;
+
+    fn builder(
+        cipher_suite: CipherSuite,
+        kem: TestKem,
+        kdf: TestKdf,
+        aead: TestAead,
+    ) -> CompositeCipherSuiteBuilder<
+        TestKem,
+        TestKdf,
+        TestAead,
+        MockHashType,
+        MockSignatureType,
+        MockRngType,
+    > {
+        CompositeCipherSuiteBuilder::new(cipher_suite)
+            .kem(kem)
+            .kdf(kdf)
+            .aead(aead)
+            .hash(MockHashType::new())
+            .signature(MockSignatureType::new())
+            .rng(MockRngType::new())
+    }
+
+    fn standard_primitives(cipher_suite: CipherSuite) -> (TestKem, TestKdf, TestAead) {
+        (
+            TestKem(KemId::new(cipher_suite).unwrap() as u16),
+            TestKdf(KdfId::new(cipher_suite).unwrap() as u16),
+            TestAead(AeadId::new(cipher_suite).unwrap() as u16),
+        )
+    }
+
+    fn test_cipher_suite(cipher_suite: CipherSuite) -> TestCipherSuite {
+        let (kem, kdf, aead) = standard_primitives(CipherSuite::CURVE25519_AES128);
+        builder(cipher_suite, kem, kdf, aead).build().unwrap()
+    }
+
+    #[test]
+    fn standard_cipher_suite_accepts_matching_primitives() {
+        let cs = CipherSuite::P384_AES256;
+        let (kem, kdf, aead) = standard_primitives(cs);
+        let suite = builder(cs, kem, kdf, aead).build().unwrap();
+
+        assert_eq!(suite.cipher_suite(), cs);
+        assert_eq!(suite.kdf_extract_size(), 32);
+        assert_eq!(suite.aead_key_size(), 16);
+        assert_eq!(suite.aead_nonce_size(), 12);
+    }
+
+    #[test]
+    fn standard_cipher_suite_rejects_mismatched_primitives() {
+        let cs = CipherSuite::CURVE25519_AES128;
+        let other = CipherSuite::P521_AES256;
+
+        let (kem, kdf, aead) = standard_primitives(cs);
+        let (other_kem, other_kdf, other_aead) = standard_primitives(other);
+
+        let mismatched = [
+            builder(cs, other_kem, kdf.clone(), aead.clone()),
+            builder(cs, kem.clone(), other_kdf, aead),
+            builder(cs, kem, kdf, other_aead),
+        ];
+
+        for builder in mismatched {
+            assert_matches!(
+                builder.build().err(),
+                Some(CompositeError::CipherSuiteMismatch(c)) if c == cs
+            );
+        }
+    }
+
+    #[test]
+    fn custom_cipher_suite_accepts_any_primitives() {
+        let suite = builder(CUSTOM, TestKem(0xffff), TestKdf(0xfffe), TestAead(0xfffd))
+            .build()
+            .unwrap();
+
+        assert_eq!(suite.cipher_suite(), CUSTOM);
+    }
+
+    #[test]
+    fn cipher_suite_delegates_to_primitives() {
+        let (kem, kdf, aead) = standard_primitives(CipherSuite::CURVE25519_AES128);
+        let mut hash = MockHashType::new();
+        let mut rng = MockRngType::new();
+
+        hash.expect_hash()
+            .withf(|data| data == b"data")
+            .return_once(|_| Ok(b"hash".to_vec()));
+
+        hash.expect_mac().return_once(|_, _| Err(TestError {}));
+
+        rng.expect_random_bytes().returning(|out| {
+            out.fill(7);
+            Ok(())
+        });
+
+        let suite = CompositeCipherSuiteBuilder::new(CUSTOM)
+            .kem(kem)
+            .kdf(kdf)
+            .aead(aead)
+            .hash(hash)
+            .signature(MockSignatureType::new())
+            .rng(rng)
+            .build()
+            .unwrap();
+
+        assert_eq!(suite.hash(b"data").unwrap(), b"hash");
+        assert_matches!(
+            suite.mac(b"key", b"data"),
+            Err(CompositeError::HashError(_))
+        );
+
+        // Fresh KEM keys are derived from the configured RNG.
+        let (secret, public) = suite.kem_generate().unwrap();
+        assert_eq!(secret.as_ref(), [7; 4]);
+        assert_eq!(public.as_ref(), [7; 4]);
+
+        // Type erasure keeps the behavior and maps errors to provider errors.
+        let erased = DynCipherSuiteProvider::new(suite);
+
+        assert_eq!(erased.cipher_suite(), CUSTOM);
+        assert_eq!(erased.kem_generate().unwrap().1.as_ref(), [7; 4]);
+
+        assert_matches!(
+            erased.hpke_seal(&public, b"info", None, b"pt"),
+            Err(CompositeError::ProviderError(_))
+        );
+    }
+
+    #[test]
+    fn provider_looks_up_cipher_suites_by_codepoint() {
+        let other = CipherSuite::new(0xf0a1);
+
+        let provider = CompositeCryptoProvider::new()
+            .with_cipher_suite(test_cipher_suite(CUSTOM))
+            .with_cipher_suite(test_cipher_suite(other));
+
+        assert_eq!(provider.supported_cipher_suites(), [CUSTOM, other]);
+
+        assert_eq!(
+            provider
+                .cipher_suite_provider(other)
+                .unwrap()
+                .cipher_suite(),
+            other
+        );
+
+        assert!(provider
+            .cipher_suite_provider(CipherSuite::CURVE25519_AES128)
+            .is_none());
+    }
+
+    #[test]
+    fn provider_replaces_cipher_suites_with_same_codepoint() {
+        let (kem, kdf, aead) = standard_primitives(CipherSuite::CURVE25519_AES128);
+        let mut hash = MockHashType::new();
+        hash.expect_hash().return_once(|_| Ok(b"second".to_vec()));
+
+        let second = CompositeCipherSuiteBuilder::new(CUSTOM)
+            .kem(kem)
+            .kdf(kdf)
+            .aead(aead)
+            .hash(hash)
+            .signature(MockSignatureType::new())
+            .rng(MockRngType::new())
+            .build()
+            .unwrap();
+
+        let provider = CompositeCryptoProvider::new()
+            .with_cipher_suite(test_cipher_suite(CUSTOM))
+            .with_cipher_suite(second);
+
+        assert_eq!(provider.supported_cipher_suites(), [CUSTOM]);
+
+        let cs = provider.cipher_suite_provider(CUSTOM).unwrap();
+        assert_eq!(cs.hash(b"data").unwrap(), b"second");
+    }
+}
@@ -2,7 +2,9 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use mls_rs_crypto_traits::{DhType, KdfType, KemResult, KemType};
+use mls_rs_crypto_traits::{
+    AuthKemType, DhType, KdfType, KemResult, KemType, SeededAuthKemType, SeededKemType,
+};
 
 use mls_rs_core::{
     crypto::{HpkePublicKey, HpkeSecretKey},
@@ -102,24 +104,8 @@ impl<DH: DhType, KDF: KdfType> KemType for DhKem<DH, KDF> {
     }
 
     async fn encap(&self, remote_pk: &HpkePublicKey) -> Result<KemResult, Self::Error> {
-        let (ephemeral_sk, ephemeral_pk) = self.generate().await?;
-
-        let ecdh_ss = self
-            .dh
-            .dh(&ephemeral_sk, remote_pk)
-            .await
-            .map(Zeroizing::new)
-            .map_err(|e| DhKemError::DhError(e.into_any_error()))?;
-
-        let kem_context = [ephemeral_pk.as_ref(), remote_pk.as_ref()].concat();
-
-        let shared_secret = self
-            .kdf
-            .labeled_extract_then_expand(&ecdh_ss, &kem_context, self.n_secret)
-            .await
-            .map_err(|e| DhKemError::KdfError(e.into_any_error()))?;
-
-        Ok(KemResult::new(shared_secret, ephemeral_pk.into()))
+        let ephemeral = self.generate().await?;
+        self.encap_with_ephemeral(remote_pk, ephemeral).await
     }
 
     async fn decap(
@@ -152,7 +138,149 @@ impl<DH: DhType, KDF: KdfType> KemType for DhKem<DH, KDF> {
     }
 }
 
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+impl<DH: DhType, KDF: KdfType> AuthKemType for DhKem<DH, KDF> {
+    async fn auth_encap(
+        &self,
+        remote_pk: &HpkePublicKey,
+        sender_sk: &HpkeSecretKey,
+        sender_pk: &HpkePublicKey,
+    ) -> Result<KemResult, Self::Error> {
+        let ephemeral = self.generate().await?;
+
+        self.auth_encap_with_ephemeral(remote_pk, sender_sk, sender_pk, ephemeral)
+            .await
+    }
+
+    async fn auth_decap(
+        &self,
+        enc: &[u8],
+        secret_key: &HpkeSecretKey,
+        public_key: &HpkePublicKey,
+        sender_pk: &HpkePublicKey,
+    ) -> Result<Vec<u8>, Self::Error> {
+        let remote_pk = enc.to_vec().into();
+
+        let dh_e = self.dh_secret(secret_key, &remote_pk).await?;
+        let dh_s = self.dh_secret(secret_key, sender_pk).await?;
+        let ecdh_ss = Zeroizing::new([dh_e.as_slice(), &dh_s].concat());
+
+        let kem_context = [enc, public_key, sender_pk].concat();
+
+        self.kdf
+            .labeled_extract_then_expand(&ecdh_ss, &kem_context, self.n_secret)
+            .await
+            .map_err(|e| DhKemError::KdfError(e.into_any_error()))
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+impl<DH: DhType, KDF: KdfType> SeededKemType for DhKem<DH, KDF> {
+    fn seed_len(&self) -> usize {
+        self.dh.secret_key_size()
+    }
+
+    async fn encap_seeded(
+        &self,
+        remote_pk: &HpkePublicKey,
+        seed: &[u8],
+    ) -> Result<KemResult, Self::Error> {
+        let ephemeral = self.derive(seed).await?;
+        self.encap_with_ephemeral(remote_pk, ephemeral).await
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+impl<DH: DhType, KDF: KdfType> SeededAuthKemType for DhKem<DH, KDF> {
+    async fn auth_encap_seeded(
+        &self,
+        remote_pk: &HpkePublicKey,
+        sender_sk: &HpkeSecretKey,
+        sender_pk: &HpkePublicKey,
+        seed: &[u8],
+    ) -> Result<KemResult, Self::Error> {
+        let ephemeral = self.derive(seed).await?;
+
+        self.auth_encap_with_ephemeral(remote_pk, sender_sk, sender_pk, ephemeral)
+            .await
+    }
+}
+
 impl<DH: DhType, KDF: KdfType> DhKem<DH, KDF> {
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn encap_with_ephemeral(
+        &self,
+        remote_pk: &HpkePublicKey,
+        (ephemeral_sk, ephemeral_pk): (HpkeSecretKey, HpkePublicKey),
+    ) -> Result<KemResult, DhKemError> {
+        let ecdh_ss = self.dh_secret(&ephemeral_sk, remote_pk).await?;
+        let kem_context = [ephemeral_pk.as_ref(), remote_pk.as_ref()].concat();
+
+        let shared_secret = self
+            .kdf
+            .labeled_extract_then_expand(&ecdh_ss, &kem_context, self.n_secret)
+            .await
+            .map_err(|e| DhKemError::KdfError(e.into_any_error()))?;
+
+        Ok(KemResult::new(shared_secret, ephemeral_pk.into()))
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn auth_encap_with_ephemeral(
+        &self,
+        remote_pk: &HpkePublicKey,
+        sender_sk: &HpkeSecretKey,
+        sender_pk: &HpkePublicKey,
+        (ephemeral_sk, ephemeral_pk): (HpkeSecretKey, HpkePublicKey),
+    ) -> Result<KemResult, DhKemError> {
+        let dh_e = self.dh_secret(&ephemeral_sk, remote_pk).await?;
+        let dh_s = self.dh_secret(sender_sk, remote_pk).await?;
+        let ecdh_ss = Zeroizing::new([dh_e.as_slice(), &dh_s].concat());
+
+        let kem_context = [
+            ephemeral_pk.as_ref(),
+            remote_pk.as_ref(),
+            sender_pk.as_ref(),
+        ]
+        .concat();
+
+        let shared_secret = self
+            .kdf
+            .labeled_extract_then_expand(&ecdh_ss, &kem_context, self.n_secret)
+            .await
+            .map_err(|e| DhKemError::KdfError(e.into_any_error()))?;
+
+        Ok(KemResult::new(shared_secret, ephemeral_pk.into()))
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn dh_secret(
+        &self,
+        secret_key: &HpkeSecretKey,
+        public_key: &HpkePublicKey,
+    ) -> Result<Zeroizing<Vec<u8>>, DhKemError> {
+        self.dh
+            .dh(secret_key, public_key)
+            .await
+            .map(Zeroizing::new)
+            .map_err(|e| DhKemError::DhError(e.into_any_error()))
+    }
+
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     async fn derive_with_rejection_sampling(
         &self,
@@ -8,12 +8,16 @@ use crate::alloc::borrow::ToOwned;
 
 use mls_rs_core::{
     crypto::{
-        HpkeCiphertext, HpkeContextR, HpkeContextS, HpkeModeId, HpkePublicKey, HpkeSecretKey,
+        HpkeCiphertext, HpkeContextR, HpkeContextS, HpkeModeId, HpkePsk, HpkePublicKey,
+        HpkeSecretKey,
     },
     error::{AnyError, IntoAnyError},
 };
 
-use mls_rs_crypto_traits::{AeadType, KdfType, KemType, AEAD_ID_EXPORT_ONLY};
+use mls_rs_crypto_traits::{
+    AeadType, AuthKemType, KdfType, KemResult, KemType, SeededAuthKemType, SeededKemType,
+    AEAD_ID_EXPORT_ONLY,
+};
 
 use zeroize::Zeroizing;
 
@@ -84,6 +88,25 @@ impl<'a> Psk<'a> {
     }
 }
 
+impl<'a> From<HpkePsk<'a>> for Psk<'a> {
+    fn from(psk: HpkePsk<'a>) -> Self {
+        Self::new(psk.id, psk.value)
+    }
+}
+
+/// Key pair of the sender used to authenticate in the Auth and AuthPSK modes.
+#[derive(Clone, Copy)]
+pub struct SenderKeyPair<'a> {
+    secret: &'a HpkeSecretKey,
+    public: &'a HpkePublicKey,
+}
+
+impl<'a> SenderKeyPair<'a> {
+    pub fn new(secret: &'a HpkeSecretKey, public: &'a HpkePublicKey) -> Self {
+        Self { secret, public }
+    }
+}
+
 impl<KEM, KDF, AEAD> Hpke<KEM, KDF, AEAD>
 where
     KEM: KemType,
@@ -166,7 +189,7 @@ where
         info: &[u8],
         psk: Option<Psk<'_>>,
     ) -> Result<(Vec<u8>, ContextS<KDF, AEAD>), HpkeError> {
-        let mode = self.base_mode(&psk);
+        let mode = self.mode(&psk, false);
 
         let kem_res = self
             .kem
@@ -174,11 +197,7 @@ where
             .await
             .map_err(|e| HpkeError::KemError(e.into_any_error()))?;
 
-        let ctx = self
-            .key_schedule(mode, kem_res.shared_secret(), info, psk)
-            .await?;
-
-        Ok((kem_res.enc().to_owned(), ContextS(ctx)))
+        self.sender_context(mode, kem_res, info, psk).await
     }
 
     /// Set up an HPKE context by receiving an `enc` value from the output of
@@ -195,7 +214,7 @@ where
         info: &[u8],
         psk: Option<Psk<'_>>,
     ) -> Result<ContextR<KDF, AEAD>, HpkeError> {
-        let mode = self.base_mode(&psk);
+        let mode = self.mode(&psk, false);
 
         let shared_secret = self
             .kem
@@ -230,6 +249,21 @@ where
             .map_err(|e| HpkeError::KemError(e.into_any_error()))
     }
 
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn sender_context(
+        &self,
+        mode: HpkeModeId,
+        kem_res: KemResult,
+        info: &[u8],
+        psk: Option<Psk<'_>>,
+    ) -> Result<(Vec<u8>, ContextS<KDF, AEAD>), HpkeError> {
+        let ctx = self
+            .key_schedule(mode, kem_res.shared_secret(), info, psk)
+            .await?;
+
+        Ok((kem_res.enc().to_owned(), ContextS(ctx)))
+    }
+
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     async fn key_schedule(
         &self,
@@ -317,11 +351,224 @@ where
     }
 
     #[inline(always)]
-    fn base_mode(&self, psk: &Option<Psk>) -> HpkeModeId {
-        if psk.is_some() {
-            HpkeModeId::Psk
-        } else {
-            HpkeModeId::Base
+    fn mode(&self, psk: &Option<Psk>, auth: bool) -> HpkeModeId {
+        match (psk.is_some(), auth) {
+            (false, false) => HpkeModeId::Base,
+            (true, false) => HpkeModeId::Psk,
+            (false, true) => HpkeModeId::Auth,
+            (true, true) => HpkeModeId::AuthPsk,
         }
     }
 }
+
+impl<KEM, KDF, AEAD> Hpke<KEM, KDF, AEAD>
+where
+    KEM: AuthKemType,
+    KDF: KdfType + Clone,
+    AEAD: AeadType + Clone,
+{
+    /// Based on RFC 9180 Single-Shot APIs. This function combines the action
+    /// of the [setup_auth_sender](Hpke::setup_auth_sender) and then calling
+    /// [seal](ContextS::seal) on the resulting [ContextS](self::ContextS).
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn seal_auth(
+        &self,
+        remote_key: &HpkePublicKey,
+        sender: SenderKeyPair<'_>,
+        info: &[u8],
+        psk: Option<Psk<'_>>,
+        aad: Option<&[u8]>,
+        pt: &[u8],
+    ) -> Result<HpkeCiphertext, HpkeError> {
+        let (kem_output, mut ctx) = self
+            .setup_auth_sender(remote_key, sender, info, psk)
+            .await?;
+
+        Ok(HpkeCiphertext {
+            kem_output,
+            ciphertext: ctx.seal(aad, pt).await?,
+        })
+    }
+
+    /// Based on RFC 9180 Single-Shot APIs. This function combines the action
+    /// of the [setup_auth_receiver](Hpke::setup_auth_receiver) and then calling
+    /// [open](ContextR::open) on the resulting [ContextR](self::ContextR).
+    #[allow(clippy::too_many_arguments)]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn open_auth(
+        &self,
+        ciphertext: &HpkeCiphertext,
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<Psk<'_>>,
+        aad: Option<&[u8]>,
+    ) -> Result<Vec<u8>, HpkeError> {
+        let mut hpke_ctx = self
+            .setup_auth_receiver(
+                &ciphertext.kem_output,
+                local_secret,
+                local_public,
+                sender_public,
+                info,
+                psk,
+            )
+            .await?;
+
+        hpke_ctx.open(aad, &ciphertext.ciphertext).await
+    }
+
+    /// Generate an HPKE context using the Auth setup mode, or the AuthPSK mode
+    /// if `psk` is provided. The receiver can verify that the context was
+    /// created by the holder of the `sender` secret key. Otherwise, this
+    /// function behaves like [setup_sender](Hpke::setup_sender).
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn setup_auth_sender(
+        &self,
+        remote_key: &HpkePublicKey,
+        sender: SenderKeyPair<'_>,
+        info: &[u8],
+        psk: Option<Psk<'_>>,
+    ) -> Result<(Vec<u8>, ContextS<KDF, AEAD>), HpkeError> {
+        let mode = self.mode(&psk, true);
+
+        let kem_res = self
+            .kem
+            .auth_encap(remote_key, sender.secret, sender.public)
+            .await
+            .map_err(|e| HpkeError::KemError(e.into_any_error()))?;
+
+        self.sender_context(mode, kem_res, info, psk).await
+    }
+
+    /// Set up an HPKE context from the `enc` value output by
+    /// [setup_auth_sender](Hpke::setup_auth_sender). If `sender_public` does not
+    /// match the sender key pair, the resulting context fails to decrypt.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn setup_auth_receiver(
+        &self,
+        enc: &[u8],
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<Psk<'_>>,
+    ) -> Result<ContextR<KDF, AEAD>, HpkeError> {
+        let mode = self.mode(&psk, true);
+
+        let shared_secret = self
+            .kem
+            .auth_decap(enc, local_secret, local_public, sender_public)
+            .await
+            .map_err(|e| HpkeError::KemError(e.into_any_error()))?;
+
+        self.key_schedule(mode, &shared_secret, info, psk)
+            .await
+            .map(ContextR)
+    }
+}
+
+impl<KEM, KDF, AEAD> Hpke<KEM, KDF, AEAD>
+where
+    KEM: SeededKemType,
+    KDF: KdfType + Clone,
+    AEAD: AeadType + Clone,
+{
+    /// Same as [seal](Hpke::seal), with the ephemeral key pair derived from
+    /// `seed` as in [setup_sender_seeded](Hpke::setup_sender_seeded).
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn seal_seeded(
+        &self,
+        remote_key: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<Psk<'_>>,
+        aad: Option<&[u8]>,
+        pt: &[u8],
+        seed: &[u8],
+    ) -> Result<HpkeCiphertext, HpkeError> {
+        let (kem_output, mut ctx) = self
+            .setup_sender_seeded(remote_key, info, psk, seed)
+            .await?;
+
+        Ok(HpkeCiphertext {
+            kem_output,
+            ciphertext: ctx.seal(aad, pt).await?,
+        })
+    }
+
+    /// Same as [setup_sender](Hpke::setup_sender), except that the ephemeral
+    /// key pair is derived from `seed` of length
+    /// [seed_len](SeededKemType::seed_len) instead of being generated by the KEM.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn setup_sender_seeded(
+        &self,
+        remote_key: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<Psk<'_>>,
+        seed: &[u8],
+    ) -> Result<(Vec<u8>, ContextS<KDF, AEAD>), HpkeError> {
+        let mode = self.mode(&psk, false);
+
+        let kem_res = self
+            .kem
+            .encap_seeded(remote_key, seed)
+            .await
+            .map_err(|e| HpkeError::KemError(e.into_any_error()))?;
+
+        self.sender_context(mode, kem_res, info, psk).await
+    }
+}
+
+impl<KEM, KDF, AEAD> Hpke<KEM, KDF, AEAD>
+where
+    KEM: SeededAuthKemType,
+    KDF: KdfType + Clone,
+    AEAD: AeadType + Clone,
+{
+    /// Same as [seal_auth](Hpke::seal_auth), with the ephemeral key pair
+    /// derived from `seed`.
+    #[allow(clippy::too_many_arguments)]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn seal_auth_seeded(
+        &self,
+        remote_key: &HpkePublicKey,
+        sender: SenderKeyPair<'_>,
+        info: &[u8],
+        psk: Option<Psk<'_>>,
+        aad: Option<&[u8]>,
+        pt: &[u8],
+        seed: &[u8],
+    ) -> Result<HpkeCiphertext, HpkeError> {
+        let (kem_output, mut ctx) = self
+            .setup_auth_sender_seeded(remote_key, sender, info, psk, seed)
+            .await?;
+
+        Ok(HpkeCiphertext {
+            kem_output,
+            ciphertext: ctx.seal(aad, pt).await?,
+        })
+    }
+
+    /// Same as [setup_auth_sender](Hpke::setup_auth_sender), with the
+    /// ephemeral key pair derived from `seed`.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn setup_auth_sender_seeded(
+        &self,
+        remote_key: &HpkePublicKey,
+        sender: SenderKeyPair<'_>,
+        info: &[u8],
+        psk: Option<Psk<'_>>,
+        seed: &[u8],
+    ) -> Result<(Vec<u8>, ContextS<KDF, AEAD>), HpkeError> {
+        let mode = self.mode(&psk, true);
+
+        let kem_res = self
+            .kem
+            .auth_encap_seeded(remote_key, sender.secret, sender.public, seed)
+            .await
+            .map_err(|e| HpkeError::KemError(e.into_any_error()))?;
+
+        self.sender_context(mode, kem_res, info, psk).await
+    }
+}
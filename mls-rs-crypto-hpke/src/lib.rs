@@ -8,6 +8,7 @@ extern crate alloc;
 #[cfg(all(test, target_arch = "wasm32"))]
 wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
 
+pub mod composite;
 pub mod context;
 pub mod dhkem;
 pub mod hpke;
@@ -12,8 +12,11 @@ use crate::{
     hpke::Hpke,
 };
 
-use mls_rs_core::crypto::test_suite::{EncapOutput, TestHpke};
-use mls_rs_crypto_traits::{AeadType, DhType, KdfType, KemResult, KemType};
+use mls_rs_core::crypto::{
+    test_suite::{EncapOutput, TestHpke},
+    HpkePsk,
+};
+use mls_rs_crypto_traits::{AeadType, AuthKemType, DhType, KdfType, KemResult, KemType};
 
 impl<DH: DhType, KDF: KdfType + Clone, AEAD: AeadType + Clone> TestHpke
     for Hpke<DhKem<DH, KDF>, KDF, AEAD>
@@ -49,4 +52,53 @@ impl<DH: DhType, KDF: KdfType + Clone, AEAD: AeadType + Clone> TestHpke
     fn decap(&mut self, enc: Vec<u8>, sk_rm: Vec<u8>, pk_rm: Vec<u8>) -> Vec<u8> {
         self.kem.decap(&enc, &sk_rm.into(), &pk_rm.into()).unwrap()
     }
+
+    fn auth_encap(
+        &mut self,
+        ikm_e: Vec<u8>,
+        pk_rm: Vec<u8>,
+        sk_sm: Vec<u8>,
+        pk_sm: Vec<u8>,
+    ) -> EncapOutput {
+        self.kem.set_test_data(ikm_e);
+
+        let KemResult { enc, shared_secret } = self
+            .kem
+            .auth_encap(&pk_rm.into(), &sk_sm.into(), &pk_sm.into())
+            .unwrap();
+
+        EncapOutput { enc, shared_secret }
+    }
+
+    fn auth_decap(
+        &mut self,
+        enc: Vec<u8>,
+        sk_rm: Vec<u8>,
+        pk_rm: Vec<u8>,
+        pk_sm: Vec<u8>,
+    ) -> Vec<u8> {
+        self.kem
+            .auth_decap(&enc, &sk_rm.into(), &pk_rm.into(), &pk_sm.into())
+            .unwrap()
+    }
+
+    fn setup_receiver(
+        &self,
+        enc: Vec<u8>,
+        sk_rm: Vec<u8>,
+        pk_rm: Vec<u8>,
+        pk_sm: Option<Vec<u8>>,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+    ) -> Self::ContextR {
+        let (sk_rm, pk_rm) = (sk_rm.into(), pk_rm.into());
+        let psk = psk.map(Into::into);
+
+        match pk_sm {
+            Some(pk_sm) => self
+                .setup_auth_receiver(&enc, &sk_rm, &pk_rm, &pk_sm.into(), info, psk)
+                .unwrap(),
+            None => Hpke::setup_receiver(self, &enc, &sk_rm, &pk_rm, info, psk).unwrap(),
+        }
+    }
 }
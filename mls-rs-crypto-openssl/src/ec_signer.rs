@@ -4,8 +4,11 @@
 
 use std::ops::Deref;
 
-use mls_rs_core::crypto::{CipherSuite, SignaturePublicKey, SignatureSecretKey};
-use mls_rs_crypto_traits::Curve;
+use mls_rs_core::{
+    crypto::{CipherSuite, SignaturePublicKey, SignatureSecretKey},
+    error::IntoAnyError,
+};
+use mls_rs_crypto_traits::{Curve, SignatureType};
 use openssl::hash::MessageDigest;
 
 #[cfg(feature = "x509")]
@@ -29,6 +32,12 @@ pub enum EcSignerError {
     InvalidSignature,
 }
 
+impl IntoAnyError for EcSignerError {
+    fn into_dyn_error(self) -> Result<Box<dyn std::error::Error + Send + Sync>, Self> {
+        Ok(self.into())
+    }
+}
+
 #[derive(Clone, Debug, Copy, PartialEq, Eq)]
 pub struct EcSigner(Curve);
 
@@ -145,6 +154,46 @@ impl EcSigner {
     }
 }
 
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+impl SignatureType for EcSigner {
+    type Error = EcSignerError;
+
+    async fn signature_key_generate(
+        &self,
+    ) -> Result<(SignatureSecretKey, SignaturePublicKey), Self::Error> {
+        EcSigner::signature_key_generate(self)
+    }
+
+    async fn signature_key_derive_public(
+        &self,
+        secret_key: &SignatureSecretKey,
+    ) -> Result<SignaturePublicKey, Self::Error> {
+        EcSigner::signature_key_derive_public(self, secret_key)
+    }
+
+    async fn sign(
+        &self,
+        secret_key: &SignatureSecretKey,
+        data: &[u8],
+    ) -> Result<Vec<u8>, Self::Error> {
+        EcSigner::sign(self, secret_key, data)
+    }
+
+    async fn verify(
+        &self,
+        public_key: &SignaturePublicKey,
+        signature: &[u8],
+        data: &[u8],
+    ) -> Result<(), Self::Error> {
+        EcSigner::verify(self, public_key, signature, data)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use mls_rs_crypto_traits::Curve;
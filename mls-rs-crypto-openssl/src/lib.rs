@@ -16,9 +16,9 @@ use aead::Aead;
 use mls_rs_crypto_hpke::{
     context::{ContextR, ContextS},
     dhkem::DhKem,
-    hpke::{Hpke, HpkeError},
+    hpke::{Hpke, HpkeError, SenderKeyPair},
 };
-use mls_rs_crypto_traits::{AeadType, KdfType, KemId, KemType};
+use mls_rs_crypto_traits::{AeadType, AuthKemType, KdfType, KemId, KemType, RngType};
 
 use ec::EcError;
 use ec_signer::{EcSigner, EcSignerError};
@@ -30,8 +30,9 @@ use thiserror::Error;
 
 use mls_rs_core::{
     crypto::{
-        CipherSuite, CipherSuiteProvider, CryptoProvider, HpkeCiphertext, HpkePublicKey,
-        HpkeSecretKey, SignaturePublicKey, SignatureSecretKey,
+        CipherSuite, CipherSuiteProvider, CryptoProvider, HpkeAuthCipherSuiteProvider,
+        HpkeCiphertext, HpkePsk, HpkePublicKey, HpkeSecretKey, SignaturePublicKey,
+        SignatureSecretKey,
     },
     error::{AnyError, IntoAnyError},
 };
@@ -118,6 +119,21 @@ impl CryptoProvider for OpensslCryptoProvider {
     }
 }
 
+/// [`RngType`] backed by the OpenSSL random number generator.
+///
+/// Can be combined with primitives from other providers using
+/// [`CompositeCipherSuiteBuilder`](mls_rs_crypto_hpke::composite::CompositeCipherSuiteBuilder).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OpensslRng;
+
+impl RngType for OpensslRng {
+    type Error = OpensslCryptoError;
+
+    fn random_bytes(&self, out: &mut [u8]) -> Result<(), Self::Error> {
+        Ok(openssl::rand::rand_bytes(out)?)
+    }
+}
+
 #[derive(Clone)]
 pub struct OpensslCipherSuite<KEM, KDF, AEAD>
 where
@@ -360,6 +376,99 @@ where
     }
 }
 
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+impl<KEM, KDF, AEAD> HpkeAuthCipherSuiteProvider for OpensslCipherSuite<KEM, KDF, AEAD>
+where
+    KEM: AuthKemType + Clone + Send + Sync,
+    KDF: KdfType + Clone + Send + Sync,
+    AEAD: AeadType + Clone + Send + Sync,
+{
+    async fn hpke_seal_auth(
+        &self,
+        remote_key: &HpkePublicKey,
+        sender_secret: &HpkeSecretKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+        aad: Option<&[u8]>,
+        pt: &[u8],
+    ) -> Result<HpkeCiphertext, Self::Error> {
+        let sender = SenderKeyPair::new(sender_secret, sender_public);
+
+        Ok(self
+            .hpke
+            .seal_auth(remote_key, sender, info, psk.map(Into::into), aad, pt)
+            .await?)
+    }
+
+    async fn hpke_open_auth(
+        &self,
+        ciphertext: &HpkeCiphertext,
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+        aad: Option<&[u8]>,
+    ) -> Result<Vec<u8>, Self::Error> {
+        Ok(self
+            .hpke
+            .open_auth(
+                ciphertext,
+                local_secret,
+                local_public,
+                sender_public,
+                info,
+                psk.map(Into::into),
+                aad,
+            )
+            .await?)
+    }
+
+    async fn hpke_setup_s_auth(
+        &self,
+        remote_key: &HpkePublicKey,
+        sender_secret: &HpkeSecretKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+    ) -> Result<(Vec<u8>, Self::HpkeContextS), Self::Error> {
+        let sender = SenderKeyPair::new(sender_secret, sender_public);
+
+        Ok(self
+            .hpke
+            .setup_auth_sender(remote_key, sender, info, psk.map(Into::into))
+            .await?)
+    }
+
+    async fn hpke_setup_r_auth(
+        &self,
+        enc: &[u8],
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+    ) -> Result<Self::HpkeContextR, Self::Error> {
+        Ok(self
+            .hpke
+            .setup_auth_receiver(
+                enc,
+                local_secret,
+                local_public,
+                sender_public,
+                info,
+                psk.map(Into::into),
+            )
+            .await?)
+    }
+}
+
 #[cfg(not(mls_build_async))]
 #[test]
 fn mls_core_tests() {
@@ -370,9 +479,103 @@ fn mls_core_tests() {
     mls_rs_core::crypto::test_suite::verify_tests(&provider, true);
 
     for cs in OpensslCryptoProvider::all_supported_cipher_suites() {
-        let mut hpke = provider.cipher_suite_provider(cs).unwrap().hpke;
+        let cipher_suite = provider.cipher_suite_provider(cs).unwrap();
+        mls_rs_core::crypto::test_suite::verify_hpke_auth_tests(&cipher_suite);
+
+        let mut hpke = cipher_suite.hpke;
 
         mls_rs_core::crypto::test_suite::verify_hpke_context_tests(&hpke, cs);
         mls_rs_core::crypto::test_suite::verify_hpke_encap_tests(&mut hpke, cs);
+        mls_rs_core::crypto::test_suite::verify_hpke_setup_tests(&hpke, cs);
+    }
+}
+
+#[cfg(not(mls_build_async))]
+#[test]
+fn composite_provider_mixes_backends() {
+    use mls_rs_core::crypto::{HpkeContextR, HpkeContextS};
+    use mls_rs_crypto_hpke::composite::{CompositeCipherSuiteBuilder, CompositeCryptoProvider};
+    use mls_rs_crypto_rustcrypto::{
+        ec_signer::EcSigner as RustCryptoSigner, ecdh::Ecdh as RustCryptoEcdh,
+        kdf::Kdf as RustCryptoKdf, RustCryptoProvider,
+    };
+
+    let base = CipherSuite::CURVE25519_AES128;
+    let custom = CipherSuite::new(0xf0a2);
+
+    // X25519 and signatures from RustCrypto, AEAD, hash and RNG from OpenSSL.
+    let rustcrypto_kdf = RustCryptoKdf::new(base).unwrap();
+    let kem_id = KemId::new(base).unwrap();
+
+    let kem = DhKem::new(
+        RustCryptoEcdh::new(base).unwrap(),
+        rustcrypto_kdf,
+        kem_id as u16,
+        kem_id.n_secret(),
+    );
+
+    let composite = CompositeCipherSuiteBuilder::new(custom)
+        .kem(kem)
+        .kdf(Kdf::new(base).unwrap())
+        .aead(Aead::new(base).unwrap())
+        .hash(Hash::new(base).unwrap())
+        .signature(RustCryptoSigner::new(base).unwrap())
+        .rng(OpensslRng)
+        .build()
+        .unwrap();
+
+    mls_rs_core::crypto::test_suite::verify_hpke_auth_tests(&composite);
+
+    // Register the composite suite next to complete suites of both backends.
+    let rustcrypto_suite = RustCryptoProvider::new()
+        .cipher_suite_provider(base)
+        .unwrap();
+
+    let openssl_suite = OpensslCryptoProvider::new()
+        .cipher_suite_provider(CipherSuite::P256_AES128)
+        .unwrap();
+
+    let provider = CompositeCryptoProvider::new()
+        .with_cipher_suite(composite)
+        .with_cipher_suite(rustcrypto_suite)
+        .with_cipher_suite(openssl_suite);
+
+    assert_eq!(
+        provider.supported_cipher_suites(),
+        vec![custom, base, CipherSuite::P256_AES128]
+    );
+
+    let composite = provider.cipher_suite_provider(custom).unwrap();
+    let rustcrypto = provider.cipher_suite_provider(base).unwrap();
+
+    let (secret, public) = rustcrypto.kem_generate().unwrap();
+    let ct = composite.hpke_seal(&public, b"info", None, b"pt").unwrap();
+    let pt = rustcrypto.hpke_open(&ct, &secret, &public, b"info", None);
+    assert_eq!(pt.unwrap(), b"pt");
+
+    let (secret, public) = composite.signature_key_generate().unwrap();
+    let signature = composite.sign(&secret, b"data").unwrap();
+    rustcrypto.verify(&public, &signature, b"data").unwrap();
+
+    let key = composite
+        .random_bytes_vec(composite.aead_key_size())
+        .unwrap();
+    let nonce = rustcrypto
+        .random_bytes_vec(rustcrypto.aead_nonce_size())
+        .unwrap();
+    let ct = composite.aead_seal(&key, b"pt", None, &nonce).unwrap();
+    let pt = rustcrypto.aead_open(&key, &ct, None, &nonce).unwrap();
+    assert_eq!(pt.as_slice(), b"pt");
+
+    for cs in provider.supported_cipher_suites() {
+        let cipher_suite = provider.cipher_suite_provider(cs).unwrap();
+        let (secret, public) = cipher_suite.kem_generate().unwrap();
+        let (enc, mut ctx_s) = cipher_suite.hpke_setup_s(&public, b"info").unwrap();
+        let mut ctx_r = cipher_suite
+            .hpke_setup_r(&enc, &secret, &public, b"info")
+            .unwrap();
+
+        let ct = ctx_s.seal(None, b"pt").unwrap();
+        assert_eq!(ctx_r.open(None, &ct).unwrap(), b"pt");
     }
 }
@@ -4,7 +4,8 @@
 
 use std::ops::Deref;
 
-use mls_rs_core::crypto::CipherSuite;
+use mls_rs_core::{crypto::CipherSuite, error::IntoAnyError};
+use mls_rs_crypto_traits::HashType;
 use openssl::{
     hash::{hash, MessageDigest},
     pkey::PKey,
@@ -20,6 +21,12 @@ pub enum HashError {
     UnsupportedCipherSuite,
 }
 
+impl IntoAnyError for HashError {
+    fn into_dyn_error(self) -> Result<Box<dyn std::error::Error + Send + Sync>, Self> {
+        Ok(self.into())
+    }
+}
+
 #[derive(Clone)]
 pub struct Hash(MessageDigest);
 
@@ -57,3 +64,21 @@ impl Hash {
         Ok(signer.sign_oneshot_to_vec(data)?)
     }
 }
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+impl HashType for Hash {
+    type Error = HashError;
+
+    async fn hash(&self, data: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        Hash::hash(self, data)
+    }
+
+    async fn mac(&self, key: &[u8], data: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        Hash::mac(self, key, data)
+    }
+}
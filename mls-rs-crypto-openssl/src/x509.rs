@@ -6,28 +6,33 @@ use std::{net::IpAddr, ops::Deref};
 
 use mls_rs_core::{
     crypto::{CipherSuite, SignaturePublicKey, SignatureSecretKey},
-    error::IntoAnyError,
+    error::{AnyError, IntoAnyError},
     identity::{CertificateChain, SigningIdentity},
 };
 use mls_rs_identity_x509::{
-    CertificateRequestParameters, DerCertificate, DerCertificateRequest, SubjectAltName,
-    SubjectComponent, SubjectIdentityExtractor, X509CredentialValidator, X509IdentityProvider,
+    CertificateRequestParameters, DerCertificate, DerCertificateRequest,
+    DerCertificateRevocationList, DerOcspResponse, SubjectAltName, SubjectComponent,
+    SubjectIdentityExtractor, X509CredentialValidator, X509CrlProvider, X509IdentityProvider,
     X509RequestWriter,
 };
 use openssl::{
+    asn1::Asn1Time,
     bn::BigNumContext,
     ec::PointConversionForm,
     error::ErrorStack,
     hash::MessageDigest,
     nid::Nid,
+    ocsp::{
+        OcspBasicResponse, OcspCertId, OcspCertStatus, OcspFlag, OcspResponse, OcspResponseStatus,
+    },
     pkey::{PKey, PKeyRef, Private, Public},
     stack::{Stack, StackRef},
     x509::{
         extension::{BasicConstraints, KeyUsage, SubjectAlternativeName},
         store::{X509Store, X509StoreBuilder},
         verify::{X509VerifyFlags, X509VerifyParam},
-        X509Builder, X509Extension, X509Name, X509NameBuilder, X509NameRef, X509Ref,
-        X509ReqBuilder, X509StoreContext, X509VerifyResult, X509v3Context, X509,
+        CrlStatus, X509Builder, X509Crl, X509Extension, X509Name, X509NameBuilder, X509NameRef,
+        X509Ref, X509ReqBuilder, X509StoreContext, X509VerifyResult, X509v3Context, X509,
     },
 };
 use thiserror::Error;
@@ -52,6 +57,12 @@ pub enum X509Error {
     EcSignerError(#[from] EcSignerError),
     #[error(transparent)]
     OpensslError(#[from] ErrorStack),
+    #[error("certificate {0} has been revoked")]
+    CertificateRevoked(String),
+    #[error("certificate revocation list issued by {0} is past its next update time")]
+    StaleRevocationList(String),
+    #[error(transparent)]
+    CrlProviderError(AnyError),
 }
 
 impl IntoAnyError for X509Error {
@@ -61,9 +72,10 @@ impl IntoAnyError for X509Error {
 }
 
 #[derive(Debug, Clone)]
-pub struct X509Validator {
+pub struct X509Validator<C = Vec<DerCertificateRevocationList>> {
     root_ca_list: Vec<DerCertificate>,
     use_system_ca: bool,
+    crl_provider: C,
 }
 
 impl X509Validator {
@@ -81,8 +93,25 @@ impl X509Validator {
         Ok(X509Validator {
             root_ca_list,
             use_system_ca: false,
+            crl_provider: Vec::new(),
         })
     }
+}
+
+impl<C> X509Validator<C> {
+    /// Check every certificate of a chain against the revocation lists
+    /// returned by `crl_provider`.
+    ///
+    /// A revocation list is only used if it is signed by the issuer of the
+    /// certificate being checked. Lists that are past their next update time
+    /// cause validation to fail.
+    pub fn with_crl_provider<P: X509CrlProvider>(self, crl_provider: P) -> X509Validator<P> {
+        X509Validator {
+            root_ca_list: self.root_ca_list,
+            use_system_ca: self.use_system_ca,
+            crl_provider,
+        }
+    }
 
     pub fn with_system_ca(self) -> Self {
         Self {
@@ -117,11 +146,22 @@ impl X509Validator {
 
         Ok(builder.build())
     }
+}
 
+impl<C: X509CrlProvider> X509Validator<C> {
     pub fn validate_chain(
         &self,
         chain: &mls_rs_identity_x509::CertificateChain,
         timestamp: Option<mls_rs_core::time::MlsTime>,
+    ) -> Result<mls_rs_core::crypto::SignaturePublicKey, X509Error> {
+        self.validate_chain_with_ocsp(chain, timestamp, &[])
+    }
+
+    pub fn validate_chain_with_ocsp(
+        &self,
+        chain: &mls_rs_identity_x509::CertificateChain,
+        timestamp: Option<mls_rs_core::time::MlsTime>,
+        ocsp_responses: &[DerOcspResponse],
     ) -> Result<mls_rs_core::crypto::SignaturePublicKey, X509Error> {
         let store = self.make_store(timestamp)?;
 
@@ -136,22 +176,127 @@ impl X509Validator {
             Ok::<_, X509Error>(stack)
         })?;
 
-        let verify_res = context.init(&store, &leaf_certificate, &cert_chain, |context| {
-            context.verify_cert()?;
-            Ok(context.error())
-        })?;
+        let (verify_res, verified_chain) =
+            context.init(&store, &leaf_certificate, &cert_chain, |context| {
+                context.verify_cert()?;
+
+                let verified_chain = context
+                    .chain()
+                    .map(|chain| chain.iter().map(ToOwned::to_owned).collect::<Vec<_>>())
+                    .unwrap_or_default();
 
-        match verify_res {
-            X509VerifyResult::OK => {
-                let signature_public_key = pub_key_to_uncompressed(leaf_certificate.public_key()?)
-                    .map(SignaturePublicKey::from)?;
-                Ok(signature_public_key)
-            }
-            _ => Err(X509Error::ChainValidationFailure(
+                Ok((context.error(), verified_chain))
+            })?;
+
+        if verify_res != X509VerifyResult::OK {
+            return Err(X509Error::ChainValidationFailure(
                 verify_res.error_string().to_string(),
-            )),
+            ));
         }
+
+        let crls = self
+            .crl_provider
+            .revocation_lists()
+            .map_err(|e| X509Error::CrlProviderError(e.into_any_error()))?
+            .iter()
+            .map(|crl| X509Crl::from_der(crl))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let ocsp_responses = ocsp_responses
+            .iter()
+            .filter_map(|response| basic_ocsp_response(response).transpose())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let now = timestamp
+            .map(|t| Asn1Time::from_unix(t.seconds_since_epoch() as i64))
+            .transpose()?;
+
+        verified_chain
+            .iter()
+            .zip(verified_chain.iter().skip(1))
+            .try_for_each(|(cert, issuer)| {
+                verify_not_revoked(issuer, cert, now.as_ref(), &store, &crls, &ocsp_responses)
+            })?;
+
+        pub_key_to_uncompressed(leaf_certificate.public_key()?).map(SignaturePublicKey::from)
+    }
+}
+
+/// Parse an OCSP response, ignoring responses that do not carry a status.
+fn basic_ocsp_response(response: &DerOcspResponse) -> Result<Option<OcspBasicResponse>, X509Error> {
+    let response = OcspResponse::from_der(response)?;
+
+    if response.status() != OcspResponseStatus::SUCCESSFUL {
+        return Ok(None);
     }
+
+    Ok(Some(response.basic()?))
+}
+
+fn verify_not_revoked(
+    issuer: &X509,
+    cert: &X509,
+    now: Option<&Asn1Time>,
+    store: &X509Store,
+    crls: &[X509Crl],
+    ocsp_responses: &[OcspBasicResponse],
+) -> Result<(), X509Error> {
+    let revoked = || X509Error::CertificateRevoked(format!("{:?}", cert.subject_name()));
+
+    let issuer_name = issuer.subject_name().to_der()?;
+    let issuer_key = issuer.public_key()?;
+
+    for crl in crls {
+        if crl.issuer_name().to_der()? != issuer_name || !crl.verify(&issuer_key)? {
+            continue;
+        }
+
+        let is_stale = match (crl.next_update(), now) {
+            (Some(next_update), Some(now)) => next_update < now,
+            _ => false,
+        };
+
+        if is_stale {
+            return Err(X509Error::StaleRevocationList(format!(
+                "{:?}",
+                crl.issuer_name()
+            )));
+        }
+
+        if let CrlStatus::Revoked(_) = crl.get_by_cert(cert) {
+            return Err(revoked());
+        }
+    }
+
+    // Only responses signed by the issuer itself are accepted. Delegated
+    // OCSP responders are not supported.
+    let mut responder = Stack::new()?;
+    responder.push(issuer.clone())?;
+
+    let cert_id = OcspCertId::from_cert(MessageDigest::sha1(), cert, issuer)?;
+
+    for response in ocsp_responses {
+        let Some(status) = response.find_status(&cert_id) else {
+            continue;
+        };
+
+        if response
+            .verify(
+                &responder,
+                store,
+                OcspFlag::TRUST_OTHER | OcspFlag::NO_INTERN,
+            )
+            .is_err()
+        {
+            continue;
+        }
+
+        if status.status == OcspCertStatus::REVOKED {
+            return Err(revoked());
+        }
+    }
+
+    Ok(())
 }
 
 pub fn pub_key_to_uncompressed(key: PKey<Public>) -> Result<Vec<u8>, X509Error> {
@@ -167,7 +312,7 @@ pub fn pub_key_to_uncompressed(key: PKey<Public>) -> Result<Vec<u8>, X509Error>
     }
 }
 
-impl X509CredentialValidator for X509Validator {
+impl<C: X509CrlProvider> X509CredentialValidator for X509Validator<C> {
     type Error = X509Error;
 
     fn validate_chain(
@@ -177,6 +322,15 @@ impl X509CredentialValidator for X509Validator {
     ) -> Result<SignaturePublicKey, Self::Error> {
         self.validate_chain(chain, timestamp)
     }
+
+    fn validate_chain_with_ocsp(
+        &self,
+        chain: &mls_rs_identity_x509::CertificateChain,
+        timestamp: Option<mls_rs_core::time::MlsTime>,
+        ocsp_responses: &[DerOcspResponse],
+    ) -> Result<SignaturePublicKey, Self::Error> {
+        self.validate_chain_with_ocsp(chain, timestamp, ocsp_responses)
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -547,7 +701,9 @@ fn looks_like_der(data: &[u8]) -> bool {
 
 #[cfg(test)]
 pub(crate) mod test_utils {
-    use mls_rs_identity_x509::{CertificateChain, DerCertificate};
+    use mls_rs_identity_x509::{
+        CertificateChain, DerCertificate, DerCertificateRevocationList, DerOcspResponse,
+    };
 
     pub fn load_test_ca() -> DerCertificate {
         DerCertificate::from(include_bytes!("../test_data/x509/ca.der").to_vec())
@@ -602,6 +758,64 @@ pub(crate) mod test_utils {
                 .map(DerCertificate::from),
         )
     }
+
+    pub fn load_revocation_ca() -> DerCertificate {
+        DerCertificate::from(include_bytes!("../test_data/x509/revocation/root.der").to_vec())
+    }
+
+    pub fn load_revocation_chain(leaf: &str) -> CertificateChain {
+        let (leaf, intermediate) = match leaf {
+            "leaf" => (
+                include_bytes!("../test_data/x509/revocation/leaf.der").to_vec(),
+                include_bytes!("../test_data/x509/revocation/intermediate.der").to_vec(),
+            ),
+            "revoked_leaf" => (
+                include_bytes!("../test_data/x509/revocation/revoked_leaf.der").to_vec(),
+                include_bytes!("../test_data/x509/revocation/intermediate.der").to_vec(),
+            ),
+            "leaf_of_revoked_intermediate" => (
+                include_bytes!("../test_data/x509/revocation/leaf_of_revoked_intermediate.der")
+                    .to_vec(),
+                include_bytes!("../test_data/x509/revocation/revoked_intermediate.der").to_vec(),
+            ),
+            _ => panic!("unknown test leaf {leaf}"),
+        };
+
+        CertificateChain::from_iter([leaf, intermediate].into_iter().map(DerCertificate::from))
+    }
+
+    pub fn load_revocation_lists() -> Vec<DerCertificateRevocationList> {
+        [
+            include_bytes!("../test_data/x509/revocation/root_crl.der").to_vec(),
+            include_bytes!("../test_data/x509/revocation/intermediate_crl.der").to_vec(),
+            include_bytes!("../test_data/x509/revocation/revoked_intermediate_crl.der").to_vec(),
+        ]
+        .into_iter()
+        .map(DerCertificateRevocationList::from)
+        .collect()
+    }
+
+    pub fn load_stale_revocation_list() -> DerCertificateRevocationList {
+        include_bytes!("../test_data/x509/revocation/stale_intermediate_crl.der")
+            .to_vec()
+            .into()
+    }
+
+    pub fn load_ocsp_response(revoked: bool) -> DerOcspResponse {
+        let data = if revoked {
+            include_bytes!("../test_data/x509/revocation/revoked_leaf_ocsp.der").to_vec()
+        } else {
+            include_bytes!("../test_data/x509/revocation/leaf_ocsp.der").to_vec()
+        };
+
+        DerOcspResponse::from(data)
+    }
+
+    pub fn load_foreign_ocsp_response() -> DerOcspResponse {
+        include_bytes!("../test_data/x509/revocation/foreign_leaf_ocsp.der")
+            .to_vec()
+            .into()
+    }
 }
 
 #[cfg(test)]
@@ -625,7 +839,11 @@ mod tests {
     use crate::{
         ec::private_key_to_bytes,
         x509::{
-            test_utils::{load_another_ca, load_test_invalid_ca_chain, load_test_invalid_chain},
+            test_utils::{
+                load_another_ca, load_foreign_ocsp_response, load_ocsp_response,
+                load_revocation_ca, load_revocation_chain, load_revocation_lists,
+                load_stale_revocation_list, load_test_invalid_ca_chain, load_test_invalid_chain,
+            },
             CertificateRequestWriter,
         },
     };
@@ -758,6 +976,125 @@ mod tests {
         assert_eq!(validator.validate_chain(&chain, None).unwrap(), expected)
     }
 
+    #[test]
+    fn can_validate_unrevoked_chain_with_crls() {
+        let validator = X509Validator::new(vec![load_revocation_ca()])
+            .unwrap()
+            .with_crl_provider(load_revocation_lists());
+
+        validator
+            .validate_chain(&load_revocation_chain("leaf"), Some(MlsTime::now()))
+            .unwrap();
+    }
+
+    #[test]
+    fn can_detect_revoked_leaf_with_crl() {
+        let validator = X509Validator::new(vec![load_revocation_ca()])
+            .unwrap()
+            .with_crl_provider(load_revocation_lists());
+
+        let res = validator.validate_chain(&load_revocation_chain("revoked_leaf"), None);
+
+        assert_matches!(res, Err(X509Error::CertificateRevoked(_)));
+    }
+
+    #[test]
+    fn can_detect_revoked_intermediate_with_crl() {
+        let validator = X509Validator::new(vec![load_revocation_ca()])
+            .unwrap()
+            .with_crl_provider(load_revocation_lists());
+
+        let chain = load_revocation_chain("leaf_of_revoked_intermediate");
+        let res = validator.validate_chain(&chain, None);
+
+        assert_matches!(res, Err(X509Error::CertificateRevoked(_)));
+    }
+
+    #[test]
+    fn ignores_crl_with_invalid_signature() {
+        let mut crls = load_revocation_lists();
+
+        for crl in crls.iter_mut() {
+            let mut data = crl.to_vec();
+            *data.last_mut().unwrap() ^= 1;
+            *crl = data.into();
+        }
+
+        let validator = X509Validator::new(vec![load_revocation_ca()])
+            .unwrap()
+            .with_crl_provider(crls);
+
+        validator
+            .validate_chain(&load_revocation_chain("revoked_leaf"), None)
+            .unwrap();
+    }
+
+    #[test]
+    fn can_detect_stale_crl() {
+        let validator = X509Validator::new(vec![load_revocation_ca()])
+            .unwrap()
+            .with_crl_provider(vec![load_stale_revocation_list()]);
+
+        let chain = load_revocation_chain("leaf");
+
+        assert_matches!(
+            validator.validate_chain(&chain, Some(MlsTime::now())),
+            Err(X509Error::StaleRevocationList(_))
+        );
+    }
+
+    #[test]
+    fn can_validate_chain_with_good_ocsp_staple() {
+        let validator = X509Validator::new(vec![load_revocation_ca()]).unwrap();
+
+        validator
+            .validate_chain_with_ocsp(
+                &load_revocation_chain("leaf"),
+                Some(MlsTime::now()),
+                &[load_ocsp_response(false)],
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn can_detect_revoked_leaf_with_ocsp_staple() {
+        let validator = X509Validator::new(vec![load_revocation_ca()]).unwrap();
+
+        let res = validator.validate_chain_with_ocsp(
+            &load_revocation_chain("revoked_leaf"),
+            Some(MlsTime::now()),
+            &[load_ocsp_response(true)],
+        );
+
+        assert_matches!(res, Err(X509Error::CertificateRevoked(_)));
+    }
+
+    #[test]
+    fn ignores_ocsp_staple_for_other_certificate() {
+        let validator = X509Validator::new(vec![load_revocation_ca()]).unwrap();
+
+        validator
+            .validate_chain_with_ocsp(
+                &load_revocation_chain("leaf"),
+                Some(MlsTime::now()),
+                &[load_ocsp_response(true)],
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn ignores_ocsp_staple_for_certificate_of_other_issuer() {
+        let validator = X509Validator::new(vec![load_revocation_ca()]).unwrap();
+
+        validator
+            .validate_chain_with_ocsp(
+                &load_revocation_chain("leaf"),
+                Some(MlsTime::now()),
+                &[load_foreign_ocsp_response()],
+            )
+            .unwrap();
+    }
+
     #[test]
     fn subject_parser_bytes() {
         let test_cert = load_test_ca();
@@ -0,0 +1,109 @@
+#!/bin/sh
+# Generates the PKI used by the certificate revocation tests. Run from this
+# directory; only the DER encoded outputs are kept.
+set -e
+
+DAYS=36500
+
+cat > ext.cnf <<CNF
+[ca]
+basicConstraints = critical, CA:TRUE
+keyUsage = critical, keyCertSign, cRLSign
+subjectKeyIdentifier = hash
+authorityKeyIdentifier = keyid
+
+[leaf]
+basicConstraints = critical, CA:FALSE
+subjectKeyIdentifier = hash
+authorityKeyIdentifier = keyid
+CNF
+
+cat > ca.cnf <<CNF
+[ca]
+default_ca = ca_default
+
+[ca_default]
+database = \$ENV::CA_DIR/index.txt
+crlnumber = \$ENV::CA_DIR/crlnumber
+default_md = default
+default_crl_days = $DAYS
+CNF
+
+new_key() {
+    openssl genpkey -algorithm ED25519 -out "$1.key"
+}
+
+new_ca_dir() {
+    mkdir -p "$1"
+    touch "$1/index.txt"
+    echo 01 > "$1/crlnumber"
+}
+
+issue() {
+    # issue <name> <subject> <issuer> <ext section> <serial>
+    new_key "$1"
+    openssl req -new -key "$1.key" -subj "$2" -out "$1.csr"
+    openssl x509 -req -in "$1.csr" -CA "$3.pem" -CAkey "$3.key" -set_serial "$5" \
+        -days $DAYS -extfile ext.cnf -extensions "$4" -out "$1.pem"
+    openssl x509 -in "$1.pem" -outform DER -out "$1.der"
+}
+
+record() {
+    # record <ca> <cert> [revoke]
+    if [ "$3" = "revoke" ]; then
+        CA_DIR="$1" openssl ca -config ca.cnf -keyfile "$1.key" -cert "$1.pem" -revoke "$2.pem"
+    else
+        CA_DIR="$1" openssl ca -config ca.cnf -keyfile "$1.key" -cert "$1.pem" -valid "$2.pem"
+    fi
+}
+
+crl() {
+    # crl <ca> [output prefix] [extra options]
+    out="${2:-$1}_crl"
+    CA_DIR="$1" openssl ca -config ca.cnf -keyfile "$1.key" -cert "$1.pem" -gencrl $3 -out "$out.pem"
+    openssl crl -in "$out.pem" -outform DER -out "$out.der"
+}
+
+ocsp() {
+    # ocsp <issuer> <cert> <out> [responder]
+    signer="${4:-$1}"
+    openssl ocsp -index "$1/index.txt" -rsigner "$signer.pem" -rkey "$signer.key" -CA "$1.pem" \
+        -issuer "$1.pem" -cert "$2.pem" -ndays $DAYS -respout "$3.der"
+}
+
+new_key root
+openssl req -new -x509 -key root.key -subj "/CN=RevocationRoot/C=CH" -days $DAYS \
+    -addext "basicConstraints=critical,CA:TRUE" -addext "keyUsage=critical,keyCertSign,cRLSign" \
+    -out root.pem
+openssl x509 -in root.pem -outform DER -out root.der
+
+new_ca_dir root
+issue intermediate "/CN=RevocationIntermediate/C=CH" root ca 1001
+issue revoked_intermediate "/CN=RevokedIntermediate/C=CH" root ca 1002
+record root intermediate
+record root revoked_intermediate revoke
+crl root
+
+new_ca_dir intermediate
+issue leaf "/CN=Leaf/C=CH" intermediate leaf 2001
+issue revoked_leaf "/CN=RevokedLeaf/C=CH" intermediate leaf 2002
+record intermediate leaf
+record intermediate revoked_leaf revoke
+crl intermediate
+crl intermediate stale_intermediate \
+    "-crl_lastupdate 20260101000000Z -crl_nextupdate 20260102000000Z"
+
+new_ca_dir revoked_intermediate
+issue leaf_of_revoked_intermediate "/CN=LeafOfRevokedIntermediate/C=CH" revoked_intermediate leaf 3001
+record revoked_intermediate leaf_of_revoked_intermediate
+# Shares its serial number with leaf but has a different issuer
+issue foreign_leaf "/CN=ForeignLeaf/C=CH" revoked_intermediate leaf 2001
+record revoked_intermediate foreign_leaf revoke
+crl revoked_intermediate
+
+ocsp intermediate leaf leaf_ocsp
+ocsp intermediate revoked_leaf revoked_leaf_ocsp
+# Signed by the intermediate, but about a certificate of another issuer
+ocsp revoked_intermediate foreign_leaf foreign_leaf_ocsp intermediate
+
+rm -rf root intermediate revoked_intermediate ext.cnf ca.cnf ./*.pem ./*.key ./*.csr
@@ -9,8 +9,11 @@ use crate::ec::{
 };
 use alloc::vec::Vec;
 use core::ops::Deref;
-use mls_rs_core::crypto::{CipherSuite, SignaturePublicKey, SignatureSecretKey};
-use mls_rs_crypto_traits::Curve;
+use mls_rs_core::{
+    crypto::{CipherSuite, SignaturePublicKey, SignatureSecretKey},
+    error::IntoAnyError,
+};
+use mls_rs_crypto_traits::{Curve, SignatureType};
 
 #[derive(Debug)]
 #[cfg_attr(feature = "std", derive(thiserror::Error))]
@@ -23,6 +26,13 @@ pub enum EcSignerError {
     InvalidSignature,
 }
 
+impl IntoAnyError for EcSignerError {
+    #[cfg(feature = "std")]
+    fn into_dyn_error(self) -> Result<Box<dyn std::error::Error + Send + Sync>, Self> {
+        Ok(self.into())
+    }
+}
+
 impl From<EcError> for EcSignerError {
     fn from(e: EcError) -> Self {
         EcSignerError::EcError(e)
@@ -94,3 +104,43 @@ impl EcSigner {
         ver.then_some(()).ok_or(EcSignerError::InvalidSignature)
     }
 }
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+impl SignatureType for EcSigner {
+    type Error = EcSignerError;
+
+    async fn signature_key_generate(
+        &self,
+    ) -> Result<(SignatureSecretKey, SignaturePublicKey), Self::Error> {
+        EcSigner::signature_key_generate(self)
+    }
+
+    async fn signature_key_derive_public(
+        &self,
+        secret_key: &SignatureSecretKey,
+    ) -> Result<SignaturePublicKey, Self::Error> {
+        EcSigner::signature_key_derive_public(self, secret_key)
+    }
+
+    async fn sign(
+        &self,
+        secret_key: &SignatureSecretKey,
+        data: &[u8],
+    ) -> Result<Vec<u8>, Self::Error> {
+        EcSigner::sign(self, secret_key, data)
+    }
+
+    async fn verify(
+        &self,
+        public_key: &SignaturePublicKey,
+        signature: &[u8],
+        data: &[u8],
+    ) -> Result<(), Self::Error> {
+        EcSigner::verify(self, public_key, signature, data)
+    }
+}
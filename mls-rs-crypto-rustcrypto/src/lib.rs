@@ -26,15 +26,16 @@ use mac::{Hash, HashError};
 use mls_rs_crypto_hpke::{
     context::{ContextR, ContextS},
     dhkem::DhKem,
-    hpke::{Hpke, HpkeError},
+    hpke::{Hpke, HpkeError, SenderKeyPair},
 };
-use mls_rs_crypto_traits::{AeadType, KdfType, KemId, KemType};
+use mls_rs_crypto_traits::{AeadType, AuthKemType, KdfType, KemId, KemType, RngType};
 use rand_core::{OsRng, RngCore};
 
 use mls_rs_core::{
     crypto::{
-        CipherSuite, CipherSuiteProvider, CryptoProvider, HpkeCiphertext, HpkePublicKey,
-        HpkeSecretKey, SignaturePublicKey, SignatureSecretKey,
+        CipherSuite, CipherSuiteProvider, CryptoProvider, HpkeAuthCipherSuiteProvider,
+        HpkeCiphertext, HpkePsk, HpkePublicKey, HpkeSecretKey, SignaturePublicKey,
+        SignatureSecretKey,
     },
     error::{AnyError, IntoAnyError},
 };
@@ -150,6 +151,21 @@ impl CryptoProvider for RustCryptoProvider {
     }
 }
 
+/// [`RngType`] backed by the operating system random number generator.
+///
+/// Can be combined with primitives from other providers using
+/// [`CompositeCipherSuiteBuilder`](mls_rs_crypto_hpke::composite::CompositeCipherSuiteBuilder).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RustCryptoRng;
+
+impl RngType for RustCryptoRng {
+    type Error = RustCryptoError;
+
+    fn random_bytes(&self, out: &mut [u8]) -> Result<(), Self::Error> {
+        OsRng.try_fill_bytes(out).map_err(Into::into)
+    }
+}
+
 #[derive(Clone)]
 pub struct RustCryptoCipherSuite<KEM, KDF, AEAD>
 where
@@ -374,6 +390,99 @@ where
     }
 }
 
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+impl<KEM, KDF, AEAD> HpkeAuthCipherSuiteProvider for RustCryptoCipherSuite<KEM, KDF, AEAD>
+where
+    KEM: AuthKemType + Clone + Send + Sync,
+    KDF: KdfType + Clone + Send + Sync,
+    AEAD: AeadType + Clone + Send + Sync,
+{
+    async fn hpke_seal_auth(
+        &self,
+        remote_key: &HpkePublicKey,
+        sender_secret: &HpkeSecretKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+        aad: Option<&[u8]>,
+        pt: &[u8],
+    ) -> Result<HpkeCiphertext, Self::Error> {
+        let sender = SenderKeyPair::new(sender_secret, sender_public);
+
+        Ok(self
+            .hpke
+            .seal_auth(remote_key, sender, info, psk.map(Into::into), aad, pt)
+            .await?)
+    }
+
+    async fn hpke_open_auth(
+        &self,
+        ciphertext: &HpkeCiphertext,
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+        aad: Option<&[u8]>,
+    ) -> Result<Vec<u8>, Self::Error> {
+        Ok(self
+            .hpke
+            .open_auth(
+                ciphertext,
+                local_secret,
+                local_public,
+                sender_public,
+                info,
+                psk.map(Into::into),
+                aad,
+            )
+            .await?)
+    }
+
+    async fn hpke_setup_s_auth(
+        &self,
+        remote_key: &HpkePublicKey,
+        sender_secret: &HpkeSecretKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+    ) -> Result<(Vec<u8>, Self::HpkeContextS), Self::Error> {
+        let sender = SenderKeyPair::new(sender_secret, sender_public);
+
+        Ok(self
+            .hpke
+            .setup_auth_sender(remote_key, sender, info, psk.map(Into::into))
+            .await?)
+    }
+
+    async fn hpke_setup_r_auth(
+        &self,
+        enc: &[u8],
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        sender_public: &HpkePublicKey,
+        info: &[u8],
+        psk: Option<HpkePsk<'_>>,
+    ) -> Result<Self::HpkeContextR, Self::Error> {
+        Ok(self
+            .hpke
+            .setup_auth_receiver(
+                enc,
+                local_secret,
+                local_public,
+                sender_public,
+                info,
+                psk.map(Into::into),
+            )
+            .await?)
+    }
+}
+
 #[cfg(not(mls_build_async))]
 #[test]
 fn mls_core_tests() {
@@ -381,9 +490,121 @@ fn mls_core_tests() {
     mls_rs_core::crypto::test_suite::verify_tests(&provider, true);
 
     for cs in RustCryptoProvider::all_supported_cipher_suites() {
-        let mut hpke = provider.cipher_suite_provider(cs).unwrap().hpke;
+        let cipher_suite = provider.cipher_suite_provider(cs).unwrap();
+        mls_rs_core::crypto::test_suite::verify_hpke_auth_tests(&cipher_suite);
+
+        let mut hpke = cipher_suite.hpke;
 
         mls_rs_core::crypto::test_suite::verify_hpke_context_tests(&hpke, cs);
         mls_rs_core::crypto::test_suite::verify_hpke_encap_tests(&mut hpke, cs);
+        mls_rs_core::crypto::test_suite::verify_hpke_setup_tests(&hpke, cs);
     }
 }
+
+#[cfg(not(mls_build_async))]
+#[test]
+fn composite_cipher_suite_with_custom_codepoint() {
+    use mls_rs_crypto_hpke::composite::{CompositeCipherSuiteBuilder, CompositeCryptoProvider};
+
+    let base = CipherSuite::CURVE25519_AES128;
+    let custom = CipherSuite::new(0xf0a1);
+
+    let kdf = Kdf::new(base).unwrap();
+    let kem_id = KemId::new(base).unwrap();
+    let kem = DhKem::new(
+        Ecdh::new(base).unwrap(),
+        kdf,
+        kem_id as u16,
+        kem_id.n_secret(),
+    );
+
+    let composite = CompositeCipherSuiteBuilder::new(custom)
+        .kem(kem)
+        .kdf(kdf)
+        .aead(Aead::new(base).unwrap())
+        .hash(Hash::new(base).unwrap())
+        .signature(EcSigner::new(base).unwrap())
+        .rng(RustCryptoRng)
+        .build()
+        .unwrap();
+
+    mls_rs_core::crypto::test_suite::verify_hpke_auth_tests(&composite);
+
+    let provider = CompositeCryptoProvider::new().with_cipher_suite(composite);
+
+    assert_eq!(provider.supported_cipher_suites(), vec![custom]);
+    assert!(provider.cipher_suite_provider(base).is_none());
+
+    let composite = provider.cipher_suite_provider(custom).unwrap();
+    let reference = RustCryptoProvider::new()
+        .cipher_suite_provider(base)
+        .unwrap();
+
+    assert_eq!(composite.cipher_suite(), custom);
+    assert_eq!(
+        composite.hash(b"data").unwrap(),
+        reference.hash(b"data").unwrap()
+    );
+
+    assert_eq!(
+        composite.kdf_extract(b"salt", b"ikm").unwrap(),
+        reference.kdf_extract(b"salt", b"ikm").unwrap()
+    );
+
+    let (secret, public) = composite.signature_key_generate().unwrap();
+    let signature = composite.sign(&secret, b"data").unwrap();
+    reference.verify(&public, &signature, b"data").unwrap();
+
+    let (secret, public) = reference.kem_generate().unwrap();
+    let ct = composite.hpke_seal(&public, b"info", None, b"pt").unwrap();
+    let pt = reference.hpke_open(&ct, &secret, &public, b"info", None);
+    assert_eq!(pt.unwrap(), b"pt");
+}
+
+#[cfg(not(mls_build_async))]
+#[test]
+fn composite_cipher_suite_uses_configured_rng_for_hpke() {
+    use mls_rs_crypto_hpke::composite::CompositeCipherSuiteBuilder;
+
+    #[derive(Clone)]
+    struct FixedRng;
+
+    impl RngType for FixedRng {
+        type Error = core::convert::Infallible;
+
+        fn random_bytes(&self, out: &mut [u8]) -> Result<(), Self::Error> {
+            out.fill(0x42);
+            Ok(())
+        }
+    }
+
+    let cs = CipherSuite::CURVE25519_AES128;
+    let kdf = Kdf::new(cs).unwrap();
+    let kem_id = KemId::new(cs).unwrap();
+    let kem = DhKem::new(
+        Ecdh::new(cs).unwrap(),
+        kdf,
+        kem_id as u16,
+        kem_id.n_secret(),
+    );
+
+    let composite = CompositeCipherSuiteBuilder::new(cs)
+        .kem(kem)
+        .kdf(kdf)
+        .aead(Aead::new(cs).unwrap())
+        .hash(Hash::new(cs).unwrap())
+        .signature(EcSigner::new(cs).unwrap())
+        .rng(FixedRng)
+        .build()
+        .unwrap();
+
+    let (secret, public) = composite.kem_generate().unwrap();
+    assert_eq!(composite.kem_generate().unwrap().1, public);
+
+    let ct1 = composite.hpke_seal(&public, b"info", None, b"pt").unwrap();
+    let ct2 = composite.hpke_seal(&public, b"info", None, b"pt").unwrap();
+    assert_eq!(ct1.kem_output, ct2.kem_output);
+
+    let pt = composite.hpke_open(&ct1, &secret, &public, b"info", None);
+    assert_eq!(pt.unwrap(), b"pt");
+}
@@ -6,7 +6,8 @@ use hmac::{
     digest::{crypto_common::BlockSizeUser, FixedOutputReset},
     Mac, SimpleHmac,
 };
-use mls_rs_core::crypto::CipherSuite;
+use mls_rs_core::{crypto::CipherSuite, error::IntoAnyError};
+use mls_rs_crypto_traits::HashType;
 use sha2::{Digest, Sha256, Sha384, Sha512};
 
 use alloc::vec::Vec;
@@ -20,6 +21,13 @@ pub enum HashError {
     UnsupportedCipherSuite,
 }
 
+impl IntoAnyError for HashError {
+    #[cfg(feature = "std")]
+    fn into_dyn_error(self) -> Result<Box<dyn std::error::Error + Send + Sync>, Self> {
+        Ok(self.into())
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[repr(u16)]
 pub enum Hash {
@@ -79,3 +87,21 @@ fn generic_generate_tag<D: Digest + BlockSizeUser + FixedOutputReset>(
     let res = hmac.finalize().into_bytes().to_vec();
     Ok(res)
 }
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+impl HashType for Hash {
+    type Error = HashError;
+
+    async fn hash(&self, data: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        Ok(Hash::hash(self, data))
+    }
+
+    async fn mac(&self, key: &[u8], data: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        Hash::mac(self, key, data)
+    }
+}
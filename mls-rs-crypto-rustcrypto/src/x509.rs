@@ -4,12 +4,16 @@
 
 use std::net::AddrParseError;
 
-use mls_rs_core::{crypto::CipherSuite, error::IntoAnyError};
+use mls_rs_core::{
+    crypto::CipherSuite,
+    error::{AnyError, IntoAnyError},
+};
 use mls_rs_identity_x509::SubjectAltName;
 use spki::{der::Tag, ObjectIdentifier};
 
 use crate::{ec::EcError, ec_for_x509::EcX509Error, ec_signer::EcSignerError};
 
+mod ocsp;
 mod reader;
 mod util;
 mod validator;
@@ -85,6 +89,17 @@ pub enum X509Error {
         error("self-signed certificate provided as chain of length {0} but it must have length 1")
     )]
     SelfSignedWrongLength(usize),
+    #[cfg_attr(feature = "std", error("certificate {0} has been revoked"))]
+    CertificateRevoked(String),
+    #[cfg_attr(
+        feature = "std",
+        error("certificate revocation list issued by {0} is past its next update time")
+    )]
+    StaleRevocationList(String),
+    #[cfg_attr(feature = "std", error("unsupported OCSP response type with OID {0}"))]
+    UnsupportedOcspResponseType(ObjectIdentifier),
+    #[cfg_attr(feature = "std", error(transparent))]
+    CrlProviderError(AnyError),
 }
 
 impl From<x509_cert::der::Error> for X509Error {
@@ -0,0 +1,116 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+//! Minimal ASN.1 definitions of OCSP responses from RFC 6960, Section 4.2.1.
+
+use der::{
+    asn1::{Any, BitString, GeneralizedTime, Null, ObjectIdentifier, OctetString},
+    Choice, Decode, Enumerated, Sequence, TagNumber, Tagged,
+};
+use x509_cert::{
+    ext::{pkix::CrlReason, Extensions},
+    name::Name,
+    serial_number::SerialNumber,
+    spki::AlgorithmIdentifierOwned,
+    Certificate, Version,
+};
+
+/// `id-pkix-ocsp-basic`
+pub(crate) const OCSP_BASIC_RESPONSE: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.3.6.1.5.5.7.48.1.1");
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Enumerated)]
+#[repr(u32)]
+pub(crate) enum OcspResponseStatus {
+    Successful = 0,
+    MalformedRequest = 1,
+    InternalError = 2,
+    TryLater = 3,
+    SigRequired = 5,
+    Unauthorized = 6,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub(crate) struct OcspResponse {
+    pub response_status: OcspResponseStatus,
+    #[asn1(context_specific = "0", optional = "true")]
+    pub response_bytes: Option<ResponseBytes>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub(crate) struct ResponseBytes {
+    pub response_type: ObjectIdentifier,
+    pub response: OctetString,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub(crate) struct BasicOcspResponse {
+    pub tbs_response_data: ResponseData,
+    pub signature_algorithm: AlgorithmIdentifierOwned,
+    pub signature: BitString,
+    #[asn1(context_specific = "0", optional = "true")]
+    pub certs: Option<Vec<Certificate>>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub(crate) struct ResponseData {
+    #[asn1(context_specific = "0", default = "Default::default")]
+    pub version: Version,
+    /// `ResponderID` is a `CHOICE` of explicitly tagged alternatives, which
+    /// is interpreted by [`ResponseData::responder_name`].
+    pub responder_id: Any,
+    pub produced_at: GeneralizedTime,
+    pub responses: Vec<SingleResponse>,
+    #[asn1(context_specific = "1", optional = "true")]
+    pub response_extensions: Option<Extensions>,
+}
+
+impl ResponseData {
+    /// Name of the responder if it is identified `byName`.
+    pub fn responder_name(&self) -> Result<Option<Name>, der::Error> {
+        let tag = self.responder_id.tag();
+
+        if tag.is_context_specific() && tag.number() == TagNumber::N1 {
+            Name::from_der(self.responder_id.value()).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub(crate) struct SingleResponse {
+    pub cert_id: CertId,
+    pub cert_status: CertStatus,
+    pub this_update: GeneralizedTime,
+    #[asn1(context_specific = "0", optional = "true")]
+    pub next_update: Option<GeneralizedTime>,
+    #[asn1(context_specific = "1", optional = "true")]
+    pub single_extensions: Option<Extensions>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub(crate) struct CertId {
+    pub hash_algorithm: AlgorithmIdentifierOwned,
+    pub issuer_name_hash: OctetString,
+    pub issuer_key_hash: OctetString,
+    pub serial_number: SerialNumber,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Choice)]
+pub(crate) enum CertStatus {
+    #[asn1(context_specific = "0", tag_mode = "IMPLICIT")]
+    Good(Null),
+    #[asn1(context_specific = "1", tag_mode = "IMPLICIT", constructed = "true")]
+    Revoked(RevokedInfo),
+    #[asn1(context_specific = "2", tag_mode = "IMPLICIT")]
+    Unknown(Null),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub(crate) struct RevokedInfo {
+    pub revocation_time: GeneralizedTime,
+    #[asn1(context_specific = "0", optional = "true")]
+    pub revocation_reason: Option<CrlReason>,
+}
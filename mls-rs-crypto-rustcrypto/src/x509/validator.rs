@@ -2,30 +2,43 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use mls_rs_core::{crypto::SignaturePublicKey, time::MlsTime};
-use mls_rs_identity_x509::{CertificateChain, DerCertificate, X509CredentialValidator};
+use const_oid::db::rfc5912::{ID_SHA_1, ID_SHA_256};
+use mls_rs_core::{crypto::SignaturePublicKey, error::IntoAnyError, time::MlsTime};
+use mls_rs_identity_x509::{
+    CertificateChain, DerCertificate, DerCertificateRevocationList, DerOcspResponse,
+    X509CredentialValidator, X509CrlProvider,
+};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
 use spki::der::{Decode, Encode};
 use std::{
     collections::HashMap,
     fmt::{self, Debug},
 };
-use x509_cert::Certificate;
+use x509_cert::{crl::CertificateList, serial_number::SerialNumber, Certificate};
 
 use crate::{
     ec::pub_key_to_uncompressed,
     ec_for_x509::{pub_key_from_spki, signer_from_algorithm},
 };
 
-use super::X509Error;
+use super::{
+    ocsp::{
+        BasicOcspResponse, CertId, CertStatus, OcspResponse, OcspResponseStatus,
+        OCSP_BASIC_RESPONSE,
+    },
+    X509Error,
+};
 
 #[derive(Clone)]
-pub struct X509Validator {
+pub struct X509Validator<C = Vec<DerCertificateRevocationList>> {
     root_ca_list: HashMap<Vec<u8>, DerCertificate>,
     pinned_cert: Option<DerCertificate>,
     allow_self_signed: bool,
+    crl_provider: C,
 }
 
-impl Debug for X509Validator {
+impl<C: Debug> Debug for X509Validator<C> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("X509Validator")
             .field(
@@ -42,6 +55,7 @@ impl Debug for X509Validator {
             )
             .field("pinned_cert", &self.pinned_cert)
             .field("allow_self_signed", &self.allow_self_signed)
+            .field("crl_provider", &self.crl_provider)
             .finish()
     }
 }
@@ -63,8 +77,26 @@ impl X509Validator {
             root_ca_list,
             pinned_cert: None,
             allow_self_signed: false,
+            crl_provider: Vec::new(),
         })
     }
+}
+
+impl<C> X509Validator<C> {
+    /// Check every certificate of a chain against the revocation lists
+    /// returned by `crl_provider`.
+    ///
+    /// A revocation list is only used if it is signed by the issuer of the
+    /// certificate being checked. Lists that are past their next update time
+    /// cause validation to fail.
+    pub fn with_crl_provider<P: X509CrlProvider>(self, crl_provider: P) -> X509Validator<P> {
+        X509Validator {
+            root_ca_list: self.root_ca_list,
+            pinned_cert: self.pinned_cert,
+            allow_self_signed: self.allow_self_signed,
+            crl_provider,
+        }
+    }
 
     pub fn set_pinned_cert(&mut self, pinned_cert: Option<DerCertificate>) {
         self.pinned_cert = pinned_cert;
@@ -74,11 +106,14 @@ impl X509Validator {
     pub fn allow_self_signed(&mut self, allow: bool) {
         self.allow_self_signed = allow;
     }
+}
 
-    fn validate_chain(
+impl<C: X509CrlProvider> X509Validator<C> {
+    fn validate_chain_with_ocsp(
         &self,
         chain: &CertificateChain,
         timestamp: Option<MlsTime>,
+        ocsp_responses: &[DerOcspResponse],
     ) -> Result<SignaturePublicKey, X509Error> {
         (!chain.is_empty())
             .then_some(())
@@ -96,6 +131,19 @@ impl X509Validator {
             .map(|cert_data| Certificate::from_der(cert_data))
             .collect::<Result<Vec<_>, _>>()?;
 
+        let crls = self
+            .crl_provider
+            .revocation_lists()
+            .map_err(|e| X509Error::CrlProviderError(e.into_any_error()))?
+            .iter()
+            .map(|crl| CertificateList::from_der(crl))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let ocsp_responses = ocsp_responses
+            .iter()
+            .filter_map(|response| basic_ocsp_response(response).transpose())
+            .collect::<Result<Vec<_>, _>>()?;
+
         for (cert1, cert2) in chain
             .iter()
             .zip(chain.iter().skip(1).chain(chain.iter().rev().take(1)))
@@ -118,6 +166,7 @@ impl X509Validator {
 
             let verifier = verifier.as_ref().unwrap_or(cert2);
             verify_cert(verifier, cert1, timestamp)?;
+            verify_not_revoked(verifier, cert1, timestamp, &crls, &ocsp_responses)?;
 
             // If we found a CA, we're done with the chain.
             if maybe_ca.is_some() {
@@ -147,14 +196,14 @@ fn verify_time(cert: &Certificate, time: MlsTime) -> Result<(), X509Error> {
         .ok_or_else(|| X509Error::ValidityError(now, format!("{cert:?}")))
 }
 
-fn verify_cert(
+fn verify_signature<T: Encode>(
     verifier: &Certificate,
-    verified: &Certificate,
-    timestamp: Option<MlsTime>,
+    signed: &T,
+    signature: &[u8],
 ) -> Result<(), X509Error> {
-    // Re-encode the verified TBS struct to get the signed bytes
+    // Re-encode the signed struct to get the signed bytes
     let mut tbs = Vec::new();
-    verified.tbs_certificate.encode_to_vec(&mut tbs)?;
+    signed.encode_to_vec(&mut tbs)?;
 
     // Create a signer for the verifier
     let signer =
@@ -165,10 +214,24 @@ fn verify_cert(
     // Verify the signature
     signer.verify(
         &pub_key_to_uncompressed(&pub_key).map(Into::into)?,
-        verified.signature.raw_bytes(),
+        signature,
         &tbs,
     )?;
 
+    Ok(())
+}
+
+fn verify_cert(
+    verifier: &Certificate,
+    verified: &Certificate,
+    timestamp: Option<MlsTime>,
+) -> Result<(), X509Error> {
+    verify_signature(
+        verifier,
+        &verified.tbs_certificate,
+        verified.signature.raw_bytes(),
+    )?;
+
     // Verify properties
     if let Some(time) = timestamp {
         verify_time(verified, time)?;
@@ -177,6 +240,141 @@ fn verify_cert(
     Ok(())
 }
 
+fn basic_ocsp_response(response: &DerOcspResponse) -> Result<Option<BasicOcspResponse>, X509Error> {
+    let response = OcspResponse::from_der(response)?;
+
+    // Error responses carry no revocation information
+    let Some(response_bytes) = response
+        .response_bytes
+        .filter(|_| response.response_status == OcspResponseStatus::Successful)
+    else {
+        return Ok(None);
+    };
+
+    if response_bytes.response_type != OCSP_BASIC_RESPONSE {
+        return Err(X509Error::UnsupportedOcspResponseType(
+            response_bytes.response_type,
+        ));
+    }
+
+    Ok(Some(BasicOcspResponse::from_der(
+        response_bytes.response.as_bytes(),
+    )?))
+}
+
+fn verify_not_revoked(
+    issuer: &Certificate,
+    cert: &Certificate,
+    timestamp: Option<MlsTime>,
+    crls: &[CertificateList],
+    ocsp_responses: &[BasicOcspResponse],
+) -> Result<(), X509Error> {
+    let issuer_name = &issuer.tbs_certificate.subject;
+    let serial_number = &cert.tbs_certificate.serial_number;
+    let now = timestamp.map(|t| t.seconds_since_epoch());
+
+    if &cert.tbs_certificate.issuer != issuer_name {
+        return Ok(());
+    }
+
+    let revoked = || X509Error::CertificateRevoked(cert.tbs_certificate.subject.to_string());
+
+    for crl in crls
+        .iter()
+        .filter(|crl| &crl.tbs_cert_list.issuer == issuer_name)
+    {
+        // Lists that are not signed by the issuer carry no revocation information
+        if verify_signature(issuer, &crl.tbs_cert_list, crl.signature.raw_bytes()).is_err() {
+            continue;
+        }
+
+        let next_update = crl
+            .tbs_cert_list
+            .next_update
+            .map(|t| t.to_unix_duration().as_secs());
+
+        if let Some((now, next_update)) = now.zip(next_update) {
+            (now <= next_update)
+                .then_some(())
+                .ok_or_else(|| X509Error::StaleRevocationList(issuer_name.to_string()))?;
+        }
+
+        let is_revoked = crl
+            .tbs_cert_list
+            .revoked_certificates
+            .iter()
+            .flatten()
+            .any(|revoked| &revoked.serial_number == serial_number);
+
+        if is_revoked {
+            return Err(revoked());
+        }
+    }
+
+    // Only responses signed directly by the issuer are supported.
+    for response in ocsp_responses {
+        let data = &response.tbs_response_data;
+
+        if matches!(data.responder_name()?, Some(name) if &name != issuer_name) {
+            continue;
+        }
+
+        let mut statuses = data
+            .responses
+            .iter()
+            .filter(|single| cert_id_matches(&single.cert_id, issuer, serial_number))
+            .peekable();
+
+        if statuses.peek().is_none()
+            || verify_signature(issuer, data, response.signature.raw_bytes()).is_err()
+        {
+            continue;
+        }
+
+        // A revocation is final, so the response does not need to be current.
+        let is_revoked = statuses.any(|single| match &single.cert_status {
+            CertStatus::Revoked(info) => {
+                let revocation_time = info.revocation_time.to_unix_duration().as_secs();
+                !matches!(now, Some(now) if revocation_time > now)
+            }
+            _ => false,
+        });
+
+        if is_revoked {
+            return Err(revoked());
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that `cert_id` identifies the certificate with `serial_number`
+/// issued by `issuer`. Unsupported hash algorithms never match.
+fn cert_id_matches(cert_id: &CertId, issuer: &Certificate, serial_number: &SerialNumber) -> bool {
+    if &cert_id.serial_number != serial_number {
+        return false;
+    }
+
+    let hash: fn(&[u8]) -> Vec<u8> = match cert_id.hash_algorithm.oid {
+        ID_SHA_1 => |data| Sha1::digest(data).to_vec(),
+        ID_SHA_256 => |data| Sha256::digest(data).to_vec(),
+        _ => return false,
+    };
+
+    let Ok(issuer_name) = issuer.tbs_certificate.subject.to_der() else {
+        return false;
+    };
+
+    let issuer_key = issuer
+        .tbs_certificate
+        .subject_public_key_info
+        .subject_public_key
+        .raw_bytes();
+
+    cert_id.issuer_name_hash.as_bytes() == hash(&issuer_name)
+        && cert_id.issuer_key_hash.as_bytes() == hash(issuer_key)
+}
+
 fn validate_self_signed(
     chain: &CertificateChain,
     timestamp: Option<MlsTime>,
@@ -196,16 +394,25 @@ fn validate_self_signed(
     Ok(pub_signing_key)
 }
 
-impl X509CredentialValidator for X509Validator {
+impl<C: X509CrlProvider> X509CredentialValidator for X509Validator<C> {
     type Error = X509Error;
 
     fn validate_chain(
         &self,
         chain: &mls_rs_identity_x509::CertificateChain,
         timestamp: Option<mls_rs_core::time::MlsTime>,
+    ) -> Result<SignaturePublicKey, Self::Error> {
+        X509CredentialValidator::validate_chain_with_ocsp(self, chain, timestamp, &[])
+    }
+
+    fn validate_chain_with_ocsp(
+        &self,
+        chain: &mls_rs_identity_x509::CertificateChain,
+        timestamp: Option<mls_rs_core::time::MlsTime>,
+        ocsp_responses: &[DerOcspResponse],
     ) -> Result<SignaturePublicKey, Self::Error> {
         if !self.allow_self_signed {
-            self.validate_chain(chain, timestamp)
+            self.validate_chain_with_ocsp(chain, timestamp, ocsp_responses)
         } else {
             validate_self_signed(chain, timestamp)
         }
@@ -214,11 +421,13 @@ impl X509CredentialValidator for X509Validator {
 
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
+    use std::{convert::Infallible, sync::Mutex, time::Duration};
 
     use assert_matches::assert_matches;
     use mls_rs_core::time::MlsTime;
-    use mls_rs_identity_x509::{CertificateChain, X509CredentialValidator};
+    use mls_rs_identity_x509::{
+        CertificateChain, DerCertificateRevocationList, X509CredentialValidator, X509CrlProvider,
+    };
     use spki::der::Decode;
     use x509_cert::Certificate;
 
@@ -226,8 +435,9 @@ mod tests {
         ec_signer::EcSignerError,
         x509::{
             util::test_utils::{
-                load_another_ca, load_test_ca, load_test_cert_chain, load_test_invalid_ca_chain,
-                load_test_invalid_chain,
+                load_another_ca, load_foreign_ocsp_response, load_ocsp_response, load_revocation_ca, load_revocation_chain,
+                load_revocation_lists, load_stale_revocation_list, load_test_ca,
+                load_test_cert_chain, load_test_invalid_ca_chain, load_test_invalid_chain,
             },
             X509Error,
         },
@@ -390,4 +600,163 @@ mod tests {
 
         assert_eq!(validator.validate_chain(&chain, None).unwrap(), expected)
     }
+
+    #[test]
+    fn can_validate_unrevoked_chain_with_crls() {
+        let validator = X509Validator::new(vec![load_revocation_ca()])
+            .unwrap()
+            .with_crl_provider(load_revocation_lists());
+
+        validator
+            .validate_chain(&load_revocation_chain("leaf"), Some(MlsTime::now()))
+            .unwrap();
+    }
+
+    #[test]
+    fn can_detect_revoked_leaf_with_crl() {
+        let validator = X509Validator::new(vec![load_revocation_ca()])
+            .unwrap()
+            .with_crl_provider(load_revocation_lists());
+
+        let res = validator.validate_chain(&load_revocation_chain("revoked_leaf"), None);
+
+        assert_matches!(res, Err(X509Error::CertificateRevoked(subject)) if subject.contains("RevokedLeaf"));
+    }
+
+    #[test]
+    fn can_detect_revoked_intermediate_with_crl() {
+        let validator = X509Validator::new(vec![load_revocation_ca()])
+            .unwrap()
+            .with_crl_provider(load_revocation_lists());
+
+        let chain = load_revocation_chain("leaf_of_revoked_intermediate");
+        let res = validator.validate_chain(&chain, Some(MlsTime::now()));
+
+        assert_matches!(res, Err(X509Error::CertificateRevoked(subject)) if subject.contains("RevokedIntermediate"));
+    }
+
+    #[test]
+    fn ignores_crl_with_invalid_signature() {
+        let mut crls = load_revocation_lists();
+
+        for crl in crls.iter_mut() {
+            let mut data = crl.to_vec();
+            *data.last_mut().unwrap() ^= 1;
+            *crl = data.into();
+        }
+
+        let validator = X509Validator::new(vec![load_revocation_ca()])
+            .unwrap()
+            .with_crl_provider(crls);
+
+        validator
+            .validate_chain(&load_revocation_chain("revoked_leaf"), None)
+            .unwrap();
+    }
+
+    #[test]
+    fn crl_provider_is_queried_on_every_validation() {
+        #[derive(Default)]
+        struct RefreshingCrlProvider(Mutex<Vec<DerCertificateRevocationList>>);
+
+        impl X509CrlProvider for &RefreshingCrlProvider {
+            type Error = Infallible;
+
+            fn revocation_lists(&self) -> Result<Vec<DerCertificateRevocationList>, Infallible> {
+                Ok(self.0.lock().unwrap().clone())
+            }
+        }
+
+        let provider = RefreshingCrlProvider::default();
+
+        let validator = X509Validator::new(vec![load_revocation_ca()])
+            .unwrap()
+            .with_crl_provider(&provider);
+
+        let chain = load_revocation_chain("revoked_leaf");
+        validator.validate_chain(&chain, None).unwrap();
+
+        *provider.0.lock().unwrap() = load_revocation_lists();
+
+        assert_matches!(
+            validator.validate_chain(&chain, None),
+            Err(X509Error::CertificateRevoked(_))
+        );
+    }
+
+    #[test]
+    fn can_detect_stale_crl() {
+        let validator = X509Validator::new(vec![load_revocation_ca()])
+            .unwrap()
+            .with_crl_provider(vec![load_stale_revocation_list()]);
+
+        let chain = load_revocation_chain("leaf");
+
+        assert_matches!(
+            validator.validate_chain(&chain, Some(MlsTime::now())),
+            Err(X509Error::StaleRevocationList(_))
+        );
+
+        validator.validate_chain(&chain, None).unwrap();
+    }
+
+    #[test]
+    fn can_validate_chain_with_good_ocsp_staple() {
+        let validator = X509Validator::new(vec![load_revocation_ca()]).unwrap();
+        let chain = load_revocation_chain("leaf");
+
+        X509CredentialValidator::validate_chain_with_ocsp(
+            &validator,
+            &chain,
+            Some(MlsTime::now()),
+            &[load_ocsp_response(false)],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn can_detect_revoked_leaf_with_ocsp_staple() {
+        let validator = X509Validator::new(vec![load_revocation_ca()]).unwrap();
+        let chain = load_revocation_chain("revoked_leaf");
+
+        X509CredentialValidator::validate_chain(&validator, &chain, Some(MlsTime::now())).unwrap();
+
+        let res = X509CredentialValidator::validate_chain_with_ocsp(
+            &validator,
+            &chain,
+            Some(MlsTime::now()),
+            &[load_ocsp_response(true)],
+        );
+
+        assert_matches!(res, Err(X509Error::CertificateRevoked(_)));
+    }
+
+    #[test]
+    fn ignores_ocsp_staple_for_other_certificate() {
+        let validator = X509Validator::new(vec![load_revocation_ca()]).unwrap();
+        let chain = load_revocation_chain("leaf_of_revoked_intermediate");
+
+        X509CredentialValidator::validate_chain_with_ocsp(
+            &validator,
+            &chain,
+            Some(MlsTime::now()),
+            &[load_ocsp_response(true)],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn ignores_ocsp_staple_for_certificate_of_other_issuer() {
+        let validator = X509Validator::new(vec![load_revocation_ca()]).unwrap();
+
+        // The staple is signed by the leaf's issuer and revokes the leaf's serial
+        // number, but identifies a certificate of another issuer.
+        X509CredentialValidator::validate_chain_with_ocsp(
+            &validator,
+            &load_revocation_chain("leaf"),
+            Some(MlsTime::now()),
+            &[load_foreign_ocsp_response()],
+        )
+        .unwrap();
+    }
 }
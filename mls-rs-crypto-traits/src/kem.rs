@@ -40,6 +40,81 @@ pub trait KemType: Send + Sync {
     ) -> Result<Vec<u8>, Self::Error>;
 }
 
+/// A KEM that additionally supports the authenticated encapsulation functions
+/// `AuthEncap` and `AuthDecap` used by the Auth and AuthPSK modes of
+/// RFC 9180, Section 5.1.3.
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+pub trait AuthKemType: KemType {
+    /// Encapsulate a shared secret to `remote_key` that also authenticates
+    /// possession of the sender secret key `sender_secret`.
+    async fn auth_encap(
+        &self,
+        remote_key: &HpkePublicKey,
+        sender_secret: &HpkeSecretKey,
+        sender_public: &HpkePublicKey,
+    ) -> Result<KemResult, Self::Error>;
+
+    /// Recover the shared secret generated by [auth_encap](AuthKemType::auth_encap)
+    /// from `enc`, checking that it was produced by the holder of the secret
+    /// key matching `sender_public`.
+    async fn auth_decap(
+        &self,
+        enc: &[u8],
+        secret_key: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        sender_public: &HpkePublicKey,
+    ) -> Result<Vec<u8>, Self::Error>;
+}
+
+/// A KEM whose encapsulation randomness can be supplied by the caller.
+///
+/// This lets HPKE draw ephemeral keys from an [`RngType`](crate::RngType) other
+/// than the one built into the KEM implementation.
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+pub trait SeededKemType: KemType {
+    /// Number of random bytes consumed by [derive](KemType::derive) to generate
+    /// a fresh key pair, and by [encap_seeded](SeededKemType::encap_seeded).
+    fn seed_len(&self) -> usize;
+
+    /// Same as [encap](KemType::encap), except that the ephemeral key pair is
+    /// derived from `seed` instead of being generated internally.
+    async fn encap_seeded(
+        &self,
+        remote_key: &HpkePublicKey,
+        seed: &[u8],
+    ) -> Result<KemResult, Self::Error>;
+}
+
+/// An [`AuthKemType`] whose encapsulation randomness can be supplied by the
+/// caller, see [`SeededKemType`].
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+pub trait SeededAuthKemType: AuthKemType + SeededKemType {
+    /// Same as [auth_encap](AuthKemType::auth_encap), except that the
+    /// ephemeral key pair is derived from `seed`.
+    async fn auth_encap_seeded(
+        &self,
+        remote_key: &HpkePublicKey,
+        sender_secret: &HpkeSecretKey,
+        sender_public: &HpkePublicKey,
+        seed: &[u8],
+    ) -> Result<KemResult, Self::Error>;
+}
+
 /// Struct to represent the output of the kem [encap](KemType::encap) function
 pub struct KemResult {
     pub shared_secret: Vec<u8>,
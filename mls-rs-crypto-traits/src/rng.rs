@@ -0,0 +1,17 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+#[cfg(feature = "mock")]
+use mockall::automock;
+
+use mls_rs_core::error::IntoAnyError;
+
+/// A trait that provides a cryptographically secure random number generator.
+#[cfg_attr(feature = "mock", automock(type Error = crate::mock::TestError;))]
+pub trait RngType: Send + Sync {
+    type Error: IntoAnyError + Send + Sync;
+
+    /// Fill `out` with random bytes.
+    fn random_bytes(&self, out: &mut [u8]) -> Result<(), Self::Error>;
+}
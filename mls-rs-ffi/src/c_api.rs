@@ -0,0 +1,52 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+//! Stable C API for mls-rs.
+//!
+//! The header `mls_rs.h` is generated with [`generate_headers`] when the
+//! `headers` feature is enabled. All symbols are prefixed with `mls_`.
+//!
+//! # Conventions
+//!
+//! * Fallible functions return an [`MlsStatus`]. On failure, a
+//!   description of the error is available from `mls_last_error_message`
+//!   on the same thread until the next call.
+//! * Results are written to out-parameters, which are only initialized
+//!   when `MLS_STATUS_OK` is returned.
+//! * Values returned by the API are owned by the caller and released with
+//!   the matching `*_free` function: `mls_buffer_free` for byte buffers,
+//!   `mls_string_free` for strings, `mls_client_free`, `mls_group_free`,
+//!   `mls_commit_output_free` and `mls_received_message_free`.
+//! * Clients may be shared between threads. A group must not be used by
+//!   more than one thread at a time.
+
+mod client;
+mod config;
+mod error;
+mod group;
+mod identity;
+mod storage;
+
+pub use client::*;
+pub use config::{CApiConfig, MlsClientConfig, MlsIdentityKind, MlsStorageKind};
+pub use error::{mls_last_error_message, mls_string_free, MlsStatus, ProviderError};
+pub use group::*;
+pub use identity::IdentityProviderWrapper;
+#[cfg(feature = "x509")]
+pub use identity::OpensslX509IdentityProvider;
+pub use storage::{GroupStateStorageWrapper, KeyPackageStorageWrapper};
+
+use safer_ffi::prelude::*;
+
+/// Release a byte buffer returned by the C API.
+#[ffi_export]
+pub fn mls_buffer_free(buffer: repr_c::Vec<u8>) {
+    drop(buffer)
+}
+
+/// Write the C header declaring the API to `path`.
+#[cfg(feature = "headers")]
+pub fn generate_headers(path: &std::path::Path) -> std::io::Result<()> {
+    safer_ffi::headers::builder().to_file(path)?.generate()
+}
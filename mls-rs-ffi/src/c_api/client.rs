@@ -0,0 +1,172 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use mls_rs::error::{IntoAnyError, MlsError};
+use mls_rs::{CipherSuite, CipherSuiteProvider, Client, CryptoProvider, ExtensionList, MlsMessage};
+use mls_rs_crypto_openssl::OpensslCryptoProvider;
+use safer_ffi::prelude::*;
+
+use super::config::{CApiConfig, MlsClientConfig};
+use super::error::{catch, Error, MlsStatus};
+use super::group::MlsGroup;
+
+/// An MLS client used to create key packages and manage groups.
+///
+/// Created with `mls_client_new` and released with `mls_client_free`.
+#[derive_ReprC]
+#[repr(opaque)]
+pub struct MlsClient {
+    pub(crate) inner: Client<CApiConfig>,
+}
+
+/// Generate a signature key pair for `cipher_suite`.
+///
+/// On success, `secret_key` and `public_key` must be released with
+/// `mls_buffer_free`.
+#[ffi_export]
+pub fn mls_generate_signature_keypair(
+    cipher_suite: u16,
+    secret_key: Out<'_, repr_c::Vec<u8>>,
+    public_key: Out<'_, repr_c::Vec<u8>>,
+) -> MlsStatus {
+    catch(|| {
+        let cipher_suite = CipherSuite::from(cipher_suite);
+
+        let provider = OpensslCryptoProvider::new()
+            .cipher_suite_provider(cipher_suite)
+            .ok_or_else(|| Error::Unsupported(format!("cipher suite {}", *cipher_suite)))?;
+
+        let (secret, public) = provider
+            .signature_key_generate()
+            .map_err(|err| MlsError::CryptoProviderError(err.into_any_error()))?;
+
+        secret_key.write(secret.to_vec().into());
+        public_key.write(public.to_vec().into());
+
+        Ok(())
+    })
+}
+
+/// Create a new client.
+///
+/// `credential` is the identifier of a basic credential or the PEM
+/// encoded certificate chain of an X.509 credential, leaf first, as
+/// selected by `config`. The signature keys must match the configured
+/// cipher suite.
+///
+/// On success, `client` must be released with `mls_client_free`.
+#[ffi_export]
+pub fn mls_client_new<'a>(
+    config: &'a MlsClientConfig<'a>,
+    credential: c_slice::Ref<'_, u8>,
+    signature_secret_key: c_slice::Ref<'_, u8>,
+    signature_public_key: c_slice::Ref<'_, u8>,
+    client: Out<'_, repr_c::Box<MlsClient>>,
+) -> MlsStatus {
+    catch(|| {
+        let (builder, signing_identity) =
+            config.builder(credential.as_slice(), signature_public_key.as_slice())?;
+
+        let inner = builder
+            .signing_identity(
+                signing_identity,
+                signature_secret_key.to_vec().into(),
+                config.cipher_suite.into(),
+            )
+            .build();
+
+        client.write(Box::new(MlsClient { inner }).into());
+
+        Ok(())
+    })
+}
+
+/// Release a client. Groups created by the client remain valid.
+#[ffi_export]
+pub fn mls_client_free(client: Option<repr_c::Box<MlsClient>>) {
+    drop(client)
+}
+
+/// Generate a key package message that others can use to add this client
+/// to a group.
+///
+/// On success, `key_package` must be released with `mls_buffer_free`.
+#[ffi_export]
+pub fn mls_client_generate_key_package_message(
+    client: &MlsClient,
+    key_package: Out<'_, repr_c::Vec<u8>>,
+) -> MlsStatus {
+    catch(|| {
+        let message = client.inner.generate_key_package_message()?;
+        key_package.write(message.to_bytes()?.into());
+        Ok(())
+    })
+}
+
+/// Create and join a new group.
+///
+/// A random group ID is used if `group_id` is empty.
+///
+/// On success, `group` must be released with `mls_group_free`.
+#[ffi_export]
+pub fn mls_client_create_group(
+    client: &MlsClient,
+    group_id: c_slice::Ref<'_, u8>,
+    group: Out<'_, repr_c::Box<MlsGroup>>,
+) -> MlsStatus {
+    catch(|| {
+        let inner = if group_id.is_empty() {
+            client.inner.create_group(ExtensionList::new())?
+        } else {
+            client
+                .inner
+                .create_group_with_id(group_id.to_vec(), ExtensionList::new())?
+        };
+
+        group.write(Box::new(MlsGroup { inner }).into());
+
+        Ok(())
+    })
+}
+
+/// Join a group using a welcome message.
+///
+/// The welcome message must contain the ratchet tree extension.
+///
+/// On success, `group` must be released with `mls_group_free`.
+#[ffi_export]
+pub fn mls_client_join_group(
+    client: &MlsClient,
+    welcome_message: c_slice::Ref<'_, u8>,
+    group: Out<'_, repr_c::Box<MlsGroup>>,
+) -> MlsStatus {
+    catch(|| {
+        let welcome_message = MlsMessage::from_bytes(welcome_message.as_slice())?;
+        let (inner, _) = client.inner.join_group(None, &welcome_message)?;
+
+        group.write(Box::new(MlsGroup { inner }).into());
+
+        Ok(())
+    })
+}
+
+/// Load a group previously written with `mls_group_write_to_storage`.
+///
+/// Returns `MLS_STATUS_NOT_FOUND` if the group is not in storage.
+///
+/// On success, `group` must be released with `mls_group_free`.
+#[ffi_export]
+pub fn mls_client_load_group(
+    client: &MlsClient,
+    group_id: c_slice::Ref<'_, u8>,
+    group: Out<'_, repr_c::Box<MlsGroup>>,
+) -> MlsStatus {
+    catch(|| {
+        let inner = client.inner.load_group(group_id.as_slice())?;
+
+        group.write(Box::new(MlsGroup { inner }).into());
+
+        Ok(())
+    })
+}
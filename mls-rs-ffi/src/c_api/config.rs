@@ -0,0 +1,203 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use mls_rs::client_builder::{
+    BaseConfig, ClientBuilder, WithCryptoProvider, WithGroupStateStorage, WithIdentityProvider,
+    WithKeyPackageRepo,
+};
+use mls_rs::identity::basic::{BasicCredential, BasicIdentityProvider};
+use mls_rs::identity::{Credential, SigningIdentity};
+use mls_rs::storage_provider::in_memory::{InMemoryGroupStateStorage, InMemoryKeyPackageStorage};
+use mls_rs_crypto_openssl::OpensslCryptoProvider;
+use safer_ffi::prelude::*;
+
+#[cfg(feature = "x509")]
+use mls_rs_core::identity::{CertificateChain, DerCertificate};
+#[cfg(feature = "x509")]
+use mls_rs_crypto_openssl::x509::{X509Reader, X509Validator};
+#[cfg(feature = "x509")]
+use mls_rs_identity_x509::{SubjectIdentityExtractor, X509IdentityProvider};
+
+#[cfg(feature = "sqlite")]
+use mls_rs_provider_sqlite::{
+    connection_strategy::FileConnectionStrategy, SqLiteDataStorageEngine, SqLiteDataStorageError,
+};
+#[cfg(feature = "sqlite")]
+use std::path::Path;
+
+use super::error::Error;
+use super::identity::IdentityProviderWrapper;
+use super::storage::{GroupStateStorageWrapper, KeyPackageStorageWrapper};
+
+pub type CApiConfig = WithIdentityProvider<
+    IdentityProviderWrapper,
+    WithCryptoProvider<
+        OpensslCryptoProvider,
+        WithKeyPackageRepo<
+            KeyPackageStorageWrapper,
+            WithGroupStateStorage<GroupStateStorageWrapper, BaseConfig>,
+        >,
+    >,
+>;
+
+/// Where a client keeps group states and key package secrets.
+#[derive_ReprC]
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MlsStorageKind {
+    /// State is kept in memory and lost when the client is released.
+    InMemory = 0,
+    /// State is kept in the SQLite database at `storage_path`. Requires
+    /// the `sqlite` feature.
+    Sqlite = 1,
+}
+
+/// How the credentials of group members are validated.
+#[derive_ReprC]
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MlsIdentityKind {
+    /// Basic credentials holding an opaque identifier, which are not
+    /// validated.
+    Basic = 0,
+    /// X.509 certificate chains validated against `x509_trust_anchors`.
+    /// Requires the `x509` feature.
+    X509 = 1,
+}
+
+/// Configuration of an `MlsClient`.
+#[derive_ReprC]
+#[repr(C)]
+pub struct MlsClientConfig<'a> {
+    /// IANA value of the cipher suite used by the client, e.g. `1` for
+    /// MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519.
+    pub cipher_suite: u16,
+    pub storage: MlsStorageKind,
+    /// Path of the SQLite database. Required for
+    /// `MLS_STORAGE_KIND_SQLITE`, ignored otherwise.
+    pub storage_path: Option<char_p::Ref<'a>>,
+    pub identity: MlsIdentityKind,
+    /// PEM encoded trust anchors. Required for `MLS_IDENTITY_KIND_X509`,
+    /// ignored otherwise.
+    pub x509_trust_anchors: Option<char_p::Ref<'a>>,
+}
+
+impl MlsClientConfig<'_> {
+    /// Create a builder for a client using `credential_data` as basic
+    /// identifier or PEM encoded certificate chain, depending on the
+    /// configured identity kind.
+    pub(crate) fn builder(
+        &self,
+        credential_data: &[u8],
+        signature_public_key: &[u8],
+    ) -> Result<(ClientBuilder<CApiConfig>, SigningIdentity), Error> {
+        let (group_state_storage, key_package_storage) = self.storage()?;
+        let (identity_provider, credential) = self.identity(credential_data)?;
+
+        let signing_identity =
+            SigningIdentity::new(credential, signature_public_key.to_vec().into());
+
+        let builder = ClientBuilder::new()
+            .group_state_storage(group_state_storage)
+            .key_package_repo(key_package_storage)
+            .crypto_provider(OpensslCryptoProvider::new())
+            .identity_provider(identity_provider);
+
+        Ok((builder, signing_identity))
+    }
+
+    fn storage(&self) -> Result<(GroupStateStorageWrapper, KeyPackageStorageWrapper), Error> {
+        match self.storage {
+            MlsStorageKind::InMemory => Ok((
+                GroupStateStorageWrapper::InMemory(InMemoryGroupStateStorage::new()),
+                KeyPackageStorageWrapper::InMemory(InMemoryKeyPackageStorage::new()),
+            )),
+            #[cfg(feature = "sqlite")]
+            MlsStorageKind::Sqlite => {
+                let path = self
+                    .storage_path
+                    .ok_or(Error::InvalidArgument("storage_path is required"))?;
+
+                let storage_error = |err: SqLiteDataStorageError| Error::Storage(err.to_string());
+
+                let engine = SqLiteDataStorageEngine::new(FileConnectionStrategy::new(Path::new(
+                    path.to_str(),
+                )))
+                .map_err(storage_error)?;
+
+                let group_state_storage = engine.group_state_storage().map_err(storage_error)?;
+                let key_package_storage = engine.key_package_storage().map_err(storage_error)?;
+
+                Ok((
+                    GroupStateStorageWrapper::Sqlite(group_state_storage),
+                    KeyPackageStorageWrapper::Sqlite(key_package_storage),
+                ))
+            }
+            #[cfg(not(feature = "sqlite"))]
+            MlsStorageKind::Sqlite => Err(Error::Unsupported(
+                "SQLite storage requires the sqlite feature".into(),
+            )),
+        }
+    }
+
+    fn identity(
+        &self,
+        credential_data: &[u8],
+    ) -> Result<(IdentityProviderWrapper, Credential), Error> {
+        match self.identity {
+            MlsIdentityKind::Basic => Ok((
+                IdentityProviderWrapper::Basic(BasicIdentityProvider::new()),
+                BasicCredential::new(credential_data.to_vec()).into_credential(),
+            )),
+            #[cfg(feature = "x509")]
+            MlsIdentityKind::X509 => {
+                let trust_anchors = self
+                    .x509_trust_anchors
+                    .ok_or(Error::InvalidArgument("x509_trust_anchors is required"))?;
+
+                let trust_anchors = pem_certificates(trust_anchors.to_bytes())?
+                    .into_iter()
+                    .map(DerCertificate::from)
+                    .collect();
+
+                let validator = X509Validator::new(trust_anchors)
+                    .map_err(|err| Error::Identity(err.to_string()))?;
+
+                let provider = X509IdentityProvider::new(
+                    SubjectIdentityExtractor::new(0, X509Reader::new()),
+                    validator,
+                );
+
+                let chain = CertificateChain::from(pem_certificates(credential_data)?);
+
+                Ok((
+                    IdentityProviderWrapper::X509(provider),
+                    Credential::X509(chain),
+                ))
+            }
+            #[cfg(not(feature = "x509"))]
+            MlsIdentityKind::X509 => Err(Error::Unsupported(
+                "X.509 credentials require the x509 feature".into(),
+            )),
+        }
+    }
+}
+
+/// Decode the PEM encoded certificates in `pem` to DER.
+#[cfg(feature = "x509")]
+fn pem_certificates(pem: &[u8]) -> Result<Vec<Vec<u8>>, Error> {
+    let decoding_error = |err: openssl::error::ErrorStack| Error::Decoding(err.to_string());
+
+    let certificates = openssl::x509::X509::stack_from_pem(pem)
+        .map_err(decoding_error)?
+        .iter()
+        .map(|certificate| certificate.to_der().map_err(decoding_error))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if certificates.is_empty() {
+        return Err(Error::InvalidArgument("no PEM encoded certificate found"));
+    }
+
+    Ok(certificates)
+}
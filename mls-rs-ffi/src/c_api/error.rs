@@ -0,0 +1,162 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use std::cell::RefCell;
+use std::fmt::{self, Display};
+
+use mls_rs::error::{AnyError, IntoAnyError, MlsError};
+use safer_ffi::prelude::*;
+
+/// Status code returned by every fallible function of the C API.
+///
+/// Values are part of the ABI and are never reassigned. A description of
+/// the last failure on the calling thread is available from
+/// `mls_last_error_message`.
+#[derive_ReprC]
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MlsStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// An argument was invalid, e.g. a required string was missing.
+    InvalidArgument = 1,
+    /// The requested cipher suite or configuration is not supported by
+    /// this build.
+    Unsupported = 2,
+    /// A message or key could not be decoded.
+    Decoding = 3,
+    /// The crypto provider failed.
+    Crypto = 4,
+    /// A credential was rejected by the identity provider.
+    Identity = 5,
+    /// Reading from or writing to storage failed.
+    Storage = 6,
+    /// The requested group or key package was not found.
+    NotFound = 7,
+    /// The operation violates the MLS protocol in the current state.
+    Protocol = 8,
+}
+
+/// Error produced by the C API before being reported as an
+/// [`MlsStatus`].
+#[derive(Debug)]
+pub(crate) enum Error {
+    InvalidArgument(&'static str),
+    Unsupported(String),
+    Decoding(String),
+    Identity(String),
+    Storage(String),
+    Mls(MlsError),
+}
+
+impl Error {
+    fn status(&self) -> MlsStatus {
+        match self {
+            Error::InvalidArgument(_) => MlsStatus::InvalidArgument,
+            Error::Unsupported(_) => MlsStatus::Unsupported,
+            Error::Decoding(_) => MlsStatus::Decoding,
+            Error::Identity(_) => MlsStatus::Identity,
+            Error::Storage(_) => MlsStatus::Storage,
+            Error::Mls(err) => match err {
+                MlsError::UnsupportedCipherSuite(_) => MlsStatus::Unsupported,
+                MlsError::SerializationError(_) => MlsStatus::Decoding,
+                MlsError::CryptoProviderError(_) => MlsStatus::Crypto,
+                MlsError::IdentityProviderError(_) => MlsStatus::Identity,
+                MlsError::KeyPackageRepoError(_)
+                | MlsError::GroupStorageError(_)
+                | MlsError::PskStoreError(_) => MlsStatus::Storage,
+                MlsError::GroupNotFound | MlsError::WelcomeKeyPackageNotFound => {
+                    MlsStatus::NotFound
+                }
+                _ => MlsStatus::Protocol,
+            },
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidArgument(message) => write!(f, "invalid argument: {message}"),
+            Error::Unsupported(message) => write!(f, "unsupported: {message}"),
+            Error::Decoding(message) | Error::Identity(message) | Error::Storage(message) => {
+                f.write_str(message)
+            }
+            Error::Mls(err) => err.fmt(f),
+        }
+    }
+}
+
+impl From<MlsError> for Error {
+    fn from(err: MlsError) -> Self {
+        Error::Mls(err)
+    }
+}
+
+/// Error of the storage and identity providers selected by an
+/// [`MlsClientConfig`](super::config::MlsClientConfig).
+#[derive(Debug)]
+pub struct ProviderError(pub(crate) AnyError);
+
+impl ProviderError {
+    pub(crate) fn new<E: IntoAnyError>(err: E) -> Self {
+        Self(err.into_any_error())
+    }
+}
+
+impl Display for ProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for ProviderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl IntoAnyError for ProviderError {
+    fn into_dyn_error(self) -> Result<Box<dyn std::error::Error + Send + Sync>, Self> {
+        Ok(self.0.into())
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Run `f`, recording its error as the last error of the calling thread.
+pub(crate) fn catch<F>(f: F) -> MlsStatus
+where
+    F: FnOnce() -> Result<(), Error>,
+{
+    let result = f();
+
+    LAST_ERROR.with(|last_error| {
+        *last_error.borrow_mut() = result.as_ref().err().map(ToString::to_string)
+    });
+
+    result.map_or_else(|err| err.status(), |_| MlsStatus::Ok)
+}
+
+/// Description of the last failure on the calling thread.
+///
+/// Returns `NULL` if the last call succeeded. The string must be released
+/// with `mls_string_free`.
+#[ffi_export]
+pub fn mls_last_error_message() -> Option<char_p::Box> {
+    LAST_ERROR.with(|last_error| {
+        last_error
+            .borrow()
+            .as_ref()
+            .and_then(|message| message.replace('\0', " ").try_into().ok())
+    })
+}
+
+/// Release a string returned by the C API.
+#[ffi_export]
+pub fn mls_string_free(string: Option<char_p::Box>) {
+    drop(string)
+}
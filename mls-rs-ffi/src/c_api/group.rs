@@ -0,0 +1,282 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use mls_rs::group::{CommitOutput, ProposalSender, ReceivedMessage};
+use mls_rs::{Group, MlsMessage};
+use safer_ffi::prelude::*;
+
+use super::config::CApiConfig;
+use super::error::{catch, MlsStatus};
+
+/// An MLS group.
+///
+/// Created by `mls_client_create_group`, `mls_client_join_group` or
+/// `mls_client_load_group` and released with `mls_group_free`. A group
+/// must not be used by more than one thread at a time.
+#[derive_ReprC]
+#[repr(opaque)]
+pub struct MlsGroup {
+    pub(crate) inner: Group<CApiConfig>,
+}
+
+/// Messages resulting from a commit.
+///
+/// Released with `mls_commit_output_free`.
+#[derive_ReprC]
+#[repr(C)]
+pub struct MlsCommitOutput {
+    /// Commit message to send to the members of the group.
+    pub commit_message: repr_c::Vec<u8>,
+    /// Welcome message to send to the new members, empty if no member
+    /// was added.
+    pub welcome_message: repr_c::Vec<u8>,
+}
+
+impl MlsCommitOutput {
+    fn new(output: CommitOutput) -> Result<Self, mls_rs::error::MlsError> {
+        let welcome_message = match output.welcome_messages.first() {
+            Some(welcome_message) => welcome_message.to_bytes()?,
+            None => Vec::new(),
+        };
+
+        Ok(Self {
+            commit_message: output.commit_message.to_bytes()?.into(),
+            welcome_message: welcome_message.into(),
+        })
+    }
+}
+
+/// Kind of a message processed by `mls_group_process_incoming_message`.
+#[derive_ReprC]
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MlsReceivedMessageKind {
+    /// A decrypted application message.
+    ApplicationMessage = 0,
+    /// A commit that advanced the group to a new epoch.
+    Commit = 1,
+    /// A proposal that was stored until the next commit.
+    Proposal = 2,
+    /// A validated group info.
+    GroupInfo = 3,
+    /// A validated welcome message.
+    Welcome = 4,
+    /// A validated key package.
+    KeyPackage = 5,
+}
+
+/// Description of a processed message.
+///
+/// Released with `mls_received_message_free`.
+#[derive_ReprC]
+#[repr(C)]
+pub struct MlsReceivedMessage {
+    pub kind: MlsReceivedMessageKind,
+    /// Index of the member who sent the application message, commit or
+    /// proposal. `UINT32_MAX` if the sender is not a member.
+    pub sender_index: u32,
+    /// Plaintext of an application message, empty otherwise.
+    pub data: repr_c::Vec<u8>,
+    /// False if a commit removed this member from the group.
+    pub active: bool,
+}
+
+impl MlsReceivedMessage {
+    fn new(kind: MlsReceivedMessageKind, sender_index: u32) -> Self {
+        Self {
+            kind,
+            sender_index,
+            data: Vec::new().into(),
+            active: true,
+        }
+    }
+}
+
+impl From<ReceivedMessage> for MlsReceivedMessage {
+    fn from(message: ReceivedMessage) -> Self {
+        match message {
+            ReceivedMessage::ApplicationMessage(description) => MlsReceivedMessage {
+                data: description.data().to_vec().into(),
+                ..Self::new(
+                    MlsReceivedMessageKind::ApplicationMessage,
+                    description.sender_index,
+                )
+            },
+            ReceivedMessage::Commit(description) => MlsReceivedMessage {
+                active: description.state_update.is_active(),
+                ..Self::new(MlsReceivedMessageKind::Commit, description.committer)
+            },
+            ReceivedMessage::Proposal(description) => {
+                let sender_index = match description.sender {
+                    ProposalSender::Member(index) => index,
+                    _ => u32::MAX,
+                };
+
+                Self::new(MlsReceivedMessageKind::Proposal, sender_index)
+            }
+            ReceivedMessage::GroupInfo(_) => Self::new(MlsReceivedMessageKind::GroupInfo, u32::MAX),
+            ReceivedMessage::Welcome => Self::new(MlsReceivedMessageKind::Welcome, u32::MAX),
+            ReceivedMessage::KeyPackage(_) => {
+                Self::new(MlsReceivedMessageKind::KeyPackage, u32::MAX)
+            }
+        }
+    }
+}
+
+/// Release a group.
+#[ffi_export]
+pub fn mls_group_free(group: Option<repr_c::Box<MlsGroup>>) {
+    drop(group)
+}
+
+/// Release the messages of a commit.
+#[ffi_export]
+pub fn mls_commit_output_free(output: MlsCommitOutput) {
+    drop(output)
+}
+
+/// Release a processed message.
+#[ffi_export]
+pub fn mls_received_message_free(message: MlsReceivedMessage) {
+    drop(message)
+}
+
+/// Copy the ID of `group` into `group_id`, which must be released with
+/// `mls_buffer_free`.
+#[ffi_export]
+pub fn mls_group_id(group: &MlsGroup, group_id: Out<'_, repr_c::Vec<u8>>) {
+    group_id.write(group.inner.group_id().to_vec().into());
+}
+
+/// The current epoch of `group`.
+#[ffi_export]
+pub fn mls_group_current_epoch(group: &MlsGroup) -> u64 {
+    group.inner.current_epoch()
+}
+
+/// The index of this member in `group`.
+#[ffi_export]
+pub fn mls_group_current_member_index(group: &MlsGroup) -> u32 {
+    group.inner.current_member_index()
+}
+
+/// The number of members of `group`.
+#[ffi_export]
+pub fn mls_group_member_count(group: &MlsGroup) -> u32 {
+    group.inner.roster().members_iter().count() as u32
+}
+
+/// Write the current state of `group` to the storage of its client.
+#[ffi_export]
+pub fn mls_group_write_to_storage(group: &mut MlsGroup) -> MlsStatus {
+    catch(|| Ok(group.inner.write_to_storage()?))
+}
+
+/// Commit all received proposals, or create an empty commit updating the
+/// path secrets of this member.
+///
+/// The commit stays pending until it is applied with
+/// `mls_group_apply_pending_commit` or processed as an incoming message.
+/// On success, `output` must be released with `mls_commit_output_free`.
+#[ffi_export]
+pub fn mls_group_commit(group: &mut MlsGroup, output: Out<'_, MlsCommitOutput>) -> MlsStatus {
+    catch(|| {
+        let commit_output = group.inner.commit(Vec::new())?;
+        output.write(MlsCommitOutput::new(commit_output)?);
+        Ok(())
+    })
+}
+
+/// Commit the addition of the member with the given key package message.
+///
+/// See `mls_group_commit` for the handling of `output`.
+#[ffi_export]
+pub fn mls_group_add_member(
+    group: &mut MlsGroup,
+    key_package: c_slice::Ref<'_, u8>,
+    output: Out<'_, MlsCommitOutput>,
+) -> MlsStatus {
+    catch(|| {
+        let key_package = MlsMessage::from_bytes(key_package.as_slice())?;
+
+        let commit_output = group
+            .inner
+            .commit_builder()
+            .add_member(key_package)?
+            .build()?;
+
+        output.write(MlsCommitOutput::new(commit_output)?);
+
+        Ok(())
+    })
+}
+
+/// Commit the removal of the member at `index`.
+///
+/// See `mls_group_commit` for the handling of `output`.
+#[ffi_export]
+pub fn mls_group_remove_member(
+    group: &mut MlsGroup,
+    index: u32,
+    output: Out<'_, MlsCommitOutput>,
+) -> MlsStatus {
+    catch(|| {
+        let commit_output = group.inner.commit_builder().remove_member(index)?.build()?;
+        output.write(MlsCommitOutput::new(commit_output)?);
+        Ok(())
+    })
+}
+
+/// Apply the pending commit created by this member.
+#[ffi_export]
+pub fn mls_group_apply_pending_commit(group: &mut MlsGroup) -> MlsStatus {
+    catch(|| {
+        group.inner.apply_pending_commit()?;
+        Ok(())
+    })
+}
+
+/// Discard the pending commit created by this member.
+#[ffi_export]
+pub fn mls_group_clear_pending_commit(group: &mut MlsGroup) {
+    group.inner.clear_pending_commit()
+}
+
+/// Encrypt an application message for the members of `group`.
+///
+/// On success, `message` must be released with `mls_buffer_free`.
+#[ffi_export]
+pub fn mls_group_encrypt_application_message(
+    group: &mut MlsGroup,
+    plaintext: c_slice::Ref<'_, u8>,
+    message: Out<'_, repr_c::Vec<u8>>,
+) -> MlsStatus {
+    catch(|| {
+        let mls_message = group
+            .inner
+            .encrypt_application_message(plaintext.as_slice(), Vec::new())?;
+
+        message.write(mls_message.to_bytes()?.into());
+
+        Ok(())
+    })
+}
+
+/// Process a message received for `group`.
+///
+/// On success, `received` must be released with
+/// `mls_received_message_free`.
+#[ffi_export]
+pub fn mls_group_process_incoming_message(
+    group: &mut MlsGroup,
+    message: c_slice::Ref<'_, u8>,
+    received: Out<'_, MlsReceivedMessage>,
+) -> MlsStatus {
+    catch(|| {
+        let message = MlsMessage::from_bytes(message.as_slice())?;
+        let received_message = group.inner.process_incoming_message(message)?;
+        received.write(received_message.into());
+        Ok(())
+    })
+}
@@ -0,0 +1,116 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use mls_rs::identity::basic::BasicIdentityProvider;
+use mls_rs::identity::{CredentialType, SigningIdentity};
+use mls_rs::time::MlsTime;
+use mls_rs::{ExtensionList, IdentityProvider};
+
+#[cfg(feature = "x509")]
+use mls_rs_crypto_openssl::x509::{X509Reader, X509Validator};
+#[cfg(feature = "x509")]
+use mls_rs_identity_x509::{SubjectIdentityExtractor, X509IdentityProvider};
+
+use super::error::ProviderError;
+
+/// Identity provider validating X.509 credentials against the trust
+/// anchors of an [`MlsClientConfig`](super::config::MlsClientConfig).
+#[cfg(feature = "x509")]
+pub type OpensslX509IdentityProvider =
+    X509IdentityProvider<SubjectIdentityExtractor<X509Reader>, X509Validator>;
+
+/// Identity provider selected by an
+/// [`MlsClientConfig`](super::config::MlsClientConfig).
+#[derive(Clone, Debug)]
+pub enum IdentityProviderWrapper {
+    Basic(BasicIdentityProvider),
+    #[cfg(feature = "x509")]
+    X509(OpensslX509IdentityProvider),
+}
+
+impl IdentityProvider for IdentityProviderWrapper {
+    type Error = ProviderError;
+
+    fn validate_member(
+        &self,
+        signing_identity: &SigningIdentity,
+        timestamp: Option<MlsTime>,
+        extensions: Option<&ExtensionList>,
+    ) -> Result<(), Self::Error> {
+        match self {
+            Self::Basic(provider) => provider
+                .validate_member(signing_identity, timestamp, extensions)
+                .map_err(ProviderError::new),
+            #[cfg(feature = "x509")]
+            Self::X509(provider) => {
+                IdentityProvider::validate_member(provider, signing_identity, timestamp, extensions)
+                    .map_err(ProviderError::new)
+            }
+        }
+    }
+
+    fn validate_external_sender(
+        &self,
+        signing_identity: &SigningIdentity,
+        timestamp: Option<MlsTime>,
+        extensions: Option<&ExtensionList>,
+    ) -> Result<(), Self::Error> {
+        match self {
+            Self::Basic(provider) => provider
+                .validate_external_sender(signing_identity, timestamp, extensions)
+                .map_err(ProviderError::new),
+            #[cfg(feature = "x509")]
+            Self::X509(provider) => IdentityProvider::validate_external_sender(
+                provider,
+                signing_identity,
+                timestamp,
+                extensions,
+            )
+            .map_err(ProviderError::new),
+        }
+    }
+
+    fn identity(
+        &self,
+        signing_identity: &SigningIdentity,
+        extensions: &ExtensionList,
+    ) -> Result<Vec<u8>, Self::Error> {
+        match self {
+            Self::Basic(provider) => provider
+                .identity(signing_identity, extensions)
+                .map_err(ProviderError::new),
+            #[cfg(feature = "x509")]
+            Self::X509(provider) => {
+                IdentityProvider::identity(provider, signing_identity, extensions)
+                    .map_err(ProviderError::new)
+            }
+        }
+    }
+
+    fn valid_successor(
+        &self,
+        predecessor: &SigningIdentity,
+        successor: &SigningIdentity,
+        extensions: &ExtensionList,
+    ) -> Result<bool, Self::Error> {
+        match self {
+            Self::Basic(provider) => provider
+                .valid_successor(predecessor, successor, extensions)
+                .map_err(ProviderError::new),
+            #[cfg(feature = "x509")]
+            Self::X509(provider) => {
+                IdentityProvider::valid_successor(provider, predecessor, successor, extensions)
+                    .map_err(ProviderError::new)
+            }
+        }
+    }
+
+    fn supported_types(&self) -> Vec<CredentialType> {
+        match self {
+            Self::Basic(provider) => provider.supported_types(),
+            #[cfg(feature = "x509")]
+            Self::X509(provider) => IdentityProvider::supported_types(provider),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use mls_rs::storage_provider::in_memory::{InMemoryGroupStateStorage, InMemoryKeyPackageStorage};
+use mls_rs_core::group::{EpochRecord, GroupState, GroupStateStorage};
+use mls_rs_core::key_package::{KeyPackageData, KeyPackageStorage};
+use mls_rs_core::mls_rs_codec::{MlsDecode, MlsEncode};
+
+#[cfg(feature = "sqlite")]
+use mls_rs_provider_sqlite::storage::{SqLiteGroupStateStorage, SqLiteKeyPackageStorage};
+
+use super::error::ProviderError;
+
+/// Group state storage selected by an
+/// [`MlsClientConfig`](super::config::MlsClientConfig).
+#[derive(Clone, Debug)]
+pub enum GroupStateStorageWrapper {
+    InMemory(InMemoryGroupStateStorage),
+    #[cfg(feature = "sqlite")]
+    Sqlite(SqLiteGroupStateStorage),
+}
+
+impl GroupStateStorage for GroupStateStorageWrapper {
+    type Error = ProviderError;
+
+    fn state<T>(&self, group_id: &[u8]) -> Result<Option<T>, Self::Error>
+    where
+        T: GroupState + MlsEncode + MlsDecode,
+    {
+        match self {
+            Self::InMemory(storage) => {
+                GroupStateStorage::state(storage, group_id).map_err(ProviderError::new)
+            }
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(storage) => {
+                GroupStateStorage::state(storage, group_id).map_err(ProviderError::new)
+            }
+        }
+    }
+
+    fn epoch<T>(&self, group_id: &[u8], epoch_id: u64) -> Result<Option<T>, Self::Error>
+    where
+        T: EpochRecord + MlsEncode + MlsDecode,
+    {
+        match self {
+            Self::InMemory(storage) => {
+                GroupStateStorage::epoch(storage, group_id, epoch_id).map_err(ProviderError::new)
+            }
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(storage) => {
+                GroupStateStorage::epoch(storage, group_id, epoch_id).map_err(ProviderError::new)
+            }
+        }
+    }
+
+    fn write<ST, ET>(
+        &mut self,
+        state: ST,
+        epoch_inserts: Vec<ET>,
+        epoch_updates: Vec<ET>,
+    ) -> Result<(), Self::Error>
+    where
+        ST: GroupState + MlsEncode + MlsDecode + Send + Sync,
+        ET: EpochRecord + MlsEncode + MlsDecode + Send + Sync,
+    {
+        match self {
+            Self::InMemory(storage) => {
+                GroupStateStorage::write(storage, state, epoch_inserts, epoch_updates)
+                    .map_err(ProviderError::new)
+            }
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(storage) => {
+                GroupStateStorage::write(storage, state, epoch_inserts, epoch_updates)
+                    .map_err(ProviderError::new)
+            }
+        }
+    }
+
+    fn max_epoch_id(&self, group_id: &[u8]) -> Result<Option<u64>, Self::Error> {
+        match self {
+            Self::InMemory(storage) => {
+                GroupStateStorage::max_epoch_id(storage, group_id).map_err(ProviderError::new)
+            }
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(storage) => {
+                GroupStateStorage::max_epoch_id(storage, group_id).map_err(ProviderError::new)
+            }
+        }
+    }
+}
+
+/// Key package storage selected by an
+/// [`MlsClientConfig`](super::config::MlsClientConfig).
+#[derive(Clone, Debug)]
+pub enum KeyPackageStorageWrapper {
+    InMemory(InMemoryKeyPackageStorage),
+    #[cfg(feature = "sqlite")]
+    Sqlite(SqLiteKeyPackageStorage),
+}
+
+impl KeyPackageStorage for KeyPackageStorageWrapper {
+    type Error = ProviderError;
+
+    fn delete(&mut self, id: &[u8]) -> Result<(), Self::Error> {
+        match self {
+            Self::InMemory(storage) => {
+                KeyPackageStorage::delete(storage, id).map_err(ProviderError::new)
+            }
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(storage) => {
+                KeyPackageStorage::delete(storage, id).map_err(ProviderError::new)
+            }
+        }
+    }
+
+    fn insert(&mut self, id: Vec<u8>, pkg: KeyPackageData) -> Result<(), Self::Error> {
+        match self {
+            Self::InMemory(storage) => {
+                KeyPackageStorage::insert(storage, id, pkg).map_err(ProviderError::new)
+            }
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(storage) => {
+                KeyPackageStorage::insert(storage, id, pkg).map_err(ProviderError::new)
+            }
+        }
+    }
+
+    fn get(&self, id: &[u8]) -> Result<Option<KeyPackageData>, Self::Error> {
+        match self {
+            Self::InMemory(storage) => {
+                KeyPackageStorage::get(storage, id).map_err(ProviderError::new)
+            }
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(storage) => {
+                KeyPackageStorage::get(storage, id).map_err(ProviderError::new)
+            }
+        }
+    }
+}
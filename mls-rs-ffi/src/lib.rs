@@ -2,7 +2,15 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-#[cfg(all(feature = "openssl", feature = "sqlite", feature = "x509"))]
+// safer-ffi derives header names from the type name of `Client<OpensslSqlMlsConfig>`, whose
+// nested generics it cannot shorten, so these specializations are left out of header builds.
+// The C API in `c_api` covers the same functionality.
+#[cfg(all(
+    feature = "openssl",
+    feature = "sqlite",
+    feature = "x509",
+    not(feature = "headers")
+))]
 mod openssl_sqlite {
     use mls_rs::client_builder::{BaseConfig, WithCryptoProvider, WithIdentityProvider};
     use mls_rs_crypto_openssl::{
@@ -20,5 +28,13 @@ mod openssl_sqlite {
     safer_ffi_gen::specialize!(OpensslSqlMlsGroup = mls_rs::group::Group<OpensslSqlMlsConfig>);
 }
 
-#[cfg(all(feature = "openssl", feature = "sqlite", feature = "x509"))]
+#[cfg(all(
+    feature = "openssl",
+    feature = "sqlite",
+    feature = "x509",
+    not(feature = "headers")
+))]
 pub use openssl_sqlite::*;
+
+#[cfg(all(feature = "openssl", not(mls_build_async)))]
+pub mod c_api;
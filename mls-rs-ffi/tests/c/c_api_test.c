@@ -0,0 +1,217 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "mls_rs.h"
+
+#define CIPHER_SUITE 1
+
+#define CHECK(call)                                                           \
+    do {                                                                      \
+        MlsStatus_t status = (call);                                          \
+        if (status != MLS_STATUS_OK) {                                        \
+            char *message = mls_last_error_message();                         \
+            fprintf(stderr, "%s:%d: %s failed with status %d: %s\n",         \
+                    __FILE__, __LINE__, #call, status,                        \
+                    message ? message : "(none)");                            \
+            mls_string_free(message);                                         \
+            exit(1);                                                          \
+        }                                                                     \
+    } while (0)
+
+#define ASSERT(condition)                                                     \
+    do {                                                                      \
+        if (!(condition)) {                                                   \
+            fprintf(stderr, "%s:%d: assertion failed: %s\n", __FILE__,      \
+                    __LINE__, #condition);                                    \
+            exit(1);                                                          \
+        }                                                                     \
+    } while (0)
+
+static slice_ref_uint8_t slice_of(Vec_uint8_t const *buffer) {
+    slice_ref_uint8_t slice = {buffer->ptr, buffer->len};
+    return slice;
+}
+
+static slice_ref_uint8_t slice_of_str(char const *string) {
+    slice_ref_uint8_t slice = {(uint8_t const *)string, strlen(string)};
+    return slice;
+}
+
+static MlsClient_t *new_client(MlsClientConfig_t const *config,
+                               char const *name) {
+    Vec_uint8_t secret_key;
+    Vec_uint8_t public_key;
+    MlsClient_t *client = NULL;
+
+    CHECK(mls_generate_signature_keypair(CIPHER_SUITE, &secret_key,
+                                         &public_key));
+
+    CHECK(mls_client_new(config, slice_of_str(name), slice_of(&secret_key),
+                         slice_of(&public_key), &client));
+
+    mls_buffer_free(secret_key);
+    mls_buffer_free(public_key);
+
+    return client;
+}
+
+static void test_group_lifecycle(void) {
+    MlsClientConfig_t config = {
+        .cipher_suite = CIPHER_SUITE,
+        .storage = MLS_STORAGE_KIND_IN_MEMORY,
+        .storage_path = NULL,
+        .identity = MLS_IDENTITY_KIND_BASIC,
+        .x509_trust_anchors = NULL,
+    };
+
+    MlsClient_t *alice = new_client(&config, "alice");
+    MlsClient_t *bob = new_client(&config, "bob");
+
+    MlsGroup_t *alice_group = NULL;
+    slice_ref_uint8_t group_id = slice_of_str("group");
+    CHECK(mls_client_create_group(alice, group_id, &alice_group));
+    ASSERT(mls_group_current_epoch(alice_group) == 0);
+
+    Vec_uint8_t key_package;
+    CHECK(mls_client_generate_key_package_message(bob, &key_package));
+
+    MlsCommitOutput_t commit;
+    CHECK(mls_group_add_member(alice_group, slice_of(&key_package), &commit));
+    ASSERT(commit.welcome_message.len > 0);
+    CHECK(mls_group_apply_pending_commit(alice_group));
+    ASSERT(mls_group_current_epoch(alice_group) == 1);
+
+    MlsGroup_t *bob_group = NULL;
+    CHECK(mls_client_join_group(bob, slice_of(&commit.welcome_message),
+                                &bob_group));
+    ASSERT(mls_group_member_count(bob_group) == 2);
+    ASSERT(mls_group_current_member_index(bob_group) == 1);
+
+    Vec_uint8_t bob_group_id;
+    mls_group_id(bob_group, &bob_group_id);
+    ASSERT(bob_group_id.len == group_id.len);
+    ASSERT(memcmp(bob_group_id.ptr, group_id.ptr, group_id.len) == 0);
+
+    Vec_uint8_t message;
+    CHECK(mls_group_encrypt_application_message(
+        alice_group, slice_of_str("hello"), &message));
+
+    MlsReceivedMessage_t received;
+    CHECK(mls_group_process_incoming_message(bob_group, slice_of(&message),
+                                             &received));
+    ASSERT(received.kind == MLS_RECEIVED_MESSAGE_KIND_APPLICATION_MESSAGE);
+    ASSERT(received.sender_index == 0);
+    ASSERT(received.data.len == 5);
+    ASSERT(memcmp(received.data.ptr, "hello", 5) == 0);
+    mls_received_message_free(received);
+    mls_buffer_free(message);
+
+    MlsCommitOutput_t removal;
+    CHECK(mls_group_remove_member(alice_group, 1, &removal));
+    ASSERT(removal.welcome_message.len == 0);
+    CHECK(mls_group_process_incoming_message(
+        bob_group, slice_of(&removal.commit_message), &received));
+    ASSERT(received.kind == MLS_RECEIVED_MESSAGE_KIND_COMMIT);
+    ASSERT(!received.active);
+    mls_received_message_free(received);
+    mls_commit_output_free(removal);
+
+    mls_buffer_free(bob_group_id);
+    mls_commit_output_free(commit);
+    mls_buffer_free(key_package);
+    mls_group_free(bob_group);
+    mls_group_free(alice_group);
+    mls_client_free(bob);
+    mls_client_free(alice);
+}
+
+static void test_errors(void) {
+    MlsClientConfig_t config = {
+        .cipher_suite = CIPHER_SUITE,
+        .storage = MLS_STORAGE_KIND_IN_MEMORY,
+        .identity = MLS_IDENTITY_KIND_BASIC,
+    };
+
+    MlsClient_t *client = new_client(&config, "alice");
+    MlsGroup_t *group = NULL;
+
+    ASSERT(mls_client_join_group(client, slice_of_str("garbage"), &group) ==
+           MLS_STATUS_DECODING);
+
+    char *message = mls_last_error_message();
+    ASSERT(message != NULL);
+    mls_string_free(message);
+
+    ASSERT(mls_client_load_group(client, slice_of_str("missing"), &group) ==
+           MLS_STATUS_NOT_FOUND);
+
+    Vec_uint8_t secret_key;
+    Vec_uint8_t public_key;
+    ASSERT(mls_generate_signature_keypair(0xffff, &secret_key, &public_key) ==
+           MLS_STATUS_UNSUPPORTED);
+
+    MlsClientConfig_t sqlite_config = {
+        .cipher_suite = CIPHER_SUITE,
+        .storage = MLS_STORAGE_KIND_SQLITE,
+        .storage_path = NULL,
+        .identity = MLS_IDENTITY_KIND_BASIC,
+    };
+    MlsClient_t *sqlite_client = NULL;
+    ASSERT(mls_client_new(&sqlite_config, slice_of_str("alice"),
+                          slice_of_str(""), slice_of_str(""),
+                          &sqlite_client) == MLS_STATUS_INVALID_ARGUMENT);
+
+    CHECK(mls_client_create_group(client, slice_of_str(""), &group));
+    ASSERT(mls_last_error_message() == NULL);
+
+    mls_group_free(group);
+    mls_client_free(client);
+}
+
+static void test_sqlite_storage(char const *database) {
+    MlsClientConfig_t config = {
+        .cipher_suite = CIPHER_SUITE,
+        .storage = MLS_STORAGE_KIND_SQLITE,
+        .storage_path = database,
+        .identity = MLS_IDENTITY_KIND_BASIC,
+    };
+
+    MlsClient_t *client = new_client(&config, "alice");
+    MlsGroup_t *group = NULL;
+    CHECK(mls_client_create_group(client, slice_of_str("stored"), &group));
+
+    MlsCommitOutput_t commit;
+    CHECK(mls_group_commit(group, &commit));
+    CHECK(mls_group_apply_pending_commit(group));
+    CHECK(mls_group_write_to_storage(group));
+    mls_commit_output_free(commit);
+    mls_group_free(group);
+    mls_client_free(client);
+
+    MlsClient_t *restored_client = new_client(&config, "alice");
+    MlsGroup_t *restored_group = NULL;
+    CHECK(mls_client_load_group(restored_client, slice_of_str("stored"),
+                                &restored_group));
+    ASSERT(mls_group_current_epoch(restored_group) == 1);
+
+    mls_group_free(restored_group);
+    mls_client_free(restored_client);
+}
+
+int main(int argc, char **argv) {
+    if (argc != 2) {
+        fprintf(stderr, "usage: %s DATABASE\n", argv[0]);
+        return 2;
+    }
+
+    test_group_lifecycle();
+    test_errors();
+    test_sqlite_storage(argv[1]);
+
+    return 0;
+}
@@ -0,0 +1,65 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+//! Compile `tests/c/c_api_test.c` against the generated header and the
+//! `cdylib` built by Cargo, then run it.
+
+#![cfg(all(unix, not(mls_build_async)))]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Directory containing the libraries built for this test run, i.e. the
+/// parent of the `deps` directory holding the test executable.
+fn library_dir() -> PathBuf {
+    let exe = std::env::current_exe().unwrap();
+    let deps_dir = exe.parent().unwrap();
+    deps_dir.parent().unwrap().to_path_buf()
+}
+
+fn compile(source: &Path, include_dir: &Path, output: &Path) {
+    let library_dir = library_dir();
+
+    let compiler = std::env::var_os("CC").unwrap_or_else(|| "cc".into());
+
+    let status = Command::new(compiler)
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(include_dir)
+        .arg(source)
+        .arg("-o")
+        .arg(output)
+        .arg("-L")
+        .arg(&library_dir)
+        .arg(format!("-Wl,-rpath,{}", library_dir.display()))
+        .arg("-lmls_rs_ffi")
+        .status()
+        .unwrap();
+
+    assert!(status.success(), "failed to compile {}", source.display());
+}
+
+#[test]
+fn c_api_test_program_succeeds() {
+    let tmp_dir = tempfile::TempDir::with_prefix("mls-rs-ffi-c-api-").unwrap();
+
+    mls_rs_ffi::c_api::generate_headers(&tmp_dir.path().join("mls_rs.h")).unwrap();
+
+    let source = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/c/c_api_test.c");
+    let program = tmp_dir.path().join("c_api_test");
+    compile(&source, tmp_dir.path(), &program);
+
+    let output = Command::new(&program)
+        .arg(tmp_dir.path().join("c_api_test.sqlite"))
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -2,6 +2,7 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
+use alloc::string::String;
 use mls_rs_core::{error::AnyError, identity::CredentialType};
 
 #[derive(Debug)]
@@ -20,6 +21,11 @@ pub enum X509IdentityError {
     InvalidOffset,
     #[cfg_attr(feature = "std", error("empty certificate chain"))]
     EmptyCertificateChain,
+    #[cfg_attr(
+        feature = "std",
+        error("no subject alt name URI starting with {0} found")
+    )]
+    SanIdentityNotFound(String),
     #[cfg_attr(feature = "std", error(transparent))]
     CredentialEncodingError(AnyError),
     #[cfg_attr(feature = "std", error(transparent))]
@@ -30,6 +36,8 @@ pub enum X509IdentityError {
     X509ValidationError(AnyError),
     #[cfg_attr(feature = "std", error(transparent))]
     IdentityWarningProviderError(AnyError),
+    #[cfg_attr(feature = "std", error(transparent))]
+    OcspStapleDecodingError(AnyError),
 }
 
 impl mls_rs_core::error::IntoAnyError for X509IdentityError {
@@ -115,7 +115,7 @@ where
     }
 }
 
-fn get_certificate(
+pub(crate) fn get_certificate(
     certificate_chain: &CertificateChain,
     offset: usize,
 ) -> Result<&DerCertificate, X509IdentityError> {
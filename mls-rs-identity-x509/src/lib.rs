@@ -8,6 +8,8 @@ extern crate alloc;
 mod error;
 mod identity_extractor;
 mod provider;
+mod revocation;
+mod san_identity_extractor;
 mod traits;
 mod util;
 
@@ -17,6 +19,8 @@ use core::fmt::{self, Debug};
 pub use error::*;
 pub use identity_extractor::*;
 pub use provider::*;
+pub use revocation::*;
+pub use san_identity_extractor::*;
 pub use traits::*;
 
 pub use mls_rs_core::identity::{CertificateChain, DerCertificate};
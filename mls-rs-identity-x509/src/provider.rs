@@ -2,7 +2,9 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use crate::{util::credential_to_chain, CertificateChain, X509IdentityError};
+use crate::{
+    util::credential_to_chain, CertificateChain, DerOcspResponse, OcspStapleExt, X509IdentityError,
+};
 use alloc::vec;
 use alloc::vec::Vec;
 use mls_rs_core::{
@@ -50,6 +52,21 @@ pub trait X509CredentialValidator {
         chain: &CertificateChain,
         timestamp: Option<MlsTime>,
     ) -> Result<SignaturePublicKey, Self::Error>;
+
+    /// Validate a certificate chain, taking into account OCSP responses
+    /// stapled by the owner of the chain.
+    ///
+    /// The default implementation ignores `ocsp_responses` and calls
+    /// [`validate_chain`](X509CredentialValidator::validate_chain).
+    fn validate_chain_with_ocsp(
+        &self,
+        chain: &CertificateChain,
+        timestamp: Option<MlsTime>,
+        ocsp_responses: &[DerOcspResponse],
+    ) -> Result<SignaturePublicKey, Self::Error> {
+        let _ = ocsp_responses;
+        self.validate_chain(chain, timestamp)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -99,6 +116,35 @@ where
         Ok(())
     }
 
+    /// Determine if a certificate is valid, taking into account the
+    /// [`OcspStapleExt`] found in `leaf_extensions`, if any.
+    pub fn validate_with_leaf_extensions(
+        &self,
+        signing_identity: &mls_rs_core::identity::SigningIdentity,
+        timestamp: Option<mls_rs_core::time::MlsTime>,
+        leaf_extensions: &ExtensionList,
+    ) -> Result<(), X509IdentityError> {
+        let Some(staple) = leaf_extensions
+            .get_as::<OcspStapleExt>()
+            .map_err(|e| X509IdentityError::OcspStapleDecodingError(e.into_any_error()))?
+        else {
+            return self.validate(signing_identity, timestamp);
+        };
+
+        let chain = credential_to_chain(&signing_identity.credential)?;
+
+        let leaf_public_key = self
+            .validator
+            .validate_chain_with_ocsp(&chain, timestamp, &staple.responses)
+            .map_err(|e| X509IdentityError::X509ValidationError(e.into_any_error()))?;
+
+        if leaf_public_key != signing_identity.signature_key {
+            return Err(X509IdentityError::SignatureKeyMismatch);
+        }
+
+        Ok(())
+    }
+
     /// Produce a unique identity value to represent the entity controlling a
     /// certificate credential within an MLS group.
     pub fn identity(
@@ -152,6 +198,16 @@ where
         self.validate(signing_identity, timestamp)
     }
 
+    async fn validate_member_with_leaf_extensions(
+        &self,
+        signing_identity: &mls_rs_core::identity::SigningIdentity,
+        timestamp: Option<MlsTime>,
+        _extensions: Option<&ExtensionList>,
+        leaf_extensions: &ExtensionList,
+    ) -> Result<(), Self::Error> {
+        self.validate_with_leaf_extensions(signing_identity, timestamp, leaf_extensions)
+    }
+
     async fn validate_external_sender(
         &self,
         signing_identity: &mls_rs_core::identity::SigningIdentity,
@@ -185,15 +241,18 @@ where
 
 #[cfg(all(test, feature = "std"))]
 mod tests {
-    use mls_rs_core::{crypto::SignaturePublicKey, identity::CredentialType, time::MlsTime};
+    use mls_rs_core::{
+        crypto::SignaturePublicKey, extension::ExtensionList, identity::CredentialType,
+        time::MlsTime,
+    };
 
     use crate::{
         test_utils::{
             test_certificate_chain, test_signing_identity, test_signing_identity_with_chain,
             TestError,
         },
-        MockX509CredentialValidator, MockX509IdentityExtractor, X509IdentityError,
-        X509IdentityProvider,
+        DerOcspResponse, MockX509CredentialValidator, MockX509IdentityExtractor, OcspStapleExt,
+        X509IdentityError, X509IdentityProvider,
     };
 
     use alloc::vec;
@@ -284,4 +343,52 @@ mod tests {
             Err(X509IdentityError::X509ValidationError(_))
         )
     }
+
+    #[test]
+    fn test_validation_with_ocsp_staple() {
+        let chain = test_certificate_chain();
+
+        let test_signing_identity = test_signing_identity_with_chain(chain.clone());
+
+        let responses = vec![DerOcspResponse::new(vec![1, 2, 3])];
+
+        let mut leaf_extensions = ExtensionList::new();
+
+        leaf_extensions
+            .set_from(OcspStapleExt::new(responses.clone()))
+            .unwrap();
+
+        let test_provider = test_setup(|_, validator| {
+            let validation_result = test_signing_identity.signature_key.clone();
+            let (chain, responses) = (chain.clone(), responses.clone());
+
+            validator
+                .expect_validate_chain_with_ocsp()
+                .once()
+                .withf(move |c, t, r| c == &chain && t.is_none() && r == responses.as_slice())
+                .return_once_st(|_, _, _| Ok(validation_result));
+        });
+
+        test_provider
+            .validate_with_leaf_extensions(&test_signing_identity, None, &leaf_extensions)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_validation_without_ocsp_staple() {
+        let test_signing_identity = test_signing_identity();
+
+        let test_provider = test_setup(|_, validator| {
+            let validation_result = test_signing_identity.signature_key.clone();
+
+            validator
+                .expect_validate_chain()
+                .once()
+                .return_once_st(|_, _| Ok(validation_result));
+        });
+
+        test_provider
+            .validate_with_leaf_extensions(&test_signing_identity, None, &ExtensionList::new())
+            .unwrap();
+    }
 }
@@ -0,0 +1,160 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::vec::Vec;
+use core::{
+    convert::Infallible,
+    fmt::{self, Debug},
+    ops::Deref,
+};
+
+use mls_rs_core::{
+    extension::{ExtensionType, MlsCodecExtension},
+    mls_rs_codec::{self, MlsDecode, MlsEncode, MlsSize},
+};
+
+use crate::X509CrlProvider;
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+/// X.509 certificate revocation list in DER format.
+pub struct DerCertificateRevocationList(Vec<u8>);
+
+impl Debug for DerCertificateRevocationList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        mls_rs_core::debug::pretty_bytes(&self.0)
+            .named("DerCertificateRevocationList")
+            .fmt(f)
+    }
+}
+
+impl DerCertificateRevocationList {
+    /// Create a DER certificate revocation list from raw bytes.
+    pub fn new(data: Vec<u8>) -> Self {
+        Self(data)
+    }
+
+    /// Convert this certificate revocation list into raw bytes.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl From<Vec<u8>> for DerCertificateRevocationList {
+    fn from(data: Vec<u8>) -> Self {
+        Self(data)
+    }
+}
+
+impl Deref for DerCertificateRevocationList {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for DerCertificateRevocationList {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl X509CrlProvider for Vec<DerCertificateRevocationList> {
+    type Error = Infallible;
+
+    fn revocation_lists(&self) -> Result<Vec<DerCertificateRevocationList>, Self::Error> {
+        Ok(self.clone())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, MlsSize, MlsEncode, MlsDecode)]
+/// OCSP response, as defined in RFC 6960, in DER format.
+pub struct DerOcspResponse(#[mls_codec(with = "mls_rs_codec::byte_vec")] Vec<u8>);
+
+impl Debug for DerOcspResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        mls_rs_core::debug::pretty_bytes(&self.0)
+            .named("DerOcspResponse")
+            .fmt(f)
+    }
+}
+
+impl DerOcspResponse {
+    /// Create a DER OCSP response from raw bytes.
+    pub fn new(data: Vec<u8>) -> Self {
+        Self(data)
+    }
+
+    /// Convert this OCSP response into raw bytes.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl From<Vec<u8>> for DerOcspResponse {
+    fn from(data: Vec<u8>) -> Self {
+        Self(data)
+    }
+}
+
+impl Deref for DerOcspResponse {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for DerOcspResponse {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
+/// Leaf node extension carrying OCSP responses stapled by the owner of an
+/// X.509 credential.
+///
+/// Responses may cover any certificate of the credential's chain. Like any
+/// other leaf node extension, its type must be listed in the capabilities
+/// of the leaf node that carries it.
+pub struct OcspStapleExt {
+    pub responses: Vec<DerOcspResponse>,
+}
+
+impl OcspStapleExt {
+    /// Extension type of [`OcspStapleExt`], allocated from the private use range.
+    pub const EXTENSION_TYPE: ExtensionType = ExtensionType::new(0xF0C5);
+
+    /// Create a new staple extension from a list of OCSP responses.
+    pub fn new(responses: Vec<DerOcspResponse>) -> Self {
+        Self { responses }
+    }
+}
+
+impl MlsCodecExtension for OcspStapleExt {
+    fn extension_type() -> ExtensionType {
+        Self::EXTENSION_TYPE
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use mls_rs_core::extension::ExtensionList;
+
+    use super::{DerOcspResponse, OcspStapleExt};
+
+    #[test]
+    fn ocsp_staple_round_trip() {
+        let ext = OcspStapleExt::new(vec![
+            DerOcspResponse::new(vec![1, 2, 3]),
+            DerOcspResponse::new(vec![4, 5]),
+        ]);
+
+        let mut extensions = ExtensionList::new();
+        extensions.set_from(ext.clone()).unwrap();
+
+        assert_eq!(extensions.get_as::<OcspStapleExt>().unwrap(), Some(ext));
+    }
+}
@@ -0,0 +1,259 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::{string::String, vec::Vec};
+use mls_rs_core::{error::IntoAnyError, identity::CertificateChain};
+
+use crate::{
+    identity_extractor::get_certificate, DerCertificate, SubjectAltName, X509CertificateReader,
+    X509IdentityError, X509IdentityExtractor,
+};
+
+#[derive(Debug, Clone)]
+/// A utility to determine unique identity for use with MLS by reading
+/// the URI subject alt names of a certificate.
+///
+/// The user controlling a certificate is identified by the first URI subject
+/// alt name starting with a configurable prefix, e.g. `im:`. Optionally, the
+/// device holding the certificate is identified by a second URI with another
+/// prefix. In that case, the MLS identity is the user URI followed by a zero
+/// byte and the device URI, so that every device of a user is a distinct
+/// group member.
+///
+/// A successor certificate is accepted only if it identifies the same user,
+/// which allows renewing the certificate of a device, or replacing the
+/// device, without changing the user.
+pub struct SanIdentityExtractor<R: X509CertificateReader> {
+    offset: usize,
+    user_uri_prefix: String,
+    device_uri_prefix: Option<String>,
+    reader: R,
+}
+
+impl<R> SanIdentityExtractor<R>
+where
+    R: X509CertificateReader,
+{
+    /// Create a new identity extractor that identifies users by the URI
+    /// subject alt name starting with `user_uri_prefix`.
+    ///
+    /// `offset` is used to determine which certificate in a [`CertificateChain`]
+    /// should be used to evaluate identity. A value of 0 indicates to use the
+    /// leaf (first value) of the chain.
+    pub fn new(offset: usize, user_uri_prefix: &str, reader: R) -> Self {
+        Self {
+            offset,
+            user_uri_prefix: user_uri_prefix.into(),
+            device_uri_prefix: None,
+            reader,
+        }
+    }
+
+    /// Require certificates to also identify a device by the URI subject alt
+    /// name starting with `device_uri_prefix`.
+    pub fn with_device_uri_prefix(self, device_uri_prefix: &str) -> Self {
+        Self {
+            device_uri_prefix: Some(device_uri_prefix.into()),
+            ..self
+        }
+    }
+
+    /// Get the URI identifying the user controlling `certificate_chain`.
+    pub fn user_identity(
+        &self,
+        certificate_chain: &CertificateChain,
+    ) -> Result<String, X509IdentityError> {
+        let cert = get_certificate(certificate_chain, self.offset)?;
+        let uris = self.uris(cert)?;
+
+        find_uri(&uris, &self.user_uri_prefix)
+    }
+
+    /// Get a unique identifier for a `certificate_chain`.
+    pub fn identity(
+        &self,
+        certificate_chain: &CertificateChain,
+    ) -> Result<Vec<u8>, X509IdentityError> {
+        let cert = get_certificate(certificate_chain, self.offset)?;
+        let uris = self.uris(cert)?;
+
+        let mut identity = find_uri(&uris, &self.user_uri_prefix)?.into_bytes();
+
+        if let Some(device_uri_prefix) = &self.device_uri_prefix {
+            identity.push(0);
+            identity.extend(find_uri(&uris, device_uri_prefix)?.into_bytes());
+        }
+
+        Ok(identity)
+    }
+
+    /// Determine if `successor` identifies the same user as `predecessor`,
+    /// indicating that `predecessor` and `successor` are controlled by the
+    /// same entity.
+    pub fn valid_successor(
+        &self,
+        predecessor: &CertificateChain,
+        successor: &CertificateChain,
+    ) -> Result<bool, X509IdentityError> {
+        Ok(self.user_identity(predecessor)? == self.user_identity(successor)?)
+    }
+
+    fn uris(&self, certificate: &DerCertificate) -> Result<Vec<String>, X509IdentityError> {
+        Ok(self
+            .reader
+            .subject_alt_names(certificate)
+            .map_err(|e| X509IdentityError::X509ReaderError(e.into_any_error()))?
+            .into_iter()
+            .filter_map(|san| match san {
+                SubjectAltName::Uri(uri) => Some(uri),
+                _ => None,
+            })
+            .collect())
+    }
+}
+
+impl<R> X509IdentityExtractor for SanIdentityExtractor<R>
+where
+    R: X509CertificateReader,
+{
+    type Error = X509IdentityError;
+
+    fn identity(&self, certificate_chain: &CertificateChain) -> Result<Vec<u8>, Self::Error> {
+        self.identity(certificate_chain)
+    }
+
+    fn valid_successor(
+        &self,
+        predecessor: &CertificateChain,
+        successor: &CertificateChain,
+    ) -> Result<bool, Self::Error> {
+        self.valid_successor(predecessor, successor)
+    }
+}
+
+fn find_uri(uris: &[String], prefix: &str) -> Result<String, X509IdentityError> {
+    uris.iter()
+        .find(|uri| uri.starts_with(prefix))
+        .cloned()
+        .ok_or_else(|| X509IdentityError::SanIdentityNotFound(prefix.into()))
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::{
+        test_utils::test_certificate_chain, MockX509CertificateReader, SanIdentityExtractor,
+        SubjectAltName, X509IdentityError,
+    };
+
+    use alloc::vec;
+    use assert_matches::assert_matches;
+    use mls_rs_core::identity::CertificateChain;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    fn test_sans(user: &str, device: &str) -> Vec<SubjectAltName> {
+        vec![
+            SubjectAltName::Email("user@domain".to_string()),
+            SubjectAltName::Uri(device.to_string()),
+            SubjectAltName::Uri(user.to_string()),
+        ]
+    }
+
+    fn test_setup<F>(mut mock_setup: F) -> SanIdentityExtractor<MockX509CertificateReader>
+    where
+        F: FnMut(&mut MockX509CertificateReader),
+    {
+        let mut x509_reader = MockX509CertificateReader::new();
+
+        mock_setup(&mut x509_reader);
+
+        SanIdentityExtractor::new(0, "im:", x509_reader).with_device_uri_prefix("device:")
+    }
+
+    fn expect_sans(
+        reader: &mut MockX509CertificateReader,
+        chain: &CertificateChain,
+        user: &'static str,
+        device: &'static str,
+    ) {
+        reader
+            .expect_subject_alt_names()
+            .with(mockall::predicate::eq(chain[0].clone()))
+            .times(1)
+            .return_once_st(move |_| Ok(test_sans(user, device)));
+    }
+
+    #[test]
+    fn identity_combines_user_and_device() {
+        let chain = test_certificate_chain();
+
+        let extractor = test_setup(|reader| {
+            expect_sans(reader, &chain, "im:alice@domain", "device:laptop");
+        });
+
+        assert_eq!(
+            extractor.identity(&chain).unwrap(),
+            b"im:alice@domain\0device:laptop".to_vec()
+        );
+    }
+
+    #[test]
+    fn identity_without_device_prefix_is_user_uri() {
+        let chain = test_certificate_chain();
+        let mut reader = MockX509CertificateReader::new();
+
+        expect_sans(&mut reader, &chain, "im:alice@domain", "device:laptop");
+
+        let extractor = SanIdentityExtractor::new(0, "im:", reader);
+
+        assert_eq!(
+            extractor.identity(&chain).unwrap(),
+            b"im:alice@domain".to_vec()
+        );
+    }
+
+    #[test]
+    fn missing_device_uri_is_rejected() {
+        let chain = test_certificate_chain();
+
+        let extractor = test_setup(|reader| {
+            expect_sans(reader, &chain, "im:alice@domain", "https://example.com");
+        });
+
+        assert_matches!(
+            extractor.identity(&chain),
+            Err(X509IdentityError::SanIdentityNotFound(prefix)) if prefix == "device:"
+        );
+    }
+
+    #[test]
+    fn valid_successor_with_new_device_certificate() {
+        let predecessor = test_certificate_chain();
+        let successor = test_certificate_chain();
+
+        let extractor = test_setup(|reader| {
+            expect_sans(reader, &predecessor, "im:alice@domain", "device:laptop");
+            expect_sans(reader, &successor, "im:alice@domain", "device:phone");
+        });
+
+        assert!(extractor.valid_successor(&predecessor, &successor).unwrap());
+    }
+
+    #[test]
+    fn invalid_successor_different_user() {
+        let predecessor = test_certificate_chain();
+        let successor = test_certificate_chain();
+
+        let extractor = test_setup(|reader| {
+            expect_sans(reader, &predecessor, "im:alice@domain", "device:laptop");
+            expect_sans(reader, &successor, "im:bob@domain", "device:laptop");
+        });
+
+        assert!(
+            !extractor.valid_successor(&predecessor, &successor).unwrap(),
+            "Successor cert chain with a different user passed valid check!"
+        );
+    }
+}
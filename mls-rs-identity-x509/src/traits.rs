@@ -2,7 +2,7 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use crate::{DerCertificate, DerCertificateRequest};
+use crate::{DerCertificate, DerCertificateRequest, DerCertificateRevocationList};
 
 use alloc::vec::Vec;
 use mls_rs_core::{crypto::SignaturePublicKey, error::IntoAnyError};
@@ -87,3 +87,16 @@ pub trait X509CertificateReader {
     /// Get the subject public key of a certificate.
     fn public_key(&self, certificate: &DerCertificate) -> Result<SignaturePublicKey, Self::Error>;
 }
+
+#[cfg_attr(all(test, feature = "std"), automock(type Error = crate::test_utils::TestError;))]
+/// Source of certificate revocation lists used during chain validation.
+///
+/// Validators query this trait every time a chain is validated, so an
+/// implementation can refresh its lists, e.g. from CRL distribution points,
+/// without the validator needing to be rebuilt.
+pub trait X509CrlProvider {
+    type Error: IntoAnyError;
+
+    /// Currently known certificate revocation lists.
+    fn revocation_lists(&self) -> Result<Vec<DerCertificateRevocationList>, Self::Error>;
+}
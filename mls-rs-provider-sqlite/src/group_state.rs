@@ -3,10 +3,12 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
 use mls_rs_core::{
-    group::{EpochRecord, GroupState, GroupStateStorage},
+    group::{
+        EpochRecord, GroupState, GroupStateDelta, GroupStateStorage, NodeUpdate, StoredGroupState,
+    },
     mls_rs_codec::{MlsDecode, MlsEncode},
 };
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
 use std::{
     fmt::{self, Debug},
     sync::{Arc, Mutex},
@@ -16,6 +18,9 @@ use crate::SqLiteDataStorageError;
 
 pub(crate) const DEFAULT_EPOCH_RETENTION_LIMIT: u64 = 3;
 
+const RATCHET_TREE: u8 = 0;
+const SECRET_TREE: u8 = 1;
+
 #[derive(Clone)]
 struct StoredEpoch {
     data: Vec<u8>,
@@ -35,6 +40,14 @@ impl StoredEpoch {
     fn new(id: u64, data: Vec<u8>) -> Self {
         Self { id, data }
     }
+
+    fn encode<ET: EpochRecord + MlsEncode>(epoch: &ET) -> Result<Self, SqLiteDataStorageError> {
+        let data = epoch
+            .mls_encode_to_vec()
+            .map_err(|e| SqLiteDataStorageError::DataConversionError(e.into()))?;
+
+        Ok(Self::new(epoch.id(), data))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -42,6 +55,7 @@ impl StoredEpoch {
 pub struct SqLiteGroupStateStorage {
     connection: Arc<Mutex<Connection>>,
     max_epoch_retention: u64,
+    incremental_writes: bool,
 }
 
 impl SqLiteGroupStateStorage {
@@ -49,13 +63,27 @@ impl SqLiteGroupStateStorage {
         SqLiteGroupStateStorage {
             connection: Arc::new(Mutex::new(connection)),
             max_epoch_retention: DEFAULT_EPOCH_RETENTION_LIMIT,
+            incremental_writes: false,
         }
     }
 
     pub fn with_max_epoch_retention(self, max_epoch_retention: u64) -> Self {
         Self {
-            connection: self.connection,
             max_epoch_retention,
+            ..self
+        }
+    }
+
+    /// Store the ratchet tree and the secret tree of groups node by node, so
+    /// that writing a group state only updates the nodes that changed.
+    ///
+    /// Groups are converted on their next write when this setting changes.
+    /// Until then, groups written incrementally are still returned by
+    /// [`GroupStateStorage::delta_state`] after disabling incremental writes.
+    pub fn with_incremental_writes(self, incremental_writes: bool) -> Self {
+        Self {
+            incremental_writes,
+            ..self
         }
     }
 
@@ -104,7 +132,7 @@ impl SqLiteGroupStateStorage {
 
         connection
             .query_row(
-                "SELECT snapshot FROM mls_group where group_id = ?",
+                "SELECT snapshot FROM mls_group where group_id = ? AND incremental = 0",
                 [group_id],
                 |row| row.get::<_, Vec<u8>>(0),
             )
@@ -141,16 +169,138 @@ impl SqLiteGroupStateStorage {
             .map_err(|e| SqLiteDataStorageError::SqlEngineError(e.into()))
     }
 
+    fn get_delta_state(
+        &self,
+        group_id: &[u8],
+    ) -> Result<Option<StoredGroupState>, SqLiteDataStorageError> {
+        let connection = self.connection.lock().unwrap();
+
+        let state = connection
+            .query_row(
+                "SELECT snapshot FROM mls_group where group_id = ? AND incremental = 1",
+                [group_id],
+                |row| row.get::<_, Vec<u8>>(0),
+            )
+            .optional()
+            .map_err(|e| SqLiteDataStorageError::SqlEngineError(e.into()))?;
+
+        let Some(state) = state else {
+            return Ok(None);
+        };
+
+        let mut statement = connection
+            .prepare("SELECT tree, node_index, data FROM mls_group_node WHERE group_id = ?")
+            .map_err(|e| SqLiteDataStorageError::SqlEngineError(e.into()))?;
+
+        let mut nodes = statement
+            .query_map([group_id], |row| {
+                Ok((
+                    row.get::<_, u8>(0)?,
+                    row.get::<_, u32>(1)?,
+                    row.get::<_, Vec<u8>>(2)?,
+                ))
+            })
+            .map_err(|e| SqLiteDataStorageError::SqlEngineError(e.into()))?;
+
+        let stored = nodes.try_fold(
+            StoredGroupState {
+                state,
+                ..Default::default()
+            },
+            |mut stored, node| {
+                let (tree, index, data) =
+                    node.map_err(|e| SqLiteDataStorageError::DataConversionError(e.into()))?;
+
+                match tree {
+                    RATCHET_TREE => stored.tree_nodes.push((index, data)),
+                    SECRET_TREE => stored.secret_tree_nodes.push((index, data)),
+                    _ => {
+                        return Err(SqLiteDataStorageError::DataConversionError(
+                            format!("unknown tree {tree}").into(),
+                        ))
+                    }
+                }
+
+                Ok(stored)
+            },
+        )?;
+
+        Ok(Some(stored))
+    }
+
     fn update_group_state<I, U>(
         &self,
         group_id: &[u8],
         group_snapshot: Vec<u8>,
         inserts: I,
+        updates: U,
+    ) -> Result<(), SqLiteDataStorageError>
+    where
+        I: Iterator<Item = Result<StoredEpoch, SqLiteDataStorageError>>,
+        U: Iterator<Item = Result<StoredEpoch, SqLiteDataStorageError>>,
+    {
+        self.write_transaction(group_id, inserts, updates, |transaction| {
+            // Upsert into the group table to set the most recent snapshot
+            transaction.execute(
+                "INSERT INTO mls_group (group_id, snapshot, incremental) VALUES (?, ?, 0) ON CONFLICT(group_id) DO UPDATE SET snapshot=excluded.snapshot, incremental=0",
+                params![group_id, group_snapshot],
+            ).map_err(|e| SqLiteDataStorageError::SqlEngineError(e.into()))?;
+
+            // Remove nodes left over from incremental writes
+            transaction
+                .execute(
+                    "DELETE FROM mls_group_node WHERE group_id = ?",
+                    params![group_id],
+                )
+                .map(|_| ())
+                .map_err(|e| SqLiteDataStorageError::SqlEngineError(e.into()))
+        })
+    }
+
+    fn update_group_state_delta<I, U>(
+        &self,
+        delta: GroupStateDelta,
+        inserts: I,
+        updates: U,
+    ) -> Result<(), SqLiteDataStorageError>
+    where
+        I: Iterator<Item = Result<StoredEpoch, SqLiteDataStorageError>>,
+        U: Iterator<Item = Result<StoredEpoch, SqLiteDataStorageError>>,
+    {
+        let group_id = delta.group_id.as_slice();
+
+        self.write_transaction(group_id, inserts, updates, |transaction| {
+            // Upsert into the group table to set the most recent state without its trees
+            transaction.execute(
+                "INSERT INTO mls_group (group_id, snapshot, incremental) VALUES (?, ?, 1) ON CONFLICT(group_id) DO UPDATE SET snapshot=excluded.snapshot, incremental=1",
+                params![group_id, delta.state],
+            ).map_err(|e| SqLiteDataStorageError::SqlEngineError(e.into()))?;
+
+            if delta.reset_nodes {
+                transaction
+                    .execute(
+                        "DELETE FROM mls_group_node WHERE group_id = ?",
+                        params![group_id],
+                    )
+                    .map_err(|e| SqLiteDataStorageError::SqlEngineError(e.into()))?;
+            }
+
+            update_nodes(transaction, group_id, RATCHET_TREE, &delta.tree_updates)?;
+            update_nodes(transaction, group_id, SECRET_TREE, &delta.secret_tree_updates)
+        })
+    }
+
+    fn write_transaction<I, U, F>(
+        &self,
+        group_id: &[u8],
+        inserts: I,
         mut updates: U,
+        write_state: F,
     ) -> Result<(), SqLiteDataStorageError>
     where
         I: Iterator<Item = Result<StoredEpoch, SqLiteDataStorageError>>,
         U: Iterator<Item = Result<StoredEpoch, SqLiteDataStorageError>>,
+        F: FnOnce(&Transaction) -> Result<(), SqLiteDataStorageError>,
     {
         let mut max_epoch_id = None;
 
@@ -159,11 +309,7 @@ impl SqLiteGroupStateStorage {
             .transaction()
             .map_err(|e| SqLiteDataStorageError::SqlEngineError(e.into()))?;
 
-        // Upsert into the group table to set the most recent snapshot
-        transaction.execute(
-            "INSERT INTO mls_group (group_id, snapshot) VALUES (?, ?) ON CONFLICT(group_id) DO UPDATE SET snapshot=excluded.snapshot",
-            params![group_id, group_snapshot],
-        ).map_err(|e| SqLiteDataStorageError::SqlEngineError(e.into()))?;
+        write_state(&transaction)?;
 
         // Insert new epochs as needed
         for epoch in inserts {
@@ -213,6 +359,34 @@ impl SqLiteGroupStateStorage {
     }
 }
 
+fn update_nodes(
+    transaction: &Transaction,
+    group_id: &[u8],
+    tree: u8,
+    updates: &[NodeUpdate],
+) -> Result<(), SqLiteDataStorageError> {
+    let mut upsert = transaction
+        .prepare_cached(
+            "INSERT INTO mls_group_node (group_id, tree, node_index, data) VALUES (?, ?, ?, ?) ON CONFLICT(group_id, tree, node_index) DO UPDATE SET data=excluded.data",
+        )
+        .map_err(|e| SqLiteDataStorageError::SqlEngineError(e.into()))?;
+
+    let mut delete = transaction
+        .prepare_cached(
+            "DELETE FROM mls_group_node WHERE group_id = ? AND tree = ? AND node_index = ?",
+        )
+        .map_err(|e| SqLiteDataStorageError::SqlEngineError(e.into()))?;
+
+    updates.iter().try_for_each(|update| {
+        match &update.data {
+            Some(data) => upsert.execute(params![group_id, tree, update.index, data]),
+            None => delete.execute(params![group_id, tree, update.index]),
+        }
+        .map(|_| ())
+        .map_err(|e| SqLiteDataStorageError::SqlEngineError(e.into()))
+    })
+}
+
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 #[cfg_attr(mls_build_async, maybe_async::must_be_async)]
 impl GroupStateStorage for SqLiteGroupStateStorage {
@@ -234,21 +408,8 @@ impl GroupStateStorage for SqLiteGroupStateStorage {
             .mls_encode_to_vec()
             .map_err(|e| SqLiteDataStorageError::DataConversionError(e.into()))?;
 
-        let inserts = epoch_inserts.iter().map(|e| {
-            Ok(StoredEpoch::new(
-                e.id(),
-                e.mls_encode_to_vec()
-                    .map_err(|e| SqLiteDataStorageError::DataConversionError(e.into()))?,
-            ))
-        });
-
-        let updates = epoch_updates.iter().map(|e| {
-            Ok(StoredEpoch::new(
-                e.id(),
-                e.mls_encode_to_vec()
-                    .map_err(|err| SqLiteDataStorageError::DataConversionError(err.into()))?,
-            ))
-        });
+        let inserts = epoch_inserts.iter().map(StoredEpoch::encode);
+        let updates = epoch_updates.iter().map(StoredEpoch::encode);
 
         self.update_group_state(group_id.as_slice(), snapshot_data, inserts, updates)
     }
@@ -276,6 +437,29 @@ impl GroupStateStorage for SqLiteGroupStateStorage {
             .transpose()
             .map_err(|e| SqLiteDataStorageError::DataConversionError(e.into()))
     }
+
+    fn supports_deltas(&self) -> bool {
+        self.incremental_writes
+    }
+
+    async fn write_delta<ET>(
+        &mut self,
+        delta: GroupStateDelta,
+        epoch_inserts: Vec<ET>,
+        epoch_updates: Vec<ET>,
+    ) -> Result<(), Self::Error>
+    where
+        ET: EpochRecord + MlsEncode + MlsDecode + Send + Sync,
+    {
+        let inserts = epoch_inserts.iter().map(StoredEpoch::encode);
+        let updates = epoch_updates.iter().map(StoredEpoch::encode);
+
+        self.update_group_state_delta(delta, inserts, updates)
+    }
+
+    async fn delta_state(&self, group_id: &[u8]) -> Result<Option<StoredGroupState>, Self::Error> {
+        self.get_delta_state(group_id)
+    }
 }
 
 #[cfg(test)]
@@ -553,6 +737,191 @@ mod tests {
         );
     }
 
+    fn test_delta(group_id: &[u8], reset_nodes: bool) -> GroupStateDelta {
+        GroupStateDelta {
+            group_id: group_id.to_vec(),
+            state: test_snapshot(),
+            reset_nodes,
+            tree_updates: vec![test_node_update(0), test_node_update(2)],
+            secret_tree_updates: vec![test_node_update(1)],
+        }
+    }
+
+    fn test_node_update(index: u32) -> NodeUpdate {
+        NodeUpdate {
+            index,
+            data: Some(gen_rand_bytes(64)),
+        }
+    }
+
+    fn stored_nodes(updates: &[NodeUpdate]) -> Vec<(u32, Vec<u8>)> {
+        updates
+            .iter()
+            .filter_map(|update| Some((update.index, update.data.clone()?)))
+            .collect()
+    }
+
+    fn get_delta_state(storage: &SqLiteGroupStateStorage, group_id: &[u8]) -> StoredGroupState {
+        let mut stored = storage.get_delta_state(group_id).unwrap().unwrap();
+        stored.tree_nodes.sort();
+        stored.secret_tree_nodes.sort();
+        stored
+    }
+
+    #[test]
+    fn delta_is_read_after_disabling_incremental_writes() {
+        let storage = get_test_storage().with_incremental_writes(true);
+        let group_id = test_group_id();
+        let delta = test_delta(&group_id, true);
+
+        storage
+            .update_group_state_delta(delta.clone(), vec![].into_iter(), vec![].into_iter())
+            .unwrap();
+
+        let storage = storage.with_incremental_writes(false);
+        assert!(!storage.supports_deltas());
+
+        let stored = storage.get_delta_state(&group_id).unwrap().unwrap();
+
+        assert_eq!(stored.state, delta.state);
+    }
+
+    #[test]
+    fn delta_can_be_written_and_read() {
+        let storage = get_test_storage();
+        let group_id = test_group_id();
+        let delta = test_delta(&group_id, true);
+
+        storage
+            .update_group_state_delta(delta.clone(), vec![].into_iter(), vec![].into_iter())
+            .unwrap();
+
+        let stored = get_delta_state(&storage, &group_id);
+
+        assert_eq!(stored.state, delta.state);
+        assert_eq!(stored.tree_nodes, stored_nodes(&delta.tree_updates));
+        assert_eq!(
+            stored.secret_tree_nodes,
+            stored_nodes(&delta.secret_tree_updates)
+        );
+
+        // Incrementally written groups are not returned as full snapshots
+        assert!(storage.get_snapshot_data(&group_id).unwrap().is_none());
+    }
+
+    #[test]
+    fn delta_updates_and_removes_nodes() {
+        let storage = get_test_storage();
+        let group_id = test_group_id();
+        let first = test_delta(&group_id, true);
+
+        storage
+            .update_group_state_delta(first.clone(), vec![].into_iter(), vec![].into_iter())
+            .unwrap();
+
+        let second = GroupStateDelta {
+            group_id: group_id.clone(),
+            state: test_snapshot(),
+            reset_nodes: false,
+            tree_updates: vec![
+                test_node_update(0),
+                NodeUpdate {
+                    index: 2,
+                    data: None,
+                },
+            ],
+            secret_tree_updates: vec![],
+        };
+
+        storage
+            .update_group_state_delta(
+                second.clone(),
+                vec![test_epoch(0)].into_iter().map(Ok),
+                vec![].into_iter(),
+            )
+            .unwrap();
+
+        let stored = get_delta_state(&storage, &group_id);
+
+        assert_eq!(stored.state, second.state);
+        assert_eq!(stored.tree_nodes, stored_nodes(&second.tree_updates[..1]));
+        assert_eq!(
+            stored.secret_tree_nodes,
+            stored_nodes(&first.secret_tree_updates)
+        );
+        assert!(storage.get_epoch_data(&group_id, 0).unwrap().is_some());
+    }
+
+    #[test]
+    fn delta_with_reset_removes_stored_nodes() {
+        let storage = get_test_storage();
+        let group_id = test_group_id();
+
+        storage
+            .update_group_state_delta(
+                test_delta(&group_id, true),
+                vec![].into_iter(),
+                vec![].into_iter(),
+            )
+            .unwrap();
+
+        let delta = GroupStateDelta {
+            tree_updates: vec![test_node_update(4)],
+            secret_tree_updates: vec![],
+            ..test_delta(&group_id, true)
+        };
+
+        storage
+            .update_group_state_delta(delta.clone(), vec![].into_iter(), vec![].into_iter())
+            .unwrap();
+
+        let stored = get_delta_state(&storage, &group_id);
+
+        assert_eq!(stored.tree_nodes, stored_nodes(&delta.tree_updates));
+        assert!(stored.secret_tree_nodes.is_empty());
+    }
+
+    #[test]
+    fn full_write_replaces_delta_state() {
+        let storage = get_test_storage();
+        let group_id = test_group_id();
+
+        storage
+            .update_group_state_delta(
+                test_delta(&group_id, true),
+                vec![].into_iter(),
+                vec![].into_iter(),
+            )
+            .unwrap();
+
+        let snapshot = test_snapshot();
+
+        storage
+            .update_group_state(
+                &group_id,
+                snapshot.clone(),
+                vec![].into_iter(),
+                vec![].into_iter(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            storage.get_snapshot_data(&group_id).unwrap(),
+            Some(snapshot)
+        );
+        assert!(storage.get_delta_state(&group_id).unwrap().is_none());
+
+        let connection = storage.connection.lock().unwrap();
+
+        let node_count = connection
+            .query_row("SELECT COUNT(*) FROM mls_group_node", [], |row| {
+                row.get::<_, u64>(0)
+            })
+            .unwrap();
+
+        assert_eq!(node_count, 0);
+    }
+
     #[test]
     fn delete_group() {
         let test_data = setup_group_storage_test();
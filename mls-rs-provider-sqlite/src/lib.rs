@@ -83,10 +83,14 @@ where
             .pragma_query_value(None, "user_version", |rows| rows.get::<_, u32>(0))
             .map_err(|e| SqLiteDataStorageError::SqlEngineError(e.into()))?;
 
-        if current_schema != 1 {
+        if current_schema < 1 {
             create_tables_v1(&connection)?;
         }
 
+        if current_schema < 2 {
+            create_tables_v2(&connection)?;
+        }
+
         Ok(connection)
     }
 
@@ -150,6 +154,25 @@ fn create_tables_v1(connection: &Connection) -> Result<(), SqLiteDataStorageErro
         .map_err(|e| SqLiteDataStorageError::SqlEngineError(e.into()))
 }
 
+fn create_tables_v2(connection: &Connection) -> Result<(), SqLiteDataStorageError> {
+    connection
+        .execute_batch(
+            "BEGIN;
+            ALTER TABLE mls_group ADD COLUMN incremental INTEGER NOT NULL DEFAULT 0;
+            CREATE TABLE mls_group_node (
+                group_id BLOB,
+                tree INTEGER,
+                node_index INTEGER,
+                data BLOB NOT NULL,
+                FOREIGN KEY (group_id) REFERENCES mls_group (group_id) ON DELETE CASCADE
+                PRIMARY KEY (group_id, tree, node_index)
+            ) WITHOUT ROWID;
+            PRAGMA user_version = 2;
+            COMMIT;",
+        )
+        .map_err(|e| SqLiteDataStorageError::SqlEngineError(e.into()))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{connection_strategy::MemoryStrategy, SqLiteDataStorageEngine};
@@ -168,6 +191,6 @@ mod tests {
             .pragma_query_value(None, "user_version", |rows| rows.get::<_, u32>(0))
             .unwrap();
 
-        assert_eq!(current_schema, 1);
+        assert_eq!(current_schema, 2);
     }
 }
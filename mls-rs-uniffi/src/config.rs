@@ -0,0 +1,153 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+//! Configuration of a [`Client`](crate::Client).
+//!
+//! Storage, identity validation and MLS rules can be implemented by the
+//! application through callback interfaces.
+
+pub mod group_state;
+pub mod identity;
+pub mod key_package;
+pub mod mls_rules;
+
+#[cfg(feature = "sqlite")]
+use std::path::Path;
+use std::sync::Arc;
+
+use mls_rs::client_builder::{self, ClientBuilder};
+use mls_rs::error::IntoAnyError;
+use mls_rs::group::proposal::ProposalType;
+use mls_rs::storage_provider::in_memory::{
+    InMemoryGroupStateStorage, InMemoryKeyPackageStorage, InMemoryPreSharedKeyStorage,
+};
+#[cfg(feature = "sqlite")]
+use mls_rs::storage_provider::sqlite::{
+    connection_strategy::FileConnectionStrategy, SqLiteDataStorageEngine,
+};
+use mls_rs_crypto_openssl::OpensslCryptoProvider;
+
+use self::group_state::{GroupStateStorage, GroupStateStorageWrapper};
+use self::identity::{BasicIdentityProvider, IdentityProvider, IdentityProviderAdapter};
+use self::key_package::{KeyPackageStorage, KeyPackageStorageWrapper};
+use self::mls_rules::{DefaultMlsRules, MlsRules, MlsRulesAdapter};
+use crate::Error;
+
+pub type UniFFIConfig = client_builder::WithMlsRules<
+    MlsRulesAdapter,
+    client_builder::WithIdentityProvider<
+        IdentityProviderAdapter,
+        client_builder::WithCryptoProvider<
+            OpensslCryptoProvider,
+            client_builder::WithPskStore<
+                InMemoryPreSharedKeyStorage,
+                client_builder::WithKeyPackageRepo<
+                    KeyPackageStorageWrapper,
+                    client_builder::WithGroupStateStorage<
+                        GroupStateStorageWrapper,
+                        client_builder::BaseConfig,
+                    >,
+                >,
+            >,
+        >,
+    >,
+>;
+
+/// Where a client keeps group states and key package secrets.
+#[derive(Clone, uniffi::Enum)]
+pub enum StorageConfig {
+    /// Keep all state in memory. The state is lost when the client is
+    /// dropped.
+    InMemory,
+    /// Keep all state in the SQLite database at `path`.
+    #[cfg(feature = "sqlite")]
+    Sqlite { path: String },
+    /// Keep all state in storage implemented by the application.
+    Custom {
+        group_state_storage: Arc<dyn GroupStateStorage>,
+        key_package_storage: Arc<dyn KeyPackageStorage>,
+    },
+}
+
+/// Configuration of a [`Client`](crate::Client).
+///
+/// Use [`client_config_default`] to get a configuration with in-memory
+/// storage, basic credentials and the default MLS rules.
+#[derive(Clone, uniffi::Record)]
+pub struct ClientConfig {
+    pub storage: StorageConfig,
+    pub identity_provider: Arc<dyn IdentityProvider>,
+    pub mls_rules: Arc<dyn MlsRules>,
+    /// Custom proposal types supported by the client, in addition to
+    /// the proposal types defined by RFC 9420.
+    pub custom_proposal_types: Vec<u16>,
+}
+
+/// Create a client configuration with in-memory storage, basic
+/// credentials and the default MLS rules.
+#[uniffi::export]
+pub fn client_config_default() -> ClientConfig {
+    ClientConfig {
+        storage: StorageConfig::InMemory,
+        identity_provider: Arc::new(BasicIdentityProvider),
+        mls_rules: Arc::new(DefaultMlsRules),
+        custom_proposal_types: Vec::new(),
+    }
+}
+
+impl ClientConfig {
+    /// Apply this configuration to an empty [`ClientBuilder`].
+    ///
+    /// External pre-shared keys are read from `psk_store`.
+    pub(crate) fn builder(
+        self,
+        psk_store: InMemoryPreSharedKeyStorage,
+    ) -> Result<ClientBuilder<UniFFIConfig>, Error> {
+        let (group_state_storage, key_package_storage) = match self.storage {
+            StorageConfig::InMemory => (
+                GroupStateStorageWrapper::InMemory(InMemoryGroupStateStorage::new()),
+                KeyPackageStorageWrapper::InMemory(InMemoryKeyPackageStorage::new()),
+            ),
+            #[cfg(feature = "sqlite")]
+            StorageConfig::Sqlite { path } => {
+                let engine =
+                    SqLiteDataStorageEngine::new(FileConnectionStrategy::new(Path::new(&path)))
+                        .map_err(|err| err.into_any_error())?;
+
+                (
+                    GroupStateStorageWrapper::Sqlite(
+                        engine
+                            .group_state_storage()
+                            .map_err(|err| err.into_any_error())?,
+                    ),
+                    KeyPackageStorageWrapper::Sqlite(
+                        engine
+                            .key_package_storage()
+                            .map_err(|err| err.into_any_error())?,
+                    ),
+                )
+            }
+            StorageConfig::Custom {
+                group_state_storage,
+                key_package_storage,
+            } => (
+                GroupStateStorageWrapper::Foreign(group_state_storage),
+                KeyPackageStorageWrapper::Foreign(key_package_storage),
+            ),
+        };
+
+        Ok(mls_rs::Client::builder()
+            .group_state_storage(group_state_storage)
+            .key_package_repo(key_package_storage)
+            .psk_store(psk_store)
+            .crypto_provider(OpensslCryptoProvider::new())
+            .identity_provider(IdentityProviderAdapter(self.identity_provider))
+            .mls_rules(MlsRulesAdapter(self.mls_rules))
+            .custom_proposal_types(
+                self.custom_proposal_types
+                    .into_iter()
+                    .map(ProposalType::new),
+            ))
+    }
+}
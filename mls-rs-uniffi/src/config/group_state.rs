@@ -0,0 +1,202 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+
+use mls_rs::error::IntoAnyError;
+use mls_rs::storage_provider::in_memory::InMemoryGroupStateStorage;
+#[cfg(feature = "sqlite")]
+use mls_rs::storage_provider::sqlite::storage::SqLiteGroupStateStorage;
+use mls_rs_core::mls_rs_codec::{MlsDecode, MlsEncode};
+
+use crate::Error;
+
+/// Serialized state of a group, identified by the group ID.
+#[derive(Clone, Debug, uniffi::Record)]
+pub struct GroupState {
+    pub id: Vec<u8>,
+    pub data: Vec<u8>,
+}
+
+/// Serialized secrets of a prior epoch of a group.
+#[derive(Clone, Debug, uniffi::Record)]
+pub struct EpochRecord {
+    pub id: u64,
+    pub data: Vec<u8>,
+}
+
+/// Storage for group states, implemented by the application.
+///
+/// The data passed to the storage is opaque and contains secret keys.
+/// See [`mls_rs_core::group::GroupStateStorage`] for details.
+#[uniffi::export(with_foreign)]
+pub trait GroupStateStorage: Send + Sync {
+    /// Fetch the state of the group with ID `group_id`.
+    fn state(&self, group_id: Vec<u8>) -> Result<Option<Vec<u8>>, Error>;
+
+    /// Fetch the prior epoch `epoch_id` of the group with ID `group_id`.
+    fn epoch(&self, group_id: Vec<u8>, epoch_id: u64) -> Result<Option<Vec<u8>>, Error>;
+
+    /// Atomically write a new group state together with new and updated
+    /// prior epochs.
+    fn write(
+        &self,
+        state: GroupState,
+        epoch_inserts: Vec<EpochRecord>,
+        epoch_updates: Vec<EpochRecord>,
+    ) -> Result<(), Error>;
+
+    /// The ID of the latest prior epoch stored for the group with ID
+    /// `group_id`.
+    fn max_epoch_id(&self, group_id: Vec<u8>) -> Result<Option<u64>, Error>;
+}
+
+/// Group state storage selected by a [`ClientConfig`](crate::config::ClientConfig).
+#[derive(Clone)]
+pub enum GroupStateStorageWrapper {
+    InMemory(InMemoryGroupStateStorage),
+    #[cfg(feature = "sqlite")]
+    Sqlite(SqLiteGroupStateStorage),
+    Foreign(Arc<dyn GroupStateStorage>),
+}
+
+impl Debug for GroupStateStorageWrapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InMemory(storage) => f.debug_tuple("InMemory").field(storage).finish(),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(storage) => f.debug_tuple("Sqlite").field(storage).finish(),
+            Self::Foreign(_) => f.debug_tuple("Foreign").finish(),
+        }
+    }
+}
+
+fn encode_record<T: MlsEncode>(record: &T) -> Result<Vec<u8>, Error> {
+    Ok(record.mls_encode_to_vec()?)
+}
+
+fn decode_record<T: MlsDecode>(data: Option<Vec<u8>>) -> Result<Option<T>, Error> {
+    Ok(data
+        .map(|data| T::mls_decode(&mut data.as_slice()))
+        .transpose()?)
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+impl mls_rs_core::group::GroupStateStorage for GroupStateStorageWrapper {
+    type Error = Error;
+
+    async fn state<T>(&self, group_id: &[u8]) -> Result<Option<T>, Self::Error>
+    where
+        T: mls_rs_core::group::GroupState + MlsEncode + MlsDecode,
+    {
+        match self {
+            Self::InMemory(storage) => {
+                mls_rs_core::group::GroupStateStorage::state(storage, group_id)
+                    .await
+                    .map_err(|err| err.into_any_error().into())
+            }
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(storage) => {
+                mls_rs_core::group::GroupStateStorage::state(storage, group_id)
+                    .await
+                    .map_err(|err| err.into_any_error().into())
+            }
+            Self::Foreign(storage) => decode_record(storage.state(group_id.to_vec())?),
+        }
+    }
+
+    async fn epoch<T>(&self, group_id: &[u8], epoch_id: u64) -> Result<Option<T>, Self::Error>
+    where
+        T: mls_rs_core::group::EpochRecord + MlsEncode + MlsDecode,
+    {
+        match self {
+            Self::InMemory(storage) => {
+                mls_rs_core::group::GroupStateStorage::epoch(storage, group_id, epoch_id)
+                    .await
+                    .map_err(|err| err.into_any_error().into())
+            }
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(storage) => {
+                mls_rs_core::group::GroupStateStorage::epoch(storage, group_id, epoch_id)
+                    .await
+                    .map_err(|err| err.into_any_error().into())
+            }
+            Self::Foreign(storage) => decode_record(storage.epoch(group_id.to_vec(), epoch_id)?),
+        }
+    }
+
+    async fn write<ST, ET>(
+        &mut self,
+        state: ST,
+        epoch_inserts: Vec<ET>,
+        epoch_updates: Vec<ET>,
+    ) -> Result<(), Self::Error>
+    where
+        ST: mls_rs_core::group::GroupState + MlsEncode + MlsDecode + Send + Sync,
+        ET: mls_rs_core::group::EpochRecord + MlsEncode + MlsDecode + Send + Sync,
+    {
+        match self {
+            Self::InMemory(storage) => mls_rs_core::group::GroupStateStorage::write(
+                storage,
+                state,
+                epoch_inserts,
+                epoch_updates,
+            )
+            .await
+            .map_err(|err| err.into_any_error().into()),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(storage) => mls_rs_core::group::GroupStateStorage::write(
+                storage,
+                state,
+                epoch_inserts,
+                epoch_updates,
+            )
+            .await
+            .map_err(|err| err.into_any_error().into()),
+            Self::Foreign(storage) => {
+                let state = GroupState {
+                    id: state.id(),
+                    data: encode_record(&state)?,
+                };
+
+                let to_records = |epochs: Vec<ET>| {
+                    epochs
+                        .iter()
+                        .map(|epoch| {
+                            Ok(EpochRecord {
+                                id: epoch.id(),
+                                data: encode_record(epoch)?,
+                            })
+                        })
+                        .collect::<Result<Vec<_>, Error>>()
+                };
+
+                storage.write(
+                    state,
+                    to_records(epoch_inserts)?,
+                    to_records(epoch_updates)?,
+                )
+            }
+        }
+    }
+
+    async fn max_epoch_id(&self, group_id: &[u8]) -> Result<Option<u64>, Self::Error> {
+        match self {
+            Self::InMemory(storage) => {
+                mls_rs_core::group::GroupStateStorage::max_epoch_id(storage, group_id)
+                    .await
+                    .map_err(|err| err.into_any_error().into())
+            }
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(storage) => {
+                mls_rs_core::group::GroupStateStorage::max_epoch_id(storage, group_id)
+                    .await
+                    .map_err(|err| err.into_any_error().into())
+            }
+            Self::Foreign(storage) => storage.max_epoch_id(group_id.to_vec()),
+        }
+    }
+}
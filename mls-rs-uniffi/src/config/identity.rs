@@ -0,0 +1,173 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+
+use mls_rs::time::MlsTime;
+use mls_rs_core::identity::{self, CredentialType};
+
+use crate::{Error, SigningIdentity};
+
+/// Identity system used to validate the signing identities of group
+/// members, implemented by the application.
+///
+/// Timestamps are given in seconds since the Unix epoch. See
+/// [`mls_rs_core::identity::IdentityProvider`] for details.
+#[uniffi::export(with_foreign)]
+pub trait IdentityProvider: Send + Sync {
+    /// Determine if `signing_identity` is valid for a group member.
+    fn validate_member(
+        &self,
+        signing_identity: Arc<SigningIdentity>,
+        timestamp: Option<u64>,
+    ) -> Result<(), Error>;
+
+    /// Determine if `signing_identity` is valid for an external sender.
+    fn validate_external_sender(
+        &self,
+        signing_identity: Arc<SigningIdentity>,
+        timestamp: Option<u64>,
+    ) -> Result<(), Error>;
+
+    /// A unique identifier for `signing_identity`.
+    fn identity(&self, signing_identity: Arc<SigningIdentity>) -> Result<Vec<u8>, Error>;
+
+    /// Determine if `successor` can replace `predecessor`.
+    fn valid_successor(
+        &self,
+        predecessor: Arc<SigningIdentity>,
+        successor: Arc<SigningIdentity>,
+    ) -> Result<bool, Error>;
+
+    /// Credential types that are supported by this provider.
+    fn supported_types(&self) -> Vec<u16>;
+}
+
+/// Identity provider accepting basic credentials, see
+/// [`mls_rs::identity::basic::BasicIdentityProvider`].
+#[derive(Clone, Debug, Default)]
+pub struct BasicIdentityProvider;
+
+fn basic_identifier(signing_identity: &SigningIdentity) -> Result<Vec<u8>, Error> {
+    signing_identity
+        .inner
+        .credential
+        .as_basic()
+        .map(|credential| credential.identifier.clone())
+        .ok_or_else(|| Error::UnsupportedCredentialType {
+            credential_type: signing_identity
+                .inner
+                .credential
+                .credential_type()
+                .raw_value(),
+        })
+}
+
+impl IdentityProvider for BasicIdentityProvider {
+    fn validate_member(
+        &self,
+        signing_identity: Arc<SigningIdentity>,
+        _timestamp: Option<u64>,
+    ) -> Result<(), Error> {
+        basic_identifier(&signing_identity).map(|_| ())
+    }
+
+    fn validate_external_sender(
+        &self,
+        signing_identity: Arc<SigningIdentity>,
+        _timestamp: Option<u64>,
+    ) -> Result<(), Error> {
+        basic_identifier(&signing_identity).map(|_| ())
+    }
+
+    fn identity(&self, signing_identity: Arc<SigningIdentity>) -> Result<Vec<u8>, Error> {
+        basic_identifier(&signing_identity)
+    }
+
+    fn valid_successor(
+        &self,
+        predecessor: Arc<SigningIdentity>,
+        successor: Arc<SigningIdentity>,
+    ) -> Result<bool, Error> {
+        Ok(basic_identifier(&predecessor)? == basic_identifier(&successor)?)
+    }
+
+    fn supported_types(&self) -> Vec<u16> {
+        vec![CredentialType::BASIC.raw_value()]
+    }
+}
+
+/// Adapter from the [`IdentityProvider`] callback interface to
+/// [`mls_rs_core::identity::IdentityProvider`].
+#[derive(Clone)]
+pub struct IdentityProviderAdapter(pub Arc<dyn IdentityProvider>);
+
+impl Debug for IdentityProviderAdapter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IdentityProviderAdapter").finish()
+    }
+}
+
+fn to_signing_identity(signing_identity: &identity::SigningIdentity) -> Arc<SigningIdentity> {
+    Arc::new(signing_identity.clone().into())
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+impl identity::IdentityProvider for IdentityProviderAdapter {
+    type Error = Error;
+
+    async fn validate_member(
+        &self,
+        signing_identity: &identity::SigningIdentity,
+        timestamp: Option<MlsTime>,
+        _extensions: Option<&mls_rs::ExtensionList>,
+    ) -> Result<(), Self::Error> {
+        self.0.validate_member(
+            to_signing_identity(signing_identity),
+            timestamp.map(|t| t.seconds_since_epoch()),
+        )
+    }
+
+    async fn validate_external_sender(
+        &self,
+        signing_identity: &identity::SigningIdentity,
+        timestamp: Option<MlsTime>,
+        _extensions: Option<&mls_rs::ExtensionList>,
+    ) -> Result<(), Self::Error> {
+        self.0.validate_external_sender(
+            to_signing_identity(signing_identity),
+            timestamp.map(|t| t.seconds_since_epoch()),
+        )
+    }
+
+    async fn identity(
+        &self,
+        signing_identity: &identity::SigningIdentity,
+        _extensions: &mls_rs::ExtensionList,
+    ) -> Result<Vec<u8>, Self::Error> {
+        self.0.identity(to_signing_identity(signing_identity))
+    }
+
+    async fn valid_successor(
+        &self,
+        predecessor: &identity::SigningIdentity,
+        successor: &identity::SigningIdentity,
+        _extensions: &mls_rs::ExtensionList,
+    ) -> Result<bool, Self::Error> {
+        self.0.valid_successor(
+            to_signing_identity(predecessor),
+            to_signing_identity(successor),
+        )
+    }
+
+    fn supported_types(&self) -> Vec<CredentialType> {
+        self.0
+            .supported_types()
+            .into_iter()
+            .map(CredentialType::new)
+            .collect()
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+
+use mls_rs::error::IntoAnyError;
+use mls_rs::storage_provider::in_memory::InMemoryKeyPackageStorage;
+#[cfg(feature = "sqlite")]
+use mls_rs::storage_provider::sqlite::storage::SqLiteKeyPackageStorage;
+use mls_rs_core::key_package::KeyPackageData;
+use mls_rs_core::mls_rs_codec::{MlsDecode, MlsEncode};
+
+use crate::Error;
+
+/// Storage for the secrets of generated key packages, implemented by the
+/// application.
+///
+/// The data passed to the storage is opaque and contains secret keys.
+/// See [`mls_rs_core::key_package::KeyPackageStorage`] for details.
+#[uniffi::export(with_foreign)]
+pub trait KeyPackageStorage: Send + Sync {
+    /// Delete the key package data referenced by `id`.
+    fn delete(&self, id: Vec<u8>) -> Result<(), Error>;
+
+    /// Store key package data that can be accessed by `id` in the future.
+    fn insert(&self, id: Vec<u8>, data: Vec<u8>) -> Result<(), Error>;
+
+    /// Retrieve the key package data referenced by `id`.
+    fn get(&self, id: Vec<u8>) -> Result<Option<Vec<u8>>, Error>;
+}
+
+/// Key package storage selected by a [`ClientConfig`](crate::config::ClientConfig).
+#[derive(Clone)]
+pub enum KeyPackageStorageWrapper {
+    InMemory(InMemoryKeyPackageStorage),
+    #[cfg(feature = "sqlite")]
+    Sqlite(SqLiteKeyPackageStorage),
+    Foreign(Arc<dyn KeyPackageStorage>),
+}
+
+impl Debug for KeyPackageStorageWrapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InMemory(storage) => f.debug_tuple("InMemory").field(storage).finish(),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(storage) => f.debug_tuple("Sqlite").field(storage).finish(),
+            Self::Foreign(_) => f.debug_tuple("Foreign").finish(),
+        }
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+impl mls_rs_core::key_package::KeyPackageStorage for KeyPackageStorageWrapper {
+    type Error = Error;
+
+    async fn delete(&mut self, id: &[u8]) -> Result<(), Self::Error> {
+        match self {
+            Self::InMemory(storage) => {
+                mls_rs_core::key_package::KeyPackageStorage::delete(storage, id)
+                    .await
+                    .map_err(|err| err.into_any_error().into())
+            }
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(storage) => {
+                mls_rs_core::key_package::KeyPackageStorage::delete(storage, id)
+                    .await
+                    .map_err(|err| err.into_any_error().into())
+            }
+            Self::Foreign(storage) => storage.delete(id.to_vec()),
+        }
+    }
+
+    async fn insert(&mut self, id: Vec<u8>, pkg: KeyPackageData) -> Result<(), Self::Error> {
+        match self {
+            Self::InMemory(storage) => {
+                mls_rs_core::key_package::KeyPackageStorage::insert(storage, id, pkg)
+                    .await
+                    .map_err(|err| err.into_any_error().into())
+            }
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(storage) => {
+                mls_rs_core::key_package::KeyPackageStorage::insert(storage, id, pkg)
+                    .await
+                    .map_err(|err| err.into_any_error().into())
+            }
+            Self::Foreign(storage) => storage.insert(id, pkg.mls_encode_to_vec()?),
+        }
+    }
+
+    async fn get(&self, id: &[u8]) -> Result<Option<KeyPackageData>, Self::Error> {
+        match self {
+            Self::InMemory(storage) => {
+                mls_rs_core::key_package::KeyPackageStorage::get(storage, id)
+                    .await
+                    .map_err(|err| err.into_any_error().into())
+            }
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(storage) => mls_rs_core::key_package::KeyPackageStorage::get(storage, id)
+                .await
+                .map_err(|err| err.into_any_error().into()),
+            Self::Foreign(storage) => Ok(storage
+                .get(id.to_vec())?
+                .map(|data| KeyPackageData::mls_decode(&mut data.as_slice()))
+                .transpose()?),
+        }
+    }
+}
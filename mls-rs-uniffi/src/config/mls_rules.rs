@@ -0,0 +1,290 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+
+use mls_rs::client_builder;
+use mls_rs::group::Roster;
+use mls_rs::mls_rules::ProposalBundle;
+
+use crate::{Error, Member, Proposal, SigningIdentity};
+
+/// Whether the commit whose proposals are filtered is sent or received.
+///
+/// See [`mls_rs::mls_rules::CommitDirection`] for details.
+#[derive(Clone, Copy, Debug, uniffi::Enum)]
+pub enum CommitDirection {
+    Send,
+    Receive,
+}
+
+impl From<mls_rs::mls_rules::CommitDirection> for CommitDirection {
+    fn from(direction: mls_rs::mls_rules::CommitDirection) -> Self {
+        match direction {
+            mls_rs::mls_rules::CommitDirection::Send => Self::Send,
+            mls_rs::mls_rules::CommitDirection::Receive => Self::Receive,
+        }
+    }
+}
+
+/// The sender of the commit whose proposals are filtered.
+///
+/// See [`mls_rs::mls_rules::CommitSource`] for details.
+#[derive(Clone, Debug, uniffi::Enum)]
+pub enum CommitSource {
+    ExistingMember {
+        member: Member,
+    },
+    NewMember {
+        signing_identity: Arc<SigningIdentity>,
+    },
+}
+
+impl From<mls_rs::mls_rules::CommitSource> for CommitSource {
+    fn from(source: mls_rs::mls_rules::CommitSource) -> Self {
+        match source {
+            mls_rs::mls_rules::CommitSource::ExistingMember(member) => Self::ExistingMember {
+                member: member.into(),
+            },
+            mls_rs::mls_rules::CommitSource::NewMember(signing_identity) => Self::NewMember {
+                signing_identity: Arc::new(signing_identity.into()),
+            },
+        }
+    }
+}
+
+/// Options controlling commit generation.
+///
+/// See [`mls_rs::mls_rules::CommitOptions`] for details.
+#[derive(Clone, Copy, Debug, uniffi::Record)]
+pub struct CommitOptions {
+    pub path_required: bool,
+    pub ratchet_tree_extension: bool,
+    pub single_welcome_message: bool,
+    pub allow_external_commit: bool,
+}
+
+impl From<mls_rs::mls_rules::CommitOptions> for CommitOptions {
+    fn from(options: mls_rs::mls_rules::CommitOptions) -> Self {
+        Self {
+            path_required: options.path_required,
+            ratchet_tree_extension: options.ratchet_tree_extension,
+            single_welcome_message: options.single_welcome_message,
+            allow_external_commit: options.allow_external_commit,
+        }
+    }
+}
+
+impl From<CommitOptions> for mls_rs::mls_rules::CommitOptions {
+    fn from(options: CommitOptions) -> Self {
+        Self::new()
+            .with_path_required(options.path_required)
+            .with_ratchet_tree_extension(options.ratchet_tree_extension)
+            .with_single_welcome_message(options.single_welcome_message)
+            .with_allow_external_commit(options.allow_external_commit)
+    }
+}
+
+/// Padding applied to encrypted messages.
+///
+/// See [`mls_rs::client_builder::PaddingMode`] for details.
+#[derive(Clone, Copy, Debug, uniffi::Enum)]
+pub enum PaddingMode {
+    StepFunction,
+    None,
+}
+
+impl From<client_builder::PaddingMode> for PaddingMode {
+    fn from(padding_mode: client_builder::PaddingMode) -> Self {
+        match padding_mode {
+            client_builder::PaddingMode::StepFunction => Self::StepFunction,
+            client_builder::PaddingMode::None => Self::None,
+        }
+    }
+}
+
+impl From<PaddingMode> for client_builder::PaddingMode {
+    fn from(padding_mode: PaddingMode) -> Self {
+        match padding_mode {
+            PaddingMode::StepFunction => Self::StepFunction,
+            PaddingMode::None => Self::None,
+        }
+    }
+}
+
+/// Options controlling encryption of messages.
+///
+/// See [`mls_rs::mls_rules::EncryptionOptions`] for details.
+#[derive(Clone, Copy, Debug, uniffi::Record)]
+pub struct EncryptionOptions {
+    pub encrypt_control_messages: bool,
+    pub padding_mode: PaddingMode,
+}
+
+impl From<mls_rs::mls_rules::EncryptionOptions> for EncryptionOptions {
+    fn from(options: mls_rs::mls_rules::EncryptionOptions) -> Self {
+        Self {
+            encrypt_control_messages: options.encrypt_control_messages,
+            padding_mode: options.padding_mode.into(),
+        }
+    }
+}
+
+impl From<EncryptionOptions> for mls_rs::mls_rules::EncryptionOptions {
+    fn from(options: EncryptionOptions) -> Self {
+        Self::new(
+            options.encrypt_control_messages,
+            options.padding_mode.into(),
+        )
+    }
+}
+
+/// Rules customizing the behavior of MLS, implemented by the application.
+///
+/// See [`mls_rs::MlsRules`] for details.
+#[uniffi::export(with_foreign)]
+pub trait MlsRules: Send + Sync {
+    /// Select the proposals of a commit sent or received by `source`,
+    /// given the members `current_roster` of the group.
+    ///
+    /// The proposals that are kept are those of `proposals` that are
+    /// returned. Every member must apply the same rules, and raising an
+    /// error rejects the commit.
+    fn filter_proposals(
+        &self,
+        direction: CommitDirection,
+        source: CommitSource,
+        current_roster: Vec<Arc<SigningIdentity>>,
+        proposals: Vec<Arc<Proposal>>,
+    ) -> Result<Vec<Arc<Proposal>>, Error>;
+
+    /// Options used to prepare a commit resulting in a group with members
+    /// `new_roster`.
+    fn commit_options(&self, new_roster: Vec<Arc<SigningIdentity>>)
+        -> Result<CommitOptions, Error>;
+
+    /// Options used to send messages to a group with members
+    /// `current_roster`.
+    fn encryption_options(
+        &self,
+        current_roster: Vec<Arc<SigningIdentity>>,
+    ) -> Result<EncryptionOptions, Error>;
+}
+
+/// Rules using the default options of [`mls_rs::mls_rules::DefaultMlsRules`].
+#[derive(Clone, Debug, Default)]
+pub struct DefaultMlsRules;
+
+impl MlsRules for DefaultMlsRules {
+    fn filter_proposals(
+        &self,
+        _direction: CommitDirection,
+        _source: CommitSource,
+        _current_roster: Vec<Arc<SigningIdentity>>,
+        proposals: Vec<Arc<Proposal>>,
+    ) -> Result<Vec<Arc<Proposal>>, Error> {
+        Ok(proposals)
+    }
+
+    fn commit_options(
+        &self,
+        _new_roster: Vec<Arc<SigningIdentity>>,
+    ) -> Result<CommitOptions, Error> {
+        Ok(mls_rs::mls_rules::CommitOptions::default().into())
+    }
+
+    fn encryption_options(
+        &self,
+        _current_roster: Vec<Arc<SigningIdentity>>,
+    ) -> Result<EncryptionOptions, Error> {
+        Ok(mls_rs::mls_rules::EncryptionOptions::default().into())
+    }
+}
+
+/// Adapter from the [`MlsRules`] callback interface to [`mls_rs::MlsRules`].
+#[derive(Clone)]
+pub struct MlsRulesAdapter(pub Arc<dyn MlsRules>);
+
+impl Debug for MlsRulesAdapter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MlsRulesAdapter").finish()
+    }
+}
+
+fn roster_identities(roster: &Roster) -> Vec<Arc<SigningIdentity>> {
+    roster
+        .members_iter()
+        .map(|member| Arc::new(member.signing_identity.into()))
+        .collect()
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+impl mls_rs::MlsRules for MlsRulesAdapter {
+    type Error = Error;
+
+    async fn filter_proposals(
+        &self,
+        direction: mls_rs::mls_rules::CommitDirection,
+        source: mls_rs::mls_rules::CommitSource,
+        current_roster: &Roster,
+        _extension_list: &mls_rs::ExtensionList,
+        mut proposals: ProposalBundle,
+    ) -> Result<ProposalBundle, Self::Error> {
+        let all_proposals = proposals
+            .iter_proposals()
+            .map(|info| Arc::new(mls_rs::group::proposal::Proposal::from(info.proposal).into()))
+            .collect();
+
+        let mut selected = self
+            .0
+            .filter_proposals(
+                direction.into(),
+                source.into(),
+                roster_identities(current_roster),
+                all_proposals,
+            )?
+            .into_iter()
+            .map(|proposal| proposal.inner.clone())
+            .collect::<Vec<_>>();
+
+        // Each selected proposal keeps at most one equal proposal of the bundle
+        let mut is_selected = |proposal: mls_rs::group::proposal::Proposal| {
+            let position = selected.iter().position(|p| p == &proposal);
+            position.map(|i| selected.swap_remove(i)).is_some()
+        };
+
+        proposals.retain(|info| Ok::<_, Error>(is_selected(info.proposal.clone().into())))?;
+
+        proposals.retain_custom(|info| {
+            Ok::<_, Error>(is_selected(mls_rs::group::proposal::Proposal::Custom(
+                info.proposal.clone(),
+            )))
+        })?;
+
+        Ok(proposals)
+    }
+
+    fn commit_options(
+        &self,
+        new_roster: &Roster,
+        _new_extension_list: &mls_rs::ExtensionList,
+        _proposals: &ProposalBundle,
+    ) -> Result<mls_rs::mls_rules::CommitOptions, Self::Error> {
+        self.0
+            .commit_options(roster_identities(new_roster))
+            .map(Into::into)
+    }
+
+    fn encryption_options(
+        &self,
+        current_roster: &Roster,
+        _current_extension_list: &mls_rs::ExtensionList,
+    ) -> Result<mls_rs::mls_rules::EncryptionOptions, Self::Error> {
+        self.0
+            .encryption_options(roster_identities(current_roster))
+            .map(Into::into)
+    }
+}
@@ -17,6 +17,7 @@
 //!
 //! [UniFFI]: https://mozilla.github.io/uniffi-rs/
 
+pub mod config;
 #[cfg(test)]
 pub mod test_utils;
 
@@ -27,14 +28,15 @@ use std::sync::Mutex;
 #[cfg(mls_build_async)]
 use tokio::sync::Mutex;
 
-use mls_rs::client_builder;
 use mls_rs::error::{IntoAnyError, MlsError};
 use mls_rs::group;
-use mls_rs::identity::basic;
+use mls_rs::storage_provider::in_memory::InMemoryPreSharedKeyStorage;
 use mls_rs::{CipherSuiteProvider, CryptoProvider};
 use mls_rs_core::identity;
-use mls_rs_core::identity::{BasicCredential, IdentityProvider};
-use mls_rs_crypto_openssl::OpensslCryptoProvider;
+use mls_rs_core::identity::BasicCredential;
+use mls_rs_core::psk::{ExternalPskId, PreSharedKey};
+
+use crate::config::{ClientConfig, UniFFIConfig};
 
 uniffi::setup_scaffolding!();
 
@@ -62,6 +64,24 @@ pub enum Error {
         #[from]
         inner: mls_rs::error::AnyError,
     },
+    #[error("A data encoding error occurred")]
+    MlsCodecError {
+        #[from]
+        inner: mls_rs_core::mls_rs_codec::Error,
+    },
+    #[error("Unsupported credential type {credential_type}")]
+    UnsupportedCredentialType { credential_type: u16 },
+    #[error("An unexpected error occurred in a callback")]
+    UnexpectedCallbackError {
+        #[from]
+        inner: uniffi::UnexpectedUniFFICallbackError,
+    },
+}
+
+impl IntoAnyError for Error {
+    fn into_dyn_error(self) -> Result<Box<dyn std::error::Error + Send + Sync>, Self> {
+        Ok(self.into())
+    }
 }
 
 /// A [`mls_rs::crypto::SignaturePublicKey`] wrapper.
@@ -96,11 +116,6 @@ pub struct SignatureKeypair {
     secret_key: Arc<SignatureSecretKey>,
 }
 
-pub type Config = client_builder::WithIdentityProvider<
-    basic::BasicIdentityProvider,
-    client_builder::WithCryptoProvider<OpensslCryptoProvider, client_builder::BaseConfig>,
->;
-
 /// Light-weight wrapper around a [`mls_rs::ExtensionList`].
 #[derive(uniffi::Object, Debug, Clone)]
 pub struct ExtensionList {
@@ -135,6 +150,15 @@ pub struct JoinInfo {
     pub group_info_extensions: Arc<ExtensionList>,
 }
 
+impl JoinInfo {
+    fn new(group: mls_rs::Group<UniFFIConfig>, new_member_info: group::NewMemberInfo) -> Self {
+        Self {
+            group: Arc::new(group.into()),
+            group_info_extensions: Arc::new(new_member_info.group_info_extensions.into()),
+        }
+    }
+}
+
 #[derive(Clone, Debug, uniffi::Object)]
 pub struct KeyPackage {
     _inner: mls_rs::KeyPackage,
@@ -160,12 +184,201 @@ impl From<mls_rs::MlsMessage> for Message {
 
 #[derive(Clone, Debug, uniffi::Object)]
 pub struct Proposal {
-    _inner: mls_rs::group::proposal::Proposal,
+    inner: mls_rs::group::proposal::Proposal,
 }
 
 impl From<mls_rs::group::proposal::Proposal> for Proposal {
     fn from(inner: mls_rs::group::proposal::Proposal) -> Self {
-        Self { _inner: inner }
+        Self { inner }
+    }
+}
+
+#[uniffi::export]
+impl Proposal {
+    /// The type of this proposal.
+    pub fn proposal_type(&self) -> u16 {
+        self.inner.proposal_type().raw_value()
+    }
+
+    /// The content of this proposal, if it is a custom proposal.
+    pub fn custom_proposal(&self) -> Option<CustomProposal> {
+        match &self.inner {
+            mls_rs::group::proposal::Proposal::Custom(proposal) => Some(proposal.clone().into()),
+            _ => None,
+        }
+    }
+}
+
+/// A proposal of a type defined by the application.
+///
+/// See [`mls_rs::group::proposal::CustomProposal`] for details.
+#[derive(Clone, Debug, uniffi::Record)]
+pub struct CustomProposal {
+    pub proposal_type: u16,
+    pub data: Vec<u8>,
+}
+
+impl From<mls_rs::group::proposal::CustomProposal> for CustomProposal {
+    fn from(proposal: mls_rs::group::proposal::CustomProposal) -> Self {
+        Self {
+            proposal_type: proposal.proposal_type().raw_value(),
+            data: proposal.data().to_vec(),
+        }
+    }
+}
+
+impl From<CustomProposal> for mls_rs::group::proposal::CustomProposal {
+    fn from(proposal: CustomProposal) -> Self {
+        Self::new(proposal.proposal_type.into(), proposal.data)
+    }
+}
+
+/// Capabilities of a group member, given as raw protocol values.
+///
+/// See [`mls_rs::group::Capabilities`] for details.
+#[derive(Clone, Debug, uniffi::Record)]
+pub struct Capabilities {
+    pub protocol_versions: Vec<u16>,
+    pub cipher_suites: Vec<u16>,
+    pub extensions: Vec<u16>,
+    pub proposals: Vec<u16>,
+    pub credentials: Vec<u16>,
+}
+
+impl From<mls_rs::group::Capabilities> for Capabilities {
+    fn from(capabilities: mls_rs::group::Capabilities) -> Self {
+        Self {
+            protocol_versions: capabilities
+                .protocol_versions
+                .iter()
+                .map(|version| version.raw_value())
+                .collect(),
+            cipher_suites: capabilities
+                .cipher_suites
+                .iter()
+                .map(|cipher_suite| cipher_suite.raw_value())
+                .collect(),
+            extensions: capabilities
+                .extensions
+                .iter()
+                .map(|extension| extension.raw_value())
+                .collect(),
+            proposals: capabilities
+                .proposals
+                .iter()
+                .map(|proposal| proposal.raw_value())
+                .collect(),
+            credentials: capabilities
+                .credentials
+                .iter()
+                .map(|credential| credential.raw_value())
+                .collect(),
+        }
+    }
+}
+
+/// A member of a group.
+///
+/// See [`mls_rs::group::Member`] for details.
+#[derive(Clone, Debug, uniffi::Record)]
+pub struct Member {
+    /// The index of the member in the group, which does not change as
+    /// the group evolves.
+    pub index: u32,
+    pub signing_identity: Arc<SigningIdentity>,
+    pub capabilities: Capabilities,
+}
+
+impl From<mls_rs::group::Member> for Member {
+    fn from(member: mls_rs::group::Member) -> Self {
+        Self {
+            index: member.index,
+            signing_identity: Arc::new(member.signing_identity.into()),
+            capabilities: member.capabilities.into(),
+        }
+    }
+}
+
+/// Update of a member due to a commit.
+#[derive(Clone, Debug, uniffi::Record)]
+pub struct MemberUpdate {
+    pub prior: Member,
+    pub new: Member,
+}
+
+/// Changes to the roster of a group due to a commit.
+///
+/// See [`mls_rs_core::group::RosterUpdate`] for details.
+#[derive(Clone, Debug, uniffi::Record)]
+pub struct RosterUpdate {
+    pub added: Vec<Member>,
+    pub removed: Vec<Member>,
+    pub updated: Vec<MemberUpdate>,
+}
+
+impl From<&mls_rs_core::group::RosterUpdate> for RosterUpdate {
+    fn from(update: &mls_rs_core::group::RosterUpdate) -> Self {
+        let to_members = |members: &[mls_rs::group::Member]| -> Vec<Member> {
+            members.iter().cloned().map(Into::into).collect()
+        };
+
+        Self {
+            added: to_members(update.added()),
+            removed: to_members(update.removed()),
+            updated: update
+                .updated()
+                .iter()
+                .map(|update| MemberUpdate {
+                    prior: update.prior.clone().into(),
+                    new: update.new.clone().into(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Changes to the state of a group due to a commit.
+///
+/// See [`mls_rs::group::StateUpdate`] for details.
+#[derive(Clone, Debug, uniffi::Record)]
+pub struct StateUpdate {
+    pub roster_update: RosterUpdate,
+    /// IDs of the external pre-shared keys used in the commit.
+    pub added_psks: Vec<Vec<u8>>,
+    /// The cipher suite of the reinitialized group if the commit
+    /// contained a ReInit proposal.
+    pub pending_reinit_cipher_suite: Option<u16>,
+    /// False if the member processing the commit was removed from the
+    /// group.
+    pub active: bool,
+    /// The new epoch of the group.
+    pub epoch: u64,
+    pub custom_proposals: Vec<CustomProposal>,
+    /// Proposals received in the prior epoch but not committed to.
+    pub unused_proposals: Vec<Arc<Proposal>>,
+}
+
+impl From<mls_rs::group::StateUpdate> for StateUpdate {
+    fn from(update: mls_rs::group::StateUpdate) -> Self {
+        Self {
+            roster_update: update.roster_update().into(),
+            added_psks: update.added_psks().iter().map(|id| id.to_vec()).collect(),
+            pending_reinit_cipher_suite: update
+                .pending_reinit_ciphersuite()
+                .map(|cipher_suite| cipher_suite.raw_value()),
+            active: update.is_active(),
+            epoch: update.new_epoch(),
+            custom_proposals: update
+                .custom_proposals()
+                .iter()
+                .map(|info| info.proposal.clone().into())
+                .collect(),
+            unused_proposals: update
+                .unused_proposals()
+                .iter()
+                .map(|info| Arc::new(info.proposal.clone().into()))
+                .collect(),
+        }
     }
 }
 
@@ -179,7 +392,10 @@ pub enum ReceivedMessage {
     },
 
     /// A new commit was processed creating a new group state.
-    Commit { committer: Arc<SigningIdentity> },
+    Commit {
+        committer: Arc<SigningIdentity>,
+        state_update: StateUpdate,
+    },
 
     /// A proposal was received.
     Proposal {
@@ -247,7 +463,8 @@ pub async fn generate_signature_keypair(
 /// See [`mls_rs::Client`] for details.
 #[derive(Clone, Debug, uniffi::Object)]
 pub struct Client {
-    inner: mls_rs::client::Client<Config>,
+    inner: mls_rs::client::Client<UniFFIConfig>,
+    psk_store: InMemoryPreSharedKeyStorage,
 }
 
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
@@ -260,21 +477,52 @@ impl Client {
     ///
     /// See [`mls_rs::Client::builder`] for details.
     #[uniffi::constructor]
-    pub fn new(id: Vec<u8>, signature_keypair: SignatureKeypair) -> Self {
+    pub fn new(
+        id: Vec<u8>,
+        signature_keypair: SignatureKeypair,
+        client_config: ClientConfig,
+    ) -> Result<Self, Error> {
         let cipher_suite = signature_keypair.cipher_suite;
         let public_key = arc_unwrap_or_clone(signature_keypair.public_key);
         let secret_key = arc_unwrap_or_clone(signature_keypair.secret_key);
-        let crypto_provider = OpensslCryptoProvider::new();
         let basic_credential = BasicCredential::new(id);
         let signing_identity =
             identity::SigningIdentity::new(basic_credential.into_credential(), public_key.inner);
-        Client {
-            inner: mls_rs::Client::builder()
-                .crypto_provider(crypto_provider)
-                .identity_provider(basic::BasicIdentityProvider::new())
-                .signing_identity(signing_identity, secret_key.inner, cipher_suite.into())
-                .build(),
-        }
+
+        let psk_store = InMemoryPreSharedKeyStorage::default();
+        let inner = client_config
+            .builder(psk_store.clone())?
+            .signing_identity(signing_identity, secret_key.inner, cipher_suite.into())
+            .build();
+
+        Ok(Client { inner, psk_store })
+    }
+
+    /// Make an external pre-shared key available to the groups of this
+    /// client.
+    ///
+    /// The key is kept in memory and is not written to storage.
+    pub fn insert_external_psk(&self, psk_id: Vec<u8>, psk: Vec<u8>) {
+        self.psk_store
+            .clone()
+            .insert(ExternalPskId::new(psk_id), PreSharedKey::new(psk));
+    }
+
+    /// Start building an external commit, which joins a group without
+    /// a welcome message.
+    ///
+    /// See [`mls_rs::Client::external_commit_builder`] for details.
+    pub fn external_commit_builder(&self) -> Result<ExternalCommitBuilder, Error> {
+        // Fail early if the client cannot sign the commit.
+        self.inner.external_commit_builder()?;
+
+        Ok(ExternalCommitBuilder {
+            client: self.inner.clone(),
+            to_remove: None,
+            authenticated_data: Vec::new(),
+            external_psks: Vec::new(),
+            custom_proposals: Vec::new(),
+        })
     }
 
     /// Generate a new key package for this client.
@@ -307,9 +555,18 @@ impl Client {
             }
             None => self.inner.create_group(extensions).await?,
         };
-        Ok(Group {
-            inner: Arc::new(Mutex::new(inner)),
-        })
+        Ok(inner.into())
+    }
+
+    /// Load an existing group from storage.
+    ///
+    /// The group must have been written to storage with
+    /// [`Group::write_to_storage`].
+    ///
+    /// See [`mls_rs::Client::load_group`] for details.
+    pub async fn load_group(&self, group_id: Vec<u8>) -> Result<Group, Error> {
+        let group = self.inner.load_group(&group_id).await?;
+        Ok(group.into())
     }
 
     /// Join an existing group.
@@ -317,18 +574,173 @@ impl Client {
     /// See [`mls_rs::Client::join_group`] for details.
     pub async fn join_group(&self, welcome_message: &Message) -> Result<JoinInfo, Error> {
         let (group, new_member_info) = self.inner.join_group(None, &welcome_message.inner).await?;
+        Ok(JoinInfo::new(group, new_member_info))
+    }
+}
 
-        let group = Arc::new(Group {
-            inner: Arc::new(Mutex::new(group)),
-        });
-        let group_info_extensions = Arc::new(new_member_info.group_info_extensions.into());
-        Ok(JoinInfo {
-            group,
-            group_info_extensions,
+/// Builder for an external commit.
+///
+/// See [`mls_rs::group::external_commit::ExternalCommitBuilder`] for
+/// details.
+#[derive(Clone, Debug, uniffi::Object)]
+pub struct ExternalCommitBuilder {
+    client: mls_rs::client::Client<UniFFIConfig>,
+    to_remove: Option<u32>,
+    authenticated_data: Vec<u8>,
+    external_psks: Vec<Vec<u8>>,
+    custom_proposals: Vec<CustomProposal>,
+}
+
+/// A group joined with an external commit.
+#[derive(Clone, uniffi::Record)]
+pub struct ExternalCommitOutput {
+    /// The group that was joined.
+    pub group: Arc<Group>,
+    /// The external commit to send to the members of the group.
+    pub commit_message: Arc<Message>,
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[uniffi::export]
+impl ExternalCommitBuilder {
+    /// Remove the prior leaf of this client at `index` as part of the
+    /// commit, e.g. to rejoin a group after losing state.
+    pub fn with_removal(self: Arc<Self>, index: u32) -> Arc<Self> {
+        let mut builder = arc_unwrap_or_clone(self);
+        builder.to_remove = Some(index);
+        Arc::new(builder)
+    }
+
+    /// Add plaintext authenticated data to the commit message.
+    pub fn with_authenticated_data(self: Arc<Self>, data: Vec<u8>) -> Arc<Self> {
+        let mut builder = arc_unwrap_or_clone(self);
+        builder.authenticated_data = data;
+        Arc::new(builder)
+    }
+
+    /// Inject the external pre-shared key `psk_id` into the commit.
+    pub fn with_external_psk(self: Arc<Self>, psk_id: Vec<u8>) -> Arc<Self> {
+        let mut builder = arc_unwrap_or_clone(self);
+        builder.external_psks.push(psk_id);
+        Arc::new(builder)
+    }
+
+    /// Include a custom proposal in the commit.
+    pub fn with_custom_proposal(self: Arc<Self>, proposal: CustomProposal) -> Arc<Self> {
+        let mut builder = arc_unwrap_or_clone(self);
+        builder.custom_proposals.push(proposal);
+        Arc::new(builder)
+    }
+
+    /// Join the group described by `group_info`.
+    ///
+    /// The group info must allow external commits and contain the
+    /// ratchet tree, see [`Group::group_info_message`].
+    pub async fn build(&self, group_info: Arc<Message>) -> Result<ExternalCommitOutput, Error> {
+        let mut builder = self
+            .client
+            .external_commit_builder()?
+            .with_authenticated_data(self.authenticated_data.clone());
+
+        if let Some(index) = self.to_remove {
+            builder = builder.with_removal(index);
+        }
+
+        for psk_id in &self.external_psks {
+            builder = builder.with_external_psk(ExternalPskId::new(psk_id.clone()));
+        }
+
+        for proposal in &self.custom_proposals {
+            builder = builder.with_custom_proposal(proposal.clone().into());
+        }
+
+        let group_info = arc_unwrap_or_clone(group_info);
+        let (group, commit_message) = builder.build(group_info.inner).await?;
+
+        Ok(ExternalCommitOutput {
+            group: Arc::new(group.into()),
+            commit_message: Arc::new(commit_message.into()),
         })
     }
 }
 
+/// A group created from a prior group by branching or
+/// reinitialization.
+#[derive(Clone, uniffi::Record)]
+pub struct ResumptionOutput {
+    /// The new group.
+    pub group: Arc<Group>,
+    /// Welcome messages to send to the members of the new group.
+    pub welcome_messages: Vec<Arc<Message>>,
+}
+
+impl ResumptionOutput {
+    fn new(group: mls_rs::Group<UniFFIConfig>, welcome_messages: Vec<mls_rs::MlsMessage>) -> Self {
+        Self {
+            group: Arc::new(group.into()),
+            welcome_messages: welcome_messages
+                .into_iter()
+                .map(|message| Arc::new(message.into()))
+                .collect(),
+        }
+    }
+}
+
+/// A client used to create or join the group reinitializing a group
+/// after a ReInit proposal was committed.
+///
+/// See [`mls_rs::group::ReinitClient`] for details.
+#[derive(Clone, uniffi::Object)]
+pub struct ReinitClient {
+    group: mls_rs::Group<UniFFIConfig>,
+    signer: Option<mls_rs::crypto::SignatureSecretKey>,
+    signing_identity: Option<identity::SigningIdentity>,
+}
+
+impl ReinitClient {
+    fn inner(&self) -> Result<mls_rs::group::ReinitClient<UniFFIConfig>, Error> {
+        Ok(self
+            .group
+            .clone()
+            .get_reinit_client(self.signer.clone(), self.signing_identity.clone())?)
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[uniffi::export]
+impl ReinitClient {
+    /// Generate a key package for the reinitialized group.
+    ///
+    /// See [`mls_rs::group::ReinitClient::generate_key_package`] for
+    /// details.
+    pub async fn generate_key_package_message(&self) -> Result<Message, Error> {
+        let message = self.inner()?.generate_key_package().await?;
+        Ok(message.into())
+    }
+
+    /// Create the reinitialized group from key packages of all members
+    /// of the prior group.
+    ///
+    /// See [`mls_rs::group::ReinitClient::commit`] for details.
+    pub async fn commit(&self, key_packages: Vec<Arc<Message>>) -> Result<ResumptionOutput, Error> {
+        let key_packages = key_packages
+            .into_iter()
+            .map(|key_package| arc_unwrap_or_clone(key_package).inner)
+            .collect();
+
+        let (group, welcome_messages) = self.inner()?.commit(key_packages).await?;
+        Ok(ResumptionOutput::new(group, welcome_messages))
+    }
+
+    /// Join a reinitialized group created by another member.
+    ///
+    /// See [`mls_rs::group::ReinitClient::join`] for details.
+    pub async fn join(&self, welcome_message: &Message) -> Result<JoinInfo, Error> {
+        let (group, new_member_info) = self.inner()?.join(&welcome_message.inner, None).await?;
+        Ok(JoinInfo::new(group, new_member_info))
+    }
+}
+
 #[derive(Clone, Debug, uniffi::Object)]
 pub struct CommitOutput {
     inner: mls_rs::group::CommitOutput,
@@ -350,8 +762,26 @@ impl CommitOutput {
             .collect::<Vec<_>>()
     }
 
-    // TODO(mgeisler): decide if we should expose ratchet_tree(),
-    // external_commit_group_info(), and unused_proposals() as well.
+    /// Group info allowing external commits, if requested by the
+    /// [`MlsRules`](crate::config::mls_rules::MlsRules) in use.
+    pub fn external_commit_group_info(&self) -> Option<Arc<Message>> {
+        self.inner
+            .external_commit_group_info
+            .clone()
+            .map(|group_info| Arc::new(group_info.into()))
+    }
+
+    /// Proposals that were received in the prior epoch but not included
+    /// in the commit.
+    pub fn unused_proposals(&self) -> Vec<Arc<Proposal>> {
+        self.inner
+            .unused_proposals
+            .iter()
+            .map(|info| Arc::new(info.proposal.clone().into()))
+            .collect()
+    }
+
+    // TODO(mgeisler): decide if we should expose ratchet_tree() as well.
 }
 
 impl From<mls_rs::group::CommitOutput> for CommitOutput {
@@ -371,6 +801,38 @@ impl From<identity::SigningIdentity> for SigningIdentity {
     }
 }
 
+#[uniffi::export]
+impl SigningIdentity {
+    /// The signature public key of this identity.
+    pub fn signature_key(&self) -> Vec<u8> {
+        self.inner.signature_key.to_vec()
+    }
+
+    /// The type of the credential of this identity.
+    pub fn credential_type(&self) -> u16 {
+        self.inner.credential.credential_type().raw_value()
+    }
+
+    /// The identifier of a basic credential, if this identity uses one.
+    pub fn basic_identifier(&self) -> Option<Vec<u8>> {
+        self.inner
+            .credential
+            .as_basic()
+            .map(|credential| credential.identifier.clone())
+    }
+
+    /// The DER encoded certificates of an X.509 credential, starting with
+    /// the leaf, if this identity uses one.
+    pub fn certificate_chain(&self) -> Option<Vec<Vec<u8>>> {
+        self.inner.credential.as_x509().map(|chain| {
+            chain
+                .iter()
+                .map(|certificate| certificate.to_vec())
+                .collect()
+        })
+    }
+}
+
 /// An MLS end-to-end encrypted group.
 ///
 /// The group is used to send and process incoming messages and to
@@ -379,25 +841,33 @@ impl From<identity::SigningIdentity> for SigningIdentity {
 /// See [`mls_rs::Group`] for details.
 #[derive(Clone, uniffi::Object)]
 pub struct Group {
-    inner: Arc<Mutex<mls_rs::Group<Config>>>,
+    inner: Arc<Mutex<mls_rs::Group<UniFFIConfig>>>,
+}
+
+impl From<mls_rs::Group<UniFFIConfig>> for Group {
+    fn from(inner: mls_rs::Group<UniFFIConfig>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(inner)),
+        }
+    }
 }
 
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 impl Group {
     #[cfg(not(mls_build_async))]
-    fn inner(&self) -> std::sync::MutexGuard<'_, mls_rs::Group<Config>> {
+    fn inner(&self) -> std::sync::MutexGuard<'_, mls_rs::Group<UniFFIConfig>> {
         self.inner.lock().unwrap()
     }
 
     #[cfg(mls_build_async)]
-    async fn inner(&self) -> tokio::sync::MutexGuard<'_, mls_rs::Group<Config>> {
+    async fn inner(&self) -> tokio::sync::MutexGuard<'_, mls_rs::Group<UniFFIConfig>> {
         self.inner.lock().await
     }
 }
 
 /// Find the identity for the member with a given index.
 fn index_to_identity(
-    group: &mls_rs::Group<Config>,
+    group: &mls_rs::Group<UniFFIConfig>,
     index: u32,
 ) -> Result<identity::SigningIdentity, Error> {
     let member = group
@@ -406,21 +876,36 @@ fn index_to_identity(
     Ok(member.signing_identity)
 }
 
-/// Extract the basic credential identifier from a  from a key package.
-#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-async fn signing_identity_to_identifier(
+/// Find the index of the member with a given signing identity.
+fn identity_to_index(
+    group: &mls_rs::Group<UniFFIConfig>,
     signing_identity: &identity::SigningIdentity,
-) -> Result<Vec<u8>, Error> {
-    let identifier = basic::BasicIdentityProvider::new()
-        .identity(signing_identity, &mls_rs::ExtensionList::new())
-        .await
-        .map_err(|err| err.into_any_error())?;
-    Ok(identifier)
+) -> Result<u32, Error> {
+    let member = group
+        .roster()
+        .members_iter()
+        .find(|member| &member.signing_identity == signing_identity)
+        .ok_or(MlsError::MemberNotFound)?;
+    Ok(member.index)
 }
 
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 #[uniffi::export]
 impl Group {
+    /// Write the current state of the group to storage defined by
+    /// [`ClientConfig::storage`].
+    ///
+    /// See [`mls_rs::Group::write_to_storage`] for details.
+    pub async fn write_to_storage(&self) -> Result<(), Error> {
+        let mut group = self.inner().await;
+        group.write_to_storage().await.map_err(Into::into)
+    }
+
+    /// The ID of this group.
+    pub async fn group_id(&self) -> Vec<u8> {
+        self.inner().await.group_id().to_vec()
+    }
+
     /// Perform a commit of received proposals (or an empty commit).
     ///
     /// TODO: ensure `path_required` is always set in
@@ -489,9 +974,7 @@ impl Group {
         // Find member indices
         let mut member_indixes = Vec::with_capacity(signing_identities.len());
         for signing_identity in signing_identities {
-            let identifier = signing_identity_to_identifier(&signing_identity.inner).await?;
-            let member = group.member_with_identity(&identifier).await?;
-            member_indixes.push(member.index);
+            member_indixes.push(identity_to_index(&group, &signing_identity.inner)?);
         }
 
         let mut commit_builder = group.commit_builder();
@@ -516,9 +999,8 @@ impl Group {
 
         let mut messages = Vec::with_capacity(signing_identities.len());
         for signing_identity in signing_identities {
-            let identifier = signing_identity_to_identifier(&signing_identity.inner).await?;
-            let member = group.member_with_identity(&identifier).await?;
-            let message = group.propose_remove(member.index, Vec::new()).await?;
+            let index = identity_to_index(&group, &signing_identity.inner)?;
+            let message = group.propose_remove(index, Vec::new()).await?;
             messages.push(Arc::new(message.into()));
         }
 
@@ -551,7 +1033,11 @@ impl Group {
             group::ReceivedMessage::Commit(commit_message) => {
                 let committer =
                     Arc::new(index_to_identity(&group, commit_message.committer)?.into());
-                Ok(ReceivedMessage::Commit { committer })
+                let state_update = commit_message.state_update.into();
+                Ok(ReceivedMessage::Commit {
+                    committer,
+                    state_update,
+                })
             }
             group::ReceivedMessage::Proposal(proposal_message) => {
                 let sender = match proposal_message.sender {
@@ -574,6 +1060,244 @@ impl Group {
             }
         }
     }
+
+    /// The current epoch of the group.
+    pub async fn current_epoch(&self) -> u64 {
+        self.inner().await.current_epoch()
+    }
+
+    /// The current members of the group.
+    ///
+    /// See [`mls_rs::group::Roster::members`] for details.
+    pub async fn roster(&self) -> Vec<Member> {
+        let group = self.inner().await;
+        group.roster().members_iter().map(Into::into).collect()
+    }
+
+    /// Create a group info message describing the current epoch.
+    ///
+    /// The ratchet tree is always included in the message. If
+    /// `allow_external_commit` is true, the message can be used to join
+    /// the group with an [`ExternalCommitBuilder`].
+    ///
+    /// See [`mls_rs::Group::group_info_message`] and
+    /// [`mls_rs::Group::group_info_message_allowing_ext_commit`] for
+    /// details.
+    pub async fn group_info_message(&self, allow_external_commit: bool) -> Result<Message, Error> {
+        let group = self.inner().await;
+
+        let group_info = if allow_external_commit {
+            group.group_info_message_allowing_ext_commit(true).await?
+        } else {
+            group.group_info_message(true).await?
+        };
+
+        Ok(group_info.into())
+    }
+
+    /// Export a secret of `len` bytes derived from the current epoch.
+    ///
+    /// See [`mls_rs::Group::export_secret`] for details.
+    pub async fn export_secret(
+        &self,
+        label: Vec<u8>,
+        context: Vec<u8>,
+        len: u64,
+    ) -> Result<Vec<u8>, Error> {
+        let group = self.inner().await;
+        let secret = group.export_secret(&label, &context, len as usize).await?;
+        Ok(secret.to_vec())
+    }
+
+    /// The epoch authenticator of the current epoch, which can be
+    /// compared out of band to detect a split view of the group.
+    ///
+    /// See [`mls_rs::Group::epoch_authenticator`] for details.
+    pub async fn epoch_authenticator(&self) -> Result<Vec<u8>, Error> {
+        let group = self.inner().await;
+        Ok(group.epoch_authenticator()?.to_vec())
+    }
+
+    /// Propose a custom proposal.
+    ///
+    /// The proposal type must be listed in
+    /// [`ClientConfig::custom_proposal_types`] of all members.
+    ///
+    /// See [`mls_rs::Group::propose_custom`] for details.
+    pub async fn propose_custom(&self, proposal: CustomProposal) -> Result<Message, Error> {
+        let mut group = self.inner().await;
+        let message = group.propose_custom(proposal.into(), Vec::new()).await?;
+        Ok(message.into())
+    }
+
+    /// Commit one or more custom proposals together with received
+    /// proposals.
+    ///
+    /// See [`mls_rs::group::CommitBuilder::custom_proposal`] for details.
+    pub async fn commit_custom_proposals(
+        &self,
+        proposals: Vec<CustomProposal>,
+    ) -> Result<CommitOutput, Error> {
+        let mut group = self.inner().await;
+        let mut commit_builder = group.commit_builder();
+        for proposal in proposals {
+            commit_builder = commit_builder.custom_proposal(proposal.into());
+        }
+        let commit_output = commit_builder.build().await?;
+        Ok(commit_output.into())
+    }
+
+    /// Propose to inject the external pre-shared key `psk_id` into the
+    /// key schedule.
+    ///
+    /// The key must be known to all members, see
+    /// [`Client::insert_external_psk`].
+    ///
+    /// See [`mls_rs::Group::propose_external_psk`] for details.
+    pub async fn propose_external_psk(&self, psk_id: Vec<u8>) -> Result<Message, Error> {
+        let mut group = self.inner().await;
+        let message = group
+            .propose_external_psk(ExternalPskId::new(psk_id), Vec::new())
+            .await?;
+        Ok(message.into())
+    }
+
+    /// Propose to inject the resumption secret of a prior `epoch` into
+    /// the key schedule.
+    ///
+    /// See [`mls_rs::Group::propose_resumption_psk`] for details.
+    pub async fn propose_resumption_psk(&self, epoch: u64) -> Result<Message, Error> {
+        let mut group = self.inner().await;
+        let message = group.propose_resumption_psk(epoch, Vec::new()).await?;
+        Ok(message.into())
+    }
+
+    /// Commit the injection of external pre-shared keys together with
+    /// received proposals.
+    ///
+    /// See [`mls_rs::group::CommitBuilder::add_external_psk`] for
+    /// details.
+    pub async fn commit_external_psks(&self, psk_ids: Vec<Vec<u8>>) -> Result<CommitOutput, Error> {
+        let mut group = self.inner().await;
+        let mut commit_builder = group.commit_builder();
+        for psk_id in psk_ids {
+            commit_builder = commit_builder.add_external_psk(ExternalPskId::new(psk_id))?;
+        }
+        let commit_output = commit_builder.build().await?;
+        Ok(commit_output.into())
+    }
+
+    /// Propose to reinitialize the group with a new cipher suite.
+    ///
+    /// If a group ID is not given, a random ID is used for the new
+    /// group. Once the proposal is committed, the new group is created
+    /// with a [`ReinitClient`].
+    ///
+    /// See [`mls_rs::Group::propose_reinit`] for details.
+    pub async fn propose_reinit(
+        &self,
+        group_id: Option<Vec<u8>>,
+        cipher_suite: CipherSuite,
+    ) -> Result<Message, Error> {
+        let mut group = self.inner().await;
+        let version = group.protocol_version();
+        let extensions = group.context().extensions().clone();
+
+        let message = group
+            .propose_reinit(
+                group_id,
+                version,
+                cipher_suite.into(),
+                extensions,
+                Vec::new(),
+            )
+            .await?;
+
+        Ok(message.into())
+    }
+
+    /// Get a client for the reinitialized group after a ReInit proposal
+    /// was committed.
+    ///
+    /// The signing identity of this group is reused unless a new
+    /// signature keypair is given. The new keypair is used with the
+    /// credential of the current signing identity.
+    ///
+    /// See [`mls_rs::Group::get_reinit_client`] for details.
+    pub async fn get_reinit_client(
+        &self,
+        signature_keypair: Option<SignatureKeypair>,
+    ) -> Result<ReinitClient, Error> {
+        let group = self.inner().await.clone();
+
+        let (signer, signing_identity) = match signature_keypair {
+            Some(signature_keypair) => {
+                let public_key = arc_unwrap_or_clone(signature_keypair.public_key);
+                let secret_key = arc_unwrap_or_clone(signature_keypair.secret_key);
+                let credential = group.current_member_signing_identity()?.credential.clone();
+                let signing_identity = identity::SigningIdentity::new(credential, public_key.inner);
+                (Some(secret_key.inner), Some(signing_identity))
+            }
+            None => (None, None),
+        };
+
+        let reinit_client = ReinitClient {
+            group,
+            signer,
+            signing_identity,
+        };
+
+        // Fail early if there is no pending ReInit proposal.
+        reinit_client.inner()?;
+
+        Ok(reinit_client)
+    }
+
+    /// Create a subgroup of this group with the members given by
+    /// `key_packages`.
+    ///
+    /// See [`mls_rs::Group::branch`] for details.
+    pub async fn branch(
+        &self,
+        sub_group_id: Vec<u8>,
+        key_packages: Vec<Arc<Message>>,
+    ) -> Result<ResumptionOutput, Error> {
+        let group = self.inner().await;
+        let key_packages = key_packages
+            .into_iter()
+            .map(|key_package| arc_unwrap_or_clone(key_package).inner)
+            .collect();
+
+        let (group, welcome_messages) = group.branch(sub_group_id, key_packages).await?;
+        Ok(ResumptionOutput::new(group, welcome_messages))
+    }
+
+    /// Join a subgroup of this group created by [`Group::branch`].
+    ///
+    /// See [`mls_rs::Group::join_subgroup`] for details.
+    pub async fn join_subgroup(&self, welcome_message: &Message) -> Result<JoinInfo, Error> {
+        let group = self.inner().await;
+        let (group, new_member_info) = group.join_subgroup(&welcome_message.inner, None).await?;
+        Ok(JoinInfo::new(group, new_member_info))
+    }
+
+    /// Apply the pending commit created by this member, instead of
+    /// processing the commit message.
+    ///
+    /// See [`mls_rs::Group::apply_pending_commit`] for details.
+    pub async fn apply_pending_commit(&self) -> Result<StateUpdate, Error> {
+        let mut group = self.inner().await;
+        let commit_message = group.apply_pending_commit().await?;
+        Ok(commit_message.state_update.into())
+    }
+
+    /// Discard the pending commit created by this member, e.g. if the
+    /// delivery service rejected it.
+    ///
+    /// See [`mls_rs::Group::clear_pending_commit`] for details.
+    pub async fn clear_pending_commit(&self) {
+        self.inner().await.clear_pending_commit()
+    }
 }
 
 #[cfg(all(test, not(mls_build_async)))]
@@ -588,6 +1312,365 @@ from mls_rs_uniffi import CipherSuite, generate_signature_keypair
 
 signature_keypair = generate_signature_keypair(CipherSuite.CURVE25519_AES128)
 assert signature_keypair.cipher_suite == CipherSuite.CURVE25519_AES128
+"#,
+        )
+    }
+
+    #[test]
+    fn test_custom_storage() -> Result<(), Box<dyn std::error::Error>> {
+        run_python(
+            r#"
+from mls_rs_uniffi import (CipherSuite, Client, GroupStateStorage, KeyPackageStorage,
+                           ReceivedMessage, StorageConfig, client_config_default,
+                           generate_signature_keypair)
+
+class PythonGroupStateStorage(GroupStateStorage):
+    def __init__(self):
+        self.states = {}
+        self.epochs = {}
+
+    def state(self, group_id):
+        return self.states.get(group_id)
+
+    def epoch(self, group_id, epoch_id):
+        return self.epochs.get(group_id, {}).get(epoch_id)
+
+    def write(self, state, epoch_inserts, epoch_updates):
+        self.states[state.id] = state.data
+        epochs = self.epochs.setdefault(state.id, {})
+        for epoch in epoch_inserts + epoch_updates:
+            epochs[epoch.id] = epoch.data
+
+    def max_epoch_id(self, group_id):
+        return max(self.epochs.get(group_id, {}), default=None)
+
+class PythonKeyPackageStorage(KeyPackageStorage):
+    def __init__(self):
+        self.key_packages = {}
+
+    def delete(self, id):
+        self.key_packages.pop(id, None)
+
+    def insert(self, id, data):
+        self.key_packages[id] = data
+
+    def get(self, id):
+        return self.key_packages.get(id)
+
+def make_client(name, storage):
+    client_config = client_config_default()
+    client_config.storage = storage
+    key = generate_signature_keypair(CipherSuite.CURVE25519_AES128)
+    return lambda: Client(name, key, client_config)
+
+alice_storage = StorageConfig.CUSTOM(PythonGroupStateStorage(), PythonKeyPackageStorage())
+bob_storage = StorageConfig.CUSTOM(PythonGroupStateStorage(), PythonKeyPackageStorage())
+new_alice = make_client(b"alice", alice_storage)
+new_bob = make_client(b"bob", bob_storage)
+
+alice = new_alice()
+group = alice.create_group(None)
+
+bob = new_bob()
+commit = group.add_members([bob.generate_key_package_message()])
+group.process_incoming_message(commit.commit_message())
+bob_group = bob.join_group(commit.welcome_messages()[0]).group
+bob_group.write_to_storage()
+
+# Reload Bob's group in a fresh client backed by the same storage.
+bob_group = new_bob().load_group(group.group_id())
+assert bob_group.group_id() == group.group_id()
+
+message = group.encrypt_application_message(b"hello, bob")
+received = bob_group.process_incoming_message(message)
+assert isinstance(received, ReceivedMessage.APPLICATION_MESSAGE)
+assert received.data == b"hello, bob"
+"#,
+        )
+    }
+
+    #[test]
+    fn test_custom_identity_provider() -> Result<(), Box<dyn std::error::Error>> {
+        run_python(
+            r#"
+from mls_rs_uniffi import (CipherSuite, Client, Error, IdentityProvider, client_config_default,
+                           generate_signature_keypair)
+
+class AllowListIdentityProvider(IdentityProvider):
+    def __init__(self, allowed):
+        self.allowed = allowed
+        self.validated = []
+
+    def validate_member(self, signing_identity, timestamp):
+        self.validated.append(signing_identity.basic_identifier())
+        if signing_identity.basic_identifier() not in self.allowed:
+            raise ValueError("unknown member")
+
+    def validate_external_sender(self, signing_identity, timestamp):
+        raise ValueError("external senders are not allowed")
+
+    def identity(self, signing_identity):
+        return signing_identity.basic_identifier()
+
+    def valid_successor(self, predecessor, successor):
+        return predecessor.basic_identifier() == successor.basic_identifier()
+
+    def supported_types(self):
+        return [1]
+
+identity_provider = AllowListIdentityProvider([b"alice", b"bob"])
+client_config = client_config_default()
+client_config.identity_provider = identity_provider
+
+def make_client(name, config):
+    return Client(name, generate_signature_keypair(CipherSuite.CURVE25519_AES128), config)
+
+alice = make_client(b"alice", client_config)
+bob = make_client(b"bob", client_config_default())
+carol = make_client(b"carol", client_config_default())
+
+group = alice.create_group(None)
+commit = group.add_members([bob.generate_key_package_message()])
+group.process_incoming_message(commit.commit_message())
+assert b"bob" in identity_provider.validated
+
+try:
+    group.add_members([carol.generate_key_package_message()])
+except Error:
+    pass
+else:
+    raise AssertionError("carol must be rejected by the identity provider")
+
+assert b"carol" in identity_provider.validated
+assert len(group.roster()) == 2
+"#,
+        )
+    }
+
+    #[test]
+    fn test_custom_mls_rules() -> Result<(), Box<dyn std::error::Error>> {
+        run_python(
+            r#"
+from mls_rs_uniffi import (CipherSuite, Client, CommitDirection, CommitOptions, CommitSource,
+                           EncryptionOptions, MlsRules, PaddingMode, ReceivedMessage,
+                           client_config_default, generate_signature_keypair)
+
+REMOVE = 3
+
+class NoRemovalMlsRules(MlsRules):
+    def __init__(self):
+        self.filtered = []
+
+    def filter_proposals(self, direction, source, current_roster, proposals):
+        self.filtered.append((direction, source, [p.proposal_type() for p in proposals]))
+        return [p for p in proposals if p.proposal_type() != REMOVE]
+
+    def commit_options(self, new_roster):
+        return CommitOptions(path_required=True, ratchet_tree_extension=True,
+                             single_welcome_message=True, allow_external_commit=False)
+
+    def encryption_options(self, current_roster):
+        return EncryptionOptions(encrypt_control_messages=True, padding_mode=PaddingMode.NONE)
+
+mls_rules = NoRemovalMlsRules()
+client_config = client_config_default()
+client_config.mls_rules = mls_rules
+
+def make_client(name, config):
+    return Client(name, generate_signature_keypair(CipherSuite.CURVE25519_AES128), config)
+
+alice = make_client(b"alice", client_config)
+bob = make_client(b"bob", client_config_default())
+
+group = alice.create_group(None)
+commit = group.add_members([bob.generate_key_package_message()])
+
+direction, source, proposal_types = mls_rules.filtered[-1]
+assert direction == CommitDirection.SEND
+assert isinstance(source, CommitSource.EXISTING_MEMBER)
+assert source.member.signing_identity.basic_identifier() == b"alice"
+
+group.process_incoming_message(commit.commit_message())
+bob_group = bob.join_group(commit.welcome_messages()[0]).group
+
+# The removal of Bob is filtered out of Alice's commit
+bob_identity = [m.signing_identity for m in group.roster() if m.index == 1][0]
+commit = group.remove_members([bob_identity])
+assert mls_rules.filtered[-1][2] == [REMOVE]
+group.process_incoming_message(commit.commit_message())
+assert [member.index for member in group.roster()] == [0, 1]
+
+received = bob_group.process_incoming_message(commit.commit_message())
+assert isinstance(received, ReceivedMessage.COMMIT)
+
+commit = bob_group.commit()
+bob_group.process_incoming_message(commit.commit_message())
+received = group.process_incoming_message(commit.commit_message())
+assert isinstance(received, ReceivedMessage.COMMIT)
+assert mls_rules.filtered[-1][0] == CommitDirection.RECEIVE
+
+message = group.encrypt_application_message(b"hello, bob")
+received = bob_group.process_incoming_message(message)
+assert received.data == b"hello, bob"
+"#,
+        )
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_sqlite_storage() -> Result<(), Box<dyn std::error::Error>> {
+        run_python(
+            r#"
+import os
+import tempfile
+
+from mls_rs_uniffi import (CipherSuite, Client, StorageConfig, client_config_default,
+                           generate_signature_keypair)
+
+with tempfile.TemporaryDirectory() as tmp_dir:
+    client_config = client_config_default()
+    client_config.storage = StorageConfig.SQLITE(os.path.join(tmp_dir, "mls.db"))
+    key = generate_signature_keypair(CipherSuite.CURVE25519_AES128)
+
+    group = Client(b"alice", key, client_config).create_group(None)
+    group.write_to_storage()
+
+    group_id = group.group_id()
+    del group
+
+    group = Client(b"alice", key, client_config).load_group(group_id)
+    assert group.group_id() == group_id
+"#,
+        )
+    }
+
+    #[test]
+    fn test_external_commit() -> Result<(), Box<dyn std::error::Error>> {
+        run_python(
+            r#"
+from mls_rs_uniffi import (CipherSuite, Client, ReceivedMessage, client_config_default,
+                           generate_signature_keypair)
+
+client_config = client_config_default()
+alice = Client(b"alice", generate_signature_keypair(CipherSuite.CURVE25519_AES128), client_config)
+bob = Client(b"bob", generate_signature_keypair(CipherSuite.CURVE25519_AES128), client_config)
+
+alice_group = alice.create_group(None)
+group_info = alice_group.group_info_message(True)
+
+output = bob.external_commit_builder().with_authenticated_data(b"hello").build(group_info)
+bob_group = output.group
+
+received = alice_group.process_incoming_message(output.commit_message)
+assert isinstance(received, ReceivedMessage.COMMIT)
+added = received.state_update.roster_update.added
+assert [member.signing_identity.basic_identifier() for member in added] == [b"bob"]
+
+assert alice_group.current_epoch() == bob_group.current_epoch() == 1
+assert alice_group.epoch_authenticator() == bob_group.epoch_authenticator()
+assert (alice_group.export_secret(b"label", b"context", 32)
+        == bob_group.export_secret(b"label", b"context", 32))
+
+roster = bob_group.roster()
+assert [member.index for member in roster] == [0, 1]
+# Capabilities list IANA code points, CURVE25519_AES128 is cipher suite 1
+assert all(1 in member.capabilities.cipher_suites for member in roster)
+"#,
+        )
+    }
+
+    #[test]
+    fn test_custom_proposals_and_psks() -> Result<(), Box<dyn std::error::Error>> {
+        run_python(
+            r#"
+from mls_rs_uniffi import (CipherSuite, Client, CustomProposal, ReceivedMessage,
+                           client_config_default, generate_signature_keypair)
+
+client_config = client_config_default()
+client_config.custom_proposal_types = [0xF000]
+alice = Client(b"alice", generate_signature_keypair(CipherSuite.CURVE25519_AES128), client_config)
+bob = Client(b"bob", generate_signature_keypair(CipherSuite.CURVE25519_AES128), client_config)
+
+for client in [alice, bob]:
+    client.insert_external_psk(b"psk id", b"psk secret")
+
+alice_group = alice.create_group(None)
+commit = alice_group.add_members([bob.generate_key_package_message()])
+alice_group.apply_pending_commit()
+bob_group = bob.join_group(commit.welcome_messages()[0]).group
+
+# Custom proposal committed by value.
+custom_proposal = CustomProposal(proposal_type=0xF000, data=b"data")
+commit = alice_group.commit_custom_proposals([custom_proposal])
+state_update = alice_group.apply_pending_commit()
+assert state_update.custom_proposals == [custom_proposal]
+
+received = bob_group.process_incoming_message(commit.commit_message())
+assert received.state_update.custom_proposals == [custom_proposal]
+
+# Custom proposal sent by reference.
+proposal = alice_group.propose_custom(custom_proposal)
+received = bob_group.process_incoming_message(proposal)
+assert isinstance(received, ReceivedMessage.PROPOSAL)
+assert received.proposal.custom_proposal() == custom_proposal
+
+commit = bob_group.commit()
+bob_group.apply_pending_commit()
+received = alice_group.process_incoming_message(commit.commit_message())
+assert received.state_update.custom_proposals == [custom_proposal]
+
+# External pre-shared key.
+commit = alice_group.commit_external_psks([b"psk id"])
+alice_group.apply_pending_commit()
+received = bob_group.process_incoming_message(commit.commit_message())
+assert received.state_update.added_psks == [b"psk id"]
+assert alice_group.epoch_authenticator() == bob_group.epoch_authenticator()
+
+# A pending commit can be discarded.
+alice_group.commit()
+alice_group.clear_pending_commit()
+assert alice_group.current_epoch() == bob_group.current_epoch()
+"#,
+        )
+    }
+
+    #[test]
+    fn test_branch_and_reinit() -> Result<(), Box<dyn std::error::Error>> {
+        run_python(
+            r#"
+from mls_rs_uniffi import (CipherSuite, Client, client_config_default,
+                           generate_signature_keypair)
+
+client_config = client_config_default()
+alice = Client(b"alice", generate_signature_keypair(CipherSuite.CURVE25519_AES128), client_config)
+bob = Client(b"bob", generate_signature_keypair(CipherSuite.CURVE25519_AES128), client_config)
+
+alice_group = alice.create_group(None)
+commit = alice_group.add_members([bob.generate_key_package_message()])
+alice_group.apply_pending_commit()
+bob_group = bob.join_group(commit.welcome_messages()[0]).group
+
+# Branch into a subgroup.
+output = alice_group.branch(b"subgroup", [bob.generate_key_package_message()])
+bob_subgroup = bob_group.join_subgroup(output.welcome_messages[0]).group
+assert bob_subgroup.group_id() == output.group.group_id() == b"subgroup"
+
+# Reinitialize the group.
+proposal = alice_group.propose_reinit(b"reinit", CipherSuite.CURVE25519_AES128)
+bob_group.process_incoming_message(proposal)
+
+commit = alice_group.commit()
+alice_group.apply_pending_commit()
+received = bob_group.process_incoming_message(commit.commit_message())
+assert received.state_update.pending_reinit_cipher_suite == 1
+
+alice_reinit = alice_group.get_reinit_client(None)
+bob_reinit = bob_group.get_reinit_client(None)
+
+output = alice_reinit.commit([bob_reinit.generate_key_package_message()])
+bob_new_group = bob_reinit.join(output.welcome_messages[0]).group
+assert bob_new_group.group_id() == output.group.group_id() == b"reinit"
+assert output.group.epoch_authenticator() == bob_new_group.epoch_authenticator()
 "#,
         )
     }
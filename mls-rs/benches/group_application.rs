@@ -3,10 +3,13 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
 use criterion::{BatchSize, BenchmarkId, Criterion, Throughput};
-use mls_rs::test_utils::benchmarks::load_group_states;
-use mls_rs::CipherSuite;
+use mls_rs::test_utils::benchmarks::{load_group_states, CountingAllocator};
+use mls_rs::{CipherSuite, MlsMessage};
 use rand::RngCore;
 
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
 fn bench(c: &mut Criterion) {
     let cipher_suite = CipherSuite::CURVE25519_AES128;
     let group_states = load_group_states(cipher_suite).pop().unwrap();
@@ -19,6 +22,27 @@ fn bench(c: &mut Criterion) {
     let mut bench_group = c.benchmark_group("group_application");
 
     while n <= 1000000 {
+        let msg = group_states
+            .clone()
+            .sender
+            .encrypt_application_message(&bytes[..n], vec![])
+            .unwrap()
+            .to_bytes()
+            .unwrap();
+
+        let (mut receiver, mut bytes_receiver) =
+            (group_states.receiver.clone(), group_states.receiver.clone());
+
+        let owned = CountingAllocator::count(|| {
+            let msg = MlsMessage::from_bytes(&msg).unwrap();
+            receiver.process_incoming_message(msg).unwrap()
+        });
+
+        let borrowed =
+            CountingAllocator::count(|| bytes_receiver.process_incoming_bytes(&msg).unwrap());
+
+        println!("group_application/{n}: {owned} allocations, {borrowed} from bytes");
+
         bench_group.throughput(Throughput::Bytes(n as u64));
         bench_group.bench_with_input(
             BenchmarkId::new(format!("{cipher_suite:?}"), n),
@@ -39,6 +63,27 @@ fn bench(c: &mut Criterion) {
             },
         );
 
+        bench_group.bench_with_input(
+            BenchmarkId::new(format!("{cipher_suite:?}/bytes"), n),
+            &n,
+            |b, _| {
+                b.iter_batched_ref(
+                    || group_states.clone(),
+                    move |group_states| {
+                        let msg = group_states
+                            .sender
+                            .encrypt_application_message(&bytes[..n], vec![])
+                            .unwrap()
+                            .to_bytes()
+                            .unwrap();
+
+                        group_states.receiver.process_incoming_bytes(&msg).unwrap();
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+
         n *= 10;
     }
     bench_group.finish();
@@ -2,10 +2,17 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use mls_rs::{test_utils::benchmarks::load_group_states, CipherSuite};
+use mls_rs::{
+    group::ExportedTree,
+    test_utils::benchmarks::{load_group_states, CountingAllocator},
+    CipherSuite,
+};
 
 use criterion::{BenchmarkId, Criterion};
 
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
 fn bench_serialize(c: &mut Criterion) {
     use criterion::BatchSize;
 
@@ -26,5 +33,30 @@ fn bench_serialize(c: &mut Criterion) {
     bench_group.finish();
 }
 
-criterion::criterion_group!(benches, bench_serialize);
+fn bench_tree_decode(c: &mut Criterion) {
+    let cs = CipherSuite::CURVE25519_AES128;
+    let group_states = load_group_states(cs);
+    let mut bench_group = c.benchmark_group("tree_decode");
+
+    for (i, group_states) in group_states.into_iter().enumerate() {
+        let tree = group_states.sender.export_tree().to_bytes().unwrap();
+
+        let owned = CountingAllocator::count(|| ExportedTree::from_bytes(&tree).unwrap());
+        let borrowed =
+            CountingAllocator::count(|| ExportedTree::from_bytes_borrowed(&tree).unwrap());
+        println!("tree_decode/{i}: {owned} allocations owned, {borrowed} borrowed");
+
+        bench_group.bench_with_input(BenchmarkId::new("owned", i), &tree, |b, tree| {
+            b.iter(|| ExportedTree::from_bytes(tree).unwrap())
+        });
+
+        bench_group.bench_with_input(BenchmarkId::new("borrowed", i), &tree, |b, tree| {
+            b.iter(|| ExportedTree::from_bytes_borrowed(tree).unwrap())
+        });
+    }
+
+    bench_group.finish();
+}
+
+criterion::criterion_group!(benches, bench_serialize, bench_tree_decode);
 criterion::criterion_main!(benches);
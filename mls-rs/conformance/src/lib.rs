@@ -0,0 +1,215 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+//! Runs every category of the official MLS test vectors against a crypto
+//! provider and summarizes the results per category and cipher suite.
+//!
+//! Vectors are read from the directory named by [`VECTORS_DIR_VAR`], which
+//! should contain the files of
+//! <https://github.com/mlswg/mls-implementations/tree/main/test-vectors>
+//! under their upstream names. Without it, the copies bundled in
+//! `mls-rs/test_data` are used. A category without a vector file fails the
+//! run unless [`ALLOW_MISSING_VAR`] is set.
+
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::{self, Display},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use mls_rs::{
+    test_utils::conformance::{self, Outcome, TestVectorCategory},
+    CipherSuite, CryptoProvider,
+};
+
+/// Environment variable pointing to a checkout of the upstream vectors.
+pub const VECTORS_DIR_VAR: &str = "MLS_TEST_VECTORS_DIR";
+
+/// Environment variable which, if set, lets categories without a vector file pass.
+pub const ALLOW_MISSING_VAR: &str = "MLS_TEST_VECTORS_ALLOW_MISSING";
+
+/// Location test vectors are read from.
+#[derive(Clone, Debug)]
+pub enum VectorSource {
+    /// Directory containing the upstream vector files.
+    Directory(PathBuf),
+    /// Vectors bundled with mls-rs, which use different file names.
+    Bundled,
+}
+
+impl VectorSource {
+    /// Use [`VECTORS_DIR_VAR`] if it is set and the bundled vectors otherwise.
+    pub fn from_env() -> Self {
+        env::var_os(VECTORS_DIR_VAR)
+            .map(|dir| Self::Directory(dir.into()))
+            .unwrap_or(Self::Bundled)
+    }
+
+    /// Contents of the vector file for `category`, or `None` if there is no such file.
+    pub fn load(&self, category: TestVectorCategory) -> io::Result<Option<Vec<u8>>> {
+        let path = match self {
+            Self::Directory(dir) => dir.join(category.file_name()),
+            Self::Bundled => match bundled_file_name(category) {
+                Some(name) => Path::new(env!("CARGO_MANIFEST_DIR"))
+                    .join("../test_data")
+                    .join(name),
+                None => return Ok(None),
+            },
+        };
+
+        match fs::read(path) {
+            Ok(vectors) => Ok(Some(vectors)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+fn bundled_file_name(category: TestVectorCategory) -> Option<&'static str> {
+    match category {
+        TestVectorCategory::TreeMath => Some("tree_math.json"),
+        TestVectorCategory::CryptoBasics => Some("basic_crypto.json"),
+        TestVectorCategory::SecretTree => Some("secret_tree_interop.json"),
+        TestVectorCategory::MessageProtection => Some("framing.json"),
+        TestVectorCategory::KeySchedule => Some("key_schedule_test_vector.json"),
+        TestVectorCategory::PskSecret => Some("psk_secret.json"),
+        TestVectorCategory::TranscriptHashes => Some("interop_transcript_hashes.json"),
+        TestVectorCategory::Welcome => Some("welcome.json"),
+        TestVectorCategory::TreeValidation => Some("interop_tree_validation.json"),
+        TestVectorCategory::TreeKem => Some("interop_tree_kem.json"),
+        TestVectorCategory::Messages => Some("serialization.json"),
+        TestVectorCategory::PassiveClientWelcome => Some("interop_passive_client_welcome.json"),
+        TestVectorCategory::PassiveClientHandleCommit => {
+            Some("interop_passive_client_handle_commit.json")
+        }
+        TestVectorCategory::PassiveClientRandom => Some("interop_passive_client_random.json"),
+    }
+}
+
+/// Number of test cases per outcome.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Counts {
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+/// Results of running all categories against one crypto provider.
+#[derive(Clone, Debug)]
+pub struct ProviderReport {
+    pub provider: String,
+    /// Counts per category and cipher suite. Categories that do not depend on
+    /// a cipher suite use `None`.
+    pub counts: BTreeMap<(TestVectorCategory, Option<CipherSuite>), Counts>,
+    /// Description of every failed test case.
+    pub failures: Vec<String>,
+    /// Categories without a vector file.
+    pub missing: Vec<TestVectorCategory>,
+}
+
+impl ProviderReport {
+    /// Whether no test case failed and no category is missing, unless
+    /// [`ALLOW_MISSING_VAR`] is set.
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+            && (self.missing.is_empty() || env::var_os(ALLOW_MISSING_VAR).is_some())
+    }
+}
+
+/// Run every category available in `source` against `crypto_provider`.
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+pub async fn run_all<C: CryptoProvider + Clone>(
+    provider: &str,
+    source: &VectorSource,
+    crypto_provider: &C,
+) -> io::Result<ProviderReport> {
+    let mut report = ProviderReport {
+        provider: provider.to_string(),
+        counts: BTreeMap::new(),
+        failures: Vec::new(),
+        missing: Vec::new(),
+    };
+
+    for category in TestVectorCategory::ALL {
+        let Some(vectors) = source.load(category)? else {
+            report.missing.push(category);
+            continue;
+        };
+
+        let cases = match conformance::run(
+            category,
+            &mut serde_json::Deserializer::from_slice(&vectors),
+            crypto_provider,
+        )
+        .await
+        {
+            Ok(cases) => cases,
+            Err(e) => {
+                report.failures.push(format!("{category}: {e}"));
+                continue;
+            }
+        };
+
+        for case in cases {
+            let counts = report
+                .counts
+                .entry((category, case.cipher_suite))
+                .or_default();
+
+            match case.outcome {
+                Outcome::Passed => counts.passed += 1,
+                Outcome::Skipped => counts.skipped += 1,
+                Outcome::Failed(reason) => {
+                    counts.failed += 1;
+
+                    report
+                        .failures
+                        .push(format!("{category} #{}: {reason}", case.index));
+                }
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+impl Display for ProviderReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "crypto provider: {}", self.provider)?;
+
+        writeln!(
+            f,
+            "  {:<30} {:>12} {:>7} {:>7} {:>7}",
+            "category", "cipher suite", "passed", "failed", "skipped"
+        )?;
+
+        for ((category, cipher_suite), counts) in &self.counts {
+            let cipher_suite = cipher_suite
+                .map(|cs| u16::from(cs).to_string())
+                .unwrap_or_else(|| "-".to_string());
+
+            writeln!(
+                f,
+                "  {:<30} {:>12} {:>7} {:>7} {:>7}",
+                category.to_string(),
+                cipher_suite,
+                counts.passed,
+                counts.failed,
+                counts.skipped
+            )?;
+        }
+
+        for category in &self.missing {
+            writeln!(f, "  {category:<30} no vectors found")?;
+        }
+
+        for failure in &self.failures {
+            writeln!(f, "  FAILED {failure}")?;
+        }
+
+        Ok(())
+    }
+}
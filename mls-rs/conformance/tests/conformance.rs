@@ -0,0 +1,41 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use conformance::{run_all, VectorSource};
+use mls_rs::CryptoProvider;
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+async fn check<C: CryptoProvider + Clone>(provider: &str, crypto_provider: &C) {
+    let report = run_all(provider, &VectorSource::from_env(), crypto_provider)
+        .await
+        .unwrap();
+
+    assert!(report.passed(), "{report}");
+}
+
+#[cfg(feature = "openssl")]
+#[maybe_async::test(not(mls_build_async), async(mls_build_async, futures_test::test))]
+async fn openssl() {
+    check(
+        "openssl",
+        &mls_rs_crypto_openssl::OpensslCryptoProvider::new(),
+    )
+    .await
+}
+
+#[cfg(feature = "rustcrypto")]
+#[maybe_async::test(not(mls_build_async), async(mls_build_async, futures_test::test))]
+async fn rustcrypto() {
+    check(
+        "rustcrypto",
+        &mls_rs_crypto_rustcrypto::RustCryptoProvider::new(),
+    )
+    .await
+}
+
+#[cfg(feature = "awslc")]
+#[maybe_async::test(not(mls_build_async), async(mls_build_async, futures_test::test))]
+async fn awslc() {
+    check("awslc", &mls_rs_crypto_awslc::AwsLcCryptoProvider::new()).await
+}
@@ -7,24 +7,26 @@ use crate::client_builder::{recreate_config, BaseConfig, ClientBuilder, MakeConf
 use crate::client_config::ClientConfig;
 use crate::group::framing::MlsMessage;
 
+use crate::extension::RatchetTreeExt;
+use crate::group::{fetch_ratchet_tree, ExportedTree, Group, NewMemberInfo};
 #[cfg(feature = "by_ref_proposal")]
 use crate::group::{
     framing::{Content, MlsMessagePayload, PublicMessage, Sender, WireFormat},
     message_signature::AuthenticatedContent,
     proposal::{AddProposal, Proposal},
 };
-use crate::group::{ExportedTree, Group, NewMemberInfo};
 use crate::identity::SigningIdentity;
 use crate::key_package::{KeyPackageGeneration, KeyPackageGenerator};
 use crate::protocol_version::ProtocolVersion;
-use crate::tree_kem::node::NodeIndex;
+use crate::tree_kem::node::{NodeIndex, NodeVec};
 use alloc::vec::Vec;
 use mls_rs_core::crypto::{CryptoProvider, SignatureSecretKey};
 use mls_rs_core::error::{AnyError, IntoAnyError};
 use mls_rs_core::extension::{ExtensionError, ExtensionList, ExtensionType};
-use mls_rs_core::group::{GroupStateStorage, ProposalType};
-use mls_rs_core::identity::CredentialType;
+use mls_rs_core::group::ProposalType;
+use mls_rs_core::identity::{CredentialType, IdentityProvider};
 use mls_rs_core::key_package::KeyPackageStorage;
+use mls_rs_core::time::TimeProvider;
 
 use crate::group::external_commit::ExternalCommitBuilder;
 
@@ -171,6 +173,11 @@ pub enum MlsError {
     MissingRequiredPsk,
     #[cfg_attr(feature = "std", error("Old group state not found"))]
     OldGroupStateNotFound,
+    #[cfg_attr(
+        feature = "std",
+        error("resumption PSK epoch {0} is not a prior epoch of the group")
+    )]
+    InvalidResumptionPskEpoch(u64),
     #[cfg_attr(feature = "std", error("leaf secret already consumed"))]
     InvalidLeafConsumption,
     #[cfg_attr(feature = "std", error("key not available, invalid generation {0}"))]
@@ -244,6 +251,8 @@ pub enum MlsError {
     PubKeyMismatch,
     #[cfg_attr(feature = "std", error("tree hash mismatch"))]
     TreeHashMismatch,
+    #[cfg_attr(feature = "std", error("ratchet tree diff does not fit the tree"))]
+    InvalidTreeDiff,
     #[cfg_attr(feature = "std", error("bad update: no suitable secret key"))]
     UpdateErrorNoSecretKey,
     #[cfg_attr(feature = "std", error("invalid lca, not found on direct path"))]
@@ -297,6 +306,11 @@ pub enum MlsError {
         error("External commit contains more than one Remove proposal")
     )]
     ExternalCommitWithMoreThanOneRemove,
+    #[cfg_attr(
+        feature = "std",
+        error("Several leaves hold the identity of the rejoining client")
+    )]
+    AmbiguousRejoinLeaf,
     #[cfg_attr(feature = "std", error("Duplicate PSK IDs"))]
     DuplicatePskIds,
     #[cfg_attr(
@@ -337,6 +351,14 @@ pub enum MlsError {
     InvalidGroupInfo,
     #[cfg_attr(feature = "std", error("Invalid welcome message"))]
     InvalidWelcomeMessage,
+    #[cfg_attr(feature = "std", error("Unsupported backup version {0}"))]
+    UnsupportedBackupVersion(u16),
+    #[cfg_attr(feature = "std", error("Group already exists in storage"))]
+    GroupAlreadyExists,
+    #[cfg_attr(feature = "std", error(transparent))]
+    KeyPackageDirectoryError(AnyError),
+    #[cfg_attr(feature = "std", error(transparent))]
+    RatchetTreeProviderError(AnyError),
 }
 
 impl IntoAnyError for MlsError {
@@ -534,6 +556,9 @@ where
     /// at the time the welcome message was created. `tree_data` can
     /// be exported from a group using the
     /// [export tree function](crate::group::Group::export_tree).
+    ///
+    /// If neither is available, the tree is fetched from the
+    /// [`ClientBuilder::ratchet_tree_provider`].
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn join_group(
         &self,
@@ -603,21 +628,134 @@ where
         ))
     }
 
+    /// Rejoin a group after losing its local state, using a group info
+    /// message allowing external commits.
+    ///
+    /// The previous leaf of this client is located in `tree_data` or in the
+    /// [`RatchetTreeExt`](crate::extension::built_in::RatchetTreeExt) of
+    /// `group_info`, and removed by the same external commit. It is the leaf
+    /// with the signature key of this client or, if there is none, the leaf
+    /// with its identity as determined by the
+    /// [IdentityProvider](crate::IdentityProvider). If several leaves have that
+    /// identity, [`MlsError::AmbiguousRejoinLeaf`] is returned. Use
+    /// [`rejoin_group_builder`](Client::rejoin_group_builder) to add PSKs to
+    /// the commit.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn rejoin_group(
+        &self,
+        group_info: MlsMessage,
+        tree_data: Option<ExportedTree<'static>>,
+    ) -> Result<(Group<C>, MlsMessage), MlsError> {
+        self.rejoin_group_builder(&group_info, tree_data)
+            .await?
+            .build(group_info)
+            .await
+    }
+
+    /// Create an external commit builder that rejoins a group as
+    /// [`rejoin_group`](Client::rejoin_group) does.
+    ///
+    /// The builder must be built with the same `group_info`. If no leaf
+    /// holds the identity of this client, the builder does not remove any
+    /// member.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn rejoin_group_builder(
+        &self,
+        group_info: &MlsMessage,
+        tree_data: Option<ExportedTree<'static>>,
+    ) -> Result<ExternalCommitBuilder<C>, MlsError> {
+        let group_info = group_info
+            .as_group_info()
+            .ok_or(MlsError::UnexpectedMessageType)?;
+
+        let mut builder = self.external_commit_builder()?;
+
+        let ratchet_tree_ext = group_info.extensions.get_as::<RatchetTreeExt>()?;
+
+        let nodes = match (tree_data, ratchet_tree_ext) {
+            (Some(tree_data), _) => {
+                builder = builder.with_tree_data(tree_data.clone());
+                tree_data.into_nodes()?
+            }
+            (None, Some(ext)) => ext.tree_data.into_nodes()?,
+            (None, None) => {
+                let tree_data = fetch_ratchet_tree(
+                    group_info,
+                    &self.config.ratchet_tree_provider(),
+                    self.config.decode_limits(),
+                )
+                .await?;
+
+                // Avoid fetching the tree again when building the commit.
+                builder = builder.with_tree_data(tree_data.clone());
+                tree_data.into_nodes()?
+            }
+        };
+
+        let previous_leaf = self
+            .find_own_leaf(&nodes, &group_info.group_context.extensions)
+            .await?;
+
+        if let Some(previous_leaf) = previous_leaf {
+            builder = builder.with_removal(previous_leaf);
+        }
+
+        Ok(builder)
+    }
+
+    /// Index of the leaf of `nodes` holding the signature key of this client
+    /// or, if there is none, its identity.
+    ///
+    /// Identity providers may map several devices to the same identity, so
+    /// more than one leaf holding the identity is an error rather than a guess.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn find_own_leaf(
+        &self,
+        nodes: &NodeVec,
+        extensions: &ExtensionList,
+    ) -> Result<Option<u32>, MlsError> {
+        let signing_identity = self.signing_identity()?.0;
+
+        let same_key = nodes.non_empty_leaves().find(|(_, leaf)| {
+            leaf.signing_identity.signature_key == signing_identity.signature_key
+        });
+
+        if let Some((index, _)) = same_key {
+            return Ok(Some(*index));
+        }
+
+        let identity_provider = self.config.identity_provider();
+
+        let own_identity = identity_provider
+            .identity(signing_identity, extensions)
+            .await
+            .map_err(|e| MlsError::IdentityProviderError(e.into_any_error()))?;
+
+        let mut own_leaf = None;
+
+        for (index, leaf) in nodes.non_empty_leaves() {
+            let identity = identity_provider
+                .identity(&leaf.signing_identity, extensions)
+                .await
+                .map_err(|e| MlsError::IdentityProviderError(e.into_any_error()))?;
+
+            if identity == own_identity && own_leaf.replace(*index).is_some() {
+                return Err(MlsError::AmbiguousRejoinLeaf);
+            }
+        }
+
+        Ok(own_leaf)
+    }
+
     /// Load an existing group state into this client using the
     /// [GroupStateStorage](crate::GroupStateStorage) that
     /// this client was configured to use.
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     #[inline(never)]
     pub async fn load_group(&self, group_id: &[u8]) -> Result<Group<C>, MlsError> {
-        let snapshot = self
-            .config
-            .group_state_storage()
-            .state(group_id)
-            .await
-            .map_err(|e| MlsError::GroupStorageError(e.into_any_error()))?
-            .ok_or(MlsError::GroupNotFound)?;
-
-        Group::from_snapshot(self.config.clone(), snapshot).await
+        Group::load(self.config.clone(), group_id)
+            .await?
+            .ok_or(MlsError::GroupNotFound)
     }
 
     /// Request to join an existing [group](crate::group::Group).
@@ -656,7 +794,10 @@ where
             group_info,
             tree_data,
             &self.config.identity_provider(),
+            &self.config.ratchet_tree_provider(),
+            self.config.decode_limits(),
             &cipher_suite_provider,
+            self.config.time_provider().now(),
         )
         .await?;
 
@@ -763,7 +904,8 @@ mod tests {
     use crate::{
         crypto::test_utils::TestCryptoProvider,
         identity::test_utils::{get_test_basic_credential, get_test_signing_identity},
-        tree_kem::leaf_node::LeafNodeSource,
+        tree_kem::leaf_node::{test_utils::get_test_node, LeafNodeSource},
+        tree_kem::node::LeafIndex,
     };
     use assert_matches::assert_matches;
 
@@ -771,13 +913,16 @@ mod tests {
         group::{
             message_processor::ProposalMessageDescription,
             proposal::Proposal,
-            test_utils::{test_group, test_group_custom_config},
+            test_utils::{test_group, test_group_custom_config, TestRatchetTreeProvider},
             ReceivedMessage,
         },
         psk::{ExternalPskId, PreSharedKey},
+        time::{FixedTimeProvider, MlsTime},
+        tree_kem::Lifetime,
     };
 
-    use alloc::vec;
+    use alloc::{sync::Arc, vec};
+    use core::sync::atomic::{AtomicU64, Ordering};
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn test_keygen() {
@@ -818,6 +963,136 @@ mod tests {
         }
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn key_package_lifetime_uses_time_provider() {
+        let client = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .time_provider(FixedTimeProvider::new(MlsTime::from(1000)))
+            .key_package_lifetime(100)
+            .build();
+
+        let key_package = client
+            .generate_key_package_message()
+            .await
+            .unwrap()
+            .into_key_package()
+            .unwrap();
+
+        assert_matches!(
+            key_package.leaf_node.leaf_node_source,
+            LeafNodeSource::KeyPackage(lifetime) if lifetime == Lifetime::new(1000, 1100)
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn adding_key_package_checks_lifetime_against_time_provider() {
+        let bob = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("bob", TEST_CIPHER_SUITE)
+            .await
+            .time_provider(FixedTimeProvider::new(MlsTime::from(1000)))
+            .key_package_lifetime(100)
+            .build();
+
+        let key_package = bob.generate_key_package_message().await.unwrap();
+
+        for (time, valid) in [(999, false), (1000, true), (1100, true), (1101, false)] {
+            let alice = TestClientBuilder::new_for_test()
+                .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+                .await
+                .time_provider(FixedTimeProvider::new(MlsTime::from(time)))
+                .build();
+
+            let mut group = alice.create_group(Default::default()).await.unwrap();
+
+            let res = group
+                .commit_builder()
+                .add_member(key_package.clone())
+                .unwrap()
+                .build()
+                .await;
+
+            if valid {
+                assert!(res.is_ok());
+            } else {
+                assert_matches!(res, Err(MlsError::InvalidLifetime));
+            }
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    struct SharedTimeProvider(Arc<AtomicU64>);
+
+    impl TimeProvider for SharedTimeProvider {
+        fn now(&self) -> Option<MlsTime> {
+            Some(MlsTime::from(self.0.load(Ordering::Relaxed)))
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn receiving_commit_checks_lifetime_against_time_provider() {
+        let time = Arc::new(AtomicU64::new(1000));
+
+        let alice = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .time_provider(FixedTimeProvider::new(MlsTime::from(1000)))
+            .build();
+
+        let charlie = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("charlie", TEST_CIPHER_SUITE)
+            .await
+            .time_provider(SharedTimeProvider(time.clone()))
+            .build();
+
+        let bob = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("bob", TEST_CIPHER_SUITE)
+            .await
+            .time_provider(FixedTimeProvider::new(MlsTime::from(1000)))
+            .key_package_lifetime(100)
+            .build();
+
+        let mut alice_group = alice.create_group(Default::default()).await.unwrap();
+
+        let commit = alice_group
+            .commit_builder()
+            .add_member(charlie.generate_key_package_message().await.unwrap())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice_group.apply_pending_commit().await.unwrap();
+
+        let (mut charlie_group, _) = charlie
+            .join_group(None, &commit.welcome_messages[0])
+            .await
+            .unwrap();
+
+        let commit = alice_group
+            .commit_builder()
+            .add_member(bob.generate_key_package_message().await.unwrap())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        time.store(2000, Ordering::Relaxed);
+
+        let res = charlie_group
+            .process_incoming_message(commit.commit_message.clone())
+            .await;
+
+        assert_matches!(res, Err(MlsError::InvalidLifetime));
+
+        time.store(1050, Ordering::Relaxed);
+
+        charlie_group
+            .process_incoming_message(commit.commit_message)
+            .await
+            .unwrap();
+    }
+
     #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn new_member_add_proposal_adds_to_group() {
@@ -1019,6 +1294,215 @@ mod tests {
         assert_matches!(res, Err(_));
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn rejoining_removes_previous_leaf() {
+        for tree_in_extension in [true, false] {
+            let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+            let (mut bob_group, _) = alice_group.join("bob").await;
+
+            let group_info = alice_group
+                .group
+                .group_info_message_allowing_ext_commit(tree_in_extension)
+                .await
+                .unwrap();
+
+            let tree_data =
+                (!tree_in_extension).then(|| alice_group.group.export_tree().into_owned());
+
+            // Bob lost his state and rejoins with a new signature key.
+            let bob = TestClientBuilder::new_for_test()
+                .with_random_signing_identity("bob", TEST_CIPHER_SUITE)
+                .await
+                .build();
+
+            let (new_bob_group, commit) = bob.rejoin_group(group_info, tree_data).await.unwrap();
+
+            assert_eq!(new_bob_group.roster().members_iter().count(), 2);
+
+            alice_group.process_message(commit.clone()).await.unwrap();
+
+            let message = bob_group.process_message(commit).await.unwrap();
+
+            #[cfg(feature = "state_update")]
+            assert_matches!(message, ReceivedMessage::Commit(desc) if !desc.state_update.active);
+
+            #[cfg(not(feature = "state_update"))]
+            assert_matches!(message, ReceivedMessage::Commit(_));
+
+            assert_eq!(
+                alice_group.group.epoch_authenticator().unwrap(),
+                new_bob_group.epoch_authenticator().unwrap()
+            );
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn rejoining_requires_a_ratchet_tree() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        alice_group.join("bob").await;
+
+        let group_info = alice_group
+            .group
+            .group_info_message_allowing_ext_commit(false)
+            .await
+            .unwrap();
+
+        let bob = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("bob", TEST_CIPHER_SUITE)
+            .await
+            .build();
+
+        let res = bob.rejoin_group(group_info, None).await.map(|_| ());
+
+        assert_matches!(res, Err(MlsError::RatchetTreeNotFound));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn rejoining_fetches_ratchet_tree_from_provider() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        alice_group.join("bob").await;
+
+        let group_info = alice_group
+            .group
+            .group_info_message_allowing_ext_commit(false)
+            .await
+            .unwrap();
+
+        let bob = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("bob", TEST_CIPHER_SUITE)
+            .await
+            .ratchet_tree_provider(TestRatchetTreeProvider::new(&alice_group.group))
+            .build();
+
+        let (new_bob_group, commit) = bob.rejoin_group(group_info, None).await.unwrap();
+
+        assert_eq!(new_bob_group.roster().members_iter().count(), 2);
+
+        alice_group.process_message(commit).await.unwrap();
+
+        assert_eq!(
+            alice_group.group.epoch_authenticator().unwrap(),
+            new_bob_group.epoch_authenticator().unwrap()
+        );
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn tree_with_leaves(identities: &[&SigningIdentity]) -> NodeVec {
+        let mut nodes = NodeVec::default();
+
+        for (index, &identity) in identities.iter().enumerate() {
+            let (_, secret) = get_test_signing_identity(TEST_CIPHER_SUITE, b"unused").await;
+
+            let (leaf, _) =
+                get_test_node(TEST_CIPHER_SUITE, identity.clone(), &secret, None, None).await;
+
+            nodes.insert_leaf(LeafIndex::new(index as u32), leaf);
+        }
+
+        nodes
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn rejoining_prefers_leaf_with_own_signature_key() {
+        let (other_device, _) = get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+        let (own_device, own_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+
+        let bob = TestClientBuilder::new_for_test()
+            .signing_identity(own_device.clone(), own_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let nodes = tree_with_leaves(&[&other_device, &own_device]).await;
+        let own_leaf = bob.find_own_leaf(&nodes, &Default::default()).await;
+
+        assert_matches!(own_leaf, Ok(Some(1)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn rejoining_rejects_several_leaves_with_own_identity() {
+        let (first_device, _) = get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+        let (second_device, _) = get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+
+        let bob = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("bob", TEST_CIPHER_SUITE)
+            .await
+            .build();
+
+        let nodes = tree_with_leaves(&[&first_device, &second_device]).await;
+        let own_leaf = bob.find_own_leaf(&nodes, &Default::default()).await;
+
+        assert_matches!(own_leaf, Err(MlsError::AmbiguousRejoinLeaf));
+    }
+
+    #[cfg(all(feature = "psk", feature = "prior_epoch"))]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn rejoining_can_use_resumption_psk_from_storage() {
+        use crate::storage_provider::in_memory::InMemoryGroupStateStorage;
+
+        let storage = InMemoryGroupStateStorage::new();
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let (mut bob_group, _) = alice_group
+            .join_with_custom_config("bob", false, |c| {
+                c.0.group_state_storage = storage.clone();
+            })
+            .await
+            .unwrap();
+
+        let psk_epoch = bob_group.group.current_epoch();
+
+        let commit = alice_group.group.commit(vec![]).await.unwrap();
+        alice_group.process_pending_commit().await.unwrap();
+        bob_group
+            .process_message(commit.commit_message)
+            .await
+            .unwrap();
+
+        // Bob stores the epoch used for the PSK, then loses the current epoch.
+        bob_group.group.write_to_storage().await.unwrap();
+
+        let group_info = alice_group
+            .group
+            .group_info_message_allowing_ext_commit(true)
+            .await
+            .unwrap();
+
+        let bob = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("bob", TEST_CIPHER_SUITE)
+            .await
+            .group_state_storage(storage)
+            .build();
+
+        let res = bob
+            .rejoin_group_builder(&group_info, None)
+            .await
+            .unwrap()
+            .with_resumption_psk(alice_group.group.current_epoch())
+            .build(group_info.clone())
+            .await
+            .map(|_| ());
+
+        assert_matches!(
+            res,
+            Err(MlsError::InvalidResumptionPskEpoch(epoch)) if epoch == alice_group.group.current_epoch()
+        );
+
+        let (new_bob_group, commit) = bob
+            .rejoin_group_builder(&group_info, None)
+            .await
+            .unwrap()
+            .with_resumption_psk(psk_epoch)
+            .build(group_info)
+            .await
+            .unwrap();
+
+        alice_group.process_message(commit).await.unwrap();
+
+        assert_eq!(
+            alice_group.group.epoch_authenticator().unwrap(),
+            new_bob_group.epoch_authenticator().unwrap()
+        );
+    }
+
     #[test]
     fn builder_can_be_obtained_from_client_to_edit_properties_for_new_client() {
         let alice = TestClientBuilder::new_for_test()
@@ -26,10 +26,10 @@ use crate::{
     Sealed,
 };
 
-#[cfg(feature = "std")]
-use crate::time::MlsTime;
+use crate::time::{SystemTimeProvider, TimeProvider};
 
 use alloc::vec::Vec;
+use mls_rs_codec::DecodeLimits;
 
 #[cfg(feature = "sqlite")]
 use mls_rs_provider_sqlite::{
@@ -51,6 +51,8 @@ pub type BaseConfig = Config<
     Missing,
     DefaultMlsRules,
     Missing,
+    SystemTimeProvider,
+    NoRatchetTreeProvider,
 >;
 
 /// Base client configuration type when instantiating `ClientBuilder`
@@ -61,9 +63,12 @@ pub type BaseInMemoryConfig = Config<
     Missing,
     Missing,
     Missing,
+    SystemTimeProvider,
+    NoRatchetTreeProvider,
 >;
 
-pub type EmptyConfig = Config<Missing, Missing, Missing, Missing, Missing, Missing>;
+pub type EmptyConfig =
+    Config<Missing, Missing, Missing, Missing, Missing, Missing, Missing, Missing>;
 
 /// Base client configuration that is backed by SQLite storage.
 #[cfg(feature = "sqlite")]
@@ -74,6 +79,8 @@ pub type BaseSqlConfig = Config<
     Missing,
     DefaultMlsRules,
     Missing,
+    SystemTimeProvider,
+    NoRatchetTreeProvider,
 >;
 
 /// Builder for [`Client`]
@@ -200,6 +207,8 @@ impl ClientBuilder<BaseConfig> {
             identity_provider: Missing,
             mls_rules: DefaultMlsRules::new(),
             crypto_provider: Missing,
+            time_provider: SystemTimeProvider,
+            ratchet_tree_provider: NoRatchetTreeProvider,
             signer: Default::default(),
             signing_identity: Default::default(),
             version: ProtocolVersion::MLS_10,
@@ -217,6 +226,8 @@ impl ClientBuilder<EmptyConfig> {
             identity_provider: Missing,
             mls_rules: Missing,
             crypto_provider: Missing,
+            time_provider: Missing,
+            ratchet_tree_provider: Missing,
             signer: Default::default(),
             signing_identity: Default::default(),
             version: ProtocolVersion::MLS_10,
@@ -238,6 +249,8 @@ impl ClientBuilder<BaseSqlConfig> {
             identity_provider: Missing,
             mls_rules: DefaultMlsRules::new(),
             crypto_provider: Missing,
+            time_provider: SystemTimeProvider,
+            ratchet_tree_provider: NoRatchetTreeProvider,
             signer: Default::default(),
             signing_identity: Default::default(),
             version: ProtocolVersion::MLS_10,
@@ -354,6 +367,17 @@ impl<C: IntoConfig> ClientBuilder<C> {
         ClientBuilder(c)
     }
 
+    /// Set the limits applied when decoding messages received by the client
+    /// with [`Group::process_incoming_bytes`](crate::Group::process_incoming_bytes)
+    /// and the group info of welcome messages.
+    ///
+    /// By default, [`DecodeLimits::DEFAULT`] is used.
+    pub fn decode_limits(self, limits: DecodeLimits) -> ClientBuilder<IntoConfigOutput<C>> {
+        let mut c = self.0.into_config();
+        c.0.settings.decode_limits = limits;
+        ClientBuilder(c)
+    }
+
     /// Set the key package repository to be used by the client.
     ///
     /// By default, an in-memory repository is used.
@@ -371,6 +395,8 @@ impl<C: IntoConfig> ClientBuilder<C> {
             identity_provider: c.identity_provider,
             mls_rules: c.mls_rules,
             crypto_provider: c.crypto_provider,
+            time_provider: c.time_provider,
+            ratchet_tree_provider: c.ratchet_tree_provider,
             signer: c.signer,
             signing_identity: c.signing_identity,
             version: c.version,
@@ -394,6 +420,8 @@ impl<C: IntoConfig> ClientBuilder<C> {
             identity_provider: c.identity_provider,
             mls_rules: c.mls_rules,
             crypto_provider: c.crypto_provider,
+            time_provider: c.time_provider,
+            ratchet_tree_provider: c.ratchet_tree_provider,
             signer: c.signer,
             signing_identity: c.signing_identity,
             version: c.version,
@@ -420,6 +448,8 @@ impl<C: IntoConfig> ClientBuilder<C> {
             identity_provider: c.identity_provider,
             crypto_provider: c.crypto_provider,
             mls_rules: c.mls_rules,
+            time_provider: c.time_provider,
+            ratchet_tree_provider: c.ratchet_tree_provider,
             signer: c.signer,
             signing_identity: c.signing_identity,
             version: c.version,
@@ -444,6 +474,8 @@ impl<C: IntoConfig> ClientBuilder<C> {
             identity_provider,
             mls_rules: c.mls_rules,
             crypto_provider: c.crypto_provider,
+            time_provider: c.time_provider,
+            ratchet_tree_provider: c.ratchet_tree_provider,
             signer: c.signer,
             signing_identity: c.signing_identity,
             version: c.version,
@@ -468,6 +500,65 @@ impl<C: IntoConfig> ClientBuilder<C> {
             identity_provider: c.identity_provider,
             mls_rules: c.mls_rules,
             crypto_provider,
+            time_provider: c.time_provider,
+            ratchet_tree_provider: c.ratchet_tree_provider,
+            signer: c.signer,
+            signing_identity: c.signing_identity,
+            version: c.version,
+        }))
+    }
+
+    /// Set the time provider used by the client.
+    ///
+    /// The current time is used for the lifetime of generated key packages and
+    /// to validate the lifetime of leaf nodes and the credentials of new members.
+    /// By default, the system clock is used.
+    pub fn time_provider<Tp>(self, time_provider: Tp) -> ClientBuilder<WithTimeProvider<Tp, C>>
+    where
+        Tp: TimeProvider,
+    {
+        let Config(c) = self.0.into_config();
+
+        ClientBuilder(Config(ConfigInner {
+            settings: c.settings,
+            key_package_repo: c.key_package_repo,
+            psk_store: c.psk_store,
+            group_state_storage: c.group_state_storage,
+            identity_provider: c.identity_provider,
+            mls_rules: c.mls_rules,
+            crypto_provider: c.crypto_provider,
+            time_provider,
+            ratchet_tree_provider: c.ratchet_tree_provider,
+            signer: c.signer,
+            signing_identity: c.signing_identity,
+            version: c.version,
+        }))
+    }
+
+    /// Set the provider used by the client to fetch ratchet trees that are
+    /// neither included in the group info nor provided by the application
+    /// when joining a group or creating an external commit.
+    ///
+    /// By default, no ratchet tree is fetched.
+    pub fn ratchet_tree_provider<Rtp>(
+        self,
+        ratchet_tree_provider: Rtp,
+    ) -> ClientBuilder<WithRatchetTreeProvider<Rtp, C>>
+    where
+        Rtp: RatchetTreeProvider,
+    {
+        let Config(c) = self.0.into_config();
+
+        ClientBuilder(Config(ConfigInner {
+            settings: c.settings,
+            key_package_repo: c.key_package_repo,
+            psk_store: c.psk_store,
+            group_state_storage: c.group_state_storage,
+            identity_provider: c.identity_provider,
+            mls_rules: c.mls_rules,
+            crypto_provider: c.crypto_provider,
+            time_provider: c.time_provider,
+            ratchet_tree_provider,
             signer: c.signer,
             signing_identity: c.signing_identity,
             version: c.version,
@@ -495,6 +586,8 @@ impl<C: IntoConfig> ClientBuilder<C> {
             identity_provider: c.identity_provider,
             mls_rules,
             crypto_provider: c.crypto_provider,
+            time_provider: c.time_provider,
+            ratchet_tree_provider: c.ratchet_tree_provider,
             signer: c.signer,
             signing_identity: c.signing_identity,
             version: c.version,
@@ -541,6 +634,8 @@ where
     C::IdentityProvider: IdentityProvider + Clone,
     C::MlsRules: MlsRules + Clone,
     C::CryptoProvider: CryptoProvider + Clone,
+    C::TimeProvider: TimeProvider + Clone,
+    C::RatchetTreeProvider: RatchetTreeProvider + Clone,
 {
     pub(crate) fn build_config(self) -> IntoConfigOutput<C> {
         let mut c = self.0.into_config();
@@ -593,6 +688,8 @@ pub type WithKeyPackageRepo<K, C> = Config<
     <C as IntoConfig>::IdentityProvider,
     <C as IntoConfig>::MlsRules,
     <C as IntoConfig>::CryptoProvider,
+    <C as IntoConfig>::TimeProvider,
+    <C as IntoConfig>::RatchetTreeProvider,
 >;
 
 /// Change the PSK store used by a client configuration.
@@ -605,6 +702,8 @@ pub type WithPskStore<P, C> = Config<
     <C as IntoConfig>::IdentityProvider,
     <C as IntoConfig>::MlsRules,
     <C as IntoConfig>::CryptoProvider,
+    <C as IntoConfig>::TimeProvider,
+    <C as IntoConfig>::RatchetTreeProvider,
 >;
 
 /// Change the group state storage used by a client configuration.
@@ -617,6 +716,8 @@ pub type WithGroupStateStorage<G, C> = Config<
     <C as IntoConfig>::IdentityProvider,
     <C as IntoConfig>::MlsRules,
     <C as IntoConfig>::CryptoProvider,
+    <C as IntoConfig>::TimeProvider,
+    <C as IntoConfig>::RatchetTreeProvider,
 >;
 
 /// Change the identity validator used by a client configuration.
@@ -629,6 +730,8 @@ pub type WithIdentityProvider<I, C> = Config<
     I,
     <C as IntoConfig>::MlsRules,
     <C as IntoConfig>::CryptoProvider,
+    <C as IntoConfig>::TimeProvider,
+    <C as IntoConfig>::RatchetTreeProvider,
 >;
 
 /// Change the proposal rules used by a client configuration.
@@ -641,6 +744,8 @@ pub type WithMlsRules<Pr, C> = Config<
     <C as IntoConfig>::IdentityProvider,
     Pr,
     <C as IntoConfig>::CryptoProvider,
+    <C as IntoConfig>::TimeProvider,
+    <C as IntoConfig>::RatchetTreeProvider,
 >;
 
 /// Change the crypto provider used by a client configuration.
@@ -653,6 +758,36 @@ pub type WithCryptoProvider<Cp, C> = Config<
     <C as IntoConfig>::IdentityProvider,
     <C as IntoConfig>::MlsRules,
     Cp,
+    <C as IntoConfig>::TimeProvider,
+    <C as IntoConfig>::RatchetTreeProvider,
+>;
+
+/// Change the time provider used by a client configuration.
+///
+/// See [`ClientBuilder::time_provider`].
+pub type WithTimeProvider<Tp, C> = Config<
+    <C as IntoConfig>::KeyPackageRepository,
+    <C as IntoConfig>::PskStore,
+    <C as IntoConfig>::GroupStateStorage,
+    <C as IntoConfig>::IdentityProvider,
+    <C as IntoConfig>::MlsRules,
+    <C as IntoConfig>::CryptoProvider,
+    Tp,
+    <C as IntoConfig>::RatchetTreeProvider,
+>;
+
+/// Change the ratchet tree provider used by a client configuration.
+///
+/// See [`ClientBuilder::ratchet_tree_provider`].
+pub type WithRatchetTreeProvider<Rtp, C> = Config<
+    <C as IntoConfig>::KeyPackageRepository,
+    <C as IntoConfig>::PskStore,
+    <C as IntoConfig>::GroupStateStorage,
+    <C as IntoConfig>::IdentityProvider,
+    <C as IntoConfig>::MlsRules,
+    <C as IntoConfig>::CryptoProvider,
+    <C as IntoConfig>::TimeProvider,
+    Rtp,
 >;
 
 /// Helper alias for `Config`.
@@ -663,6 +798,8 @@ pub type IntoConfigOutput<C> = Config<
     <C as IntoConfig>::IdentityProvider,
     <C as IntoConfig>::MlsRules,
     <C as IntoConfig>::CryptoProvider,
+    <C as IntoConfig>::TimeProvider,
+    <C as IntoConfig>::RatchetTreeProvider,
 >;
 
 /// Helper alias to make a `Config` from a `ClientConfig`
@@ -673,9 +810,12 @@ pub type MakeConfig<C> = Config<
     <C as ClientConfig>::IdentityProvider,
     <C as ClientConfig>::MlsRules,
     <C as ClientConfig>::CryptoProvider,
+    <C as ClientConfig>::TimeProvider,
+    <C as ClientConfig>::RatchetTreeProvider,
 >;
 
-impl<Kpr, Ps, Gss, Ip, Pr, Cp> ClientConfig for ConfigInner<Kpr, Ps, Gss, Ip, Pr, Cp>
+impl<Kpr, Ps, Gss, Ip, Pr, Cp, Tp, Rtp> ClientConfig
+    for ConfigInner<Kpr, Ps, Gss, Ip, Pr, Cp, Tp, Rtp>
 where
     Kpr: KeyPackageStorage + Clone,
     Ps: PreSharedKeyStorage + Clone,
@@ -683,6 +823,8 @@ where
     Ip: IdentityProvider + Clone,
     Pr: MlsRules + Clone,
     Cp: CryptoProvider + Clone,
+    Tp: TimeProvider + Clone,
+    Rtp: RatchetTreeProvider + Clone,
 {
     type KeyPackageRepository = Kpr;
     type PskStore = Ps;
@@ -690,6 +832,8 @@ where
     type IdentityProvider = Ip;
     type MlsRules = Pr;
     type CryptoProvider = Cp;
+    type TimeProvider = Tp;
+    type RatchetTreeProvider = Rtp;
 
     fn supported_extensions(&self) -> Vec<ExtensionType> {
         self.settings.extension_types.clone()
@@ -723,6 +867,14 @@ where
         self.crypto_provider.clone()
     }
 
+    fn time_provider(&self) -> Self::TimeProvider {
+        self.time_provider.clone()
+    }
+
+    fn ratchet_tree_provider(&self) -> Self::RatchetTreeProvider {
+        self.ratchet_tree_provider.clone()
+    }
+
     fn key_package_extensions(&self) -> ExtensionList {
         self.settings.key_package_extensions.clone()
     }
@@ -732,11 +884,10 @@ where
     }
 
     fn lifetime(&self) -> Lifetime {
-        #[cfg(feature = "std")]
-        let now_timestamp = MlsTime::now().seconds_since_epoch();
-
-        #[cfg(not(feature = "std"))]
-        let now_timestamp = 0;
+        let now_timestamp = self
+            .time_provider
+            .now()
+            .map_or(0, |now| now.seconds_since_epoch());
 
         Lifetime {
             not_before: now_timestamp,
@@ -747,11 +898,15 @@ where
     fn supported_custom_proposals(&self) -> Vec<crate::group::proposal::ProposalType> {
         self.settings.custom_proposal_types.clone()
     }
+
+    fn decode_limits(&self) -> DecodeLimits {
+        self.settings.decode_limits
+    }
 }
 
-impl<Kpr, Ps, Gss, Ip, Pr, Cp> Sealed for Config<Kpr, Ps, Gss, Ip, Pr, Cp> {}
+impl<Kpr, Ps, Gss, Ip, Pr, Cp, Tp, Rtp> Sealed for Config<Kpr, Ps, Gss, Ip, Pr, Cp, Tp, Rtp> {}
 
-impl<Kpr, Ps, Gss, Ip, Pr, Cp> MlsConfig for Config<Kpr, Ps, Gss, Ip, Pr, Cp>
+impl<Kpr, Ps, Gss, Ip, Pr, Cp, Tp, Rtp> MlsConfig for Config<Kpr, Ps, Gss, Ip, Pr, Cp, Tp, Rtp>
 where
     Kpr: KeyPackageStorage + Clone,
 
@@ -760,8 +915,10 @@ where
     Ip: IdentityProvider + Clone,
     Pr: MlsRules + Clone,
     Cp: CryptoProvider + Clone,
+    Tp: TimeProvider + Clone,
+    Rtp: RatchetTreeProvider + Clone,
 {
-    type Output = ConfigInner<Kpr, Ps, Gss, Ip, Pr, Cp>;
+    type Output = ConfigInner<Kpr, Ps, Gss, Ip, Pr, Cp, Tp, Rtp>;
 
     fn get(&self) -> &Self::Output {
         &self.0
@@ -787,6 +944,8 @@ impl<T: MlsConfig> ClientConfig for T {
     type IdentityProvider = <T::Output as ClientConfig>::IdentityProvider;
     type MlsRules = <T::Output as ClientConfig>::MlsRules;
     type CryptoProvider = <T::Output as ClientConfig>::CryptoProvider;
+    type TimeProvider = <T::Output as ClientConfig>::TimeProvider;
+    type RatchetTreeProvider = <T::Output as ClientConfig>::RatchetTreeProvider;
 
     fn supported_extensions(&self) -> Vec<ExtensionType> {
         self.get().supported_extensions()
@@ -824,6 +983,14 @@ impl<T: MlsConfig> ClientConfig for T {
         self.get().crypto_provider()
     }
 
+    fn time_provider(&self) -> Self::TimeProvider {
+        self.get().time_provider()
+    }
+
+    fn ratchet_tree_provider(&self) -> Self::RatchetTreeProvider {
+        self.get().ratchet_tree_provider()
+    }
+
     fn key_package_extensions(&self) -> ExtensionList {
         self.get().key_package_extensions()
     }
@@ -836,6 +1003,10 @@ impl<T: MlsConfig> ClientConfig for T {
         self.get().lifetime()
     }
 
+    fn decode_limits(&self) -> DecodeLimits {
+        self.get().decode_limits()
+    }
+
     fn capabilities(&self) -> Capabilities {
         self.get().capabilities()
     }
@@ -857,6 +1028,7 @@ pub(crate) struct Settings {
     pub(crate) key_package_extensions: ExtensionList,
     pub(crate) leaf_node_extensions: ExtensionList,
     pub(crate) lifetime_in_s: u64,
+    pub(crate) decode_limits: DecodeLimits,
 }
 
 impl Default for Settings {
@@ -868,6 +1040,7 @@ impl Default for Settings {
             leaf_node_extensions: Default::default(),
             lifetime_in_s: 365 * 24 * 3600,
             custom_proposal_types: Default::default(),
+            decode_limits: Default::default(),
         }
     }
 }
@@ -889,6 +1062,7 @@ pub(crate) fn recreate_config<T: ClientConfig>(
                 let l = c.lifetime();
                 l.not_after - l.not_before
             },
+            decode_limits: c.decode_limits(),
         },
         key_package_repo: c.key_package_repo(),
         psk_store: c.secret_store(),
@@ -896,6 +1070,8 @@ pub(crate) fn recreate_config<T: ClientConfig>(
         identity_provider: c.identity_provider(),
         mls_rules: c.mls_rules(),
         crypto_provider: c.crypto_provider(),
+        time_provider: c.time_provider(),
+        ratchet_tree_provider: c.ratchet_tree_provider(),
         signer,
         signing_identity,
         version,
@@ -914,10 +1090,12 @@ mod private {
     use crate::client_builder::{IntoConfigOutput, Settings};
 
     #[derive(Clone, Debug)]
-    pub struct Config<Kpr, Ps, Gss, Ip, Pr, Cp>(pub(crate) ConfigInner<Kpr, Ps, Gss, Ip, Pr, Cp>);
+    pub struct Config<Kpr, Ps, Gss, Ip, Pr, Cp, Tp, Rtp>(
+        pub(crate) ConfigInner<Kpr, Ps, Gss, Ip, Pr, Cp, Tp, Rtp>,
+    );
 
     #[derive(Clone, Debug)]
-    pub struct ConfigInner<Kpr, Ps, Gss, Ip, Pr, Cp> {
+    pub struct ConfigInner<Kpr, Ps, Gss, Ip, Pr, Cp, Tp, Rtp> {
         pub(crate) settings: Settings,
         pub(crate) key_package_repo: Kpr,
         pub(crate) psk_store: Ps,
@@ -925,6 +1103,8 @@ mod private {
         pub(crate) identity_provider: Ip,
         pub(crate) mls_rules: Pr,
         pub(crate) crypto_provider: Cp,
+        pub(crate) time_provider: Tp,
+        pub(crate) ratchet_tree_provider: Rtp,
         pub(crate) signer: Option<SignatureSecretKey>,
         pub(crate) signing_identity: Option<(SigningIdentity, CipherSuite)>,
         pub(crate) version: ProtocolVersion,
@@ -937,17 +1117,21 @@ mod private {
         type IdentityProvider;
         type MlsRules;
         type CryptoProvider;
+        type TimeProvider;
+        type RatchetTreeProvider;
 
         fn into_config(self) -> IntoConfigOutput<Self>;
     }
 
-    impl<Kpr, Ps, Gss, Ip, Pr, Cp> IntoConfig for Config<Kpr, Ps, Gss, Ip, Pr, Cp> {
+    impl<Kpr, Ps, Gss, Ip, Pr, Cp, Tp, Rtp> IntoConfig for Config<Kpr, Ps, Gss, Ip, Pr, Cp, Tp, Rtp> {
         type KeyPackageRepository = Kpr;
         type PskStore = Ps;
         type GroupStateStorage = Gss;
         type IdentityProvider = Ip;
         type MlsRules = Pr;
         type CryptoProvider = Cp;
+        type TimeProvider = Tp;
+        type RatchetTreeProvider = Rtp;
 
         fn into_config(self) -> Self {
             self
@@ -958,7 +1142,7 @@ mod private {
 use mls_rs_core::{
     crypto::{CryptoProvider, SignatureSecretKey},
     extension::{ExtensionError, ExtensionList},
-    group::GroupStateStorage,
+    group::{GroupStateStorage, NoRatchetTreeProvider, RatchetTreeProvider},
     identity::IdentityProvider,
     key_package::KeyPackageStorage,
     psk::PreSharedKeyStorage,
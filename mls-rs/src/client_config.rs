@@ -11,9 +11,14 @@ use crate::{
     ExtensionList,
 };
 use alloc::vec::Vec;
+use mls_rs_codec::DecodeLimits;
 use mls_rs_core::{
-    crypto::CryptoProvider, group::GroupStateStorage, identity::IdentityProvider,
-    key_package::KeyPackageStorage, psk::PreSharedKeyStorage,
+    crypto::CryptoProvider,
+    group::{GroupStateStorage, RatchetTreeProvider},
+    identity::IdentityProvider,
+    key_package::KeyPackageStorage,
+    psk::PreSharedKeyStorage,
+    time::TimeProvider,
 };
 
 pub trait ClientConfig: Send + Sync + Clone {
@@ -23,6 +28,8 @@ pub trait ClientConfig: Send + Sync + Clone {
     type IdentityProvider: IdentityProvider + Clone;
     type MlsRules: MlsRules + Clone;
     type CryptoProvider: CryptoProvider + Clone;
+    type TimeProvider: TimeProvider + Clone;
+    type RatchetTreeProvider: RatchetTreeProvider + Clone;
 
     fn supported_extensions(&self) -> Vec<ExtensionType>;
     fn supported_custom_proposals(&self) -> Vec<ProposalType>;
@@ -36,11 +43,17 @@ pub trait ClientConfig: Send + Sync + Clone {
     fn group_state_storage(&self) -> Self::GroupStateStorage;
     fn identity_provider(&self) -> Self::IdentityProvider;
     fn crypto_provider(&self) -> Self::CryptoProvider;
+    fn time_provider(&self) -> Self::TimeProvider;
+    fn ratchet_tree_provider(&self) -> Self::RatchetTreeProvider;
 
     fn key_package_extensions(&self) -> ExtensionList;
     fn leaf_node_extensions(&self) -> ExtensionList;
     fn lifetime(&self) -> Lifetime;
 
+    fn decode_limits(&self) -> DecodeLimits {
+        DecodeLimits::default()
+    }
+
     fn capabilities(&self) -> Capabilities {
         Capabilities {
             protocol_versions: self.supported_protocol_versions(),
@@ -9,6 +9,9 @@ pub(crate) use built_in::*;
 /// Default extension types required by the MLS RFC.
 pub mod built_in;
 
+#[cfg(feature = "serde")]
+pub(crate) mod list_serde;
+
 #[cfg(test)]
 pub(crate) mod test_utils {
     use alloc::vec::Vec;
@@ -29,9 +29,11 @@ use mls_rs_core::crypto::HpkePublicKey;
     safer_ffi_gen::ffi_type(clone, opaque)
 )]
 #[derive(Clone, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ApplicationIdExt {
     /// Application level identifier presented by this extension.
     #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    #[cfg_attr(feature = "serde", serde(with = "mls_rs_core::vec_serde"))]
     pub identifier: Vec<u8>,
 }
 
@@ -75,6 +77,7 @@ impl MlsCodecExtension for ApplicationIdExt {
     safer_ffi_gen::ffi_type(clone, opaque)
 )]
 #[derive(Clone, Debug, PartialEq, MlsSize, MlsEncode, MlsDecode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RatchetTreeExt {
     pub tree_data: ExportedTree<'static>,
 }
@@ -111,6 +114,7 @@ impl MlsCodecExtension for RatchetTreeExt {
     safer_ffi_gen::ffi_type(clone, opaque)
 )]
 #[derive(Clone, Debug, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RequiredCapabilitiesExt {
     pub extensions: Vec<ExtensionType>,
     pub proposals: Vec<ProposalType>,
@@ -166,6 +170,7 @@ impl MlsCodecExtension for RequiredCapabilitiesExt {
     safer_ffi_gen::ffi_type(clone, opaque)
 )]
 #[derive(Clone, Debug, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExternalPubExt {
     /// Public key to be used for an external commit.
     #[mls_codec(with = "mls_rs_codec::byte_vec")]
@@ -194,6 +199,7 @@ impl MlsCodecExtension for ExternalPubExt {
     safer_ffi_gen::ffi_type(clone, opaque)
 )]
 #[derive(Clone, Debug, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub struct ExternalSendersExt {
     pub allowed_senders: Vec<SigningIdentity>,
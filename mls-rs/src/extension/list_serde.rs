@@ -0,0 +1,231 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+//! Serde support for the [`ExtensionList`] fields of wire types.
+//!
+//! Human readable formats write the built-in extensions as their decoded
+//! content and other extensions as hex bytes. Built-in extensions whose data
+//! does not decode, or does not encode back to the same bytes, are also
+//! written as hex so that signatures over them stay valid. Both forms are accepted when deserializing, so data written
+//! with the hex form only can still be read. Other formats are unchanged.
+
+use alloc::vec::Vec;
+use mls_rs_codec::MlsEncode;
+use mls_rs_core::extension::{Extension, ExtensionList, ExtensionType, MlsExtension};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(feature = "by_ref_proposal")]
+use super::ExternalSendersExt;
+use super::{ApplicationIdExt, ExternalPubExt, RatchetTreeExt, RequiredCapabilitiesExt};
+
+#[derive(Serialize, Deserialize)]
+#[serde(
+    tag = "extension_type",
+    content = "extension_data",
+    rename_all = "snake_case"
+)]
+enum ExtensionRepr<E> {
+    ApplicationId(ApplicationIdExt),
+    RatchetTree(RatchetTreeExt),
+    RequiredCapabilities(RequiredCapabilitiesExt),
+    ExternalPub(ExternalPubExt),
+    #[cfg(feature = "by_ref_proposal")]
+    ExternalSenders(ExternalSendersExt),
+    #[serde(untagged)]
+    Other(E),
+}
+
+impl<'a> From<&'a Extension> for ExtensionRepr<&'a Extension> {
+    fn from(ext: &'a Extension) -> Self {
+        let decoded = match ext.extension_type {
+            ExtensionType::APPLICATION_ID => {
+                ApplicationIdExt::from_extension(ext).map(Self::ApplicationId)
+            }
+            ExtensionType::RATCHET_TREE => {
+                RatchetTreeExt::from_extension(ext).map(Self::RatchetTree)
+            }
+            ExtensionType::REQUIRED_CAPABILITIES => {
+                RequiredCapabilitiesExt::from_extension(ext).map(Self::RequiredCapabilities)
+            }
+            ExtensionType::EXTERNAL_PUB => {
+                ExternalPubExt::from_extension(ext).map(Self::ExternalPub)
+            }
+            #[cfg(feature = "by_ref_proposal")]
+            ExtensionType::EXTERNAL_SENDERS => {
+                ExternalSendersExt::from_extension(ext).map(Self::ExternalSenders)
+            }
+            _ => return Self::Other(ext),
+        };
+
+        // Decoding ignores trailing bytes, which the decoded form would drop.
+        // Extensions are part of signed content, so only use it if it encodes
+        // back to the same bytes.
+        match decoded {
+            Ok(repr) if repr.encodes_to(&ext.extension_data) => repr,
+            _ => Self::Other(ext),
+        }
+    }
+}
+
+impl<E> ExtensionRepr<E> {
+    fn encodes_to(&self, data: &[u8]) -> bool {
+        let encoded = match self {
+            Self::ApplicationId(ext) => ext.mls_encode_to_vec(),
+            Self::RatchetTree(ext) => ext.mls_encode_to_vec(),
+            Self::RequiredCapabilities(ext) => ext.mls_encode_to_vec(),
+            Self::ExternalPub(ext) => ext.mls_encode_to_vec(),
+            #[cfg(feature = "by_ref_proposal")]
+            Self::ExternalSenders(ext) => ext.mls_encode_to_vec(),
+            Self::Other(_) => return false,
+        };
+
+        matches!(encoded, Ok(encoded) if encoded == data)
+    }
+}
+
+impl TryFrom<ExtensionRepr<Extension>> for Extension {
+    type Error = mls_rs_core::extension::ExtensionError;
+
+    fn try_from(repr: ExtensionRepr<Extension>) -> Result<Self, Self::Error> {
+        match repr {
+            ExtensionRepr::ApplicationId(ext) => ext.into_extension(),
+            ExtensionRepr::RatchetTree(ext) => ext.into_extension(),
+            ExtensionRepr::RequiredCapabilities(ext) => ext.into_extension(),
+            ExtensionRepr::ExternalPub(ext) => ext.into_extension(),
+            #[cfg(feature = "by_ref_proposal")]
+            ExtensionRepr::ExternalSenders(ext) => ext.into_extension(),
+            ExtensionRepr::Other(ext) => Ok(ext),
+        }
+    }
+}
+
+pub(crate) fn serialize<S: Serializer>(list: &ExtensionList, s: S) -> Result<S::Ok, S::Error> {
+    if s.is_human_readable() {
+        s.collect_seq(list.iter().map(ExtensionRepr::from))
+    } else {
+        list.serialize(s)
+    }
+}
+
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<ExtensionList, D::Error> {
+    if !d.is_human_readable() {
+        return ExtensionList::deserialize(d);
+    }
+
+    Vec::<ExtensionRepr<Extension>>::deserialize(d)?
+        .into_iter()
+        .map(|repr| Extension::try_from(repr).map_err(de::Error::custom))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use mls_rs_core::extension::{Extension, ExtensionList, ExtensionType, MlsExtension};
+
+    use crate::{
+        extension::{ApplicationIdExt, RatchetTreeExt, RequiredCapabilitiesExt},
+        group::ExportedTree,
+        tree_kem::node::NodeVec,
+    };
+
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct Wrapper(#[serde(with = "super")] ExtensionList);
+
+    fn test_list() -> ExtensionList {
+        ExtensionList::from(vec![
+            ApplicationIdExt::new(b"alice".to_vec())
+                .into_extension()
+                .unwrap(),
+            RatchetTreeExt {
+                tree_data: ExportedTree::new(NodeVec::from(vec![None])),
+            }
+            .into_extension()
+            .unwrap(),
+            RequiredCapabilitiesExt::default().into_extension().unwrap(),
+            Extension::new(0xff00.into(), vec![1, 2]),
+        ])
+    }
+
+    #[test]
+    fn built_in_extensions_are_decoded() {
+        let json = serde_json::to_value(Wrapper(test_list())).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {
+                    "extension_type": "application_id",
+                    "extension_data": { "identifier": "616c696365" }
+                },
+                {
+                    "extension_type": "ratchet_tree",
+                    "extension_data": { "tree_data": [null] }
+                },
+                {
+                    "extension_type": "required_capabilities",
+                    "extension_data": { "extensions": [], "proposals": [], "credentials": [] }
+                },
+                { "extension_type": 65280, "extension_data": "0102" }
+            ])
+        );
+
+        let decoded: Wrapper = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, Wrapper(test_list()));
+    }
+
+    #[test]
+    fn hex_extensions_are_accepted() {
+        let json = serde_json::to_string(&test_list()).unwrap();
+        let decoded: Wrapper = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(decoded, Wrapper(test_list()));
+    }
+
+    #[test]
+    fn malformed_built_in_extensions_use_hex() {
+        let list = ExtensionList::from(vec![Extension::new(
+            ExtensionType::REQUIRED_CAPABILITIES,
+            vec![0xff],
+        )]);
+
+        let json = serde_json::to_value(Wrapper(list.clone())).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!([
+                { "extension_type": "required_capabilities", "extension_data": "ff" }
+            ])
+        );
+
+        assert_eq!(
+            serde_json::from_value::<Wrapper>(json).unwrap(),
+            Wrapper(list)
+        );
+    }
+
+    #[test]
+    fn built_in_extensions_with_trailing_bytes_use_hex() {
+        let mut ext = ApplicationIdExt::new(b"alice".to_vec())
+            .into_extension()
+            .unwrap();
+
+        ext.extension_data.push(0);
+
+        let list = ExtensionList::from(vec![ext]);
+        let json = serde_json::to_value(Wrapper(list.clone())).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!([
+                { "extension_type": "application_id", "extension_data": "05616c69636500" }
+            ])
+        );
+
+        assert_eq!(
+            serde_json::from_value::<Wrapper>(json).unwrap(),
+            Wrapper(list)
+        );
+    }
+}
@@ -16,6 +16,7 @@ pub(crate) use config::ExternalClientConfig;
 use mls_rs_core::{
     crypto::{CryptoProvider, SignatureSecretKey},
     identity::SigningIdentity,
+    time::TimeProvider,
 };
 
 use builder::{ExternalBaseConfig, ExternalClientBuilder};
@@ -71,6 +72,9 @@ where
     /// at the time the welcome message
     /// was created. `tree_data` can be exported from a group using the
     /// [export tree function](crate::group::Group::export_tree).
+    ///
+    /// If neither is available, the tree is fetched from the
+    /// [`ExternalClientBuilder::ratchet_tree_provider`].
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn observe_group(
         &self,
@@ -115,8 +119,9 @@ where
             .ok_or(MlsError::UnsupportedCipherSuite(key_package.cipher_suite))?;
 
         let id = self.config.identity_provider();
+        let time = self.config.time_provider().now();
 
-        validate_key_package(&key_package, version, &cs, &id).await?;
+        validate_key_package(&key_package, version, &cs, &id, time).await?;
 
         Ok(key_package)
     }
@@ -125,10 +130,16 @@ where
 #[cfg(test)]
 pub(crate) mod tests_utils {
     use crate::{
-        client::test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+        client::{
+            test_utils::{TestClientBuilder, TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+            MlsError,
+        },
         key_package::test_utils::test_key_package_message,
+        time::{FixedTimeProvider, MlsTime},
     };
 
+    use assert_matches::assert_matches;
+
     pub use super::builder::test_utils::*;
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
@@ -139,4 +150,31 @@ pub(crate) mod tests_utils {
 
         assert_eq!(kp.into_key_package().unwrap(), validated_kp);
     }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_client_validates_key_package_lifetime_with_time_provider() {
+        let kp = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("john", TEST_CIPHER_SUITE)
+            .await
+            .time_provider(FixedTimeProvider::new(MlsTime::from(1000)))
+            .key_package_lifetime(100)
+            .build()
+            .generate_key_package_message()
+            .await
+            .unwrap();
+
+        for (time, valid) in [(1050, true), (2000, false)] {
+            let server = TestExternalClientBuilder::new_for_test()
+                .time_provider(FixedTimeProvider::new(MlsTime::from(time)))
+                .build();
+
+            let res = server.validate_key_package(kp.clone()).await;
+
+            if valid {
+                assert!(res.is_ok());
+            } else {
+                assert_matches!(res, Err(MlsError::InvalidLifetime));
+            }
+        }
+    }
 }
@@ -16,6 +16,7 @@ use crate::{
     },
     identity::CredentialType,
     protocol_version::ProtocolVersion,
+    time::{SystemTimeProvider, TimeProvider},
     tree_kem::Capabilities,
     CryptoProvider, Sealed,
 };
@@ -25,7 +26,8 @@ use std::{
 };
 
 /// Base client configuration type when instantiating `ExternalClientBuilder`
-pub type ExternalBaseConfig = Config<Missing, DefaultMlsRules, Missing>;
+pub type ExternalBaseConfig =
+    Config<Missing, DefaultMlsRules, Missing, SystemTimeProvider, NoRatchetTreeProvider>;
 
 /// Builder for [`ExternalClient`]
 ///
@@ -112,6 +114,8 @@ impl ExternalClientBuilder<ExternalBaseConfig> {
             identity_provider: Missing,
             mls_rules: DefaultMlsRules::new(),
             crypto_provider: Missing,
+            time_provider: SystemTimeProvider,
+            ratchet_tree_provider: NoRatchetTreeProvider,
             signing_data: None,
         }))
     }
@@ -224,6 +228,8 @@ impl<C: IntoConfig> ExternalClientBuilder<C> {
             identity_provider,
             mls_rules: c.mls_rules,
             crypto_provider: c.crypto_provider,
+            time_provider: c.time_provider,
+            ratchet_tree_provider: c.ratchet_tree_provider,
             signing_data: c.signing_data,
         }))
     }
@@ -244,6 +250,55 @@ impl<C: IntoConfig> ExternalClientBuilder<C> {
             identity_provider: c.identity_provider,
             mls_rules: c.mls_rules,
             crypto_provider,
+            time_provider: c.time_provider,
+            ratchet_tree_provider: c.ratchet_tree_provider,
+            signing_data: c.signing_data,
+        }))
+    }
+
+    /// Set the time provider used by the client.
+    ///
+    /// The current time is used to validate the lifetime of leaf nodes and the
+    /// credentials of members. By default, the system clock is used.
+    pub fn time_provider<Tp>(
+        self,
+        time_provider: Tp,
+    ) -> ExternalClientBuilder<WithTimeProvider<Tp, C>>
+    where
+        Tp: TimeProvider,
+    {
+        let Config(c) = self.0.into_config();
+        ExternalClientBuilder(Config(ConfigInner {
+            settings: c.settings,
+            identity_provider: c.identity_provider,
+            mls_rules: c.mls_rules,
+            crypto_provider: c.crypto_provider,
+            time_provider,
+            ratchet_tree_provider: c.ratchet_tree_provider,
+            signing_data: c.signing_data,
+        }))
+    }
+
+    /// Set the provider used by the client to fetch ratchet trees that are
+    /// neither included in the group info nor provided by the application
+    /// when observing a group.
+    ///
+    /// By default, no ratchet tree is fetched.
+    pub fn ratchet_tree_provider<Rtp>(
+        self,
+        ratchet_tree_provider: Rtp,
+    ) -> ExternalClientBuilder<WithRatchetTreeProvider<Rtp, C>>
+    where
+        Rtp: RatchetTreeProvider,
+    {
+        let Config(c) = self.0.into_config();
+        ExternalClientBuilder(Config(ConfigInner {
+            settings: c.settings,
+            identity_provider: c.identity_provider,
+            mls_rules: c.mls_rules,
+            crypto_provider: c.crypto_provider,
+            time_provider: c.time_provider,
+            ratchet_tree_provider,
             signing_data: c.signing_data,
         }))
     }
@@ -265,6 +320,8 @@ impl<C: IntoConfig> ExternalClientBuilder<C> {
             identity_provider: c.identity_provider,
             mls_rules,
             crypto_provider: c.crypto_provider,
+            time_provider: c.time_provider,
+            ratchet_tree_provider: c.ratchet_tree_provider,
             signing_data: c.signing_data,
         }))
     }
@@ -286,6 +343,8 @@ where
     C::IdentityProvider: IdentityProvider + Clone,
     C::MlsRules: MlsRules + Clone,
     C::CryptoProvider: CryptoProvider + Clone,
+    C::TimeProvider: TimeProvider + Clone,
+    C::RatchetTreeProvider: RatchetTreeProvider + Clone,
 {
     pub(crate) fn build_config(self) -> IntoConfigOutput<C> {
         let mut c = self.0.into_config();
@@ -315,37 +374,80 @@ pub struct Missing;
 /// Change the identity validator used by a client configuration.
 ///
 /// See [`ExternalClientBuilder::identity_provider`].
-pub type WithIdentityProvider<I, C> =
-    Config<I, <C as IntoConfig>::MlsRules, <C as IntoConfig>::CryptoProvider>;
+pub type WithIdentityProvider<I, C> = Config<
+    I,
+    <C as IntoConfig>::MlsRules,
+    <C as IntoConfig>::CryptoProvider,
+    <C as IntoConfig>::TimeProvider,
+    <C as IntoConfig>::RatchetTreeProvider,
+>;
 
 /// Change the proposal filter used by a client configuration.
 ///
 /// See [`ExternalClientBuilder::mls_rules`].
-pub type WithMlsRules<Pr, C> =
-    Config<<C as IntoConfig>::IdentityProvider, Pr, <C as IntoConfig>::CryptoProvider>;
+pub type WithMlsRules<Pr, C> = Config<
+    <C as IntoConfig>::IdentityProvider,
+    Pr,
+    <C as IntoConfig>::CryptoProvider,
+    <C as IntoConfig>::TimeProvider,
+    <C as IntoConfig>::RatchetTreeProvider,
+>;
 
 /// Change the crypto provider used by a client configuration.
 ///
 /// See [`ExternalClientBuilder::crypto_provider`].
-pub type WithCryptoProvider<Cp, C> =
-    Config<<C as IntoConfig>::IdentityProvider, <C as IntoConfig>::MlsRules, Cp>;
+pub type WithCryptoProvider<Cp, C> = Config<
+    <C as IntoConfig>::IdentityProvider,
+    <C as IntoConfig>::MlsRules,
+    Cp,
+    <C as IntoConfig>::TimeProvider,
+    <C as IntoConfig>::RatchetTreeProvider,
+>;
+
+/// Change the time provider used by a client configuration.
+///
+/// See [`ExternalClientBuilder::time_provider`].
+pub type WithTimeProvider<Tp, C> = Config<
+    <C as IntoConfig>::IdentityProvider,
+    <C as IntoConfig>::MlsRules,
+    <C as IntoConfig>::CryptoProvider,
+    Tp,
+    <C as IntoConfig>::RatchetTreeProvider,
+>;
+
+/// Change the ratchet tree provider used by a client configuration.
+///
+/// See [`ExternalClientBuilder::ratchet_tree_provider`].
+pub type WithRatchetTreeProvider<Rtp, C> = Config<
+    <C as IntoConfig>::IdentityProvider,
+    <C as IntoConfig>::MlsRules,
+    <C as IntoConfig>::CryptoProvider,
+    <C as IntoConfig>::TimeProvider,
+    Rtp,
+>;
 
 /// Helper alias for `Config`.
 pub type IntoConfigOutput<C> = Config<
     <C as IntoConfig>::IdentityProvider,
     <C as IntoConfig>::MlsRules,
     <C as IntoConfig>::CryptoProvider,
+    <C as IntoConfig>::TimeProvider,
+    <C as IntoConfig>::RatchetTreeProvider,
 >;
 
-impl<Ip, Pr, Cp> ExternalClientConfig for ConfigInner<Ip, Pr, Cp>
+impl<Ip, Pr, Cp, Tp, Rtp> ExternalClientConfig for ConfigInner<Ip, Pr, Cp, Tp, Rtp>
 where
     Ip: IdentityProvider + Clone,
     Pr: MlsRules + Clone,
     Cp: CryptoProvider + Clone,
+    Tp: TimeProvider + Clone,
+    Rtp: RatchetTreeProvider + Clone,
 {
     type IdentityProvider = Ip;
     type MlsRules = Pr;
     type CryptoProvider = Cp;
+    type TimeProvider = Tp;
+    type RatchetTreeProvider = Rtp;
 
     fn supported_extensions(&self) -> Vec<ExtensionType> {
         self.settings.extension_types.clone()
@@ -363,6 +465,14 @@ where
         self.crypto_provider.clone()
     }
 
+    fn time_provider(&self) -> Self::TimeProvider {
+        self.time_provider.clone()
+    }
+
+    fn ratchet_tree_provider(&self) -> Self::RatchetTreeProvider {
+        self.ratchet_tree_provider.clone()
+    }
+
     fn external_signing_key(&self, external_key_id: &[u8]) -> Option<SignaturePublicKey> {
         self.settings
             .external_signing_keys
@@ -387,15 +497,17 @@ where
     }
 }
 
-impl<Ip, Mpf, Cp> Sealed for Config<Ip, Mpf, Cp> {}
+impl<Ip, Mpf, Cp, Tp, Rtp> Sealed for Config<Ip, Mpf, Cp, Tp, Rtp> {}
 
-impl<Ip, Pr, Cp> MlsConfig for Config<Ip, Pr, Cp>
+impl<Ip, Pr, Cp, Tp, Rtp> MlsConfig for Config<Ip, Pr, Cp, Tp, Rtp>
 where
     Ip: IdentityProvider + Clone,
     Pr: MlsRules + Clone,
     Cp: CryptoProvider + Clone,
+    Tp: TimeProvider + Clone,
+    Rtp: RatchetTreeProvider + Clone,
 {
-    type Output = ConfigInner<Ip, Pr, Cp>;
+    type Output = ConfigInner<Ip, Pr, Cp, Tp, Rtp>;
 
     fn get(&self) -> &Self::Output {
         &self.0
@@ -420,6 +532,8 @@ impl<T: MlsConfig> ExternalClientConfig for T {
     type IdentityProvider = <T::Output as ExternalClientConfig>::IdentityProvider;
     type MlsRules = <T::Output as ExternalClientConfig>::MlsRules;
     type CryptoProvider = <T::Output as ExternalClientConfig>::CryptoProvider;
+    type TimeProvider = <T::Output as ExternalClientConfig>::TimeProvider;
+    type RatchetTreeProvider = <T::Output as ExternalClientConfig>::RatchetTreeProvider;
 
     fn supported_extensions(&self) -> Vec<ExtensionType> {
         self.get().supported_extensions()
@@ -441,6 +555,14 @@ impl<T: MlsConfig> ExternalClientConfig for T {
         self.get().crypto_provider()
     }
 
+    fn time_provider(&self) -> Self::TimeProvider {
+        self.get().time_provider()
+    }
+
+    fn ratchet_tree_provider(&self) -> Self::RatchetTreeProvider {
+        self.get().ratchet_tree_provider()
+    }
+
     fn external_signing_key(&self, external_key_id: &[u8]) -> Option<SignaturePublicKey> {
         self.get().external_signing_key(external_key_id)
     }
@@ -525,14 +647,16 @@ mod private {
     use super::{IntoConfigOutput, Settings};
 
     #[derive(Clone, Debug)]
-    pub struct Config<Ip, Pr, Cp>(pub(crate) ConfigInner<Ip, Pr, Cp>);
+    pub struct Config<Ip, Pr, Cp, Tp, Rtp>(pub(crate) ConfigInner<Ip, Pr, Cp, Tp, Rtp>);
 
     #[derive(Clone, Debug)]
-    pub struct ConfigInner<Ip, Mpf, Cp> {
+    pub struct ConfigInner<Ip, Mpf, Cp, Tp, Rtp> {
         pub(crate) settings: Settings,
         pub(crate) identity_provider: Ip,
         pub(crate) mls_rules: Mpf,
         pub(crate) crypto_provider: Cp,
+        pub(crate) time_provider: Tp,
+        pub(crate) ratchet_tree_provider: Rtp,
         pub(crate) signing_data: Option<(SignatureSecretKey, SigningIdentity)>,
     }
 
@@ -540,14 +664,18 @@ mod private {
         type IdentityProvider;
         type MlsRules;
         type CryptoProvider;
+        type TimeProvider;
+        type RatchetTreeProvider;
 
         fn into_config(self) -> IntoConfigOutput<Self>;
     }
 
-    impl<Ip, Pr, Cp> IntoConfig for Config<Ip, Pr, Cp> {
+    impl<Ip, Pr, Cp, Tp, Rtp> IntoConfig for Config<Ip, Pr, Cp, Tp, Rtp> {
         type IdentityProvider = Ip;
         type MlsRules = Pr;
         type CryptoProvider = Cp;
+        type TimeProvider = Tp;
+        type RatchetTreeProvider = Rtp;
 
         fn into_config(self) -> Self {
             self
@@ -557,6 +685,7 @@ mod private {
 
 use mls_rs_core::{
     crypto::SignatureSecretKey,
+    group::{NoRatchetTreeProvider, RatchetTreeProvider},
     identity::{IdentityProvider, SigningIdentity},
 };
 use private::{Config, ConfigInner, IntoConfig};
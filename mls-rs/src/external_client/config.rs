@@ -2,7 +2,7 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use mls_rs_core::identity::IdentityProvider;
+use mls_rs_core::{group::RatchetTreeProvider, identity::IdentityProvider};
 
 use crate::{
     crypto::SignaturePublicKey,
@@ -10,6 +10,7 @@ use crate::{
     group::{mls_rules::MlsRules, proposal::ProposalType},
     identity::CredentialType,
     protocol_version::ProtocolVersion,
+    time::TimeProvider,
     tree_kem::Capabilities,
     CryptoProvider,
 };
@@ -18,12 +19,16 @@ pub trait ExternalClientConfig: Send + Sync + Clone {
     type IdentityProvider: IdentityProvider + Clone;
     type MlsRules: MlsRules + Clone;
     type CryptoProvider: CryptoProvider;
+    type TimeProvider: TimeProvider + Clone;
+    type RatchetTreeProvider: RatchetTreeProvider + Clone;
 
     fn supported_extensions(&self) -> Vec<ExtensionType>;
     fn supported_custom_proposals(&self) -> Vec<ProposalType>;
     fn supported_protocol_versions(&self) -> Vec<ProtocolVersion>;
     fn identity_provider(&self) -> Self::IdentityProvider;
     fn crypto_provider(&self) -> Self::CryptoProvider;
+    fn time_provider(&self) -> Self::TimeProvider;
+    fn ratchet_tree_provider(&self) -> Self::RatchetTreeProvider;
     fn external_signing_key(&self, external_key_id: &[u8]) -> Option<SignaturePublicKey>;
 
     fn mls_rules(&self) -> Self::MlsRules;
@@ -2,10 +2,14 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+use mls_rs_codec::{DecodeLimits, MlsDecode, MlsEncode, MlsSize};
 use mls_rs_core::{
-    crypto::SignatureSecretKey, error::IntoAnyError, extension::ExtensionList, group::Member,
+    crypto::SignatureSecretKey,
+    error::IntoAnyError,
+    extension::ExtensionList,
+    group::Member,
     identity::IdentityProvider,
+    time::{MlsTime, TimeProvider},
 };
 
 use crate::{
@@ -68,7 +72,7 @@ use crate::{
 };
 
 #[cfg(feature = "private_message")]
-use crate::group::framing::PrivateMessage;
+use crate::group::framing::PrivateMessageRef;
 
 use alloc::boxed::Box;
 
@@ -132,7 +136,10 @@ impl<C: ExternalClientConfig + Clone> ExternalGroup<C> {
             &group_info,
             tree_data,
             &config.identity_provider(),
+            &config.ratchet_tree_provider(),
+            DecodeLimits::default(),
             &cipher_suite_provider,
+            config.time_provider().now(),
         )
         .await?;
 
@@ -598,7 +605,7 @@ where
     #[cfg(feature = "private_message")]
     async fn process_ciphertext(
         &mut self,
-        cipher_text: &PrivateMessage,
+        cipher_text: PrivateMessageRef<'_>,
     ) -> Result<EventOrContent<Self::OutputType>, MlsError> {
         Ok(EventOrContent::Event(ExternalReceivedMessage::Ciphertext(
             cipher_text.content_type,
@@ -630,6 +637,10 @@ where
         AlwaysFoundPskStorage
     }
 
+    fn current_time(&self) -> Option<MlsTime> {
+        self.config.time_provider().now()
+    }
+
     fn group_state(&self) -> &GroupState {
         &self.state
     }
@@ -813,7 +824,7 @@ mod tests {
             framing::{Content, MlsMessagePayload},
             proposal::{AddProposal, Proposal, ProposalOrRef},
             proposal_ref::ProposalRef,
-            test_utils::{test_group, TestGroup},
+            test_utils::{test_group, TestGroup, TestRatchetTreeProvider},
             ProposalMessageDescription,
         },
         identity::{test_utils::get_test_signing_identity, SigningIdentity},
@@ -878,6 +889,37 @@ mod tests {
         }
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_group_fetches_ratchet_tree_from_provider() {
+        let alice = test_group_with_one_commit(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let group_info = alice
+            .group
+            .group_info_message_allowing_ext_commit(false)
+            .await
+            .unwrap();
+
+        let server = TestExternalClientBuilder::new_for_test()
+            .ratchet_tree_provider(TestRatchetTreeProvider::new(&alice.group))
+            .build()
+            .observe_group(group_info.clone(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            server.export_tree().unwrap(),
+            alice.group.export_tree().to_bytes().unwrap()
+        );
+
+        let res = TestExternalClientBuilder::new_for_test()
+            .build()
+            .observe_group(group_info, None)
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::RatchetTreeNotFound));
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn external_group_can_process_commit() {
         let mut alice = test_group_with_one_commit(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
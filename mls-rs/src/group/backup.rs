@@ -0,0 +1,525 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+use mls_rs_core::{
+    crypto::{CipherSuite, CipherSuiteProvider},
+    error::IntoAnyError,
+    key_package::{KeyPackageData, KeyPackageStorage},
+};
+use zeroize::Zeroizing;
+
+use crate::{client::MlsError, client_config::ClientConfig, Client, KeyPackageRef};
+
+#[cfg(feature = "prior_epoch")]
+use super::epoch::PriorEpoch;
+
+use super::{
+    cipher_suite_provider, key_schedule::kdf_expand_with_label, snapshot::Snapshot, CommitOutput,
+    Group,
+};
+
+const BACKUP_VERSION: u16 = 2;
+
+#[derive(Clone, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
+struct BackupHeader {
+    version: u16,
+    cipher_suite: CipherSuite,
+    #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    group_id: Vec<u8>,
+    epoch: u64,
+    #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    salt: Vec<u8>,
+}
+
+/// Encrypted backup of the state of a group.
+///
+/// A backup contains the current epoch of the group, the prior epochs kept
+/// in storage, the private keys of this member and optionally the secrets of
+/// key packages of this client, encrypted under a key chosen by the
+/// application. The header, containing the group id and the
+/// epoch, is authenticated but not encrypted.
+#[derive(Clone, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
+pub struct GroupBackup {
+    header: BackupHeader,
+    #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    ciphertext: Vec<u8>,
+}
+
+impl Debug for GroupBackup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GroupBackup")
+            .field("version", &self.header.version)
+            .field("cipher_suite", &self.header.cipher_suite)
+            .field(
+                "group_id",
+                &mls_rs_core::debug::pretty_group_id(&self.header.group_id),
+            )
+            .field("epoch", &self.header.epoch)
+            .field(
+                "ciphertext",
+                &mls_rs_core::debug::pretty_bytes(&self.ciphertext),
+            )
+            .finish()
+    }
+}
+
+impl GroupBackup {
+    pub fn version(&self) -> u16 {
+        self.header.version
+    }
+
+    pub fn cipher_suite(&self) -> CipherSuite {
+        self.header.cipher_suite
+    }
+
+    pub fn group_id(&self) -> &[u8] {
+        &self.header.group_id
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.header.epoch
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, MlsError> {
+        self.mls_encode_to_vec().map_err(Into::into)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MlsError> {
+        Self::mls_decode(&mut &*bytes).map_err(Into::into)
+    }
+}
+
+#[derive(MlsSize, MlsEncode, MlsDecode)]
+struct BackupContents {
+    snapshot: Snapshot,
+    #[cfg(feature = "prior_epoch")]
+    prior_epochs: Vec<PriorEpoch>,
+    key_packages: Vec<BackupKeyPackage>,
+}
+
+#[derive(MlsSize, MlsEncode, MlsDecode)]
+struct BackupKeyPackage {
+    #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    id: Vec<u8>,
+    data: KeyPackageData,
+}
+
+struct BackupCipher<'a, P: CipherSuiteProvider> {
+    cipher_suite: &'a P,
+    key: Zeroizing<Vec<u8>>,
+    nonce: Zeroizing<Vec<u8>>,
+    aad: Vec<u8>,
+}
+
+impl<'a, P: CipherSuiteProvider> BackupCipher<'a, P> {
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn new(
+        cipher_suite: &'a P,
+        backup_key: &[u8],
+        header: &BackupHeader,
+    ) -> Result<BackupCipher<'a, P>, MlsError> {
+        let secret = cipher_suite
+            .kdf_extract(&header.salt, backup_key)
+            .await
+            .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))?;
+
+        let key_len = cipher_suite.aead_key_size();
+
+        let key =
+            kdf_expand_with_label(cipher_suite, &secret, b"backup key", &[], Some(key_len)).await?;
+
+        let nonce_len = cipher_suite.aead_nonce_size();
+
+        let nonce =
+            kdf_expand_with_label(cipher_suite, &secret, b"backup nonce", &[], Some(nonce_len))
+                .await?;
+
+        Ok(Self {
+            cipher_suite,
+            key,
+            nonce,
+            aad: header.mls_encode_to_vec()?,
+        })
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, MlsError> {
+        self.cipher_suite
+            .aead_seal(&self.key, plaintext, Some(&self.aad), &self.nonce)
+            .await
+            .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn decrypt(&self, ciphertext: &[u8]) -> Result<Zeroizing<Vec<u8>>, MlsError> {
+        self.cipher_suite
+            .aead_open(&self.key, ciphertext, Some(&self.aad), &self.nonce)
+            .await
+            .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))
+    }
+}
+
+impl<C> Group<C>
+where
+    C: ClientConfig + Clone,
+{
+    /// Export the state of this group, encrypted under `backup_key`, so that
+    /// it can be restored on another device with [`Client::import_backup`].
+    ///
+    /// Each backup is encrypted with a fresh salt, so the same `backup_key`
+    /// can be reused across backups. The backup contains the private keys of
+    /// this member and must be treated accordingly, even when encrypted.
+    ///
+    /// A pending commit is not included in the backup, as the restored group
+    /// starts by committing an update of its own leaf. If the pending commit
+    /// was sent, the restored group will fail to process it and should be
+    /// restored from a backup exported after the commit was applied.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn export_backup(&self, backup_key: &[u8]) -> Result<GroupBackup, MlsError> {
+        self.export_backup_with_key_packages(backup_key, &[]).await
+    }
+
+    /// Export the state of this group as [`Group::export_backup`] does,
+    /// together with the secrets of the key packages of this client
+    /// referenced by `key_packages`.
+    ///
+    /// Including the key packages that this client published lets the
+    /// restored device join the groups it is invited to with them. Key
+    /// packages that are no longer in the
+    /// [KeyPackageStorage](crate::KeyPackageStorage) of the client, for
+    /// instance because they were used to join a group, are skipped.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn export_backup_with_key_packages(
+        &self,
+        backup_key: &[u8],
+        key_packages: &[KeyPackageRef],
+    ) -> Result<GroupBackup, MlsError> {
+        let mut snapshot = self.snapshot();
+        snapshot.clear_pending_commit();
+
+        let key_package_repo = self.config.key_package_repo();
+        let mut backup_key_packages = Vec::new();
+
+        for key_package in key_packages {
+            let data = key_package_repo
+                .get(key_package)
+                .await
+                .map_err(|e| MlsError::KeyPackageRepoError(e.into_any_error()))?;
+
+            if let Some(data) = data {
+                backup_key_packages.push(BackupKeyPackage {
+                    id: key_package.to_vec(),
+                    data,
+                });
+            }
+        }
+
+        let contents = BackupContents {
+            snapshot,
+            #[cfg(feature = "prior_epoch")]
+            prior_epochs: self.state_repo.prior_epochs().await?,
+            key_packages: backup_key_packages,
+        };
+
+        let salt = self
+            .cipher_suite_provider
+            .random_bytes_vec(self.cipher_suite_provider.kdf_extract_size())
+            .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))?;
+
+        let header = BackupHeader {
+            version: BACKUP_VERSION,
+            cipher_suite: self.cipher_suite(),
+            group_id: self.group_id().to_vec(),
+            epoch: self.current_epoch(),
+            salt,
+        };
+
+        let plaintext = Zeroizing::new(contents.mls_encode_to_vec()?);
+        let cipher = BackupCipher::new(&self.cipher_suite_provider, backup_key, &header).await?;
+        let ciphertext = cipher.encrypt(&plaintext).await?;
+
+        Ok(GroupBackup { header, ciphertext })
+    }
+}
+
+impl<C> Client<C>
+where
+    C: ClientConfig + Clone,
+{
+    /// Restore a group from a backup created by [`Group::export_backup`].
+    ///
+    /// The device that created the backup knows all secrets of the restored
+    /// member. To retire them, the restored group immediately creates a commit
+    /// updating the leaf of this member, which is returned along with the
+    /// group. The commit must be sent to the other members and applied with
+    /// [`Group::apply_pending_commit`] before the group is used further.
+    ///
+    /// The restored member keeps the signing identity and signature key of
+    /// the member that created the backup. The group must not already exist
+    /// in the storage of this client. Key packages included in the backup are
+    /// inserted in the [KeyPackageStorage](crate::KeyPackageStorage) of this
+    /// client.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn import_backup(
+        &self,
+        backup_key: &[u8],
+        backup: &GroupBackup,
+    ) -> Result<(Group<C>, CommitOutput), MlsError> {
+        if backup.header.version != BACKUP_VERSION {
+            return Err(MlsError::UnsupportedBackupVersion(backup.header.version));
+        }
+
+        let existing = Snapshot::load(
+            &self.config.group_state_storage(),
+            self.config.crypto_provider(),
+            &backup.header.group_id,
+        )
+        .await?;
+
+        if existing.is_some() {
+            return Err(MlsError::GroupAlreadyExists);
+        }
+
+        let cipher_suite_provider =
+            cipher_suite_provider(self.config.crypto_provider(), backup.header.cipher_suite)?;
+
+        let cipher = BackupCipher::new(&cipher_suite_provider, backup_key, &backup.header).await?;
+        let plaintext = cipher.decrypt(&backup.ciphertext).await?;
+        let contents = BackupContents::mls_decode(&mut &**plaintext)?;
+
+        if contents.snapshot.group_id() != backup.header.group_id {
+            return Err(MlsError::GroupIdMismatch);
+        }
+
+        let mut group = Group::from_snapshot(self.config.clone(), contents.snapshot).await?;
+
+        #[cfg(feature = "prior_epoch")]
+        for epoch in contents.prior_epochs {
+            group.state_repo.insert(epoch).await?;
+        }
+
+        let mut key_package_repo = self.config.key_package_repo();
+
+        for key_package in contents.key_packages {
+            key_package_repo
+                .insert(key_package.id, key_package.data)
+                .await
+                .map_err(|e| MlsError::KeyPackageRepoError(e.into_any_error()))?;
+        }
+
+        let commit = group.commit(Vec::new()).await?;
+
+        Ok((group, commit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use assert_matches::assert_matches;
+
+    use crate::{
+        client::{
+            test_utils::{TestClientBuilder, TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+            MlsError,
+        },
+        crypto::test_utils::test_cipher_suite_provider,
+        group::test_utils::test_n_member_group,
+        identity::test_utils::get_test_signing_identity,
+        storage_provider::in_memory::InMemoryGroupStateStorage,
+        Client,
+    };
+
+    #[cfg(feature = "prior_epoch")]
+    use crate::group::epoch::PriorEpoch;
+
+    #[cfg(feature = "prior_epoch")]
+    use mls_rs_core::group::GroupStateStorage;
+
+    use super::GroupBackup;
+
+    const BACKUP_KEY: &[u8] = b"backup key of the application";
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn restored_group_retires_leaf_secrets() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
+
+        // Move to a new epoch so that the backup includes a prior epoch.
+        let commit = groups[0].group.commit(vec![]).await.unwrap();
+        groups[0].process_pending_commit().await.unwrap();
+
+        groups[1]
+            .process_message(commit.commit_message)
+            .await
+            .unwrap();
+
+        let backup = groups[1].group.export_backup(BACKUP_KEY).await.unwrap();
+        let backup = GroupBackup::from_bytes(&backup.to_bytes().unwrap()).unwrap();
+
+        assert_eq!(backup.group_id(), groups[1].group.group_id());
+        assert_eq!(backup.epoch(), groups[1].group.current_epoch());
+
+        let storage = InMemoryGroupStateStorage::new();
+
+        let new_device = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("new device", TEST_CIPHER_SUITE)
+            .await
+            .group_state_storage(storage.clone())
+            .build();
+
+        let (mut restored, commit) = new_device.import_backup(BACKUP_KEY, &backup).await.unwrap();
+
+        let old_leaf = groups[1]
+            .group
+            .current_user_leaf_node()
+            .unwrap()
+            .public_key
+            .clone();
+
+        groups[0]
+            .process_message(commit.commit_message)
+            .await
+            .unwrap();
+
+        restored.apply_pending_commit().await.unwrap();
+        restored.write_to_storage().await.unwrap();
+
+        assert_ne!(
+            restored.current_user_leaf_node().unwrap().public_key,
+            old_leaf
+        );
+
+        assert_eq!(
+            restored.fork_fingerprint().unwrap(),
+            groups[0].group.fork_fingerprint().unwrap()
+        );
+
+        #[cfg(feature = "prior_epoch")]
+        {
+            let group_id = groups[0].group.group_id();
+            let max_epoch_id = storage.max_epoch_id(group_id).await.unwrap();
+            let first_epoch = storage.epoch::<PriorEpoch>(group_id, 1).await.unwrap();
+
+            assert_eq!(max_epoch_id, Some(2));
+            assert!(first_epoch.is_some());
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn backup_requires_correct_key() {
+        let groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
+        let backup = groups[1].group.export_backup(BACKUP_KEY).await.unwrap();
+
+        let new_device = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("new device", TEST_CIPHER_SUITE)
+            .await
+            .build();
+
+        let res = new_device
+            .import_backup(b"wrong key", &backup)
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::CryptoProviderError(_)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn backup_is_not_restored_over_existing_group() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
+        groups[1].group.write_to_storage().await.unwrap();
+
+        let backup = groups[1].group.export_backup(BACKUP_KEY).await.unwrap();
+
+        let client = Client::new(
+            groups[1].group.config.clone(),
+            None,
+            None,
+            TEST_PROTOCOL_VERSION,
+        );
+
+        let res = client.import_backup(BACKUP_KEY, &backup).await.map(|_| ());
+
+        assert_matches!(res, Err(MlsError::GroupAlreadyExists));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn backup_includes_key_package_secrets() {
+        let (identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+
+        let old_device = TestClientBuilder::new_for_test()
+            .signing_identity(identity.clone(), secret_key.clone(), TEST_CIPHER_SUITE)
+            .build();
+
+        let key_package = old_device.generate_key_package_message().await.unwrap();
+
+        let key_package_ref = key_package
+            .clone()
+            .into_key_package()
+            .unwrap()
+            .to_reference(&test_cipher_suite_provider(TEST_CIPHER_SUITE))
+            .await
+            .unwrap();
+
+        let group = old_device.create_group(Default::default()).await.unwrap();
+
+        let backup = group
+            .export_backup_with_key_packages(BACKUP_KEY, &[key_package_ref])
+            .await
+            .unwrap();
+
+        let new_device = TestClientBuilder::new_for_test()
+            .signing_identity(identity, secret_key, TEST_CIPHER_SUITE)
+            .build();
+
+        new_device.import_backup(BACKUP_KEY, &backup).await.unwrap();
+
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 1).await;
+
+        let commit = groups[0]
+            .group
+            .commit_builder()
+            .add_member(key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        let (joined, _) = new_device
+            .join_group(None, &commit.welcome_messages[0])
+            .await
+            .unwrap();
+
+        assert_eq!(joined.roster().members_iter().count(), 2);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn backup_excludes_pending_commit() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
+        groups[1].group.commit(vec![]).await.unwrap();
+
+        let backup = groups[1].group.export_backup(BACKUP_KEY).await.unwrap();
+
+        let new_device = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("new device", TEST_CIPHER_SUITE)
+            .await
+            .build();
+
+        let (mut restored, commit) = new_device.import_backup(BACKUP_KEY, &backup).await.unwrap();
+
+        groups[0]
+            .process_message(commit.commit_message)
+            .await
+            .unwrap();
+
+        restored.apply_pending_commit().await.unwrap();
+
+        assert_eq!(
+            restored.fork_fingerprint().unwrap(),
+            groups[0].group.fork_fingerprint().unwrap()
+        );
+    }
+}
@@ -13,14 +13,14 @@ use super::{
     framing::{ContentType, FramedContent, Sender, WireFormat},
     message_signature::AuthenticatedContent,
     padding::PaddingMode,
-    secret_tree::{KeyType, MessageKeyData},
+    secret_tree::{KeyType, MessageKeyData, SenderRatchetLimits},
     GroupContext,
 };
 use crate::{
     client::MlsError,
     tree_kem::node::{LeafIndex, NodeIndex},
 };
-use mls_rs_codec::MlsEncode;
+use mls_rs_codec::{DecodeLimits, MlsEncode};
 use mls_rs_core::{crypto::CipherSuiteProvider, error::IntoAnyError};
 use zeroize::Zeroizing;
 
@@ -29,9 +29,9 @@ mod reuse_guard;
 mod sender_data_key;
 
 #[cfg(feature = "private_message")]
-use super::framing::{PrivateContentAAD, PrivateMessage, PrivateMessageContent};
+use super::framing::{PrivateContentAAD, PrivateMessage, PrivateMessageContent, PrivateMessageRef};
 
-#[cfg(test)]
+#[cfg(any(test, feature = "conformance_util"))]
 pub use sender_data_key::test_utils::*;
 
 pub(crate) trait GroupStateProvider {
@@ -48,6 +48,8 @@ where
 {
     group_state: &'a mut GS,
     cipher_suite_provider: CP,
+    ratchet_limits: SenderRatchetLimits,
+    decode_limits: DecodeLimits,
 }
 
 impl<'a, GS, CP> CiphertextProcessor<'a, GS, CP>
@@ -62,6 +64,25 @@ where
         Self {
             group_state,
             cipher_suite_provider,
+            ratchet_limits: Default::default(),
+            decode_limits: Default::default(),
+        }
+    }
+
+    /// Bound the generations for which decryption keys are derived.
+    pub fn with_ratchet_limits(self, ratchet_limits: SenderRatchetLimits) -> Self {
+        Self {
+            ratchet_limits,
+            ..self
+        }
+    }
+
+    /// Limits for decoding decrypted content.
+    #[cfg(feature = "private_message")]
+    pub fn with_decode_limits(self, decode_limits: DecodeLimits) -> Self {
+        Self {
+            decode_limits,
+            ..self
         }
     }
 
@@ -91,7 +112,13 @@ where
         self.group_state
             .epoch_secrets_mut()
             .secret_tree
-            .message_key_generation(&self.cipher_suite_provider, sender, key_type, generation)
+            .message_key_generation_with_limits(
+                &self.cipher_suite_provider,
+                sender,
+                key_type,
+                generation,
+                self.ratchet_limits,
+            )
             .await
     }
 
@@ -194,7 +221,7 @@ where
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn open(
         &mut self,
-        ciphertext: &PrivateMessage,
+        ciphertext: PrivateMessageRef<'_>,
     ) -> Result<AuthenticatedContent, MlsError> {
         // Decrypt the sender data with the derived sender_key and sender_nonce from the message
         // epoch's key schedule
@@ -206,13 +233,13 @@ where
 
         let sender_data_key = SenderDataKey::new(
             &self.group_state.epoch_secrets().sender_data_secret,
-            &ciphertext.ciphertext,
+            ciphertext.ciphertext,
             &self.cipher_suite_provider,
         )
         .await?;
 
         let sender_data = sender_data_key
-            .open(&ciphertext.encrypted_sender_data, &sender_data_aad)
+            .open(ciphertext.encrypted_sender_data, &sender_data_aad)
             .await?;
 
         if self.group_state.self_index() == sender_data.sender {
@@ -235,24 +262,27 @@ where
         let decrypted_content = MessageKey::new(key)
             .decrypt(
                 &self.cipher_suite_provider,
-                &ciphertext.ciphertext,
+                ciphertext.ciphertext,
                 &PrivateContentAAD::from(ciphertext).mls_encode_to_vec()?,
                 &sender_data.reuse_guard,
             )
             .await
             .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))?;
 
-        let ciphertext_content =
-            PrivateMessageContent::mls_decode(&mut &**decrypted_content, ciphertext.content_type)?;
+        let ciphertext_content = PrivateMessageContent::mls_decode(
+            &mut &**decrypted_content,
+            ciphertext.content_type,
+            self.decode_limits,
+        )?;
 
         // Build the MLS plaintext object and process it
         let auth_content = AuthenticatedContent {
             wire_format: WireFormat::PrivateMessage,
             content: FramedContent {
-                group_id: ciphertext.group_id.clone(),
+                group_id: ciphertext.group_id.to_vec(),
                 epoch: ciphertext.epoch,
                 sender,
-                authenticated_data: ciphertext.authenticated_data.clone(),
+                authenticated_data: ciphertext.authenticated_data.to_vec(),
                 content: ciphertext_content.content,
             },
             auth: ciphertext_content.auth,
@@ -335,7 +365,7 @@ mod test {
 
             let mut receiver_processor = test_processor(&mut receiver_group, cipher_suite);
 
-            let decrypted = receiver_processor.open(&ciphertext).await.unwrap();
+            let decrypted = receiver_processor.open((&ciphertext).into()).await.unwrap();
 
             assert_eq!(decrypted, test_data.content);
         }
@@ -384,7 +414,7 @@ mod test {
             .await
             .unwrap();
 
-        let res = ciphertext_processor.open(&ciphertext).await;
+        let res = ciphertext_processor.open((&ciphertext).into()).await;
 
         assert_matches!(res, Err(MlsError::CantProcessMessageFromSelf))
     }
@@ -403,7 +433,7 @@ mod test {
         ciphertext.ciphertext = random_bytes(ciphertext.ciphertext.len());
         receiver_group.group.private_tree.self_index = LeafIndex::new(1);
 
-        let res = ciphertext_processor.open(&ciphertext).await;
+        let res = ciphertext_processor.open((&ciphertext).into()).await;
 
         assert!(res.is_err());
     }
@@ -136,7 +136,7 @@ impl<'a, CP: CipherSuiteProvider> SenderDataKey<'a, CP> {
     }
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "conformance_util"))]
 pub(crate) mod test_utils {
     use alloc::vec::Vec;
     use mls_rs_core::crypto::CipherSuiteProvider;
@@ -156,7 +156,7 @@ pub(crate) mod test_utils {
     }
 
     impl InteropSenderData {
-        #[cfg(not(mls_build_async))]
+        #[cfg(all(test, not(mls_build_async)))]
         #[cfg_attr(coverage_nightly, coverage(off))]
         pub(crate) fn new<P: CipherSuiteProvider>(cs: &P) -> Self {
             let secret = cs.random_bytes_vec(cs.kdf_extract_size()).unwrap().into();
@@ -9,6 +9,7 @@ use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
 use mls_rs_core::{
     crypto::{CipherSuiteProvider, SignatureSecretKey},
     error::IntoAnyError,
+    time::TimeProvider,
 };
 
 use crate::{
@@ -189,6 +190,7 @@ where
     group_info_extensions: ExtensionList,
     new_signer: Option<SignatureSecretKey>,
     new_signing_identity: Option<SigningIdentity>,
+    path_required: bool,
 }
 
 impl<'a, C> CommitBuilder<'a, C>
@@ -337,6 +339,18 @@ where
         }
     }
 
+    /// Include a path update in this commit even if neither the proposals
+    /// nor the [`MlsRules`](crate::MlsRules) in use require one.
+    ///
+    /// A path update refreshes the committer's leaf keys, providing
+    /// post-compromise security.
+    pub fn path_required(self, path_required: bool) -> Self {
+        Self {
+            path_required,
+            ..self
+        }
+    }
+
     /// Finalize the commit to send.
     ///
     /// # Errors
@@ -355,6 +369,7 @@ where
                 self.group_info_extensions,
                 self.new_signer,
                 self.new_signing_identity,
+                self.path_required,
             )
             .await
     }
@@ -413,6 +428,7 @@ where
             Default::default(),
             None,
             None,
+            false,
         )
         .await
     }
@@ -427,6 +443,7 @@ where
             group_info_extensions: Default::default(),
             new_signer: Default::default(),
             new_signing_identity: Default::default(),
+            path_required: false,
         }
     }
 
@@ -442,6 +459,7 @@ where
         mut welcome_group_info_extensions: ExtensionList,
         new_signer: Option<SignatureSecretKey>,
         new_signing_identity: Option<SigningIdentity>,
+        path_required: bool,
     ) -> Result<CommitOutput, MlsError> {
         if self.pending_commit.is_some() {
             return Err(MlsError::ExistingPendingCommit);
@@ -467,11 +485,7 @@ where
         let new_signer_ref = new_signer.as_ref().unwrap_or(&self.signer);
         let old_signer = &self.signer;
 
-        #[cfg(feature = "std")]
-        let time = Some(crate::time::MlsTime::now());
-
-        #[cfg(not(feature = "std"))]
-        let time = None;
+        let time = self.config.time_provider().now();
 
         #[cfg(feature = "by_ref_proposal")]
         let proposals = self.state.proposals.prepare_commit(sender, proposals);
@@ -518,7 +532,8 @@ where
             )
             .map_err(|e| MlsError::MlsRulesError(e.into_any_error()))?;
 
-        let perform_path_update = commit_options.path_required
+        let perform_path_update = path_required
+            || commit_options.path_required
             || path_update_required(&provisional_state.applied_proposals);
 
         let (update_path, path_secrets, commit_secret) = if perform_path_update {
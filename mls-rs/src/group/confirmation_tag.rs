@@ -68,7 +68,7 @@ impl ConfirmationTag {
     }
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "conformance_util"))]
 impl ConfirmationTag {
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub(crate) async fn empty<P: CipherSuiteProvider>(cipher_suite_provider: &P) -> Self {
@@ -29,6 +29,7 @@ pub struct GroupContext {
     #[mls_codec(with = "mls_rs_codec::byte_vec")]
     pub(crate) tree_hash: Vec<u8>,
     pub(crate) confirmed_transcript_hash: ConfirmedTranscriptHash,
+    #[cfg_attr(feature = "serde", serde(with = "crate::extension::list_serde"))]
     pub(crate) extensions: ExtensionList,
 }
 
@@ -95,4 +96,9 @@ impl GroupContext {
     pub fn extensions(&self) -> &ExtensionList {
         &self.extensions
     }
+
+    /// Get the tree hash of the ratchet tree for the current epoch.
+    pub fn tree_hash(&self) -> &[u8] {
+        &self.tree_hash
+    }
 }
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
 use alloc::{borrow::Cow, vec::Vec};
-use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+use mls_rs_codec::{DecodeContext, DecodeLimits, MlsDecode, MlsEncode, MlsSize, Writer};
 
 use crate::{client::MlsError, tree_kem::node::NodeVec};
 
@@ -11,17 +11,25 @@ use crate::{client::MlsError, tree_kem::node::NodeVec};
     all(feature = "ffi", not(test)),
     safer_ffi_gen::ffi_type(clone, opaque)
 )]
-#[derive(Debug, MlsSize, MlsEncode, MlsDecode, PartialEq, Clone)]
-pub struct ExportedTree<'a>(pub(crate) Cow<'a, NodeVec>);
+#[derive(Debug, Clone)]
+pub struct ExportedTree<'a>(pub(crate) TreeData<'a>);
+
+#[derive(Debug, Clone)]
+pub(crate) enum TreeData<'a> {
+    Nodes(Cow<'a, NodeVec>),
+    /// Encoded nodes, including the length prefix, which are only decoded
+    /// within the given limits when they are used.
+    Encoded(Cow<'a, [u8]>, DecodeLimits),
+}
 
 #[cfg_attr(all(feature = "ffi", not(test)), ::safer_ffi_gen::safer_ffi_gen)]
 impl<'a> ExportedTree<'a> {
     pub(crate) fn new(node_data: NodeVec) -> Self {
-        Self(Cow::Owned(node_data))
+        Self(TreeData::Nodes(Cow::Owned(node_data)))
     }
 
     pub(crate) fn new_borrowed(node_data: &'a NodeVec) -> Self {
-        Self(Cow::Borrowed(node_data))
+        Self(TreeData::Nodes(Cow::Borrowed(node_data)))
     }
 
     pub fn to_bytes(&self) -> Result<Vec<u8>, MlsError> {
@@ -33,19 +41,184 @@ impl<'a> ExportedTree<'a> {
     }
 
     pub fn into_owned(self) -> ExportedTree<'static> {
-        ExportedTree(Cow::Owned(self.0.into_owned()))
+        ExportedTree(match self.0 {
+            TreeData::Nodes(nodes) => TreeData::Nodes(Cow::Owned(nodes.into_owned())),
+            TreeData::Encoded(bytes, limits) => {
+                TreeData::Encoded(Cow::Owned(bytes.into_owned()), limits)
+            }
+        })
+    }
+}
+
+impl<'a> ExportedTree<'a> {
+    /// Deserialize from `bytes` without copying them, failing if they exceed
+    /// the default [`DecodeLimits`].
+    ///
+    /// Only the length of the tree is checked here. The nodes are decoded
+    /// when the tree is used, e.g. to join a group, and trees that are only
+    /// passed on with [`ExportedTree::to_bytes`] are never decoded.
+    pub fn from_bytes_borrowed(bytes: &'a [u8]) -> Result<Self, MlsError> {
+        Self::from_bytes_borrowed_with_limits(bytes, DecodeLimits::default())
+    }
+
+    /// Deserialize from `bytes` without copying them, failing if they exceed
+    /// `limits`.
+    ///
+    /// The nodes are decoded within the same `limits` when the tree is used.
+    pub fn from_bytes_borrowed_with_limits(
+        bytes: &'a [u8],
+        limits: DecodeLimits,
+    ) -> Result<Self, MlsError> {
+        let context = DecodeContext::new(limits);
+        context.check_total_len(bytes.len())?;
+
+        let (_, rest) = mls_rs_codec::iter::mls_decode_split_on_collection(&mut &*bytes, &context)?;
+
+        let encoded = &bytes[..bytes.len() - rest.len()];
+
+        Ok(Self(TreeData::Encoded(Cow::Borrowed(encoded), limits)))
+    }
+
+    pub(crate) fn nodes(&self) -> Result<Cow<'_, NodeVec>, MlsError> {
+        match &self.0 {
+            TreeData::Nodes(nodes) => Ok(Cow::Borrowed(nodes)),
+            TreeData::Encoded(bytes, limits) => decode_nodes(bytes, *limits).map(Cow::Owned),
+        }
+    }
+
+    pub(crate) fn into_nodes(self) -> Result<NodeVec, MlsError> {
+        match self.0 {
+            TreeData::Nodes(nodes) => Ok(nodes.into_owned()),
+            TreeData::Encoded(bytes, limits) => decode_nodes(&bytes, limits),
+        }
     }
 }
 
 #[cfg_attr(all(feature = "ffi", not(test)), ::safer_ffi_gen::safer_ffi_gen)]
 impl ExportedTree<'static> {
+    /// Deserialize from `bytes`, failing if they exceed the default [`DecodeLimits`].
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, MlsError> {
-        Self::mls_decode(&mut &*bytes).map_err(Into::into)
+        Self::from_bytes_with_limits(bytes, DecodeLimits::default())
+    }
+
+    /// Deserialize from `bytes`, failing if they exceed `limits`.
+    #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
+    pub fn from_bytes_with_limits(bytes: &[u8], limits: DecodeLimits) -> Result<Self, MlsError> {
+        Self::mls_decode_with_limits(&mut &*bytes, limits).map_err(Into::into)
+    }
+}
+
+fn decode_nodes(bytes: &[u8], limits: DecodeLimits) -> Result<NodeVec, MlsError> {
+    NodeVec::mls_decode_with_limits(&mut &*bytes, limits).map_err(Into::into)
+}
+
+impl PartialEq for ExportedTree<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (TreeData::Encoded(a, _), TreeData::Encoded(b, _)) => a == b,
+            _ => matches!((self.nodes(), other.nodes()), (Ok(a), Ok(b)) if a == b),
+        }
+    }
+}
+
+impl MlsSize for ExportedTree<'_> {
+    fn mls_encoded_len(&self) -> usize {
+        match &self.0 {
+            TreeData::Nodes(nodes) => nodes.mls_encoded_len(),
+            TreeData::Encoded(bytes, _) => bytes.len(),
+        }
+    }
+}
+
+impl MlsEncode for ExportedTree<'_> {
+    fn mls_encode<W: Writer + ?Sized>(&self, writer: &mut W) -> Result<(), mls_rs_codec::Error> {
+        match &self.0 {
+            TreeData::Nodes(nodes) => nodes.mls_encode(writer),
+            TreeData::Encoded(bytes, _) => writer.write(bytes),
+        }
     }
 }
 
-impl From<ExportedTree<'_>> for NodeVec {
-    fn from(value: ExportedTree) -> Self {
-        value.0.into_owned()
+impl MlsDecode for ExportedTree<'_> {
+    fn mls_decode_with_context(
+        reader: &mut &[u8],
+        context: &mut DecodeContext,
+    ) -> Result<Self, mls_rs_codec::Error> {
+        NodeVec::mls_decode_with_context(reader, context).map(Self::new)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ExportedTree<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let nodes = self.nodes().map_err(serde::ser::Error::custom)?;
+        serializer.serialize_newtype_struct("ExportedTree", &*nodes)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ExportedTree<'_> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "ExportedTree")]
+        struct Repr(NodeVec);
+
+        Repr::deserialize(deserializer).map(|Repr(nodes)| Self::new(nodes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::{
+        client::test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+        group::test_utils::test_group,
+    };
+
+    use super::*;
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn borrowed_tree_matches_owned_tree() {
+        let group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let bytes = group.group.export_tree().to_bytes().unwrap();
+
+        let borrowed = ExportedTree::from_bytes_borrowed(&bytes).unwrap();
+        let owned = ExportedTree::from_bytes(&bytes).unwrap();
+
+        assert_eq!(borrowed, owned);
+        assert_eq!(borrowed.to_bytes().unwrap(), bytes);
+        assert_eq!(borrowed.byte_size(), bytes.len());
+        assert_eq!(borrowed.into_nodes().unwrap(), owned.into_nodes().unwrap());
+    }
+
+    #[test]
+    fn borrowed_tree_checks_length() {
+        let bytes = ExportedTree::new(NodeVec::from(vec![None]))
+            .to_bytes()
+            .unwrap();
+
+        assert!(ExportedTree::from_bytes_borrowed(&bytes[..bytes.len() - 1]).is_err());
+
+        let padded = [bytes.as_slice(), &[0]].concat();
+        let tree = ExportedTree::from_bytes_borrowed(&padded).unwrap();
+
+        assert_eq!(tree.to_bytes().unwrap(), bytes);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn borrowed_tree_decodes_nodes_within_limits() {
+        let group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let bytes = group.group.export_tree().to_bytes().unwrap();
+        let limits = DecodeLimits::default().with_max_depth(1);
+
+        let tree = ExportedTree::from_bytes_borrowed_with_limits(&bytes, limits).unwrap();
+
+        assert!(tree.nodes().is_err());
+        assert!(ExportedTree::from_bytes_with_limits(&bytes, limits).is_err());
+        assert!(ExportedTree::from_bytes_borrowed(&bytes)
+            .unwrap()
+            .nodes()
+            .is_ok());
     }
 }
@@ -2,7 +2,7 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use mls_rs_core::{crypto::SignatureSecretKey, identity::SigningIdentity};
+use mls_rs_core::{crypto::SignatureSecretKey, identity::SigningIdentity, time::TimeProvider};
 
 use crate::{
     client_config::ClientConfig,
@@ -36,7 +36,8 @@ use mls_rs_core::psk::{ExternalPskId, PreSharedKey};
 
 #[cfg(feature = "psk")]
 use crate::group::{
-    PreSharedKeyProposal, {JustPreSharedKeyID, PreSharedKeyID},
+    PreSharedKeyProposal, PskGroupId, ResumptionPSKUsage, ResumptionPsk,
+    {JustPreSharedKeyID, PreSharedKeyID},
 };
 
 use super::{validate_group_info_joiner, ExportedTree};
@@ -51,6 +52,8 @@ pub struct ExternalCommitBuilder<C: ClientConfig> {
     to_remove: Option<u32>,
     #[cfg(feature = "psk")]
     external_psks: Vec<ExternalPskId>,
+    #[cfg(feature = "psk")]
+    resumption_psk_epochs: Vec<u64>,
     authenticated_data: Vec<u8>,
     #[cfg(feature = "custom_proposal")]
     custom_proposals: Vec<Proposal>,
@@ -73,6 +76,8 @@ impl<C: ClientConfig> ExternalCommitBuilder<C> {
             config,
             #[cfg(feature = "psk")]
             external_psks: Vec::new(),
+            #[cfg(feature = "psk")]
+            resumption_psk_epochs: Vec::new(),
             #[cfg(feature = "custom_proposal")]
             custom_proposals: Vec::new(),
             #[cfg(feature = "custom_proposal")]
@@ -83,6 +88,9 @@ impl<C: ClientConfig> ExternalCommitBuilder<C> {
     #[must_use]
     /// Use external tree data if the GroupInfo message does not contain a
     /// [`RatchetTreeExt`](crate::extension::built_in::RatchetTreeExt)
+    ///
+    /// Without tree data, the tree is fetched from the
+    /// [`ClientBuilder::ratchet_tree_provider`](crate::client_builder::ClientBuilder::ratchet_tree_provider).
     pub fn with_tree_data(self, tree_data: ExportedTree<'static>) -> Self {
         Self {
             tree_data: Some(tree_data),
@@ -117,6 +125,20 @@ impl<C: ClientConfig> ExternalCommitBuilder<C> {
         self
     }
 
+    #[cfg(feature = "psk")]
+    #[must_use]
+    /// Add a resumption psk from a previous epoch of the group as part of the external commit.
+    ///
+    /// The secret of `psk_epoch` is read from the prior epochs kept in the
+    /// [GroupStateStorage](crate::GroupStateStorage) of the client, which is
+    /// only possible if the client was a member at that epoch and stored the
+    /// group up to the epoch of the GroupInfo, e.g. before diverging from the
+    /// other members. The current epoch of the group can not be used.
+    pub fn with_resumption_psk(mut self, psk_epoch: u64) -> Self {
+        self.resumption_psk_epochs.push(psk_epoch);
+        self
+    }
+
     #[cfg(feature = "custom_proposal")]
     #[must_use]
     /// Insert a [`CustomProposal`] into the current commit that is being built.
@@ -168,7 +190,10 @@ impl<C: ClientConfig> ExternalCommitBuilder<C> {
             &group_info,
             self.tree_data,
             &self.config.identity_provider(),
+            &self.config.ratchet_tree_provider(),
+            self.config.decode_limits(),
             &cipher_suite,
+            self.config.time_provider().now(),
         )
         .await?;
 
@@ -204,11 +229,29 @@ impl<C: ClientConfig> ExternalCommitBuilder<C> {
         )
         .await?;
 
+        // The resumption secret of the current epoch is not known to a new member.
+        #[cfg(feature = "psk")]
+        if let Some(psk_epoch) = self
+            .resumption_psk_epochs
+            .iter()
+            .find(|psk_epoch| **psk_epoch >= group.current_epoch())
+        {
+            return Err(MlsError::InvalidResumptionPskEpoch(*psk_epoch));
+        }
+
         #[cfg(feature = "psk")]
         let psk_ids = self
             .external_psks
             .into_iter()
-            .map(|psk_id| PreSharedKeyID::new(JustPreSharedKeyID::External(psk_id), &cipher_suite))
+            .map(JustPreSharedKeyID::External)
+            .chain(self.resumption_psk_epochs.into_iter().map(|psk_epoch| {
+                JustPreSharedKeyID::Resumption(ResumptionPsk {
+                    psk_epoch,
+                    usage: ResumptionPSKUsage::Application,
+                    psk_group_id: PskGroupId(group.group_id().to_vec()),
+                })
+            }))
+            .map(|psk_id| PreSharedKeyID::new(psk_id, &cipher_suite))
             .collect::<Result<Vec<_>, MlsError>>()?;
 
         let mut proposals = vec![Proposal::ExternalInit(ExternalInit { kem_output })];
@@ -256,6 +299,7 @@ impl<C: ClientConfig> ExternalCommitBuilder<C> {
                 Default::default(),
                 None,
                 None,
+                false,
             )
             .await?;
 
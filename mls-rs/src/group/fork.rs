@@ -0,0 +1,304 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+use mls_rs_core::extension::ExtensionType;
+
+use crate::{client::MlsError, client_config::ClientConfig, Client, MlsMessage};
+
+use super::{ExportedTree, Group};
+
+/// Summary of the state of a group at a given epoch.
+///
+/// Members that processed the same commits have equal fingerprints.
+/// Fingerprints can be exchanged out of band, e.g. through the delivery
+/// service, to detect members that applied a different commit for the same
+/// epoch.
+#[derive(Clone, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
+pub struct ForkFingerprint {
+    epoch: u64,
+    #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    epoch_authenticator: Vec<u8>,
+    #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    tree_hash: Vec<u8>,
+    #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    confirmed_transcript_hash: Vec<u8>,
+}
+
+impl Debug for ForkFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ForkFingerprint")
+            .field("epoch", &self.epoch)
+            .field(
+                "epoch_authenticator",
+                &mls_rs_core::debug::pretty_bytes(&self.epoch_authenticator),
+            )
+            .field(
+                "tree_hash",
+                &mls_rs_core::debug::pretty_bytes(&self.tree_hash),
+            )
+            .field(
+                "confirmed_transcript_hash",
+                &mls_rs_core::debug::pretty_bytes(&self.confirmed_transcript_hash),
+            )
+            .finish()
+    }
+}
+
+impl ForkFingerprint {
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn epoch_authenticator(&self) -> &[u8] {
+        &self.epoch_authenticator
+    }
+
+    pub fn tree_hash(&self) -> &[u8] {
+        &self.tree_hash
+    }
+
+    pub fn confirmed_transcript_hash(&self) -> &[u8] {
+        &self.confirmed_transcript_hash
+    }
+
+    /// Returns true if `other` describes a different state of the group at
+    /// the same epoch.
+    ///
+    /// Fingerprints of different epochs cannot be compared and never
+    /// diverge.
+    pub fn diverges_from(&self, other: &ForkFingerprint) -> bool {
+        self.epoch == other.epoch && self != other
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, MlsError> {
+        self.mls_encode_to_vec().map_err(Into::into)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MlsError> {
+        Self::mls_decode(&mut &*bytes).map_err(Into::into)
+    }
+}
+
+impl<C> Group<C>
+where
+    C: ClientConfig + Clone,
+{
+    /// Fingerprint of the current epoch, to compare with the fingerprints of
+    /// other members.
+    pub fn fork_fingerprint(&self) -> Result<ForkFingerprint, MlsError> {
+        let context = self.context();
+
+        Ok(ForkFingerprint {
+            epoch: context.epoch,
+            epoch_authenticator: self.epoch_authenticator()?.as_bytes().to_vec(),
+            tree_hash: context.tree_hash.clone(),
+            confirmed_transcript_hash: context.confirmed_transcript_hash.to_vec(),
+        })
+    }
+}
+
+impl<C> Client<C>
+where
+    C: ClientConfig + Clone,
+{
+    /// Rejoin the group with ID `group_id` after the local state diverged
+    /// from the state of the other members, for instance because this client
+    /// applied a commit that lost a race against another commit for the same
+    /// epoch.
+    ///
+    /// `group_info` must be a group info message allowing external commits,
+    /// created by a member on the agreed upon branch. The client rejoins as
+    /// with [`Client::rejoin_group`], removing its stale leaf found by
+    /// identity in `tree_data` or in the ratchet tree of `group_info`. If
+    /// neither is available, the stale leaf is the one at the leaf index of
+    /// the group stored by this client and the tree is fetched from the
+    /// [`RatchetTreeProvider`](crate::group::RatchetTreeProvider) of the
+    /// client.
+    ///
+    /// The group context extensions, including custom ones, are those of the
+    /// agreed upon branch, and the new leaf uses the capabilities and leaf
+    /// node extensions of this client, as the stale leaf did. External PSKs
+    /// stay available from the PSK store of this client, but resumption PSKs
+    /// of epochs of the diverged branch are lost.
+    ///
+    /// As with [`Client::commit_external`], the resulting commit message
+    /// must be sent to the other members and the returned group replaces the
+    /// diverged one, including in storage once written.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn recover_from_fork(
+        &self,
+        group_id: &[u8],
+        group_info: MlsMessage,
+        tree_data: Option<ExportedTree<'static>>,
+    ) -> Result<(Group<C>, MlsMessage), MlsError> {
+        let info = group_info
+            .as_group_info()
+            .ok_or(MlsError::UnexpectedMessageType)?;
+
+        if info.group_context.group_id != group_id {
+            return Err(MlsError::GroupIdMismatch);
+        }
+
+        let builder =
+            if tree_data.is_some() || info.extensions.has_extension(ExtensionType::RATCHET_TREE) {
+                self.rejoin_group_builder(&group_info, tree_data).await?
+            } else {
+                let stale_leaf = self.load_group(group_id).await?.current_member_index();
+                self.external_commit_builder()?.with_removal(stale_leaf)
+            };
+
+        builder.build(group_info).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+    use assert_matches::assert_matches;
+
+    use crate::{
+        client::{
+            test_utils::{TestClientBuilder, TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+            MlsError,
+        },
+        group::test_utils::{test_n_member_group, TestGroup},
+        Client,
+    };
+
+    use super::ForkFingerprint;
+
+    /// Create a group of alice, bob and carol in which bob applied his own
+    /// commit while alice and carol applied a commit from alice.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn forked_groups() -> Vec<TestGroup> {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+
+        let alice_commit = groups[0].group.commit(vec![]).await.unwrap();
+        groups[1].group.commit(vec![]).await.unwrap();
+
+        groups[0].process_pending_commit().await.unwrap();
+        groups[1].process_pending_commit().await.unwrap();
+
+        groups[2]
+            .process_message(alice_commit.commit_message)
+            .await
+            .unwrap();
+
+        groups
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn client(name: &str) -> Client<crate::client::test_utils::TestClientConfig> {
+        TestClientBuilder::new_for_test()
+            .with_random_signing_identity(name, TEST_CIPHER_SUITE)
+            .await
+            .build()
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn fingerprints_detect_forks() {
+        let groups = forked_groups().await;
+
+        let fingerprints = groups
+            .iter()
+            .map(|group| group.group.fork_fingerprint().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(fingerprints[0], fingerprints[2]);
+        assert!(!fingerprints[0].diverges_from(&fingerprints[2]));
+        assert!(fingerprints[1].diverges_from(&fingerprints[0]));
+        assert_eq!(fingerprints[1].epoch(), fingerprints[0].epoch());
+
+        let bytes = fingerprints[1].to_bytes().unwrap();
+        assert_eq!(
+            ForkFingerprint::from_bytes(&bytes).unwrap(),
+            fingerprints[1]
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn forked_member_recovers_with_external_commit() {
+        let mut groups = forked_groups().await;
+
+        let group_info = groups[0]
+            .group
+            .group_info_message_allowing_ext_commit(true)
+            .await
+            .unwrap();
+
+        let group_id = groups[1].group.group_id().to_vec();
+
+        // The name matches the identity used by `test_n_member_group`.
+        let (recovered, commit) = client("name 1")
+            .await
+            .recover_from_fork(&group_id, group_info, None)
+            .await
+            .unwrap();
+
+        for index in [0, 2] {
+            groups[index].process_message(commit.clone()).await.unwrap();
+        }
+
+        assert_eq!(recovered.roster().members_iter().count(), 3);
+
+        assert_eq!(
+            recovered.fork_fingerprint().unwrap(),
+            groups[0].group.fork_fingerprint().unwrap()
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn forked_member_recovers_with_tree_provided_out_of_band() {
+        let mut groups = forked_groups().await;
+
+        let group_info = groups[0]
+            .group
+            .group_info_message_allowing_ext_commit(false)
+            .await
+            .unwrap();
+
+        let tree = groups[0].group.export_tree().into_owned();
+        let group_id = groups[1].group.group_id().to_vec();
+
+        // The recovering client has no stored group, the stale leaf is found in the tree.
+        let (recovered, commit) = client("name 1")
+            .await
+            .recover_from_fork(&group_id, group_info, Some(tree))
+            .await
+            .unwrap();
+
+        for index in [0, 2] {
+            groups[index].process_message(commit.clone()).await.unwrap();
+        }
+
+        assert_eq!(recovered.roster().members_iter().count(), 3);
+
+        assert_eq!(
+            recovered.fork_fingerprint().unwrap(),
+            groups[0].group.fork_fingerprint().unwrap()
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn recovering_requires_matching_group_id() {
+        let groups = forked_groups().await;
+
+        let group_info = groups[0]
+            .group
+            .group_info_message_allowing_ext_commit(true)
+            .await
+            .unwrap();
+
+        let res = client("name 1")
+            .await
+            .recover_from_fork(b"other group", group_info, None)
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::GroupIdMismatch));
+    }
+}
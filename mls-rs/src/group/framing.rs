@@ -13,7 +13,7 @@ use crate::{group::Proposal, mls_rules::ProposalRef};
 
 use alloc::vec::Vec;
 use core::fmt::{self, Debug};
-use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+use mls_rs_codec::{DecodeContext, DecodeLimits, MlsDecode, MlsEncode, MlsSize};
 use mls_rs_core::{
     crypto::{CipherSuite, CipherSuiteProvider},
     protocol_version::ProtocolVersion,
@@ -23,11 +23,15 @@ use zeroize::ZeroizeOnDrop;
 #[cfg(feature = "private_message")]
 use alloc::boxed::Box;
 
+#[cfg(feature = "private_message")]
+use mls_rs_codec::MlsDecodeBorrowed;
+
 #[cfg(feature = "custom_proposal")]
 use crate::group::proposal::{CustomProposal, ProposalOrRef};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum ContentType {
     #[cfg(feature = "private_message")]
@@ -145,6 +149,7 @@ impl Content {
 
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct PublicMessage {
     pub content: FramedContent,
     pub auth: FramedContentAuthData,
@@ -163,7 +168,10 @@ impl MlsSize for PublicMessage {
 }
 
 impl MlsEncode for PublicMessage {
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), mls_rs_codec::Error> {
+    fn mls_encode<W: mls_rs_codec::Writer + ?Sized>(
+        &self,
+        writer: &mut W,
+    ) -> Result<(), mls_rs_codec::Error> {
         self.content.mls_encode(writer)?;
         self.auth.mls_encode(writer)?;
 
@@ -174,12 +182,15 @@ impl MlsEncode for PublicMessage {
 }
 
 impl MlsDecode for PublicMessage {
-    fn mls_decode(reader: &mut &[u8]) -> Result<Self, mls_rs_codec::Error> {
-        let content = FramedContent::mls_decode(reader)?;
-        let auth = FramedContentAuthData::mls_decode(reader, content.content_type())?;
+    fn mls_decode_with_context(
+        reader: &mut &[u8],
+        context: &mut DecodeContext,
+    ) -> Result<Self, mls_rs_codec::Error> {
+        let content = FramedContent::mls_decode_with_context(reader, context)?;
+        let auth = FramedContentAuthData::mls_decode(reader, content.content_type(), context)?;
 
         let membership_tag = match content.sender {
-            Sender::Member(_) => Some(MembershipTag::mls_decode(reader)?),
+            Sender::Member(_) => Some(MembershipTag::mls_decode_with_context(reader, context)?),
             _ => None,
         };
 
@@ -214,7 +225,10 @@ impl MlsSize for PrivateMessageContent {
 
 #[cfg(feature = "private_message")]
 impl MlsEncode for PrivateMessageContent {
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), mls_rs_codec::Error> {
+    fn mls_encode<W: mls_rs_codec::Writer + ?Sized>(
+        &self,
+        writer: &mut W,
+    ) -> Result<(), mls_rs_codec::Error> {
         match &self.content {
             Content::Application(c) => c.mls_encode(writer),
             #[cfg(feature = "by_ref_proposal")]
@@ -230,20 +244,29 @@ impl MlsEncode for PrivateMessageContent {
 
 #[cfg(feature = "private_message")]
 impl PrivateMessageContent {
+    /// Decode decrypted content, failing if it exceeds `limits`.
     pub(crate) fn mls_decode(
         reader: &mut &[u8],
         content_type: ContentType,
+        limits: DecodeLimits,
     ) -> Result<Self, mls_rs_codec::Error> {
+        let context = &mut DecodeContext::new(limits);
+        context.check_total_len(reader.len())?;
+
         let content = match content_type {
-            ContentType::Application => Content::Application(ApplicationData::mls_decode(reader)?),
-            #[cfg(feature = "by_ref_proposal")]
-            ContentType::Proposal => Content::Proposal(Box::new(Proposal::mls_decode(reader)?)),
-            ContentType::Commit => {
-                Content::Commit(alloc::boxed::Box::new(Commit::mls_decode(reader)?))
+            ContentType::Application => {
+                Content::Application(ApplicationData::mls_decode_with_context(reader, context)?)
             }
+            #[cfg(feature = "by_ref_proposal")]
+            ContentType::Proposal => Content::Proposal(Box::new(
+                Proposal::mls_decode_with_context(reader, context)?,
+            )),
+            ContentType::Commit => Content::Commit(alloc::boxed::Box::new(
+                Commit::mls_decode_with_context(reader, context)?,
+            )),
         };
 
-        let auth = FramedContentAuthData::mls_decode(reader, content.content_type())?;
+        let auth = FramedContentAuthData::mls_decode(reader, content.content_type(), context)?;
 
         if reader.iter().any(|&i| i != 0u8) {
             // #[cfg(feature = "std")]
@@ -291,16 +314,21 @@ impl Debug for PrivateContentAAD {
 #[cfg(feature = "private_message")]
 #[derive(Clone, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PrivateMessage {
     #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    #[cfg_attr(feature = "serde", serde(with = "mls_rs_core::vec_serde"))]
     pub group_id: Vec<u8>,
     pub epoch: u64,
     pub content_type: ContentType,
     #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    #[cfg_attr(feature = "serde", serde(with = "mls_rs_core::vec_serde"))]
     pub authenticated_data: Vec<u8>,
     #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    #[cfg_attr(feature = "serde", serde(with = "mls_rs_core::vec_serde"))]
     pub encrypted_sender_data: Vec<u8>,
     #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    #[cfg_attr(feature = "serde", serde(with = "mls_rs_core::vec_serde"))]
     pub ciphertext: Vec<u8>,
 }
 
@@ -330,14 +358,41 @@ impl Debug for PrivateMessage {
     }
 }
 
+/// View of a [`PrivateMessage`] that borrows its byte fields, allowing to
+/// decode and decrypt a message without copying the ciphertext.
+#[cfg(feature = "private_message")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, MlsSize, MlsEncode, MlsDecodeBorrowed)]
+pub(crate) struct PrivateMessageRef<'a> {
+    pub group_id: &'a [u8],
+    pub epoch: u64,
+    pub content_type: ContentType,
+    pub authenticated_data: &'a [u8],
+    pub encrypted_sender_data: &'a [u8],
+    pub ciphertext: &'a [u8],
+}
+
 #[cfg(feature = "private_message")]
-impl From<&PrivateMessage> for PrivateContentAAD {
-    fn from(ciphertext: &PrivateMessage) -> Self {
+impl<'a> From<&'a PrivateMessage> for PrivateMessageRef<'a> {
+    fn from(message: &'a PrivateMessage) -> Self {
         Self {
-            group_id: ciphertext.group_id.clone(),
+            group_id: &message.group_id,
+            epoch: message.epoch,
+            content_type: message.content_type,
+            authenticated_data: &message.authenticated_data,
+            encrypted_sender_data: &message.encrypted_sender_data,
+            ciphertext: &message.ciphertext,
+        }
+    }
+}
+
+#[cfg(feature = "private_message")]
+impl From<PrivateMessageRef<'_>> for PrivateContentAAD {
+    fn from(ciphertext: PrivateMessageRef<'_>) -> Self {
+        Self {
+            group_id: ciphertext.group_id.to_vec(),
             epoch: ciphertext.epoch,
             content_type: ciphertext.content_type,
-            authenticated_data: ciphertext.authenticated_data.clone(),
+            authenticated_data: ciphertext.authenticated_data.to_vec(),
         }
     }
 }
@@ -348,6 +403,7 @@ impl From<&PrivateMessage> for PrivateContentAAD {
     ::safer_ffi_gen::ffi_type(clone, opaque)
 )]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A MLS protocol message for sending data over the wire.
 pub struct MlsMessage {
     pub(crate) version: ProtocolVersion,
@@ -457,10 +513,17 @@ impl MlsMessage {
         }
     }
 
-    /// Deserialize a message from transport.
+    /// Deserialize a message from transport, failing if it exceeds the
+    /// default [`DecodeLimits`].
     #[inline(never)]
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, MlsError> {
-        Self::mls_decode(&mut &*bytes).map_err(Into::into)
+        Self::from_bytes_with_limits(bytes, DecodeLimits::default())
+    }
+
+    /// Deserialize a message from transport, failing if it exceeds `limits`.
+    #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
+    pub fn from_bytes_with_limits(bytes: &[u8], limits: DecodeLimits) -> Result<Self, MlsError> {
+        Self::mls_decode_with_limits(&mut &*bytes, limits).map_err(Into::into)
     }
 
     /// Serialize a message for transport.
@@ -542,6 +605,7 @@ impl MlsMessage {
 #[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug, PartialEq, MlsSize, MlsEncode, MlsDecode)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u16)]
 pub(crate) enum MlsMessagePayload {
     Plain(PublicMessage) = 1u16,
@@ -688,9 +752,12 @@ mod tests {
         let mut encoded = ciphertext_content.mls_encode_to_vec().unwrap();
         encoded.extend_from_slice(&[0u8; 128]);
 
-        let decoded =
-            PrivateMessageContent::mls_decode(&mut &*encoded, (&ciphertext_content.content).into())
-                .unwrap();
+        let decoded = PrivateMessageContent::mls_decode(
+            &mut &*encoded,
+            (&ciphertext_content.content).into(),
+            DecodeLimits::default(),
+        )
+        .unwrap();
 
         assert_eq!(ciphertext_content, decoded);
     }
@@ -702,12 +769,29 @@ mod tests {
         let mut encoded = ciphertext_content.mls_encode_to_vec().unwrap();
         encoded.extend_from_slice(&[1u8; 128]);
 
-        let decoded =
-            PrivateMessageContent::mls_decode(&mut &*encoded, (&ciphertext_content.content).into());
+        let decoded = PrivateMessageContent::mls_decode(
+            &mut &*encoded,
+            (&ciphertext_content.content).into(),
+            DecodeLimits::default(),
+        );
 
         assert_matches!(decoded, Err(mls_rs_codec::Error::Custom(_)));
     }
 
+    #[test]
+    fn test_mls_ciphertext_content_exceeding_limits_error() {
+        let ciphertext_content = get_test_ciphertext_content();
+        let encoded = ciphertext_content.mls_encode_to_vec().unwrap();
+
+        let decoded = PrivateMessageContent::mls_decode(
+            &mut &*encoded,
+            (&ciphertext_content.content).into(),
+            DecodeLimits::default().with_max_total_bytes(encoded.len() - 1),
+        );
+
+        assert_matches!(decoded, Err(mls_rs_codec::Error::TotalSizeExceeded(_)));
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn proposal_ref() {
         let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
@@ -738,4 +822,87 @@ mod tests {
 
         assert_eq!(computed_ref, expected_ref.to_vec());
     }
+
+    #[cfg(feature = "serde")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn wire_types_round_trip_through_json() {
+        use crate::client::test_utils::test_client_with_key_pkg;
+        use crate::group::{test_utils::test_group, CommitOutput, ExportedTree};
+
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let (_, key_package) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let CommitOutput {
+            commit_message,
+            welcome_messages,
+            ..
+        } = alice
+            .group
+            .commit_builder()
+            .add_member(key_package.clone())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice.group.apply_pending_commit().await.unwrap();
+
+        let mut messages = vec![key_package, commit_message, welcome_messages[0].clone()];
+        messages.push(alice.group.group_info_message(true).await.unwrap());
+
+        #[cfg(feature = "private_message")]
+        messages.push(
+            alice
+                .group
+                .encrypt_application_message(b"hello", vec![])
+                .await
+                .unwrap(),
+        );
+
+        for message in messages {
+            let json = serde_json::to_string(&message).unwrap();
+            assert_eq!(serde_json::from_str::<MlsMessage>(&json).unwrap(), message);
+        }
+
+        let tree = alice.group.export_tree().into_owned();
+        let json = serde_json::to_string(&tree).unwrap();
+        assert_eq!(serde_json::from_str::<ExportedTree>(&json).unwrap(), tree);
+    }
+
+    #[cfg(all(feature = "serde", feature = "private_message"))]
+    #[test]
+    fn json_uses_readable_names_and_hex() {
+        let message = MlsMessage::new(
+            TEST_PROTOCOL_VERSION,
+            MlsMessagePayload::Cipher(PrivateMessage {
+                group_id: b"group".to_vec(),
+                epoch: 1,
+                content_type: ContentType::Application,
+                authenticated_data: vec![],
+                encrypted_sender_data: vec![0xab],
+                ciphertext: vec![0xcd, 0xef],
+            }),
+        );
+
+        let json = serde_json::to_value(&message).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "version": "mls10",
+                "payload": {
+                    "Cipher": {
+                        "group_id": "67726f7570",
+                        "epoch": 1,
+                        "content_type": "Application",
+                        "authenticated_data": "",
+                        "encrypted_sender_data": "ab",
+                        "ciphertext": "cdef",
+                    }
+                }
+            })
+        );
+    }
 }
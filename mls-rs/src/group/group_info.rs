@@ -13,16 +13,19 @@ use super::{ConfirmationTag, GroupContext};
 
 #[derive(Clone, PartialEq, MlsSize, MlsEncode, MlsDecode)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(
     all(feature = "ffi", not(test)),
     safer_ffi_gen::ffi_type(clone, opaque)
 )]
 pub struct GroupInfo {
     pub(crate) group_context: GroupContext,
+    #[cfg_attr(feature = "serde", serde(with = "crate::extension::list_serde"))]
     pub(crate) extensions: ExtensionList,
     pub(crate) confirmation_tag: ConfirmationTag,
     pub(crate) signer: LeafIndex,
     #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    #[cfg_attr(feature = "serde", serde(with = "mls_rs_core::vec_serde"))]
     pub(crate) signature: Vec<u8>,
 }
 
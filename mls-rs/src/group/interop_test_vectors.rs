@@ -2,8 +2,10 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-mod framing;
-mod passive_client;
-mod serialization;
-mod tree_kem;
+pub(crate) mod framing;
+pub(crate) mod passive_client;
+pub(crate) mod serialization;
+pub(crate) mod tree_kem;
+#[cfg(test)]
 mod tree_modifications;
+pub(crate) mod welcome;
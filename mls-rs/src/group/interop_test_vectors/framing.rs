@@ -5,32 +5,42 @@
 use alloc::vec;
 use alloc::vec::Vec;
 use mls_rs_codec::{MlsDecode, MlsEncode};
-use mls_rs_core::crypto::{CipherSuite, CipherSuiteProvider, SignaturePublicKey};
+use mls_rs_core::{
+    crypto::{CipherSuiteProvider, CryptoProvider, SignaturePublicKey},
+    protocol_version::ProtocolVersion,
+};
 
 use crate::{
-    client::test_utils::{TestClientConfig, TEST_PROTOCOL_VERSION},
-    crypto::test_utils::{test_cipher_suite_provider, try_test_cipher_suite_provider},
+    client_builder::MlsConfig,
+    client_config::ClientConfig,
     group::{
         confirmation_tag::ConfirmationTag,
         epoch::EpochSecrets,
         framing::{Content, WireFormat},
         message_processor::{EventOrContent, MessageProcessor},
-        mls_rules::EncryptionOptions,
-        padding::PaddingMode,
-        proposal::{Proposal, RemoveProposal},
+        proposal::Proposal,
         secret_tree::test_utils::get_test_tree,
-        test_utils::{random_bytes, test_group_custom_config},
         AuthenticatedContent, Commit, Group, GroupContext, MlsMessage, Sender,
     },
-    mls_rules::DefaultMlsRules,
-    test_utils::is_edwards,
-    tree_kem::{leaf_node::test_utils::get_basic_test_node, node::LeafIndex},
+    test_utils::{generate_basic_client, is_edwards},
+    tree_kem::node::LeafIndex,
+};
+
+#[cfg(test)]
+use mls_rs_core::crypto::CipherSuite;
+
+#[cfg(test)]
+use crate::{
+    crypto::test_utils::{
+        test_cipher_suite_provider, try_test_cipher_suite_provider, TestCryptoProvider,
+    },
+    group::{proposal::RemoveProposal, test_utils::random_bytes},
 };
 
 const FRAMING_N_LEAVES: u32 = 2;
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
-struct FramingTestCase {
+pub struct FramingTestCase {
     #[serde(flatten)]
     pub context: InteropGroupContext,
 
@@ -67,6 +77,7 @@ struct FramingTestCase {
 }
 
 impl FramingTestCase {
+    #[cfg(test)]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     #[cfg_attr(coverage_nightly, coverage(off))]
     async fn random<P: CipherSuiteProvider>(cs: &P) -> Self {
@@ -104,6 +115,7 @@ pub struct InteropGroupContext {
 }
 
 impl InteropGroupContext {
+    #[cfg(test)]
     #[cfg_attr(coverage_nightly, coverage(off))]
     fn random<P: CipherSuiteProvider>(cs: &P) -> Self {
         Self {
@@ -121,7 +133,7 @@ impl From<InteropGroupContext> for GroupContext {
     fn from(ctx: InteropGroupContext) -> Self {
         Self {
             cipher_suite: ctx.cipher_suite.into(),
-            protocol_version: TEST_PROTOCOL_VERSION,
+            protocol_version: ProtocolVersion::MLS_10,
             group_id: ctx.group_id,
             epoch: ctx.epoch,
             tree_hash: ctx.tree_hash,
@@ -131,27 +143,22 @@ impl From<InteropGroupContext> for GroupContext {
     }
 }
 
-// The test vector can be found here:
-// https://github.com/mlswg/mls-implementations/blob/main/test-vectors/message-protection.json
-#[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-async fn framing_proposal() {
-    #[cfg(not(mls_build_async))]
-    let test_cases: Vec<FramingTestCase> =
-        load_test_case_json!(framing, generate_framing_test_vector());
+impl FramingTestCase {
+    #[cfg(feature = "conformance_util")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn verify<C: CryptoProvider + Clone>(&self, crypto: &C) {
+        self.verify_proposal(crypto).await;
 
-    #[cfg(mls_build_async)]
-    let test_cases: Vec<FramingTestCase> =
-        load_test_case_json!(framing, generate_framing_test_vector().await);
+        // Wasm uses incompatible signature secret key format
+        #[cfg(not(target_arch = "wasm32"))]
+        self.verify_application(crypto).await;
 
-    for test_case in test_cases.into_iter() {
-        let Some(cs) = try_test_cipher_suite_provider(test_case.context.cipher_suite) else {
-            continue;
-        };
+        self.verify_commit(crypto).await;
+    }
 
-        let to_check = vec![
-            test_case.proposal_priv.clone(),
-            test_case.proposal_pub.clone(),
-        ];
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn verify_proposal<C: CryptoProvider + Clone>(&self, crypto: &C) {
+        let to_check = vec![self.proposal_priv.clone(), self.proposal_pub.clone()];
 
         // Wasm uses incompatible signature secret key format
         #[cfg(not(target_arch = "wasm32"))]
@@ -159,9 +166,9 @@ async fn framing_proposal() {
 
         #[cfg(not(target_arch = "wasm32"))]
         for enable_encryption in [true, false] {
-            let proposal = Proposal::mls_decode(&mut &*test_case.proposal).unwrap();
+            let proposal = Proposal::mls_decode(&mut &*self.proposal).unwrap();
 
-            let built = make_group(&test_case, true, enable_encryption, &cs)
+            let built = make_group(self, true, enable_encryption, crypto)
                 .await
                 .proposal_message(proposal, vec![])
                 .await
@@ -172,88 +179,59 @@ async fn framing_proposal() {
             to_check.push(built);
         }
 
-        let proposal = Proposal::mls_decode(&mut &*test_case.proposal).unwrap();
+        let proposal = Proposal::mls_decode(&mut &*self.proposal).unwrap();
 
         for message in to_check {
-            match process_message(&test_case, &message, &cs).await {
+            match process_message(self, &message, crypto).await {
                 Content::Proposal(p) => assert_eq!(p.as_ref(), &proposal),
                 _ => panic!("received value not proposal"),
             };
         }
     }
-}
-
-// The test vector can be found here:
-// https://github.com/mlswg/mls-implementations/blob/main/test-vectors/message-protection.json
-// Wasm uses incompatible signature secret key format
-#[cfg(not(target_arch = "wasm32"))]
-#[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-async fn framing_application() {
-    #[cfg(not(mls_build_async))]
-    let test_cases: Vec<FramingTestCase> =
-        load_test_case_json!(framing, generate_framing_test_vector());
 
-    #[cfg(mls_build_async)]
-    let test_cases: Vec<FramingTestCase> =
-        load_test_case_json!(framing, generate_framing_test_vector().await);
-
-    for test_case in test_cases.into_iter() {
-        let Some(cs) = try_test_cipher_suite_provider(test_case.context.cipher_suite) else {
-            continue;
-        };
-
-        let built_priv = make_group(&test_case, true, true, &cs)
+    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn verify_application<C: CryptoProvider + Clone>(&self, crypto: &C) {
+        let built_priv = make_group(self, true, true, crypto)
             .await
-            .encrypt_application_message(&test_case.application, vec![])
+            .encrypt_application_message(&self.application, vec![])
             .await
             .unwrap()
             .mls_encode_to_vec()
             .unwrap();
 
-        for message in [&test_case.application_priv, &built_priv] {
-            match process_message(&test_case, message, &cs).await {
-                Content::Application(data) => assert_eq!(data.as_ref(), &test_case.application),
+        for message in [&self.application_priv, &built_priv] {
+            match process_message(self, message, crypto).await {
+                Content::Application(data) => assert_eq!(data.as_ref(), &self.application),
                 _ => panic!("decrypted value not application data"),
             };
         }
     }
-}
 
-// The test vector can be found here:
-// https://github.com/mlswg/mls-implementations/blob/main/test-vectors/message-protection.json
-#[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-async fn framing_commit() {
-    #[cfg(not(mls_build_async))]
-    let test_cases: Vec<FramingTestCase> =
-        load_test_case_json!(framing, generate_framing_test_vector());
-
-    #[cfg(mls_build_async)]
-    let test_cases: Vec<FramingTestCase> =
-        load_test_case_json!(framing, generate_framing_test_vector().await);
-
-    for test_case in test_cases.into_iter() {
-        let Some(cs) = try_test_cipher_suite_provider(test_case.context.cipher_suite) else {
-            continue;
-        };
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn verify_commit<C: CryptoProvider + Clone>(&self, crypto: &C) {
+        let cs = crypto
+            .cipher_suite_provider(self.context.cipher_suite.into())
+            .unwrap();
 
-        let commit = Commit::mls_decode(&mut &*test_case.commit).unwrap();
+        let commit = Commit::mls_decode(&mut &*self.commit).unwrap();
 
-        let to_check = vec![test_case.commit_priv.clone(), test_case.commit_pub.clone()];
+        let to_check = vec![self.commit_priv.clone(), self.commit_pub.clone()];
 
         // Wasm uses incompatible signature secret key format
         #[cfg(not(target_arch = "wasm32"))]
         let to_check = {
             let mut to_check = to_check;
 
-            let mut signature_priv = test_case.signature_priv.clone();
+            let mut signature_priv = self.signature_priv.clone();
 
-            if is_edwards(test_case.context.cipher_suite) {
-                signature_priv.extend(test_case.signature_pub.iter());
+            if is_edwards(self.context.cipher_suite) {
+                signature_priv.extend(self.signature_pub.iter());
             }
 
             let mut auth_content = AuthenticatedContent::new_signed(
                 &cs,
-                &test_case.context.clone().into(),
+                &self.context.clone().into(),
                 Sender::Member(1),
                 Content::Commit(alloc::boxed::Box::new(commit.clone())),
                 &signature_priv.into(),
@@ -266,7 +244,7 @@ async fn framing_commit() {
             auth_content.auth.confirmation_tag = Some(ConfirmationTag::empty(&cs).await);
 
             for enable_encryption in [true, false] {
-                let built = make_group(&test_case, true, enable_encryption, &cs)
+                let built = make_group(self, true, enable_encryption, crypto)
                     .await
                     .format_for_wire(auth_content.clone())
                     .await
@@ -281,25 +259,91 @@ async fn framing_commit() {
         };
 
         for message in to_check {
-            match process_message(&test_case, &message, &cs).await {
+            match process_message(self, &message, crypto).await {
                 Content::Commit(c) => assert_eq!(&*c, &commit),
                 _ => panic!("received value not commit"),
             };
         }
-        let commit = Commit::mls_decode(&mut &*test_case.commit).unwrap();
+        let commit = Commit::mls_decode(&mut &*self.commit).unwrap();
 
-        match process_message(&test_case, &test_case.commit_priv.clone(), &cs).await {
+        match process_message(self, &self.commit_priv.clone(), crypto).await {
             Content::Commit(c) => assert_eq!(&*c, &commit),
             _ => panic!("received value not commit"),
         };
     }
 }
 
+// The test vector can be found here:
+// https://github.com/mlswg/mls-implementations/blob/main/test-vectors/message-protection.json
+#[cfg(test)]
+#[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+async fn framing_proposal() {
+    #[cfg(not(mls_build_async))]
+    let test_cases: Vec<FramingTestCase> =
+        load_test_case_json!(framing, generate_framing_test_vector());
+
+    #[cfg(mls_build_async)]
+    let test_cases: Vec<FramingTestCase> =
+        load_test_case_json!(framing, generate_framing_test_vector().await);
+
+    for test_case in test_cases.into_iter() {
+        if try_test_cipher_suite_provider(test_case.context.cipher_suite).is_some() {
+            test_case.verify_proposal(&TestCryptoProvider::new()).await;
+        }
+    }
+}
+
+// The test vector can be found here:
+// https://github.com/mlswg/mls-implementations/blob/main/test-vectors/message-protection.json
+// Wasm uses incompatible signature secret key format
+#[cfg(all(test, not(target_arch = "wasm32")))]
+#[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+async fn framing_application() {
+    #[cfg(not(mls_build_async))]
+    let test_cases: Vec<FramingTestCase> =
+        load_test_case_json!(framing, generate_framing_test_vector());
+
+    #[cfg(mls_build_async)]
+    let test_cases: Vec<FramingTestCase> =
+        load_test_case_json!(framing, generate_framing_test_vector().await);
+
+    for test_case in test_cases.into_iter() {
+        if try_test_cipher_suite_provider(test_case.context.cipher_suite).is_some() {
+            test_case
+                .verify_application(&TestCryptoProvider::new())
+                .await;
+        }
+    }
+}
+
+// The test vector can be found here:
+// https://github.com/mlswg/mls-implementations/blob/main/test-vectors/message-protection.json
+#[cfg(test)]
+#[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+async fn framing_commit() {
+    #[cfg(not(mls_build_async))]
+    let test_cases: Vec<FramingTestCase> =
+        load_test_case_json!(framing, generate_framing_test_vector());
+
+    #[cfg(mls_build_async)]
+    let test_cases: Vec<FramingTestCase> =
+        load_test_case_json!(framing, generate_framing_test_vector().await);
+
+    for test_case in test_cases.into_iter() {
+        if try_test_cipher_suite_provider(test_case.context.cipher_suite).is_some() {
+            test_case.verify_commit(&TestCryptoProvider::new()).await;
+        }
+    }
+}
+
+#[cfg(test)]
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 #[cfg_attr(coverage_nightly, coverage(off))]
 async fn generate_framing_test_vector() -> Vec<FramingTestCase> {
     let mut test_vector = vec![];
 
+    let crypto = TestCryptoProvider::new();
+
     for cs in CipherSuite::all() {
         let cs = test_cipher_suite_provider(cs);
 
@@ -308,7 +352,7 @@ async fn generate_framing_test_vector() -> Vec<FramingTestCase> {
         // Generate private application message
         test_case.application = cs.random_bytes_vec(42).unwrap();
 
-        let application_priv = make_group(&test_case, true, true, &cs)
+        let application_priv = make_group(&test_case, true, true, &crypto)
             .await
             .encrypt_application_message(&test_case.application, vec![])
             .await
@@ -323,11 +367,11 @@ async fn generate_framing_test_vector() -> Vec<FramingTestCase> {
 
         test_case.proposal = proposal.mls_encode_to_vec().unwrap();
 
-        let mut group = make_group(&test_case, true, false, &cs).await;
+        let mut group = make_group(&test_case, true, false, &crypto).await;
         let proposal_pub = group.proposal_message(proposal.clone(), vec![]).await;
         test_case.proposal_pub = proposal_pub.unwrap().mls_encode_to_vec().unwrap();
 
-        let mut group = make_group(&test_case, true, true, &cs).await;
+        let mut group = make_group(&test_case, true, true, &crypto).await;
         let proposal_priv = group.proposal_message(proposal, vec![]).await.unwrap();
         test_case.proposal_priv = proposal_priv.mls_encode_to_vec().unwrap();
 
@@ -353,7 +397,7 @@ async fn generate_framing_test_vector() -> Vec<FramingTestCase> {
 
         auth_content.auth.confirmation_tag = Some(ConfirmationTag::empty(&cs).await);
 
-        let mut group = make_group(&test_case, true, false, &cs).await;
+        let mut group = make_group(&test_case, true, false, &crypto).await;
         let commit_pub = group.format_for_wire(auth_content.clone()).await.unwrap();
         test_case.commit_pub = commit_pub.mls_encode_to_vec().unwrap();
 
@@ -371,7 +415,7 @@ async fn generate_framing_test_vector() -> Vec<FramingTestCase> {
 
         auth_content.auth.confirmation_tag = Some(ConfirmationTag::empty(&cs).await);
 
-        let mut group = make_group(&test_case, true, true, &cs).await;
+        let mut group = make_group(&test_case, true, true, &crypto).await;
         let commit_priv = group.format_for_wire(auth_content.clone()).await.unwrap();
         test_case.commit_priv = commit_priv.mls_encode_to_vec().unwrap();
 
@@ -382,34 +426,51 @@ async fn generate_framing_test_vector() -> Vec<FramingTestCase> {
 }
 
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-async fn make_group<P: CipherSuiteProvider>(
+async fn make_group<C: CryptoProvider + Clone>(
     test_case: &FramingTestCase,
     for_send: bool,
     control_encryption_enabled: bool,
-    cs: &P,
-) -> Group<TestClientConfig> {
-    let mut group =
-        test_group_custom_config(
-            TEST_PROTOCOL_VERSION,
-            test_case.context.cipher_suite.into(),
-            |b| {
-                b.mls_rules(DefaultMlsRules::default().with_encryption_options(
-                    EncryptionOptions::new(control_encryption_enabled, PaddingMode::None),
-                ))
-            },
-        )
-        .await
-        .group;
+    crypto: &C,
+) -> Group<impl MlsConfig> {
+    let cipher_suite = test_case.context.cipher_suite.into();
+    let cs = crypto.cipher_suite_provider(cipher_suite).unwrap();
+
+    let mut group = generate_basic_client(
+        cipher_suite,
+        ProtocolVersion::MLS_10,
+        0,
+        None,
+        control_encryption_enabled,
+        crypto,
+    )
+    .await
+    .create_group(Default::default())
+    .await
+    .unwrap();
 
     // Add a leaf for the sender. It will get index 1.
-    let mut leaf = get_basic_test_node(cs.cipher_suite(), "leaf").await;
+    let mut leaf = generate_basic_client(
+        cipher_suite,
+        ProtocolVersion::MLS_10,
+        1,
+        None,
+        false,
+        crypto,
+    )
+    .await
+    .generate_key_package_message()
+    .await
+    .unwrap()
+    .into_key_package()
+    .unwrap()
+    .leaf_node;
 
     leaf.signing_identity.signature_key = SignaturePublicKey::from(test_case.signature_pub.clone());
 
     group
         .state
         .public_tree
-        .add_leaves(vec![leaf], &group.config.0.identity_provider, cs)
+        .add_leaves(vec![leaf], &group.config.identity_provider(), &cs)
         .await
         .unwrap();
 
@@ -444,13 +505,13 @@ async fn make_group<P: CipherSuiteProvider>(
 }
 
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-async fn process_message<P: CipherSuiteProvider>(
+async fn process_message<C: CryptoProvider + Clone>(
     test_case: &FramingTestCase,
     message: &[u8],
-    cs: &P,
+    crypto: &C,
 ) -> Content {
     // Enabling encryption doesn't matter for processing
-    let mut group = make_group(test_case, false, true, cs).await;
+    let mut group = make_group(test_case, false, true, crypto).await;
     let message = MlsMessage::mls_decode(&mut &*message).unwrap();
     let evt_or_cont = group.get_event_from_incoming_message(message);
 
@@ -2,33 +2,51 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use alloc::vec;
 use alloc::vec::Vec;
 
-use itertools::Itertools;
 use mls_rs_core::{
-    crypto::{CipherSuite, CipherSuiteProvider, CryptoProvider},
-    identity::SigningIdentity,
-    protocol_version::ProtocolVersion,
+    crypto::{CipherSuiteProvider, CryptoProvider},
     psk::ExternalPskId,
+    time::{MlsTime, TimeProvider},
 };
-use rand::{seq::IteratorRandom, Rng, SeedableRng};
 
 use crate::{
-    client_builder::{ClientBuilder, MlsConfig},
-    crypto::test_utils::TestCryptoProvider,
-    group::{ClientConfig, CommitBuilder, ExportedTree},
+    client_builder::ClientBuilder,
+    group::{ClientConfig, ExportedTree},
     identity::basic::BasicIdentityProvider,
     key_package::KeyPackageGeneration,
+    MlsMessage,
+};
+
+#[cfg(test)]
+use alloc::vec;
+
+#[cfg(test)]
+use itertools::Itertools;
+
+#[cfg(test)]
+use mls_rs_core::{
+    crypto::CipherSuite, identity::SigningIdentity, protocol_version::ProtocolVersion,
+};
+
+#[cfg(test)]
+use rand::{seq::IteratorRandom, Rng, SeedableRng};
+
+#[cfg(test)]
+use crate::{
+    client_builder::MlsConfig,
+    crypto::test_utils::{try_test_cipher_suite_provider, TestCryptoProvider},
+    group::CommitBuilder,
     mls_rules::CommitOptions,
     storage_provider::in_memory::InMemoryKeyPackageStorage,
     test_utils::{
         all_process_message, generate_basic_client, get_test_basic_credential, get_test_groups,
         make_test_ext_psk, TEST_EXT_PSK_ID,
     },
-    Client, Group, MlsMessage,
+    Client, Group,
 };
 
+#[cfg(test)]
 const VERSION: ProtocolVersion = ProtocolVersion::MLS_10;
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
@@ -77,6 +95,7 @@ pub struct TestMlsMessage(#[serde(with = "hex::serde")] pub Vec<u8>);
 #[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
 pub struct TestRatchetTree(#[serde(with = "hex::serde")] pub Vec<u8>);
 
+#[cfg(test)]
 impl TestEpoch {
     #[cfg_attr(coverage_nightly, coverage(off))]
     pub fn new(
@@ -97,6 +116,86 @@ impl TestEpoch {
     }
 }
 
+/// The key packages added in the test vectors have long expired.
+#[derive(Clone, Debug)]
+struct NoTimeProvider;
+
+impl TimeProvider for NoTimeProvider {
+    fn now(&self) -> Option<MlsTime> {
+        None
+    }
+}
+
+impl TestCase {
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn verify<C: CryptoProvider + Clone>(&self, crypto: &C) {
+        let cs = crypto
+            .cipher_suite_provider(self.cipher_suite.into())
+            .unwrap();
+
+        let message = MlsMessage::from_bytes(&self.key_package).unwrap();
+        let key_package = message.into_key_package().unwrap();
+        let id = key_package.leaf_node.signing_identity.clone();
+        let key = self.signature_priv.clone().into();
+
+        let mut client_builder = ClientBuilder::new()
+            .crypto_provider(crypto.clone())
+            .identity_provider(BasicIdentityProvider::new())
+            .time_provider(NoTimeProvider);
+
+        for psk in self.external_psks.iter() {
+            client_builder = client_builder.psk(
+                ExternalPskId::new(psk.psk_id.clone()),
+                psk.psk.clone().into(),
+            );
+        }
+
+        let client = client_builder
+            .signing_identity(id, key, cs.cipher_suite())
+            .build();
+
+        let key_pckg_gen = KeyPackageGeneration {
+            reference: key_package.to_reference(&cs).await.unwrap(),
+            key_package,
+            init_secret_key: self.init_priv.clone().into(),
+            leaf_node_secret_key: self.encryption_priv.clone().into(),
+        };
+
+        let (id, pkg) = key_pckg_gen.to_storage().unwrap();
+        client.config.key_package_repo().insert(id, pkg);
+
+        let welcome = MlsMessage::from_bytes(&self.welcome).unwrap();
+
+        let tree = self
+            .ratchet_tree
+            .as_ref()
+            .map(|t| ExportedTree::from_bytes(&t.0).unwrap());
+
+        let (mut group, _info) = client.join_group(tree, &welcome).await.unwrap();
+
+        assert_eq!(
+            group.epoch_authenticator().unwrap().to_vec(),
+            self.initial_epoch_authenticator
+        );
+
+        for epoch in self.epochs.iter() {
+            for proposal in epoch.proposals.iter() {
+                let message = MlsMessage::from_bytes(&proposal.0).unwrap();
+                group.process_incoming_message(message).await.unwrap();
+            }
+
+            let message = MlsMessage::from_bytes(&epoch.commit).unwrap();
+            group.process_incoming_message(message).await.unwrap();
+
+            assert_eq!(
+                epoch.epoch_authenticator,
+                group.epoch_authenticator().unwrap().to_vec()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
 #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
 async fn interop_passive_client() {
     // Test vectors can be found here:
@@ -150,68 +249,13 @@ async fn interop_passive_client() {
         .chain(test_cases_wel)
         .chain(test_cases_rand)
     {
-        let crypto_provider = TestCryptoProvider::new();
-        let Some(cs) = crypto_provider.cipher_suite_provider(test_case.cipher_suite.into()) else {
-            continue;
-        };
-
-        let message = MlsMessage::from_bytes(&test_case.key_package).unwrap();
-        let key_package = message.into_key_package().unwrap();
-        let id = key_package.leaf_node.signing_identity.clone();
-        let key = test_case.signature_priv.clone().into();
-
-        let mut client_builder = ClientBuilder::new()
-            .crypto_provider(crypto_provider)
-            .identity_provider(BasicIdentityProvider::new());
-
-        for psk in test_case.external_psks {
-            client_builder = client_builder.psk(ExternalPskId::new(psk.psk_id), psk.psk.into());
-        }
-
-        let client = client_builder
-            .signing_identity(id, key, cs.cipher_suite())
-            .build();
-
-        let key_pckg_gen = KeyPackageGeneration {
-            reference: key_package.to_reference(&cs).await.unwrap(),
-            key_package,
-            init_secret_key: test_case.init_priv.into(),
-            leaf_node_secret_key: test_case.encryption_priv.into(),
-        };
-
-        let (id, pkg) = key_pckg_gen.to_storage().unwrap();
-        client.config.key_package_repo().insert(id, pkg);
-
-        let welcome = MlsMessage::from_bytes(&test_case.welcome).unwrap();
-
-        let tree = test_case
-            .ratchet_tree
-            .map(|t| ExportedTree::from_bytes(&t.0).unwrap());
-
-        let (mut group, _info) = client.join_group(tree, &welcome).await.unwrap();
-
-        assert_eq!(
-            group.epoch_authenticator().unwrap().to_vec(),
-            test_case.initial_epoch_authenticator
-        );
-
-        for epoch in test_case.epochs {
-            for proposal in epoch.proposals.iter() {
-                let message = MlsMessage::from_bytes(&proposal.0).unwrap();
-                group.process_incoming_message(message).await.unwrap();
-            }
-
-            let message = MlsMessage::from_bytes(&epoch.commit).unwrap();
-            group.process_incoming_message(message).await.unwrap();
-
-            assert_eq!(
-                epoch.epoch_authenticator,
-                group.epoch_authenticator().unwrap().to_vec()
-            );
+        if try_test_cipher_suite_provider(test_case.cipher_suite).is_some() {
+            test_case.verify(&TestCryptoProvider::new()).await;
         }
     }
 }
 
+#[cfg(test)]
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 #[cfg_attr(coverage_nightly, coverage(off))]
 async fn invite_passive_client<P: CipherSuiteProvider>(
@@ -271,6 +315,7 @@ async fn invite_passive_client<P: CipherSuiteProvider>(
     }
 }
 
+#[cfg(test)]
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 #[cfg_attr(coverage_nightly, coverage(off))]
 pub async fn generate_passive_client_proposal_tests() {
@@ -436,6 +481,7 @@ pub async fn generate_passive_client_proposal_tests() {
     }
 }
 
+#[cfg(test)]
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 #[cfg_attr(coverage_nightly, coverage(off))]
 async fn commit_by_value<F, C: MlsConfig>(
@@ -456,6 +502,7 @@ where
     test_case
 }
 
+#[cfg(test)]
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 #[cfg_attr(coverage_nightly, coverage(off))]
 async fn create_key_package(cs: CipherSuite) -> MlsMessage {
@@ -465,6 +512,7 @@ async fn create_key_package(cs: CipherSuite) -> MlsMessage {
     client.generate_key_package_message().await.unwrap()
 }
 
+#[cfg(test)]
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 #[cfg_attr(coverage_nightly, coverage(off))]
 pub async fn generate_passive_client_welcome_tests() {
@@ -510,6 +558,7 @@ pub async fn generate_passive_client_welcome_tests() {
     }
 }
 
+#[cfg(test)]
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 #[cfg_attr(coverage_nightly, coverage(off))]
 pub async fn generate_passive_client_random_tests() {
@@ -580,6 +629,7 @@ pub async fn generate_passive_client_random_tests() {
     }
 }
 
+#[cfg(test)]
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 #[cfg_attr(coverage_nightly, coverage(off))]
 pub async fn add_random_members<C: MlsConfig>(
@@ -640,6 +690,7 @@ pub async fn add_random_members<C: MlsConfig>(
     }
 }
 
+#[cfg(test)]
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 #[cfg_attr(coverage_nightly, coverage(off))]
 pub async fn remove_members<C: MlsConfig>(
@@ -20,7 +20,7 @@ use crate::{
 };
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
-struct TestCase {
+pub struct TestCase {
     #[serde(with = "hex::serde")]
     mls_welcome: Vec<u8>,
     #[serde(with = "hex::serde")]
@@ -61,109 +61,113 @@ struct TestCase {
     private_message: Vec<u8>,
 }
 
-// The test vector can be found here:
-// https://github.com/mlswg/mls-implementations/blob/main/test-vectors/messages.json
-#[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-async fn serialization() {
-    let test_cases: Vec<TestCase> = load_test_case_json!(serialization, Vec::<TestCase>::new());
-
-    for test_case in test_cases.into_iter() {
-        let message = MlsMessage::from_bytes(&test_case.mls_welcome).unwrap();
+impl TestCase {
+    pub fn verify(&self) {
+        let message = MlsMessage::from_bytes(&self.mls_welcome).unwrap();
         message.clone().into_welcome().unwrap();
-        assert_eq!(&message.to_bytes().unwrap(), &test_case.mls_welcome);
+        assert_eq!(&message.to_bytes().unwrap(), &self.mls_welcome);
 
-        let message = MlsMessage::from_bytes(&test_case.mls_group_info).unwrap();
+        let message = MlsMessage::from_bytes(&self.mls_group_info).unwrap();
         message.clone().into_group_info().unwrap();
-        assert_eq!(&message.to_bytes().unwrap(), &test_case.mls_group_info);
+        assert_eq!(&message.to_bytes().unwrap(), &self.mls_group_info);
 
-        let message = MlsMessage::from_bytes(&test_case.mls_key_package).unwrap();
+        let message = MlsMessage::from_bytes(&self.mls_key_package).unwrap();
         message.clone().into_key_package().unwrap();
-        assert_eq!(&message.to_bytes().unwrap(), &test_case.mls_key_package);
+        assert_eq!(&message.to_bytes().unwrap(), &self.mls_key_package);
 
-        let tree = NodeVec::mls_decode(&mut &*test_case.ratchet_tree).unwrap();
+        let tree = NodeVec::mls_decode(&mut &*self.ratchet_tree).unwrap();
 
-        assert_eq!(&tree.mls_encode_to_vec().unwrap(), &test_case.ratchet_tree);
+        assert_eq!(&tree.mls_encode_to_vec().unwrap(), &self.ratchet_tree);
 
-        let secs = GroupSecrets::mls_decode(&mut &*test_case.group_secrets).unwrap();
+        let secs = GroupSecrets::mls_decode(&mut &*self.group_secrets).unwrap();
 
-        assert_eq!(&secs.mls_encode_to_vec().unwrap(), &test_case.group_secrets);
+        assert_eq!(&secs.mls_encode_to_vec().unwrap(), &self.group_secrets);
 
-        let proposal = AddProposal::mls_decode(&mut &*test_case.add_proposal).unwrap();
+        let proposal = AddProposal::mls_decode(&mut &*self.add_proposal).unwrap();
 
-        assert_eq!(
-            &proposal.mls_encode_to_vec().unwrap(),
-            &test_case.add_proposal
-        );
+        assert_eq!(&proposal.mls_encode_to_vec().unwrap(), &self.add_proposal);
 
-        let proposal = UpdateProposal::mls_decode(&mut &*test_case.update_proposal).unwrap();
+        let proposal = UpdateProposal::mls_decode(&mut &*self.update_proposal).unwrap();
 
         assert_eq!(
             &proposal.mls_encode_to_vec().unwrap(),
-            &test_case.update_proposal
+            &self.update_proposal
         );
 
-        let proposal = RemoveProposal::mls_decode(&mut &*test_case.remove_proposal).unwrap();
+        let proposal = RemoveProposal::mls_decode(&mut &*self.remove_proposal).unwrap();
 
         assert_eq!(
             &proposal.mls_encode_to_vec().unwrap(),
-            &test_case.remove_proposal
+            &self.remove_proposal
         );
 
-        let proposal = ReInitProposal::mls_decode(&mut &*test_case.re_init_proposal).unwrap();
+        let proposal = ReInitProposal::mls_decode(&mut &*self.re_init_proposal).unwrap();
 
         assert_eq!(
             &proposal.mls_encode_to_vec().unwrap(),
-            &test_case.re_init_proposal
+            &self.re_init_proposal
         );
 
         let proposal =
-            PreSharedKeyProposal::mls_decode(&mut &*test_case.pre_shared_key_proposal).unwrap();
+            PreSharedKeyProposal::mls_decode(&mut &*self.pre_shared_key_proposal).unwrap();
 
         assert_eq!(
             &proposal.mls_encode_to_vec().unwrap(),
-            &test_case.pre_shared_key_proposal
+            &self.pre_shared_key_proposal
         );
 
-        let proposal = ExternalInit::mls_decode(&mut &*test_case.external_init_proposal).unwrap();
+        let proposal = ExternalInit::mls_decode(&mut &*self.external_init_proposal).unwrap();
 
         assert_eq!(
             &proposal.mls_encode_to_vec().unwrap(),
-            &test_case.external_init_proposal
+            &self.external_init_proposal
         );
 
         let proposal =
-            ExtensionList::mls_decode(&mut &*test_case.group_context_extensions_proposal).unwrap();
+            ExtensionList::mls_decode(&mut &*self.group_context_extensions_proposal).unwrap();
 
         assert_eq!(
             &proposal.mls_encode_to_vec().unwrap(),
-            &test_case.group_context_extensions_proposal
+            &self.group_context_extensions_proposal
         );
 
-        let commit = Commit::mls_decode(&mut &*test_case.commit).unwrap();
+        let commit = Commit::mls_decode(&mut &*self.commit).unwrap();
 
-        assert_eq!(&commit.mls_encode_to_vec().unwrap(), &test_case.commit);
+        assert_eq!(&commit.mls_encode_to_vec().unwrap(), &self.commit);
 
-        let message = MlsMessage::from_bytes(&test_case.public_message_application).unwrap();
+        let message = MlsMessage::from_bytes(&self.public_message_application).unwrap();
         let serialized = message.mls_encode_to_vec().unwrap();
-        assert_eq!(&serialized, &test_case.public_message_application);
+        assert_eq!(&serialized, &self.public_message_application);
         let content_type = message.into_plaintext().unwrap().content.content_type();
         assert_eq!(content_type, ContentType::Application);
 
-        let message = MlsMessage::from_bytes(&test_case.public_message_proposal).unwrap();
+        let message = MlsMessage::from_bytes(&self.public_message_proposal).unwrap();
         let serialized = message.mls_encode_to_vec().unwrap();
-        assert_eq!(&serialized, &test_case.public_message_proposal);
+        assert_eq!(&serialized, &self.public_message_proposal);
         let content_type = message.into_plaintext().unwrap().content.content_type();
         assert_eq!(content_type, ContentType::Proposal);
 
-        let message = MlsMessage::from_bytes(&test_case.public_message_commit).unwrap();
+        let message = MlsMessage::from_bytes(&self.public_message_commit).unwrap();
         let serialized = message.mls_encode_to_vec().unwrap();
-        assert_eq!(&serialized, &test_case.public_message_commit);
+        assert_eq!(&serialized, &self.public_message_commit);
         let content_type = message.into_plaintext().unwrap().content.content_type();
         assert_eq!(content_type, ContentType::Commit);
 
-        let message = MlsMessage::from_bytes(&test_case.private_message).unwrap();
+        let message = MlsMessage::from_bytes(&self.private_message).unwrap();
         let serialized = message.mls_encode_to_vec().unwrap();
-        assert_eq!(&serialized, &test_case.private_message);
+        assert_eq!(&serialized, &self.private_message);
         message.into_ciphertext().unwrap();
     }
 }
+
+// The test vector can be found here:
+// https://github.com/mlswg/mls-implementations/blob/main/test-vectors/messages.json
+#[cfg(test)]
+#[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+async fn serialization() {
+    let test_cases: Vec<TestCase> = load_test_case_json!(serialization, Vec::<TestCase>::new());
+
+    for test_case in test_cases.into_iter() {
+        test_case.verify();
+    }
+}
@@ -2,28 +2,46 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
+use core::ops::{Deref, DerefMut};
+
 use crate::{
-    client::test_utils::TEST_PROTOCOL_VERSION,
-    crypto::test_utils::try_test_cipher_suite_provider,
+    client::MlsError,
+    client_config::ClientConfig,
     group::{
-        confirmation_tag::ConfirmationTag, framing::Content, message_processor::MessageProcessor,
-        message_signature::AuthenticatedContent, test_utils::GroupWithoutKeySchedule, Commit,
-        GroupContext, PathSecret, Sender,
+        confirmation_tag::ConfirmationTag,
+        framing::{Content, PrivateMessageRef, PublicMessage},
+        message_processor::{EventOrContent, MessageProcessor, ProvisionalState},
+        message_signature::AuthenticatedContent,
+        state::GroupState,
+        transcript_hash::InterimTranscriptHash,
+        Commit, Group, GroupContext, PathSecret, Sender,
     },
     identity::basic::BasicIdentityProvider,
+    test_utils::generate_basic_client,
     tree_kem::{
         node::{LeafIndex, NodeVec},
-        TreeKemPrivate, TreeKemPublic, UpdatePath,
+        TreeKemPrivate, TreeKemPublic, UpdatePath, ValidatedUpdatePath,
     },
     WireFormat,
 };
 use alloc::vec;
 use alloc::vec::Vec;
 use mls_rs_codec::MlsDecode;
-use mls_rs_core::{crypto::CipherSuiteProvider, extension::ExtensionList};
+use mls_rs_core::{
+    crypto::{CipherSuiteProvider, CryptoProvider},
+    extension::ExtensionList,
+    protocol_version::ProtocolVersion,
+    time::MlsTime,
+};
+
+#[cfg(mls_build_async)]
+use alloc::boxed::Box;
+
+#[cfg(test)]
+use crate::crypto::test_utils::{try_test_cipher_suite_provider, TestCryptoProvider};
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
-struct TreeKemTestCase {
+pub struct TreeKemTestCase {
     pub cipher_suite: u16,
 
     #[serde(with = "hex::serde")]
@@ -66,20 +84,15 @@ struct TestUpdatePath {
     commit_secret: Vec<u8>,
 }
 
-#[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-async fn tree_kem() {
-    // The test vector can be found here https://github.com/mlswg/mls-implementations/blob/main/test-vectors/treekem.json
-
-    let test_cases: Vec<TreeKemTestCase> =
-        load_test_case_json!(interop_tree_kem, Vec::<TreeKemTestCase>::new());
-
-    for test_case in test_cases {
-        let Some(cs) = try_test_cipher_suite_provider(test_case.cipher_suite) else {
-            continue;
-        };
+impl TreeKemTestCase {
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn verify<C: CryptoProvider + Clone>(&self, crypto: &C) {
+        let cs = &crypto
+            .cipher_suite_provider(self.cipher_suite.into())
+            .unwrap();
 
         // Import the public ratchet tree
-        let nodes = NodeVec::mls_decode(&mut &*test_case.ratchet_tree).unwrap();
+        let nodes = NodeVec::mls_decode(&mut &*self.ratchet_tree).unwrap();
 
         let mut tree =
             TreeKemPublic::import_node_data(nodes, &BasicIdentityProvider, &Default::default())
@@ -88,16 +101,16 @@ async fn tree_kem() {
 
         // Construct GroupContext
         let group_context = GroupContext {
-            protocol_version: TEST_PROTOCOL_VERSION,
+            protocol_version: ProtocolVersion::MLS_10,
             cipher_suite: cs.cipher_suite(),
-            group_id: test_case.group_id,
-            epoch: test_case.epoch,
-            tree_hash: tree.tree_hash(&cs).await.unwrap(),
-            confirmed_transcript_hash: test_case.confirmed_transcript_hash.into(),
+            group_id: self.group_id.clone(),
+            epoch: self.epoch,
+            tree_hash: tree.tree_hash(cs).await.unwrap(),
+            confirmed_transcript_hash: self.confirmed_transcript_hash.clone().into(),
             extensions: ExtensionList::new(),
         };
 
-        for leaf in test_case.leaves_private.iter() {
+        for leaf in self.leaves_private.iter() {
             // Construct the private ratchet tree
             let mut tree_private = TreeKemPrivate::new(LeafIndex(leaf.index));
 
@@ -115,10 +128,8 @@ async fn tree_kem() {
                     .find_map(|s| (s.node == dp).then_some(s.path_secret.clone()));
 
                 let private_key = if let Some(secret) = secret {
-                    let (secret_key, public_key) = PathSecret::from(secret)
-                        .to_hpke_key_pair(&cs)
-                        .await
-                        .unwrap();
+                    let (secret_key, public_key) =
+                        PathSecret::from(secret).to_hpke_key_pair(cs).await.unwrap();
 
                     let tree_public = &tree.nodes.borrow_as_parent(dp).unwrap().public_key;
                     assert_eq!(&public_key, tree_public);
@@ -136,13 +147,26 @@ async fn tree_kem() {
                 .secret_keys
                 .insert(0, Some(leaf.encryption_priv.clone().into()));
 
-            let paths = test_case
+            let paths = self
                 .update_paths
                 .iter()
                 .filter(|path| path.sender != leaf.index);
 
             for update_path in paths {
-                let mut group = GroupWithoutKeySchedule::new(cs.cipher_suite()).await;
+                let client = generate_basic_client(
+                    cs.cipher_suite(),
+                    ProtocolVersion::MLS_10,
+                    0,
+                    None,
+                    false,
+                    crypto,
+                )
+                .await;
+
+                let mut group = GroupWithoutKeySchedule::new(
+                    client.create_group(Default::default()).await.unwrap(),
+                );
+
                 group.state.context = group_context.clone();
                 group.state.public_tree = tree.clone();
                 group.private_tree = tree_private.clone();
@@ -162,7 +186,7 @@ async fn tree_kem() {
                     WireFormat::PublicMessage,
                 );
 
-                auth_content.auth.confirmation_tag = Some(ConfirmationTag::empty(&cs).await);
+                auth_content.auth.confirmation_tag = Some(ConfirmationTag::empty(cs).await);
 
                 // Hack not to increment epoch
                 group.state.context.epoch -= 1;
@@ -176,10 +200,156 @@ async fn tree_kem() {
                 assert_eq!(&*commit_secret, &update_path.commit_secret);
 
                 let new_tree = &mut group.provisional_public_state.unwrap().public_tree;
-                let new_tree_hash = new_tree.tree_hash(&cs).await.unwrap();
+                let new_tree_hash = new_tree.tree_hash(cs).await.unwrap();
 
                 assert_eq!(&new_tree_hash, &update_path.tree_hash_after);
             }
         }
     }
 }
+
+#[cfg(test)]
+#[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+async fn tree_kem() {
+    // The test vector can be found here https://github.com/mlswg/mls-implementations/blob/main/test-vectors/treekem.json
+
+    let test_cases: Vec<TreeKemTestCase> =
+        load_test_case_json!(interop_tree_kem, Vec::<TreeKemTestCase>::new());
+
+    for test_case in test_cases {
+        if try_test_cipher_suite_provider(test_case.cipher_suite).is_some() {
+            test_case.verify(&TestCryptoProvider::new()).await;
+        }
+    }
+}
+
+struct GroupWithoutKeySchedule<C: ClientConfig> {
+    inner: Group<C>,
+    secrets: Option<(TreeKemPrivate, PathSecret)>,
+    provisional_public_state: Option<ProvisionalState>,
+}
+
+impl<C: ClientConfig> Deref for GroupWithoutKeySchedule<C> {
+    type Target = Group<C>;
+
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<C: ClientConfig> DerefMut for GroupWithoutKeySchedule<C> {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<C: ClientConfig> GroupWithoutKeySchedule<C> {
+    fn new(inner: Group<C>) -> Self {
+        Self {
+            inner,
+            secrets: None,
+            provisional_public_state: None,
+        }
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+impl<C: ClientConfig> MessageProcessor for GroupWithoutKeySchedule<C> {
+    type CipherSuiteProvider = <Group<C> as MessageProcessor>::CipherSuiteProvider;
+    type OutputType = <Group<C> as MessageProcessor>::OutputType;
+    type PreSharedKeyStorage = <Group<C> as MessageProcessor>::PreSharedKeyStorage;
+    type IdentityProvider = <Group<C> as MessageProcessor>::IdentityProvider;
+    type MlsRules = <Group<C> as MessageProcessor>::MlsRules;
+
+    fn group_state(&self) -> &GroupState {
+        self.inner.group_state()
+    }
+
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn group_state_mut(&mut self) -> &mut GroupState {
+        self.inner.group_state_mut()
+    }
+
+    fn mls_rules(&self) -> Self::MlsRules {
+        self.inner.mls_rules()
+    }
+
+    fn identity_provider(&self) -> Self::IdentityProvider {
+        self.inner.identity_provider()
+    }
+
+    fn cipher_suite_provider(&self) -> &Self::CipherSuiteProvider {
+        self.inner.cipher_suite_provider()
+    }
+
+    fn psk_storage(&self) -> Self::PreSharedKeyStorage {
+        self.inner.psk_storage()
+    }
+
+    fn current_time(&self) -> Option<MlsTime> {
+        self.inner.current_time()
+    }
+
+    fn can_continue_processing(&self, provisional_state: &ProvisionalState) -> bool {
+        self.inner.can_continue_processing(provisional_state)
+    }
+
+    #[cfg(feature = "private_message")]
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn min_epoch_available(&self) -> Option<u64> {
+        self.inner.min_epoch_available()
+    }
+
+    async fn apply_update_path(
+        &mut self,
+        sender: LeafIndex,
+        update_path: &ValidatedUpdatePath,
+        provisional_state: &mut ProvisionalState,
+    ) -> Result<Option<(TreeKemPrivate, PathSecret)>, MlsError> {
+        self.inner
+            .apply_update_path(sender, update_path, provisional_state)
+            .await
+    }
+
+    #[cfg(feature = "private_message")]
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    async fn process_ciphertext(
+        &mut self,
+        cipher_text: PrivateMessageRef<'_>,
+    ) -> Result<EventOrContent<Self::OutputType>, MlsError> {
+        self.inner.process_ciphertext(cipher_text).await
+    }
+
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    async fn verify_plaintext_authentication(
+        &self,
+        message: PublicMessage,
+    ) -> Result<EventOrContent<Self::OutputType>, MlsError> {
+        self.inner.verify_plaintext_authentication(message).await
+    }
+
+    async fn update_key_schedule(
+        &mut self,
+        secrets: Option<(TreeKemPrivate, PathSecret)>,
+        _interim_transcript_hash: InterimTranscriptHash,
+        _confirmation_tag: &ConfirmationTag,
+        provisional_public_state: ProvisionalState,
+    ) -> Result<(), MlsError> {
+        self.provisional_public_state = Some(provisional_public_state);
+        self.secrets = secrets;
+        Ok(())
+    }
+
+    #[cfg(feature = "private_message")]
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn self_index(&self) -> Option<LeafIndex> {
+        <Group<C> as MessageProcessor>::self_index(&self.inner)
+    }
+}
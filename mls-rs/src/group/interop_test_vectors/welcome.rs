@@ -0,0 +1,180 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::vec::Vec;
+use mls_rs_codec::{DecodeLimits, MlsDecode};
+use mls_rs_core::crypto::CipherSuiteProvider;
+
+use crate::{
+    group::{
+        key_schedule::{KeySchedule, WelcomeSecret},
+        GroupInfo, GroupSecrets, MlsMessage,
+    },
+    psk::secret::PskSecret,
+    signer::Signable,
+    tree_kem::hpke_encryption::HpkeEncryptable,
+};
+
+#[cfg(test)]
+use alloc::vec;
+
+#[cfg(test)]
+use crate::{
+    client::test_utils::{TestClientBuilder, TEST_PROTOCOL_VERSION},
+    crypto::test_utils::{try_test_cipher_suite_provider, TestCryptoProvider},
+    group::{test_utils::test_group, ClientConfig},
+    identity::test_utils::get_test_signing_identity,
+};
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct WelcomeTestCase {
+    pub cipher_suite: u16,
+    #[serde(with = "hex::serde")]
+    init_priv: Vec<u8>,
+    #[serde(with = "hex::serde")]
+    signer_pub: Vec<u8>,
+    #[serde(with = "hex::serde")]
+    key_package: Vec<u8>,
+    #[serde(with = "hex::serde")]
+    welcome: Vec<u8>,
+}
+
+impl WelcomeTestCase {
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn verify<P: CipherSuiteProvider>(&self, cs: &P) {
+        let key_package = MlsMessage::mls_decode(&mut &*self.key_package)
+            .unwrap()
+            .into_key_package()
+            .unwrap();
+
+        let welcome = MlsMessage::mls_decode(&mut &*self.welcome)
+            .unwrap()
+            .into_welcome()
+            .unwrap();
+
+        let key_package_ref = key_package.to_reference(cs).await.unwrap();
+
+        let encrypted_secrets = welcome
+            .secrets
+            .iter()
+            .find(|secrets| secrets.new_member == key_package_ref)
+            .unwrap();
+
+        let group_secrets = GroupSecrets::decrypt(
+            cs,
+            &self.init_priv.clone().into(),
+            &key_package.hpke_init_key,
+            &welcome.encrypted_group_info,
+            &encrypted_secrets.encrypted_group_secrets,
+            DecodeLimits::default(),
+        )
+        .await
+        .unwrap();
+
+        let psk_secret = PskSecret::new(cs);
+
+        let group_info =
+            WelcomeSecret::from_joiner_secret(cs, &group_secrets.joiner_secret, &psk_secret)
+                .await
+                .unwrap()
+                .decrypt(&welcome.encrypted_group_info)
+                .await
+                .unwrap();
+
+        let group_info = GroupInfo::mls_decode(&mut &**group_info).unwrap();
+
+        group_info
+            .verify(cs, &self.signer_pub.clone().into(), &())
+            .await
+            .unwrap();
+
+        let key_schedule = KeySchedule::from_joiner(
+            cs,
+            &group_secrets.joiner_secret,
+            &group_info.group_context,
+            32,
+            &psk_secret,
+        )
+        .await
+        .unwrap();
+
+        let tag_matches = group_info
+            .confirmation_tag
+            .matches(
+                &key_schedule.confirmation_key,
+                &group_info.group_context.confirmed_transcript_hash,
+                cs,
+            )
+            .await
+            .unwrap();
+
+        assert!(tag_matches);
+    }
+}
+
+#[cfg(test)]
+#[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+async fn interop_welcome() {
+    // The vectors use the format of
+    // https://github.com/mlswg/mls-implementations/blob/main/test-vectors/welcome.json
+
+    #[cfg(not(mls_build_async))]
+    let test_cases: Vec<WelcomeTestCase> = load_test_case_json!(welcome, generate_test_vector());
+
+    #[cfg(mls_build_async)]
+    let test_cases: Vec<WelcomeTestCase> =
+        load_test_case_json!(welcome, generate_test_vector().await);
+
+    for test_case in test_cases {
+        if let Some(cs) = try_test_cipher_suite_provider(test_case.cipher_suite) {
+            test_case.verify(&cs).await;
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+async fn generate_test_vector() -> Vec<WelcomeTestCase> {
+    let mut test_cases = vec![];
+
+    for cipher_suite in TestCryptoProvider::all_supported_cipher_suites() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, cipher_suite).await;
+
+        let (bob_identity, bob_key) = get_test_signing_identity(cipher_suite, b"bob").await;
+
+        let bob = TestClientBuilder::new_for_test()
+            .signing_identity(bob_identity, bob_key, cipher_suite)
+            .build();
+
+        let key_package = bob.generate_key_package_message().await.unwrap();
+        let (_, secrets) = bob.config.key_package_repo().key_packages()[0].clone();
+
+        let commit = alice
+            .group
+            .commit_builder()
+            .add_member(key_package.clone())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        let signer_pub = alice
+            .group
+            .current_member_signing_identity()
+            .unwrap()
+            .signature_key
+            .to_vec();
+
+        test_cases.push(WelcomeTestCase {
+            cipher_suite: cipher_suite.into(),
+            init_priv: secrets.init_key.to_vec(),
+            signer_pub,
+            key_package: key_package.to_bytes().unwrap(),
+            welcome: commit.welcome_messages[0].to_bytes().unwrap(),
+        });
+    }
+
+    test_cases
+}
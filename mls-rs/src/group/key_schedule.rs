@@ -502,21 +502,33 @@ async fn get_welcome_secret<P: CipherSuiteProvider>(
     kdf_derive_secret(cipher_suite, &epoch_seed, b"welcome").await
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "conformance_util"))]
 pub(crate) mod test_utils {
-    use alloc::vec;
+    use alloc::string::String;
     use alloc::vec::Vec;
+    use mls_rs_codec::MlsEncode;
     use mls_rs_core::crypto::CipherSuiteProvider;
+    use mls_rs_core::extension::ExtensionList;
     use zeroize::Zeroizing;
 
+    use crate::group::GroupContext;
+    use crate::protocol_version::ProtocolVersion;
+
+    #[cfg(test)]
+    use alloc::vec;
+
+    #[cfg(test)]
     use crate::{cipher_suite::CipherSuite, crypto::test_utils::test_cipher_suite_provider};
 
-    use super::{InitSecret, JoinerSecret, KeySchedule};
+    use super::{
+        get_welcome_secret, kdf_derive_secret, kdf_expand_with_label, InitSecret, JoinerSecret,
+        KeySchedule,
+    };
 
-    #[cfg(all(feature = "rfc_compliant", not(mls_build_async)))]
+    #[cfg(all(feature = "rfc_compliant", test, not(mls_build_async)))]
     use mls_rs_core::error::IntoAnyError;
 
-    #[cfg(all(feature = "rfc_compliant", not(mls_build_async)))]
+    #[cfg(all(feature = "rfc_compliant", test, not(mls_build_async)))]
     use super::MlsError;
 
     impl From<JoinerSecret> for Vec<u8> {
@@ -525,6 +537,7 @@ pub(crate) mod test_utils {
         }
     }
 
+    #[cfg(test)]
     pub(crate) fn get_test_key_schedule(cipher_suite: CipherSuite) -> KeySchedule {
         let key_size = test_cipher_suite_provider(cipher_suite).kdf_extract_size();
         let fake_secret = Zeroizing::new(vec![1u8; key_size]);
@@ -539,6 +552,7 @@ pub(crate) mod test_utils {
     }
 
     impl InitSecret {
+        #[cfg(test)]
         pub fn new(init_secret: Vec<u8>) -> Self {
             InitSecret(Zeroizing::new(init_secret))
         }
@@ -560,135 +574,94 @@ pub(crate) mod test_utils {
             self.membership_key = Zeroizing::new(key)
         }
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::client::test_utils::TEST_PROTOCOL_VERSION;
-    use crate::crypto::test_utils::try_test_cipher_suite_provider;
-    use crate::group::key_schedule::{
-        get_welcome_secret, kdf_derive_secret, kdf_expand_with_label,
-    };
-    use crate::group::GroupContext;
-    use alloc::string::String;
-    use alloc::vec::Vec;
-    use mls_rs_codec::MlsEncode;
-    use mls_rs_core::crypto::CipherSuiteProvider;
-    use mls_rs_core::extension::ExtensionList;
-
-    #[cfg(all(not(mls_build_async), feature = "rfc_compliant"))]
-    use crate::{
-        crypto::test_utils::{test_cipher_suite_provider, TestCryptoProvider},
-        group::{
-            key_schedule::KeyScheduleDerivationResult, test_utils::random_bytes, InitSecret,
-            PskSecret,
-        },
-    };
-
-    #[cfg(all(not(mls_build_async), feature = "rfc_compliant"))]
-    use alloc::{string::ToString, vec};
-
-    #[cfg(target_arch = "wasm32")]
-    use wasm_bindgen_test::wasm_bindgen_test as test;
-    use zeroize::Zeroizing;
-
-    use super::test_utils::get_test_key_schedule;
-    use super::KeySchedule;
 
     #[derive(serde::Deserialize, serde::Serialize)]
-    struct TestCase {
-        cipher_suite: u16,
+    pub struct TestCase {
+        pub cipher_suite: u16,
         #[serde(with = "hex::serde")]
-        group_id: Vec<u8>,
+        pub group_id: Vec<u8>,
         #[serde(with = "hex::serde")]
-        initial_init_secret: Vec<u8>,
-        epochs: Vec<KeyScheduleEpoch>,
+        pub initial_init_secret: Vec<u8>,
+        pub epochs: Vec<KeyScheduleEpoch>,
     }
 
     #[derive(serde::Deserialize, serde::Serialize)]
-    struct KeyScheduleEpoch {
+    pub struct KeyScheduleEpoch {
         #[serde(with = "hex::serde")]
-        commit_secret: Vec<u8>,
+        pub commit_secret: Vec<u8>,
         #[serde(with = "hex::serde")]
-        psk_secret: Vec<u8>,
+        pub psk_secret: Vec<u8>,
         #[serde(with = "hex::serde")]
-        confirmed_transcript_hash: Vec<u8>,
+        pub confirmed_transcript_hash: Vec<u8>,
         #[serde(with = "hex::serde")]
-        tree_hash: Vec<u8>,
+        pub tree_hash: Vec<u8>,
 
         #[serde(with = "hex::serde")]
-        group_context: Vec<u8>,
+        pub group_context: Vec<u8>,
 
         #[serde(with = "hex::serde")]
-        joiner_secret: Vec<u8>,
+        pub joiner_secret: Vec<u8>,
         #[serde(with = "hex::serde")]
-        welcome_secret: Vec<u8>,
+        pub welcome_secret: Vec<u8>,
         #[serde(with = "hex::serde")]
-        init_secret: Vec<u8>,
+        pub init_secret: Vec<u8>,
 
         #[serde(with = "hex::serde")]
-        sender_data_secret: Vec<u8>,
+        pub sender_data_secret: Vec<u8>,
         #[cfg(any(feature = "secret_tree_access", feature = "private_message"))]
         #[serde(with = "hex::serde")]
-        encryption_secret: Vec<u8>,
+        pub encryption_secret: Vec<u8>,
         #[serde(with = "hex::serde")]
-        exporter_secret: Vec<u8>,
+        pub exporter_secret: Vec<u8>,
         #[serde(with = "hex::serde")]
-        epoch_authenticator: Vec<u8>,
+        pub epoch_authenticator: Vec<u8>,
         #[serde(with = "hex::serde")]
-        external_secret: Vec<u8>,
+        pub external_secret: Vec<u8>,
         #[serde(with = "hex::serde")]
-        confirmation_key: Vec<u8>,
+        pub confirmation_key: Vec<u8>,
         #[serde(with = "hex::serde")]
-        membership_key: Vec<u8>,
+        pub membership_key: Vec<u8>,
         #[cfg(feature = "psk")]
         #[serde(with = "hex::serde")]
-        resumption_psk: Vec<u8>,
+        pub resumption_psk: Vec<u8>,
 
         #[serde(with = "hex::serde")]
-        external_pub: Vec<u8>,
+        pub external_pub: Vec<u8>,
 
-        exporter: KeyScheduleExporter,
+        pub exporter: KeyScheduleExporter,
     }
 
     #[derive(serde::Deserialize, serde::Serialize)]
-    struct KeyScheduleExporter {
-        label: String,
+    pub struct KeyScheduleExporter {
+        pub label: String,
         #[serde(with = "hex::serde")]
-        context: Vec<u8>,
-        length: usize,
+        pub context: Vec<u8>,
+        pub length: usize,
         #[serde(with = "hex::serde")]
-        secret: Vec<u8>,
+        pub secret: Vec<u8>,
     }
 
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn test_key_schedule() {
-        let test_cases: Vec<TestCase> =
-            load_test_case_json!(key_schedule_test_vector, generate_test_vector());
+    impl TestCase {
+        #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+        pub async fn verify<P: CipherSuiteProvider>(&self, cs: &P) {
+            let mut key_schedule =
+                KeySchedule::new(InitSecret(Zeroizing::new(self.initial_init_secret.clone())));
 
-        for test_case in test_cases {
-            let Some(cs_provider) = try_test_cipher_suite_provider(test_case.cipher_suite) else {
-                continue;
-            };
-
-            let mut key_schedule = get_test_key_schedule(cs_provider.cipher_suite());
-            key_schedule.init_secret.0 = Zeroizing::new(test_case.initial_init_secret);
-
-            for (i, epoch) in test_case.epochs.into_iter().enumerate() {
+            for (i, epoch) in self.epochs.iter().enumerate() {
                 let context = GroupContext {
-                    protocol_version: TEST_PROTOCOL_VERSION,
-                    cipher_suite: cs_provider.cipher_suite(),
-                    group_id: test_case.group_id.clone(),
+                    protocol_version: ProtocolVersion::MLS_10,
+                    cipher_suite: cs.cipher_suite(),
+                    group_id: self.group_id.clone(),
                     epoch: i as u64,
-                    tree_hash: epoch.tree_hash,
-                    confirmed_transcript_hash: epoch.confirmed_transcript_hash.into(),
+                    tree_hash: epoch.tree_hash.clone(),
+                    confirmed_transcript_hash: epoch.confirmed_transcript_hash.clone().into(),
                     extensions: ExtensionList::new(),
                 };
 
                 assert_eq!(context.mls_encode_to_vec().unwrap(), epoch.group_context);
 
-                let psk = epoch.psk_secret.into();
-                let commit = epoch.commit_secret.into();
+                let psk = epoch.psk_secret.clone().into();
+                let commit = epoch.commit_secret.clone().into();
 
                 let key_schedule_res = KeySchedule::from_key_schedule(
                     &key_schedule,
@@ -697,17 +670,16 @@ mod tests {
                     #[cfg(any(feature = "secret_tree_access", feature = "private_message"))]
                     32,
                     &psk,
-                    &cs_provider,
+                    cs,
                 )
                 .await
                 .unwrap();
 
                 key_schedule = key_schedule_res.key_schedule;
 
-                let welcome =
-                    get_welcome_secret(&cs_provider, &key_schedule_res.joiner_secret, &psk)
-                        .await
-                        .unwrap();
+                let welcome = get_welcome_secret(cs, &key_schedule_res.joiner_secret, &psk)
+                    .await
+                    .unwrap();
 
                 assert_eq!(*welcome, epoch.welcome_secret);
 
@@ -751,17 +723,15 @@ mod tests {
                     assert_eq!(epoch.resumption_psk, expected);
                 }
 
-                let (_external_sec, external_pub) = key_schedule
-                    .get_external_key_pair(&cs_provider)
-                    .await
-                    .unwrap();
+                let (_external_sec, external_pub) =
+                    key_schedule.get_external_key_pair(cs).await.unwrap();
 
                 assert_eq!(epoch.external_pub, *external_pub);
 
-                let exp = epoch.exporter;
+                let exp = &epoch.exporter;
 
                 let exported = key_schedule
-                    .export_secret(exp.label.as_bytes(), &exp.context, exp.length, &cs_provider)
+                    .export_secret(exp.label.as_bytes(), &exp.context, exp.length, cs)
                     .await
                     .unwrap();
 
@@ -770,6 +740,104 @@ mod tests {
         }
     }
 
+    #[derive(Debug, serde::Serialize, serde::Deserialize)]
+    pub struct ExpandWithLabelTestCase {
+        #[serde(with = "hex::serde")]
+        pub secret: Vec<u8>,
+        pub label: String,
+        #[serde(with = "hex::serde")]
+        pub context: Vec<u8>,
+        pub length: usize,
+        #[serde(with = "hex::serde")]
+        pub out: Vec<u8>,
+    }
+
+    #[derive(Debug, serde::Serialize, serde::Deserialize)]
+    pub struct DeriveSecretTestCase {
+        #[serde(with = "hex::serde")]
+        pub secret: Vec<u8>,
+        pub label: String,
+        #[serde(with = "hex::serde")]
+        pub out: Vec<u8>,
+    }
+
+    impl ExpandWithLabelTestCase {
+        #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+        pub async fn verify<P: CipherSuiteProvider>(&self, cs: &P) {
+            let computed = kdf_expand_with_label(
+                cs,
+                &self.secret,
+                self.label.as_bytes(),
+                &self.context,
+                Some(self.length),
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(&computed.to_vec(), &self.out);
+        }
+    }
+
+    impl DeriveSecretTestCase {
+        #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+        pub async fn verify<P: CipherSuiteProvider>(&self, cs: &P) {
+            let computed = kdf_derive_secret(cs, &self.secret, self.label.as_bytes())
+                .await
+                .unwrap();
+
+            assert_eq!(&computed.to_vec(), &self.out);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::test_utils::try_test_cipher_suite_provider;
+    use alloc::vec::Vec;
+
+    #[cfg(all(not(mls_build_async), feature = "rfc_compliant"))]
+    use crate::{
+        client::test_utils::TEST_PROTOCOL_VERSION,
+        crypto::test_utils::{test_cipher_suite_provider, TestCryptoProvider},
+        group::{
+            key_schedule::{get_welcome_secret, KeyScheduleDerivationResult},
+            test_utils::random_bytes,
+            GroupContext, InitSecret, PskSecret,
+        },
+    };
+
+    #[cfg(all(not(mls_build_async), feature = "rfc_compliant"))]
+    use alloc::{string::ToString, vec};
+
+    #[cfg(all(not(mls_build_async), feature = "rfc_compliant"))]
+    use mls_rs_codec::MlsEncode;
+
+    #[cfg(all(not(mls_build_async), feature = "rfc_compliant"))]
+    use mls_rs_core::crypto::CipherSuiteProvider;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    use super::test_utils::{DeriveSecretTestCase, ExpandWithLabelTestCase, TestCase};
+
+    #[cfg(all(not(mls_build_async), feature = "rfc_compliant"))]
+    use super::{
+        test_utils::{get_test_key_schedule, KeyScheduleEpoch, KeyScheduleExporter},
+        KeySchedule,
+    };
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn test_key_schedule() {
+        let test_cases: Vec<TestCase> =
+            load_test_case_json!(key_schedule_test_vector, generate_test_vector());
+
+        for test_case in test_cases {
+            if let Some(cs) = try_test_cipher_suite_provider(test_case.cipher_suite) {
+                test_case.verify(&cs).await;
+            }
+        }
+    }
+
     #[cfg(all(not(mls_build_async), feature = "rfc_compliant"))]
     #[cfg_attr(coverage_nightly, coverage(off))]
     fn generate_test_vector() -> Vec<TestCase> {
@@ -924,27 +992,6 @@ mod tests {
         }
     }
 
-    #[derive(Debug, serde::Serialize, serde::Deserialize)]
-    struct ExpandWithLabelTestCase {
-        #[serde(with = "hex::serde")]
-        secret: Vec<u8>,
-        label: String,
-        #[serde(with = "hex::serde")]
-        context: Vec<u8>,
-        length: usize,
-        #[serde(with = "hex::serde")]
-        out: Vec<u8>,
-    }
-
-    #[derive(Debug, serde::Serialize, serde::Deserialize)]
-    struct DeriveSecretTestCase {
-        #[serde(with = "hex::serde")]
-        secret: Vec<u8>,
-        label: String,
-        #[serde(with = "hex::serde")]
-        out: Vec<u8>,
-    }
-
     #[derive(Debug, serde::Serialize, serde::Deserialize)]
     pub struct InteropTestCase {
         cipher_suite: u16,
@@ -960,28 +1007,8 @@ mod tests {
 
         for test_case in test_cases {
             if let Some(cs) = try_test_cipher_suite_provider(test_case.cipher_suite) {
-                let test_exp = &test_case.expand_with_label;
-
-                let computed = kdf_expand_with_label(
-                    &cs,
-                    &test_exp.secret,
-                    test_exp.label.as_bytes(),
-                    &test_exp.context,
-                    Some(test_exp.length),
-                )
-                .await
-                .unwrap();
-
-                assert_eq!(&computed.to_vec(), &test_exp.out);
-
-                let test_derive = &test_case.derive_secret;
-
-                let computed =
-                    kdf_derive_secret(&cs, &test_derive.secret, test_derive.label.as_bytes())
-                        .await
-                        .unwrap();
-
-                assert_eq!(&computed.to_vec(), &test_derive.out);
+                test_case.expand_with_label.verify(&cs).await;
+                test_case.derive_secret.verify(&cs).await;
             }
         }
     }
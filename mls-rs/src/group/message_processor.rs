@@ -66,7 +66,7 @@ use super::{member_from_key_package, member_from_leaf_node};
 use super::proposal::CustomProposal;
 
 #[cfg(feature = "private_message")]
-use crate::group::framing::PrivateMessage;
+use crate::group::framing::PrivateMessageRef;
 
 #[cfg(feature = "by_ref_proposal")]
 use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
@@ -447,11 +447,13 @@ pub(crate) trait MessageProcessor: Send + Sync {
         message: MlsMessage,
         #[cfg(feature = "by_ref_proposal")] cache_proposal: bool,
     ) -> Result<Self::OutputType, MlsError> {
+        let time = self.current_time();
+
         self.process_incoming_message_with_time(
             message,
             #[cfg(feature = "by_ref_proposal")]
             cache_proposal,
-            None,
+            time,
         )
         .await
     }
@@ -484,7 +486,9 @@ pub(crate) trait MessageProcessor: Send + Sync {
                 self.verify_plaintext_authentication(plaintext).await
             }
             #[cfg(feature = "private_message")]
-            MlsMessagePayload::Cipher(cipher_text) => self.process_ciphertext(&cipher_text).await,
+            MlsMessagePayload::Cipher(cipher_text) => {
+                self.process_ciphertext((&cipher_text).into()).await
+            }
             MlsMessagePayload::GroupInfo(group_info) => {
                 validate_group_info_member(
                     self.group_state(),
@@ -867,15 +871,14 @@ pub(crate) trait MessageProcessor: Send + Sync {
     fn identity_provider(&self) -> Self::IdentityProvider;
     fn cipher_suite_provider(&self) -> &Self::CipherSuiteProvider;
     fn psk_storage(&self) -> Self::PreSharedKeyStorage;
+    fn current_time(&self) -> Option<MlsTime>;
     fn can_continue_processing(&self, provisional_state: &ProvisionalState) -> bool;
 
     #[cfg(feature = "private_message")]
     fn min_epoch_available(&self) -> Option<u64>;
 
     fn check_metadata(&self, message: &MlsMessage) -> Result<(), MlsError> {
-        let context = &self.group_state().context;
-
-        if message.version != context.protocol_version {
+        if message.version != self.group_state().context.protocol_version {
             return Err(MlsError::ProtocolVersionMismatch);
         }
 
@@ -893,57 +896,75 @@ pub(crate) trait MessageProcessor: Send + Sync {
             )),
             _ => None,
         } {
-            if group_id != &context.group_id {
-                return Err(MlsError::GroupIdMismatch);
-            }
+            self.check_content_metadata(
+                group_id,
+                epoch,
+                content_type,
+                #[cfg(feature = "private_message")]
+                matches!(&message.payload, MlsMessagePayload::Cipher(_)),
+            )?;
+        }
 
-            match content_type {
-                ContentType::Commit => {
-                    if context.epoch != epoch {
-                        Err(MlsError::InvalidEpoch)
-                    } else {
-                        Ok(())
-                    }
+        Ok(())
+    }
+
+    fn check_content_metadata(
+        &self,
+        group_id: &[u8],
+        epoch: u64,
+        content_type: ContentType,
+        #[cfg(feature = "private_message")] encrypted: bool,
+    ) -> Result<(), MlsError> {
+        let context = &self.group_state().context;
+
+        if group_id != context.group_id {
+            return Err(MlsError::GroupIdMismatch);
+        }
+
+        match content_type {
+            ContentType::Commit => {
+                if context.epoch != epoch {
+                    Err(MlsError::InvalidEpoch)
+                } else {
+                    Ok(())
                 }
-                #[cfg(feature = "by_ref_proposal")]
-                ContentType::Proposal => {
-                    if context.epoch != epoch {
-                        Err(MlsError::InvalidEpoch)
-                    } else {
-                        Ok(())
-                    }
+            }
+            #[cfg(feature = "by_ref_proposal")]
+            ContentType::Proposal => {
+                if context.epoch != epoch {
+                    Err(MlsError::InvalidEpoch)
+                } else {
+                    Ok(())
                 }
-                #[cfg(feature = "private_message")]
-                ContentType::Application => {
-                    if let Some(min) = self.min_epoch_available() {
-                        if epoch < min {
-                            Err(MlsError::InvalidEpoch)
-                        } else {
-                            Ok(())
-                        }
+            }
+            #[cfg(feature = "private_message")]
+            ContentType::Application => {
+                if let Some(min) = self.min_epoch_available() {
+                    if epoch < min {
+                        Err(MlsError::InvalidEpoch)
                     } else {
                         Ok(())
                     }
+                } else {
+                    Ok(())
                 }
-            }?;
+            }
+        }?;
 
-            // Proposal and commit messages must be sent in the current epoch
-            let check_epoch = content_type == ContentType::Commit;
+        // Proposal and commit messages must be sent in the current epoch
+        let check_epoch = content_type == ContentType::Commit;
 
-            #[cfg(feature = "by_ref_proposal")]
-            let check_epoch = check_epoch || content_type == ContentType::Proposal;
+        #[cfg(feature = "by_ref_proposal")]
+        let check_epoch = check_epoch || content_type == ContentType::Proposal;
 
-            if check_epoch && epoch != context.epoch {
-                return Err(MlsError::InvalidEpoch);
-            }
+        if check_epoch && epoch != context.epoch {
+            return Err(MlsError::InvalidEpoch);
+        }
 
-            // Unencrypted application messages are not allowed
-            #[cfg(feature = "private_message")]
-            if !matches!(&message.payload, MlsMessagePayload::Cipher(_))
-                && content_type == ContentType::Application
-            {
-                return Err(MlsError::UnencryptedApplicationMessage);
-            }
+        // Unencrypted application messages are not allowed
+        #[cfg(feature = "private_message")]
+        if !encrypted && content_type == ContentType::Application {
+            return Err(MlsError::UnencryptedApplicationMessage);
         }
 
         Ok(())
@@ -970,13 +991,13 @@ pub(crate) trait MessageProcessor: Send + Sync {
         let cs = self.cipher_suite_provider();
         let id = self.identity_provider();
 
-        validate_key_package(key_package, version, cs, &id).await
+        validate_key_package(key_package, version, cs, &id, self.current_time()).await
     }
 
     #[cfg(feature = "private_message")]
     async fn process_ciphertext(
         &mut self,
-        cipher_text: &PrivateMessage,
+        cipher_text: PrivateMessageRef<'_>,
     ) -> Result<EventOrContent<Self::OutputType>, MlsError>;
 
     async fn verify_plaintext_authentication(
@@ -1018,16 +1039,10 @@ pub(crate) async fn validate_key_package<C: CipherSuiteProvider, I: IdentityProv
     version: ProtocolVersion,
     cs: &C,
     id: &I,
+    time: Option<MlsTime>,
 ) -> Result<(), MlsError> {
     let validator = LeafNodeValidator::new(cs, id, None);
-
-    #[cfg(feature = "std")]
-    let context = Some(MlsTime::now());
-
-    #[cfg(not(feature = "std"))]
-    let context = None;
-
-    let context = ValidationContext::Add(context);
+    let context = ValidationContext::Add(time);
 
     validator
         .check_if_valid(&key_package.leaf_node, context)
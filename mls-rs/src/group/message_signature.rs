@@ -15,7 +15,7 @@ use core::{
     fmt::{self, Debug},
     ops::Deref,
 };
-use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+use mls_rs_codec::{DecodeContext, MlsDecode, MlsEncode, MlsSize};
 use mls_rs_core::protocol_version::ProtocolVersion;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -37,7 +37,10 @@ impl MlsSize for FramedContentAuthData {
 }
 
 impl MlsEncode for FramedContentAuthData {
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), mls_rs_codec::Error> {
+    fn mls_encode<W: mls_rs_codec::Writer + ?Sized>(
+        &self,
+        writer: &mut W,
+    ) -> Result<(), mls_rs_codec::Error> {
         self.signature.mls_encode(writer)?;
 
         if let Some(ref tag) = self.confirmation_tag {
@@ -52,11 +55,14 @@ impl FramedContentAuthData {
     pub(crate) fn mls_decode(
         reader: &mut &[u8],
         content_type: ContentType,
+        context: &mut DecodeContext,
     ) -> Result<Self, mls_rs_codec::Error> {
         Ok(FramedContentAuthData {
-            signature: MessageSignature::mls_decode(reader)?,
+            signature: MessageSignature::mls_decode_with_context(reader, context)?,
             confirmation_tag: match content_type {
-                ContentType::Commit => Some(ConfirmationTag::mls_decode(reader)?),
+                ContentType::Commit => {
+                    Some(ConfirmationTag::mls_decode_with_context(reader, context)?)
+                }
                 #[cfg(feature = "private_message")]
                 ContentType::Application => None,
                 #[cfg(feature = "by_ref_proposal")]
@@ -138,10 +144,14 @@ impl AuthenticatedContent {
 }
 
 impl MlsDecode for AuthenticatedContent {
-    fn mls_decode(reader: &mut &[u8]) -> Result<Self, mls_rs_codec::Error> {
-        let wire_format = WireFormat::mls_decode(reader)?;
-        let content = FramedContent::mls_decode(reader)?;
-        let auth_data = FramedContentAuthData::mls_decode(reader, content.content_type())?;
+    fn mls_decode_with_context(
+        reader: &mut &[u8],
+        context: &mut DecodeContext,
+    ) -> Result<Self, mls_rs_codec::Error> {
+        let wire_format = WireFormat::mls_decode_with_context(reader, context)?;
+        let content = FramedContent::mls_decode_with_context(reader, context)?;
+
+        let auth_data = FramedContentAuthData::mls_decode(reader, content.content_type(), context)?;
 
         Ok(AuthenticatedContent {
             wire_format,
@@ -169,7 +179,10 @@ impl<'a> MlsSize for AuthenticatedContentTBS<'a> {
 }
 
 impl<'a> MlsEncode for AuthenticatedContentTBS<'a> {
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), mls_rs_codec::Error> {
+    fn mls_encode<W: mls_rs_codec::Writer + ?Sized>(
+        &self,
+        writer: &mut W,
+    ) -> Result<(), mls_rs_codec::Error> {
         self.protocol_version.mls_encode(writer)?;
         self.wire_format.mls_encode(writer)?;
         self.content.mls_encode(writer)?;
@@ -0,0 +1,715 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+
+#[cfg(not(target_has_atomic = "ptr"))]
+use portable_atomic_util::Arc;
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::convert::Infallible;
+use mls_rs_core::{
+    crypto::{CipherSuite, SignatureSecretKey},
+    error::IntoAnyError,
+    extension::ExtensionList,
+    identity::{IdentityProvider, SigningIdentity},
+    protocol_version::ProtocolVersion,
+    time::{MlsTime, TimeProvider},
+};
+
+#[cfg(mls_build_async)]
+use alloc::boxed::Box;
+
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+
+#[cfg(not(feature = "std"))]
+use spin::Mutex;
+
+use crate::{client::MlsError, client_config::ClientConfig, MlsMessage};
+
+use super::{CommitOutput, ExportedTree, Group, NewMemberInfo, ReinitClient};
+
+/// Directory through which the members of a group that is being
+/// reinitialized exchange their key packages for the new group.
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+pub trait KeyPackageDirectory: Send + Sync {
+    type Error: IntoAnyError;
+
+    /// Publish `key_package` for the new group with ID `group_id`.
+    async fn publish(&self, group_id: &[u8], key_package: MlsMessage) -> Result<(), Self::Error>;
+
+    /// All key packages published for the new group with ID `group_id`, in
+    /// the order in which they were published.
+    async fn key_packages(&self, group_id: &[u8]) -> Result<Vec<MlsMessage>, Self::Error>;
+}
+
+/// In memory [`KeyPackageDirectory`].
+///
+/// All clones of an instance of this type share the same published key
+/// packages.
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryKeyPackageDirectory {
+    inner: Arc<Mutex<BTreeMap<Vec<u8>, Vec<MlsMessage>>>>,
+}
+
+impl InMemoryKeyPackageDirectory {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    #[cfg(feature = "std")]
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<Vec<u8>, Vec<MlsMessage>>> {
+        self.inner.lock().unwrap()
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn lock(&self) -> spin::mutex::MutexGuard<'_, BTreeMap<Vec<u8>, Vec<MlsMessage>>> {
+        self.inner.lock()
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+impl KeyPackageDirectory for InMemoryKeyPackageDirectory {
+    type Error = Infallible;
+
+    async fn publish(&self, group_id: &[u8], key_package: MlsMessage) -> Result<(), Self::Error> {
+        self.lock()
+            .entry(group_id.to_vec())
+            .or_default()
+            .push(key_package);
+
+        Ok(())
+    }
+
+    async fn key_packages(&self, group_id: &[u8]) -> Result<Vec<MlsMessage>, Self::Error> {
+        Ok(self.lock().get(group_id).cloned().unwrap_or_default())
+    }
+}
+
+/// Result of [`ReinitMigration::commit`].
+#[non_exhaustive]
+pub struct ReinitOutput<C: ClientConfig + Clone> {
+    /// The new group.
+    pub group: Group<C>,
+    /// Welcome messages for the members of the previous group that
+    /// published a key package.
+    pub welcome_messages: Vec<MlsMessage>,
+    /// Signing identities, in the previous group, of the members that did
+    /// not publish a key package yet. They can be added later with
+    /// [`Group::add_late_members`].
+    pub missing_members: Vec<SigningIdentity>,
+}
+
+/// Migration of a group to the parameters of a committed
+/// [`ReInitProposal`](crate::group::proposal::ReInitProposal).
+///
+/// Every member publishes a key package for the new group with
+/// [`ReinitMigration::publish_key_package`]. One member, chosen by the
+/// application, then creates the new group with
+/// [`ReinitMigration::commit`] from the key packages found in the directory,
+/// and the other members join it with [`ReinitMigration::join`].
+///
+/// A key package is only used for a member of the previous group if the
+/// [IdentityProvider](crate::IdentityProvider) accepts its signing identity
+/// as a [valid successor](crate::IdentityProvider::valid_successor) of the
+/// member's signing identity in the previous group.
+///
+/// The group being migrated keeps its pending reinitialization in storage.
+/// A member that fails during the migration can load it again and restart
+/// with [`Group::reinit_migration`].
+pub struct ReinitMigration<C: ClientConfig + Clone, D> {
+    reinit_client: ReinitClient<C>,
+    directory: D,
+    members: Vec<SigningIdentity>,
+}
+
+impl<C> Group<C>
+where
+    C: ClientConfig + Clone,
+{
+    /// Start migrating this group to a new cipher suite or protocol version
+    /// by committing a [`ReInitProposal`](crate::group::proposal::ReInitProposal).
+    ///
+    /// Once the commit is applied, the migration continues with
+    /// [`Group::reinit_migration`].
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn commit_reinit(
+        &mut self,
+        version: ProtocolVersion,
+        cipher_suite: CipherSuite,
+        extensions: ExtensionList,
+    ) -> Result<CommitOutput, MlsError> {
+        self.commit_builder()
+            .reinit(None, version, cipher_suite, extensions)?
+            .build()
+            .await
+    }
+
+    /// Continue the migration of this group after a commit to a
+    /// [`ReInitProposal`](crate::group::proposal::ReInitProposal) was
+    /// applied, exchanging key packages through `directory`.
+    ///
+    /// `new_signer` and `new_signing_identity` are used as in
+    /// [`Group::get_reinit_client`].
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn reinit_migration<D: KeyPackageDirectory>(
+        self,
+        directory: D,
+        new_signer: Option<SignatureSecretKey>,
+        new_signing_identity: Option<SigningIdentity>,
+    ) -> Result<ReinitMigration<C, D>, MlsError> {
+        let members = self
+            .roster()
+            .members_iter()
+            .filter(|member| member.index != self.current_member_index())
+            .map(|member| member.signing_identity)
+            .collect();
+
+        Ok(ReinitMigration {
+            reinit_client: self.get_reinit_client(new_signer, new_signing_identity)?,
+            directory,
+            members,
+        })
+    }
+
+    /// Add the members of the previous group that published a key package
+    /// after this group was created by [`ReinitMigration::commit`].
+    ///
+    /// Only key packages of valid successors of `members`, the signing
+    /// identities in the previous group of typically the
+    /// [missing members](ReinitOutput::missing_members) of the migration,
+    /// are added. Returns `None` if none of them published a key package.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn add_late_members<D: KeyPackageDirectory>(
+        &mut self,
+        directory: &D,
+        members: &[SigningIdentity],
+    ) -> Result<Option<CommitOutput>, MlsError> {
+        let identity_provider = self.config.identity_provider();
+        let extensions = &self.context().extensions;
+        let mut wanted = Vec::new();
+
+        for predecessor in members {
+            let mut joined = false;
+
+            for member in self.roster().members_iter() {
+                joined = identity_provider
+                    .valid_successor(predecessor, &member.signing_identity, extensions)
+                    .await
+                    .map_err(|e| MlsError::IdentityProviderError(e.into_any_error()))?;
+
+                if joined {
+                    break;
+                }
+            }
+
+            if !joined {
+                wanted.push(predecessor.clone());
+            }
+        }
+
+        let key_packages = directory
+            .key_packages(self.group_id())
+            .await
+            .map_err(|e| MlsError::KeyPackageDirectoryError(e.into_any_error()))?;
+
+        let selected = select_key_packages(
+            &identity_provider,
+            extensions,
+            self.cipher_suite(),
+            self.protocol_version(),
+            self.config.time_provider().now(),
+            key_packages,
+            &wanted,
+        )
+        .await;
+
+        if selected.is_empty() {
+            return Ok(None);
+        }
+
+        let mut commit = self.commit_builder();
+
+        for (_, key_package) in selected {
+            commit = commit.add_member(key_package)?;
+        }
+
+        commit.build().await.map(Some)
+    }
+}
+
+impl<C, D> ReinitMigration<C, D>
+where
+    C: ClientConfig + Clone,
+    D: KeyPackageDirectory,
+{
+    /// ID of the new group.
+    pub fn new_group_id(&self) -> &[u8] {
+        self.reinit_client.reinit.group_id()
+    }
+
+    /// Generate a key package for the new group and publish it to the
+    /// directory.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn publish_key_package(&self) -> Result<(), MlsError> {
+        let key_package = self.reinit_client.generate_key_package().await?;
+
+        self.directory
+            .publish(self.new_group_id(), key_package)
+            .await
+            .map_err(|e| MlsError::KeyPackageDirectoryError(e.into_any_error()))
+    }
+
+    /// Signing identities of the other members of the previous group that
+    /// have not published a key package for the new group yet.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn missing_members(&self) -> Result<Vec<SigningIdentity>, MlsError> {
+        let (_, missing) = self.collect_key_packages().await?;
+        Ok(missing)
+    }
+
+    /// Create the new group with the latest key package published by each
+    /// other member of the previous group.
+    ///
+    /// Members that did not publish a key package are reported in the
+    /// output and can be added later with [`Group::add_late_members`].
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn commit(self) -> Result<ReinitOutput<C>, MlsError> {
+        let (key_packages, missing_members) = self.collect_key_packages().await?;
+        let (group, welcome_messages) = self.reinit_client.commit(key_packages).await?;
+
+        Ok(ReinitOutput {
+            group,
+            welcome_messages,
+            missing_members,
+        })
+    }
+
+    /// Join the new group created by [`ReinitMigration::commit`].
+    ///
+    /// The welcome message must include the resumption PSK of the previous
+    /// group, binding the new group to the reinitialization. Members added
+    /// late by [`Group::add_late_members`] join with
+    /// [`ReinitMigration::join_without_resumption_psk`] instead.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn join(
+        self,
+        welcome: &MlsMessage,
+        tree_data: Option<ExportedTree<'_>>,
+    ) -> Result<(Group<C>, NewMemberInfo), MlsError> {
+        self.reinit_client.join(welcome, tree_data).await
+    }
+
+    /// Join the new group with a welcome message created by
+    /// [`Group::add_late_members`].
+    ///
+    /// Such a welcome message does not include the resumption PSK of the
+    /// previous group, so only the group ID, cipher suite and protocol
+    /// version of the new group are checked against the reinitialization.
+    /// Whether the new group was created by a member of the previous group
+    /// can not be verified, and the welcome message must be authenticated
+    /// by the application, e.g. by the delivery service.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn join_without_resumption_psk(
+        self,
+        welcome: &MlsMessage,
+        tree_data: Option<ExportedTree<'_>>,
+    ) -> Result<(Group<C>, NewMemberInfo), MlsError> {
+        let reinit = self.reinit_client.reinit;
+        let client = self.reinit_client.client;
+        let signer = client.signer.ok_or(MlsError::SignerNotFound)?;
+
+        let (group, new_member_info) =
+            Group::from_welcome_message(welcome, tree_data, client.config, signer, None).await?;
+
+        if group.protocol_version() != reinit.new_version() {
+            Err(MlsError::ProtocolVersionMismatch)
+        } else if group.cipher_suite() != reinit.new_cipher_suite() {
+            Err(MlsError::CipherSuiteMismatch)
+        } else if group.group_id() != reinit.group_id() {
+            Err(MlsError::GroupIdMismatch)
+        } else {
+            Ok((group, new_member_info))
+        }
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn collect_key_packages(
+        &self,
+    ) -> Result<(Vec<MlsMessage>, Vec<SigningIdentity>), MlsError> {
+        let reinit = &self.reinit_client.reinit;
+        let config = &self.reinit_client.client.config;
+
+        let key_packages = self
+            .directory
+            .key_packages(reinit.group_id())
+            .await
+            .map_err(|e| MlsError::KeyPackageDirectoryError(e.into_any_error()))?;
+
+        let selected = select_key_packages(
+            &config.identity_provider(),
+            reinit.new_group_context_extensions(),
+            reinit.new_cipher_suite(),
+            reinit.new_version(),
+            config.time_provider().now(),
+            key_packages,
+            &self.members,
+        )
+        .await;
+
+        let missing = self
+            .members
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !selected.iter().any(|(selected, _)| selected == index))
+            .map(|(_, member)| member.clone())
+            .collect();
+
+        let key_packages = selected.into_iter().map(|(_, kp)| kp).collect();
+
+        Ok((key_packages, missing))
+    }
+}
+
+/// Select the latest key package of a valid successor of each member of
+/// `predecessors` that matches the new group parameters, along with the
+/// index of the member. Key packages that can not be used are skipped, so
+/// that a single invalid key package does not block the migration.
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+async fn select_key_packages<I: IdentityProvider>(
+    identity_provider: &I,
+    extensions: &ExtensionList,
+    cipher_suite: CipherSuite,
+    version: ProtocolVersion,
+    time: Option<MlsTime>,
+    key_packages: Vec<MlsMessage>,
+    predecessors: &[SigningIdentity],
+) -> Vec<(usize, MlsMessage)> {
+    let mut selected: Vec<(usize, MlsMessage)> = Vec::new();
+
+    for message in key_packages.into_iter().rev() {
+        let Some(key_package) = message.clone().into_key_package() else {
+            continue;
+        };
+
+        if key_package.cipher_suite != cipher_suite || key_package.version != version {
+            continue;
+        }
+
+        let successor = key_package.signing_identity();
+
+        if identity_provider
+            .validate_member(successor, time, Some(extensions))
+            .await
+            .is_err()
+        {
+            continue;
+        }
+
+        for (index, predecessor) in predecessors.iter().enumerate() {
+            if selected.iter().any(|(selected, _)| *selected == index) {
+                continue;
+            }
+
+            let valid = identity_provider
+                .valid_successor(predecessor, successor, extensions)
+                .await
+                .unwrap_or(false);
+
+            if valid {
+                selected.push((index, message));
+                break;
+            }
+        }
+    }
+
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+    use assert_matches::assert_matches;
+
+    use mls_rs_core::{
+        crypto::{CipherSuite, SignatureSecretKey},
+        extension::ExtensionList,
+        identity::SigningIdentity,
+    };
+
+    use crate::{
+        client::{
+            test_utils::{TestClientConfig, TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+            MlsError,
+        },
+        crypto::test_utils::TestCryptoProvider,
+        group::test_utils::{test_n_member_group, TestGroup},
+        identity::test_utils::get_test_signing_identity,
+        Client,
+    };
+
+    use super::{InMemoryKeyPackageDirectory, ReinitMigration};
+
+    type TestMigration = ReinitMigration<TestClientConfig, InMemoryKeyPackageDirectory>;
+
+    fn new_cipher_suite() -> CipherSuite {
+        TestCryptoProvider::all_supported_cipher_suites()
+            .into_iter()
+            .find(|cs| cs != &TEST_CIPHER_SUITE)
+            .unwrap_or(TEST_CIPHER_SUITE)
+    }
+
+    /// Create a group of three members that applied a commit reinitializing
+    /// the group with a new cipher suite.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn reinitialized_groups() -> Vec<TestGroup> {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+
+        let commit = groups[0]
+            .group
+            .commit_reinit(
+                TEST_PROTOCOL_VERSION,
+                new_cipher_suite(),
+                ExtensionList::new(),
+            )
+            .await
+            .unwrap();
+
+        groups[0].process_pending_commit().await.unwrap();
+
+        for group in groups.iter_mut().skip(1) {
+            group
+                .process_message(commit.commit_message.clone())
+                .await
+                .unwrap();
+        }
+
+        groups
+    }
+
+    fn signing_identity(group: &TestGroup) -> SigningIdentity {
+        group
+            .group
+            .current_member_signing_identity()
+            .unwrap()
+            .clone()
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn new_signer(identity: &[u8]) -> (SignatureSecretKey, SigningIdentity) {
+        let (signing_identity, signer) =
+            get_test_signing_identity(new_cipher_suite(), identity).await;
+        (signer, signing_identity)
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn migration(
+        group: TestGroup,
+        directory: &InMemoryKeyPackageDirectory,
+        identity: &[u8],
+    ) -> TestMigration {
+        let (signer, signing_identity) = new_signer(identity).await;
+
+        group
+            .group
+            .reinit_migration(directory.clone(), Some(signer), Some(signing_identity))
+            .await
+            .unwrap()
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn all_members_migrate_to_new_cipher_suite() {
+        let groups = reinitialized_groups().await;
+        let carol_identity = signing_identity(&groups[2]);
+        let mut groups = groups.into_iter();
+        let directory = InMemoryKeyPackageDirectory::new();
+
+        let alice = migration(groups.next().unwrap(), &directory, b"member").await;
+        let bob = migration(groups.next().unwrap(), &directory, b"name 1").await;
+        let carol = migration(groups.next().unwrap(), &directory, b"name 2").await;
+
+        bob.publish_key_package().await.unwrap();
+
+        let missing_members = alice.missing_members().await.unwrap();
+        assert_eq!(missing_members, vec![carol_identity]);
+
+        // Carol publishing twice must not add her twice.
+        carol.publish_key_package().await.unwrap();
+        carol.publish_key_package().await.unwrap();
+
+        let output = alice.commit().await.unwrap();
+
+        assert!(output.missing_members.is_empty());
+        assert_eq!(output.group.cipher_suite(), new_cipher_suite());
+
+        let (bob_group, _) = bob.join(&output.welcome_messages[0], None).await.unwrap();
+        let (carol_group, _) = carol.join(&output.welcome_messages[0], None).await.unwrap();
+
+        assert_eq!(bob_group.roster().members_iter().count(), 3);
+
+        assert_eq!(
+            bob_group.fork_fingerprint().unwrap(),
+            carol_group.fork_fingerprint().unwrap()
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn late_member_is_added_after_migration() {
+        let groups = reinitialized_groups().await;
+        let carol_identity = signing_identity(&groups[2]);
+        let mut groups = groups.into_iter();
+        let directory = InMemoryKeyPackageDirectory::new();
+
+        let alice = migration(groups.next().unwrap(), &directory, b"member").await;
+        let bob = migration(groups.next().unwrap(), &directory, b"name 1").await;
+        let carol = migration(groups.next().unwrap(), &directory, b"name 2").await;
+
+        bob.publish_key_package().await.unwrap();
+
+        let mut output = alice.commit().await.unwrap();
+        assert_eq!(output.missing_members, vec![carol_identity]);
+
+        let (mut bob_group, _) = bob.join(&output.welcome_messages[0], None).await.unwrap();
+
+        let res = output
+            .group
+            .add_late_members(&directory, &output.missing_members)
+            .await
+            .unwrap();
+
+        assert!(res.is_none());
+
+        carol.publish_key_package().await.unwrap();
+
+        let commit = output
+            .group
+            .add_late_members(&directory, &output.missing_members)
+            .await
+            .unwrap()
+            .unwrap();
+
+        output.group.apply_pending_commit().await.unwrap();
+
+        bob_group
+            .process_incoming_message(commit.commit_message)
+            .await
+            .unwrap();
+
+        let (carol_group, _) = carol
+            .join_without_resumption_psk(&commit.welcome_messages[0], None)
+            .await
+            .unwrap();
+
+        assert_eq!(carol_group.cipher_suite(), new_cipher_suite());
+
+        assert_eq!(
+            carol_group.fork_fingerprint().unwrap(),
+            bob_group.fork_fingerprint().unwrap()
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn migration_resumes_after_member_failure() {
+        let mut groups = reinitialized_groups().await;
+        let alice_identity = signing_identity(&groups[0]);
+        let directory = InMemoryKeyPackageDirectory::new();
+
+        for group in groups.iter_mut() {
+            group.group.write_to_storage().await.unwrap();
+        }
+
+        let group_id = groups[0].group.group_id().to_vec();
+
+        // Alice fails after committing the reinit. Bob creates the new group
+        // instead and alice resumes the migration from storage later.
+        let alice_client = Client::new(
+            groups[0].group.config.clone(),
+            None,
+            None,
+            TEST_PROTOCOL_VERSION,
+        );
+
+        let mut groups = groups.into_iter().skip(1);
+
+        let bob = migration(groups.next().unwrap(), &directory, b"name 1").await;
+        let carol = migration(groups.next().unwrap(), &directory, b"name 2").await;
+
+        carol.publish_key_package().await.unwrap();
+
+        let mut output = bob.commit().await.unwrap();
+        assert_eq!(output.missing_members, vec![alice_identity]);
+
+        carol.join(&output.welcome_messages[0], None).await.unwrap();
+
+        let alice_group = TestGroup {
+            group: alice_client.load_group(&group_id).await.unwrap(),
+        };
+
+        let alice = migration(alice_group, &directory, b"member").await;
+        alice.publish_key_package().await.unwrap();
+
+        let commit = output
+            .group
+            .add_late_members(&directory, &output.missing_members)
+            .await
+            .unwrap()
+            .unwrap();
+
+        output.group.apply_pending_commit().await.unwrap();
+
+        let (alice_group, _) = alice
+            .join_without_resumption_psk(&commit.welcome_messages[0], None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            alice_group.fork_fingerprint().unwrap(),
+            output.group.fork_fingerprint().unwrap()
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn join_requires_resumption_psk() {
+        let mut groups = reinitialized_groups().await.into_iter();
+        let directory = InMemoryKeyPackageDirectory::new();
+
+        let alice = migration(groups.next().unwrap(), &directory, b"member").await;
+        let bob = migration(groups.next().unwrap(), &directory, b"name 1").await;
+
+        let mut output = alice.commit().await.unwrap();
+
+        bob.publish_key_package().await.unwrap();
+
+        let commit = output
+            .group
+            .add_late_members(&directory, &output.missing_members)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let res = bob
+            .join(&commit.welcome_messages[0], None)
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::UnexpectedPskId));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn migration_requires_pending_reinit() {
+        let groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
+        let group = groups.into_iter().next().unwrap().group;
+
+        let res = group
+            .reinit_migration(InMemoryKeyPackageDirectory::new(), None, None)
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::PendingReInitNotFound));
+    }
+}
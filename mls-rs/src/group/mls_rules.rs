@@ -6,7 +6,7 @@ use crate::group::{proposal_filter::ProposalBundle, Roster};
 
 #[cfg(feature = "private_message")]
 use crate::{
-    group::{padding::PaddingMode, Sender},
+    group::{padding::PaddingMode, secret_tree::SenderRatchetLimits, Sender},
     WireFormat,
 };
 
@@ -86,21 +86,65 @@ impl CommitOptions {
 }
 
 /// Options controlling encryption of control and application messages
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[non_exhaustive]
 pub struct EncryptionOptions {
     #[cfg(feature = "private_message")]
     pub encrypt_control_messages: bool,
     #[cfg(feature = "private_message")]
     pub padding_mode: PaddingMode,
+    /// Number of generations behind the newest received message of a sender
+    /// for which keys of skipped messages are kept. By default, all skipped
+    /// keys are kept. Requires the `out_of_order` feature to have an effect.
+    #[cfg(feature = "private_message")]
+    pub out_of_order_tolerance: u32,
+    /// Number of generations a received message may skip ahead of the next
+    /// expected message of its sender. Defaults to 1024.
+    #[cfg(feature = "private_message")]
+    pub maximum_forward_distance: u32,
+}
+
+impl Default for EncryptionOptions {
+    fn default() -> Self {
+        #[cfg(feature = "private_message")]
+        return Self::new(false, PaddingMode::default());
+
+        #[cfg(not(feature = "private_message"))]
+        return Self {};
+    }
 }
 
 #[cfg(feature = "private_message")]
 impl EncryptionOptions {
     pub fn new(encrypt_control_messages: bool, padding_mode: PaddingMode) -> Self {
+        let limits = SenderRatchetLimits::default();
+
         Self {
             encrypt_control_messages,
             padding_mode,
+            out_of_order_tolerance: limits.out_of_order_tolerance,
+            maximum_forward_distance: limits.maximum_forward_distance,
+        }
+    }
+
+    pub fn with_out_of_order_tolerance(self, out_of_order_tolerance: u32) -> Self {
+        Self {
+            out_of_order_tolerance,
+            ..self
+        }
+    }
+
+    pub fn with_maximum_forward_distance(self, maximum_forward_distance: u32) -> Self {
+        Self {
+            maximum_forward_distance,
+            ..self
+        }
+    }
+
+    pub(crate) fn sender_ratchet_limits(&self) -> SenderRatchetLimits {
+        SenderRatchetLimits {
+            out_of_order_tolerance: self.out_of_order_tolerance,
+            maximum_forward_distance: self.maximum_forward_distance,
         }
     }
 
@@ -5,10 +5,10 @@
 use alloc::vec;
 use alloc::vec::Vec;
 use core::fmt::{self, Debug};
-use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+use mls_rs_codec::{DecodeLimits, MlsDecode, MlsEncode, MlsSize};
 use mls_rs_core::error::IntoAnyError;
 use mls_rs_core::secret::Secret;
-use mls_rs_core::time::MlsTime;
+use mls_rs_core::time::{MlsTime, TimeProvider};
 
 use crate::cipher_suite::CipherSuite;
 use crate::client::MlsError;
@@ -42,6 +42,9 @@ use crate::extension::ExternalPubExt;
 #[cfg(feature = "private_message")]
 use self::mls_rules::{EncryptionOptions, MlsRules};
 
+#[cfg(feature = "private_message")]
+use mls_rs_codec::MlsDecodeBorrowed;
+
 #[cfg(feature = "psk")]
 pub use self::resumption::ReinitClient;
 
@@ -106,6 +109,8 @@ pub(crate) use util::*;
 #[cfg(all(feature = "by_ref_proposal", feature = "external_client"))]
 pub use self::message_processor::CachedProposal;
 
+/// Encrypted backups of the state of a group.
+pub mod backup;
 #[cfg(feature = "private_message")]
 mod ciphertext_processor;
 
@@ -113,6 +118,8 @@ mod commit;
 pub(crate) mod confirmation_tag;
 mod context;
 pub(crate) mod epoch;
+/// Detection of and recovery from diverged group states.
+pub mod fork;
 pub(crate) mod framing;
 mod group_info;
 pub(crate) mod key_schedule;
@@ -120,6 +127,9 @@ mod membership_tag;
 pub(crate) mod message_processor;
 pub(crate) mod message_signature;
 pub(crate) mod message_verifier;
+/// Migration of a group to a new cipher suite or protocol version.
+#[cfg(feature = "psk")]
+pub mod migration;
 pub mod mls_rules;
 #[cfg(feature = "private_message")]
 pub(crate) mod padding;
@@ -129,6 +139,8 @@ mod proposal_cache;
 pub(crate) mod proposal_filter;
 #[cfg(feature = "by_ref_proposal")]
 pub(crate) mod proposal_ref;
+/// Role-based access control for the members of a group.
+pub mod rbac;
 #[cfg(feature = "psk")]
 mod resumption;
 mod roster;
@@ -143,6 +155,9 @@ pub(crate) mod state_repo_light;
 pub(crate) use state_repo_light as state_repo;
 
 pub(crate) mod transcript_hash;
+/// Policies keeping the leaf keys of group members fresh.
+#[cfg(feature = "state_update")]
+pub mod update_policy;
 mod util;
 
 /// External commit building.
@@ -154,12 +169,14 @@ pub(crate) mod secret_tree;
 #[cfg(any(feature = "secret_tree_access", feature = "private_message"))]
 pub use secret_tree::MessageKeyData as MessageKey;
 
-#[cfg(all(test, feature = "rfc_compliant"))]
-mod interop_test_vectors;
+#[cfg(all(any(test, feature = "conformance_util"), feature = "rfc_compliant"))]
+pub(crate) mod interop_test_vectors;
 
 mod exported_tree;
+mod tree_diff;
 
 pub use exported_tree::ExportedTree;
+pub use tree_diff::TreeDiff;
 
 #[derive(Clone, Debug, PartialEq, MlsSize, MlsEncode, MlsDecode)]
 struct GroupSecrets {
@@ -171,8 +188,8 @@ struct GroupSecrets {
 impl HpkeEncryptable for GroupSecrets {
     const ENCRYPT_LABEL: &'static str = "Welcome";
 
-    fn from_bytes(bytes: Vec<u8>) -> Result<Self, MlsError> {
-        Self::mls_decode(&mut bytes.as_slice()).map_err(Into::into)
+    fn from_bytes(bytes: Vec<u8>, limits: DecodeLimits) -> Result<Self, MlsError> {
+        Self::mls_decode_with_limits(&mut bytes.as_slice(), limits).map_err(Into::into)
     }
 
     fn get_bytes(&self) -> Result<Vec<u8>, MlsError> {
@@ -182,6 +199,7 @@ impl HpkeEncryptable for GroupSecrets {
 
 #[derive(Clone, Debug, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct EncryptedGroupSecrets {
     pub new_member: KeyPackageRef,
     pub encrypted_group_secrets: HpkeCiphertext,
@@ -189,10 +207,12 @@ pub(crate) struct EncryptedGroupSecrets {
 
 #[derive(Clone, Eq, PartialEq, MlsSize, MlsEncode, MlsDecode)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct Welcome {
     pub cipher_suite: CipherSuite,
     pub secrets: Vec<EncryptedGroupSecrets>,
     #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    #[cfg_attr(feature = "serde", serde(with = "mls_rs_core::vec_serde"))]
     pub encrypted_group_info: Vec<u8>,
 }
 
@@ -312,7 +332,10 @@ where
         );
 
         leaf_node_validator
-            .check_if_valid(&leaf_node, ValidationContext::Add(None))
+            .check_if_valid(
+                &leaf_node,
+                ValidationContext::Add(config.time_provider().now()),
+            )
             .await?;
 
         let (mut public_tree, private_tree) = TreeKemPublic::derive(
@@ -446,6 +469,7 @@ where
             &key_package_generation.key_package.hpke_init_key,
             &welcome.encrypted_group_info,
             &encrypted_group_secrets.encrypted_group_secrets,
+            config.decode_limits(),
         )
         .await?;
 
@@ -499,14 +523,20 @@ where
             .decrypt(&welcome.encrypted_group_info)
             .await?;
 
-        let group_info = GroupInfo::mls_decode(&mut &**decrypted_group_info)?;
+        let group_info = GroupInfo::mls_decode_with_limits(
+            &mut &**decrypted_group_info,
+            config.decode_limits(),
+        )?;
 
         let public_tree = validate_group_info_joiner(
             protocol_version,
             &group_info,
             tree_data,
             &config.identity_provider(),
+            &config.ratchet_tree_provider(),
+            config.decode_limits(),
             &cipher_suite_provider,
+            config.time_provider().now(),
         )
         .await?;
 
@@ -863,7 +893,7 @@ where
         &mut self,
         authenticated_data: Vec<u8>,
     ) -> Result<MlsMessage, MlsError> {
-        let proposal = self.update_proposal(None, None).await?;
+        let proposal = self.update_proposal(None, None, None).await?;
         self.proposal_message(proposal, authenticated_data).await
     }
 
@@ -893,7 +923,30 @@ where
         authenticated_data: Vec<u8>,
     ) -> Result<MlsMessage, MlsError> {
         let proposal = self
-            .update_proposal(Some(signer), Some(signing_identity))
+            .update_proposal(Some(signer), Some(signing_identity), None)
+            .await?;
+
+        self.proposal_message(proposal, authenticated_data).await
+    }
+
+    /// Create a proposal message that updates your own public keys and
+    /// replaces the extensions of your leaf node, for example to change your
+    /// [`ApplicationIdExt`](crate::extension::built_in::ApplicationIdExt).
+    ///
+    /// The new extensions are only used by this update. Later updates and
+    /// commits use the leaf node extensions of the client configuration again.
+    ///
+    /// `authenticated_data` will be sent unencrypted along with the contents
+    /// of the proposal message.
+    #[cfg(feature = "by_ref_proposal")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn propose_update_with_extensions(
+        &mut self,
+        leaf_node_extensions: ExtensionList,
+        authenticated_data: Vec<u8>,
+    ) -> Result<MlsMessage, MlsError> {
+        let proposal = self
+            .update_proposal(None, None, Some(leaf_node_extensions))
             .await?;
 
         self.proposal_message(proposal, authenticated_data).await
@@ -905,16 +958,23 @@ where
         &mut self,
         signer: Option<SignatureSecretKey>,
         signing_identity: Option<SigningIdentity>,
+        leaf_node_extensions: Option<ExtensionList>,
     ) -> Result<Proposal, MlsError> {
         // Grab a copy of the current node and update it to have new key material
         let mut new_leaf_node = self.current_user_leaf_node()?.clone();
 
+        let mut leaf_properties = self.config.leaf_properties();
+
+        if let Some(extensions) = leaf_node_extensions {
+            leaf_properties.extensions = extensions;
+        }
+
         let secret_key = new_leaf_node
             .update(
                 &self.cipher_suite_provider,
                 self.group_id(),
                 self.current_member_index(),
-                self.config.leaf_properties(),
+                leaf_properties,
                 signing_identity,
                 signer.as_ref().unwrap_or(&self.signer),
             )
@@ -1197,17 +1257,22 @@ where
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     async fn decrypt_incoming_ciphertext(
         &mut self,
-        message: &PrivateMessage,
+        message: PrivateMessageRef<'_>,
     ) -> Result<AuthenticatedContent, MlsError> {
         let epoch_id = message.epoch;
 
         // Does this need a cfg flag?
         if epoch_id > self.context().epoch {
-            return Err(MlsError::FutureEpochNotFound)
-        } 
+            return Err(MlsError::FutureEpochNotFound);
+        }
+
+        let ratchet_limits = self.encryption_options()?.sender_ratchet_limits();
+        let decode_limits = self.config.decode_limits();
 
         let auth_content = if epoch_id == self.context().epoch {
             let content = CiphertextProcessor::new(self, self.cipher_suite_provider.clone())
+                .with_ratchet_limits(ratchet_limits)
+                .with_decode_limits(decode_limits)
                 .open(message)
                 .await?;
 
@@ -1232,6 +1297,8 @@ where
                     .ok_or(MlsError::EpochNotFound)?;
 
                 let content = CiphertextProcessor::new(epoch, self.cipher_suite_provider.clone())
+                    .with_ratchet_limits(ratchet_limits)
+                    .with_decode_limits(decode_limits)
                     .open(message)
                     .await?;
 
@@ -1309,6 +1376,67 @@ where
         .await
     }
 
+    /// Process an inbound message for this group from its encoding, as
+    /// returned by [`MlsMessage::to_bytes`].
+    ///
+    /// Private messages are decrypted directly from `bytes`, without first
+    /// copying their ciphertext into an [`MlsMessage`]. Other messages are
+    /// processed as with [`Group::process_incoming_message`].
+    ///
+    /// Decoding `bytes` fails if the message exceeds the limits set with
+    /// [`ClientBuilder::decode_limits`](crate::client_builder::ClientBuilder::decode_limits).
+    ///
+    /// # Warning
+    ///
+    /// Changes to the group's state as a result of processing `bytes` will
+    /// not be persisted by the
+    /// [`GroupStateStorage`](crate::GroupStateStorage)
+    /// in use by this group until [`Group::write_to_storage`] is called.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn process_incoming_bytes(
+        &mut self,
+        bytes: &[u8],
+    ) -> Result<ReceivedMessage, MlsError> {
+        let limits = self.config.decode_limits();
+
+        // A pending commit is recognized by the hash of the full message.
+        #[cfg(feature = "private_message")]
+        if self.pending_commit.is_none() {
+            let reader = &mut &*bytes;
+            let version = ProtocolVersion::mls_decode(reader)?;
+
+            if WireFormat::mls_decode(reader)? == WireFormat::PrivateMessage {
+                let message = PrivateMessageRef::mls_decode_borrowed_with_limits(reader, limits)?;
+
+                if version != self.context().protocol_version {
+                    return Err(MlsError::ProtocolVersionMismatch);
+                }
+
+                self.check_content_metadata(
+                    message.group_id,
+                    message.epoch,
+                    message.content_type,
+                    true,
+                )?;
+
+                let event_or_content = self.process_ciphertext(message).await?;
+                let time = self.current_time();
+
+                return MessageProcessor::process_event_or_content(
+                    self,
+                    event_or_content,
+                    #[cfg(feature = "by_ref_proposal")]
+                    true,
+                    time,
+                )
+                .await;
+            }
+        }
+
+        self.process_incoming_message(MlsMessage::from_bytes_with_limits(bytes, limits)?)
+            .await
+    }
+
     /// Process an inbound message for this group, providing additional context
     /// with a message timestamp.
     ///
@@ -1613,7 +1741,7 @@ where
     #[cfg(feature = "private_message")]
     async fn process_ciphertext(
         &mut self,
-        cipher_text: &PrivateMessage,
+        cipher_text: PrivateMessageRef<'_>,
     ) -> Result<EventOrContent<Self::OutputType>, MlsError> {
         self.decrypt_incoming_ciphertext(cipher_text)
             .await
@@ -1809,6 +1937,10 @@ where
         self.config.secret_store()
     }
 
+    fn current_time(&self) -> Option<MlsTime> {
+        self.config.time_provider().now()
+    }
+
     fn group_state(&self) -> &GroupState {
         &self.state
     }
@@ -1890,7 +2022,8 @@ mod tests {
     use super::{
         test_utils::{
             get_test_25519_key, get_test_groups_with_features, group_extensions, process_commit,
-            test_group, test_group_custom, test_n_member_group, TestGroup, TEST_GROUP,
+            test_group, test_group_custom, test_n_member_group, TestGroup, TestRatchetTreeProvider,
+            TEST_GROUP,
         },
         *,
     };
@@ -2035,6 +2168,47 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn update_proposal_can_change_leaf_extensions() {
+        let mut alice = test_group_custom(
+            TEST_PROTOCOL_VERSION,
+            TEST_CIPHER_SUITE,
+            vec![ExtensionType::APPLICATION_ID],
+            None,
+            None,
+        )
+        .await;
+
+        let (mut bob, _) = alice.join("bob").await;
+
+        let mut extensions = ExtensionList::new();
+
+        extensions
+            .set_from(crate::extension::ApplicationIdExt::new(b"alice".to_vec()))
+            .unwrap();
+
+        let update = alice
+            .group
+            .propose_update_with_extensions(extensions.clone(), vec![])
+            .await
+            .unwrap();
+
+        bob.process_message(update).await.unwrap();
+        let commit_output = bob.group.commit(vec![]).await.unwrap();
+        bob.process_pending_commit().await.unwrap();
+
+        alice
+            .process_message(commit_output.commit_message)
+            .await
+            .unwrap();
+
+        for group in [&alice, &bob] {
+            let updated = group.group.roster().member_with_index(0).unwrap();
+            assert_eq!(updated.extensions, extensions);
+        }
+    }
+
     #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn test_invalid_commit_self_update() {
@@ -2192,6 +2366,76 @@ mod tests {
         assert_matches!(bob_group, Err(MlsError::RatchetTreeNotFound));
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn test_welcome_processing_fetches_tree() {
+        let (test_group, bob_client, welcome) = add_member_without_tree_extension("bob").await;
+
+        let bob_client = bob_client
+            .to_builder()
+            .ratchet_tree_provider(TestRatchetTreeProvider::new(&test_group.group))
+            .build();
+
+        let (bob_group, _) = bob_client.join_group(None, &welcome).await.unwrap();
+
+        assert_eq!(bob_group.export_tree(), test_group.group.export_tree());
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn test_welcome_processing_rejects_fetched_tree_with_wrong_hash() {
+        let (test_group, bob_client, welcome) = add_member_without_tree_extension("bob").await;
+
+        // Tree of the same epoch in which carol was added instead of bob.
+        let mut other_group = test_group.clone();
+        other_group.join("carol").await;
+        let other_tree = other_group.group.export_tree().to_bytes().unwrap();
+
+        let bob_client = bob_client
+            .to_builder()
+            .ratchet_tree_provider(TestRatchetTreeProvider::with_tree(
+                &test_group.group,
+                other_tree,
+            ))
+            .build();
+
+        let res = bob_client.join_group(None, &welcome).await.map(|_| ());
+
+        assert_matches!(res, Err(MlsError::TreeHashMismatch));
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn add_member_without_tree_extension(
+        name: &str,
+    ) -> (TestGroup, crate::Client<TestClientConfig>, MlsMessage) {
+        let mut test_group = test_group_custom(
+            TEST_PROTOCOL_VERSION,
+            TEST_CIPHER_SUITE,
+            Default::default(),
+            None,
+            Some(CommitOptions::new().with_ratchet_tree_extension(false)),
+        )
+        .await;
+
+        let (client, key_package) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, name).await;
+
+        let commit_output = test_group
+            .group
+            .commit_builder()
+            .add_member(key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        test_group.group.apply_pending_commit().await.unwrap();
+
+        (
+            test_group,
+            client,
+            commit_output.welcome_messages[0].clone(),
+        )
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn test_group_context_ext_proposal_create() {
         let test_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
@@ -3036,6 +3280,112 @@ mod tests {
         assert_matches!(res, Err(MlsError::KeyMissing(0)));
     }
 
+    #[cfg(feature = "private_message")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn member_can_decrypt_message_bytes() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob_group, _) = alice_group.join("bob").await;
+
+        let message = alice_group
+            .group
+            .encrypt_application_message(b"foobar", Vec::new())
+            .await
+            .unwrap()
+            .to_bytes()
+            .unwrap();
+
+        let received_message = bob_group
+            .group
+            .process_incoming_bytes(&message)
+            .await
+            .unwrap();
+
+        assert_matches!(
+            received_message,
+            ReceivedMessage::ApplicationMessage(m) if m.data() == b"foobar"
+        );
+
+        let res = bob_group.group.process_incoming_bytes(&message).await;
+
+        assert_matches!(res, Err(MlsError::KeyMissing(0)));
+    }
+
+    #[cfg(all(feature = "std", feature = "private_message"))]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn message_bytes_exceeding_decode_limits_are_rejected() {
+        let limits = mls_rs_codec::DecodeLimits::default().with_max_vec_len(500);
+
+        let mut alice_group =
+            test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+                b.decode_limits(limits)
+            })
+            .await;
+
+        let (mut bob_group, _) = alice_group.join("bob").await;
+
+        let small_message = bob_group
+            .group
+            .encrypt_application_message(&[0u8; 10], Vec::new())
+            .await
+            .unwrap()
+            .to_bytes()
+            .unwrap();
+
+        let res = alice_group
+            .group
+            .process_incoming_bytes(&small_message)
+            .await;
+
+        assert_matches!(res, Ok(ReceivedMessage::ApplicationMessage(_)));
+
+        let large_message = bob_group
+            .group
+            .encrypt_application_message(&[0u8; 1000], Vec::new())
+            .await
+            .unwrap()
+            .to_bytes()
+            .unwrap();
+
+        let res = alice_group
+            .group
+            .process_incoming_bytes(&large_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::SerializationError(_)));
+
+        let res = MlsMessage::from_bytes_with_limits(&large_message, limits);
+        assert_matches!(res, Err(MlsError::SerializationError(_)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn member_can_process_commit_bytes() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob_group, _) = alice_group.join("bob").await;
+
+        let commit = alice_group.group.commit(Vec::new()).await.unwrap();
+        let commit_bytes = commit.commit_message.to_bytes().unwrap();
+
+        let received_by_alice = alice_group
+            .group
+            .process_incoming_bytes(&commit_bytes)
+            .await
+            .unwrap();
+
+        let received_by_bob = bob_group
+            .group
+            .process_incoming_bytes(&commit_bytes)
+            .await
+            .unwrap();
+
+        assert_matches!(received_by_alice, ReceivedMessage::Commit(_));
+        assert_matches!(received_by_bob, ReceivedMessage::Commit(_));
+
+        assert_eq!(
+            alice_group.group.epoch_authenticator().unwrap(),
+            bob_group.group.epoch_authenticator().unwrap()
+        );
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn removing_requirements_allows_to_add() {
         let mut alice_group = test_group_custom(
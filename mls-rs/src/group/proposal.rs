@@ -12,7 +12,7 @@ use crate::{
     ProtocolVersion,
 };
 use core::fmt::{self, Debug};
-use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+use mls_rs_codec::{DecodeContext, MlsDecode, MlsEncode, MlsSize};
 use mls_rs_core::{group::Capabilities, identity::SigningIdentity};
 
 #[cfg(feature = "by_ref_proposal")]
@@ -166,6 +166,7 @@ pub struct ReInitProposal {
     pub(crate) group_id: Vec<u8>,
     pub(crate) version: ProtocolVersion,
     pub(crate) cipher_suite: CipherSuite,
+    #[cfg_attr(feature = "serde", serde(with = "crate::extension::list_serde"))]
     pub(crate) extensions: ExtensionList,
 }
 
@@ -325,7 +326,9 @@ pub enum Proposal {
     Psk(PreSharedKeyProposal),
     ReInit(ReInitProposal),
     ExternalInit(ExternalInit),
-    GroupContextExtensions(ExtensionList),
+    GroupContextExtensions(
+        #[cfg_attr(feature = "serde", serde(with = "crate::extension::list_serde"))] ExtensionList,
+    ),
     #[cfg(feature = "custom_proposal")]
     Custom(CustomProposal),
 }
@@ -351,7 +354,10 @@ impl MlsSize for Proposal {
 }
 
 impl MlsEncode for Proposal {
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), mls_rs_codec::Error> {
+    fn mls_encode<W: mls_rs_codec::Writer + ?Sized>(
+        &self,
+        writer: &mut W,
+    ) -> Result<(), mls_rs_codec::Error> {
         self.proposal_type().mls_encode(writer)?;
 
         match self {
@@ -382,29 +388,40 @@ impl MlsEncode for Proposal {
 }
 
 impl MlsDecode for Proposal {
-    fn mls_decode(reader: &mut &[u8]) -> Result<Self, mls_rs_codec::Error> {
-        let proposal_type = ProposalType::mls_decode(reader)?;
+    fn mls_decode_with_context(
+        reader: &mut &[u8],
+        context: &mut DecodeContext,
+    ) -> Result<Self, mls_rs_codec::Error> {
+        let proposal_type = ProposalType::mls_decode_with_context(reader, context)?;
 
         Ok(match proposal_type {
-            ProposalType::ADD => {
-                Proposal::Add(alloc::boxed::Box::new(AddProposal::mls_decode(reader)?))
-            }
+            ProposalType::ADD => Proposal::Add(alloc::boxed::Box::new(
+                AddProposal::mls_decode_with_context(reader, context)?,
+            )),
             #[cfg(feature = "by_ref_proposal")]
-            ProposalType::UPDATE => Proposal::Update(UpdateProposal::mls_decode(reader)?),
-            ProposalType::REMOVE => Proposal::Remove(RemoveProposal::mls_decode(reader)?),
+            ProposalType::UPDATE => {
+                Proposal::Update(UpdateProposal::mls_decode_with_context(reader, context)?)
+            }
+            ProposalType::REMOVE => {
+                Proposal::Remove(RemoveProposal::mls_decode_with_context(reader, context)?)
+            }
             #[cfg(feature = "psk")]
-            ProposalType::PSK => Proposal::Psk(PreSharedKeyProposal::mls_decode(reader)?),
-            ProposalType::RE_INIT => Proposal::ReInit(ReInitProposal::mls_decode(reader)?),
-            ProposalType::EXTERNAL_INIT => {
-                Proposal::ExternalInit(ExternalInit::mls_decode(reader)?)
+            ProposalType::PSK => Proposal::Psk(PreSharedKeyProposal::mls_decode_with_context(
+                reader, context,
+            )?),
+            ProposalType::RE_INIT => {
+                Proposal::ReInit(ReInitProposal::mls_decode_with_context(reader, context)?)
             }
-            ProposalType::GROUP_CONTEXT_EXTENSIONS => {
-                Proposal::GroupContextExtensions(ExtensionList::mls_decode(reader)?)
+            ProposalType::EXTERNAL_INIT => {
+                Proposal::ExternalInit(ExternalInit::mls_decode_with_context(reader, context)?)
             }
+            ProposalType::GROUP_CONTEXT_EXTENSIONS => Proposal::GroupContextExtensions(
+                ExtensionList::mls_decode_with_context(reader, context)?,
+            ),
             #[cfg(feature = "custom_proposal")]
             custom => Proposal::Custom(CustomProposal {
                 proposal_type: custom,
-                data: mls_rs_codec::byte_vec::mls_decode(reader)?,
+                data: mls_rs_codec::byte_vec::mls_decode_with_context(reader, context)?,
             }),
             // TODO fix test dependency on openssl loading codec with default features
             #[cfg(not(feature = "custom_proposal"))]
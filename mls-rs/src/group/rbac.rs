@@ -0,0 +1,901 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+use core::fmt::{self, Debug};
+use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+use mls_rs_core::{
+    error::{AnyError, IntoAnyError},
+    extension::{ExtensionList, ExtensionType, MlsCodecExtension},
+    group::ProposalType,
+    identity::IdentityProvider,
+};
+
+#[cfg(mls_build_async)]
+use alloc::boxed::Box;
+
+use crate::{client::MlsError, client_config::ClientConfig};
+
+use super::{
+    mls_rules::{CommitDirection, CommitOptions, CommitSource, EncryptionOptions, MlsRules},
+    proposal::{AddProposal, Proposal, ReInitProposal, RemoveProposal},
+    proposal_filter::{ProposalBundle, ProposalInfo, ProposalSource},
+    CommitOutput, Group, Roster, Sender,
+};
+
+/// Extension type of [`RolesExt`], taken from the range reserved for private
+/// use.
+pub const ROLES_EXTENSION_TYPE: ExtensionType = ExtensionType::new(0xff00);
+
+/// Role of a member identity in a group using [`RbacMlsRules`].
+///
+/// Roles are ordered from least to most privileged.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, MlsSize, MlsEncode, MlsDecode,
+)]
+#[repr(u8)]
+pub enum Role {
+    Member = 1u8,
+    Moderator = 2u8,
+    Admin = 3u8,
+}
+
+/// Role assigned to a member identity, as returned by
+/// [`IdentityProvider::identity`].
+#[derive(Clone, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
+pub struct RoleAssignment {
+    #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    pub identity: Vec<u8>,
+    pub role: Role,
+}
+
+impl Debug for RoleAssignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RoleAssignment")
+            .field(
+                "identity",
+                &mls_rs_core::debug::pretty_bytes(&self.identity),
+            )
+            .field("role", &self.role)
+            .finish()
+    }
+}
+
+/// Group context extension listing the roles of member identities.
+///
+/// Identities that are not listed have the [`Role::Member`] role. All
+/// members must list [`ROLES_EXTENSION_TYPE`] in their capabilities for
+/// the extension to be set.
+#[derive(Clone, Debug, Default, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
+pub struct RolesExt {
+    assignments: Vec<RoleAssignment>,
+}
+
+impl RolesExt {
+    /// Create an extension without any role assignment.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assign `role` to `identity`, replacing its previous role.
+    pub fn with_role(mut self, identity: Vec<u8>, role: Role) -> Self {
+        self.set_role(identity, role);
+        self
+    }
+
+    /// Assign `role` to `identity`, replacing its previous role.
+    ///
+    /// Assigning [`Role::Member`] removes the identity from the list.
+    pub fn set_role(&mut self, identity: Vec<u8>, role: Role) {
+        self.remove(&identity);
+
+        if role != Role::Member {
+            self.assignments.push(RoleAssignment { identity, role });
+        }
+    }
+
+    /// Remove the role assigned to `identity`, making it a
+    /// [`Role::Member`].
+    pub fn remove(&mut self, identity: &[u8]) {
+        self.assignments
+            .retain(|assignment| assignment.identity != identity);
+    }
+
+    /// Role of `identity`.
+    pub fn role_of(&self, identity: &[u8]) -> Role {
+        self.assignments
+            .iter()
+            .find(|assignment| assignment.identity == identity)
+            .map_or(Role::Member, |assignment| assignment.role)
+    }
+
+    /// Identities with an assigned role.
+    pub fn assignments(&self) -> &[RoleAssignment] {
+        &self.assignments
+    }
+}
+
+impl MlsCodecExtension for RolesExt {
+    fn extension_type() -> ExtensionType {
+        ROLES_EXTENSION_TYPE
+    }
+}
+
+/// Minimum role required to send each kind of restricted proposal.
+///
+/// Update and pre-shared key proposals are not restricted and any member
+/// may remove itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RolePermissions {
+    pub add: Role,
+    pub remove: Role,
+    pub group_context_extensions: Role,
+    pub reinit: Role,
+}
+
+impl Default for RolePermissions {
+    fn default() -> Self {
+        Self {
+            add: Role::Moderator,
+            remove: Role::Moderator,
+            group_context_extensions: Role::Admin,
+            reinit: Role::Admin,
+        }
+    }
+}
+
+impl RolePermissions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_add(self, add: Role) -> Self {
+        Self { add, ..self }
+    }
+
+    pub fn with_remove(self, remove: Role) -> Self {
+        Self { remove, ..self }
+    }
+
+    pub fn with_group_context_extensions(self, group_context_extensions: Role) -> Self {
+        Self {
+            group_context_extensions,
+            ..self
+        }
+    }
+
+    pub fn with_reinit(self, reinit: Role) -> Self {
+        Self { reinit, ..self }
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[non_exhaustive]
+pub enum RbacError {
+    #[cfg_attr(
+        feature = "std",
+        error("a member with role {role:?} may not send {proposal_type:?} proposals")
+    )]
+    Unauthorized {
+        role: Role,
+        proposal_type: ProposalType,
+    },
+    #[cfg_attr(feature = "std", error("a {sender:?} may not remove a {target:?}"))]
+    RemovalOfHigherRole { sender: Role, target: Role },
+    #[cfg_attr(
+        feature = "std",
+        error("the commit does not revoke the roles of removed members")
+    )]
+    StaleRoleAssignment,
+    #[cfg_attr(feature = "std", error(transparent))]
+    IdentityProviderError(AnyError),
+    #[cfg_attr(feature = "std", error(transparent))]
+    ExtensionError(AnyError),
+}
+
+impl IntoAnyError for RbacError {
+    #[cfg(feature = "std")]
+    fn into_dyn_error(self) -> Result<Box<dyn std::error::Error + Send + Sync>, Self> {
+        Ok(self.into())
+    }
+}
+
+/// MLS rules restricting proposals according to the roles listed in the
+/// [`RolesExt`] group context extension.
+///
+/// The role of a member is the role of its identity, as determined by the
+/// identity provider. Proposals from external senders are trusted, while
+/// new members have the [`Role::Member`] role. In addition to requiring the
+/// roles configured by [`RolePermissions`], a member may not remove a
+/// member with a higher role than its own.
+///
+/// When a commit removes the last member with a given identity, the role of
+/// that identity is revoked by the same commit. If the commit does not
+/// update the group context extensions, a proposal doing so is added
+/// automatically.
+///
+/// The group creator should list itself as [`Role::Admin`] in the
+/// extensions of the group, since without a role list every member has the
+/// [`Role::Member`] role.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct RbacMlsRules<Ip> {
+    pub identity_provider: Ip,
+    pub permissions: RolePermissions,
+    pub commit_options: CommitOptions,
+    pub encryption_options: EncryptionOptions,
+}
+
+impl<Ip> RbacMlsRules<Ip>
+where
+    Ip: IdentityProvider + Clone,
+{
+    /// Create new rules resolving member identities with
+    /// `identity_provider`, which should match the identity provider of the
+    /// client.
+    pub fn new(identity_provider: Ip) -> Self {
+        Self {
+            identity_provider,
+            permissions: Default::default(),
+            commit_options: Default::default(),
+            encryption_options: Default::default(),
+        }
+    }
+
+    /// Set the roles required by each kind of restricted proposal.
+    pub fn with_permissions(self, permissions: RolePermissions) -> Self {
+        Self {
+            permissions,
+            ..self
+        }
+    }
+
+    /// Set commit options.
+    pub fn with_commit_options(self, commit_options: CommitOptions) -> Self {
+        Self {
+            commit_options,
+            ..self
+        }
+    }
+
+    /// Set encryption options.
+    pub fn with_encryption_options(self, encryption_options: EncryptionOptions) -> Self {
+        Self {
+            encryption_options,
+            ..self
+        }
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn identity(
+        &self,
+        signing_identity: &mls_rs_core::identity::SigningIdentity,
+        extensions: &ExtensionList,
+    ) -> Result<Vec<u8>, RbacError> {
+        self.identity_provider
+            .identity(signing_identity, extensions)
+            .await
+            .map_err(|e| RbacError::IdentityProviderError(e.into_any_error()))
+    }
+}
+
+/// State used to check the senders of proposals against their roles.
+struct RoleCheck<'a> {
+    roles: &'a RolesExt,
+    permissions: RolePermissions,
+    direction: CommitDirection,
+    identities: BTreeMap<u32, Vec<u8>>,
+    new_member: Option<Vec<u8>>,
+}
+
+impl RoleCheck<'_> {
+    fn sender_identity(&self, sender: &Sender) -> Option<&[u8]> {
+        match sender {
+            Sender::Member(index) => self.identities.get(index).map(Vec::as_slice),
+            Sender::NewMemberCommit => self.new_member.as_deref(),
+            #[cfg(feature = "by_ref_proposal")]
+            _ => None,
+        }
+    }
+
+    fn sender_role(&self, sender: &Sender) -> Role {
+        match sender {
+            #[cfg(feature = "by_ref_proposal")]
+            Sender::External(_) => Role::Admin,
+            _ => self
+                .sender_identity(sender)
+                .map_or(Role::Member, |identity| self.roles.role_of(identity)),
+        }
+    }
+
+    /// Returns `Ok(false)` if an unauthorized proposal should be filtered
+    /// out instead of failing the commit.
+    fn authorize<T>(
+        &self,
+        proposal: &ProposalInfo<T>,
+        result: Result<(), RbacError>,
+    ) -> Result<bool, RbacError> {
+        match result {
+            Ok(()) => Ok(true),
+            Err(_) if self.direction == CommitDirection::Send && proposal.is_by_reference() => {
+                Ok(false)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn require<T>(
+        &self,
+        proposal: &ProposalInfo<T>,
+        proposal_type: ProposalType,
+        required: Role,
+    ) -> Result<bool, RbacError> {
+        let role = self.sender_role(&proposal.sender);
+
+        let result = if role >= required {
+            Ok(())
+        } else {
+            Err(RbacError::Unauthorized {
+                role,
+                proposal_type,
+            })
+        };
+
+        self.authorize(proposal, result)
+    }
+
+    fn require_remove(&self, proposal: &ProposalInfo<RemoveProposal>) -> Result<bool, RbacError> {
+        let target = self.identities.get(&proposal.proposal.to_remove());
+
+        if target.is_some() && target.map(Vec::as_slice) == self.sender_identity(&proposal.sender) {
+            return Ok(true);
+        }
+
+        let role = self.sender_role(&proposal.sender);
+        let target_role = target.map_or(Role::Member, |identity| self.roles.role_of(identity));
+
+        let result = if role < self.permissions.remove {
+            Err(RbacError::Unauthorized {
+                role,
+                proposal_type: ProposalType::REMOVE,
+            })
+        } else if role < target_role {
+            Err(RbacError::RemovalOfHigherRole {
+                sender: role,
+                target: target_role,
+            })
+        } else {
+            Ok(())
+        };
+
+        self.authorize(proposal, result)
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+impl<Ip> MlsRules for RbacMlsRules<Ip>
+where
+    Ip: IdentityProvider + Clone,
+{
+    type Error = RbacError;
+
+    async fn filter_proposals(
+        &self,
+        direction: CommitDirection,
+        source: CommitSource,
+        current_roster: &Roster,
+        extension_list: &ExtensionList,
+        mut proposals: ProposalBundle,
+    ) -> Result<ProposalBundle, Self::Error> {
+        let restricted = !proposals.add_proposals().is_empty()
+            || !proposals.remove_proposals().is_empty()
+            || !proposals.reinit_proposals().is_empty()
+            || !proposals.group_context_ext_proposals().is_empty();
+
+        if !restricted {
+            return Ok(proposals);
+        }
+
+        let roles = extension_list
+            .get_as::<RolesExt>()
+            .map_err(|e| RbacError::ExtensionError(e.into_any_error()))?
+            .unwrap_or_default();
+
+        let mut identities = BTreeMap::new();
+
+        for member in current_roster.members_iter() {
+            let identity = self
+                .identity(&member.signing_identity, extension_list)
+                .await?;
+
+            identities.insert(member.index, identity);
+        }
+
+        let new_member = match &source {
+            CommitSource::NewMember(signing_identity) => {
+                Some(self.identity(signing_identity, extension_list).await?)
+            }
+            CommitSource::ExistingMember(_) => None,
+        };
+
+        let check = RoleCheck {
+            roles: &roles,
+            permissions: self.permissions,
+            direction,
+            identities,
+            new_member,
+        };
+
+        proposals.retain_by_type::<AddProposal, _, _>(|p| {
+            check.require(p, ProposalType::ADD, check.permissions.add)
+        })?;
+
+        proposals.retain_by_type::<ReInitProposal, _, _>(|p| {
+            check.require(p, ProposalType::RE_INIT, check.permissions.reinit)
+        })?;
+
+        proposals.retain_by_type::<RemoveProposal, _, _>(|p| check.require_remove(p))?;
+
+        let stale = self
+            .stale_identities(&check, &proposals, extension_list)
+            .await?;
+
+        let mut updated_extensions = extension_list.clone();
+
+        if !stale.is_empty() {
+            updated_extensions
+                .set_from(without_identities(roles.clone(), &stale))
+                .map_err(|e| RbacError::ExtensionError(e.into_any_error()))?;
+        }
+
+        proposals.retain_by_type::<ExtensionList, _, _>(|p| {
+            // Revoking the roles of removed members does not require any role.
+            if !stale.is_empty() && p.proposal == updated_extensions {
+                return Ok(true);
+            }
+
+            check.require(
+                p,
+                ProposalType::GROUP_CONTEXT_EXTENSIONS,
+                check.permissions.group_context_extensions,
+            )
+        })?;
+
+        if stale.is_empty() {
+            return Ok(proposals);
+        }
+
+        let Some(proposal) = proposals.group_context_ext_proposals().first().cloned() else {
+            return match (direction, source) {
+                (CommitDirection::Send, CommitSource::ExistingMember(committer)) => {
+                    proposals.add(
+                        Proposal::GroupContextExtensions(updated_extensions),
+                        Sender::Member(committer.index),
+                        ProposalSource::ByValue,
+                    );
+
+                    Ok(proposals)
+                }
+                _ => Err(RbacError::StaleRoleAssignment),
+            };
+        };
+
+        let Some(new_roles) = proposal
+            .proposal
+            .get_as::<RolesExt>()
+            .map_err(|e| RbacError::ExtensionError(e.into_any_error()))?
+        else {
+            return Ok(proposals);
+        };
+
+        if !new_roles
+            .assignments()
+            .iter()
+            .any(|assignment| stale.contains(&assignment.identity))
+        {
+            return Ok(proposals);
+        }
+
+        if direction == CommitDirection::Receive || !proposal.is_by_value() {
+            return Err(RbacError::StaleRoleAssignment);
+        }
+
+        let mut extensions = proposal.proposal;
+
+        extensions
+            .set_from(without_identities(new_roles, &stale))
+            .map_err(|e| RbacError::ExtensionError(e.into_any_error()))?;
+
+        proposals.remove::<ExtensionList>(0);
+
+        proposals.add(
+            Proposal::GroupContextExtensions(extensions),
+            proposal.sender,
+            ProposalSource::ByValue,
+        );
+
+        Ok(proposals)
+    }
+
+    fn commit_options(
+        &self,
+        _: &Roster,
+        _: &ExtensionList,
+        _: &ProposalBundle,
+    ) -> Result<CommitOptions, Self::Error> {
+        Ok(self.commit_options)
+    }
+
+    fn encryption_options(
+        &self,
+        _: &Roster,
+        _: &ExtensionList,
+    ) -> Result<EncryptionOptions, Self::Error> {
+        Ok(self.encryption_options)
+    }
+}
+
+impl<Ip> RbacMlsRules<Ip>
+where
+    Ip: IdentityProvider + Clone,
+{
+    /// Identities with an assigned role whose members are all removed by
+    /// `proposals`.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn stale_identities(
+        &self,
+        check: &RoleCheck<'_>,
+        proposals: &ProposalBundle,
+        extension_list: &ExtensionList,
+    ) -> Result<BTreeSet<Vec<u8>>, RbacError> {
+        let removed = proposals
+            .remove_proposals()
+            .iter()
+            .map(|p| p.proposal.to_remove())
+            .collect::<BTreeSet<_>>();
+
+        let remaining = check
+            .identities
+            .iter()
+            .filter(|(index, _)| !removed.contains(index))
+            .map(|(_, identity)| identity)
+            .chain(&check.new_member)
+            .collect::<BTreeSet<_>>();
+
+        let mut stale = removed
+            .iter()
+            .filter_map(|index| check.identities.get(index))
+            .filter(|identity| check.roles.role_of(identity) != Role::Member)
+            .filter(|identity| !remaining.contains(identity))
+            .cloned()
+            .collect::<BTreeSet<_>>();
+
+        // A member may be removed and added back by the same commit.
+        for p in proposals.add_proposals() {
+            if stale.is_empty() {
+                break;
+            }
+
+            let identity = self
+                .identity(p.proposal.signing_identity(), extension_list)
+                .await?;
+
+            stale.remove(&identity);
+        }
+
+        Ok(stale)
+    }
+}
+
+fn without_identities(mut roles: RolesExt, identities: &BTreeSet<Vec<u8>>) -> RolesExt {
+    roles
+        .assignments
+        .retain(|assignment| !identities.contains(&assignment.identity));
+
+    roles
+}
+
+impl<C> Group<C>
+where
+    C: ClientConfig + Clone,
+{
+    /// Roles listed in the group context, as used by [`RbacMlsRules`].
+    pub fn roles(&self) -> Result<RolesExt, MlsError> {
+        Ok(self
+            .context()
+            .extensions()
+            .get_as::<RolesExt>()?
+            .unwrap_or_default())
+    }
+
+    /// Create a commit assigning `role` to the member identity `identity`.
+    ///
+    /// As with [`Group::commit`], the commit is pending until it is applied.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn grant_role(
+        &mut self,
+        identity: Vec<u8>,
+        role: Role,
+    ) -> Result<CommitOutput, MlsError> {
+        let roles = self.roles()?.with_role(identity, role);
+        self.commit_roles(roles).await
+    }
+
+    /// Create a commit revoking the role of the member identity `identity`,
+    /// making it a [`Role::Member`].
+    ///
+    /// As with [`Group::commit`], the commit is pending until it is applied.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn revoke_role(&mut self, identity: &[u8]) -> Result<CommitOutput, MlsError> {
+        let mut roles = self.roles()?;
+        roles.remove(identity);
+        self.commit_roles(roles).await
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn commit_roles(&mut self, roles: RolesExt) -> Result<CommitOutput, MlsError> {
+        let mut extensions = self.context().extensions().clone();
+        extensions.set_from(roles)?;
+
+        self.commit_builder()
+            .set_group_context_ext(extensions)?
+            .build()
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use assert_matches::assert_matches;
+    use mls_rs_core::extension::ExtensionList;
+
+    use crate::{
+        client::{test_utils::TEST_CIPHER_SUITE, MlsError},
+        client_builder::test_utils::TestClientConfig,
+        client_builder::{test_utils::TestClientBuilder, WithMlsRules},
+        group::mls_rules::{DefaultMlsRules, MlsRules},
+        identity::basic::BasicIdentityProvider,
+        Client, Group, MlsMessage,
+    };
+
+    use super::{RbacMlsRules, Role, RolesExt, ROLES_EXTENSION_TYPE};
+
+    type RbacClient = Client<WithMlsRules<RbacMlsRules<BasicIdentityProvider>, TestClientConfig>>;
+    type RbacGroup = Group<WithMlsRules<RbacMlsRules<BasicIdentityProvider>, TestClientConfig>>;
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn test_client<R: MlsRules + Clone>(
+        name: &str,
+        mls_rules: R,
+    ) -> Client<WithMlsRules<R, TestClientConfig>> {
+        TestClientBuilder::new_for_test()
+            .with_random_signing_identity(name, TEST_CIPHER_SUITE)
+            .await
+            .extension_type(ROLES_EXTENSION_TYPE)
+            .mls_rules(mls_rules)
+            .build()
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn rbac_client(name: &str) -> RbacClient {
+        test_client(name, RbacMlsRules::new(BasicIdentityProvider::new())).await
+    }
+
+    /// Create a group administered by alice, with bob as a member.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn test_groups() -> (RbacGroup, RbacGroup) {
+        let mut extensions = ExtensionList::new();
+
+        extensions
+            .set_from(RolesExt::new().with_role(b"alice".to_vec(), Role::Admin))
+            .unwrap();
+
+        let mut alice = rbac_client("alice")
+            .await
+            .create_group(extensions)
+            .await
+            .unwrap();
+
+        let (bob, _) = join(&mut alice, &rbac_client("bob").await).await;
+
+        (alice, bob)
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn join(group: &mut RbacGroup, client: &RbacClient) -> (RbacGroup, MlsMessage) {
+        let key_package = client.generate_key_package_message().await.unwrap();
+
+        let output = group
+            .commit_builder()
+            .add_member(key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        group.apply_pending_commit().await.unwrap();
+
+        let (new_group, _) = client
+            .join_group(None, &output.welcome_messages[0])
+            .await
+            .unwrap();
+
+        (new_group, output.commit_message)
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn members_cannot_add_or_remove() {
+        let (_, mut bob) = test_groups().await;
+
+        let carol = rbac_client("carol").await;
+        let key_package = carol.generate_key_package_message().await.unwrap();
+
+        let res = bob
+            .commit_builder()
+            .add_member(key_package)
+            .unwrap()
+            .build()
+            .await;
+
+        assert_matches!(res, Err(MlsError::MlsRulesError(_)));
+
+        let res = bob.commit_builder().remove_member(0).unwrap().build().await;
+
+        assert_matches!(res, Err(MlsError::MlsRulesError(_)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn moderators_can_add_but_not_change_roles_or_remove_admins() {
+        let (mut alice, mut bob) = test_groups().await;
+
+        let output = alice
+            .grant_role(b"bob".to_vec(), Role::Moderator)
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+
+        bob.process_incoming_message(output.commit_message)
+            .await
+            .unwrap();
+
+        assert_eq!(bob.roles().unwrap().role_of(b"bob"), Role::Moderator);
+
+        let (carol, _) = join(&mut bob, &rbac_client("carol").await).await;
+        assert_eq!(carol.roster().members_iter().count(), 3);
+
+        let res = bob.grant_role(b"carol".to_vec(), Role::Admin).await;
+        assert_matches!(res, Err(MlsError::MlsRulesError(_)));
+
+        let res = bob.commit_builder().remove_member(0).unwrap().build().await;
+        assert_matches!(res, Err(MlsError::MlsRulesError(_)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn receivers_reject_unauthorized_commits() {
+        let mut extensions = ExtensionList::new();
+
+        extensions
+            .set_from(RolesExt::new().with_role(b"alice".to_vec(), Role::Admin))
+            .unwrap();
+
+        let mut alice = rbac_client("alice")
+            .await
+            .create_group(extensions)
+            .await
+            .unwrap();
+
+        // Bob does not enforce the roles when committing.
+        let bob_client = test_client("bob", DefaultMlsRules::new()).await;
+        let key_package = bob_client.generate_key_package_message().await.unwrap();
+
+        let output = alice
+            .commit_builder()
+            .add_member(key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+
+        let (mut bob, _) = bob_client
+            .join_group(None, &output.welcome_messages[0])
+            .await
+            .unwrap();
+
+        let key_package = rbac_client("carol")
+            .await
+            .generate_key_package_message()
+            .await
+            .unwrap();
+
+        let output = bob
+            .commit_builder()
+            .add_member(key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        let res = alice.process_incoming_message(output.commit_message).await;
+
+        assert_matches!(res, Err(MlsError::MlsRulesError(_)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn removing_admin_revokes_its_role() {
+        let (mut alice, mut bob) = test_groups().await;
+        let (mut carol, commit) = join(&mut alice, &rbac_client("carol").await).await;
+        bob.process_incoming_message(commit).await.unwrap();
+
+        let output = alice
+            .grant_role(b"bob".to_vec(), Role::Admin)
+            .await
+            .unwrap();
+        alice.apply_pending_commit().await.unwrap();
+
+        for group in [&mut bob, &mut carol] {
+            group
+                .process_incoming_message(output.commit_message.clone())
+                .await
+                .unwrap();
+        }
+
+        let output = bob
+            .commit_builder()
+            .remove_member(alice.current_member_index())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        bob.apply_pending_commit().await.unwrap();
+
+        carol
+            .process_incoming_message(output.commit_message)
+            .await
+            .unwrap();
+
+        for group in [&bob, &carol] {
+            let roles = group.roles().unwrap();
+
+            let identities = roles
+                .assignments()
+                .iter()
+                .map(|assignment| assignment.identity.as_slice())
+                .collect::<Vec<_>>();
+
+            assert_eq!(identities, [b"bob".as_slice()]);
+            assert_eq!(roles.role_of(b"alice"), Role::Member);
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn granting_member_role_revokes_role() {
+        let (mut alice, _) = test_groups().await;
+
+        alice
+            .grant_role(b"bob".to_vec(), Role::Moderator)
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+        alice
+            .grant_role(b"bob".to_vec(), Role::Member)
+            .await
+            .unwrap();
+        alice.apply_pending_commit().await.unwrap();
+
+        assert_eq!(alice.roles().unwrap().assignments().len(), 1);
+        assert_eq!(alice.roles().unwrap().role_of(b"bob"), Role::Member);
+    }
+}
@@ -25,9 +25,10 @@ struct ResumptionGroupParameters<'a> {
     extensions: &'a ExtensionList,
 }
 
+#[derive(Clone)]
 pub struct ReinitClient<C: ClientConfig + Clone> {
-    client: Client<C>,
-    reinit: ReInitProposal,
+    pub(super) client: Client<C>,
+    pub(super) reinit: ReInitProposal,
     psk_input: PskSecretInput,
 }
 
@@ -10,10 +10,14 @@ use core::{
 
 use zeroize::Zeroizing;
 
-use crate::{client::MlsError, tree_kem::math::TreeIndex, CipherSuiteProvider};
+use crate::{
+    client::MlsError,
+    tree_kem::{math::TreeIndex, node::NodeIndex},
+    CipherSuiteProvider,
+};
 
-use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
-use mls_rs_core::error::IntoAnyError;
+use mls_rs_codec::{DecodeContext, MlsDecode, MlsEncode, MlsSize};
+use mls_rs_core::{error::IntoAnyError, group::NodeUpdate};
 
 #[cfg(feature = "std")]
 use std::collections::HashMap;
@@ -25,6 +29,24 @@ use super::key_schedule::kdf_expand_with_label;
 
 pub(crate) const MAX_RATCHET_BACK_HISTORY: u32 = 1024;
 
+/// Bounds on the generations for which a receiver derives message keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct SenderRatchetLimits {
+    /// Number of generations behind the newest requested one whose skipped keys are kept.
+    pub out_of_order_tolerance: u32,
+    /// Number of generations a requested key may be ahead of the next expected one.
+    pub maximum_forward_distance: u32,
+}
+
+impl Default for SenderRatchetLimits {
+    fn default() -> Self {
+        Self {
+            out_of_order_tolerance: u32::MAX,
+            maximum_forward_distance: MAX_RATCHET_BACK_HISTORY,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, MlsSize, MlsEncode, MlsDecode)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
@@ -109,6 +131,14 @@ impl<T: TreeIndex> TreeSecretsVec<T> {
     fn take_node(&mut self, index: &T) -> Option<SecretTreeNode> {
         self.inner.remove(index)
     }
+
+    fn get_node(&self, index: &T) -> Option<&SecretTreeNode> {
+        self.inner.get(index)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&T, &SecretTreeNode)> {
+        self.inner.iter()
+    }
 }
 
 #[cfg(not(feature = "std"))]
@@ -125,6 +155,14 @@ impl<T: TreeIndex> TreeSecretsVec<T> {
         self.find_node(index).map(|i| self.inner.remove(i).1)
     }
 
+    fn get_node(&self, index: &T) -> Option<&SecretTreeNode> {
+        self.find_node(index).map(|i| &self.inner[i].1)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&T, &SecretTreeNode)> {
+        self.inner.iter().map(|(index, node)| (index, node))
+    }
+
     fn find_node(&self, index: &T) -> Option<usize> {
         use itertools::Itertools;
 
@@ -151,6 +189,62 @@ impl<T: TreeIndex> SecretTree<T> {
     }
 }
 
+/// Known secrets of a secret tree, split off the tree when the group state is
+/// written incrementally.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct SecretTreeNodes(TreeSecretsVec<NodeIndex>);
+
+impl SecretTreeNodes {
+    /// Encoded nodes that differ from `old`, ordered by index. Nodes of `old`
+    /// that were removed are returned without data.
+    pub(crate) fn changes(&self, old: &Self) -> Result<Vec<NodeUpdate>, MlsError> {
+        let updated = self
+            .0
+            .iter()
+            .filter(|&(index, node)| old.0.get_node(index) != Some(node))
+            .map(|(&index, node)| {
+                Ok(NodeUpdate {
+                    index,
+                    data: Some(node.mls_encode_to_vec()?),
+                })
+            });
+
+        let removed = old
+            .0
+            .iter()
+            .filter(|(index, _)| self.0.get_node(index).is_none())
+            .map(|(&index, _)| Ok(NodeUpdate { index, data: None }));
+
+        let mut changes = updated
+            .chain(removed)
+            .collect::<Result<Vec<_>, MlsError>>()?;
+
+        changes.sort_by_key(|change| change.index);
+
+        Ok(changes)
+    }
+
+    pub(crate) fn decode(nodes: Vec<(u32, Vec<u8>)>) -> Result<Self, MlsError> {
+        let mut secrets = TreeSecretsVec::default();
+
+        for (index, data) in nodes {
+            secrets.set_node(index, SecretTreeNode::mls_decode(&mut &*data)?);
+        }
+
+        Ok(Self(secrets))
+    }
+}
+
+impl SecretTree<NodeIndex> {
+    pub(crate) fn take_nodes(&mut self) -> SecretTreeNodes {
+        SecretTreeNodes(core::mem::take(&mut self.known_secrets))
+    }
+
+    pub(crate) fn set_nodes(&mut self, nodes: SecretTreeNodes) {
+        self.known_secrets = nodes.0;
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, MlsSize, MlsEncode, MlsDecode)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SecretRatchets {
@@ -165,16 +259,17 @@ impl SecretRatchets {
         cipher_suite_provider: &P,
         generation: u32,
         key_type: KeyType,
+        limits: SenderRatchetLimits,
     ) -> Result<MessageKeyData, MlsError> {
         match key_type {
             KeyType::Handshake => {
                 self.handshake
-                    .get_message_key(cipher_suite_provider, generation)
+                    .get_message_key(cipher_suite_provider, generation, limits)
                     .await
             }
             KeyType::Application => {
                 self.application
-                    .get_message_key(cipher_suite_provider, generation)
+                    .get_message_key(cipher_suite_provider, generation, limits)
                     .await
             }
         }
@@ -284,6 +379,7 @@ impl<T: TreeIndex> SecretTree<T> {
         Ok(res)
     }
 
+    #[cfg_attr(not(feature = "secret_tree_access"), allow(dead_code))]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn message_key_generation<P: CipherSuiteProvider>(
         &mut self,
@@ -291,11 +387,30 @@ impl<T: TreeIndex> SecretTree<T> {
         leaf_index: T,
         key_type: KeyType,
         generation: u32,
+    ) -> Result<MessageKeyData, MlsError> {
+        self.message_key_generation_with_limits(
+            cipher_suite,
+            leaf_index,
+            key_type,
+            generation,
+            SenderRatchetLimits::default(),
+        )
+        .await
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub(crate) async fn message_key_generation_with_limits<P: CipherSuiteProvider>(
+        &mut self,
+        cipher_suite: &P,
+        leaf_index: T,
+        key_type: KeyType,
+        generation: u32,
+        limits: SenderRatchetLimits,
     ) -> Result<MessageKeyData, MlsError> {
         let mut ratchet = self.take_leaf_ratchet(cipher_suite, &leaf_index).await?;
 
         let res = ratchet
-            .message_key_generation(cipher_suite, generation, key_type)
+            .message_key_generation(cipher_suite, generation, key_type, limits)
             .await?;
 
         self.known_secrets
@@ -384,7 +499,10 @@ impl MlsSize for SecretKeyRatchet {
 
 #[cfg(feature = "out_of_order")]
 impl MlsEncode for SecretKeyRatchet {
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), mls_rs_codec::Error> {
+    fn mls_encode<W: mls_rs_codec::Writer + ?Sized>(
+        &self,
+        writer: &mut W,
+    ) -> Result<(), mls_rs_codec::Error> {
         mls_rs_codec::byte_vec::mls_encode(&self.secret, writer)?;
         self.generation.mls_encode(writer)?;
         mls_rs_codec::iter::mls_encode(self.history.values(), writer)
@@ -393,39 +511,53 @@ impl MlsEncode for SecretKeyRatchet {
 
 #[cfg(not(feature = "out_of_order"))]
 impl MlsEncode for SecretKeyRatchet {
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), mls_rs_codec::Error> {
+    fn mls_encode<W: mls_rs_codec::Writer + ?Sized>(
+        &self,
+        writer: &mut W,
+    ) -> Result<(), mls_rs_codec::Error> {
         mls_rs_codec::byte_vec::mls_encode(&self.secret, writer)?;
         self.generation.mls_encode(writer)
     }
 }
 
 impl MlsDecode for SecretKeyRatchet {
-    fn mls_decode(reader: &mut &[u8]) -> Result<Self, mls_rs_codec::Error> {
+    fn mls_decode_with_context(
+        reader: &mut &[u8],
+        context: &mut DecodeContext,
+    ) -> Result<Self, mls_rs_codec::Error> {
         Ok(Self {
-            secret: mls_rs_codec::byte_vec::mls_decode(reader)?,
-            generation: u32::mls_decode(reader)?,
+            secret: mls_rs_codec::byte_vec::mls_decode_with_context(reader, context)?,
+            generation: u32::mls_decode_with_context(reader, context)?,
             #[cfg(all(feature = "std", feature = "out_of_order"))]
-            history: mls_rs_codec::iter::mls_decode_collection(reader, |data| {
-                let mut items = HashMap::default();
-
-                while !data.is_empty() {
-                    let item = MessageKeyData::mls_decode(data)?;
-                    items.insert(item.generation, item);
-                }
-
-                Ok(items)
-            })?,
+            history: mls_rs_codec::iter::mls_decode_collection(
+                reader,
+                context,
+                |data, context| {
+                    let mut items = HashMap::default();
+
+                    while !data.is_empty() {
+                        let item = MessageKeyData::mls_decode_with_context(data, context)?;
+                        items.insert(item.generation, item);
+                    }
+
+                    Ok(items)
+                },
+            )?,
             #[cfg(all(not(feature = "std"), feature = "out_of_order"))]
-            history: mls_rs_codec::iter::mls_decode_collection(reader, |data| {
-                let mut items = alloc::collections::BTreeMap::default();
-
-                while !data.is_empty() {
-                    let item = MessageKeyData::mls_decode(data)?;
-                    items.insert(item.generation, item);
-                }
-
-                Ok(items)
-            })?,
+            history: mls_rs_codec::iter::mls_decode_collection(
+                reader,
+                context,
+                |data, context| {
+                    let mut items = alloc::collections::BTreeMap::default();
+
+                    while !data.is_empty() {
+                        let item = MessageKeyData::mls_decode_with_context(data, context)?;
+                        items.insert(item.generation, item);
+                    }
+
+                    Ok(items)
+                },
+            )?,
         })
     }
 }
@@ -459,6 +591,7 @@ impl SecretKeyRatchet {
         &mut self,
         cipher_suite_provider: &P,
         generation: u32,
+        limits: SenderRatchetLimits,
     ) -> Result<MessageKeyData, MlsError> {
         #[cfg(feature = "out_of_order")]
         if generation < self.generation {
@@ -474,7 +607,9 @@ impl SecretKeyRatchet {
             return Err(MlsError::KeyMissing(generation));
         }
 
-        let max_generation_allowed = self.generation + MAX_RATCHET_BACK_HISTORY;
+        let max_generation_allowed = self
+            .generation
+            .saturating_add(limits.maximum_forward_distance);
 
         if generation > max_generation_allowed {
             return Err(MlsError::InvalidFutureGeneration(generation));
@@ -486,9 +621,17 @@ impl SecretKeyRatchet {
         }
 
         #[cfg(feature = "out_of_order")]
-        while self.generation < generation {
-            let key_data = self.next_message_key(cipher_suite_provider).await?;
-            self.history.insert(key_data.generation, key_data);
+        {
+            let oldest_kept = generation.saturating_sub(limits.out_of_order_tolerance);
+            self.history.retain(|kept, _| *kept >= oldest_kept);
+
+            while self.generation < generation {
+                let key_data = self.next_message_key(cipher_suite_provider).await?;
+
+                if key_data.generation >= oldest_kept {
+                    self.history.insert(key_data.generation, key_data);
+                }
+            }
         }
 
         self.next_message_key(cipher_suite_provider).await
@@ -552,13 +695,16 @@ impl SecretKeyRatchet {
     }
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "conformance_util"))]
 pub(crate) mod test_utils {
     use alloc::{string::String, vec::Vec};
     use mls_rs_core::crypto::CipherSuiteProvider;
     use zeroize::Zeroizing;
 
-    use crate::{crypto::test_utils::try_test_cipher_suite_provider, tree_kem::math::TreeIndex};
+    use crate::tree_kem::math::TreeIndex;
+
+    #[cfg(test)]
+    use crate::crypto::test_utils::try_test_cipher_suite_provider;
 
     use super::{KeyType, SecretKeyRatchet, SecretTree};
 
@@ -589,12 +735,14 @@ pub(crate) mod test_utils {
         out: Vec<u8>,
     }
 
+    #[cfg(test)]
     #[derive(Debug, serde::Serialize, serde::Deserialize)]
     pub struct InteropTestCase {
         cipher_suite: u16,
         derive_tree_secret: RatchetInteropTestCase,
     }
 
+    #[cfg(test)]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn test_basic_crypto_test_vectors() {
         let test_cases: Vec<InteropTestCase> =
@@ -746,12 +894,18 @@ mod tests {
             let clone_2 = ratchet_clone.next_message_key(&provider).await.unwrap();
 
             // Going back in time should result in an error
-            let res = ratchet_clone.get_message_key(&provider, 0).await;
+            let res = ratchet_clone
+                .get_message_key(&provider, 0, SenderRatchetLimits::default())
+                .await;
             assert!(res.is_err());
 
             // Calling get key should be the same as calling next until hitting the desired generation
             let second_key = ratchet
-                .get_message_key(&provider, ratchet_clone.generation - 1)
+                .get_message_key(
+                    &provider,
+                    ratchet_clone.generation - 1,
+                    SenderRatchetLimits::default(),
+                )
                 .await
                 .unwrap();
 
@@ -794,12 +948,21 @@ mod tests {
         let mut ordered_keys = Vec::<MessageKeyData>::new();
 
         for i in 0..=MAX_RATCHET_BACK_HISTORY {
-            ordered_keys.push(ratchet.get_message_key(&provider, i).await.unwrap());
+            ordered_keys.push(
+                ratchet
+                    .get_message_key(&provider, i, SenderRatchetLimits::default())
+                    .await
+                    .unwrap(),
+            );
         }
 
         // Ask for a key at index MAX_RATCHET_BACK_HISTORY in the clone
         let last_key = ratchet_clone
-            .get_message_key(&provider, MAX_RATCHET_BACK_HISTORY)
+            .get_message_key(
+                &provider,
+                MAX_RATCHET_BACK_HISTORY,
+                SenderRatchetLimits::default(),
+            )
             .await
             .unwrap();
 
@@ -809,7 +972,12 @@ mod tests {
         let mut back_history_keys = Vec::<MessageKeyData>::new();
 
         for i in 0..MAX_RATCHET_BACK_HISTORY - 1 {
-            back_history_keys.push(ratchet_clone.get_message_key(&provider, i).await.unwrap());
+            back_history_keys.push(
+                ratchet_clone
+                    .get_message_key(&provider, i, SenderRatchetLimits::default())
+                    .await
+                    .unwrap(),
+            );
         }
 
         assert_eq!(
@@ -828,8 +996,13 @@ mod tests {
             .await
             .unwrap();
 
-        ratchet.get_message_key(&provider, 10).await.unwrap();
-        let res = ratchet.get_message_key(&provider, 9).await;
+        ratchet
+            .get_message_key(&provider, 10, SenderRatchetLimits::default())
+            .await
+            .unwrap();
+        let res = ratchet
+            .get_message_key(&provider, 9, SenderRatchetLimits::default())
+            .await;
         assert_matches!(res, Err(MlsError::KeyMissing(9)))
     }
 
@@ -843,7 +1016,11 @@ mod tests {
             .unwrap();
 
         let res = ratchet
-            .get_message_key(&provider, MAX_RATCHET_BACK_HISTORY + 1)
+            .get_message_key(
+                &provider,
+                MAX_RATCHET_BACK_HISTORY + 1,
+                SenderRatchetLimits::default(),
+            )
             .await;
 
         let invalid_generation = MAX_RATCHET_BACK_HISTORY + 1;
@@ -855,6 +1032,53 @@ mod tests {
         )
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn maximum_forward_distance_is_configurable() {
+        let provider = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+
+        let mut ratchet = SecretKeyRatchet::new(&provider, &[0u8; 32], KeyType::Handshake)
+            .await
+            .unwrap();
+
+        let limits = SenderRatchetLimits {
+            maximum_forward_distance: 5,
+            ..Default::default()
+        };
+
+        let res = ratchet.get_message_key(&provider, 6, limits).await;
+        assert_matches!(res, Err(MlsError::InvalidFutureGeneration(6)));
+
+        ratchet.get_message_key(&provider, 5, limits).await.unwrap();
+    }
+
+    #[cfg(feature = "out_of_order")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn keys_beyond_out_of_order_tolerance_are_dropped() {
+        let provider = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+
+        let mut ratchet = SecretKeyRatchet::new(&provider, &[0u8; 32], KeyType::Handshake)
+            .await
+            .unwrap();
+
+        let limits = SenderRatchetLimits {
+            out_of_order_tolerance: 2,
+            ..Default::default()
+        };
+
+        ratchet.get_message_key(&provider, 5, limits).await.unwrap();
+
+        let res = ratchet.get_message_key(&provider, 2, limits).await;
+        assert_matches!(res, Err(MlsError::KeyMissing(2)));
+
+        ratchet.get_message_key(&provider, 3, limits).await.unwrap();
+        ratchet.get_message_key(&provider, 7, limits).await.unwrap();
+
+        let res = ratchet.get_message_key(&provider, 4, limits).await;
+        assert_matches!(res, Err(MlsError::KeyMissing(4)));
+
+        ratchet.get_message_key(&provider, 6, limits).await.unwrap();
+    }
+
     #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
     struct Ratchet {
         application_keys: Vec<Vec<u8>>,
@@ -962,41 +1186,76 @@ mod tests {
     }
 }
 
-#[cfg(all(test, feature = "rfc_compliant", feature = "std"))]
-mod interop_tests {
-    #[cfg(not(mls_build_async))]
-    use mls_rs_core::crypto::{CipherSuite, CipherSuiteProvider};
+#[cfg(all(
+    any(test, feature = "conformance_util"),
+    feature = "rfc_compliant",
+    feature = "std"
+))]
+pub(crate) mod interop_tests {
+    use alloc::vec::Vec;
+    use mls_rs_core::crypto::CipherSuiteProvider;
     use zeroize::Zeroizing;
 
-    use crate::{
-        crypto::test_utils::try_test_cipher_suite_provider,
-        group::{ciphertext_processor::InteropSenderData, secret_tree::KeyType},
-    };
+    use crate::group::{ciphertext_processor::InteropSenderData, secret_tree::KeyType};
+
+    #[cfg(test)]
+    use crate::crypto::test_utils::try_test_cipher_suite_provider;
+
+    #[cfg(all(test, not(mls_build_async)))]
+    use mls_rs_core::crypto::CipherSuite;
 
     use super::SecretTree;
 
+    #[cfg(test)]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn interop_test_vector() {
         // The test vector can be found here https://github.com/mlswg/mls-implementations/blob/main/test-vectors/secret-tree.json
         let test_cases = load_interop_test_cases();
 
         for case in test_cases {
-            let Some(cs) = try_test_cipher_suite_provider(case.cipher_suite) else {
-                continue;
-            };
+            if let Some(cs) = try_test_cipher_suite_provider(case.cipher_suite) {
+                case.verify(&cs).await;
+            }
+        }
+    }
+
+    #[derive(Debug, serde::Serialize, serde::Deserialize)]
+    pub struct InteropTestCase {
+        pub cipher_suite: u16,
+        #[serde(with = "hex::serde")]
+        encryption_secret: Vec<u8>,
+        sender_data: InteropSenderData,
+        leaves: Vec<Vec<InteropLeaf>>,
+    }
+
+    #[derive(Debug, serde::Serialize, serde::Deserialize)]
+    struct InteropLeaf {
+        generation: u32,
+        #[serde(with = "hex::serde")]
+        application_key: Vec<u8>,
+        #[serde(with = "hex::serde")]
+        application_nonce: Vec<u8>,
+        #[serde(with = "hex::serde")]
+        handshake_key: Vec<u8>,
+        #[serde(with = "hex::serde")]
+        handshake_nonce: Vec<u8>,
+    }
 
-            case.sender_data.verify(&cs).await;
+    impl InteropTestCase {
+        #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+        pub async fn verify<P: CipherSuiteProvider>(&self, cs: &P) {
+            self.sender_data.verify(cs).await;
 
             let mut tree = SecretTree::new(
-                case.leaves.len() as u32,
-                Zeroizing::new(case.encryption_secret),
+                self.leaves.len() as u32,
+                Zeroizing::new(self.encryption_secret.clone()),
             );
 
-            for (index, leaves) in case.leaves.iter().enumerate() {
+            for (index, leaves) in self.leaves.iter().enumerate() {
                 for leaf in leaves.iter() {
                     let key = tree
                         .message_key_generation(
-                            &cs,
+                            cs,
                             (index as u32) * 2,
                             KeyType::Application,
                             leaf.generation,
@@ -1009,7 +1268,7 @@ mod interop_tests {
 
                     let key = tree
                         .message_key_generation(
-                            &cs,
+                            cs,
                             (index as u32) * 2,
                             KeyType::Handshake,
                             leaf.generation,
@@ -1024,33 +1283,12 @@ mod interop_tests {
         }
     }
 
-    #[derive(Debug, serde::Serialize, serde::Deserialize)]
-    struct InteropTestCase {
-        cipher_suite: u16,
-        #[serde(with = "hex::serde")]
-        encryption_secret: Vec<u8>,
-        sender_data: InteropSenderData,
-        leaves: Vec<Vec<InteropLeaf>>,
-    }
-
-    #[derive(Debug, serde::Serialize, serde::Deserialize)]
-    struct InteropLeaf {
-        generation: u32,
-        #[serde(with = "hex::serde")]
-        application_key: Vec<u8>,
-        #[serde(with = "hex::serde")]
-        application_nonce: Vec<u8>,
-        #[serde(with = "hex::serde")]
-        handshake_key: Vec<u8>,
-        #[serde(with = "hex::serde")]
-        handshake_nonce: Vec<u8>,
-    }
-
+    #[cfg(test)]
     fn load_interop_test_cases() -> Vec<InteropTestCase> {
         load_test_case_json!(secret_tree_interop, generate_test_vector())
     }
 
-    #[cfg(not(mls_build_async))]
+    #[cfg(all(test, not(mls_build_async)))]
     #[cfg_attr(coverage_nightly, coverage(off))]
     fn generate_test_vector() -> Vec<InteropTestCase> {
         let mut test_cases = vec![];
@@ -1108,7 +1346,7 @@ mod interop_tests {
         test_cases
     }
 
-    #[cfg(mls_build_async)]
+    #[cfg(all(test, mls_build_async))]
     fn generate_test_vector() -> Vec<InteropTestCase> {
         panic!("Tests cannot be generated in async mode");
     }
@@ -9,9 +9,12 @@ use crate::{
         key_schedule::KeySchedule, CommitGeneration, ConfirmationTag, Group, GroupContext,
         GroupState, InterimTranscriptHash, ReInitProposal, TreeKemPublic,
     },
-    tree_kem::TreeKemPrivate,
+    tree_kem::{node::NodeVec, TreeKemPrivate},
 };
 
+#[cfg(any(feature = "secret_tree_access", feature = "private_message"))]
+use crate::group::secret_tree::SecretTreeNodes;
+
 #[cfg(feature = "by_ref_proposal")]
 use crate::{
     crypto::{HpkePublicKey, HpkeSecretKey},
@@ -23,13 +26,19 @@ use super::proposal_cache::{CachedProposal, ProposalCache};
 
 use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
 
-use mls_rs_core::crypto::SignatureSecretKey;
 #[cfg(feature = "tree_index")]
 use mls_rs_core::identity::IdentityProvider;
+use mls_rs_core::{
+    crypto::{CryptoProvider, SignatureSecretKey},
+    error::IntoAnyError,
+    group::{GroupStateDelta, GroupStateStorage, NodeUpdate, StoredGroupState},
+};
 
 #[cfg(all(feature = "std", feature = "by_ref_proposal"))]
 use std::collections::HashMap;
 
+use alloc::vec;
+
 #[cfg(all(feature = "by_ref_proposal", not(feature = "std")))]
 use alloc::vec::Vec;
 
@@ -55,6 +64,162 @@ impl Snapshot {
     pub(crate) fn group_id(&self) -> &[u8] {
         &self.state.context.group_id
     }
+
+    pub(crate) fn clear_pending_commit(&mut self) {
+        self.pending_commit = None;
+    }
+
+    fn take_trees(&mut self) -> SnapshotTrees {
+        SnapshotTrees {
+            tree: core::mem::take(&mut self.state.public_tree).nodes,
+            #[cfg(any(feature = "secret_tree_access", feature = "private_message"))]
+            secret_tree: self.epoch_secrets.secret_tree.take_nodes(),
+        }
+    }
+
+    fn set_trees(&mut self, trees: SnapshotTrees) {
+        // Tree hashes are not stored with the nodes and are recomputed when needed.
+        self.state.public_tree = TreeKemPublic::from_nodes(trees.tree);
+
+        #[cfg(any(feature = "secret_tree_access", feature = "private_message"))]
+        self.epoch_secrets.secret_tree.set_nodes(trees.secret_tree);
+    }
+
+    /// Changes to write to storage since the trees in `written` were written,
+    /// along with the trees of this snapshot to compute the next delta from.
+    pub(crate) fn into_delta(
+        mut self,
+        written: Option<&SnapshotTrees>,
+    ) -> Result<(GroupStateDelta, SnapshotTrees), MlsError> {
+        let trees = self.take_trees();
+        let empty = SnapshotTrees::default();
+        let old = written.unwrap_or(&empty);
+
+        let tree_updates = (0..trees.tree.len().max(old.tree.len()))
+            .filter_map(|index| {
+                let node = trees.tree.get(index).and_then(Option::as_ref);
+                let old_node = old.tree.get(index).and_then(Option::as_ref);
+
+                (node != old_node).then_some((index, node))
+            })
+            .map(|(index, node)| {
+                Ok(NodeUpdate {
+                    index: index as u32,
+                    data: node.map(|node| node.mls_encode_to_vec()).transpose()?,
+                })
+            })
+            .collect::<Result<_, MlsError>>()?;
+
+        let delta = GroupStateDelta {
+            group_id: self.group_id().to_vec(),
+            state: SnapshotBase {
+                tree_size: trees.tree.len() as u32,
+                snapshot: self,
+            }
+            .mls_encode_to_vec()?,
+            reset_nodes: written.is_none(),
+            tree_updates,
+            #[cfg(any(feature = "secret_tree_access", feature = "private_message"))]
+            secret_tree_updates: trees.secret_tree.changes(&old.secret_tree)?,
+            #[cfg(not(any(feature = "secret_tree_access", feature = "private_message")))]
+            secret_tree_updates: Default::default(),
+        };
+
+        Ok((delta, trees))
+    }
+
+    /// Reassemble a snapshot written with [`Snapshot::into_delta`], along with
+    /// its trees as they are in storage.
+    ///
+    /// The nodes are stored separately from the group context, so the tree
+    /// hash of the reassembled ratchet tree is checked against the context.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub(crate) async fn from_delta_state<P: CryptoProvider>(
+        stored: StoredGroupState,
+        crypto_provider: P,
+    ) -> Result<(Self, SnapshotTrees), MlsError> {
+        let SnapshotBase {
+            mut snapshot,
+            tree_size,
+        } = SnapshotBase::mls_decode(&mut &*stored.state)?;
+
+        let mut tree = NodeVec::from(vec![None; tree_size as usize]);
+
+        for (index, data) in stored.tree_nodes {
+            *tree
+                .get_mut(index as usize)
+                .ok_or(MlsError::InvalidNodeIndex(index))? =
+                Some(MlsDecode::mls_decode(&mut &*data)?);
+        }
+
+        let trees = SnapshotTrees {
+            tree,
+            #[cfg(any(feature = "secret_tree_access", feature = "private_message"))]
+            secret_tree: SecretTreeNodes::decode(stored.secret_tree_nodes)?,
+        };
+
+        snapshot.set_trees(trees.clone());
+
+        let cipher_suite_provider =
+            cipher_suite_provider(crypto_provider, snapshot.state.context.cipher_suite)?;
+
+        let tree_hash = snapshot
+            .state
+            .public_tree
+            .tree_hash(&cipher_suite_provider)
+            .await?;
+
+        if tree_hash != snapshot.state.context.tree_hash {
+            return Err(MlsError::TreeHashMismatch);
+        }
+
+        Ok((snapshot, trees))
+    }
+
+    /// Fetch the snapshot of a group from `storage`, reassembling it if it was
+    /// written with deltas. In that case, the trees of the snapshot as they
+    /// are in storage are returned as well.
+    ///
+    /// Deltas are looked up even if `storage` no longer writes them, so that
+    /// groups written before incremental writes were turned off still load.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub(crate) async fn load<S: GroupStateStorage, P: CryptoProvider>(
+        storage: &S,
+        crypto_provider: P,
+        group_id: &[u8],
+    ) -> Result<Option<(Self, Option<SnapshotTrees>)>, MlsError> {
+        let stored = storage
+            .delta_state(group_id)
+            .await
+            .map_err(|e| MlsError::GroupStorageError(e.into_any_error()))?;
+
+        if let Some(stored) = stored {
+            let (snapshot, trees) = Self::from_delta_state(stored, crypto_provider).await?;
+            return Ok(Some((snapshot, Some(trees))));
+        }
+
+        Ok(storage
+            .state(group_id)
+            .await
+            .map_err(|e| MlsError::GroupStorageError(e.into_any_error()))?
+            .map(|snapshot| (snapshot, None)))
+    }
+}
+
+/// Ratchet tree and secret tree of a snapshot, which are written node by node
+/// when the group state storage supports deltas.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct SnapshotTrees {
+    tree: NodeVec,
+    #[cfg(any(feature = "secret_tree_access", feature = "private_message"))]
+    secret_tree: SecretTreeNodes,
+}
+
+/// Snapshot with its trees split off, as stored in [`GroupStateDelta::state`].
+#[derive(MlsEncode, MlsDecode, MlsSize)]
+struct SnapshotBase {
+    snapshot: Snapshot,
+    tree_size: u32,
 }
 
 #[derive(Debug, MlsEncode, MlsDecode, MlsSize, PartialEq, Clone)]
@@ -162,6 +327,25 @@ where
         self.state_repo.write_to_storage(self.snapshot()).await
     }
 
+    /// Load a group from the [`GroupStateStorage`] of `config`.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub(crate) async fn load(config: C, group_id: &[u8]) -> Result<Option<Self>, MlsError> {
+        let Some((snapshot, written_trees)) = Snapshot::load(
+            &config.group_state_storage(),
+            config.crypto_provider(),
+            group_id,
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let mut group = Group::from_snapshot(config, snapshot).await?;
+        group.state_repo.set_written_trees(written_trees);
+
+        Ok(Some(group))
+    }
+
     pub(crate) fn snapshot(&self) -> Snapshot {
         Snapshot {
             state: RawGroupState::export(&self.state),
@@ -275,6 +459,34 @@ mod tests {
         },
     };
 
+    #[cfg(feature = "std")]
+    use crate::{
+        client::{test_utils::test_client_with_key_pkg, MlsError},
+        client_builder::test_utils::TestClientBuilder,
+        identity::test_utils::get_test_signing_identity,
+        storage_provider::in_memory::InMemoryGroupStateStorage,
+    };
+
+    #[cfg(feature = "std")]
+    use assert_matches::assert_matches;
+
+    #[cfg(feature = "std")]
+    use alloc::vec::Vec;
+
+    #[cfg(feature = "std")]
+    use mls_rs_codec::{MlsDecode, MlsEncode};
+
+    #[cfg(feature = "std")]
+    use mls_rs_core::group::{
+        EpochRecord, GroupState, GroupStateDelta, GroupStateStorage, NodeUpdate, StoredGroupState,
+    };
+
+    #[cfg(feature = "std")]
+    use std::sync::{Arc, Mutex};
+
+    #[cfg(all(feature = "std", mls_build_async))]
+    use alloc::boxed::Box;
+
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     async fn snapshot_restore(group: TestGroup) {
         let snapshot = group.group.snapshot();
@@ -321,6 +533,238 @@ mod tests {
         snapshot_restore(group).await
     }
 
+    #[cfg(feature = "std")]
+    #[derive(Clone, Default)]
+    struct TestDeltaStorage {
+        stored: Arc<Mutex<Option<StoredGroupState>>>,
+        deltas: Arc<Mutex<Vec<GroupStateDelta>>>,
+    }
+
+    #[cfg(feature = "std")]
+    impl TestDeltaStorage {
+        fn last_delta(&self) -> GroupStateDelta {
+            self.deltas.lock().unwrap().last().unwrap().clone()
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn apply_node_updates(nodes: &mut Vec<(u32, Vec<u8>)>, updates: &[NodeUpdate]) {
+        for update in updates {
+            nodes.retain(|(index, _)| *index != update.index);
+
+            if let Some(data) = &update.data {
+                nodes.push((update.index, data.clone()));
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+    impl GroupStateStorage for TestDeltaStorage {
+        type Error = mls_rs_codec::Error;
+
+        async fn state<T>(&self, _group_id: &[u8]) -> Result<Option<T>, Self::Error>
+        where
+            T: GroupState + MlsEncode + MlsDecode,
+        {
+            Ok(None)
+        }
+
+        async fn epoch<T>(&self, _group_id: &[u8], _epoch_id: u64) -> Result<Option<T>, Self::Error>
+        where
+            T: EpochRecord + MlsEncode + MlsDecode,
+        {
+            Ok(None)
+        }
+
+        async fn write<ST, ET>(
+            &mut self,
+            _state: ST,
+            _epoch_inserts: Vec<ET>,
+            _epoch_updates: Vec<ET>,
+        ) -> Result<(), Self::Error>
+        where
+            ST: GroupState + MlsEncode + MlsDecode + Send + Sync,
+            ET: EpochRecord + MlsEncode + MlsDecode + Send + Sync,
+        {
+            panic!("full state written to storage supporting deltas")
+        }
+
+        async fn max_epoch_id(&self, _group_id: &[u8]) -> Result<Option<u64>, Self::Error> {
+            Ok(None)
+        }
+
+        fn supports_deltas(&self) -> bool {
+            true
+        }
+
+        async fn write_delta<ET>(
+            &mut self,
+            delta: GroupStateDelta,
+            _epoch_inserts: Vec<ET>,
+            _epoch_updates: Vec<ET>,
+        ) -> Result<(), Self::Error>
+        where
+            ET: EpochRecord + MlsEncode + MlsDecode + Send + Sync,
+        {
+            let mut stored = self.stored.lock().unwrap();
+            let stored = stored.get_or_insert_with(Default::default);
+
+            stored.state = delta.state.clone();
+
+            if delta.reset_nodes {
+                stored.tree_nodes.clear();
+                stored.secret_tree_nodes.clear();
+            }
+
+            apply_node_updates(&mut stored.tree_nodes, &delta.tree_updates);
+            apply_node_updates(&mut stored.secret_tree_nodes, &delta.secret_tree_updates);
+
+            self.deltas.lock().unwrap().push(delta);
+
+            Ok(())
+        }
+
+        async fn delta_state(
+            &self,
+            _group_id: &[u8],
+        ) -> Result<Option<StoredGroupState>, Self::Error> {
+            Ok(self.stored.lock().unwrap().clone())
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn group_can_be_written_and_loaded_with_deltas() {
+        let storage = TestDeltaStorage::default();
+
+        let alice = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .group_state_storage(storage.clone())
+            .build();
+
+        let mut group = alice.create_group(Default::default()).await.unwrap();
+
+        for name in ["bob", "carol", "dave"] {
+            let (_, key_package) =
+                test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, name).await;
+
+            group
+                .commit_builder()
+                .add_member(key_package)
+                .unwrap()
+                .build()
+                .await
+                .unwrap();
+
+            group.apply_pending_commit().await.unwrap();
+        }
+
+        group.write_to_storage().await.unwrap();
+        assert!(storage.last_delta().reset_nodes);
+
+        let mut loaded = alice.load_group(group.group_id()).await.unwrap();
+        assert!(Group::equal_group_state(&group, &loaded));
+
+        // The loaded group only writes the nodes on the direct path of the committer
+        loaded.commit(vec![]).await.unwrap();
+        loaded.apply_pending_commit().await.unwrap();
+        loaded.write_to_storage().await.unwrap();
+
+        let delta = storage.last_delta();
+        assert!(!delta.reset_nodes);
+        assert_eq!(delta.tree_updates.len(), 3);
+
+        let reloaded = alice.load_group(group.group_id()).await.unwrap();
+        assert!(Group::equal_group_state(&loaded, &reloaded));
+    }
+
+    #[cfg(feature = "std")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn loading_deltas_checks_tree_hash() {
+        let storage = TestDeltaStorage::default();
+
+        let alice = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .group_state_storage(storage.clone())
+            .build();
+
+        let mut group = alice.create_group(Default::default()).await.unwrap();
+
+        let (_, key_package) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        group
+            .commit_builder()
+            .add_member(key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        group.apply_pending_commit().await.unwrap();
+        group.write_to_storage().await.unwrap();
+
+        // Drop the leaf of bob from storage
+        storage
+            .stored
+            .lock()
+            .unwrap()
+            .as_mut()
+            .unwrap()
+            .tree_nodes
+            .retain(|(index, _)| *index != 2);
+
+        let res = alice.load_group(group.group_id()).await.map(|_| ());
+        assert_matches!(res, Err(MlsError::TreeHashMismatch));
+    }
+
+    #[cfg(feature = "std")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn group_written_with_deltas_loads_after_disabling_them() {
+        let storage = InMemoryGroupStateStorage::new().with_incremental_writes(true);
+
+        let (signing_identity, secret_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+
+        let alice = TestClientBuilder::new_for_test()
+            .signing_identity(
+                signing_identity.clone(),
+                secret_key.clone(),
+                TEST_CIPHER_SUITE,
+            )
+            .group_state_storage(storage.clone())
+            .build();
+
+        let mut group = alice.create_group(Default::default()).await.unwrap();
+        group.write_to_storage().await.unwrap();
+
+        let stored = storage.delta_state(group.group_id()).await.unwrap();
+        assert!(stored.is_some());
+
+        let storage = storage.with_incremental_writes(false);
+
+        let alice = TestClientBuilder::new_for_test()
+            .signing_identity(signing_identity, secret_key, TEST_CIPHER_SUITE)
+            .group_state_storage(storage.clone())
+            .build();
+
+        let mut loaded = alice.load_group(group.group_id()).await.unwrap();
+        assert!(Group::equal_group_state(&group, &loaded));
+
+        // The next write converts the group back to a full state
+        loaded.write_to_storage().await.unwrap();
+
+        let stored = storage.delta_state(group.group_id()).await.unwrap();
+        assert!(stored.is_none());
+
+        let reloaded = alice.load_group(group.group_id()).await.unwrap();
+        assert!(Group::equal_group_state(&loaded, &reloaded));
+    }
+
     #[cfg(feature = "serde")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn serde() {
@@ -10,7 +10,7 @@ use alloc::vec::Vec;
 use core::fmt::{self, Debug};
 use mls_rs_core::{error::IntoAnyError, group::GroupStateStorage, key_package::KeyPackageStorage};
 
-use super::snapshot::Snapshot;
+use super::snapshot::{Snapshot, SnapshotTrees};
 
 #[cfg(feature = "psk")]
 use crate::group::ResumptionPsk;
@@ -37,6 +37,7 @@ where
     group_id: Vec<u8>,
     storage: S,
     key_package_repo: K,
+    written_trees: Option<SnapshotTrees>,
 }
 
 impl<S, K> Debug for GroupStateRepository<S, K>
@@ -57,6 +58,7 @@ where
             )
             .field("storage", &self.storage)
             .field("key_package_repo", &self.key_package_repo)
+            .field("written_trees", &self.written_trees)
             .finish()
     }
 }
@@ -80,9 +82,16 @@ where
             pending_key_package_removal: key_package_to_remove,
             pending_commit: Default::default(),
             key_package_repo,
+            written_trees: None,
         })
     }
 
+    /// Set the trees of the snapshot that is currently in storage, if it was
+    /// written with deltas.
+    pub fn set_written_trees(&mut self, trees: Option<SnapshotTrees>) {
+        self.written_trees = trees;
+    }
+
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     async fn find_max_id(&self) -> Result<Option<u64>, MlsError> {
         if let Some(max) = self.pending_commit.inserts.back().map(|e| e.epoch_id()) {
@@ -164,6 +173,41 @@ where
         })
     }
 
+    /// All prior epochs that are available, including pending changes, ordered
+    /// from the oldest to the most recent epoch.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn prior_epochs(&self) -> Result<Vec<PriorEpoch>, MlsError> {
+        let mut epochs = VecDeque::new();
+        let mut next_id = self.find_max_id().await?;
+
+        while let Some(epoch_id) = next_id {
+            let pending = self
+                .pending_commit
+                .inserts
+                .iter()
+                .chain(self.pending_commit.updates.iter())
+                .find(|epoch| epoch.epoch_id() == epoch_id);
+
+            let epoch = match pending {
+                Some(epoch) => Some(epoch.clone()),
+                None => self
+                    .storage
+                    .epoch(&self.group_id, epoch_id)
+                    .await
+                    .map_err(|e| MlsError::GroupStorageError(e.into_any_error()))?,
+            };
+
+            let Some(epoch) = epoch else {
+                break;
+            };
+
+            epochs.push_front(epoch);
+            next_id = epoch_id.checked_sub(1);
+        }
+
+        Ok(epochs.into())
+    }
+
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn insert(&mut self, epoch: PriorEpoch) -> Result<(), MlsError> {
         if epoch.group_id() != self.group_id {
@@ -188,10 +232,21 @@ where
         let inserts = self.pending_commit.inserts.iter().cloned().collect();
         let updates = self.pending_commit.updates.clone();
 
-        self.storage
-            .write(group_snapshot, inserts, updates)
-            .await
-            .map_err(|e| MlsError::GroupStorageError(e.into_any_error()))?;
+        if self.storage.supports_deltas() {
+            let (delta, trees) = group_snapshot.into_delta(self.written_trees.as_ref())?;
+
+            self.storage
+                .write_delta(delta, inserts, updates)
+                .await
+                .map_err(|e| MlsError::GroupStorageError(e.into_any_error()))?;
+
+            self.written_trees = Some(trees);
+        } else {
+            self.storage
+                .write(group_snapshot, inserts, updates)
+                .await
+                .map_err(|e| MlsError::GroupStorageError(e.into_any_error()))?;
+        }
 
         if let Some(ref key_package_ref) = self.pending_key_package_removal {
             self.key_package_repo
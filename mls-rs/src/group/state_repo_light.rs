@@ -13,7 +13,7 @@ use mls_rs_core::{
     key_package::KeyPackageStorage,
 };
 
-use super::snapshot::Snapshot;
+use super::snapshot::{Snapshot, SnapshotTrees};
 
 #[derive(Debug, Clone)]
 pub(crate) struct GroupStateRepository<S, K>
@@ -24,6 +24,7 @@ where
     pending_key_package_removal: Option<KeyPackageRef>,
     storage: S,
     key_package_repo: K,
+    written_trees: Option<SnapshotTrees>,
 }
 
 impl<S, K> GroupStateRepository<S, K>
@@ -42,15 +43,33 @@ where
             storage,
             pending_key_package_removal: key_package_to_remove,
             key_package_repo,
+            written_trees: None,
         })
     }
 
+    /// Set the trees of the snapshot that is currently in storage, if it was
+    /// written with deltas.
+    pub fn set_written_trees(&mut self, trees: Option<SnapshotTrees>) {
+        self.written_trees = trees;
+    }
+
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn write_to_storage(&mut self, group_snapshot: Snapshot) -> Result<(), MlsError> {
-        self.storage
-            .write(group_snapshot, Vec::<PriorEpoch>::new(), Vec::new())
-            .await
-            .map_err(|e| MlsError::GroupStorageError(e.into_any_error()))?;
+        if self.storage.supports_deltas() {
+            let (delta, trees) = group_snapshot.into_delta(self.written_trees.as_ref())?;
+
+            self.storage
+                .write_delta(delta, Vec::<PriorEpoch>::new(), Vec::new())
+                .await
+                .map_err(|e| MlsError::GroupStorageError(e.into_any_error()))?;
+
+            self.written_trees = Some(trees);
+        } else {
+            self.storage
+                .write(group_snapshot, Vec::<PriorEpoch>::new(), Vec::new())
+                .await
+                .map_err(|e| MlsError::GroupStorageError(e.into_any_error()))?;
+        }
 
         if let Some(ref key_package_ref) = self.pending_key_package_removal {
             self.key_package_repo
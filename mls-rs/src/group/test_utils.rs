@@ -2,9 +2,12 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use core::ops::{Deref, DerefMut};
+use core::convert::Infallible;
 
+#[cfg(mls_build_async)]
+use alloc::boxed::Box;
 use alloc::format;
+use mls_rs_core::group::RatchetTreeProvider;
 use rand::RngCore;
 
 use super::*;
@@ -48,7 +51,7 @@ impl TestGroup {
     #[cfg(feature = "by_ref_proposal")]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub(crate) async fn update_proposal(&mut self) -> Proposal {
-        self.group.update_proposal(None, None).await.unwrap()
+        self.group.update_proposal(None, None, None).await.unwrap()
     }
 
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
@@ -391,131 +394,40 @@ pub fn random_bytes(count: usize) -> Vec<u8> {
     buf
 }
 
-pub(crate) struct GroupWithoutKeySchedule {
-    inner: Group<TestClientConfig>,
-    pub secrets: Option<(TreeKemPrivate, PathSecret)>,
-    pub provisional_public_state: Option<ProvisionalState>,
+/// [`RatchetTreeProvider`] serving the tree of the current epoch of a group.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TestRatchetTreeProvider {
+    tree: Option<(Vec<u8>, u64, Vec<u8>)>,
 }
 
-impl Deref for GroupWithoutKeySchedule {
-    type Target = Group<TestClientConfig>;
-
-    #[cfg_attr(coverage_nightly, coverage(off))]
-    fn deref(&self) -> &Self::Target {
-        &self.inner
+impl TestRatchetTreeProvider {
+    pub fn new<C: ClientConfig>(group: &Group<C>) -> Self {
+        Self::with_tree(group, group.export_tree().to_bytes().unwrap())
     }
-}
-
-impl DerefMut for GroupWithoutKeySchedule {
-    #[cfg_attr(coverage_nightly, coverage(off))]
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.inner
-    }
-}
 
-#[cfg(feature = "rfc_compliant")]
-impl GroupWithoutKeySchedule {
-    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-    pub async fn new(cs: CipherSuite) -> Self {
+    /// Serve `tree` for the current epoch of `group`, whether it matches or not.
+    pub fn with_tree<C: ClientConfig>(group: &Group<C>, tree: Vec<u8>) -> Self {
         Self {
-            inner: test_group(TEST_PROTOCOL_VERSION, cs).await.group,
-            secrets: None,
-            provisional_public_state: None,
+            tree: Some((group.group_id().to_vec(), group.current_epoch(), tree)),
         }
     }
 }
 
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
-#[cfg_attr(
-    all(not(target_arch = "wasm32"), mls_build_async),
-    maybe_async::must_be_async
-)]
-impl MessageProcessor for GroupWithoutKeySchedule {
-    type CipherSuiteProvider = <Group<TestClientConfig> as MessageProcessor>::CipherSuiteProvider;
-    type OutputType = <Group<TestClientConfig> as MessageProcessor>::OutputType;
-    type PreSharedKeyStorage = <Group<TestClientConfig> as MessageProcessor>::PreSharedKeyStorage;
-    type IdentityProvider = <Group<TestClientConfig> as MessageProcessor>::IdentityProvider;
-    type MlsRules = <Group<TestClientConfig> as MessageProcessor>::MlsRules;
-
-    fn group_state(&self) -> &GroupState {
-        self.inner.group_state()
-    }
-
-    #[cfg_attr(coverage_nightly, coverage(off))]
-    fn group_state_mut(&mut self) -> &mut GroupState {
-        self.inner.group_state_mut()
-    }
-
-    fn mls_rules(&self) -> Self::MlsRules {
-        self.inner.mls_rules()
-    }
-
-    fn identity_provider(&self) -> Self::IdentityProvider {
-        self.inner.identity_provider()
-    }
-
-    fn cipher_suite_provider(&self) -> &Self::CipherSuiteProvider {
-        self.inner.cipher_suite_provider()
-    }
-
-    fn psk_storage(&self) -> Self::PreSharedKeyStorage {
-        self.inner.psk_storage()
-    }
-
-    fn can_continue_processing(&self, provisional_state: &ProvisionalState) -> bool {
-        self.inner.can_continue_processing(provisional_state)
-    }
-
-    #[cfg(feature = "private_message")]
-    #[cfg_attr(coverage_nightly, coverage(off))]
-    fn min_epoch_available(&self) -> Option<u64> {
-        self.inner.min_epoch_available()
-    }
-
-    async fn apply_update_path(
-        &mut self,
-        sender: LeafIndex,
-        update_path: &ValidatedUpdatePath,
-        provisional_state: &mut ProvisionalState,
-    ) -> Result<Option<(TreeKemPrivate, PathSecret)>, MlsError> {
-        self.inner
-            .apply_update_path(sender, update_path, provisional_state)
-            .await
-    }
-
-    #[cfg(feature = "private_message")]
-    #[cfg_attr(coverage_nightly, coverage(off))]
-    async fn process_ciphertext(
-        &mut self,
-        cipher_text: &PrivateMessage,
-    ) -> Result<EventOrContent<Self::OutputType>, MlsError> {
-        self.inner.process_ciphertext(cipher_text).await
-    }
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+impl RatchetTreeProvider for TestRatchetTreeProvider {
+    type Error = Infallible;
 
-    #[cfg_attr(coverage_nightly, coverage(off))]
-    async fn verify_plaintext_authentication(
+    async fn ratchet_tree(
         &self,
-        message: PublicMessage,
-    ) -> Result<EventOrContent<Self::OutputType>, MlsError> {
-        self.inner.verify_plaintext_authentication(message).await
-    }
-
-    async fn update_key_schedule(
-        &mut self,
-        secrets: Option<(TreeKemPrivate, PathSecret)>,
-        _interim_transcript_hash: InterimTranscriptHash,
-        _confirmation_tag: &ConfirmationTag,
-        provisional_public_state: ProvisionalState,
-    ) -> Result<(), MlsError> {
-        self.provisional_public_state = Some(provisional_public_state);
-        self.secrets = secrets;
-        Ok(())
-    }
-
-    #[cfg(feature = "private_message")]
-    #[cfg_attr(coverage_nightly, coverage(off))]
-    fn self_index(&self) -> Option<LeafIndex> {
-        <Group<TestClientConfig> as MessageProcessor>::self_index(&self.inner)
+        group_id: &[u8],
+        epoch: u64,
+        _tree_hash: &[u8],
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self
+            .tree
+            .as_ref()
+            .filter(|(id, tree_epoch, _)| id == group_id && *tree_epoch == epoch)
+            .map(|(_, _, tree)| tree.clone()))
     }
 }
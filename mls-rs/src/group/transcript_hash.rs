@@ -137,40 +137,19 @@ impl InterimTranscriptHash {
 }
 
 // Test vectors come from the MLS interop repository and contain a proposal by reference.
-#[cfg(feature = "by_ref_proposal")]
-#[cfg(test)]
-mod tests {
+#[cfg(all(feature = "by_ref_proposal", any(test, feature = "conformance_util")))]
+pub(crate) mod test_utils {
     use alloc::vec::Vec;
 
     use mls_rs_codec::MlsDecode;
+    use mls_rs_core::crypto::CipherSuiteProvider;
 
-    use crate::{
-        crypto::test_utils::try_test_cipher_suite_provider,
-        group::{framing::ContentType, message_signature::AuthenticatedContent, transcript_hashes},
+    use crate::group::{
+        framing::ContentType, message_signature::AuthenticatedContent, transcript_hashes,
     };
 
-    #[cfg(not(mls_build_async))]
-    use alloc::{boxed::Box, vec};
-
-    #[cfg(not(mls_build_async))]
-    use crate::{
-        crypto::test_utils::test_cipher_suite_provider,
-        group::{
-            confirmation_tag::ConfirmationTag,
-            framing::Content,
-            proposal::{Proposal, ProposalOrRef, RemoveProposal},
-            test_utils::get_test_group_context,
-            Commit, LeafIndex, Sender,
-        },
-        mls_rs_codec::MlsEncode,
-        CipherSuite, CipherSuiteProvider, WireFormat,
-    };
-
-    #[cfg(not(mls_build_async))]
-    use super::{ConfirmedTranscriptHash, InterimTranscriptHash};
-
     #[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
-    struct TestCase {
+    pub struct TestCase {
         pub cipher_suite: u16,
 
         #[serde(with = "hex::serde")]
@@ -186,44 +165,80 @@ mod tests {
         pub interim_transcript_hash_after: Vec<u8>,
     }
 
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn transcript_hash() {
-        let test_cases: Vec<TestCase> =
-            load_test_case_json!(interop_transcript_hashes, generate_test_vector());
-
-        for test_case in test_cases.into_iter() {
-            let Some(cs) = try_test_cipher_suite_provider(test_case.cipher_suite) else {
-                continue;
-            };
-
+    impl TestCase {
+        #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+        pub async fn verify<P: CipherSuiteProvider>(&self, cs: &P) {
             let auth_content =
-                AuthenticatedContent::mls_decode(&mut &*test_case.authenticated_content).unwrap();
+                AuthenticatedContent::mls_decode(&mut &*self.authenticated_content).unwrap();
 
             assert!(auth_content.content.content_type() == ContentType::Commit);
 
-            let conf_key = &test_case.confirmation_key;
-            let conf_hash_after = test_case.confirmed_transcript_hash_after.into();
+            let conf_key = &self.confirmation_key;
+            let conf_hash_after = self.confirmed_transcript_hash_after.clone().into();
             let conf_tag = auth_content.auth.confirmation_tag.clone().unwrap();
 
             let matches = conf_tag
-                .matches(conf_key, &conf_hash_after, &cs)
+                .matches(conf_key, &conf_hash_after, cs)
                 .await
                 .unwrap();
 
             assert!(matches);
 
             let (expected_interim, expected_conf) = transcript_hashes(
-                &cs,
-                &test_case.interim_transcript_hash_before.into(),
+                cs,
+                &self.interim_transcript_hash_before.clone().into(),
                 &auth_content,
             )
             .await
             .unwrap();
 
-            assert_eq!(*expected_interim, test_case.interim_transcript_hash_after);
+            assert_eq!(*expected_interim, self.interim_transcript_hash_after);
             assert_eq!(expected_conf, conf_hash_after);
         }
     }
+}
+
+#[cfg(feature = "by_ref_proposal")]
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::crypto::test_utils::try_test_cipher_suite_provider;
+
+    use super::test_utils::TestCase;
+
+    #[cfg(not(mls_build_async))]
+    use alloc::{boxed::Box, vec};
+
+    #[cfg(not(mls_build_async))]
+    use crate::{
+        crypto::test_utils::test_cipher_suite_provider,
+        group::{
+            confirmation_tag::ConfirmationTag,
+            framing::Content,
+            message_signature::AuthenticatedContent,
+            proposal::{Proposal, ProposalOrRef, RemoveProposal},
+            test_utils::get_test_group_context,
+            Commit, LeafIndex, Sender,
+        },
+        mls_rs_codec::MlsEncode,
+        CipherSuite, CipherSuiteProvider, WireFormat,
+    };
+
+    #[cfg(not(mls_build_async))]
+    use super::{ConfirmedTranscriptHash, InterimTranscriptHash};
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn transcript_hash() {
+        let test_cases: Vec<TestCase> =
+            load_test_case_json!(interop_transcript_hashes, generate_test_vector());
+
+        for test_case in test_cases.into_iter() {
+            if let Some(cs) = try_test_cipher_suite_provider(test_case.cipher_suite) {
+                test_case.verify(&cs).await;
+            }
+        }
+    }
 
     #[cfg(not(mls_build_async))]
     #[cfg_attr(coverage_nightly, coverage(off))]
@@ -0,0 +1,273 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::vec::Vec;
+use mls_rs_codec::{DecodeLimits, MlsDecode, MlsEncode, MlsSize};
+
+use crate::{
+    client::MlsError,
+    tree_kem::{node::Node, TreeKemPublic},
+    CipherSuiteProvider,
+};
+
+use super::{ExportedTree, GroupContext};
+
+/// Changes to the ratchet tree of a group between two epochs.
+///
+/// A diff only contains the nodes that differ between the two trees, which
+/// are the nodes touched by the update path and the proposals of the commits
+/// in between. This allows a server to send incremental updates instead of
+/// the full tree to clients that already know an older version of it.
+///
+/// A diff is created with [`TreeDiff::new`] from the old and the new tree and
+/// verified against the tree hash of the new epoch when applied with
+/// [`TreeDiff::apply`], so it does not need to come from a trusted source.
+#[cfg_attr(
+    all(feature = "ffi", not(test)),
+    safer_ffi_gen::ffi_type(clone, opaque)
+)]
+#[derive(Clone, Debug, PartialEq, MlsSize, MlsEncode, MlsDecode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TreeDiff {
+    node_count: u32,
+    changes: Vec<NodeChange>,
+}
+
+#[derive(Clone, Debug, PartialEq, MlsSize, MlsEncode, MlsDecode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct NodeChange {
+    index: u32,
+    node: Option<Node>,
+}
+
+impl TreeDiff {
+    /// Compute the changes turning `old` into `new`.
+    pub fn new(old: &ExportedTree<'_>, new: &ExportedTree<'_>) -> Result<Self, MlsError> {
+        let (old, new) = (old.nodes()?, new.nodes()?);
+
+        let changes = new
+            .iter()
+            .enumerate()
+            .filter(|&(index, node)| old.get(index) != Some(node))
+            .map(|(index, node)| NodeChange {
+                index: index as u32,
+                node: node.clone(),
+            })
+            .collect();
+
+        Ok(Self {
+            node_count: new.len() as u32,
+            changes,
+        })
+    }
+
+    /// Number of nodes that changed.
+    pub fn changed_nodes(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Apply the changes to `tree` and check that the result matches the tree
+    /// hash of `context`, which must be the context of the epoch the diff
+    /// leads to.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn apply<P: CipherSuiteProvider>(
+        &self,
+        tree: &ExportedTree<'_>,
+        context: &GroupContext,
+        cipher_suite_provider: &P,
+    ) -> Result<ExportedTree<'static>, MlsError> {
+        if context.cipher_suite != cipher_suite_provider.cipher_suite() {
+            return Err(MlsError::CipherSuiteMismatch);
+        }
+
+        let mut nodes = tree.nodes()?.into_owned();
+
+        // A diff only grows the tree up to the last node it changes, and the
+        // last node of a tree is a leaf, which has an even index.
+        let max_node_count = self
+            .changes
+            .iter()
+            .map(|change| change.index as usize + 1)
+            .max()
+            .unwrap_or_default()
+            .max(nodes.len());
+
+        if self.node_count as usize > max_node_count || self.node_count % 2 == 0 {
+            return Err(MlsError::InvalidTreeDiff);
+        }
+
+        nodes.resize(self.node_count as usize, None);
+
+        for change in &self.changes {
+            let node = nodes
+                .get_mut(change.index as usize)
+                .ok_or(MlsError::InvalidTreeDiff)?;
+
+            *node = change.node.clone();
+        }
+
+        let mut tree = TreeKemPublic::from_nodes(nodes);
+
+        if tree.tree_hash(cipher_suite_provider).await? != context.tree_hash {
+            return Err(MlsError::TreeHashMismatch);
+        }
+
+        Ok(ExportedTree::new(tree.nodes))
+    }
+}
+
+#[cfg_attr(all(feature = "ffi", not(test)), ::safer_ffi_gen::safer_ffi_gen)]
+impl TreeDiff {
+    pub fn to_bytes(&self) -> Result<Vec<u8>, MlsError> {
+        self.mls_encode_to_vec().map_err(Into::into)
+    }
+
+    /// Deserialize from `bytes`, failing if they exceed the default [`DecodeLimits`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MlsError> {
+        Self::from_bytes_with_limits(bytes, DecodeLimits::default())
+    }
+
+    /// Deserialize from `bytes`, failing if they exceed `limits`.
+    #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
+    pub fn from_bytes_with_limits(bytes: &[u8], limits: DecodeLimits) -> Result<Self, MlsError> {
+        Self::mls_decode_with_limits(&mut &*bytes, limits).map_err(Into::into)
+    }
+
+    pub fn byte_size(&self) -> usize {
+        self.mls_encoded_len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use assert_matches::assert_matches;
+
+    use crate::{
+        client::{
+            test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+            MlsError,
+        },
+        crypto::test_utils::test_cipher_suite_provider,
+        group::{
+            test_utils::{test_group, test_n_member_group},
+            ExportedTree,
+        },
+    };
+
+    use super::{NodeChange, TreeDiff};
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn applying_diff_yields_new_tree() {
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 8).await;
+        let old_tree = groups[0].group.export_tree().into_owned();
+
+        groups[0].group.commit(vec![]).await.unwrap();
+        groups[0].process_pending_commit().await.unwrap();
+
+        let new_tree = groups[0].group.export_tree();
+        let diff = TreeDiff::new(&old_tree, &new_tree).unwrap();
+
+        // Only the committer's leaf and direct path changed.
+        assert_eq!(diff.changed_nodes(), 4);
+        assert!(diff.byte_size() < new_tree.byte_size());
+
+        let diff = TreeDiff::from_bytes(&diff.to_bytes().unwrap()).unwrap();
+
+        let applied = diff
+            .apply(&old_tree, groups[0].group.context(), &cs)
+            .await
+            .unwrap();
+
+        assert_eq!(applied, new_tree);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn applying_diff_handles_truncated_tree() {
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 5).await;
+        let old_tree = groups[0].group.export_tree().into_owned();
+
+        groups[0]
+            .group
+            .commit_builder()
+            .remove_member(4)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        groups[0].process_pending_commit().await.unwrap();
+
+        let new_tree = groups[0].group.export_tree();
+        let diff = TreeDiff::new(&old_tree, &new_tree).unwrap();
+
+        let applied = diff
+            .apply(&old_tree, groups[0].group.context(), &cs)
+            .await
+            .unwrap();
+
+        assert!(new_tree.nodes().unwrap().len() < old_tree.nodes().unwrap().len());
+        assert_eq!(applied, new_tree);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn applying_diff_checks_tree_hash() {
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let old_tree = group.group.export_tree().into_owned();
+        let old_context = group.group.context().clone();
+
+        group.join("bob").await;
+
+        let diff = TreeDiff::new(&old_tree, &group.group.export_tree()).unwrap();
+        let res = diff.apply(&old_tree, &old_context, &cs).await;
+
+        assert_matches!(res, Err(MlsError::TreeHashMismatch));
+
+        // Applying the diff to a different tree does not give the expected tree either.
+        let other_tree = ExportedTree::new(Default::default());
+        let res = diff.apply(&other_tree, group.group.context(), &cs).await;
+
+        assert_matches!(res, Err(MlsError::TreeHashMismatch));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn applying_diff_rejects_changes_outside_tree() {
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+        let group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let tree = group.group.export_tree();
+
+        let diff = TreeDiff {
+            node_count: 1,
+            changes: vec![NodeChange {
+                index: 2,
+                node: None,
+            }],
+        };
+
+        let res = diff.apply(&tree, group.group.context(), &cs).await;
+
+        assert_matches!(res, Err(MlsError::InvalidTreeDiff));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn applying_diff_rejects_hostile_node_count() {
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+        let group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let tree = group.group.export_tree();
+
+        for node_count in [u32::MAX, 3, 0, 2] {
+            let diff = TreeDiff {
+                node_count,
+                changes: vec![],
+            };
+
+            let res = diff.apply(&tree, group.group.context(), &cs).await;
+
+            assert_matches!(res, Err(MlsError::InvalidTreeDiff));
+        }
+    }
+}
@@ -0,0 +1,459 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::time::Duration;
+use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+use mls_rs_core::time::{MlsTime, TimeProvider};
+
+use crate::{client::MlsError, client_config::ClientConfig};
+
+use super::{CommitOutput, Group, StateUpdate};
+
+/// Epoch and time at which a member last refreshed its leaf keys, either by
+/// joining the group or by an update.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
+pub struct LeafUpdate {
+    epoch: u64,
+    time: Option<u64>,
+}
+
+impl LeafUpdate {
+    fn new(epoch: u64, time: Option<MlsTime>) -> Self {
+        Self {
+            epoch,
+            time: time.map(|time| time.seconds_since_epoch()),
+        }
+    }
+
+    /// Epoch created by the commit that refreshed the leaf.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Time at which the commit that refreshed the leaf was processed, if a
+    /// clock was available.
+    pub fn time(&self) -> Option<MlsTime> {
+        self.time.map(MlsTime::from)
+    }
+}
+
+/// Maximum age of a leaf, in epochs and / or time, before it is considered
+/// stale.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MaxLeafAge {
+    pub epochs: Option<u64>,
+    pub duration: Option<Duration>,
+}
+
+impl MaxLeafAge {
+    /// A leaf is stale once `epochs` epochs were created since it was
+    /// refreshed.
+    pub fn epochs(epochs: u64) -> Self {
+        Self {
+            epochs: Some(epochs),
+            duration: None,
+        }
+    }
+
+    /// A leaf is stale once `duration` elapsed since it was refreshed.
+    pub fn duration(duration: Duration) -> Self {
+        Self {
+            epochs: None,
+            duration: Some(duration),
+        }
+    }
+
+    pub fn with_epochs(self, epochs: u64) -> Self {
+        Self {
+            epochs: Some(epochs),
+            ..self
+        }
+    }
+
+    pub fn with_duration(self, duration: Duration) -> Self {
+        Self {
+            duration: Some(duration),
+            ..self
+        }
+    }
+
+    /// Returns true if `update` is too old at `current_epoch` and `now`.
+    ///
+    /// The time limit is not enforced if `now` or the time of the update is
+    /// unknown.
+    pub fn is_exceeded(
+        &self,
+        update: &LeafUpdate,
+        current_epoch: u64,
+        now: Option<MlsTime>,
+    ) -> bool {
+        let epochs_exceeded = self.epochs.map_or(false, |max| {
+            current_epoch.saturating_sub(update.epoch) >= max
+        });
+
+        let duration_exceeded = match (self.duration, update.time, now) {
+            (Some(max), Some(time), Some(now)) => {
+                now.seconds_since_epoch().saturating_sub(time) >= max.as_secs()
+            }
+            _ => false,
+        };
+
+        epochs_exceeded || duration_exceeded
+    }
+}
+
+/// Tracks when each member of a group last refreshed its leaf keys.
+///
+/// The tracker is kept up to date by passing it the
+/// [`StateUpdate`] of every commit applied to the group, including
+/// commits created by this member. It can be persisted next to the group
+/// with [`UpdateTracker::to_bytes`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
+pub struct UpdateTracker {
+    updates: BTreeMap<u32, LeafUpdate>,
+}
+
+impl UpdateTracker {
+    /// Start tracking the members of `group`.
+    ///
+    /// The history of existing members is unknown, so they are all
+    /// considered to have refreshed their leaf in the current epoch.
+    pub fn new<C: ClientConfig>(group: &Group<C>) -> Self {
+        let update = LeafUpdate::new(group.current_epoch(), group.config.time_provider().now());
+
+        let updates = group
+            .roster()
+            .members_iter()
+            .map(|member| (member.index, update))
+            .collect();
+
+        Self { updates }
+    }
+
+    /// Record the changes made by a commit processed at `time`.
+    pub fn record(&mut self, state_update: &StateUpdate, time: Option<MlsTime>) {
+        let roster_update = state_update.roster_update();
+        let update = LeafUpdate::new(state_update.new_epoch(), time);
+
+        roster_update.removed().iter().for_each(|member| {
+            self.updates.remove(&member.index);
+        });
+
+        let refreshed = roster_update
+            .added()
+            .iter()
+            .map(|member| member.index)
+            .chain(roster_update.updated().iter().map(|update| update.index()));
+
+        refreshed.for_each(|index| {
+            self.updates.insert(index, update);
+        });
+    }
+
+    /// Last refresh of the leaf of the member at `index`.
+    pub fn last_update(&self, index: u32) -> Option<&LeafUpdate> {
+        self.updates.get(&index)
+    }
+
+    /// Indexes of the members whose leaf exceeds `max_age`.
+    pub fn stale_members(
+        &self,
+        max_age: &MaxLeafAge,
+        current_epoch: u64,
+        now: Option<MlsTime>,
+    ) -> Vec<u32> {
+        self.updates
+            .iter()
+            .filter(|(_, update)| max_age.is_exceeded(update, current_epoch, now))
+            .map(|(index, _)| *index)
+            .collect()
+    }
+
+    /// Indexes of the members who did not refresh their leaf for at least
+    /// `duration` at time `now`.
+    pub fn members_not_updated_for(&self, duration: Duration, now: MlsTime) -> Vec<u32> {
+        self.stale_members(&MaxLeafAge::duration(duration), 0, Some(now))
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, MlsError> {
+        self.mls_encode_to_vec().map_err(Into::into)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MlsError> {
+        Self::mls_decode(&mut &*bytes).map_err(Into::into)
+    }
+}
+
+/// Policy keeping the leaf keys of a group fresh in order to provide
+/// post-compromise security.
+///
+/// See [`Group::enforce_update_policy`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct UpdatePolicy {
+    /// Commit with a path update once the leaf of this member is stale.
+    pub self_update: Option<MaxLeafAge>,
+    /// Remove the other members whose leaf is stale.
+    pub remove_stale_members: Option<MaxLeafAge>,
+}
+
+impl UpdatePolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_self_update(self, max_age: MaxLeafAge) -> Self {
+        Self {
+            self_update: Some(max_age),
+            ..self
+        }
+    }
+
+    pub fn with_stale_member_removal(self, max_age: MaxLeafAge) -> Self {
+        Self {
+            remove_stale_members: Some(max_age),
+            ..self
+        }
+    }
+
+    /// Determine the action required by this policy for the member at
+    /// `self_index`.
+    pub fn evaluate(
+        &self,
+        tracker: &UpdateTracker,
+        self_index: u32,
+        current_epoch: u64,
+        now: Option<MlsTime>,
+    ) -> UpdateAction {
+        let self_update = self.self_update.map_or(false, |max_age| {
+            tracker.last_update(self_index).map_or(true, |update| {
+                max_age.is_exceeded(update, current_epoch, now)
+            })
+        });
+
+        let remove = self
+            .remove_stale_members
+            .map(|max_age| {
+                tracker
+                    .stale_members(&max_age, current_epoch, now)
+                    .into_iter()
+                    .filter(|index| *index != self_index)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        UpdateAction {
+            self_update,
+            remove,
+        }
+    }
+}
+
+/// Action required by an [`UpdatePolicy`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct UpdateAction {
+    /// This member must refresh its leaf with a path update.
+    pub self_update: bool,
+    /// Indexes of the stale members to remove.
+    pub remove: Vec<u32>,
+}
+
+impl UpdateAction {
+    /// Returns true if no action is required.
+    pub fn is_empty(&self) -> bool {
+        !self.self_update && self.remove.is_empty()
+    }
+}
+
+impl<C> Group<C>
+where
+    C: ClientConfig + Clone,
+{
+    /// Create a commit enforcing `policy` based on the leaf updates recorded
+    /// by `tracker`.
+    ///
+    /// The commit removes the stale members and includes a path update
+    /// refreshing the leaf of this member. Returns `None` if no action is
+    /// required. As with [`Group::commit`], the commit is pending until it is
+    /// applied, after which its state update must be recorded by `tracker`.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn enforce_update_policy(
+        &mut self,
+        policy: &UpdatePolicy,
+        tracker: &UpdateTracker,
+    ) -> Result<Option<CommitOutput>, MlsError> {
+        let action = policy.evaluate(
+            tracker,
+            self.current_member_index(),
+            self.current_epoch(),
+            self.config.time_provider().now(),
+        );
+
+        if action.is_empty() {
+            return Ok(None);
+        }
+
+        let builder = action
+            .remove
+            .into_iter()
+            .try_fold(self.commit_builder(), |builder, index| {
+                builder.remove_member(index)
+            })?;
+
+        builder.path_required(true).build().await.map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use core::time::Duration;
+
+    use crate::{
+        client::test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+        group::test_utils::{process_commit, test_n_member_group, TestGroup},
+        time::MlsTime,
+    };
+
+    use super::{LeafUpdate, MaxLeafAge, UpdatePolicy, UpdateTracker};
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn commit_and_track(
+        groups: &mut [TestGroup],
+        committer: usize,
+        tracker: &mut UpdateTracker,
+    ) {
+        let commit = groups[committer].group.commit(vec![]).await.unwrap();
+        let description = groups[committer].process_pending_commit().await.unwrap();
+
+        process_commit(groups, commit.commit_message, committer as u32).await;
+        tracker.record(&description.state_update, None);
+    }
+
+    #[test]
+    fn max_leaf_age_is_exceeded() {
+        let update = LeafUpdate::new(5, Some(MlsTime::from(1000)));
+        let max_age = MaxLeafAge::epochs(2).with_duration(Duration::from_secs(100));
+
+        assert!(!max_age.is_exceeded(&update, 6, Some(MlsTime::from(1099))));
+        assert!(max_age.is_exceeded(&update, 7, Some(MlsTime::from(1000))));
+        assert!(max_age.is_exceeded(&update, 5, Some(MlsTime::from(1100))));
+        assert!(!max_age.is_exceeded(&update, 5, None));
+
+        let update = LeafUpdate::new(5, None);
+        assert!(!max_age.is_exceeded(&update, 5, Some(MlsTime::from(u64::MAX))));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn tracker_records_updates() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+        let mut tracker = UpdateTracker::new(&groups[0].group);
+        let epoch = groups[0].group.current_epoch();
+
+        commit_and_track(&mut groups, 1, &mut tracker).await;
+
+        assert_eq!(tracker.last_update(0).unwrap().epoch(), epoch);
+        assert_eq!(tracker.last_update(1).unwrap().epoch(), epoch + 1);
+        assert_eq!(tracker.last_update(2).unwrap().epoch(), epoch);
+
+        let max_age = MaxLeafAge::epochs(1);
+        assert_eq!(tracker.stale_members(&max_age, epoch + 1, None), vec![0, 2]);
+
+        let tracker_bytes = tracker.to_bytes().unwrap();
+        assert_eq!(UpdateTracker::from_bytes(&tracker_bytes).unwrap(), tracker);
+    }
+
+    #[test]
+    fn tracker_finds_members_not_updated_for_duration() {
+        let tracker = UpdateTracker {
+            updates: [
+                (0, LeafUpdate::new(0, Some(MlsTime::from(0)))),
+                (1, LeafUpdate::new(0, Some(MlsTime::from(86400)))),
+                (2, LeafUpdate::new(0, None)),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        let one_day = Duration::from_secs(86400);
+
+        assert_eq!(
+            tracker.members_not_updated_for(one_day, MlsTime::from(86400)),
+            vec![0]
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn policy_triggers_self_update() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
+        let mut tracker = UpdateTracker::new(&groups[0].group);
+        let policy = UpdatePolicy::new().with_self_update(MaxLeafAge::epochs(1));
+
+        let res = groups[0]
+            .group
+            .enforce_update_policy(&policy, &tracker)
+            .await;
+        assert!(res.unwrap().is_none());
+
+        commit_and_track(&mut groups, 1, &mut tracker).await;
+
+        let commit = groups[0]
+            .group
+            .enforce_update_policy(&policy, &tracker)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let description = groups[0].process_pending_commit().await.unwrap();
+        tracker.record(&description.state_update, None);
+
+        assert_eq!(description.state_update.roster_update().updated().len(), 1);
+        assert!(description
+            .state_update
+            .roster_update()
+            .removed()
+            .is_empty());
+
+        let res = groups[0]
+            .group
+            .enforce_update_policy(&policy, &tracker)
+            .await;
+        assert!(res.unwrap().is_none());
+
+        groups[1]
+            .process_message(commit.commit_message)
+            .await
+            .unwrap();
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn policy_removes_stale_members() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+        let mut tracker = UpdateTracker::new(&groups[0].group);
+        let policy = UpdatePolicy::new().with_stale_member_removal(MaxLeafAge::epochs(2));
+
+        commit_and_track(&mut groups, 1, &mut tracker).await;
+        commit_and_track(&mut groups, 0, &mut tracker).await;
+
+        let action = policy.evaluate(&tracker, 0, groups[0].group.current_epoch(), None);
+        assert_eq!(action.remove, vec![2]);
+        assert!(!action.self_update);
+
+        groups[0]
+            .group
+            .enforce_update_policy(&policy, &tracker)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let description = groups[0].process_pending_commit().await.unwrap();
+        let removed = description.state_update.roster_update().removed();
+
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].index, 2);
+    }
+}
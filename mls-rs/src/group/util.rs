@@ -2,8 +2,10 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
+use mls_rs_codec::DecodeLimits;
 use mls_rs_core::{
-    error::IntoAnyError, identity::IdentityProvider, key_package::KeyPackageStorage,
+    error::IntoAnyError, group::RatchetTreeProvider, identity::IdentityProvider,
+    key_package::KeyPackageStorage,
 };
 
 use crate::{
@@ -13,6 +15,7 @@ use crate::{
     key_package::KeyPackageGeneration,
     protocol_version::ProtocolVersion,
     signer::Signable,
+    time::MlsTime,
     tree_kem::{node::LeafIndex, tree_validator::TreeValidator, TreeKemPublic},
     CipherSuiteProvider, CryptoProvider,
 };
@@ -77,38 +80,44 @@ pub(crate) async fn validate_group_info_member<C: CipherSuiteProvider>(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-pub(crate) async fn validate_group_info_joiner<C, I>(
+pub(crate) async fn validate_group_info_joiner<C, I, P>(
     msg_version: ProtocolVersion,
     group_info: &GroupInfo,
     tree: Option<ExportedTree<'_>>,
     id_provider: &I,
+    tree_provider: &P,
+    decode_limits: DecodeLimits,
     cs: &C,
+    time: Option<MlsTime>,
 ) -> Result<TreeKemPublic, MlsError>
 where
     C: CipherSuiteProvider,
     I: IdentityProvider,
+    P: RatchetTreeProvider,
 {
-    let tree = match group_info.extensions.get_as::<RatchetTreeExt>()? {
-        Some(ext) => ext.tree_data,
-        None => tree.ok_or(MlsError::RatchetTreeNotFound)?,
+    let tree = match (group_info.extensions.get_as::<RatchetTreeExt>()?, tree) {
+        (Some(ext), _) => ext.tree_data,
+        (None, Some(tree)) => tree,
+        (None, None) => fetch_ratchet_tree(group_info, tree_provider, decode_limits).await?,
     };
 
     let context = &group_info.group_context;
 
     let mut tree =
-        TreeKemPublic::import_node_data(tree.into(), id_provider, &context.extensions).await?;
+        TreeKemPublic::import_node_data(tree.into_nodes()?, id_provider, &context.extensions)
+            .await?;
 
     // Verify the integrity of the ratchet tree
-    TreeValidator::new(cs, context, id_provider)
+    TreeValidator::new(cs, context, id_provider, time)
         .validate(&mut tree)
         .await?;
 
     #[cfg(feature = "by_ref_proposal")]
     if let Some(ext_senders) = context.extensions.get_as::<ExternalSendersExt>()? {
-        // TODO do joiners verify group against current time??
         ext_senders
-            .verify_all(id_provider, None, &context.extensions)
+            .verify_all(id_provider, time, &context.extensions)
             .await
             .map_err(|e| MlsError::IdentityProviderError(e.into_any_error()))?;
     }
@@ -118,6 +127,26 @@ where
     Ok(tree)
 }
 
+/// Fetch the ratchet tree described by the context of `group_info` from `tree_provider`.
+///
+/// The fetched tree is not validated and fails to decode if it exceeds `decode_limits`.
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+pub(crate) async fn fetch_ratchet_tree<P: RatchetTreeProvider>(
+    group_info: &GroupInfo,
+    tree_provider: &P,
+    decode_limits: DecodeLimits,
+) -> Result<ExportedTree<'static>, MlsError> {
+    let context = &group_info.group_context;
+
+    let tree = tree_provider
+        .ratchet_tree(&context.group_id, context.epoch, &context.tree_hash)
+        .await
+        .map_err(|e| MlsError::RatchetTreeProviderError(e.into_any_error()))?
+        .ok_or(MlsError::RatchetTreeNotFound)?;
+
+    ExportedTree::from_bytes_with_limits(&tree, decode_limits)
+}
+
 pub(crate) fn commit_sender(
     sender: &Sender,
     provisional_state: &ProvisionalState,
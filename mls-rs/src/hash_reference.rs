@@ -85,6 +85,34 @@ impl HashReference {
     }
 }
 
+#[cfg(any(test, feature = "conformance_util"))]
+pub(crate) mod test_utils {
+    use alloc::{string::String, vec::Vec};
+    use mls_rs_core::crypto::CipherSuiteProvider;
+
+    use super::HashReference;
+
+    #[derive(Debug, serde::Serialize, serde::Deserialize)]
+    pub struct HashRefTestCase {
+        pub label: String,
+        #[serde(with = "hex::serde")]
+        pub value: Vec<u8>,
+        #[serde(with = "hex::serde")]
+        pub out: Vec<u8>,
+    }
+
+    impl HashRefTestCase {
+        #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+        pub async fn verify<P: CipherSuiteProvider>(&self, cs: &P) {
+            let computed = HashReference::compute(&self.value, self.label.as_bytes(), cs)
+                .await
+                .unwrap();
+
+            assert_eq!(&*computed, &self.out);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::crypto::test_utils::try_test_cipher_suite_provider;
@@ -92,9 +120,12 @@ mod tests {
     #[cfg(not(mls_build_async))]
     use crate::{cipher_suite::CipherSuite, crypto::test_utils::test_cipher_suite_provider};
 
+    use super::test_utils::HashRefTestCase;
+
+    #[cfg(not(mls_build_async))]
     use super::*;
-    use alloc::string::String;
-    use serde::{Deserialize, Serialize};
+
+    use alloc::vec::Vec;
 
     #[cfg(not(mls_build_async))]
     use alloc::string::ToString;
@@ -102,15 +133,6 @@ mod tests {
     #[cfg(target_arch = "wasm32")]
     use wasm_bindgen_test::wasm_bindgen_test as test;
 
-    #[derive(Debug, Deserialize, Serialize)]
-    struct HashRefTestCase {
-        label: String,
-        #[serde(with = "hex::serde")]
-        value: Vec<u8>,
-        #[serde(with = "hex::serde")]
-        out: Vec<u8>,
-    }
-
     #[derive(Debug, serde::Serialize, serde::Deserialize)]
     pub struct InteropTestCase {
         cipher_suite: u16,
@@ -156,10 +178,7 @@ mod tests {
 
         for test_case in test_cases {
             if let Some(cs) = try_test_cipher_suite_provider(test_case.cipher_suite) {
-                let label = test_case.ref_hash.label.as_bytes();
-                let value = &test_case.ref_hash.value;
-                let computed = HashReference::compute(value, label, &cs).await.unwrap();
-                assert_eq!(&*computed, &test_case.ref_hash.out);
+                test_case.ref_hash.verify(&cs).await;
             }
         }
     }
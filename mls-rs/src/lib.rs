@@ -151,6 +151,10 @@ mod key_package;
 /// Pre-shared key support.
 pub mod psk;
 mod signer;
+/// Randomized network simulator for property testing groups of clients.
+#[cfg(feature = "simulator")]
+#[cfg_attr(docsrs, doc(cfg(feature = "simulator")))]
+pub mod simulator;
 /// Storage providers to use with
 /// [`ClientBuilder`](client_builder::ClientBuilder).
 pub mod storage_provider;
@@ -170,6 +174,7 @@ pub mod mls_rules {
             CommitDirection, CommitOptions, CommitSource, DefaultMlsRules, EncryptionOptions,
         },
         proposal_filter::{ProposalBundle, ProposalInfo, ProposalSource},
+        rbac::{RbacError, RbacMlsRules, RolePermissions},
     };
 
     #[cfg(feature = "by_ref_proposal")]
@@ -42,7 +42,7 @@ impl Debug for PskSecret {
     }
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "conformance_util"))]
 impl From<Vec<u8>> for PskSecret {
     fn from(value: Vec<u8>) -> Self {
         PskSecret(Zeroizing::new(value))
@@ -109,21 +109,20 @@ impl PskSecret {
 }
 
 #[cfg(feature = "psk")]
-#[cfg(test)]
-mod tests {
+#[cfg(any(test, feature = "conformance_util"))]
+pub(crate) mod test_utils {
     use alloc::vec::Vec;
-    #[cfg(not(mls_build_async))]
+    #[cfg(all(test, not(mls_build_async)))]
     use core::iter;
     use serde::{Deserialize, Serialize};
 
     use crate::{
-        crypto::test_utils::try_test_cipher_suite_provider,
         psk::ExternalPskId,
         psk::{JustPreSharedKeyID, PreSharedKeyID, PskNonce},
         CipherSuiteProvider,
     };
 
-    #[cfg(not(mls_build_async))]
+    #[cfg(all(test, not(mls_build_async)))]
     use crate::{
         crypto::test_utils::test_cipher_suite_provider, psk::test_utils::make_external_psk_id,
         CipherSuite,
@@ -156,8 +155,8 @@ mod tests {
     }
 
     #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
-    struct TestScenario {
-        cipher_suite: u16,
+    pub struct TestScenario {
+        pub cipher_suite: u16,
         psks: Vec<PskInfo>,
         #[serde(with = "hex::serde")]
         psk_secret: Vec<u8>,
@@ -165,7 +164,7 @@ mod tests {
 
     impl TestScenario {
         #[cfg_attr(coverage_nightly, coverage(off))]
-        #[cfg(not(mls_build_async))]
+        #[cfg(all(test, not(mls_build_async)))]
         fn make_psk_list<CS: CipherSuiteProvider>(cs: &CS, n: usize) -> Vec<PskInfo> {
             iter::repeat_with(
                 #[cfg_attr(coverage_nightly, coverage(off))]
@@ -179,9 +178,9 @@ mod tests {
             .collect::<Vec<_>>()
         }
 
-        #[cfg(not(mls_build_async))]
+        #[cfg(all(test, not(mls_build_async)))]
         #[cfg_attr(coverage_nightly, coverage(off))]
-        fn generate() -> Vec<TestScenario> {
+        pub fn generate() -> Vec<TestScenario> {
             CipherSuite::all()
                 .flat_map(
                     #[cfg_attr(coverage_nightly, coverage(off))]
@@ -203,8 +202,8 @@ mod tests {
                 .collect()
         }
 
-        #[cfg(mls_build_async)]
-        fn generate() -> Vec<TestScenario> {
+        #[cfg(all(test, mls_build_async))]
+        pub fn generate() -> Vec<TestScenario> {
             panic!("Tests cannot be generated in async mode");
         }
 
@@ -220,7 +219,23 @@ mod tests {
 
             PskSecret::calculate(&input, provider).await.unwrap()
         }
+
+        #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+        pub async fn verify<P: CipherSuiteProvider>(&self, provider: &P) {
+            let computed = Self::compute_psk_secret(provider, self.psks.clone()).await;
+
+            assert_eq!(self.psk_secret, computed.to_vec());
+        }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::crypto::test_utils::try_test_cipher_suite_provider;
+
+    use super::test_utils::TestScenario;
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn expected_psk_secret_is_produced() {
@@ -229,10 +244,7 @@ mod tests {
 
         for scenario in scenarios {
             if let Some(provider) = try_test_cipher_suite_provider(scenario.cipher_suite) {
-                let computed =
-                    TestScenario::compute_psk_secret(&provider, scenario.psks.clone()).await;
-
-                assert_eq!(scenario.psk_secret, computed.to_vec());
+                scenario.verify(&provider).await;
             }
         }
     }
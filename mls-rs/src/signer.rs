@@ -93,12 +93,13 @@ pub(crate) trait Signable<'a> {
     }
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "conformance_util"))]
 pub(crate) mod test_utils {
     use alloc::vec;
     use alloc::{string::String, vec::Vec};
     use mls_rs_core::crypto::CipherSuiteProvider;
 
+    #[cfg(test)]
     use crate::crypto::test_utils::try_test_cipher_suite_provider;
 
     use super::Signable;
@@ -116,12 +117,14 @@ pub(crate) mod test_utils {
         signature: Vec<u8>,
     }
 
+    #[cfg(test)]
     #[derive(Debug, serde::Serialize, serde::Deserialize)]
     pub struct InteropTestCase {
         cipher_suite: u16,
         sign_with_label: SignatureInteropTestCase,
     }
 
+    #[cfg(test)]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn test_basic_crypto_test_vectors() {
         let test_cases: Vec<InteropTestCase> =
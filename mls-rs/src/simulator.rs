@@ -0,0 +1,988 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+//! Randomized multi-client network simulator.
+//!
+//! [`NetworkSimulator`] drives a group of [`Client`]s through a virtual
+//! delivery service that drops, duplicates and reorders messages and lets
+//! several members race to commit in the same epoch. After every step the
+//! simulator checks that members agree on the group state and that replayed
+//! messages are rejected.
+//!
+//! Runs are fully determined by [`SimulatorConfig::seed`], so a failing seed
+//! can be replayed while debugging. Clients are produced by a user supplied
+//! [`ClientFactory`], which makes it possible to plug in custom
+//! [`MlsRules`](crate::MlsRules), identity providers or crypto providers.
+
+mod delivery_service;
+mod rng;
+
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::vec::Vec;
+use std::collections::HashSet;
+
+#[cfg(mls_build_async)]
+use alloc::boxed::Box;
+
+use mls_rs_core::{
+    extension::ExtensionList, group::Member, identity::SigningIdentity, secret::Secret,
+};
+
+use crate::{client::MlsError, client_builder::MlsConfig, group::CommitOutput, Client, Group};
+
+use delivery_service::{DeliveryService, Envelope, Payload};
+use rng::SimulatorRng;
+
+/// Source of clients for a [`NetworkSimulator`].
+///
+/// Every client must have a distinct signing identity.
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+pub trait ClientFactory {
+    type Config: MlsConfig;
+
+    /// Create the client for simulated member number `member_id`.
+    async fn new_client(&mut self, member_id: usize) -> Client<Self::Config>;
+}
+
+/// Knobs controlling the behavior of a [`NetworkSimulator`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct SimulatorConfig {
+    /// Seed for the deterministic random number generator.
+    pub seed: u64,
+    /// Probability that a step makes a member act (commit or send an
+    /// application message) rather than deliver a message.
+    pub action_rate: f64,
+    /// Probability that a delivery is lost and retransmitted later.
+    pub drop_rate: f64,
+    /// Probability that a delivered message is delivered again later.
+    pub duplicate_rate: f64,
+    /// Probability that a delivery picks a random queued message instead of
+    /// the oldest one.
+    pub reorder_rate: f64,
+    /// Probability that a commit action makes two members commit
+    /// concurrently in the same epoch.
+    pub concurrent_commit_rate: f64,
+    /// Maximum group size. Add commits are not generated past this size.
+    pub max_members: usize,
+    /// Maximum number of deliveries [`NetworkSimulator::settle`] performs
+    /// before giving up.
+    pub max_settle_deliveries: usize,
+}
+
+impl Default for SimulatorConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            action_rate: 0.3,
+            drop_rate: 0.1,
+            duplicate_rate: 0.1,
+            reorder_rate: 0.3,
+            concurrent_commit_rate: 0.2,
+            max_members: 8,
+            max_settle_deliveries: 10_000,
+        }
+    }
+}
+
+impl SimulatorConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self { seed, ..self }
+    }
+
+    pub fn with_action_rate(self, action_rate: f64) -> Self {
+        Self {
+            action_rate,
+            ..self
+        }
+    }
+
+    pub fn with_drop_rate(self, drop_rate: f64) -> Self {
+        Self { drop_rate, ..self }
+    }
+
+    pub fn with_duplicate_rate(self, duplicate_rate: f64) -> Self {
+        Self {
+            duplicate_rate,
+            ..self
+        }
+    }
+
+    pub fn with_reorder_rate(self, reorder_rate: f64) -> Self {
+        Self {
+            reorder_rate,
+            ..self
+        }
+    }
+
+    pub fn with_concurrent_commit_rate(self, concurrent_commit_rate: f64) -> Self {
+        Self {
+            concurrent_commit_rate,
+            ..self
+        }
+    }
+
+    pub fn with_max_members(self, max_members: usize) -> Self {
+        Self {
+            max_members,
+            ..self
+        }
+    }
+
+    pub fn with_max_settle_deliveries(self, max_settle_deliveries: usize) -> Self {
+        Self {
+            max_settle_deliveries,
+            ..self
+        }
+    }
+}
+
+/// Counters describing what happened during a simulation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SimulationStats {
+    pub commits_accepted: usize,
+    pub commits_rejected: usize,
+    pub application_messages: usize,
+    pub deliveries: usize,
+    pub dropped: usize,
+    pub duplicated: usize,
+    pub reordered: usize,
+    pub deferred: usize,
+    pub replays_rejected: usize,
+    pub stale_messages: usize,
+    pub members_added: usize,
+    pub members_removed: usize,
+}
+
+/// Group property compared between members by the invariant checks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupProperty {
+    EpochAuthenticator,
+    TreeHash,
+    Roster,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum SimulationError {
+    #[error("member {member} failed: {error}")]
+    Member {
+        member: usize,
+        #[source]
+        error: MlsError,
+    },
+    #[error("member {member} accepted a replayed message")]
+    ReplayAccepted { member: usize },
+    #[error(
+        "member {member} is at epoch {actual} but the delivery service is at epoch {expected}"
+    )]
+    EpochMismatch {
+        member: usize,
+        expected: u64,
+        actual: u64,
+    },
+    #[error("member {member} never joined the group")]
+    NotJoined { member: usize },
+    #[error("members {first} and {second} disagree on {property:?}")]
+    Diverged {
+        first: usize,
+        second: usize,
+        property: GroupProperty,
+    },
+    #[error("roster has {actual} members but the delivery service tracks {expected}")]
+    UnexpectedRosterSize { expected: usize, actual: usize },
+    #[error("{0} messages are still undelivered")]
+    NotSettled(usize),
+    #[error("a simulation requires at least one member")]
+    EmptyGroup,
+}
+
+impl SimulationError {
+    fn member(member: usize) -> impl FnOnce(MlsError) -> Self {
+        move |error| SimulationError::Member { member, error }
+    }
+}
+
+struct SimulatedMember<C: MlsConfig> {
+    client: Client<C>,
+    signing_identity: SigningIdentity,
+    group: Option<Group<C>>,
+    inbox: VecDeque<Envelope>,
+    seen: HashSet<u64>,
+    pending_commit: bool,
+    removed: bool,
+}
+
+struct PreparedCommit {
+    committer: usize,
+    epoch: u64,
+    output: CommitOutput,
+    joiner: Option<usize>,
+    removed: Vec<usize>,
+}
+
+/// Member id, epoch authenticator, tree hash and roster observed at an epoch.
+type EpochView<'a> = (usize, Secret, &'a [u8], Vec<Member>);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CommitKind {
+    Update,
+    Add,
+    Remove,
+}
+
+/// Simulates a group of clients communicating over an unreliable network.
+pub struct NetworkSimulator<F: ClientFactory> {
+    config: SimulatorConfig,
+    factory: F,
+    rng: SimulatorRng,
+    delivery_service: DeliveryService,
+    members: Vec<SimulatedMember<F::Config>>,
+    stats: SimulationStats,
+}
+
+impl<F: ClientFactory> NetworkSimulator<F> {
+    /// Create a group with `initial_members` members, all of whom have
+    /// joined before the first step.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn new(
+        config: SimulatorConfig,
+        initial_members: usize,
+        mut factory: F,
+    ) -> Result<Self, SimulationError> {
+        if initial_members == 0 {
+            return Err(SimulationError::EmptyGroup);
+        }
+
+        let mut members = Vec::with_capacity(initial_members);
+
+        for member_id in 0..initial_members {
+            let client = factory.new_client(member_id).await;
+            members.push(SimulatedMember::new(client, member_id)?);
+        }
+
+        let mut creator = members[0]
+            .client
+            .create_group(ExtensionList::default())
+            .await
+            .map_err(SimulationError::member(0))?;
+
+        if initial_members > 1 {
+            let mut builder = creator.commit_builder();
+
+            for (member_id, member) in members.iter().enumerate().skip(1) {
+                let key_package = member
+                    .client
+                    .generate_key_package_message()
+                    .await
+                    .map_err(SimulationError::member(member_id))?;
+
+                builder = builder
+                    .add_member(key_package)
+                    .map_err(SimulationError::member(0))?;
+            }
+
+            let output = builder.build().await.map_err(SimulationError::member(0))?;
+
+            creator
+                .apply_pending_commit()
+                .await
+                .map_err(SimulationError::member(0))?;
+
+            for (member_id, member) in members.iter_mut().enumerate().skip(1) {
+                for welcome in &output.welcome_messages {
+                    let (group, _) = member
+                        .client
+                        .join_group(output.ratchet_tree.clone(), welcome)
+                        .await
+                        .map_err(SimulationError::member(member_id))?;
+
+                    member.group = Some(group);
+                }
+            }
+        }
+
+        let epoch = creator.current_epoch();
+        members[0].group = Some(creator);
+
+        Ok(Self {
+            rng: SimulatorRng::new(config.seed),
+            config,
+            factory,
+            delivery_service: DeliveryService::new(epoch, (0..initial_members).collect()),
+            members,
+            stats: SimulationStats::default(),
+        })
+    }
+
+    pub fn stats(&self) -> &SimulationStats {
+        &self.stats
+    }
+
+    /// Epoch of the most recent commit accepted by the delivery service.
+    pub fn epoch(&self) -> u64 {
+        self.delivery_service.epoch()
+    }
+
+    /// Simulated members currently in the group according to the delivery
+    /// service.
+    pub fn members(&self) -> &[usize] {
+        self.delivery_service.members()
+    }
+
+    /// Group state of a simulated member, if it has joined and was not
+    /// removed.
+    pub fn group(&self, member: usize) -> Option<&Group<F::Config>> {
+        self.members.get(member)?.group.as_ref()
+    }
+
+    /// Run `steps` random steps, checking invariants after each, then
+    /// deliver every outstanding message.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn run(&mut self, steps: usize) -> Result<(), SimulationError> {
+        for _ in 0..steps {
+            self.step().await?;
+            self.check_invariants()?;
+        }
+
+        self.settle().await
+    }
+
+    /// Perform a single random action or message delivery.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn step(&mut self) -> Result<(), SimulationError> {
+        let in_flight = self.in_flight() > 0;
+
+        if !in_flight || self.rng.chance(self.config.action_rate) {
+            if self.rng.chance(0.5) {
+                self.send_application_message().await
+            } else if self.rng.chance(self.config.concurrent_commit_rate) {
+                self.concurrent_commits().await
+            } else {
+                self.single_commit().await
+            }
+        } else {
+            self.deliver_random().await
+        }
+    }
+
+    /// Deliver all outstanding messages without faults and check that every
+    /// member converged to the delivery service's view of the group.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn settle(&mut self) -> Result<(), SimulationError> {
+        for _ in 0..self.config.max_settle_deliveries {
+            let Some(member) = self.members.iter().position(|m| !m.inbox.is_empty()) else {
+                return self.check_converged();
+            };
+
+            if let Some(envelope) = self.members[member].inbox.pop_front() {
+                self.deliver(member, envelope).await?;
+            }
+        }
+
+        match self.in_flight() {
+            0 => self.check_converged(),
+            remaining => Err(SimulationError::NotSettled(remaining)),
+        }
+    }
+
+    /// Check that all members that reached the same epoch agree on the
+    /// epoch authenticator, tree hash and roster.
+    pub fn check_invariants(&self) -> Result<(), SimulationError> {
+        let mut views: BTreeMap<u64, EpochView<'_>> = BTreeMap::new();
+
+        for (member_id, group) in self.active_groups() {
+            let authenticator = group
+                .epoch_authenticator()
+                .map_err(SimulationError::member(member_id))?;
+
+            let tree_hash = group.context().tree_hash();
+            let roster = group.roster().members();
+
+            let Some((first, first_authenticator, first_tree_hash, first_roster)) =
+                views.get(&group.current_epoch())
+            else {
+                views.insert(
+                    group.current_epoch(),
+                    (member_id, authenticator, tree_hash, roster),
+                );
+
+                continue;
+            };
+
+            let diverged = |property| SimulationError::Diverged {
+                first: *first,
+                second: member_id,
+                property,
+            };
+
+            if first_authenticator != &authenticator {
+                return Err(diverged(GroupProperty::EpochAuthenticator));
+            }
+
+            if first_tree_hash != &tree_hash {
+                return Err(diverged(GroupProperty::TreeHash));
+            }
+
+            if first_roster != &roster {
+                return Err(diverged(GroupProperty::Roster));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_converged(&self) -> Result<(), SimulationError> {
+        let expected = self.delivery_service.epoch();
+
+        for &member_id in self.delivery_service.members() {
+            let group = self.members[member_id]
+                .group
+                .as_ref()
+                .ok_or(SimulationError::NotJoined { member: member_id })?;
+
+            if group.current_epoch() != expected {
+                return Err(SimulationError::EpochMismatch {
+                    member: member_id,
+                    expected,
+                    actual: group.current_epoch(),
+                });
+            }
+
+            let roster_size = group.roster().members_iter().count();
+
+            if roster_size != self.delivery_service.members().len() {
+                return Err(SimulationError::UnexpectedRosterSize {
+                    expected: self.delivery_service.members().len(),
+                    actual: roster_size,
+                });
+            }
+        }
+
+        self.check_invariants()
+    }
+
+    fn active_groups(&self) -> impl Iterator<Item = (usize, &Group<F::Config>)> {
+        self.members
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| !m.removed)
+            .filter_map(|(id, m)| Some((id, m.group.as_ref()?)))
+    }
+
+    fn in_flight(&self) -> usize {
+        self.members.iter().map(|m| m.inbox.len()).sum()
+    }
+
+    /// Members that may send messages: joined, not removed and without an
+    /// outstanding commit.
+    fn eligible_senders(&self) -> Vec<usize> {
+        self.delivery_service
+            .members()
+            .iter()
+            .copied()
+            .filter(|&id| {
+                let member = &self.members[id];
+                member.group.is_some() && !member.pending_commit
+            })
+            .collect()
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn send_application_message(&mut self) -> Result<(), SimulationError> {
+        let Some(sender) = self.rng.choose(&self.eligible_senders()) else {
+            return Ok(());
+        };
+
+        let group = self.members[sender].group.as_mut().unwrap();
+        let epoch = group.current_epoch();
+
+        let message = group
+            .encrypt_application_message(b"simulated message", Vec::new())
+            .await
+            .map_err(SimulationError::member(sender))?;
+
+        let envelope = self
+            .delivery_service
+            .envelope(Payload::Application { message, epoch });
+
+        for &recipient in self.delivery_service.members() {
+            if recipient != sender {
+                self.members[recipient].inbox.push_back(envelope.clone());
+            }
+        }
+
+        self.stats.application_messages += 1;
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn single_commit(&mut self) -> Result<(), SimulationError> {
+        let Some(committer) = self.rng.choose(&self.eligible_senders()) else {
+            return Ok(());
+        };
+
+        let commit = self.prepare_commit(committer).await?;
+        self.submit_commit(commit)
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn concurrent_commits(&mut self) -> Result<(), SimulationError> {
+        let mut senders = self.eligible_senders();
+
+        if senders.len() < 2 {
+            return self.single_commit().await;
+        }
+
+        let first = senders.remove(self.rng.below(senders.len()));
+        let second = senders.remove(self.rng.below(senders.len()));
+
+        // Both commits are created before either reaches the delivery service
+        // so they race for the same epoch.
+        let first = self.prepare_commit(first).await?;
+        let second = self.prepare_commit(second).await?;
+
+        if self.rng.chance(0.5) {
+            self.submit_commit(first)?;
+            self.submit_commit(second)
+        } else {
+            self.submit_commit(second)?;
+            self.submit_commit(first)
+        }
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn prepare_commit(
+        &mut self,
+        committer: usize,
+    ) -> Result<PreparedCommit, SimulationError> {
+        let group = self.members[committer].group.as_ref().unwrap();
+        let roster = group.roster().members();
+
+        let removal_targets = roster
+            .iter()
+            .filter(|m| m.index != group.current_member_index())
+            .filter_map(|m| {
+                let target = self
+                    .members
+                    .iter()
+                    .position(|sim| !sim.removed && sim.signing_identity == m.signing_identity)?;
+
+                Some((m.index, target))
+            })
+            .collect::<Vec<_>>();
+
+        let mut kinds = vec![CommitKind::Update];
+
+        if roster.len() < self.config.max_members {
+            kinds.push(CommitKind::Add);
+        }
+
+        if roster.len() > 2 && !removal_targets.is_empty() {
+            kinds.push(CommitKind::Remove);
+        }
+
+        let kind = self.rng.choose(&kinds).unwrap_or(CommitKind::Update);
+        let mut joiner = None;
+        let mut removed = Vec::new();
+        let mut remove_index = None;
+
+        let key_package = match kind {
+            CommitKind::Update => None,
+            CommitKind::Add => {
+                // The joiner is registered right away so that its id is never
+                // handed out again, even if the commit is rejected. It only
+                // receives messages once the commit is accepted.
+                let joiner_id = self.members.len();
+                let client = self.factory.new_client(joiner_id).await;
+
+                let key_package = client
+                    .generate_key_package_message()
+                    .await
+                    .map_err(SimulationError::member(joiner_id))?;
+
+                self.members.push(SimulatedMember::new(client, joiner_id)?);
+                joiner = Some(joiner_id);
+
+                Some(key_package)
+            }
+            CommitKind::Remove => {
+                let (index, target) = removal_targets[self.rng.below(removal_targets.len())];
+                removed.push(target);
+                remove_index = Some(index);
+                None
+            }
+        };
+
+        let member = &mut self.members[committer];
+        let group = member.group.as_mut().unwrap();
+        let epoch = group.current_epoch();
+
+        let mut commit_builder = group.commit_builder();
+
+        if let Some(key_package) = key_package {
+            commit_builder = commit_builder
+                .add_member(key_package)
+                .map_err(SimulationError::member(committer))?;
+        }
+
+        if let Some(index) = remove_index {
+            commit_builder = commit_builder
+                .remove_member(index)
+                .map_err(SimulationError::member(committer))?;
+        }
+
+        let output = commit_builder
+            .build()
+            .await
+            .map_err(SimulationError::member(committer))?;
+
+        member.pending_commit = true;
+
+        Ok(PreparedCommit {
+            committer,
+            epoch,
+            output,
+            joiner,
+            removed,
+        })
+    }
+
+    fn submit_commit(&mut self, commit: PreparedCommit) -> Result<(), SimulationError> {
+        let added = commit.joiner.into_iter().collect::<Vec<_>>();
+
+        let Some(recipients) =
+            self.delivery_service
+                .sequence_commit(commit.epoch, &added, &commit.removed)
+        else {
+            let member = &mut self.members[commit.committer];
+            member.group.as_mut().unwrap().clear_pending_commit();
+            member.pending_commit = false;
+            self.stats.commits_rejected += 1;
+
+            return Ok(());
+        };
+
+        let envelope = self.delivery_service.envelope(Payload::Commit {
+            message: commit.output.commit_message,
+            epoch: commit.epoch,
+            removed: commit.removed.clone(),
+        });
+
+        for recipient in recipients {
+            self.members[recipient].inbox.push_back(envelope.clone());
+        }
+
+        if let Some(joiner) = commit.joiner {
+            for message in commit.output.welcome_messages {
+                let envelope = self.delivery_service.envelope(Payload::Welcome {
+                    message,
+                    ratchet_tree: commit.output.ratchet_tree.clone(),
+                });
+
+                self.members[joiner].inbox.push_back(envelope);
+            }
+
+            self.stats.members_added += 1;
+        }
+
+        self.stats.members_removed += commit.removed.len();
+        self.stats.commits_accepted += 1;
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn deliver_random(&mut self) -> Result<(), SimulationError> {
+        let candidates = (0..self.members.len())
+            .filter(|&id| !self.members[id].inbox.is_empty())
+            .collect::<Vec<_>>();
+
+        let Some(member_id) = self.rng.choose(&candidates) else {
+            return Ok(());
+        };
+
+        let reorder = self.rng.chance(self.config.reorder_rate);
+        let inbox_len = self.members[member_id].inbox.len();
+        let position = if reorder {
+            self.rng.below(inbox_len)
+        } else {
+            0
+        };
+
+        let Some(envelope) = self.members[member_id].inbox.remove(position) else {
+            return Ok(());
+        };
+
+        if position > 0 {
+            self.stats.reordered += 1;
+        }
+
+        if self.rng.chance(self.config.drop_rate) {
+            // The network lost this copy; the sender retransmits it later.
+            self.members[member_id].inbox.push_back(envelope);
+            self.stats.dropped += 1;
+
+            return Ok(());
+        }
+
+        if self.rng.chance(self.config.duplicate_rate) {
+            self.members[member_id].inbox.push_back(envelope.clone());
+            self.stats.duplicated += 1;
+        }
+
+        self.deliver(member_id, envelope).await
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn deliver(
+        &mut self,
+        member_id: usize,
+        envelope: Envelope,
+    ) -> Result<(), SimulationError> {
+        let member = &mut self.members[member_id];
+
+        if member.removed {
+            member.inbox.clear();
+            return Ok(());
+        }
+
+        self.stats.deliveries += 1;
+
+        let (message, message_epoch) = match &envelope.payload {
+            Payload::Welcome {
+                message,
+                ratchet_tree,
+            } => {
+                if member.seen.insert(envelope.id) {
+                    let (group, _) = member
+                        .client
+                        .join_group(ratchet_tree.clone(), message)
+                        .await
+                        .map_err(SimulationError::member(member_id))?;
+
+                    member.group = Some(group);
+                }
+
+                return Ok(());
+            }
+            Payload::Commit { message, epoch, .. } | Payload::Application { message, epoch } => {
+                (message.clone(), *epoch)
+            }
+        };
+
+        let Some(group) = member.group.as_mut() else {
+            // Not joined yet, wait for the welcome message.
+            member.inbox.push_back(envelope);
+            self.stats.deferred += 1;
+
+            return Ok(());
+        };
+
+        let current_epoch = group.current_epoch();
+
+        if message_epoch > current_epoch {
+            // Buffer until the missing commits have been processed.
+            member.inbox.push_back(envelope);
+            self.stats.deferred += 1;
+
+            return Ok(());
+        }
+
+        if !member.seen.insert(envelope.id) {
+            let authenticator = group
+                .epoch_authenticator()
+                .map_err(SimulationError::member(member_id))?;
+
+            let replay = group.process_incoming_message(message).await;
+
+            let unchanged = group.current_epoch() == current_epoch
+                && group.epoch_authenticator().ok().as_ref() == Some(&authenticator);
+
+            if replay.is_ok() || !unchanged {
+                return Err(SimulationError::ReplayAccepted { member: member_id });
+            }
+
+            self.stats.replays_rejected += 1;
+
+            return Ok(());
+        }
+
+        if message_epoch < current_epoch {
+            // Messages from prior epochs may legitimately fail, e.g. if
+            // the member joined after they were sent.
+            let res = group.process_incoming_message(message).await;
+            self.stats.stale_messages += usize::from(res.is_err());
+
+            return Ok(());
+        }
+
+        group
+            .process_incoming_message(message)
+            .await
+            .map_err(SimulationError::member(member_id))?;
+
+        if let Payload::Commit { removed, .. } = &envelope.payload {
+            member.pending_commit = false;
+
+            if removed.contains(&member_id) {
+                member.removed = true;
+                member.group = None;
+                member.inbox.clear();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<C: MlsConfig> SimulatedMember<C> {
+    fn new(client: Client<C>, member_id: usize) -> Result<Self, SimulationError> {
+        let signing_identity = client
+            .signing_identity()
+            .map_err(SimulationError::member(member_id))?
+            .0
+            .clone();
+
+        Ok(Self {
+            client,
+            signing_identity,
+            group: None,
+            inbox: VecDeque::new(),
+            seen: HashSet::new(),
+            pending_commit: false,
+            removed: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        client::test_utils::{TestClientBuilder, TestClientConfig, TEST_CIPHER_SUITE},
+        Client,
+    };
+
+    #[cfg(mls_build_async)]
+    use alloc::boxed::Box;
+
+    use super::{ClientFactory, NetworkSimulator, SimulationError, SimulatorConfig};
+
+    struct TestFactory;
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+    impl ClientFactory for TestFactory {
+        type Config = TestClientConfig;
+
+        async fn new_client(&mut self, member_id: usize) -> Client<TestClientConfig> {
+            TestClientBuilder::new_for_test()
+                .with_random_signing_identity(&format!("member {member_id}"), TEST_CIPHER_SUITE)
+                .await
+                .build()
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn reliable_network_converges() {
+        let config = SimulatorConfig::new()
+            .with_drop_rate(0.0)
+            .with_duplicate_rate(0.0)
+            .with_reorder_rate(0.0)
+            .with_concurrent_commit_rate(0.0);
+
+        let mut simulator = NetworkSimulator::new(config, 3, TestFactory).await.unwrap();
+        simulator.run(100).await.unwrap();
+
+        let stats = simulator.stats();
+        assert!(stats.commits_accepted > 0 && stats.application_messages > 0);
+        assert_eq!(stats.dropped + stats.duplicated + stats.reordered, 0);
+        assert!(simulator.epoch() > 1);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn unreliable_network_converges() {
+        for seed in 0..4 {
+            let config = SimulatorConfig::new().with_seed(seed);
+
+            let mut simulator = NetworkSimulator::new(config, 4, TestFactory).await.unwrap();
+            simulator.run(200).await.unwrap();
+
+            let stats = simulator.stats();
+            assert!(stats.dropped > 0 && stats.duplicated > 0 && stats.reordered > 0);
+            assert!(stats.replays_rejected > 0);
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn concurrent_commits_are_rejected_by_delivery_service() {
+        let config = SimulatorConfig::new().with_concurrent_commit_rate(1.0);
+
+        let mut simulator = NetworkSimulator::new(config, 3, TestFactory).await.unwrap();
+        simulator.run(100).await.unwrap();
+
+        assert!(simulator.stats().commits_rejected > 0);
+    }
+
+    #[derive(Default)]
+    struct RecordingFactory(Vec<usize>);
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+    impl ClientFactory for RecordingFactory {
+        type Config = TestClientConfig;
+
+        async fn new_client(&mut self, member_id: usize) -> Client<TestClientConfig> {
+            self.0.push(member_id);
+            TestFactory.new_client(member_id).await
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn rejected_joiners_do_not_reuse_ids() {
+        let config = SimulatorConfig::new().with_concurrent_commit_rate(1.0);
+
+        let mut simulator = NetworkSimulator::new(config, 3, RecordingFactory::default())
+            .await
+            .unwrap();
+
+        simulator.run(100).await.unwrap();
+
+        let stats = simulator.stats();
+        assert!(stats.commits_rejected > 0 && stats.members_added > 0);
+
+        let ids = &simulator.factory.0;
+        assert!(ids.iter().enumerate().all(|(i, &id)| id == i));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn same_seed_is_reproducible() {
+        let config = SimulatorConfig::new().with_seed(7);
+
+        let mut first = NetworkSimulator::new(config.clone(), 2, TestFactory)
+            .await
+            .unwrap();
+
+        let mut second = NetworkSimulator::new(config, 2, TestFactory).await.unwrap();
+
+        first.run(100).await.unwrap();
+        second.run(100).await.unwrap();
+
+        assert_eq!(first.stats(), second.stats());
+        assert_eq!(first.members(), second.members());
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn empty_group_is_rejected() {
+        let res = NetworkSimulator::new(SimulatorConfig::new(), 0, TestFactory).await;
+        assert!(matches!(res, Err(SimulationError::EmptyGroup)));
+    }
+}
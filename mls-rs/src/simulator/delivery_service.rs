@@ -0,0 +1,110 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::vec::Vec;
+
+use crate::{group::ExportedTree, MlsMessage};
+
+/// Message in flight between the delivery service and a single member.
+#[derive(Clone, Debug)]
+pub(crate) struct Envelope {
+    /// Identifier shared by every copy of the same logical message delivered
+    /// to a member. Used to recognize replays.
+    pub id: u64,
+    pub payload: Payload,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Payload {
+    Commit {
+        message: MlsMessage,
+        epoch: u64,
+        removed: Vec<usize>,
+    },
+    Application {
+        message: MlsMessage,
+        epoch: u64,
+    },
+    Welcome {
+        message: MlsMessage,
+        ratchet_tree: Option<ExportedTree<'static>>,
+    },
+}
+
+/// Virtual delivery service that totally orders commits.
+///
+/// Like a real MLS delivery service, at most one commit is accepted per
+/// epoch. A commit created against any other epoch is rejected and its
+/// sender is expected to discard it.
+#[derive(Clone, Debug)]
+pub(crate) struct DeliveryService {
+    epoch: u64,
+    members: Vec<usize>,
+    next_envelope_id: u64,
+}
+
+impl DeliveryService {
+    pub(crate) fn new(epoch: u64, members: Vec<usize>) -> Self {
+        Self {
+            epoch,
+            members,
+            next_envelope_id: 0,
+        }
+    }
+
+    pub(crate) fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub(crate) fn members(&self) -> &[usize] {
+        &self.members
+    }
+
+    /// Attempt to sequence a commit created at `epoch`.
+    ///
+    /// On success, returns the list of members the commit must be broadcast
+    /// to. This is the membership *before* the commit so that the committer
+    /// receives its own commit back and removed members learn about their
+    /// removal.
+    pub(crate) fn sequence_commit(
+        &mut self,
+        epoch: u64,
+        added: &[usize],
+        removed: &[usize],
+    ) -> Option<Vec<usize>> {
+        if epoch != self.epoch {
+            return None;
+        }
+
+        let recipients = self.members.clone();
+
+        self.epoch += 1;
+        self.members.retain(|m| !removed.contains(m));
+        self.members.extend_from_slice(added);
+
+        Some(recipients)
+    }
+
+    pub(crate) fn envelope(&mut self, payload: Payload) -> Envelope {
+        let id = self.next_envelope_id;
+        self.next_envelope_id += 1;
+
+        Envelope { id, payload }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DeliveryService;
+
+    #[test]
+    fn only_one_commit_per_epoch_is_accepted() {
+        let mut ds = DeliveryService::new(1, vec![0, 1, 2]);
+
+        assert_eq!(ds.sequence_commit(1, &[3], &[1]), Some(vec![0, 1, 2]));
+        assert_eq!(ds.sequence_commit(1, &[], &[]), None);
+        assert_eq!(ds.epoch(), 2);
+        assert_eq!(ds.members(), &[0, 2, 3]);
+    }
+}
@@ -12,7 +12,9 @@ use alloc::boxed::Box;
 use alloc::vec::Vec;
 use core::fmt::{self, Debug};
 use mls_rs_codec::{MlsDecode, MlsEncode};
-use mls_rs_core::group::{EpochRecord, GroupState, GroupStateStorage};
+use mls_rs_core::group::{
+    EpochRecord, GroupState, GroupStateDelta, GroupStateStorage, NodeUpdate, StoredGroupState,
+};
 #[cfg(not(target_has_atomic = "ptr"))]
 use portable_atomic_util::Arc;
 
@@ -35,6 +37,7 @@ pub(crate) const DEFAULT_EPOCH_RETENTION_LIMIT: usize = 3;
 #[derive(Clone)]
 pub(crate) struct InMemoryGroupData {
     pub(crate) state_data: Vec<u8>,
+    pub(crate) delta_state: Option<StoredGroupState>,
     pub(crate) epoch_data: VecDeque<EpochData>,
 }
 
@@ -45,6 +48,7 @@ impl Debug for InMemoryGroupData {
                 "state_data",
                 &mls_rs_core::debug::pretty_bytes(&self.state_data),
             )
+            .field("delta_state", &self.delta_state)
             .field("epoch_data", &self.epoch_data)
             .finish()
     }
@@ -54,10 +58,45 @@ impl InMemoryGroupData {
     pub fn new(state_data: Vec<u8>) -> InMemoryGroupData {
         InMemoryGroupData {
             state_data,
+            delta_state: None,
             epoch_data: Default::default(),
         }
     }
 
+    fn apply_delta(&mut self, delta: GroupStateDelta) {
+        self.state_data.clear();
+
+        let stored = self.delta_state.get_or_insert_with(Default::default);
+        stored.state = delta.state;
+
+        if delta.reset_nodes {
+            stored.tree_nodes.clear();
+            stored.secret_tree_nodes.clear();
+        }
+
+        apply_node_updates(&mut stored.tree_nodes, delta.tree_updates);
+        apply_node_updates(&mut stored.secret_tree_nodes, delta.secret_tree_updates);
+    }
+
+    fn write_epochs<ET: EpochRecord + MlsEncode>(
+        &mut self,
+        epoch_inserts: Vec<ET>,
+        epoch_updates: Vec<ET>,
+        max_epoch_retention: usize,
+    ) -> Result<(), mls_rs_codec::Error> {
+        for epoch in epoch_inserts {
+            self.insert_epoch(EpochData::new(epoch)?);
+        }
+
+        for epoch in epoch_updates {
+            self.update_epoch(EpochData::new(epoch)?);
+        }
+
+        self.trim_epochs(max_epoch_retention);
+
+        Ok(())
+    }
+
     fn get_epoch_data_index(&self, epoch_id: u64) -> Option<u64> {
         self.epoch_data
             .front()
@@ -94,6 +133,16 @@ impl InMemoryGroupData {
     }
 }
 
+fn apply_node_updates(nodes: &mut Vec<(u32, Vec<u8>)>, updates: Vec<NodeUpdate>) {
+    for update in updates {
+        nodes.retain(|(index, _)| *index != update.index);
+
+        if let Some(data) = update.data {
+            nodes.push((update.index, data));
+        }
+    }
+}
+
 #[derive(Clone)]
 /// In memory group state storage backed by a HashMap.
 ///
@@ -104,6 +153,7 @@ pub struct InMemoryGroupStateStorage {
     #[cfg(not(feature = "std"))]
     pub(crate) inner: Arc<Mutex<BTreeMap<Vec<u8>, InMemoryGroupData>>>,
     pub(crate) max_epoch_retention: usize,
+    pub(crate) incremental_writes: bool,
 }
 
 impl Debug for InMemoryGroupStateStorage {
@@ -122,6 +172,7 @@ impl Debug for InMemoryGroupStateStorage {
                 }),
             )
             .field("max_epoch_retention", &self.max_epoch_retention)
+            .field("incremental_writes", &self.incremental_writes)
             .finish()
     }
 }
@@ -132,6 +183,7 @@ impl InMemoryGroupStateStorage {
         Self {
             inner: Default::default(),
             max_epoch_retention: DEFAULT_EPOCH_RETENTION_LIMIT,
+            incremental_writes: false,
         }
     }
 
@@ -141,11 +193,20 @@ impl InMemoryGroupStateStorage {
             .ok_or(MlsError::NonZeroRetentionRequired)?;
 
         Ok(Self {
-            inner: self.inner,
             max_epoch_retention,
+            ..self
         })
     }
 
+    /// Store the ratchet tree and the secret tree of groups node by node, so
+    /// that writing a group state only updates the nodes that changed.
+    pub fn with_incremental_writes(self, incremental_writes: bool) -> Self {
+        Self {
+            incremental_writes,
+            ..self
+        }
+    }
+
     /// Get the set of unique group ids that have data stored.
     pub fn stored_groups(&self) -> Vec<Vec<u8>> {
         self.lock().keys().cloned().collect()
@@ -191,6 +252,7 @@ impl GroupStateStorage for InMemoryGroupStateStorage {
     {
         self.lock()
             .get(group_id)
+            .filter(|v| v.delta_state.is_none())
             .map(|v| T::mls_decode(&mut v.state_data.as_slice()))
             .transpose()
             .map_err(Into::into)
@@ -225,24 +287,43 @@ impl GroupStateStorage for InMemoryGroupStateStorage {
             Entry::Occupied(entry) => {
                 let data = entry.into_mut();
                 data.state_data = state_data;
+                data.delta_state = None;
                 data
             }
             Entry::Vacant(entry) => entry.insert(InMemoryGroupData::new(state_data)),
         };
 
-        epoch_inserts.into_iter().try_for_each(|e| {
-            group_data.insert_epoch(EpochData::new(e)?);
-            Ok::<_, Self::Error>(())
-        })?;
+        group_data.write_epochs(epoch_inserts, epoch_updates, self.max_epoch_retention)
+    }
 
-        epoch_updates.into_iter().try_for_each(|e| {
-            group_data.update_epoch(EpochData::new(e)?);
-            Ok::<_, Self::Error>(())
-        })?;
+    fn supports_deltas(&self) -> bool {
+        self.incremental_writes
+    }
 
-        group_data.trim_epochs(self.max_epoch_retention);
+    async fn write_delta<ET>(
+        &mut self,
+        delta: GroupStateDelta,
+        epoch_inserts: Vec<ET>,
+        epoch_updates: Vec<ET>,
+    ) -> Result<(), Self::Error>
+    where
+        ET: EpochRecord + MlsEncode + MlsDecode + Send + Sync,
+    {
+        let mut group_map = self.lock();
 
-        Ok(())
+        let group_data = group_map
+            .entry(delta.group_id.clone())
+            .or_insert_with(|| InMemoryGroupData::new(Vec::new()));
+
+        group_data.apply_delta(delta);
+        group_data.write_epochs(epoch_inserts, epoch_updates, self.max_epoch_retention)
+    }
+
+    async fn delta_state(&self, group_id: &[u8]) -> Result<Option<StoredGroupState>, Self::Error> {
+        Ok(self
+            .lock()
+            .get(group_id)
+            .and_then(|group_data| group_data.delta_state.clone()))
     }
 }
 
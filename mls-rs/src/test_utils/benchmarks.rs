@@ -1,3 +1,8 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
 use mls_rs_codec::MlsEncode;
 use mls_rs_core::protocol_version::ProtocolVersion;
 
@@ -136,3 +141,38 @@ pub async fn join_group(cs: CipherSuite, group_info: MlsMessage) -> GroupStates<
 
     GroupStates { sender, receiver }
 }
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Global allocator counting allocations, to report how many allocations a
+/// benchmarked operation makes next to its timing.
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    /// Number of allocations made by `f`, not counting those that are dropped
+    /// with its result.
+    pub fn count<T>(f: impl FnOnce() -> T) -> usize {
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        let result = f();
+        let count = ALLOCATIONS.load(Ordering::Relaxed) - before;
+        drop(result);
+
+        count
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
@@ -0,0 +1,493 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+//! Runs the official MLS test vectors through the interop checks of this crate.
+//!
+//! The vectors published at
+//! <https://github.com/mlswg/mls-implementations/tree/main/test-vectors>
+//! are grouped in one JSON file per [`TestVectorCategory`]. [`run`] checks
+//! every test case of such a file against a [`CryptoProvider`] using the same
+//! code as the interop tests of this crate, and reports the outcome per test
+//! case. Test cases using a cipher suite the crypto provider does not support
+//! are skipped.
+
+use std::{
+    any::Any,
+    boxed::Box,
+    fmt::{self, Display},
+    panic::AssertUnwindSafe,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use mls_rs_core::crypto::{CipherSuite, CryptoProvider};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
+
+use crate::{
+    group::{
+        interop_test_vectors::{
+            framing::FramingTestCase, passive_client, serialization, tree_kem::TreeKemTestCase,
+            welcome::WelcomeTestCase,
+        },
+        key_schedule::{
+            self,
+            test_utils::{DeriveSecretTestCase, ExpandWithLabelTestCase},
+        },
+        secret_tree::{interop_tests::InteropTestCase, test_utils::RatchetInteropTestCase},
+        transcript_hash,
+    },
+    hash_reference::test_utils::HashRefTestCase,
+    psk::secret::test_utils::TestScenario,
+    signer::test_utils::SignatureInteropTestCase,
+    tree_kem::{
+        hpke_encryption::test_utils::HpkeInteropTestCase, interop_test_vectors::ValidationTestCase,
+        math,
+    },
+};
+
+/// Category of official test vectors, each published as a separate file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TestVectorCategory {
+    TreeMath,
+    CryptoBasics,
+    SecretTree,
+    MessageProtection,
+    KeySchedule,
+    PskSecret,
+    TranscriptHashes,
+    Welcome,
+    TreeValidation,
+    TreeKem,
+    Messages,
+    PassiveClientWelcome,
+    PassiveClientHandleCommit,
+    PassiveClientRandom,
+}
+
+impl TestVectorCategory {
+    /// All categories, in the order they are listed upstream.
+    pub const ALL: [TestVectorCategory; 14] = [
+        Self::TreeMath,
+        Self::CryptoBasics,
+        Self::SecretTree,
+        Self::MessageProtection,
+        Self::KeySchedule,
+        Self::PskSecret,
+        Self::TranscriptHashes,
+        Self::Welcome,
+        Self::TreeValidation,
+        Self::TreeKem,
+        Self::Messages,
+        Self::PassiveClientWelcome,
+        Self::PassiveClientHandleCommit,
+        Self::PassiveClientRandom,
+    ];
+
+    /// Name of the upstream file containing vectors of this category.
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Self::TreeMath => "tree-math.json",
+            Self::CryptoBasics => "crypto-basics.json",
+            Self::SecretTree => "secret-tree.json",
+            Self::MessageProtection => "message-protection.json",
+            Self::KeySchedule => "key-schedule.json",
+            Self::PskSecret => "psk_secret.json",
+            Self::TranscriptHashes => "transcript-hashes.json",
+            Self::Welcome => "welcome.json",
+            Self::TreeValidation => "tree-validation.json",
+            Self::TreeKem => "treekem.json",
+            Self::Messages => "messages.json",
+            Self::PassiveClientWelcome => "passive-client-welcome.json",
+            Self::PassiveClientHandleCommit => "passive-client-handle-commit.json",
+            Self::PassiveClientRandom => "passive-client-random.json",
+        }
+    }
+}
+
+impl Display for TestVectorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.file_name().trim_end_matches(".json"))
+    }
+}
+
+/// Result of checking a single test case.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Passed,
+    /// The check failed with the given message.
+    Failed(String),
+    /// The crypto provider does not support the cipher suite of the test case.
+    Skipped,
+}
+
+/// Result of checking the test case at position `index` of a vector file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CaseReport {
+    pub index: usize,
+    /// Cipher suite of the test case, if the category is cipher suite
+    /// specific.
+    pub cipher_suite: Option<CipherSuite>,
+    pub outcome: Outcome,
+}
+
+/// Check every test case of a vector file of the given `category`.
+///
+/// An error is returned only if `vectors` cannot be deserialized as test
+/// cases of `category`.
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+pub async fn run<'de, D: Deserializer<'de>, C: CryptoProvider + Clone>(
+    category: TestVectorCategory,
+    vectors: D,
+    crypto_provider: &C,
+) -> Result<Vec<CaseReport>, D::Error> {
+    match category {
+        TestVectorCategory::TreeMath => {
+            run_cases::<math::test_utils::TestCase, _, _>(vectors, crypto_provider).await
+        }
+        TestVectorCategory::CryptoBasics => {
+            run_cases::<CryptoBasicsTestCase, _, _>(vectors, crypto_provider).await
+        }
+        TestVectorCategory::SecretTree => {
+            run_cases::<InteropTestCase, _, _>(vectors, crypto_provider).await
+        }
+        TestVectorCategory::MessageProtection => {
+            run_cases::<FramingTestCase, _, _>(vectors, crypto_provider).await
+        }
+        TestVectorCategory::KeySchedule => {
+            run_cases::<key_schedule::test_utils::TestCase, _, _>(vectors, crypto_provider).await
+        }
+        TestVectorCategory::PskSecret => {
+            run_cases::<TestScenario, _, _>(vectors, crypto_provider).await
+        }
+        TestVectorCategory::TranscriptHashes => {
+            run_cases::<transcript_hash::test_utils::TestCase, _, _>(vectors, crypto_provider).await
+        }
+        TestVectorCategory::Welcome => {
+            run_cases::<WelcomeTestCase, _, _>(vectors, crypto_provider).await
+        }
+        TestVectorCategory::TreeValidation => {
+            run_cases::<ValidationTestCase, _, _>(vectors, crypto_provider).await
+        }
+        TestVectorCategory::TreeKem => {
+            run_cases::<TreeKemTestCase, _, _>(vectors, crypto_provider).await
+        }
+        TestVectorCategory::Messages => {
+            run_cases::<serialization::TestCase, _, _>(vectors, crypto_provider).await
+        }
+        TestVectorCategory::PassiveClientWelcome
+        | TestVectorCategory::PassiveClientHandleCommit
+        | TestVectorCategory::PassiveClientRandom => {
+            run_cases::<passive_client::TestCase, _, _>(vectors, crypto_provider).await
+        }
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+async fn run_cases<'de, T: TestVector, D: Deserializer<'de>, C: CryptoProvider + Clone>(
+    vectors: D,
+    crypto_provider: &C,
+) -> Result<Vec<CaseReport>, D::Error> {
+    let cases = Vec::<T>::deserialize(vectors)?;
+    let mut reports = Vec::with_capacity(cases.len());
+
+    for (index, case) in cases.into_iter().enumerate() {
+        let cipher_suite = case.cipher_suite();
+
+        let supported = cipher_suite
+            .map(|cs| crypto_provider.cipher_suite_provider(cs).is_some())
+            .unwrap_or(true);
+
+        let outcome = if supported {
+            check(&case, crypto_provider).await
+        } else {
+            Outcome::Skipped
+        };
+
+        reports.push(CaseReport {
+            index,
+            cipher_suite,
+            outcome,
+        });
+    }
+
+    Ok(reports)
+}
+
+/// The interop checks assert on mismatches, so a failed check is a panic.
+#[cfg(not(mls_build_async))]
+fn check<T: TestVector, C: CryptoProvider + Clone>(case: &T, crypto_provider: &C) -> Outcome {
+    std::panic::catch_unwind(AssertUnwindSafe(|| case.verify(crypto_provider))).map_or_else(
+        |payload| Outcome::Failed(panic_message(payload)),
+        |_| Outcome::Passed,
+    )
+}
+
+#[cfg(mls_build_async)]
+async fn check<T: TestVector, C: CryptoProvider + Clone>(case: &T, crypto_provider: &C) -> Outcome {
+    use futures::FutureExt;
+
+    AssertUnwindSafe(case.verify(crypto_provider))
+        .catch_unwind()
+        .await
+        .map_or_else(
+            |payload| Outcome::Failed(panic_message(payload)),
+            |_| Outcome::Passed,
+        )
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .unwrap_or_else(|| "test case panicked".to_string()),
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async(?Send))]
+trait TestVector: DeserializeOwned {
+    fn cipher_suite(&self) -> Option<CipherSuite>;
+
+    async fn verify<C: CryptoProvider + Clone>(&self, crypto_provider: &C);
+}
+
+fn cipher_suite_provider<C: CryptoProvider>(
+    crypto_provider: &C,
+    cipher_suite: u16,
+) -> C::CipherSuiteProvider {
+    crypto_provider
+        .cipher_suite_provider(cipher_suite.into())
+        .unwrap()
+}
+
+/// Test vectors checking individual primitives with labels.
+#[derive(Debug, serde::Deserialize)]
+struct CryptoBasicsTestCase {
+    cipher_suite: u16,
+    ref_hash: HashRefTestCase,
+    expand_with_label: ExpandWithLabelTestCase,
+    derive_secret: DeriveSecretTestCase,
+    derive_tree_secret: RatchetInteropTestCase,
+    sign_with_label: SignatureInteropTestCase,
+    encrypt_with_label: HpkeInteropTestCase,
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async(?Send))]
+impl TestVector for math::test_utils::TestCase {
+    fn cipher_suite(&self) -> Option<CipherSuite> {
+        None
+    }
+
+    async fn verify<C: CryptoProvider + Clone>(&self, _crypto_provider: &C) {
+        self.verify()
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async(?Send))]
+impl TestVector for CryptoBasicsTestCase {
+    fn cipher_suite(&self) -> Option<CipherSuite> {
+        Some(self.cipher_suite.into())
+    }
+
+    async fn verify<C: CryptoProvider + Clone>(&self, crypto_provider: &C) {
+        let cs = cipher_suite_provider(crypto_provider, self.cipher_suite);
+
+        self.ref_hash.verify(&cs).await;
+        self.expand_with_label.verify(&cs).await;
+        self.derive_secret.verify(&cs).await;
+        self.derive_tree_secret.verify(&cs).await;
+        self.sign_with_label.verify(&cs).await;
+        self.encrypt_with_label.verify(&cs).await;
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async(?Send))]
+impl TestVector for InteropTestCase {
+    fn cipher_suite(&self) -> Option<CipherSuite> {
+        Some(self.cipher_suite.into())
+    }
+
+    async fn verify<C: CryptoProvider + Clone>(&self, crypto_provider: &C) {
+        self.verify(&cipher_suite_provider(crypto_provider, self.cipher_suite))
+            .await
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async(?Send))]
+impl TestVector for FramingTestCase {
+    fn cipher_suite(&self) -> Option<CipherSuite> {
+        Some(self.context.cipher_suite.into())
+    }
+
+    async fn verify<C: CryptoProvider + Clone>(&self, crypto_provider: &C) {
+        self.verify(crypto_provider).await
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async(?Send))]
+impl TestVector for key_schedule::test_utils::TestCase {
+    fn cipher_suite(&self) -> Option<CipherSuite> {
+        Some(self.cipher_suite.into())
+    }
+
+    async fn verify<C: CryptoProvider + Clone>(&self, crypto_provider: &C) {
+        self.verify(&cipher_suite_provider(crypto_provider, self.cipher_suite))
+            .await
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async(?Send))]
+impl TestVector for TestScenario {
+    fn cipher_suite(&self) -> Option<CipherSuite> {
+        Some(self.cipher_suite.into())
+    }
+
+    async fn verify<C: CryptoProvider + Clone>(&self, crypto_provider: &C) {
+        self.verify(&cipher_suite_provider(crypto_provider, self.cipher_suite))
+            .await
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async(?Send))]
+impl TestVector for transcript_hash::test_utils::TestCase {
+    fn cipher_suite(&self) -> Option<CipherSuite> {
+        Some(self.cipher_suite.into())
+    }
+
+    async fn verify<C: CryptoProvider + Clone>(&self, crypto_provider: &C) {
+        self.verify(&cipher_suite_provider(crypto_provider, self.cipher_suite))
+            .await
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async(?Send))]
+impl TestVector for WelcomeTestCase {
+    fn cipher_suite(&self) -> Option<CipherSuite> {
+        Some(self.cipher_suite.into())
+    }
+
+    async fn verify<C: CryptoProvider + Clone>(&self, crypto_provider: &C) {
+        self.verify(&cipher_suite_provider(crypto_provider, self.cipher_suite))
+            .await
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async(?Send))]
+impl TestVector for ValidationTestCase {
+    fn cipher_suite(&self) -> Option<CipherSuite> {
+        Some(self.cipher_suite.into())
+    }
+
+    async fn verify<C: CryptoProvider + Clone>(&self, crypto_provider: &C) {
+        self.verify(&cipher_suite_provider(crypto_provider, self.cipher_suite))
+            .await
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async(?Send))]
+impl TestVector for TreeKemTestCase {
+    fn cipher_suite(&self) -> Option<CipherSuite> {
+        Some(self.cipher_suite.into())
+    }
+
+    async fn verify<C: CryptoProvider + Clone>(&self, crypto_provider: &C) {
+        self.verify(crypto_provider).await
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async(?Send))]
+impl TestVector for serialization::TestCase {
+    fn cipher_suite(&self) -> Option<CipherSuite> {
+        None
+    }
+
+    async fn verify<C: CryptoProvider + Clone>(&self, _crypto_provider: &C) {
+        self.verify()
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async(?Send))]
+impl TestVector for passive_client::TestCase {
+    fn cipher_suite(&self) -> Option<CipherSuite> {
+        Some(self.cipher_suite.into())
+    }
+
+    async fn verify<C: CryptoProvider + Clone>(&self, crypto_provider: &C) {
+        self.verify(crypto_provider).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use crate::crypto::test_utils::TestCryptoProvider;
+
+    use super::{run, Outcome, TestVectorCategory};
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn bundled_tree_math_vectors_pass() {
+        let vectors = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/test_data/tree_math.json"
+        ));
+
+        let reports = run(
+            TestVectorCategory::TreeMath,
+            &mut serde_json::Deserializer::from_slice(vectors),
+            &TestCryptoProvider::new(),
+        )
+        .await
+        .unwrap();
+
+        assert!(!reports.is_empty());
+
+        assert!(reports
+            .iter()
+            .all(|report| report.outcome == Outcome::Passed && report.cipher_suite.is_none()));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn mismatching_vector_fails_case() {
+        let vectors = br#"[{"n_leaves": 1, "n_nodes": 3, "root": 0,
+            "left": [null], "right": [null], "parent": [null], "sibling": [null]}]"#;
+
+        let reports = run(
+            TestVectorCategory::TreeMath,
+            &mut serde_json::Deserializer::from_slice(vectors),
+            &TestCryptoProvider::new(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(reports.len(), 1);
+        assert_matches!(reports[0].outcome, Outcome::Failed(_));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn invalid_vectors_are_rejected() {
+        let res = run(
+            TestVectorCategory::KeySchedule,
+            &mut serde_json::Deserializer::from_slice(b"{}"),
+            &TestCryptoProvider::new(),
+        )
+        .await;
+
+        assert!(res.is_err());
+    }
+}
@@ -8,6 +8,9 @@ pub mod benchmarks;
 #[cfg(all(feature = "fuzz_util", not(mls_build_async)))]
 pub mod fuzz_tests;
 
+#[cfg(feature = "conformance_util")]
+pub mod conformance;
+
 use mls_rs_core::{
     crypto::{CipherSuite, CipherSuiteProvider, CryptoProvider},
     identity::{BasicCredential, Credential, SigningIdentity},
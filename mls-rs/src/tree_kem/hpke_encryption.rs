@@ -4,7 +4,7 @@
 
 use alloc::vec::Vec;
 use core::fmt::{self, Debug};
-use mls_rs_codec::{MlsEncode, MlsSize};
+use mls_rs_codec::{DecodeLimits, MlsEncode, MlsSize};
 use mls_rs_core::{
     crypto::{CipherSuiteProvider, HpkeCiphertext, HpkePublicKey, HpkeSecretKey},
     error::IntoAnyError,
@@ -73,6 +73,7 @@ pub(crate) trait HpkeEncryptable: Sized {
         public_key: &HpkePublicKey,
         context: &[u8],
         ciphertext: &HpkeCiphertext,
+        limits: DecodeLimits,
     ) -> Result<Self, MlsError> {
         let context = EncryptContext::new(Self::ENCRYPT_LABEL, context).mls_encode_to_vec()?;
 
@@ -81,20 +82,24 @@ pub(crate) trait HpkeEncryptable: Sized {
             .await
             .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))?;
 
-        Self::from_bytes(plaintext.to_vec())
+        Self::from_bytes(plaintext.to_vec(), limits)
     }
 
-    fn from_bytes(bytes: Vec<u8>) -> Result<Self, MlsError>;
+    /// Create a value from decrypted `bytes`, which are decoded within `limits`.
+    fn from_bytes(bytes: Vec<u8>, limits: DecodeLimits) -> Result<Self, MlsError>;
     fn get_bytes(&self) -> Result<Vec<u8>, MlsError>;
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "conformance_util"))]
 pub(crate) mod test_utils {
     use alloc::{string::String, vec::Vec};
-    use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+    use mls_rs_codec::{DecodeLimits, MlsDecode, MlsEncode, MlsSize};
     use mls_rs_core::crypto::{CipherSuiteProvider, HpkeCiphertext};
 
-    use crate::{client::MlsError, crypto::test_utils::try_test_cipher_suite_provider};
+    use crate::client::MlsError;
+
+    #[cfg(test)]
+    use crate::crypto::test_utils::try_test_cipher_suite_provider;
 
     use super::HpkeEncryptable;
 
@@ -115,12 +120,14 @@ pub(crate) mod test_utils {
         ciphertext: Vec<u8>,
     }
 
+    #[cfg(test)]
     #[derive(Debug, serde::Serialize, serde::Deserialize)]
     pub struct InteropTestCase {
         cipher_suite: u16,
         encrypt_with_label: HpkeInteropTestCase,
     }
 
+    #[cfg(test)]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn test_basic_crypto_test_vectors() {
         // The test vector can be found here https://github.com/mlswg/mls-implementations/blob/main/test-vectors/crypto-basics.json
@@ -140,7 +147,7 @@ pub(crate) mod test_utils {
     impl HpkeEncryptable for TestEncryptable {
         const ENCRYPT_LABEL: &'static str = "EncryptWithLabel";
 
-        fn from_bytes(bytes: Vec<u8>) -> Result<Self, MlsError> {
+        fn from_bytes(bytes: Vec<u8>, _limits: DecodeLimits) -> Result<Self, MlsError> {
             Ok(Self(bytes))
         }
 
@@ -161,10 +168,16 @@ pub(crate) mod test_utils {
                 ciphertext: self.ciphertext.clone(),
             };
 
-            let computed_plaintext =
-                TestEncryptable::decrypt(cs, &secret, &public, &self.context, &ciphertext)
-                    .await
-                    .unwrap();
+            let computed_plaintext = TestEncryptable::decrypt(
+                cs,
+                &secret,
+                &public,
+                &self.context,
+                &ciphertext,
+                DecodeLimits::default(),
+            )
+            .await
+            .unwrap();
 
             assert_eq!(&computed_plaintext.0, &self.plaintext)
         }
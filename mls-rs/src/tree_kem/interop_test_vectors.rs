@@ -2,23 +2,36 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use alloc::vec;
 use alloc::vec::Vec;
-use mls_rs_codec::{MlsDecode, MlsEncode};
-use mls_rs_core::crypto::{CipherSuite, CipherSuiteProvider};
+use mls_rs_codec::MlsDecode;
+use mls_rs_core::crypto::CipherSuiteProvider;
+use mls_rs_core::extension::ExtensionList;
 
 use itertools::Itertools;
 
 use crate::{
-    crypto::test_utils::try_test_cipher_suite_provider, identity::basic::BasicIdentityProvider,
+    group::GroupContext, identity::basic::BasicIdentityProvider, protocol_version::ProtocolVersion,
 };
 
-use super::{
-    node::NodeVec, test_utils::TreeWithSigners, tree_validator::TreeValidator, TreeKemPublic,
-};
+use super::{node::NodeVec, tree_validator::TreeValidator, TreeKemPublic};
+
+#[cfg(test)]
+use alloc::vec;
+
+#[cfg(test)]
+use mls_rs_codec::MlsEncode;
+
+#[cfg(test)]
+use mls_rs_core::crypto::CipherSuite;
+
+#[cfg(test)]
+use crate::crypto::test_utils::try_test_cipher_suite_provider;
+
+#[cfg(test)]
+use super::test_utils::TreeWithSigners;
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
-struct ValidationTestCase {
+pub struct ValidationTestCase {
     pub cipher_suite: u16,
 
     #[serde(with = "hex::serde")]
@@ -30,8 +43,9 @@ struct ValidationTestCase {
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
-struct TreeHash(#[serde(with = "hex::serde")] pub Vec<u8>);
+pub struct TreeHash(#[serde(with = "hex::serde")] pub Vec<u8>);
 
+#[cfg(test)]
 impl From<crate::tree_kem::tree_hash::TreeHash> for TreeHash {
     #[cfg_attr(coverage_nightly, coverage(off))]
     fn from(value: crate::tree_kem::tree_hash::TreeHash) -> Self {
@@ -40,6 +54,7 @@ impl From<crate::tree_kem::tree_hash::TreeHash> for TreeHash {
 }
 
 impl ValidationTestCase {
+    #[cfg(test)]
     #[cfg_attr(coverage_nightly, coverage(off))]
     fn new<P: CipherSuiteProvider>(tree: TreeKemPublic, group_id: &[u8], cs: &P) -> Self {
         let tree_size = tree.total_leaf_count() * 2 - 1;
@@ -69,65 +84,68 @@ impl ValidationTestCase {
             resolutions,
         }
     }
-}
-
-#[cfg(feature = "rfc_compliant")]
-#[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-#[cfg_attr(coverage_nightly, coverage(off))]
-async fn validation() {
-    use crate::group::test_utils::get_test_group_context;
-
-    #[cfg(mls_build_async)]
-    let test_cases: Vec<ValidationTestCase> = load_test_case_json!(
-        interop_tree_validation,
-        generate_validation_test_vector().await
-    );
-
-    #[cfg(not(mls_build_async))]
-    let test_cases: Vec<ValidationTestCase> =
-        load_test_case_json!(interop_tree_validation, generate_validation_test_vector());
-
-    for test_case in test_cases.into_iter() {
-        let Some(cs) = try_test_cipher_suite_provider(test_case.cipher_suite) else {
-            continue;
-        };
 
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn verify<P: CipherSuiteProvider>(&self, cs: &P) {
         let mut tree = TreeKemPublic::import_node_data(
-            NodeVec::mls_decode(&mut &*test_case.tree).unwrap(),
+            NodeVec::mls_decode(&mut &*self.tree).unwrap(),
             &BasicIdentityProvider,
             &Default::default(),
         )
         .await
         .unwrap();
 
-        let tree_hash = tree.tree_hash(&cs).await.unwrap();
+        let tree_hash = tree.tree_hash(cs).await.unwrap();
 
         tree.tree_hashes
             .current
             .iter()
-            .zip_eq(test_case.tree_hashes.iter())
+            .zip_eq(self.tree_hashes.iter())
             .for_each(|(l, r)| assert_eq!(**l, *r.0));
 
-        test_case
-            .resolutions
-            .iter()
-            .enumerate()
-            .for_each(|(i, res)| {
-                assert_eq!(&tree.nodes.get_resolution_index(i as u32).unwrap(), res)
-            });
-
-        let mut context = get_test_group_context(1, test_case.cipher_suite.into()).await;
-        context.tree_hash = tree_hash;
-        context.group_id = test_case.group_id;
+        self.resolutions.iter().enumerate().for_each(|(i, res)| {
+            assert_eq!(&tree.nodes.get_resolution_index(i as u32).unwrap(), res)
+        });
+
+        let context = GroupContext {
+            protocol_version: ProtocolVersion::MLS_10,
+            cipher_suite: cs.cipher_suite(),
+            group_id: self.group_id.clone(),
+            epoch: 1,
+            tree_hash,
+            confirmed_transcript_hash: Vec::new().into(),
+            extensions: ExtensionList::new(),
+        };
 
-        TreeValidator::new(&cs, &context, &BasicIdentityProvider)
+        TreeValidator::new(cs, &context, &BasicIdentityProvider, None)
             .validate(&mut tree)
             .await
             .unwrap();
     }
 }
 
-#[cfg(feature = "rfc_compliant")]
+#[cfg(all(test, feature = "rfc_compliant"))]
+#[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+async fn validation() {
+    #[cfg(mls_build_async)]
+    let test_cases: Vec<ValidationTestCase> = load_test_case_json!(
+        interop_tree_validation,
+        generate_validation_test_vector().await
+    );
+
+    #[cfg(not(mls_build_async))]
+    let test_cases: Vec<ValidationTestCase> =
+        load_test_case_json!(interop_tree_validation, generate_validation_test_vector());
+
+    for test_case in test_cases.into_iter() {
+        if let Some(cs) = try_test_cipher_suite_provider(test_case.cipher_suite) {
+            test_case.verify(&cs).await;
+        }
+    }
+}
+
+#[cfg(all(test, feature = "rfc_compliant"))]
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 #[cfg_attr(coverage_nightly, coverage(off))]
 async fn generate_validation_test_vector() -> Vec<ValidationTestCase> {
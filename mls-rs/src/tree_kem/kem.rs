@@ -11,7 +11,7 @@ use crate::tree_kem::math as tree_math;
 use alloc::vec;
 use alloc::vec::Vec;
 use itertools::Itertools;
-use mls_rs_codec::MlsEncode;
+use mls_rs_codec::{DecodeLimits, MlsEncode};
 use tree_math::{CopathNode, TreeIndex};
 
 #[cfg(all(not(mls_build_async), feature = "rayon"))]
@@ -286,8 +286,16 @@ impl<'a> TreeKem<'a> {
             .ok_or(MlsError::UpdateErrorNoSecretKey)?
             .public_key();
 
-        let lca_path_secret =
-            PathSecret::decrypt(cipher_suite_provider, secret, public, context_bytes, ct).await?;
+        // Path secrets are taken as they are, without being decoded.
+        let lca_path_secret = PathSecret::decrypt(
+            cipher_suite_provider,
+            secret,
+            public,
+            context_bytes,
+            ct,
+            DecodeLimits::UNLIMITED,
+        )
+        .await?;
 
         // Derive the rest of the secrets for the tree and assign to the proper nodes
         let mut node_secret_gen =
@@ -30,6 +30,7 @@ pub struct LeafNode {
     pub signing_identity: SigningIdentity,
     pub capabilities: Capabilities,
     pub leaf_node_source: LeafNodeSource,
+    #[cfg_attr(feature = "serde", serde(with = "crate::extension::list_serde"))]
     pub extensions: ExtensionList,
     #[mls_codec(with = "mls_rs_codec::byte_vec")]
     #[cfg_attr(feature = "serde", serde(with = "mls_rs_core::vec_serde"))]
@@ -195,7 +196,10 @@ impl<'a> MlsSize for LeafNodeTBS<'a> {
 }
 
 impl<'a> MlsEncode for LeafNodeTBS<'a> {
-    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), mls_rs_codec::Error> {
+    fn mls_encode<W: mls_rs_codec::Writer + ?Sized>(
+        &self,
+        writer: &mut W,
+    ) -> Result<(), mls_rs_codec::Error> {
         self.public_key.mls_encode(writer)?;
         self.signing_identity.mls_encode(writer)?;
         self.capabilities.mls_encode(writer)?;
@@ -104,14 +104,17 @@ impl<'a, C: IdentityProvider, CP: CipherSuiteProvider> LeafNodeValidator<'a, C,
         leaf_node: &LeafNode,
         group_id: &[u8],
         leaf_index: u32,
+        time: Option<MlsTime>,
     ) -> Result<(), MlsError> {
         let context = match leaf_node.leaf_node_source {
+            // Members are not required to update before the lifetime of the key package they
+            // joined with expires, so only the credential is checked against `time`.
             LeafNodeSource::KeyPackage(_) => ValidationContext::Add(None),
-            LeafNodeSource::Update => ValidationContext::Update((group_id, leaf_index, None)),
-            LeafNodeSource::Commit(_) => ValidationContext::Commit((group_id, leaf_index, None)),
+            LeafNodeSource::Update => ValidationContext::Update((group_id, leaf_index, time)),
+            LeafNodeSource::Commit(_) => ValidationContext::Commit((group_id, leaf_index, time)),
         };
 
-        self.check_if_valid(leaf_node, context).await
+        self.validate_at(leaf_node, context, time).await
     }
 
     pub fn validate_required_capabilities(&self, leaf_node: &LeafNode) -> Result<(), MlsError> {
@@ -173,16 +176,28 @@ impl<'a, C: IdentityProvider, CP: CipherSuiteProvider> LeafNodeValidator<'a, C,
         &self,
         leaf_node: &LeafNode,
         context: ValidationContext<'_>,
+    ) -> Result<(), MlsError> {
+        let time = context.generation_time();
+        self.validate_at(leaf_node, context, time).await
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn validate_at(
+        &self,
+        leaf_node: &LeafNode,
+        context: ValidationContext<'_>,
+        time: Option<MlsTime>,
     ) -> Result<(), MlsError> {
         // Check that we are validating within the proper context
         self.check_context(leaf_node, &context)?;
 
         // Verify the credential
         self.identity_provider
-            .validate_member(
+            .validate_member_with_leaf_extensions(
                 &leaf_node.signing_identity,
-                context.generation_time(),
+                time,
                 self.group_context_extensions,
+                &leaf_node.extensions,
             )
             .await
             .map_err(|e| MlsError::IdentityProviderError(e.into_any_error()))?;
@@ -22,18 +22,28 @@ impl Lifetime {
         }
     }
 
+    /// Lifetime covering the `s` seconds after the current system time.
+    ///
+    /// Use [`Lifetime::seconds_from`] to start the lifetime at a time given by a
+    /// [`TimeProvider`](crate::time::TimeProvider) instead.
     pub fn seconds(s: u64) -> Result<Self, MlsError> {
         #[cfg(feature = "std")]
-        let not_before = MlsTime::now().seconds_since_epoch();
+        let now = MlsTime::now();
         #[cfg(not(feature = "std"))]
         // There is no clock on no_std, this is here just so that we can run tests.
-        let not_before = 3600u64;
+        let now = MlsTime::from(3600u64);
 
+        Self::seconds_from(now, s)
+    }
+
+    /// Lifetime covering the `s` seconds after `now`.
+    pub fn seconds_from(now: MlsTime, s: u64) -> Result<Self, MlsError> {
+        let not_before = now.seconds_since_epoch();
         let not_after = not_before.checked_add(s).ok_or(MlsError::TimeOverflow)?;
 
         Ok(Lifetime {
             // Subtract 1 hour to address time difference between machines
-            not_before: not_before - 3600,
+            not_before: not_before.saturating_sub(3600),
             not_after,
         })
     }
@@ -72,6 +82,12 @@ mod tests {
         assert_eq!(lifetime.not_after - lifetime.not_before, 3610);
     }
 
+    #[test]
+    fn test_seconds_from() {
+        let lifetime = Lifetime::seconds_from(MlsTime::from(10_000u64), 10).unwrap();
+        assert_eq!(lifetime, Lifetime::new(6400, 10_010));
+    }
+
     #[test]
     fn test_days() {
         let days = 2;
@@ -191,24 +191,41 @@ impl Iterator for BfsIterTopDown {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use itertools::Itertools;
-    use serde::{Deserialize, Serialize};
+#[cfg(any(test, feature = "conformance_util"))]
+pub(crate) mod test_utils {
+    use alloc::vec::Vec;
+
+    use super::TreeIndex;
+
+    #[derive(Debug, serde::Serialize, serde::Deserialize)]
+    pub struct TestCase {
+        pub n_leaves: u32,
+        pub n_nodes: u32,
+        pub root: u32,
+        pub left: Vec<Option<u32>>,
+        pub right: Vec<Option<u32>>,
+        pub parent: Vec<Option<u32>>,
+        pub sibling: Vec<Option<u32>>,
+    }
 
-    #[cfg(target_arch = "wasm32")]
-    use wasm_bindgen_test::wasm_bindgen_test as test;
+    impl TestCase {
+        pub fn verify(&self) {
+            assert_eq!(node_width(self.n_leaves), self.n_nodes);
+            assert_eq!(self.n_leaves.root(), self.root);
+
+            for x in 0..self.n_nodes {
+                assert_eq!(x.left(), self.left[x as usize]);
+                assert_eq!(x.right(), self.right[x as usize]);
 
-    #[derive(Serialize, Deserialize)]
-    struct TestCase {
-        n_leaves: u32,
-        n_nodes: u32,
-        root: u32,
-        left: Vec<Option<u32>>,
-        right: Vec<Option<u32>>,
-        parent: Vec<Option<u32>>,
-        sibling: Vec<Option<u32>>,
+                let (p, s) = x
+                    .parent_sibling(&self.n_leaves)
+                    .map(|ps| (ps.parent, ps.sibling))
+                    .unzip();
+
+                assert_eq!(p, self.parent[x as usize]);
+                assert_eq!(s, self.sibling[x as usize]);
+            }
+        }
     }
 
     pub fn node_width(n: u32) -> u32 {
@@ -218,6 +235,16 @@ mod tests {
             2 * (n - 1) + 1
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_utils::{node_width, TestCase};
+    use super::*;
+    use itertools::Itertools;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
 
     #[test]
     fn test_bfs_iterator() {
@@ -267,21 +294,7 @@ mod tests {
         let test_cases = load_test_cases();
 
         for case in test_cases {
-            assert_eq!(node_width(case.n_leaves), case.n_nodes);
-            assert_eq!(case.n_leaves.root(), case.root);
-
-            for x in 0..case.n_nodes {
-                assert_eq!(x.left(), case.left[x as usize]);
-                assert_eq!(x.right(), case.right[x as usize]);
-
-                let (p, s) = x
-                    .parent_sibling(&case.n_leaves)
-                    .map(|ps| (ps.parent, ps.sibling))
-                    .unzip();
-
-                assert_eq!(p, case.parent[x as usize]);
-                assert_eq!(s, case.sibling[x as usize]);
-            }
+            case.verify();
         }
     }
 
@@ -59,8 +59,8 @@ mod tree_index;
 #[cfg(feature = "std")]
 pub(crate) mod tree_utils;
 
-#[cfg(test)]
-mod interop_test_vectors;
+#[cfg(any(test, feature = "conformance_util"))]
+pub(crate) mod interop_test_vectors;
 
 #[cfg(feature = "custom_proposal")]
 use crate::group::proposal::ProposalType;
@@ -86,6 +86,15 @@ impl TreeKemPublic {
         Default::default()
     }
 
+    /// Tree made of `nodes` whose identity index is not initialized, which
+    /// is enough to compute tree hashes.
+    pub(crate) fn from_nodes(nodes: NodeVec) -> TreeKemPublic {
+        TreeKemPublic {
+            nodes,
+            ..Default::default()
+        }
+    }
+
     #[cfg_attr(not(feature = "tree_index"), allow(unused))]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub(crate) async fn import_node_data<IP>(
@@ -214,7 +223,7 @@ impl TreeKemPublic {
             .all(|(_, l)| l.capabilities.proposals.contains(&proposal_type))
     }
 
-    #[cfg(test)]
+    #[cfg(any(test, feature = "conformance_util"))]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn add_leaves<I: IdentityProvider, CP: CipherSuiteProvider>(
         &mut self,
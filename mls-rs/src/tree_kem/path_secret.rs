@@ -11,7 +11,7 @@ use core::{
     fmt::{self, Debug},
     ops::Deref,
 };
-use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+use mls_rs_codec::{DecodeLimits, MlsDecode, MlsEncode, MlsSize};
 use mls_rs_core::error::IntoAnyError;
 use zeroize::Zeroizing;
 
@@ -72,7 +72,7 @@ impl PathSecret {
 impl HpkeEncryptable for PathSecret {
     const ENCRYPT_LABEL: &'static str = "UpdatePathNode";
 
-    fn from_bytes(bytes: Vec<u8>) -> Result<Self, MlsError> {
+    fn from_bytes(bytes: Vec<u8>, _limits: DecodeLimits) -> Result<Self, MlsError> {
         Ok(Self(Zeroizing::new(bytes)))
     }
 
@@ -97,7 +97,7 @@ impl TreeKemPrivate {
     }
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "conformance_util"))]
 impl TreeKemPrivate {
     pub fn new(self_index: LeafIndex) -> Self {
         TreeKemPrivate {
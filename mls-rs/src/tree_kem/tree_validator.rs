@@ -14,6 +14,7 @@ use crate::client::MlsError;
 use crate::crypto::CipherSuiteProvider;
 use crate::group::GroupContext;
 use crate::iter::wrap_impl_iter;
+use crate::time::MlsTime;
 use crate::tree_kem::math as tree_math;
 use crate::tree_kem::{leaf_node_validator::LeafNodeValidator, TreeKemPublic};
 use mls_rs_core::identity::IdentityProvider;
@@ -33,6 +34,7 @@ where
     leaf_node_validator: LeafNodeValidator<'a, C, CSP>,
     group_id: &'a [u8],
     cipher_suite_provider: &'a CSP,
+    time: Option<MlsTime>,
 }
 
 impl<'a, C: IdentityProvider, CSP: CipherSuiteProvider> TreeValidator<'a, C, CSP> {
@@ -40,6 +42,7 @@ impl<'a, C: IdentityProvider, CSP: CipherSuiteProvider> TreeValidator<'a, C, CSP
         cipher_suite_provider: &'a CSP,
         context: &'a GroupContext,
         identity_provider: &'a C,
+        time: Option<MlsTime>,
     ) -> Self {
         TreeValidator {
             expected_tree_hash: &context.tree_hash,
@@ -50,6 +53,7 @@ impl<'a, C: IdentityProvider, CSP: CipherSuiteProvider> TreeValidator<'a, C, CSP
             ),
             group_id: &context.group_id,
             cipher_suite_provider,
+            time,
         }
     }
 
@@ -96,7 +100,7 @@ impl<'a, C: IdentityProvider, CSP: CipherSuiteProvider> TreeValidator<'a, C, CSP
         { leaves }
             .try_for_each(|(index, leaf_node)| async move {
                 self.leaf_node_validator
-                    .revalidate(leaf_node, self.group_id, *index)
+                    .revalidate(leaf_node, self.group_id, *index, self.time)
                     .await
             })
             .await
@@ -240,8 +244,12 @@ mod tests {
             let mut context = get_test_group_context(1, cipher_suite).await;
             context.tree_hash = test_tree.tree_hash(&cipher_suite_provider).await.unwrap();
 
-            let validator =
-                TreeValidator::new(&cipher_suite_provider, &context, &BasicIdentityProvider);
+            let validator = TreeValidator::new(
+                &cipher_suite_provider,
+                &context,
+                &BasicIdentityProvider,
+                None,
+            );
 
             validator.validate(&mut test_tree).await.unwrap();
         }
@@ -255,8 +263,12 @@ mod tests {
             let cipher_suite_provider = test_cipher_suite_provider(cipher_suite);
             let context = get_test_group_context(1, cipher_suite).await;
 
-            let validator =
-                TreeValidator::new(&cipher_suite_provider, &context, &BasicIdentityProvider);
+            let validator = TreeValidator::new(
+                &cipher_suite_provider,
+                &context,
+                &BasicIdentityProvider,
+                None,
+            );
 
             let res = validator.validate(&mut test_tree).await;
 
@@ -276,8 +288,12 @@ mod tests {
             let mut context = get_test_group_context(1, cipher_suite).await;
             context.tree_hash = test_tree.tree_hash(&cipher_suite_provider).await.unwrap();
 
-            let validator =
-                TreeValidator::new(&cipher_suite_provider, &context, &BasicIdentityProvider);
+            let validator = TreeValidator::new(
+                &cipher_suite_provider,
+                &context,
+                &BasicIdentityProvider,
+                None,
+            );
 
             let res = validator.validate(&mut test_tree).await;
 
@@ -300,8 +316,12 @@ mod tests {
             let mut context = get_test_group_context(1, cipher_suite).await;
             context.tree_hash = test_tree.tree_hash(&cipher_suite_provider).await.unwrap();
 
-            let validator =
-                TreeValidator::new(&cipher_suite_provider, &context, &BasicIdentityProvider);
+            let validator = TreeValidator::new(
+                &cipher_suite_provider,
+                &context,
+                &BasicIdentityProvider,
+                None,
+            );
 
             let res = validator.validate(&mut test_tree).await;
 
@@ -3,6 +3,10 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tonic_build::compile_protos("./proto/mls_client.proto")?;
+    tonic_build::configure().compile(
+        &["./proto/mls_client.proto", "./proto/mls_rs_client.proto"],
+        &["./proto"],
+    )?;
+
     Ok(())
 }
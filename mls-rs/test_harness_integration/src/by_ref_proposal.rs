@@ -10,7 +10,7 @@ pub(crate) trait ByRefProposalSender<T> {
 #[cfg(feature = "by_ref_proposal")]
 pub(crate) mod inner {
     use mls_rs::{
-        extension::built_in::ExternalSendersExt,
+        extension::built_in::{ApplicationIdExt, ExternalSendersExt},
         identity::{basic::BasicCredential, Credential, SigningIdentity},
         mls_rs_codec::MlsDecode,
         psk::ExternalPskId,
@@ -26,6 +26,10 @@ pub(crate) mod inner {
             ReInitProposalRequest, RemoveProposalRequest, ResumptionPskProposalRequest,
             UpdateProposalRequest,
         },
+        mls_rs_client::{
+            ApplicationIdUpdateProposalRequest, CustomProposalRequest,
+            ExternalSendersProposalRequest, RemoveOwnLeafProposalRequest,
+        },
         parse_extensions, MlsClientImpl, TestClientConfig, PROPOSAL_DESC_ADD, PROPOSAL_DESC_GCE,
         PROPOSAL_DESC_REMOVE,
     };
@@ -33,6 +37,9 @@ pub(crate) mod inner {
     #[cfg(feature = "psk")]
     use crate::{PROPOSAL_DESC_EXTERNAL_PSK, PROPOSAL_DESC_REINIT, PROPOSAL_DESC_RESUMPTION_PSK};
 
+    #[cfg(feature = "custom_proposal")]
+    use mls_rs::group::proposal::CustomProposal;
+
     use super::ByRefProposalSender;
 
     impl MlsClientImpl {
@@ -244,6 +251,111 @@ pub(crate) mod inner {
         }
     }
 
+    #[tonic::async_trait]
+    impl ByRefProposalSender<RemoveOwnLeafProposalRequest> for MlsClientImpl {
+        async fn propose(
+            &self,
+            request: Request<RemoveOwnLeafProposalRequest>,
+        ) -> Result<Response<ProposalResponse>, Status> {
+            let request = request.into_inner();
+
+            self.send_proposal(request.state_id, move |group| {
+                let own_index = group.current_member_index();
+                group.propose_remove(own_index, vec![]).map_err(abort)
+            })
+            .await
+        }
+    }
+
+    #[tonic::async_trait]
+    impl ByRefProposalSender<ApplicationIdUpdateProposalRequest> for MlsClientImpl {
+        async fn propose(
+            &self,
+            request: Request<ApplicationIdUpdateProposalRequest>,
+        ) -> Result<Response<ProposalResponse>, Status> {
+            let request = request.into_inner();
+
+            self.send_proposal(request.state_id, move |group| {
+                let mut extensions = group
+                    .member_at_index(group.current_member_index())
+                    .ok_or_else(|| Status::aborted("own leaf not found"))?
+                    .extensions;
+
+                extensions
+                    .set_from(ApplicationIdExt::new(request.application_id))
+                    .map_err(abort)?;
+
+                group
+                    .propose_update_with_extensions(extensions, vec![])
+                    .map_err(abort)
+            })
+            .await
+        }
+    }
+
+    #[tonic::async_trait]
+    impl ByRefProposalSender<ExternalSendersProposalRequest> for MlsClientImpl {
+        async fn propose(
+            &self,
+            request: Request<ExternalSendersProposalRequest>,
+        ) -> Result<Response<ProposalResponse>, Status> {
+            let request = request.into_inner();
+
+            self.send_proposal(request.state_id, move |group| {
+                let mut extensions = parse_extensions(request.extensions);
+
+                let ext_senders = request
+                    .external_senders
+                    .iter()
+                    .map(|sender| SigningIdentity::mls_decode(&mut &**sender))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(abort)?;
+
+                if !ext_senders.is_empty() {
+                    extensions
+                        .set_from(ExternalSendersExt::new(ext_senders))
+                        .map_err(abort)?;
+                }
+
+                group
+                    .propose_group_context_extensions(extensions, vec![])
+                    .map_err(abort)
+            })
+            .await
+        }
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[tonic::async_trait]
+    impl ByRefProposalSender<CustomProposalRequest> for MlsClientImpl {
+        async fn propose(
+            &self,
+            request: Request<CustomProposalRequest>,
+        ) -> Result<Response<ProposalResponse>, Status> {
+            let request = request.into_inner();
+
+            let proposal_type = u16::try_from(request.proposal_type).map_err(abort)?;
+
+            self.send_proposal(request.state_id, move |group| {
+                let proposal = CustomProposal::new(proposal_type.into(), request.data);
+
+                group.propose_custom(proposal, vec![]).map_err(abort)
+            })
+            .await
+        }
+    }
+
+    #[cfg(not(feature = "custom_proposal"))]
+    #[tonic::async_trait]
+    impl ByRefProposalSender<CustomProposalRequest> for MlsClientImpl {
+        async fn propose(
+            &self,
+            _: Request<CustomProposalRequest>,
+        ) -> Result<Response<ProposalResponse>, Status> {
+            Err(Status::aborted("Unsupported"))
+        }
+    }
+
     #[tonic::async_trait]
     impl ByRefProposalSender<ExternalSignerProposalRequest> for MlsClientImpl {
         async fn propose(
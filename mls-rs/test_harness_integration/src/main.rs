@@ -18,6 +18,7 @@ use mls_rs::{
     },
     crypto::SignatureSecretKey,
     error::MlsError,
+    extension::ExtensionType,
     external_client::ExternalClient,
     group::{ExportedTree, Member, ReceivedMessage, Roster, StateUpdate},
     identity::{
@@ -34,6 +35,9 @@ use mls_rs::{
 #[cfg(feature = "by_ref_proposal")]
 use mls_rs::external_client::builder::ExternalBaseConfig;
 
+#[cfg(feature = "private_message")]
+use mls_rs::client_builder::PaddingMode;
+
 use mls_rs_crypto_openssl::OpensslCryptoProvider;
 
 use clap::Parser;
@@ -60,6 +64,13 @@ use mls_client::{
     UnprotectRequest, UnprotectResponse, UpdateProposalRequest,
 };
 
+use mls_rs_client::mls_rs_client_server::{MlsRsClient, MlsRsClientServer};
+
+use mls_rs_client::{
+    ApplicationIdUpdateProposalRequest, CustomProposalRequest, ExternalSendersProposalRequest,
+    RemoveOwnLeafProposalRequest, SetApplicationConfigRequest, SetApplicationConfigResponse,
+};
+
 fn abort<T: std::fmt::Debug>(e: T) -> Status {
     Status::aborted(format!("Aborted with error {e:?}"))
 }
@@ -70,6 +81,11 @@ pub mod mls_client {
     tonic::include_proto!("mls_client");
 }
 
+pub mod mls_rs_client {
+    #![allow(clippy::derive_partial_eq_without_eq)]
+    tonic::include_proto!("mls_rs_client");
+}
+
 const IMPLEMENTATION_NAME: &str = "Wickr MLS";
 
 const PROPOSAL_DESC_ADD: &[u8] = b"add";
@@ -82,6 +98,10 @@ const PROPOSAL_DESC_GCE: &[u8] = b"groupContextExtensions";
 #[cfg(feature = "psk")]
 const PROPOSAL_DESC_REINIT: &[u8] = b"reinit";
 
+// The only custom proposal type supported by clients, see rpc CustomProposal.
+#[cfg(feature = "custom_proposal")]
+const CUSTOM_PROPOSAL_TYPE: u16 = 0xf000;
+
 type TestClientConfig = WithIdentityProvider<
     BasicIdentityProvider,
     WithCryptoProvider<OpensslCryptoProvider, WithMlsRules<TestMlsRules, BaseInMemoryConfig>>,
@@ -132,6 +152,44 @@ impl ClientDetails {
 
     #[cfg(not(feature = "private_message"))]
     async fn set_enc_controls(&self, _: bool) {}
+
+    #[cfg(feature = "private_message")]
+    async fn set_padding(&self, padding: bool) {
+        let padding_mode = if padding {
+            PaddingMode::StepFunction
+        } else {
+            PaddingMode::None
+        };
+
+        self.mls_rules
+            .encryption_options
+            .lock()
+            .unwrap()
+            .padding_mode = padding_mode;
+    }
+
+    #[cfg(not(feature = "private_message"))]
+    async fn set_padding(&self, _: bool) {}
+
+    #[cfg(feature = "private_message")]
+    async fn set_ratchet_limits(
+        &self,
+        out_of_order_tolerance: Option<u32>,
+        maximum_forward_distance: Option<u32>,
+    ) {
+        let mut options = self.mls_rules.encryption_options.lock().unwrap();
+
+        if let Some(out_of_order_tolerance) = out_of_order_tolerance {
+            options.out_of_order_tolerance = out_of_order_tolerance;
+        }
+
+        if let Some(maximum_forward_distance) = maximum_forward_distance {
+            options.maximum_forward_distance = maximum_forward_distance;
+        }
+    }
+
+    #[cfg(not(feature = "private_message"))]
+    async fn set_ratchet_limits(&self, _: Option<u32>, _: Option<u32>) {}
 }
 
 struct ExternalClientDetails {
@@ -665,6 +723,61 @@ impl MlsClient for MlsClientImpl {
     }
 }
 
+#[tonic::async_trait]
+impl MlsRsClient for MlsClientImpl {
+    async fn remove_own_leaf_proposal(
+        &self,
+        request: Request<RemoveOwnLeafProposalRequest>,
+    ) -> Result<Response<ProposalResponse>, Status> {
+        self.propose(request).await
+    }
+
+    async fn application_id_update_proposal(
+        &self,
+        request: Request<ApplicationIdUpdateProposalRequest>,
+    ) -> Result<Response<ProposalResponse>, Status> {
+        self.propose(request).await
+    }
+
+    async fn external_senders_proposal(
+        &self,
+        request: Request<ExternalSendersProposalRequest>,
+    ) -> Result<Response<ProposalResponse>, Status> {
+        self.propose(request).await
+    }
+
+    async fn custom_proposal(
+        &self,
+        request: Request<CustomProposalRequest>,
+    ) -> Result<Response<ProposalResponse>, Status> {
+        self.propose(request).await
+    }
+
+    async fn set_application_config(
+        &self,
+        request: Request<SetApplicationConfigRequest>,
+    ) -> Result<Response<SetApplicationConfigResponse>, Status> {
+        let request = request.into_inner();
+        let clients = self.clients.lock().await;
+
+        let client = clients
+            .get(&request.state_id)
+            .ok_or_else(|| Status::aborted("no group with such index."))?;
+
+        client.set_enc_controls(request.encrypt_handshake).await;
+        client.set_padding(request.padding).await;
+
+        client
+            .set_ratchet_limits(
+                request.out_of_order_tolerance,
+                request.maximum_forward_distance,
+            )
+            .await;
+
+        Ok(Response::new(SetApplicationConfigResponse {}))
+    }
+}
+
 impl MlsClientImpl {
     async fn insert_client(&self, client: ClientDetails) -> u32 {
         let mut clients = self.clients.lock().await;
@@ -826,14 +939,19 @@ async fn create_client(cipher_suite: u16, identity: &[u8]) -> Result<ClientDetai
     let key_package_repo = InMemoryKeyPackageStorage::new();
     let mls_rules = TestMlsRules::new();
 
-    let client = ClientBuilder::new()
+    let builder = ClientBuilder::new()
         .crypto_provider(OpensslCryptoProvider::default())
         .identity_provider(BasicIdentityProvider::new())
         .mls_rules(mls_rules.clone())
         .psk_store(psk_store.clone())
         .key_package_repo(key_package_repo.clone())
-        .signing_identity(signing_identity.clone(), secret_key.clone(), cipher_suite)
-        .build();
+        .extension_type(ExtensionType::APPLICATION_ID)
+        .signing_identity(signing_identity.clone(), secret_key.clone(), cipher_suite);
+
+    #[cfg(feature = "custom_proposal")]
+    let builder = builder.custom_proposal_type(CUSTOM_PROPOSAL_TYPE.into());
+
+    let client = builder.build();
 
     Ok(ClientDetails {
         client,
@@ -882,13 +1000,16 @@ struct Opts {
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let opts = Opts::parse();
-    let mls_client_impl =
-        MlsClientImpl::new(format!("{IMPLEMENTATION_NAME} on port {}", opts.port));
+    let mls_client_impl = Arc::new(MlsClientImpl::new(format!(
+        "{IMPLEMENTATION_NAME} on port {}",
+        opts.port
+    )));
 
     println!("serving on host {} port {}", opts.host, opts.port);
 
     Server::builder()
-        .add_service(MlsClientServer::new(mls_client_impl))
+        .add_service(MlsClientServer::from_arc(mls_client_impl.clone()))
+        .add_service(MlsRsClientServer::from_arc(mls_client_impl))
         .serve((opts.host, opts.port).into())
         .await?;
 